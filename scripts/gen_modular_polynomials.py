@@ -0,0 +1,155 @@
+#!/usr/bin/env python3
+"""Generate the classical modular polynomials Phi_l(X, Y) for small primes l.
+
+Phi_l(X, j(q)) = prod over the l + 1 roots X = j(q^l), j(zeta^k q^(1/l)).
+The power sums of the roots are q-series; Newton's identities turn them
+into the elementary symmetric functions, and each of those is rewritten
+as a polynomial in j by cancelling its principal part. Everything is
+exact integer arithmetic.
+Usage: python3 scripts/gen_modular_polynomials.py > testdata/modular_polynomials.txt
+"""
+
+LEVELS = [2, 3, 5, 7]
+
+
+def sigma3(n):
+    return sum(d ** 3 for d in range(1, n + 1) if n % d == 0)
+
+
+def mul(a, b, n):
+    """Product of two power series truncated to n terms."""
+    out = [0] * n
+    for i, x in enumerate(a[:n]):
+        if x:
+            for k, y in enumerate(b[: n - i]):
+                out[i + k] += x * y
+    return out
+
+
+def j_series(n):
+    """Coefficients c[k] of q^(k - 1) in j(q) = 1/q + 744 + 196884 q + ..."""
+    e4 = [1] + [240 * sigma3(k) for k in range(1, n + 1)]
+    # prod (1 - q^k) = sum (-1)^k q^(k(3k-1)/2) over the pentagonal numbers
+    euler = [0] * (n + 1)
+    for k in range(-n, n + 1):
+        e = k * (3 * k - 1) // 2
+        if 0 <= e <= n:
+            euler[e] = -1 if k % 2 else 1
+    # Delta / q = prod (1 - q^k)^24
+    e2 = mul(euler, euler, n + 1)
+    e8 = mul(mul(e2, e2, n + 1), mul(e2, e2, n + 1), n + 1)
+    eta = mul(mul(e8, e8, n + 1), e8, n + 1)
+    # 1 / (Delta / q)
+    inv = [0] * (n + 1)
+    inv[0] = 1
+    for i in range(1, n + 1):
+        inv[i] = -sum(eta[k] * inv[i - k] for k in range(1, i + 1))
+    return mul(mul(mul(e4, e4, n + 1), e4, n + 1), inv, n + 1)
+
+
+class Laurent:
+    """Laurent series: coefficients from exponent `low` upwards, truncated at `high`."""
+
+    def __init__(self, low, coeffs, high):
+        self.low, self.high = low, high
+        self.c = {low + i: x for i, x in enumerate(coeffs) if x and low + i < high}
+
+    def __mul__(self, other):
+        high = min(self.high + other.low, other.high + self.low)
+        out = Laurent(0, [], high)
+        for e1, x in self.c.items():
+            for e2, y in other.c.items():
+                if e1 + e2 < high:
+                    out.c[e1 + e2] = out.c.get(e1 + e2, 0) + x * y
+        out.low = min(out.c, default=high)
+        return out
+
+    def scaled(self, k):
+        out = Laurent(0, [], self.high)
+        out.c = {e: x * k for e, x in self.c.items()}
+        out.low = self.low
+        return out
+
+    def plus(self, other, sign=1):
+        out = Laurent(0, [], min(self.high, other.high))
+        for e, x in list(self.c.items()) + [(e, sign * y) for e, y in other.c.items()]:
+            if e < out.high:
+                out.c[e] = out.c.get(e, 0) + x
+        out.c = {e: x for e, x in out.c.items() if x}
+        out.low = min(out.c, default=out.high)
+        return out
+
+
+def modular_polynomial(l):
+    degree = l + 1
+    # multiplying by series with poles loses precision; leave ample room
+    high = l * degree * degree // 2 + 8
+    terms = l * high + degree + 8
+    j = j_series(terms)
+    # j(t) in the variable t = q^(1/l)
+    jt = Laurent(-1, j, terms - 1)
+    # j(q^l) as a series in q
+    jql = Laurent(-l, [x if i % l == 0 else 0 for i, x in enumerate(
+        sum(([c] + [0] * (l - 1) for c in j), [])
+    )], high)
+    jq = Laurent(-1, j, high)
+
+    power_sums = []
+    jt_power = Laurent(0, [1], terms - 1)
+    jql_power = Laurent(0, [1], high)
+    for m in range(1, degree + 1):
+        jt_power = jt_power * jt
+        jql_power = jql_power * jql
+        # sum over k of j(zeta^k t)^m keeps the exponents divisible by l, times l
+        conj = Laurent(0, [], high)
+        conj.c = {e // l: l * x for e, x in jt_power.c.items() if e % l == 0 and e // l < high}
+        conj.low = min(conj.c, default=high)
+        power_sums.append(jql_power.plus(conj))
+
+    # Newton: m e_m = sum_{i=1..m} (-1)^(i-1) e_{m-i} p_i
+    elementary = [Laurent(0, [1], high)]
+    for m in range(1, degree + 1):
+        acc = Laurent(0, [], high)
+        for i in range(1, m + 1):
+            term = elementary[m - i] * power_sums[i - 1]
+            acc = acc.plus(term, 1 if i % 2 == 1 else -1)
+        assert all(x % m == 0 for x in acc.c.values())
+        out = Laurent(0, [], acc.high)
+        out.c = {e: x // m for e, x in acc.c.items()}
+        out.low = acc.low
+        elementary.append(out)
+
+    # powers of j, used to cancel principal parts
+    j_powers = [Laurent(0, [1], high)]
+    for _ in range(l * degree):
+        j_powers.append(j_powers[-1] * jq)
+
+    coefficients = {}
+    for m, series in enumerate(elementary):
+        poly = {}
+        rest = series
+        while rest.c and min(rest.c) <= 0:
+            e = min(rest.c)
+            d = -e
+            c = rest.c[e]
+            poly[d] = poly.get(d, 0) + c
+            rest = rest.plus(j_powers[d].scaled(c), -1)
+        assert rest.high > 1 and not rest.c, f"l = {l}, m = {m}: leftover {rest.c}"
+        sign = -1 if m % 2 else 1
+        for d, c in poly.items():
+            if c:
+                coefficients[(degree - m, d)] = sign * c
+    for (i, k), c in coefficients.items():
+        assert coefficients.get((k, i)) == c, "Phi_l is symmetric"
+    return coefficients
+
+
+def main():
+    print("# l i k c: Phi_l(X, Y) = sum c X^i Y^k")
+    for l in LEVELS:
+        for (i, k), c in sorted(modular_polynomial(l).items(), reverse=True):
+            print(l, i, k, c)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,143 @@
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::point::Point;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// 只保存坐标的轻量点，曲线参数通过引用共享
+#[derive(Debug, Clone, Copy)]
+pub struct AffinePoint<'c> {
+    curve: &'c Curve,
+    coords: Coords,
+}
+
+impl<'c> AffinePoint<'c> {
+    // 和 Curve::point 一样，曲线外的坐标直接 panic；运算符因此不用再检查
+    pub fn new(curve: &'c Curve, x: i64, y: i64) -> Self {
+        Self::try_new(curve, x, y).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    pub fn try_new(curve: &'c Curve, x: i64, y: i64) -> Result<Self, EcError> {
+        if !curve.contains(x, y) {
+            return Err(EcError::PointNotOnCurve { x, y });
+        }
+        Ok(AffinePoint {
+            curve,
+            coords: Some((x.rem_euclid(curve.p), y.rem_euclid(curve.p))),
+        })
+    }
+
+    pub fn identity(curve: &'c Curve) -> Self {
+        AffinePoint {
+            curve,
+            coords: None,
+        }
+    }
+
+    pub fn from_point(curve: &'c Curve, point: &Point) -> Self {
+        assert_eq!(*curve, point.curve(), "point belongs to a different curve");
+        AffinePoint {
+            curve,
+            coords: point.coords(),
+        }
+    }
+
+    pub fn curve(&self) -> &'c Curve {
+        self.curve
+    }
+
+    pub fn coords(&self) -> Coords {
+        self.coords
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.coords.is_none()
+    }
+
+    pub fn to_point(self) -> Point {
+        Point::from_coords(self.curve, self.coords)
+    }
+
+    fn with_coords(&self, coords: Coords) -> Self {
+        AffinePoint {
+            curve: self.curve,
+            coords,
+        }
+    }
+
+    // 同一个曲线句柄（或参数完全相同）才能相加
+    fn check_same_curve(&self, other: &AffinePoint<'_>) {
+        assert!(
+            std::ptr::eq(self.curve, other.curve) || self.curve == other.curve,
+            "cannot combine points from different curves: {:?} vs {:?}",
+            self.curve,
+            other.curve
+        );
+    }
+}
+
+impl PartialEq for AffinePoint<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.curve == other.curve && self.coords == other.coords
+    }
+}
+
+impl Eq for AffinePoint<'_> {}
+
+impl<'c> From<AffinePoint<'c>> for Point {
+    fn from(point: AffinePoint<'c>) -> Point {
+        point.to_point()
+    }
+}
+
+impl<'c> Add for AffinePoint<'c> {
+    type Output = AffinePoint<'c>;
+
+    fn add(self, other: AffinePoint<'c>) -> AffinePoint<'c> {
+        self.check_same_curve(&other);
+        self.with_coords(self.curve.add_coords(self.coords, other.coords))
+    }
+}
+
+impl<'c> Neg for AffinePoint<'c> {
+    type Output = AffinePoint<'c>;
+
+    fn neg(self) -> AffinePoint<'c> {
+        self.with_coords(self.curve.neg_coords(self.coords))
+    }
+}
+
+impl<'c> Sub for AffinePoint<'c> {
+    type Output = AffinePoint<'c>;
+
+    fn sub(self, other: AffinePoint<'c>) -> AffinePoint<'c> {
+        self + (-other)
+    }
+}
+
+impl<'c> AddAssign for AffinePoint<'c> {
+    fn add_assign(&mut self, other: AffinePoint<'c>) {
+        *self = *self + other;
+    }
+}
+
+impl<'c> SubAssign for AffinePoint<'c> {
+    fn sub_assign(&mut self, other: AffinePoint<'c>) {
+        *self = *self - other;
+    }
+}
+
+impl<'c> Mul<u64> for AffinePoint<'c> {
+    type Output = AffinePoint<'c>;
+
+    fn mul(self, k: u64) -> AffinePoint<'c> {
+        self.with_coords(self.curve.mul_coords(self.coords, k))
+    }
+}
+
+impl<'c> Mul<AffinePoint<'c>> for u64 {
+    type Output = AffinePoint<'c>;
+
+    fn mul(self, point: AffinePoint<'c>) -> AffinePoint<'c> {
+        point * self
+    }
+}
@@ -0,0 +1,156 @@
+// k·P 的动画：从高位到低位的倍点-加法，每一帧是一次倍点或一次加法，
+// 连同这一步用到的切线或弦，播放时一帧一帧画出来
+use crate::chord::Chord;
+use crate::curve::{Coords, Curve};
+
+// 每秒播放的帧数
+pub const FRAMES_PER_SECOND: f32 = 1.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    // 例如 "2·3P = 6P"
+    pub label: String,
+    // 这一帧得到的是 multiple·P
+    pub multiple: u64,
+    pub point: Coords,
+    // 第一帧和涉及无穷远点的一步没有直线
+    pub chord: Option<Chord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeline {
+    pub curve: Curve,
+    pub base: Coords,
+    pub k: u64,
+    pub frames: Vec<Frame>,
+}
+
+impl Timeline {
+    // k ≥ 1；模数必须是素数，合数时切线或弦的分母可能不可逆
+    pub fn new(curve: &Curve, base: Coords, k: u64) -> Self {
+        let line = |p: Coords, q: Coords| Chord::new(curve, p?, q?).ok();
+        let mut frames = vec![Frame {
+            label: "P".to_string(),
+            multiple: 1,
+            point: base,
+            chord: None,
+        }];
+        let (mut acc, mut multiple) = (base, 1);
+        for bit in (0..u64::BITS - k.leading_zeros()).rev().skip(1) {
+            let doubled = curve.add_coords(acc, acc);
+            frames.push(Frame {
+                label: format!("2·{} = {}", name(multiple), name(2 * multiple)),
+                multiple: 2 * multiple,
+                point: doubled,
+                chord: line(acc, acc),
+            });
+            (acc, multiple) = (doubled, 2 * multiple);
+            if (k >> bit) & 1 == 1 {
+                let sum = curve.add_coords(acc, base);
+                frames.push(Frame {
+                    label: format!("{} + P = {}", name(multiple), name(multiple + 1)),
+                    multiple: multiple + 1,
+                    point: sum,
+                    chord: line(acc, base),
+                });
+                (acc, multiple) = (sum, multiple + 1);
+            }
+        }
+        Timeline {
+            curve: *curve,
+            base,
+            k,
+            frames,
+        }
+    }
+}
+
+// 1P 写成 P
+pub fn name(multiple: u64) -> String {
+    match multiple {
+        1 => "P".to_string(),
+        m => format!("{m}P"),
+    }
+}
+
+// 播放位置；playing 时每 1 / FRAMES_PER_SECOND 秒前进一帧，到最后一帧停下
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Playback {
+    pub frame: usize,
+    pub playing: bool,
+    elapsed: f32,
+}
+
+impl Playback {
+    // 走过 dt 秒，返回是否换了一帧
+    pub fn advance(&mut self, dt: f32, frames: usize) -> bool {
+        if !self.playing {
+            return false;
+        }
+        self.elapsed += dt;
+        let before = self.frame;
+        while self.elapsed >= 1.0 / FRAMES_PER_SECOND {
+            self.elapsed -= 1.0 / FRAMES_PER_SECOND;
+            self.step(frames);
+        }
+        if self.frame + 1 >= frames {
+            self.playing = false;
+        }
+        self.frame != before
+    }
+
+    pub fn step(&mut self, frames: usize) {
+        self.frame = (self.frame + 1).min(frames.saturating_sub(1));
+    }
+
+    // 停在最后一帧时按播放从头开始
+    pub fn toggle(&mut self, frames: usize) {
+        if !self.playing && self.frame + 1 >= frames {
+            self.frame = 0;
+        }
+        self.playing = !self.playing;
+        self.elapsed = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_follow_double_and_add() {
+        let curve = Curve::new(2, 3, 97);
+        let base = Some((3, 6));
+        // 11 = 0b1011：P, 2P, 4P, 5P, 10P, 11P
+        let timeline = Timeline::new(&curve, base, 11);
+        let multiples: Vec<u64> = timeline.frames.iter().map(|f| f.multiple).collect();
+        assert_eq!(multiples, [1, 2, 4, 5, 10, 11]);
+        for frame in &timeline.frames {
+            assert_eq!(frame.point, curve.mul_coords(base, frame.multiple));
+            if let Some(chord) = &frame.chord {
+                assert_eq!(chord.sum, frame.point);
+            }
+        }
+        assert_eq!(timeline.frames[3].label, "4P + P = 5P");
+        assert_eq!(timeline.frames[1].label, "2·P = 2P");
+        // (3, 6) 的阶是 5，10P 是无穷远点，下一步 O + P 没有直线
+        assert_eq!(timeline.frames[4].point, None);
+        assert_eq!(timeline.frames[5].chord, None);
+        assert_eq!(Timeline::new(&curve, base, 1).frames.len(), 1);
+    }
+
+    #[test]
+    fn playback_advances_and_stops_at_the_end() {
+        let mut playback = Playback::default();
+        assert!(!playback.advance(10.0, 4));
+        playback.toggle(4);
+        assert!(!playback.advance(0.4, 4));
+        assert!(playback.advance(0.4, 4));
+        assert_eq!(playback.frame, 1);
+        playback.advance(10.0, 4);
+        assert_eq!((playback.frame, playback.playing), (3, false));
+        // 在最后一帧按播放从头开始
+        playback.toggle(4);
+        assert_eq!((playback.frame, playback.playing), (0, true));
+    }
+}
@@ -0,0 +1,5710 @@
+use crate::animation::{self, Playback, Timeline};
+use crate::bip340;
+use crate::cache::{self, LruCache};
+use crate::coinflip::{CoinFlip, Outcome, Stage};
+use crate::console::LogBuffer;
+use crate::curve::{Coords, Curve};
+use crate::diagnostics::{self, CheckResult, SelfCheck};
+use crate::dlog::{Bsgs, RHO_ATTEMPTS, Rho};
+use crate::ecdh::{self, Exchange};
+use crate::ecdsa::{self, SigningKey, Verifier};
+use crate::ecm::{self, Ecm};
+use crate::ed25519;
+use crate::elgamal::{self, Ciphertext};
+use crate::error::EcError;
+use crate::explorer::{self, Row, SortKey};
+use crate::fault::{self, Fault, FaultKind};
+use crate::field;
+use crate::invalid_curve::{self, Attack};
+use crate::ipa;
+use crate::jacobian::{JacobianPoint, MulAlgorithm};
+use crate::job::{self, Job, Progress};
+use crate::lattice::Lattice;
+use crate::layers::{self, Canvas, Marker};
+use crate::models::Models;
+use crate::modular::{self, Neighbours};
+use crate::net;
+use crate::orbit::{TrackedPoint, Trajectory};
+use crate::p256::P256;
+use crate::pedersen::{Collision, PedersenHash};
+use crate::pohlig_hellman::PohligHellman;
+use crate::point::{Coordinates, Point};
+use crate::presets;
+use crate::protocol::Party;
+use crate::real::{PlotMode, RealView};
+use crate::recording::{Action, Player, Recorder, Recording, Start};
+use crate::rng::{self, DemoRng};
+use crate::scalar::{Scalar, SecretScalar};
+use crate::script;
+use crate::sha256;
+use crate::stats;
+use crate::subgroup::{self, Cosets, PrimeGroup};
+use crate::summary::CurveSummary;
+use crate::twisted_edwards::{self, EdwardsReduction, TwistedEdwards};
+use crate::u256;
+use crate::view::{self, CurveView, ViewWindow};
+use crate::weierstrass::{LongWeierstrass, Reduction};
+use crate::zeroize::Zeroize;
+use eframe::egui;
+use rand_core::RngCore;
+use std::sync::mpsc;
+
+// 脚本编辑器里的示例
+const EXAMPLE_SCRIPT: &str = "\
+# y^2 = x^3 + 2x + 3 over F_97
+curve(2, 3, 97)
+let P = point(3, 6)
+print \"#E =\", order(), \"ord(P) =\", ord(P)
+for k in 1..4
+  print k, \"* P =\", k * P
+end
+assert ord(P) * P == infinity()
+pin(P)
+ui(\"trajectory 1 doublings\")
+";
+
+// BSGS 在画布上的颜色：小步蓝、大步橙
+const BABY_STEP: egui::Color32 = egui::Color32::from_rgb(40, 110, 220);
+const GIANT_STEP: egui::Color32 = egui::Color32::from_rgb(230, 120, 0);
+// 生成元用紫色
+const GENERATOR: egui::Color32 = egui::Color32::from_rgb(150, 60, 200);
+
+// 后台枚举一个 p 上全部曲线的结果
+type ExplorerResult = Result<Vec<Row>, String>;
+// 标准曲线上的 (预设序号, k) 和 k·G 的仿射坐标
+type PresetProduct = ((usize, u256::Limbs), Option<(u256::Limbs, u256::Limbs)>);
+// 化简后的形式和 Edwards 模型上的全部点
+type EdwardsResult = Result<(EdwardsReduction, Vec<(i64, i64)>), String>;
+
+// GUI 应用程序
+pub struct EllipticCurveApp {
+    view: CurveView,
+    // 在额外窗口里打开的独立视图
+    windows: Vec<ViewWindow>,
+    next_window: usize,
+    // 待添加基点的输入框
+    new_base: (i64, i64),
+    new_base_error: Option<String>,
+    // 向量场视图：每个点 P 指向 P + G，G 取自某个追踪的基点
+    show_field: bool,
+    field_base: usize,
+    // 按子群 H = <base> 的陪集给点着色
+    show_cosets: bool,
+    coset_base: usize,
+    coset_cache: Option<((Curve, Coords), Cosets)>,
+    // 点选一个点时高亮 <P>，陪集也改按 <P> 着色
+    subgroup_of_click: bool,
+    clicked_subgroup: Option<((Curve, Coords), Vec<Coords>)>,
+    // 用另一种颜色标出生成元，按曲线缓存
+    show_generators: bool,
+    generators: LruCache<Curve, Vec<Coords>>,
+    show_x_stats: bool,
+    x_stats_k: u64,
+    // 自检在后台线程运行，结果保留到下一次运行
+    show_diagnostics: bool,
+    diagnostics: Option<Vec<CheckResult>>,
+    diagnostics_job: Option<mpsc::Receiver<Vec<CheckResult>>>,
+    // 当前曲线上的群公理抽查，结果连同曲线一起保留
+    selfcheck: Option<(Curve, Result<SelfCheck, String>)>,
+    selfcheck_job: Option<(Curve, Job<Result<SelfCheck, String>>)>,
+    // 曲线统计在后台线程计算，按曲线缓存，超过预算时淘汰最久没看的
+    summaries: LruCache<Curve, CurveSummary>,
+    summary_job: Option<(Curve, Job<CurveSummary>)>,
+    p: i64,
+    // 参数输入框，点 Apply 才生效
+    param_a: i64,
+    param_b: i64,
+    param_p: i64,
+    param_error: Option<String>,
+    // p 为合数时仍允许探索，但很多基于域的功能不可用
+    composite: bool,
+    dismissed_factor: Option<EcError>,
+    pending_singular: Option<Curve>,
+    // 所有随机演示共用的种子，相同种子得到相同的结果
+    seed: u64,
+    rng: DemoRng,
+    // tracing 的日志，由 main 安装的 subscriber 写入
+    log: LogBuffer,
+    log_level: tracing::Level,
+    // 会话录制与回放，session 是最近录制或导入的一段
+    recorder: Option<Recorder>,
+    player: Option<Player>,
+    session: Option<Recording>,
+    session_path: String,
+    session_status: Option<Result<String, String>>,
+    replay_speed: f32,
+    // 脚本编辑器，输出保留到下一次运行
+    show_script: bool,
+    script: String,
+    script_output: Option<script::Output>,
+    // 表达式控制台：变量在多次输入之间保留，历史里是每次的输入和输出
+    show_repl: bool,
+    repl: script::Session,
+    repl_input: String,
+    repl_history: Vec<(String, script::Output)>,
+    // 内积论证演示：向量长度、结果和正在查看的轮次
+    show_ipa: bool,
+    ipa_n: usize,
+    ipa: Option<Result<ipa::Demo, String>>,
+    ipa_round: usize,
+    // Pedersen 哈希：生成元随曲线生成，碰撞结果在消息或曲线改变时清掉
+    show_pedersen: bool,
+    pedersen: Option<Result<PedersenHash, String>>,
+    pedersen_message: String,
+    pedersen_collision: Option<Option<Collision>>,
+    // 用 Pedersen 承诺抛硬币，曲线改变时重新开始
+    show_coinflip: bool,
+    coinflip: Option<Result<CoinFlip, String>>,
+    // ECDH 中间人演示：群随曲线生成，选项改变时重新跑一遍交换
+    show_ecdh: bool,
+    ecdh_config: ecdh::Config,
+    // 手动选的私钥 (a, b)，None 时从种子生成；曲线改变时丢弃（drop 时清零）
+    ecdh_secrets: Option<(SecretScalar, SecretScalar)>,
+    ecdh_group: Option<Result<PrimeGroup, String>>,
+    ecdh: Option<Exchange>,
+    // EC ElGamal：Bob 的私钥 x、消息 m 和 Alice 的临时密钥 k，密文连同曲线保存下来画在图上。
+    // (x, k) 在群确定后才生成，曲线改变时丢弃
+    show_elgamal: bool,
+    elgamal_keys: Option<(SecretScalar, SecretScalar)>,
+    elgamal_message: u64,
+    elgamal_group: Option<Result<PrimeGroup, String>>,
+    elgamal: Option<(Curve, Coords, Ciphertext)>,
+    // 故障注入：标量、故障的种类和位置，攻击结果连同 (曲线, P, k) 一起保存
+    show_fault: bool,
+    fault_k: u64,
+    fault_kind: Option<FaultKind>,
+    fault_bit: u32,
+    fault_check: bool,
+    fault_attack: Option<((Curve, Coords, u64), u64)>,
+    // 画布显示 F_p 上的点、实数上的曲线，或者两个并排
+    plot_mode: PlotMode,
+    real_view: RealView,
+    // k·P 的动画：时间线按 (曲线, P, k) 缓存，播放位置单独保存
+    show_animation: bool,
+    animation_k: u64,
+    animation: Option<Timeline>,
+    playback: Playback,
+    // 无效曲线攻击：服务器不检查收到的点时私钥被一点点拼出来，结果按 (曲线, d, 是否检查) 缓存
+    show_invalid_curve: bool,
+    invalid_d: u64,
+    invalid_validate: bool,
+    invalid_attack: Option<((Curve, u64, bool), Attack)>,
+    // Pohlig–Hellman：按 G 的阶的素数幂因子分别求离散对数，以 (曲线, G, k) 为键
+    show_pohlig: bool,
+    pohlig_secret: u64,
+    pohlig: Option<(DlogKey, PohligHellman)>,
+    pohlig_job: Option<(DlogKey, Job<PohligHellman>)>,
+    // Lenstra 的 ECM，换 N 或者按"New curves"时重新随机选曲线
+    show_ecm: bool,
+    ecm_n: i64,
+    ecm: Option<Ecm>,
+    // 倍点-加法和 Montgomery ladder 的对比
+    show_ladder: bool,
+    ladder_k: u64,
+    ladder_algorithm: MulAlgorithm,
+    // 扭曲 Edwards 曲线，p 取当前曲线的；换了 a、d、p 才重新枚举点
+    show_edwards: bool,
+    edwards_a: i64,
+    edwards_d: i64,
+    edwards: Option<(TwistedEdwards, EdwardsResult)>,
+    // 当前曲线的 Montgomery 和 Edwards 形式，换曲线时重新算
+    show_models: bool,
+    models: Option<Models>,
+    // ECDSA 签名的可塑性：签名由种子决定，每帧重新算
+    show_ecdsa: bool,
+    ecdsa_group: Option<Result<PrimeGroup, String>>,
+    ecdsa_message: String,
+    ecdsa_verifier: Verifier,
+    ecdsa_ed25519: bool,
+    ecdsa_steps: bool,
+    // 逐步演示里手动选的 (d, k)
+    ecdsa_keys: Option<(SecretScalar, SecretScalar)>,
+    ecdsa_p256: bool,
+    // 默认按 RFC 6979 确定 nonce，攻击演示时切回随机
+    ecdsa_random_nonces: bool,
+    // nonce 重用攻击：受害者的 d、两次签名共用的 k 和两条消息
+    show_nonce_reuse: bool,
+    reuse_keys: Option<(SecretScalar, SecretScalar)>,
+    reuse_messages: [String; 2],
+    reuse_group: Option<Result<PrimeGroup, String>>,
+    ecdsa_bip340: bool,
+    show_dlog: bool,
+    dlog_secret: u64,
+    // (曲线, G, k) 和对应的求解过程
+    dlog: Option<(DlogKey, Bsgs)>,
+    dlog_job: Option<(DlogKey, Job<Bsgs>)>,
+    // Pollard rho，同样以 (曲线, G, k) 为键
+    show_rho: bool,
+    rho_secret: u64,
+    rho: Option<(DlogKey, Rho)>,
+    rho_job: Option<(DlogKey, Job<Rho>)>,
+    // 标准曲线：选中的预设和 k 的输入
+    show_presets: bool,
+    preset: usize,
+    preset_k: String,
+    preset_product: Option<PresetProduct>,
+    // 椭圆网和两种 Tate 配对的计算结果，按按钮时才算
+    show_net: bool,
+    net: Option<Result<net::Demo, String>>,
+    // 模多项式给出的同源邻居，曲线变了才重算
+    show_isogenies: bool,
+    isogenies: Option<Result<Neighbours, String>>,
+    isogeny_other: (i64, i64),
+    // 曲线浏览器：整张表按 p 缓存，在后台线程里并行计算
+    show_explorer: bool,
+    explorer_p: i64,
+    explorer_filter: explorer::Filter,
+    explorer_sort: SortKey,
+    explorer_descending: bool,
+    explorers: LruCache<i64, Vec<Row>>,
+    explorer_job: Option<(i64, mpsc::Receiver<ExplorerResult>)>,
+    explorer_error: Option<String>,
+    // 子群格，曲线变了就重建；选中节点的元素在画布上圈出
+    show_lattice: bool,
+    lattice: Option<(Curve, Result<Lattice, String>)>,
+    lattice_selected: Option<usize>,
+    lattice_highlight: Vec<Coords>,
+    // 长 Weierstrass 形式的输入和最近一次化简，用来把点映射回去
+    long_coefficients: [i64; 5],
+    long_form: Option<Reduction>,
+}
+
+impl EllipticCurveApp {
+    pub fn new(seed: u64, log: LogBuffer) -> Self {
+        let params = Curve::builder()
+            .a(1)
+            .b(1)
+            .prime(599) // 大素数
+            .generator(1, 188)
+            .build()
+            .expect("default curve parameters are valid");
+        EllipticCurveApp {
+            view: CurveView::new(params.curve, &params.generator),
+            windows: vec![],
+            next_window: 1,
+            new_base: (0, 0),
+            new_base_error: None,
+            show_field: false,
+            field_base: 0,
+            show_cosets: false,
+            coset_base: 0,
+            coset_cache: None,
+            subgroup_of_click: false,
+            clicked_subgroup: None,
+            show_generators: false,
+            generators: LruCache::new(cache::GENERATORS_BUDGET),
+            show_x_stats: false,
+            x_stats_k: 500,
+            show_diagnostics: false,
+            diagnostics: None,
+            diagnostics_job: None,
+            selfcheck: None,
+            selfcheck_job: None,
+            summaries: LruCache::new(cache::SUMMARY_BUDGET),
+            summary_job: None,
+            p: params.curve.p,
+            param_a: params.curve.a,
+            param_b: params.curve.b,
+            param_p: params.curve.p,
+            param_error: None,
+            composite: false,
+            dismissed_factor: None,
+            pending_singular: None,
+            seed,
+            rng: rng::seeded(seed),
+            log,
+            log_level: tracing::Level::DEBUG,
+            recorder: None,
+            player: None,
+            session: None,
+            session_path: "session.txt".to_string(),
+            session_status: None,
+            replay_speed: 1.0,
+            show_script: false,
+            script: EXAMPLE_SCRIPT.to_string(),
+            script_output: None,
+            show_repl: false,
+            repl: script::Session::new(params.curve, seed),
+            repl_input: String::new(),
+            repl_history: Vec::new(),
+            show_ipa: false,
+            ipa_n: 8,
+            ipa: None,
+            ipa_round: 0,
+            show_pedersen: false,
+            pedersen: None,
+            pedersen_message: "toy curves are weak".to_string(),
+            pedersen_collision: None,
+            show_coinflip: false,
+            coinflip: None,
+            show_ecdh: false,
+            ecdh_config: ecdh::Config {
+                mallory: true,
+                authenticate: false,
+                x25519: false,
+            },
+            ecdh_secrets: None,
+            ecdh_group: None,
+            ecdh: None,
+            show_elgamal: false,
+            elgamal_keys: None,
+            elgamal_message: 42,
+            elgamal_group: None,
+            elgamal: None,
+            show_fault: false,
+            fault_k: 77,
+            fault_kind: None,
+            fault_bit: 2,
+            fault_check: false,
+            fault_attack: None,
+            plot_mode: PlotMode::default(),
+            real_view: RealView::default(),
+            show_animation: false,
+            animation_k: 20,
+            animation: None,
+            playback: Playback::default(),
+            show_invalid_curve: false,
+            invalid_d: 123,
+            invalid_validate: false,
+            invalid_attack: None,
+            show_pohlig: false,
+            pohlig_secret: 37,
+            pohlig: None,
+            pohlig_job: None,
+            show_ecm: false,
+            ecm_n: 8051,
+            ecm: None,
+            show_ladder: false,
+            ladder_k: 77,
+            ladder_algorithm: MulAlgorithm::default(),
+            show_edwards: false,
+            edwards_a: 1,
+            edwards_d: 7,
+            edwards: None,
+            show_models: false,
+            models: None,
+            show_ecdsa: false,
+            ecdsa_group: None,
+            ecdsa_message: "pay Bob 5 coins".to_string(),
+            ecdsa_verifier: Verifier::default(),
+            ecdsa_ed25519: false,
+            ecdsa_steps: false,
+            ecdsa_keys: None,
+            ecdsa_p256: false,
+            ecdsa_random_nonces: false,
+            show_nonce_reuse: false,
+            reuse_keys: None,
+            reuse_messages: [
+                "pay 5 coins to Bob".to_string(),
+                "pay 500 coins to Mallory".to_string(),
+            ],
+            reuse_group: None,
+            ecdsa_bip340: false,
+            show_dlog: false,
+            dlog_secret: 37,
+            dlog: None,
+            dlog_job: None,
+            show_rho: false,
+            rho_secret: 37,
+            rho: None,
+            rho_job: None,
+            show_presets: false,
+            preset: 0,
+            preset_k: "2".to_string(),
+            preset_product: None,
+            show_net: false,
+            net: None,
+            show_isogenies: false,
+            isogenies: None,
+            isogeny_other: (1, 1),
+            show_explorer: false,
+            explorer_p: 13,
+            explorer_filter: explorer::Filter::default(),
+            explorer_sort: SortKey::default(),
+            explorer_descending: false,
+            explorers: LruCache::new(cache::EXPLORER_BUDGET),
+            explorer_job: None,
+            explorer_error: None,
+            show_lattice: false,
+            lattice: None,
+            lattice_selected: None,
+            lattice_highlight: Vec::new(),
+            long_coefficients: [0, 0, 1, -1, 0],
+            long_form: None,
+        }
+    }
+
+    fn set_curve(&mut self, curve: Curve, generator: &Point) {
+        self.view.set_curve(curve, generator);
+        self.p = curve.p;
+        (self.param_a, self.param_b, self.param_p) = (curve.a, curve.b, curve.p);
+        self.composite = !field::is_prime(curve.p);
+    }
+
+    fn apply_params(&mut self) {
+        let curve = Curve::new(self.param_a, self.param_b, self.param_p);
+        if curve.is_singular() {
+            // 不直接拒绝，让用户选择是否进入奇异曲线模式
+            self.pending_singular = Some(curve);
+            return;
+        }
+        self.load_curve(curve);
+    }
+
+    fn load_curve(&mut self, curve: Curve) {
+        self.pending_singular = None;
+        match curve.first_point() {
+            Some(generator) => {
+                self.param_error = None;
+                self.set_curve(curve, &generator);
+            }
+            None => {
+                tracing::warn!(?curve, "curve has no affine points");
+                self.param_error = Some("curve has no affine points".to_string());
+            }
+        }
+    }
+
+    fn track(&mut self, base: Point) {
+        let index = self.view.tracked.len();
+        self.view
+            .tracked
+            .push(TrackedPoint::new(&self.view.curve, base, index));
+    }
+
+    fn cosets(&mut self) -> &Cosets {
+        self.coset_base = self.coset_base.min(self.view.tracked.len() - 1);
+        let base = self
+            .clicked_point()
+            .unwrap_or_else(|| self.view.tracked[self.coset_base].base.coords());
+        let key = (self.view.curve, base);
+        if self
+            .coset_cache
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != key)
+        {
+            let mut coords: Vec<Coords> =
+                self.view.all_points().iter().map(Point::coords).collect();
+            // 无穷远点也属于某个陪集，放在最后，和 self.view.points 的下标保持一致
+            coords.push(None);
+            let h = subgroup::cyclic_subgroup(&self.view.curve, key.1);
+            self.coset_cache = Some((key, subgroup::cosets(&self.view.curve, &coords, &h)));
+        }
+        &self.coset_cache.as_ref().expect("cache was just filled").1
+    }
+
+    // 开启“点选的点生成的子群”并且正好选中一个点时，这个点
+    fn clicked_point(&self) -> Option<Coords> {
+        match *self.view.selection.as_slice() {
+            [point] if self.subgroup_of_click && !self.composite => Some(Some(point)),
+            _ => None,
+        }
+    }
+
+    // <P> 的全部元素，按 (曲线, P) 缓存
+    fn clicked_subgroup(&mut self) -> Option<&[Coords]> {
+        let g = self.clicked_point()?;
+        let key = (self.view.curve, g);
+        if self
+            .clicked_subgroup
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != key)
+        {
+            let point = Point::from_coords(&self.view.curve, g);
+            let elements = point
+                .generate_subgroup()
+                .iter()
+                .map(Point::coords)
+                .collect();
+            self.clicked_subgroup = Some((key, elements));
+        }
+        self.clicked_subgroup
+            .as_ref()
+            .map(|(_, elements)| elements.as_slice())
+    }
+
+    fn generators(&mut self) -> &[Coords] {
+        let curve = self.view.curve;
+        if self.generators.get(&curve).is_none() {
+            let generators: Vec<Coords> = curve.generators().iter().map(Point::coords).collect();
+            self.generators.insert(curve, generators);
+        }
+        self.generators
+            .peek(&curve)
+            .expect("p ≤ MAX_P, so the generators fit in the budget")
+    }
+
+    fn poll_summary(&mut self, ctx: &egui::Context) {
+        let current = self.view.curve;
+        if let Some((curve, job)) = &mut self.summary_job {
+            if let Some(summary) = job.poll() {
+                self.summaries.insert(*curve, summary);
+                self.summary_job = None;
+            } else if *curve != current {
+                // 换了曲线，旧曲线的统计不再需要；丢弃 Job 时工作线程随之取消
+                self.summary_job = None;
+            }
+        }
+        // 取消后的 Job 留在原处，直到换曲线或者点 Restart 才重新开始
+        if self.summary_job.is_none() && !self.composite && self.summaries.get(&current).is_none() {
+            let job = Job::spawn(ctx, move |progress| {
+                CurveSummary::compute_with(&current, progress)
+            });
+            self.summary_job = Some((current, job));
+        }
+    }
+
+    fn summary_panel(&mut self, ui: &mut egui::Ui) {
+        if self.composite {
+            ui.label(format!(
+                "p = {} is composite: Z/pZ is not a field, so there are no group statistics",
+                self.p
+            ));
+            return;
+        }
+        let Some(summary) = self.summaries.peek(&self.view.curve) else {
+            if let Some((_, job)) = &self.summary_job
+                && job_controls(ui, job, "Counting points for the curve statistics")
+            {
+                self.summary_job = None;
+            }
+            return;
+        };
+        let j = summary
+            .j_invariant
+            .map_or("undefined".to_string(), |j| j.to_string());
+        // Hasse 界只对非奇异曲线成立
+        let hasse = if self.view.curve.is_singular() {
+            String::new()
+        } else {
+            let (low, high) = self.view.curve.hasse_interval();
+            format!(" ∈ [{low}, {high}] (Hasse)")
+        };
+        ui.label(format!(
+            "#E = {}{}{hasse}   E ≅ {}   generators: {}   Δ = {}   j = {}",
+            summary.group_order,
+            if summary.is_prime_order() {
+                " (prime)"
+            } else {
+                ""
+            },
+            summary.structure_label(),
+            summary.generators,
+            summary.discriminant,
+            j
+        ));
+        let mut orders: Vec<String> = summary
+            .order_counts
+            .iter()
+            .take(SHOWN_ORDERS)
+            .map(|(order, count)| format!("{order}: {count}"))
+            .collect();
+        if summary.order_counts.len() > SHOWN_ORDERS {
+            orders.push("…".to_string());
+        }
+        ui.label(format!(
+            "points by order (order: count)   {}",
+            orders.join(", ")
+        ));
+        let points = &self.view.cache;
+        ui.weak(format!(
+            "cached curves: points {} ({} KiB of {} MiB), statistics {}",
+            points.len(),
+            points.used() >> 10,
+            points.budget() >> 20,
+            self.summaries.len()
+        ));
+    }
+
+    // 可折叠的日志面板，按级别过滤，显示相对启动的时间
+    fn log_panel(&mut self, ui: &mut egui::Ui) {
+        let entries = self.log.entries();
+        egui::CollapsingHeader::new(format!("Log ({} entries)", entries.len()))
+            .id_salt("log")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label("level")
+                        .selected_text(self.log_level.as_str())
+                        .show_ui(ui, |ui| {
+                            for level in [
+                                tracing::Level::ERROR,
+                                tracing::Level::WARN,
+                                tracing::Level::INFO,
+                                tracing::Level::DEBUG,
+                                tracing::Level::TRACE,
+                            ] {
+                                ui.selectable_value(&mut self.log_level, level, level.as_str());
+                            }
+                        });
+                    if ui.button("Clear log").clicked() {
+                        self.log.clear();
+                    }
+                });
+                egui::ScrollArea::vertical()
+                    .max_height(160.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for entry in entries.iter().filter(|e| e.level <= self.log_level) {
+                            let color = match entry.level {
+                                tracing::Level::ERROR => egui::Color32::RED,
+                                tracing::Level::WARN => egui::Color32::from_rgb(200, 120, 0),
+                                tracing::Level::INFO => ui.visuals().text_color(),
+                                _ => egui::Color32::GRAY,
+                            };
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{:>9.3}s {:<5} {}: {}",
+                                    entry.at.as_secs_f64(),
+                                    entry.level.as_str(),
+                                    entry.scope,
+                                    entry.message
+                                ))
+                                .monospace()
+                                .color(color),
+                            );
+                        }
+                    });
+            });
+    }
+
+    fn random_curve(&mut self) {
+        // 跳过只有无穷远点的曲线，生成元从曲线上的点中随机挑选
+        loop {
+            let curve = Curve::random(&mut self.rng, self.p);
+            let points = curve.nonsingular_points();
+            if !points.is_empty() {
+                let index = rng::uniform_below(&mut self.rng, points.len() as u64) as usize;
+                self.set_curve(curve, &points[index]);
+                return;
+            }
+        }
+    }
+
+    // 界面只收集操作，统一交给 perform 执行，录制和回放走的是同一条路径
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Controls");
+        let mut actions = Vec::new();
+        // 回放时不接受手动操作，否则会和录制的过程分叉
+        ui.add_enabled_ui(self.player.is_none(), |ui| {
+            self.curve_controls(ui, &mut actions);
+            self.tracked_controls(ui, &mut actions);
+            self.view_controls(ui, &mut actions);
+        });
+        // 图层只影响显示，回放时也可以切换
+        ui.separator();
+        egui::CollapsingHeader::new("Layers").show(ui, |ui| self.view.layers.ui(ui));
+        // 新窗口从当前曲线和第一个追踪点开始，之后各自独立
+        if ui.button("Open view in new window").clicked() {
+            let generator = self.view.tracked[0].base.clone();
+            let curve = self.view.curve;
+            self.windows
+                .push(ViewWindow::new(self.next_window, curve, &generator));
+            self.next_window += 1;
+        }
+        ui.separator();
+        ui.checkbox(&mut self.show_diagnostics, "Diagnostics");
+        ui.checkbox(&mut self.show_script, "Script editor");
+        ui.checkbox(&mut self.show_repl, "Expression console");
+        ui.checkbox(&mut self.show_ipa, "Inner-product argument");
+        ui.checkbox(&mut self.show_pedersen, "Pedersen hash");
+        ui.checkbox(&mut self.show_coinflip, "Coin flip by telephone");
+        ui.checkbox(&mut self.show_ecdh, "ECDH man in the middle");
+        ui.checkbox(&mut self.show_elgamal, "EC ElGamal");
+        ui.checkbox(&mut self.show_fault, "Fault injection");
+        ui.checkbox(&mut self.show_ecdsa, "ECDSA malleability");
+        ui.checkbox(&mut self.show_nonce_reuse, "ECDSA nonce reuse");
+        ui.checkbox(&mut self.show_net, "Elliptic net");
+        ui.checkbox(&mut self.show_isogenies, "Isogeny neighbours");
+        ui.checkbox(&mut self.show_explorer, "Curve explorer");
+        ui.checkbox(&mut self.show_lattice, "Subgroup lattice");
+        ui.checkbox(&mut self.show_dlog, "Discrete log (BSGS)");
+        ui.checkbox(&mut self.show_rho, "Discrete log (Pollard's rho)");
+        ui.checkbox(&mut self.show_presets, "Standard curves");
+        ui.checkbox(&mut self.show_ladder, "Double-and-add vs ladder");
+        ui.checkbox(&mut self.show_edwards, "Twisted Edwards");
+        ui.checkbox(&mut self.show_models, "Curve models");
+        ui.checkbox(&mut self.show_animation, "Animate k·P");
+        ui.checkbox(&mut self.show_invalid_curve, "Invalid-curve attack");
+        ui.checkbox(&mut self.show_pohlig, "Pohlig–Hellman attack");
+        ui.checkbox(&mut self.show_ecm, "ECM factorization");
+        ui.separator();
+        self.session_controls(ui);
+        for action in actions {
+            self.perform(action);
+        }
+    }
+
+    fn curve_controls(&mut self, ui: &mut egui::Ui, actions: &mut Vec<Action>) {
+        ui.horizontal(|ui| {
+            let label = ui.label("Seed:");
+            ui.add(egui::DragValue::new(&mut self.seed))
+                .labelled_by(label.id);
+        });
+        if ui.button("Reset RNG").clicked() {
+            actions.push(Action::ResetRng { seed: self.seed });
+        }
+        if ui.button("Random curve").clicked() {
+            actions.push(Action::RandomCurve);
+        }
+
+        ui.separator();
+        let mut edited = false;
+        ui.horizontal(|ui| {
+            let label = ui.label("a:");
+            edited |= ui
+                .add(egui::DragValue::new(&mut self.param_a).range(0..=self.param_p - 1))
+                .labelled_by(label.id)
+                .changed();
+            let label = ui.label("b:");
+            edited |= ui
+                .add(egui::DragValue::new(&mut self.param_b).range(0..=self.param_p - 1))
+                .labelled_by(label.id)
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            let label = ui.label("p:");
+            // 枚举是 O(p)，在后台分批进行，不会卡住界面
+            edited |= ui
+                .add(egui::DragValue::new(&mut self.param_p).range(3..=view::MAX_P))
+                .labelled_by(label.id)
+                .changed();
+            if edited {
+                actions.push(Action::EditParams {
+                    a: self.param_a,
+                    b: self.param_b,
+                    p: self.param_p,
+                });
+            }
+            if ui.button("Apply").clicked() {
+                actions.push(Action::Apply);
+            }
+        });
+        if !field::is_prime(self.param_p) {
+            ui.colored_label(
+                egui::Color32::from_rgb(200, 120, 0),
+                format!(
+                    "p = {} is composite: points can still be explored, but additions may hit \
+                     a denominator that is not invertible, which reveals a factor of p.",
+                    self.param_p
+                ),
+            );
+        }
+        let entered = Curve::new(self.param_a, self.param_b, self.param_p);
+        if self.pending_singular.is_none() && entered != self.view.curve && entered.is_singular() {
+            ui.colored_label(
+                egui::Color32::from_rgb(200, 120, 0),
+                "Warning: 4a³ + 27b² ≡ 0 (mod p) for these parameters, so the curve is singular. \
+                 Apply asks before loading it.",
+            );
+        }
+        if self.pending_singular.is_some() {
+            ui.colored_label(
+                egui::Color32::from_rgb(200, 120, 0),
+                "4a³ + 27b² ≡ 0: this curve is singular and the group law breaks at the singular point.",
+            );
+            ui.horizontal(|ui| {
+                if ui.button("Explore singular curve").clicked() {
+                    actions.push(Action::ExploreSingular);
+                }
+                if ui.button("Cancel").clicked() {
+                    actions.push(Action::CancelSingular);
+                }
+            });
+        }
+        if let Some(error) = &self.param_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        egui::CollapsingHeader::new("Long Weierstrass form")
+            .show(ui, |ui| self.long_form_controls(ui, actions));
+    }
+
+    // 按课本上的写法输入 y² + a1xy + a3y = x³ + a2x² + a4x + a6，化成短形式后照常 Apply
+    fn long_form_controls(&mut self, ui: &mut egui::Ui, actions: &mut Vec<Action>) {
+        ui.label("y² + a1·xy + a3·y = x³ + a2·x² + a4·x + a6 over F_p, with p from above");
+        ui.horizontal_wrapped(|ui| {
+            for (name, value) in ["a1:", "a2:", "a3:", "a4:", "a6:"]
+                .iter()
+                .zip(&mut self.long_coefficients)
+            {
+                let label = ui.label(*name);
+                ui.add(egui::DragValue::new(value).range(-999..=999))
+                    .labelled_by(label.id);
+            }
+        });
+        if ui.button("Reduce to short form").clicked() {
+            match LongWeierstrass::new(self.long_coefficients, self.param_p).reduce() {
+                Ok(reduction) => {
+                    let curve = reduction.curve;
+                    self.long_form = Some(reduction);
+                    actions.push(Action::EditParams {
+                        a: curve.a,
+                        b: curve.b,
+                        p: curve.p,
+                    });
+                    actions.push(Action::Apply);
+                }
+                Err(error) => self.param_error = Some(error),
+            }
+        }
+        let Some(reduction) = self.long_form.filter(|r| r.curve == self.view.curve) else {
+            return;
+        };
+        ui.label(format!(
+            "{} is y² = x³ + {}x + {} via X = 36x + 3b2, Y = 108(2y + a1·x + a3)",
+            reduction.long, reduction.curve.a, reduction.curve.b
+        ));
+        for tracked in &self.view.tracked {
+            let long = match reduction.map_to_long(tracked.base.coords()) {
+                Some((x, y)) => format!("({x}, {y})"),
+                None => "Infinity".to_string(),
+            };
+            ui.label(format!(
+                "{} on the short form is {long} on the long form",
+                tracked.base
+            ));
+        }
+    }
+
+    fn tracked_controls(&mut self, ui: &mut egui::Ui, actions: &mut Vec<Action>) {
+        ui.separator();
+        ui.heading("Tracked points");
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let removable = self.view.tracked.len() > 1;
+            for (index, tracked) in self.view.tracked.iter_mut().enumerate() {
+                ui.push_id(index, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.color_edit_button_srgba(&mut tracked.color);
+                        ui.label(format!("P = {}", tracked.base));
+                        if removable && ui.small_button("✖").clicked() {
+                            actions.push(Action::RemoveTracked(index));
+                        }
+                    });
+                    let mut trajectory = tracked.trajectory;
+                    ui.radio_value(&mut trajectory, Trajectory::Multiples, "P, 2P, 3P, ..., kP");
+                    ui.radio_value(
+                        &mut trajectory,
+                        Trajectory::Doublings,
+                        "P, 2P, 4P, ..., 2^k P",
+                    );
+                    if trajectory != tracked.trajectory {
+                        actions.push(Action::SetTrajectory { index, trajectory });
+                    }
+                    ui.horizontal(|ui| {
+                        let label = ui.label("k:");
+                        let mut k = tracked.k;
+                        let response = ui
+                            .add(egui::DragValue::new(&mut k).range(1..=tracked.trajectory.max_k()))
+                            .labelled_by(label.id);
+                        if response.changed() {
+                            actions.push(Action::SetK { index, k });
+                        }
+                    });
+                });
+                ui.separator();
+            }
+        });
+
+        let (mut x, mut y) = self.new_base;
+        ui.horizontal(|ui| {
+            let label = ui.label("x:");
+            ui.add(egui::DragValue::new(&mut x).range(0..=self.p - 1))
+                .labelled_by(label.id);
+            let label = ui.label("y:");
+            ui.add(egui::DragValue::new(&mut y).range(0..=self.p - 1))
+                .labelled_by(label.id);
+        });
+        if (x, y) != self.new_base {
+            actions.push(Action::EditNewBase { x, y });
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Add point").clicked() {
+                actions.push(Action::AddPoint);
+            }
+            if ui.button("Add random point").clicked() {
+                actions.push(Action::AddRandomPoint);
+            }
+        });
+        if let Some(error) = &self.new_base_error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+    }
+
+    fn view_controls(&mut self, ui: &mut egui::Ui, actions: &mut Vec<Action>) {
+        ui.separator();
+        let mut show_field = self.show_field;
+        ui.add_enabled(
+            !self.composite,
+            egui::Checkbox::new(&mut show_field, "Vector field: P → P + G"),
+        );
+        if show_field != self.show_field {
+            actions.push(Action::ShowField(show_field));
+        }
+        if self.show_field && !self.composite {
+            let mut base = self.field_base.min(self.view.tracked.len() - 1);
+            egui::ComboBox::from_label("G")
+                .selected_text(self.view.tracked[base].base.to_string())
+                .show_ui(ui, |ui| {
+                    for (i, tracked) in self.view.tracked.iter().enumerate() {
+                        ui.selectable_value(&mut base, i, tracked.base.to_string());
+                    }
+                });
+            if base != self.field_base {
+                actions.push(Action::FieldBase(base));
+            }
+        }
+
+        ui.separator();
+        let mut show_cosets = self.show_cosets;
+        ui.add_enabled(
+            !self.composite,
+            egui::Checkbox::new(&mut show_cosets, "Color cosets of H"),
+        );
+        if show_cosets != self.show_cosets {
+            actions.push(Action::ShowCosets(show_cosets));
+        }
+        if self.show_cosets && !self.composite {
+            let mut base = self.coset_base.min(self.view.tracked.len() - 1);
+            egui::ComboBox::from_label("H = <P>")
+                .selected_text(self.view.tracked[base].base.to_string())
+                .show_ui(ui, |ui| {
+                    for (i, tracked) in self.view.tracked.iter().enumerate() {
+                        ui.selectable_value(&mut base, i, tracked.base.to_string());
+                    }
+                });
+            if base != self.coset_base {
+                actions.push(Action::CosetBase(base));
+            }
+            let cosets = self.cosets();
+            ui.label(format!(
+                "|H| = {}, [E : H] = {}",
+                cosets.subgroup_order, cosets.count
+            ));
+        }
+
+        ui.add_enabled(
+            !self.composite,
+            egui::Checkbox::new(&mut self.subgroup_of_click, "Subgroup of the clicked point"),
+        );
+        if self.subgroup_of_click && !self.composite {
+            let group_order = self.view.all_points().len() + 1;
+            match (
+                self.clicked_point(),
+                self.clicked_subgroup().map(<[_]>::len),
+            ) {
+                (Some(Some((x, y))), Some(order)) => ui.label(format!(
+                    "<({x}, {y})> has order {order} and index {} in E",
+                    group_order / order
+                )),
+                _ => ui.label("Click a point to highlight the subgroup it generates"),
+            };
+        }
+
+        ui.add_enabled(
+            !self.composite,
+            egui::Checkbox::new(&mut self.show_generators, "Highlight generators"),
+        );
+        if self.show_generators && !self.composite {
+            let count = self.generators().len();
+            let group_order = self.view.all_points().len() + 1;
+            ui.colored_label(
+                GENERATOR,
+                if count == 0 {
+                    "E is not cyclic, so no point generates it".to_string()
+                } else {
+                    format!("{count} of the {group_order} points generate E")
+                },
+            );
+        }
+
+        ui.separator();
+        let mut show_x_stats = self.show_x_stats;
+        ui.add_enabled(
+            !self.composite,
+            egui::Checkbox::new(&mut show_x_stats, "x-coordinate statistics"),
+        );
+        if show_x_stats != self.show_x_stats {
+            actions.push(Action::ShowXStats(show_x_stats));
+        }
+    }
+
+    // 修改状态的唯一入口；录制时顺便记下操作
+    fn perform(&mut self, action: Action) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(action);
+        }
+        match action {
+            Action::EditParams { a, b, p } => {
+                (self.param_a, self.param_b, self.param_p) = (a, b, p);
+            }
+            Action::Apply => self.apply_params(),
+            Action::ExploreSingular => {
+                if let Some(curve) = self.pending_singular {
+                    self.load_curve(curve);
+                }
+            }
+            Action::CancelSingular => self.pending_singular = None,
+            Action::ResetRng { seed } => {
+                self.seed = seed;
+                self.rng = rng::seeded(seed);
+            }
+            Action::RandomCurve => self.random_curve(),
+            Action::EditNewBase { x, y } => self.new_base = (x, y),
+            Action::AddPoint => {
+                let (x, y) = self.new_base;
+                match self.view.curve.try_point(x, y) {
+                    Ok(point) => {
+                        self.new_base_error = None;
+                        self.track(point);
+                    }
+                    Err(error) => {
+                        tracing::warn!(x, y, "point is not on the curve");
+                        self.new_base_error = Some(error.to_string());
+                    }
+                }
+            }
+            Action::AddRandomPoint => {
+                let points = self.view.all_points();
+                if !points.is_empty() {
+                    let index = rng::uniform_below(&mut self.rng, points.len() as u64) as usize;
+                    let point = points[index].clone();
+                    self.track(point);
+                }
+            }
+            // 回放的录制可能来自别的文件，下标不合法时忽略
+            Action::RemoveTracked(index) => {
+                if self.view.tracked.len() > 1 && index < self.view.tracked.len() {
+                    self.view.tracked.remove(index);
+                }
+            }
+            Action::SetTrajectory { index, trajectory } => {
+                if let Some(tracked) = self.view.tracked.get_mut(index) {
+                    tracked.trajectory = trajectory;
+                    tracked.recompute(&self.view.curve);
+                }
+            }
+            Action::SetK { index, k } => {
+                if let Some(tracked) = self.view.tracked.get_mut(index) {
+                    tracked.k = k;
+                    tracked.recompute(&self.view.curve);
+                }
+            }
+            Action::ShowField(on) => self.show_field = on,
+            Action::FieldBase(index) => self.field_base = index,
+            Action::ShowCosets(on) => self.show_cosets = on,
+            Action::CosetBase(index) => self.coset_base = index,
+            Action::ShowXStats(on) => self.show_x_stats = on,
+        }
+    }
+
+    // 脚本从当前曲线和种子开始运行，它产生的界面操作照常经过 perform，
+    // 所以录制中运行的脚本也会被录下
+    pub fn run_script(&mut self, source: String) -> &script::Output {
+        let output = script::run(&source, self.view.curve, self.seed);
+        for &action in &output.actions {
+            self.perform(action);
+        }
+        self.script = source;
+        self.show_script = true;
+        self.script_output.insert(output)
+    }
+
+    // 控制台在当前曲线上求值，和脚本一样，产生的界面操作经过 perform
+    fn eval_expression(&mut self, input: String) {
+        self.repl.follow(self.view.curve);
+        let output = self.repl.eval(&input);
+        for &action in &output.actions {
+            self.perform(action);
+        }
+        self.repl_history.push((input, output));
+    }
+
+    // 开始录制：随机数从种子重新开始，当前的追踪点和视图开关作为 0 时刻的操作记下，
+    // 这样回放时能从同样的状态出发
+    fn start_recording(&mut self) {
+        self.rng = rng::seeded(self.seed);
+        let base = self.view.tracked[0]
+            .base
+            .coords()
+            .expect("tracked base points are affine");
+        let mut recorder = Recorder::new(Start {
+            seed: self.seed,
+            curve: (self.view.curve.a, self.view.curve.b, self.view.curve.p),
+            base,
+        });
+        for (index, tracked) in self.view.tracked.iter().enumerate() {
+            if index > 0
+                && let Some((x, y)) = tracked.base.coords()
+            {
+                recorder.record(Action::EditNewBase { x, y });
+                recorder.record(Action::AddPoint);
+            }
+            recorder.record(Action::SetTrajectory {
+                index,
+                trajectory: tracked.trajectory,
+            });
+            recorder.record(Action::SetK {
+                index,
+                k: tracked.k,
+            });
+        }
+        recorder.record(Action::ShowField(self.show_field));
+        recorder.record(Action::ShowCosets(self.show_cosets));
+        recorder.record(Action::ShowXStats(self.show_x_stats));
+        tracing::info!("recording started");
+        self.recorder = Some(recorder);
+    }
+
+    fn start_replay(&mut self, recording: Recording) {
+        let Start {
+            seed,
+            curve: (a, b, p),
+            base: (x, y),
+        } = recording.start;
+        let curve = Curve::new(a, b, p);
+        self.pending_singular = None;
+        self.param_error = None;
+        self.new_base_error = None;
+        self.set_curve(curve, &curve.point(x, y));
+        self.seed = seed;
+        self.rng = rng::seeded(seed);
+        tracing::info!(events = recording.events.len(), "replaying session");
+        self.player = Some(Player::new(recording, self.replay_speed));
+    }
+
+    fn poll_replay(&mut self, ctx: &egui::Context) {
+        let Some(player) = &mut self.player else {
+            return;
+        };
+        let due = player.due();
+        if let Some(wait) = player.until_next() {
+            ctx.request_repaint_after(wait);
+        }
+        let finished = player.is_finished();
+        for action in due {
+            self.perform(action);
+        }
+        if finished {
+            tracing::info!("replay finished");
+            self.player = None;
+        }
+    }
+
+    fn session_controls(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Session");
+        ui.horizontal(|ui| {
+            if let Some(recorder) = &self.recorder {
+                let stop = ui.button("Stop recording").clicked();
+                ui.colored_label(egui::Color32::RED, format!("● {} events", recorder.len()));
+                if stop {
+                    let recording = self.recorder.take().expect("recording is active").finish();
+                    tracing::info!(events = recording.events.len(), "recording stopped");
+                    self.session = Some(recording);
+                }
+            } else if self.player.is_none() && ui.button("Record").clicked() {
+                self.start_recording();
+            }
+        });
+
+        if let Some(player) = &self.player {
+            let (done, total) = player.progress();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::ProgressBar::new(done as f32 / total.max(1) as f32)
+                        .text(format!("{done} / {total}")),
+                );
+                if ui.button("Stop replay").clicked() {
+                    self.player = None;
+                }
+            });
+        } else if let Some(recording) = &self.session {
+            let info = format!(
+                "{} events, {:.1}s",
+                recording.events.len(),
+                recording.duration().as_secs_f32()
+            );
+            let mut play = false;
+            ui.horizontal(|ui| {
+                play = ui
+                    .add_enabled(self.recorder.is_none(), egui::Button::new("Replay"))
+                    .clicked();
+                ui.add(
+                    egui::Slider::new(&mut self.replay_speed, 0.25..=8.0)
+                        .logarithmic(true)
+                        .suffix("×"),
+                );
+                ui.label(info);
+            });
+            if play && let Some(recording) = self.session.clone() {
+                self.start_replay(recording);
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let label = ui.label("File:");
+            ui.text_edit_singleline(&mut self.session_path)
+                .labelled_by(label.id);
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.session.is_some(), egui::Button::new("Export"))
+                .clicked()
+                && let Some(recording) = &self.session
+            {
+                self.session_status = Some(
+                    std::fs::write(&self.session_path, recording.to_string())
+                        .map(|()| format!("saved to {}", self.session_path))
+                        .map_err(|error| error.to_string()),
+                );
+            }
+            if ui.button("Import").clicked() {
+                let loaded = std::fs::read_to_string(&self.session_path)
+                    .map_err(|error| error.to_string())
+                    .and_then(|text| text.parse::<Recording>());
+                self.session_status = Some(match loaded {
+                    Ok(recording) => {
+                        let message = format!("loaded {} events", recording.events.len());
+                        self.session = Some(recording);
+                        Ok(message)
+                    }
+                    Err(error) => Err(error),
+                });
+            }
+        });
+        match &self.session_status {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            None => {}
+        }
+    }
+
+    // 合数模数下加法失败时弹出找到的因子
+    fn factor_window(&mut self, ctx: &egui::Context) {
+        let Some(failure) = self.view.tracked.iter().find_map(|t| t.failure.clone()) else {
+            return;
+        };
+        if self.dismissed_factor.as_ref() == Some(&failure) {
+            return;
+        }
+        let EcError::NonInvertible {
+            value,
+            modulus,
+            gcd,
+        } = failure
+        else {
+            return;
+        };
+        let mut dismissed = false;
+        egui::Window::new("Factor of p discovered")
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Adding two points needed the inverse of {value} mod {modulus}, \
+                     but gcd({value}, {modulus}) = {gcd}."
+                ));
+                if gcd > 1 && gcd < modulus {
+                    ui.strong(format!("{modulus} = {gcd} × {}", modulus / gcd));
+                } else {
+                    ui.label("The gcd is the modulus itself, so no proper factor was found.");
+                }
+                dismissed = ui.button("OK").clicked();
+            });
+        if dismissed {
+            self.dismissed_factor = Some(failure);
+        }
+    }
+
+    fn diagnostics_window(&mut self, ctx: &egui::Context) {
+        if let Some(rx) = &self.diagnostics_job {
+            match rx.try_recv() {
+                Ok(results) => {
+                    self.diagnostics = Some(results);
+                    self.diagnostics_job = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => self.diagnostics_job = None,
+            }
+        }
+
+        let mut open = self.show_diagnostics;
+        egui::Window::new("Diagnostics")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(diagnostics::build_info());
+                ui.horizontal(|ui| {
+                    let running = self.diagnostics_job.is_some();
+                    if ui
+                        .add_enabled(!running, egui::Button::new("Run checks"))
+                        .clicked()
+                    {
+                        let (tx, rx) = mpsc::channel();
+                        let ctx = ctx.clone();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(diagnostics::run_all());
+                            ctx.request_repaint();
+                        });
+                        self.diagnostics_job = Some(rx);
+                    }
+                    if running {
+                        ui.spinner();
+                    }
+                });
+                self.selfcheck_section(ui);
+                let Some(results) = &self.diagnostics else {
+                    return;
+                };
+                ui.separator();
+                egui::Grid::new("diagnostics").striped(true).show(ui, |ui| {
+                    for result in results {
+                        ui.label(result.name);
+                        let (status, color) = if result.passed() {
+                            ("PASS", egui::Color32::DARK_GREEN)
+                        } else {
+                            ("FAIL", egui::Color32::RED)
+                        };
+                        let (Ok(detail) | Err(detail)) = &result.outcome;
+                        ui.label(egui::RichText::new(status).color(color).strong());
+                        ui.label(format!("{:.1?}", result.elapsed));
+                        ui.label(detail);
+                        ui.end_row();
+                    }
+                });
+            });
+        self.show_diagnostics = open;
+    }
+
+    // 每次用共享随机源取一个新种子，结果里带着种子，命令行 `selfcheck --seed` 可以复现
+    fn selfcheck_section(&mut self, ui: &mut egui::Ui) {
+        if let Some((curve, job)) = &mut self.selfcheck_job
+            && let Some(result) = job.poll()
+        {
+            self.selfcheck = Some((*curve, result));
+            self.selfcheck_job = None;
+        }
+        let curve = self.view.curve;
+        ui.horizontal(|ui| {
+            let running = self.selfcheck_job.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Self-check curve"))
+                .on_hover_text("Group axioms on random point triples, and ord(G)·G = O")
+                .clicked()
+            {
+                let seed = self.rng.next_u64();
+                let job = Job::spawn(ui.ctx(), move |_| {
+                    Some(diagnostics::check_curve(
+                        &curve,
+                        diagnostics::SELFCHECK_TRIPLES,
+                        seed,
+                    ))
+                });
+                self.selfcheck_job = Some((curve, job));
+            }
+            if running {
+                ui.spinner();
+            }
+        });
+        if let Some((checked, result)) = &self.selfcheck {
+            ui.horizontal_wrapped(|ui| {
+                let (status, color, detail) = match result {
+                    Ok(check) => ("PASS", egui::Color32::DARK_GREEN, check.to_string()),
+                    Err(error) => ("FAIL", egui::Color32::RED, error.clone()),
+                };
+                ui.label(egui::RichText::new(status).color(color).strong());
+                ui.label(format!(
+                    "y² = x³ + {}x + {} (mod {}): {detail}",
+                    checked.a, checked.b, checked.p
+                ));
+            });
+        }
+    }
+
+    fn script_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_script;
+        egui::Window::new("Script").open(&mut open).show(ctx, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.script)
+                    .code_editor()
+                    .desired_rows(12)
+                    .desired_width(f32::INFINITY),
+            );
+            let run = ui
+                .add_enabled(self.player.is_none(), egui::Button::new("Run"))
+                .clicked();
+            if let Some(output) = &self.script_output {
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for line in &output.lines {
+                            ui.monospace(line);
+                        }
+                        if let Some(error) = &output.error {
+                            ui.colored_label(egui::Color32::RED, error);
+                        }
+                    });
+            }
+            if run {
+                self.run_script(self.script.clone());
+            }
+        });
+        self.show_script = open;
+    }
+
+    fn repl_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_repl;
+        egui::Window::new("Expression console")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for (input, output) in &self.repl_history {
+                            ui.monospace(format!("> {input}"));
+                            for line in &output.lines {
+                                ui.monospace(line);
+                            }
+                            if let Some(error) = &output.error {
+                                ui.colored_label(egui::Color32::RED, error);
+                            }
+                        }
+                    });
+                ui.separator();
+                let mut submit = false;
+                ui.horizontal(|ui| {
+                    let input = ui.add(
+                        egui::TextEdit::singleline(&mut self.repl_input)
+                            .code_editor()
+                            .hint_text("P = (1, 188); Q = 3*P + P; order(Q)"),
+                    );
+                    // 回车提交后焦点留在输入框里
+                    if input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        submit = true;
+                        input.request_focus();
+                    }
+                    submit |= ui
+                        .add_enabled(self.player.is_none(), egui::Button::new("Evaluate"))
+                        .clicked();
+                });
+                if submit && self.player.is_none() && !self.repl_input.trim().is_empty() {
+                    let input = std::mem::take(&mut self.repl_input);
+                    self.eval_expression(input);
+                }
+            });
+        self.show_repl = open;
+    }
+
+    fn ipa_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_ipa;
+        egui::Window::new("Inner-product argument")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Prove knowledge of vectors a, b with P = <a, G> + <b, H> + <a, b> U. \
+                     Each round halves the vectors and sends two points L, R.",
+                );
+                ui.horizontal(|ui| {
+                    ui.label("n:");
+                    for n in [2, 4, 8, 16] {
+                        ui.selectable_value(&mut self.ipa_n, n, n.to_string());
+                    }
+                    if ui.button("Prove").clicked() {
+                        // 用自己的 RNG，不打乱主 RNG，录制的会话回放时结果不变
+                        let mut rng = rng::seeded(self.seed);
+                        self.ipa = Some(ipa::Demo::run(&self.view.curve, self.ipa_n, &mut rng));
+                        self.ipa_round = 0;
+                    }
+                });
+                match &self.ipa {
+                    None => {}
+                    Some(Err(error)) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                    Some(Ok(demo)) => ipa_demo(ui, demo, &mut self.ipa_round),
+                }
+            });
+        self.show_ipa = open;
+    }
+
+    fn pedersen_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let stale = match &self.pedersen {
+            Some(Ok(hasher)) => hasher.curve != curve,
+            _ => true,
+        };
+        if stale {
+            self.pedersen = Some(PedersenHash::new(&curve));
+            self.pedersen_collision = None;
+        }
+        let mut open = self.show_pedersen;
+        egui::Window::new("Pedersen hash")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let hasher = match self.pedersen.as_mut().expect("hasher was just set") {
+                    Ok(hasher) => hasher,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error.as_str());
+                        return;
+                    }
+                };
+                ui.label(format!(
+                    "H(m) = Σ (m_i + 1)·G_i over {}-bit chunks m_i, generators hashed to the \
+                     subgroup of order q = {}",
+                    hasher.chunk_bits, hasher.q
+                ));
+                ui.horizontal(|ui| {
+                    let label = ui.label("Message:");
+                    if ui
+                        .text_edit_singleline(&mut self.pedersen_message)
+                        .labelled_by(label.id)
+                        .changed()
+                    {
+                        self.pedersen_collision = None;
+                    }
+                });
+                let message = self.pedersen_message.as_bytes();
+                let chunks = hasher.chunks(message);
+                ui.label(format!("chunks: {chunks:?}"));
+                ui.label(format!("H(m) = {}", Coordinates(hasher.hash(message))));
+                if ui.button("Find collision").clicked() {
+                    self.pedersen_collision = Some(hasher.find_collision(message));
+                }
+                match &self.pedersen_collision {
+                    None => {}
+                    Some(None) => {
+                        ui.label(
+                            "Message too short: the first two chunks must not contain padding",
+                        );
+                    }
+                    Some(Some(collision)) => {
+                        ui.label(format!(
+                            "log_G0 G1 = {} found by brute force in {} steps",
+                            collision.log, collision.steps
+                        ));
+                        ui.label(format!(
+                            "m' = {:?} (hex {})",
+                            String::from_utf8_lossy(&collision.message),
+                            sha256::hex(&collision.message)
+                        ));
+                        ui.label(format!("H(m') = {:?}", hasher.hash(&collision.message)));
+                        // SHA-256 没有这样的代数结构，同样的两条消息哈希值不同
+                        ui.label(format!(
+                            "SHA-256(m) = {}…, SHA-256(m') = {}…",
+                            &sha256::hex(&sha256::sha256(message))[..16],
+                            &sha256::hex(&sha256::sha256(&collision.message))[..16]
+                        ));
+                        ui.weak(
+                            "A collision gives a relation between the generators, i.e. a discrete \
+                             log. In a 256-bit group that takes about 2^128 steps.",
+                        );
+                    }
+                }
+            });
+        self.show_pedersen = open;
+    }
+
+    fn coinflip_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let stale = match &self.coinflip {
+            Some(Ok(game)) => game.params.curve != curve,
+            _ => true,
+        };
+        if stale {
+            // 和其它演示一样用自己的 RNG，不影响录制的会话
+            self.coinflip = Some(CoinFlip::new(&curve, self.seed));
+        }
+        let mut open = self.show_coinflip;
+        egui::Window::new("Coin flip by telephone")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let game = match self.coinflip.as_mut().expect("game was just set") {
+                    Ok(game) => game,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error.as_str());
+                        return;
+                    }
+                };
+                ui.label(format!(
+                    "Alice commits to the coin with C = b·G + r·H (q = {}), Bob guesses, \
+                     Alice opens the commitment.",
+                    game.params.q
+                ));
+                ui.columns(2, |columns| {
+                    let ui = &mut columns[0];
+                    ui.strong("Alice");
+                    if ui
+                        .add_enabled(
+                            game.stage == Stage::Commit,
+                            egui::Button::new("Flip and commit"),
+                        )
+                        .clicked()
+                    {
+                        game.commit();
+                    }
+                    if let Some((bit, blinding)) = game.secret() {
+                        ui.label(format!(
+                            "secret: b = {}, r = {}",
+                            bit as u8,
+                            blinding.value()
+                        ));
+                    }
+                    let revealing = game.stage == Stage::Reveal;
+                    if ui
+                        .add_enabled(revealing, egui::Button::new("Reveal"))
+                        .clicked()
+                    {
+                        game.reveal();
+                    }
+                    if ui
+                        .add_enabled(revealing, egui::Button::new("Cheat: claim the other side"))
+                        .clicked()
+                    {
+                        game.cheat();
+                    }
+
+                    let ui = &mut columns[1];
+                    ui.strong("Bob");
+                    if game.stage != Stage::Commit {
+                        ui.label(format!("received C = {}", Coordinates(game.commitment)));
+                    }
+                    let guessing = game.stage == Stage::Guess;
+                    for (label, heads) in [("Guess heads", true), ("Guess tails", false)] {
+                        if ui.add_enabled(guessing, egui::Button::new(label)).clicked() {
+                            game.guess(heads);
+                        }
+                    }
+                });
+                ui.separator();
+                for line in &game.transcript {
+                    ui.monospace(line);
+                }
+                if let Some(outcome) = game.outcome {
+                    let (text, color) = match outcome {
+                        Outcome::BobWins => ("Bob wins", egui::Color32::DARK_GREEN),
+                        Outcome::AliceWins => ("Alice wins", egui::Color32::DARK_GREEN),
+                        Outcome::CheatDetected => ("Cheating detected", egui::Color32::RED),
+                    };
+                    ui.label(egui::RichText::new(text).color(color).strong());
+                    if ui.button("Play again").clicked() {
+                        game.restart();
+                    }
+                }
+            });
+        self.show_coinflip = open;
+    }
+
+    fn ecdh_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let stale = match &self.ecdh_group {
+            Some(Ok(group)) => group.curve != curve,
+            _ => true,
+        };
+        if stale {
+            self.ecdh_group = Some(PrimeGroup::new(&curve));
+            self.ecdh = None;
+            self.ecdh_secrets = None;
+        }
+        let mut open = self.show_ecdh;
+        egui::Window::new("ECDH man in the middle")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let group = match self.ecdh_group.as_ref().expect("group was just set") {
+                    Ok(group) => group,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error.as_str());
+                        return;
+                    }
+                };
+                ui.label(format!(
+                    "Alice and Bob exchange a·G and b·G in the subgroup of order q = {}, \
+                     G = {}.",
+                    group.q,
+                    Coordinates(group.g)
+                ));
+                let config = self.ecdh_config;
+                ui.checkbox(&mut self.ecdh_config.mallory, "Mallory in the middle");
+                ui.checkbox(&mut self.ecdh_config.authenticate, "Sign key shares");
+                ui.checkbox(&mut self.ecdh_config.x25519, "Real world: X25519");
+                // 用自己的 RNG，交换的结果只取决于种子、选项和手动选的私钥
+                let run = |secrets: Option<&(SecretScalar, SecretScalar)>| {
+                    Exchange::run(group, self.ecdh_config, secrets, self.seed)
+                };
+                let (a, b) = &self
+                    .ecdh
+                    .get_or_insert_with(|| run(self.ecdh_secrets.as_ref()))
+                    .secrets;
+                let labels = ["Alice's a:", "Bob's b:", "Randomize keys"];
+                let chosen = ui
+                    .horizontal(|ui| edit_secret_pair(ui, labels, (a, b), &mut self.rng))
+                    .inner;
+                if chosen.is_some() {
+                    self.ecdh_secrets = chosen;
+                    self.ecdh = None;
+                }
+                if self.ecdh_config != config {
+                    self.ecdh = None;
+                }
+                let exchange = self
+                    .ecdh
+                    .get_or_insert_with(|| run(self.ecdh_secrets.as_ref()));
+                let (alice_public, bob_public) = exchange.shares;
+                ui.label(format!(
+                    "A = a·G = {}, B = b·G = {}; \
+                     Alice computes a·B, Bob computes b·A",
+                    Coordinates(alice_public),
+                    Coordinates(bob_public)
+                ));
+
+                let secret = |ui: &mut egui::Ui, label: &str, secret: Option<Coords>| match secret {
+                    Some(secret) => ui.label(format!("{label}: {}", Coordinates(secret))),
+                    None => ui.colored_label(egui::Color32::RED, format!("{label}: aborted")),
+                };
+                ui.columns(3, |columns| {
+                    columns[0].strong("Alice");
+                    secret(&mut columns[0], "secret", exchange.alice);
+                    columns[1].strong("Mallory");
+                    match exchange.mallory {
+                        Some((with_alice, with_bob)) => {
+                            secret(&mut columns[1], "with Alice", Some(with_alice));
+                            secret(&mut columns[1], "with Bob", Some(with_bob));
+                        }
+                        None if self.ecdh_config.mallory => {
+                            columns[1].label("could not finish the attack");
+                        }
+                        None => {
+                            columns[1].label("not on the line");
+                        }
+                    }
+                    columns[2].strong("Bob");
+                    secret(&mut columns[2], "secret", exchange.bob);
+                });
+                ui.separator();
+                for entry in &exchange.transcript {
+                    let color = match entry.party {
+                        Party::Mallory => egui::Color32::from_rgb(200, 60, 60),
+                        Party::Alice | Party::Bob => ui.visuals().text_color(),
+                    };
+                    ui.label(
+                        egui::RichText::new(entry.to_string())
+                            .monospace()
+                            .color(color),
+                    );
+                }
+                let (text, color) = if exchange.compromised() {
+                    (
+                        "Alice and Bob hold different secrets, Mallory knows both",
+                        egui::Color32::RED,
+                    )
+                } else if exchange.agreed() {
+                    ("Alice and Bob share a secret", egui::Color32::DARK_GREEN)
+                } else {
+                    (
+                        "The substituted key shares were rejected",
+                        egui::Color32::DARK_GREEN,
+                    )
+                };
+                ui.label(egui::RichText::new(text).color(color).strong());
+
+                let Some(real) = &exchange.real_world else {
+                    return;
+                };
+                ui.separator();
+                ui.label(
+                    "The same exchange with X25519 (RFC 7748) over 2^255 − 19: 32-byte secrets, \
+                     clamped, and only u-coordinates on the wire. Mallory and the signatures \
+                     above act on the toy group only.",
+                );
+                for (label, bytes) in [
+                    ("Alice sends", &real.alice_public),
+                    ("Bob sends", &real.bob_public),
+                    ("Alice computes", &real.alice),
+                    ("Bob computes", &real.bob),
+                ] {
+                    ui.label(
+                        egui::RichText::new(format!("{label:>14}: {}", sha256::hex(bytes)))
+                            .monospace(),
+                    );
+                }
+                if real.alice == real.bob {
+                    ui.colored_label(egui::Color32::DARK_GREEN, "The X25519 secrets agree");
+                } else {
+                    ui.colored_label(egui::Color32::RED, "The X25519 secrets differ");
+                }
+            });
+        self.show_ecdh = open;
+    }
+
+    fn elgamal_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let stale = match &self.elgamal_group {
+            Some(Ok(group)) => group.curve != curve,
+            _ => true,
+        };
+        if stale {
+            self.elgamal_group = Some(PrimeGroup::new(&curve));
+            self.elgamal = None;
+            self.elgamal_keys = None;
+        }
+        let mut open = self.show_elgamal;
+        egui::Window::new("EC ElGamal")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let group = match self.elgamal_group.as_ref().expect("group was just set") {
+                    Ok(group) => group,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error.as_str());
+                        self.elgamal = None;
+                        return;
+                    }
+                };
+                ui.label(format!(
+                    "Bob publishes Y = x·G in the subgroup of order q = {}, G = {}. Alice \
+                     encodes her message as a point M and sends (C1, C2) = (k·G, M + k·Y) with \
+                     a fresh k; Bob removes the mask with C2 − x·C1.",
+                    group.q,
+                    Point::from_coords(&curve, group.g)
+                ));
+                let max_message = elgamal::max_message(&curve);
+                let (secret, nonce) = self
+                    .elgamal_keys
+                    .get_or_insert_with(|| (demo_secret(17, group.q), demo_secret(5, group.q)));
+                self.elgamal_message = self.elgamal_message.min(max_message);
+                ui.horizontal(|ui| {
+                    if let Some(edited) = edit_secret(ui, "Bob's x:", secret) {
+                        *secret = edited;
+                    }
+                    let label = ui.label("Message m:");
+                    ui.add(egui::DragValue::new(&mut self.elgamal_message).range(0..=max_message))
+                        .labelled_by(label.id);
+                });
+                ui.horizontal(|ui| {
+                    if let Some(edited) = edit_secret(ui, "Alice's k:", nonce) {
+                        *nonce = edited;
+                    }
+                    if ui.button("Fresh k").clicked() {
+                        *nonce = SecretScalar::new(Scalar::random(&mut self.rng, group.q));
+                    }
+                });
+
+                let Some(encoding) = elgamal::encode(&curve, self.elgamal_message) else {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!(
+                            "none of x = {m}·{k}, ..., {m}·{k} + {} is on the curve",
+                            elgamal::KOBLITZ_K - 1,
+                            m = self.elgamal_message,
+                            k = elgamal::KOBLITZ_K,
+                        ),
+                    );
+                    self.elgamal = None;
+                    return;
+                };
+                let (x, y) = encoding.point;
+                ui.label(format!(
+                    "Encoding: x = {}·{} + {} = {x} is the first x with x³ + ax + b a square, \
+                     so M = ({x}, {y}). Messages up to {max_message} fit below p = {}.",
+                    encoding.message,
+                    elgamal::KOBLITZ_K,
+                    encoding.tries - 1,
+                    curve.p
+                ));
+                let bob = elgamal::KeyPair::from_secret(group, *secret.expose());
+                let message = Some(encoding.point);
+                let k = *nonce.expose();
+                let ciphertext = elgamal::encrypt_with(group, bob.public, message, k);
+                let mask = group.mul(bob.public, k);
+                let decrypted = bob.decrypt(group, &ciphertext);
+                let show = |coords| Point::from_coords(&curve, coords);
+                egui::Grid::new("elgamal_steps")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (label, value) in [
+                            ("Y = x·G", show(bob.public)),
+                            ("C1 = k·G", show(ciphertext.c1)),
+                            ("k·Y", show(mask)),
+                            ("C2 = M + k·Y", show(ciphertext.c2)),
+                            ("C2 − x·C1", show(decrypted)),
+                        ] {
+                            ui.label(label);
+                            ui.monospace(value.to_string());
+                            ui.end_row();
+                        }
+                    });
+                match elgamal::decode(decrypted) {
+                    Some(m) if decrypted == message => ui.colored_label(
+                        egui::Color32::DARK_GREEN,
+                        format!("Bob decodes ⌊{x}/{}⌋ = {m}", elgamal::KOBLITZ_K),
+                    ),
+                    _ => ui.colored_label(egui::Color32::RED, "Bob recovered the wrong point"),
+                };
+                ui.label(
+                    "Reusing k leaks C2 − C2' = M − M': one known plaintext reveals the other.",
+                );
+                self.elgamal = Some((curve, bob.public, ciphertext));
+            });
+        self.show_elgamal = open;
+    }
+
+    fn fault_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let point = self.view.tracked[0].base.coords();
+        let mut open = self.show_fault;
+        egui::Window::new("Fault injection")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Left-to-right double-and-add of k·P, P = {}, with an optional fault.",
+                    Coordinates(point)
+                ));
+                ui.horizontal(|ui| {
+                    let label = ui.label("scalar k:");
+                    ui.add(egui::DragValue::new(&mut self.fault_k).range(1..=1_000_000))
+                        .labelled_by(label.id);
+                    let label = ui.label("fault bit:");
+                    ui.add(egui::DragValue::new(&mut self.fault_bit).range(0..=30))
+                        .labelled_by(label.id);
+                });
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.fault_kind, None, "No fault");
+                    ui.radio_value(&mut self.fault_kind, Some(FaultKind::FlipBit), "Flip scalar bit");
+                    ui.radio_value(&mut self.fault_kind, Some(FaultKind::CorruptX), "Corrupt x");
+                });
+                ui.checkbox(&mut self.fault_check, "Check the result is on the curve");
+
+                let fault = self.fault_kind.map(|kind| Fault {
+                    kind,
+                    bit: self.fault_bit,
+                });
+                let trace = fault::traced_mul(&curve, point, self.fault_k, fault);
+                egui::Grid::new("fault_steps").striped(true).show(ui, |ui| {
+                    for header in ["bit", "k_i", "accumulator", "on curve"] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for step in &trace.steps {
+                        let faulty = fault.is_some_and(|f| f.bit == step.bit);
+                        let bit = egui::RichText::new(step.bit.to_string());
+                        ui.label(if faulty { bit.color(egui::Color32::RED) } else { bit });
+                        ui.label((step.set as u8).to_string());
+                        ui.label(Coordinates(step.acc).to_string());
+                        if step.on_curve {
+                            ui.label("yes");
+                        } else {
+                            ui.colored_label(egui::Color32::RED, "no");
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                let correct = curve.mul_coords(point, self.fault_k);
+                let output = if self.fault_check {
+                    fault::checked_mul(&curve, point, self.fault_k, fault)
+                } else {
+                    Ok(trace.result)
+                };
+                let faulty = match output {
+                    Err(error) => {
+                        ui.colored_label(
+                            egui::Color32::DARK_GREEN,
+                            format!("Output withheld: {error}"),
+                        );
+                        return;
+                    }
+                    Ok(output) if output == correct => {
+                        ui.label(format!("k·P = {}", Coordinates(output)));
+                        return;
+                    }
+                    Ok(output) => output,
+                };
+                ui.colored_label(
+                    egui::Color32::RED,
+                    format!("Faulty output {}, correct k·P = {}",
+                        Coordinates(faulty),
+                        Coordinates(correct)),
+                );
+                match fault.map(|f| f.kind) {
+                    Some(FaultKind::FlipBit) => {
+                        let bit = self.fault_bit;
+                        match fault::recover_bit(&curve, point, correct, faulty, bit) {
+                            Some(value) => ui.label(format!(
+                                "Q' − Q = {}2^{bit}·P, so bit {bit} of k is {}",
+                                if value { "−" } else { "+" },
+                                value as u8
+                            )),
+                            None => ui.label(format!("2^{bit}·P has order ≤ 2, bit {bit} is hidden")),
+                        };
+                    }
+                    Some(FaultKind::CorruptX) => {
+                        if let Some(other) = fault::actual_curve(&curve, faulty) {
+                            ui.label(format!(
+                                "The output lies on y² = x³ + {}x + {} instead; discrete logs on that \
+                                 curve leak k modulo the small factors of its order.",
+                                other.a, other.b
+                            ));
+                        }
+                    }
+                    None => {}
+                }
+
+                ui.separator();
+                let key = (curve, point, self.fault_k);
+                if ui.button("Recover k with one bit flip per bit").clicked() {
+                    // #E < 2p（Hasse 界），k 模点的阶只需要这么多位
+                    let bits = u64::BITS - (2 * curve.p as u64).leading_zeros();
+                    let check = self.fault_check;
+                    let k = self.fault_k;
+                    let recovered = fault::bit_flip_attack(&curve, point, bits, |fault| {
+                        if check {
+                            fault::checked_mul(&curve, point, k, fault).unwrap_or(None)
+                        } else {
+                            fault::traced_mul(&curve, point, k, fault).result
+                        }
+                    });
+                    self.fault_attack = Some((key, recovered));
+                }
+                if let Some((attacked, recovered)) = self.fault_attack
+                    && attacked == key
+                {
+                    let matches = curve.mul_coords(point, recovered) == correct;
+                    ui.label(format!(
+                        "recovered k = {recovered}: k·P {}",
+                        if matches { "matches" } else { "does not match" }
+                    ));
+                }
+            });
+        self.show_fault = open;
+    }
+
+    fn ecdsa_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        if !matches!(&self.ecdsa_group, Some(Ok(group)) if group.curve == curve) {
+            self.ecdsa_group = Some(PrimeGroup::new(&curve));
+            // 换曲线时丢弃手动选的 (d, k)，drop 时清零
+            self.ecdsa_keys = None;
+        }
+        let mut open = self.show_ecdsa;
+        egui::Window::new("ECDSA malleability")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                match self.ecdsa_group.as_ref().expect("group was just set") {
+                    Ok(group) => {
+                    ui.label(format!(
+                        "(r, s) and (r, q − s) both verify: negating the nonce negates R, which keeps \
+                         its x coordinate. q = {}, G = {}.",
+                        group.q, Coordinates(group.g)
+                    ));
+                    ui.horizontal(|ui| {
+                        let label = ui.label("Message:");
+                        ui.text_edit_singleline(&mut self.ecdsa_message)
+                            .labelled_by(label.id);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.ecdsa_verifier.reject_high_s, "Reject high s");
+                        ui.checkbox(&mut self.ecdsa_random_nonces, "Random nonces")
+                            .on_hover_text(
+                                "Off: k is derived from d and the message (RFC 6979). \
+                                 On: k comes from the RNG, as in the nonce attacks.",
+                            );
+                    });
+
+                    // 用自己的 RNG，不影响录制的会话
+                    let mut rng = rng::seeded(self.seed);
+                    let key = SigningKey::generate(group, &mut rng);
+                    let message = self.ecdsa_message.as_bytes();
+                    let signature = if self.ecdsa_random_nonces {
+                        key.sign_random(group, message, &mut rng)
+                    } else {
+                        key.sign(group, message)
+                    };
+                    ui.label(format!(
+                        "Q = {}, z = H(m) mod q = {}",
+                        Coordinates(key.public),
+                        ecdsa::hash_message(group, message).value()
+                    ));
+                    egui::Grid::new("ecdsa_signatures")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in ["", "r", "s", "", "verifies"] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+                            for (name, signature) in [
+                                ("signed", signature),
+                                ("malleated", signature.malleate()),
+                                ("normalized", signature.normalize_s()),
+                            ] {
+                                ui.label(name);
+                                ui.label(signature.r.value().to_string());
+                                ui.label(signature.s.value().to_string());
+                                ui.label(if signature.is_low_s() {
+                                    "low s"
+                                } else {
+                                    "high s"
+                                });
+                                if self
+                                    .ecdsa_verifier
+                                    .verify(group, key.public, message, &signature)
+                                {
+                                    ui.colored_label(egui::Color32::DARK_GREEN, "valid");
+                                } else {
+                                    ui.colored_label(egui::Color32::RED, "rejected");
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                    ui.separator();
+                    ui.checkbox(&mut self.ecdsa_steps, "Step by step");
+                    if self.ecdsa_steps {
+                        let keys = |chosen: Option<&(SecretScalar, SecretScalar)>| {
+                            ecdsa_keys(
+                                chosen,
+                                self.seed,
+                                group,
+                                self.ecdsa_message.as_bytes(),
+                                self.ecdsa_random_nonces,
+                            )
+                        };
+                        let (d, k) = keys(self.ecdsa_keys.as_ref());
+                        let labels = ["Private key d:", "Nonce k:", "Randomize d and k"];
+                        let chosen = ui
+                            .horizontal(|ui| edit_secret_pair(ui, labels, (&d, &k), &mut self.rng))
+                            .inner;
+                        if chosen.is_some() {
+                            self.ecdsa_keys = chosen;
+                        }
+                        let (d, k) = keys(self.ecdsa_keys.as_ref());
+                        ecdsa_steps(
+                            ui,
+                            group,
+                            *d.expose(),
+                            self.ecdsa_message.as_bytes(),
+                            *k.expose(),
+                        );
+                    }
+                    }
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error.as_str());
+                    }
+                }
+                ui.separator();
+                ui.checkbox(&mut self.ecdsa_ed25519, "Compare with Ed25519");
+                if self.ecdsa_ed25519 {
+                    ed25519_section(ui, self.seed, self.ecdsa_message.as_bytes());
+                }
+                ui.checkbox(&mut self.ecdsa_p256, "Real world: P-256");
+                if self.ecdsa_p256 {
+                    p256_section(ui, self.seed, self.ecdsa_message.as_bytes());
+                }
+                ui.checkbox(&mut self.ecdsa_bip340, "Real world: BIP-340 Schnorr");
+                if self.ecdsa_bip340 {
+                    bip340_section(ui, self.seed, self.ecdsa_message.as_bytes());
+                }
+            });
+        self.show_ecdsa = open;
+    }
+
+    fn nonce_reuse_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        if !matches!(&self.reuse_group, Some(Ok(group)) if group.curve == curve) {
+            self.reuse_group = Some(PrimeGroup::new(&curve));
+            self.reuse_keys = None;
+        }
+        let mut open = self.show_nonce_reuse;
+        egui::Window::new("ECDSA nonce reuse")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let group = match self.reuse_group.as_ref().expect("group was just set") {
+                    Ok(group) => group,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error.as_str());
+                        return;
+                    }
+                };
+                let q = group.q;
+                ui.label(format!(
+                    "The signer uses the same nonce k for two messages. Both signatures then \
+                     share r, and two linear equations in k and d over Z_q (q = {q}) give both \
+                     away."
+                ));
+                let (d, k) = self
+                    .reuse_keys
+                    .get_or_insert_with(|| (demo_secret(57, q), demo_secret(23, q)));
+                ui.horizontal(|ui| {
+                    if let Some(edited) = edit_secret(ui, "Victim's d:", d) {
+                        *d = edited;
+                    }
+                    if let Some(edited) = edit_secret(ui, "Reused k:", k) {
+                        *k = edited;
+                    }
+                });
+                for (i, message) in self.reuse_messages.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let label = ui.label(format!("Message {}:", i + 1));
+                        ui.text_edit_singleline(message).labelled_by(label.id);
+                    });
+                }
+
+                let (d, k) = (*d.expose(), *k.expose());
+                let public = group.base_mul(d);
+                let [m1, m2] = self.reuse_messages.each_ref().map(|m| m.as_bytes());
+                let sign = |message| {
+                    let z = ecdsa::hash_message(group, message);
+                    ecdsa::sign_with_nonce(group, d, z, k).map(|full| full.signature)
+                };
+                let (Some(sig1), Some(sig2)) = (sign(m1), sign(m2)) else {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        "r = 0 or s = 0 with this nonce, pick another k",
+                    );
+                    return;
+                };
+                ui.label(format!("Q = d·G = {}", Point::from_coords(&curve, public)));
+                egui::Grid::new("reuse_signatures")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["", "r", "s"] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for (name, signature) in [("m1", sig1), ("m2", sig2)] {
+                            ui.label(name);
+                            ui.monospace(signature.r.value().to_string());
+                            ui.monospace(signature.s.value().to_string());
+                            ui.end_row();
+                        }
+                    });
+
+                let attack = ecdsa::recover_reused_nonce(group, public, (m1, &sig1), (m2, &sig2));
+                let recovered = match attack {
+                    Ok(recovered) => recovered,
+                    Err(failure) => {
+                        ui.colored_label(egui::Color32::RED, failure.to_string());
+                        return;
+                    }
+                };
+                let ecdsa::NonceReuse {
+                    z1,
+                    z2,
+                    s2,
+                    nonce,
+                    secret,
+                } = recovered;
+                let (r, s1) = (sig1.r, sig1.s);
+                for line in [
+                    format!(
+                        "1. r1 = r2 = {}: both came from the same R = k·G",
+                        r.value()
+                    ),
+                    format!(
+                        "2. z1 = H(m1) = {}, z2 = H(m2) = {}",
+                        z1.value(),
+                        z2.value()
+                    ),
+                    format!(
+                        "3. s1 − s2 = k⁻¹(z1 − z2) ⇒ k = (z1 − z2)/(s1 − s2) = {}/{} = {} mod q",
+                        (z1 - z2).value(),
+                        (s1 - s2).value(),
+                        nonce.value()
+                    ),
+                    format!(
+                        "4. s1·k = z1 + r·d ⇒ d = (s1·k − z1)/r = {}/{} = {} mod q",
+                        (s1 * nonce - z1).value(),
+                        r.value(),
+                        secret.value()
+                    ),
+                ] {
+                    ui.monospace(line);
+                }
+                ui.colored_label(
+                    egui::Color32::RED,
+                    format!(
+                        "5. d·G = Q: the private key {} is recovered",
+                        secret.value()
+                    ),
+                );
+            });
+        self.show_nonce_reuse = open;
+    }
+
+    fn explorer_window(&mut self, ctx: &egui::Context) {
+        if let Some((p, rx)) = &self.explorer_job {
+            match rx.try_recv() {
+                Ok(result) => {
+                    match result {
+                        Ok(rows) => {
+                            self.explorers.insert(*p, rows);
+                            self.explorer_error = None;
+                        }
+                        Err(error) => self.explorer_error = Some(error),
+                    }
+                    self.explorer_job = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => self.explorer_job = None,
+            }
+        }
+
+        let mut load = None;
+        let mut open = self.show_explorer;
+        egui::Window::new("Curve explorer")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let label = ui.label("Field size p:");
+                    ui.add(egui::DragValue::new(&mut self.explorer_p).range(5..=explorer::MAX_P))
+                        .labelled_by(label.id);
+                    let running = self.explorer_job.is_some();
+                    let cached = self.explorers.peek(&self.explorer_p).is_some();
+                    if ui
+                        .add_enabled(!running && !cached, egui::Button::new("Enumerate curves"))
+                        .clicked()
+                    {
+                        let (tx, rx) = mpsc::channel();
+                        let p = self.explorer_p;
+                        let ctx = ctx.clone();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(explorer::enumerate(p));
+                            ctx.request_repaint();
+                        });
+                        self.explorer_job = Some((p, rx));
+                    }
+                    if running {
+                        ui.spinner();
+                    }
+                });
+                if let Some(error) = &self.explorer_error {
+                    ui.colored_label(egui::Color32::RED, error.as_str());
+                }
+                let filter = &mut self.explorer_filter;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut filter.prime_order, "Prime order only");
+                    ui.checkbox(&mut filter.supersingular, "Supersingular only");
+                    ui.checkbox(&mut filter.cyclic, "Cyclic only");
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Sort by:");
+                    for key in SortKey::ALL {
+                        ui.selectable_value(&mut self.explorer_sort, key, key.label());
+                    }
+                    ui.checkbox(&mut self.explorer_descending, "Descending");
+                });
+                let Some(rows) = self.explorers.peek(&self.explorer_p) else {
+                    return;
+                };
+                let selected = explorer::select(
+                    rows,
+                    &self.explorer_filter,
+                    self.explorer_sort,
+                    self.explorer_descending,
+                );
+                ui.label(format!(
+                    "{} of {} non-singular curves over F_{}; click one to load it",
+                    selected.len(),
+                    rows.len(),
+                    self.explorer_p
+                ));
+                // 只画滚动区域里看得见的行
+                let row_height = ui.spacing().interact_size.y;
+                egui::ScrollArea::vertical().max_height(320.0).show_rows(
+                    ui,
+                    row_height,
+                    selected.len(),
+                    |ui, range| {
+                        egui::Grid::new("explorer_rows")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for &i in &selected[range] {
+                                    let row = &rows[i];
+                                    let (a, b) = (row.curve.a, row.curve.b);
+                                    if ui.button(format!("y² = x³ + {a}x + {b}")).clicked() {
+                                        load = Some(row.curve);
+                                    }
+                                    ui.label(format!("#E = {}", row.group_order));
+                                    ui.label(row.structure_label());
+                                    ui.label(format!("j = {}", row.j_invariant));
+                                    let mut tags = vec![];
+                                    if row.is_prime_order() {
+                                        tags.push("prime order");
+                                    }
+                                    if row.is_supersingular() {
+                                        tags.push("supersingular");
+                                    }
+                                    ui.weak(tags.join(", "));
+                                    ui.end_row();
+                                }
+                            });
+                    },
+                );
+            });
+        self.show_explorer = open;
+        if let Some(curve) = load {
+            self.perform(Action::EditParams {
+                a: curve.a,
+                b: curve.b,
+                p: curve.p,
+            });
+            self.perform(Action::Apply);
+        }
+    }
+
+    // 小步大步求 k·G = Q，G 是第一个追踪点，Q = k·G 由选定的 k 算出
+    fn dlog_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let g = self.view.tracked[0].base.coords();
+        let key = (curve, g, self.dlog_secret);
+        if !self.composite {
+            let group_order = self
+                .summaries
+                .peek(&curve)
+                .map(|summary| summary.group_order);
+            poll_dlog(
+                ctx,
+                key,
+                group_order,
+                &mut self.dlog,
+                &mut self.dlog_job,
+                Bsgs::run,
+            );
+        }
+        let mut open = self.show_dlog;
+        egui::Window::new("Discrete log (BSGS)")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.composite {
+                    ui.colored_label(egui::Color32::RED, "BSGS needs a prime modulus");
+                    return;
+                }
+                if let Some((_, job)) = &self.dlog_job
+                    && job_controls(ui, job, "Finding the order of G and solving")
+                {
+                    self.dlog_job = None;
+                }
+                let Some((_, bsgs)) = self.dlog.as_ref() else {
+                    return;
+                };
+                ui.label(format!(
+                    "Solve k·G = Q for G = {} of order n = {}. Baby steps store j·G for \
+                     j < m = ⌈√n⌉ = {}, giant steps walk Q − i·m·G until one is in the table, \
+                     so both sides take at most m steps instead of n.",
+                    Coordinates(g),
+                    bsgs.order,
+                    bsgs.m
+                ));
+                let order = bsgs.order;
+                ui.horizontal(|ui| {
+                    let label = ui.label("Secret k:");
+                    ui.add(egui::DragValue::new(&mut self.dlog_secret).range(0..=order - 1))
+                        .labelled_by(label.id);
+                    if ui.button("Random Q").clicked() {
+                        self.dlog_secret = rng::uniform_below(&mut self.rng, order);
+                    }
+                });
+                ui.colored_label(
+                    BABY_STEP,
+                    format!("{} baby steps (blue on the plot)", bsgs.baby.len()),
+                );
+                ui.colored_label(
+                    GIANT_STEP,
+                    format!("{} giant steps (orange on the plot)", bsgs.giant.len()),
+                );
+                match (bsgs.hit, bsgs.log) {
+                    (Some((i, j)), Some(log)) => ui.label(format!(
+                        "Q − {i}·m·G = {j}·G, so k = {i}·{} + {j} = {log}",
+                        bsgs.m
+                    )),
+                    _ => ui.colored_label(egui::Color32::RED, "Q is not a multiple of G"),
+                };
+            });
+        self.show_dlog = open;
+    }
+
+    // Pollard rho 求同一个问题，游走画在曲线上
+    fn rho_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let g = self.view.tracked[0].base.coords();
+        let key = (curve, g, self.rho_secret);
+        if !self.composite {
+            let group_order = self
+                .summaries
+                .peek(&curve)
+                .map(|summary| summary.group_order);
+            poll_dlog(
+                ctx,
+                key,
+                group_order,
+                &mut self.rho,
+                &mut self.rho_job,
+                Rho::run,
+            );
+        }
+        let mut open = self.show_rho;
+        egui::Window::new("Discrete log (Pollard's rho)")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.composite {
+                    ui.colored_label(egui::Color32::RED, "Pollard's rho needs a prime modulus");
+                    return;
+                }
+                if let Some((_, job)) = &self.rho_job
+                    && job_controls(ui, job, "Finding the order of G and solving")
+                {
+                    self.rho_job = None;
+                }
+                let Some((_, rho)) = self.rho.as_ref() else {
+                    return;
+                };
+                ui.label(format!(
+                    "Solve k·G = Q for G = {} of order n = {}. The walk X ↦ X + G, 2X or X + Q, \
+                     picked by x mod 3, keeps X = a·G + b·Q. It must run into a cycle; Floyd's \
+                     tortoise and hare find a repeat with constant memory, and the two ways of \
+                     writing it give k.",
+                    self.view.tracked[0].base, rho.order
+                ));
+                let order = rho.order;
+                ui.horizontal(|ui| {
+                    let label = ui.label("Secret k:");
+                    ui.add(egui::DragValue::new(&mut self.rho_secret).range(0..=order - 1))
+                        .labelled_by(label.id);
+                    if ui.button("Random Q").clicked() {
+                        self.rho_secret = rng::uniform_below(&mut self.rng, order);
+                    }
+                });
+                let (a0, b0) = rho.start;
+                ui.label(format!(
+                    "X_0 = {a0}·G + {b0}·Q, start {} of at most {RHO_ATTEMPTS}",
+                    rho.attempts
+                ));
+                ui.colored_label(
+                    layers::RHO_TAIL,
+                    format!("tail: {} steps before the cycle", rho.tail),
+                );
+                ui.colored_label(layers::RHO_CYCLE, format!("cycle: {} steps", rho.cycle));
+                ui.label(format!(
+                    "Tortoise and hare meet after {} steps (√n ≈ {})",
+                    rho.meeting,
+                    order.isqrt()
+                ));
+                match rho.log {
+                    Some(log) => ui.label(format!("k = {log}")),
+                    None => ui.colored_label(egui::Color32::RED, "Q is not a multiple of G"),
+                };
+            });
+        self.show_rho = open;
+    }
+
+    fn animation_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let base = self.view.tracked[0].base.coords();
+        let key = (curve, base, self.animation_k);
+        if !self.composite
+            && self
+                .animation
+                .as_ref()
+                .is_none_or(|timeline| (timeline.curve, timeline.base, timeline.k) != key)
+        {
+            self.animation = Some(Timeline::new(&curve, base, self.animation_k));
+            self.playback = Playback::default();
+        }
+        let mut open = self.show_animation;
+        egui::Window::new("Animate k·P")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let Some(timeline) = self.animation.as_ref().filter(|_| !self.composite) else {
+                    ui.colored_label(egui::Color32::RED, "The animation needs a prime modulus");
+                    return;
+                };
+                let frames = timeline.frames.len();
+                ui.label(format!(
+                    "Double-and-add for k·P, P = {}, from the top bit of k down: \
+                     every bit doubles along the tangent, every set bit adds P along a chord.",
+                    Coordinates(base)
+                ));
+                ui.horizontal(|ui| {
+                    let label = ui.label("Animate k:");
+                    ui.add(egui::Slider::new(&mut self.animation_k, 1..=1000))
+                        .labelled_by(label.id);
+                });
+                ui.horizontal(|ui| {
+                    let text = if self.playback.playing {
+                        "Pause"
+                    } else {
+                        "Play"
+                    };
+                    if ui.button(text).clicked() {
+                        self.playback.toggle(frames);
+                    }
+                    if ui.button("Step").clicked() {
+                        self.playback.playing = false;
+                        self.playback.step(frames);
+                    }
+                    if ui.button("Restart").clicked() {
+                        self.playback = Playback::default();
+                    }
+                });
+                let label = ui.label("Frame:");
+                ui.add(egui::Slider::new(&mut self.playback.frame, 0..=frames - 1))
+                    .labelled_by(label.id);
+                let frame = &timeline.frames[self.playback.frame];
+                ui.label(format!(
+                    "Step {} of {}: {} = {}",
+                    self.playback.frame,
+                    frames - 1,
+                    frame.label,
+                    Point::from_coords(&timeline.curve, frame.point)
+                ));
+                if self.playback.playing {
+                    let dt = ui.input(|i| i.stable_dt);
+                    self.playback.advance(dt, frames);
+                    ui.ctx().request_repaint();
+                }
+            });
+        self.show_animation = open;
+    }
+
+    fn invalid_curve_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let mut open = self.show_invalid_curve;
+        egui::Window::new("Invalid-curve attack")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "A server answers ECDH requests by returning d·Q for whatever public key Q \
+                     it receives. The addition formulas never use b, so a point off the curve is \
+                     multiplied on y² = x³ + ax + b' instead. The attacker picks b' so that Q has \
+                     a small prime order r: the reply then takes only r values, which reveals \
+                     d mod r, and the Chinese remainder theorem combines the residues into d.",
+                );
+                if self.composite || curve.is_singular() {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        "The attack needs a non-singular curve over a prime field",
+                    );
+                    return;
+                }
+                let group_order = self.view.all_points().len() as u64 + 1;
+                ui.horizontal(|ui| {
+                    let label = ui.label("Server secret d:");
+                    ui.add(egui::DragValue::new(&mut self.invalid_d).range(1..=group_order - 1))
+                        .labelled_by(label.id);
+                });
+                ui.checkbox(&mut self.invalid_validate, "Server checks that Q is on the curve");
+                self.invalid_d = self.invalid_d.clamp(1, group_order - 1);
+                let key = (curve, self.invalid_d, self.invalid_validate);
+                if self
+                    .invalid_attack
+                    .as_ref()
+                    .is_none_or(|(cached, _)| *cached != key)
+                {
+                    self.invalid_attack =
+                        Some((key, Attack::run(&curve, key.1, self.invalid_validate)));
+                }
+                let (_, attack) = self.invalid_attack.as_ref().expect("attack was just run");
+                egui::Grid::new("invalid_curve_queries")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["b'", "Q", "order r", "server replies", "d mod r"] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for query in &attack.queries {
+                            ui.label(query.curve.b.to_string());
+                            ui.label(Coordinates(Some(query.point)).to_string());
+                            ui.label(query.order.to_string());
+                            match &query.reply {
+                                Ok(reply) => ui.label(Coordinates(*reply).to_string()),
+                                Err(error) => ui.colored_label(egui::Color32::RED, error.to_string()),
+                            };
+                            ui.label(query.residue.map_or("?".to_string(), |t| t.to_string()));
+                            ui.end_row();
+                        }
+                    });
+                match attack.recovered {
+                    Some(d) => ui.colored_label(
+                        egui::Color32::RED,
+                        format!(
+                            "{} queries give d mod {}, which exceeds #E = {group_order}: d = {d}",
+                            attack.queries.len(),
+                            attack.modulus
+                        ),
+                    ),
+                    None if self.invalid_validate => ui.colored_label(
+                        egui::Color32::DARK_GREEN,
+                        "The server rejects the first point off the curve and the attacker learns nothing",
+                    ),
+                    None => ui.label(format!(
+                        "Not enough curves with points of order at most {} to pin down d",
+                        invalid_curve::MAX_ORDER
+                    )),
+                };
+            });
+        self.show_invalid_curve = open;
+    }
+
+    // G 是第一个追踪点，Q = k·G
+    fn pohlig_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let g = self.view.tracked[0].base.coords();
+        let key = (curve, g, self.pohlig_secret);
+        if !self.composite {
+            let group_order = self
+                .summaries
+                .peek(&curve)
+                .map(|summary| summary.group_order);
+            poll_dlog(
+                ctx,
+                key,
+                group_order,
+                &mut self.pohlig,
+                &mut self.pohlig_job,
+                PohligHellman::run,
+            );
+        }
+        let mut open = self.show_pohlig;
+        egui::Window::new("Pohlig–Hellman attack")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.composite {
+                    ui.colored_label(egui::Color32::RED, "The attack needs a prime modulus");
+                    return;
+                }
+                if let Some((_, job)) = &self.pohlig_job
+                    && job_controls(ui, job, "Finding the order of G and solving")
+                {
+                    self.pohlig_job = None;
+                }
+                let Some((_, attack)) = self.pohlig.as_ref() else {
+                    return;
+                };
+                let order = attack.order;
+                let factors: Vec<String> = attack
+                    .stages
+                    .iter()
+                    .map(|stage| match stage.exponent {
+                        1 => stage.prime.to_string(),
+                        e => format!("{}^{e}", stage.prime),
+                    })
+                    .collect();
+                ui.label(format!(
+                    "Solve k·G = Q for G = {} of order n = {order} = {}. For each prime power \
+                     q^e dividing n, γ = (n/q)·G has order q and the base-q digits of k mod q^e \
+                     come one at a time from a BSGS in <γ>. The Chinese remainder theorem then \
+                     combines the residues, so the work depends on the largest prime factor of n, \
+                     not on n: curves with a smooth group order are weak.",
+                    self.view.tracked[0].base,
+                    factors.join(" · ")
+                ));
+                ui.horizontal(|ui| {
+                    let label = ui.label("Secret k:");
+                    ui.add(egui::DragValue::new(&mut self.pohlig_secret).range(0..=order - 1))
+                        .labelled_by(label.id);
+                    if ui.button("Random Q").clicked() {
+                        self.pohlig_secret = rng::uniform_below(&mut self.rng, order);
+                    }
+                });
+                egui::Grid::new("pohlig_stages")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in ["q^e", "γ = (n/q)·G", "digits of k mod q^e", "k mod q^e"] {
+                            ui.strong(header);
+                        }
+                        ui.end_row();
+                        for (stage, factor) in attack.stages.iter().zip(&factors) {
+                            ui.label(factor);
+                            ui.label(Point::from_coords(&curve, stage.gamma).to_string());
+                            let digits: Vec<String> = stage
+                                .digits
+                                .iter()
+                                .enumerate()
+                                .map(|(j, digit)| match digit.digit {
+                                    Some(d) => format!("d_{j} = {d}"),
+                                    None => format!("d_{j} = ?"),
+                                })
+                                .collect();
+                            ui.label(digits.join(", "));
+                            ui.label(stage.residue.map_or("?".to_string(), |r| r.to_string()));
+                            ui.end_row();
+                        }
+                    });
+                match attack.log {
+                    Some(log) => {
+                        let congruences: Vec<String> = attack
+                            .stages
+                            .iter()
+                            .filter_map(|stage| {
+                                Some(format!("k ≡ {} (mod {})", stage.residue?, stage.modulus()))
+                            })
+                            .collect();
+                        ui.label(format!("CRT: {} ⇒ k = {log}", congruences.join(", ")));
+                    }
+                    None => {
+                        ui.colored_label(egui::Color32::RED, "Q is not a multiple of G");
+                    }
+                }
+                let m = order.isqrt() + u64::from(order.isqrt().pow(2) < order);
+                ui.label(format!(
+                    "{} BSGS steps in total, against ⌈√n⌉ = {m} for BSGS on all of <G>",
+                    attack.steps()
+                ));
+            });
+        self.show_pohlig = open;
+    }
+
+    fn ecm_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_ecm;
+        egui::Window::new("ECM factorization")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Lenstra's method works on y² = x³ + ax + b (mod N) as if N were prime. It \
+                     multiplies a random point by 2, 3, ..., B = {}. When the curve's group \
+                     order modulo some prime q | N has only factors up to B, a slope denominator \
+                     becomes a multiple of q, and gcd(denominator, N) reveals q. If that does \
+                     not happen, another random curve gets another group order.",
+                    ecm::BOUND
+                ));
+                let mut fresh = false;
+                ui.horizontal(|ui| {
+                    let label = ui.label("N:");
+                    ui.add(egui::DragValue::new(&mut self.ecm_n).range(4..=ecm::MAX_N))
+                        .labelled_by(label.id);
+                    fresh = ui.button("New curves").clicked();
+                });
+                if fresh || self.ecm.as_ref().is_none_or(|ecm| ecm.n != self.ecm_n) {
+                    self.ecm = Some(Ecm::run(self.ecm_n, ecm::BOUND, &mut self.rng));
+                }
+                let ecm = self.ecm.as_ref().expect("ECM was just run");
+                let n = ecm.n;
+                if ecm.trials.is_empty() {
+                    ui.label(format!("{n} is prime, so there is nothing to split"));
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("ecm_trials").striped(true).show(ui, |ui| {
+                            for header in ["curve", "a", "b", "P", "stage 1"] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+                            for (i, trial) in ecm.trials.iter().enumerate() {
+                                ui.label((i + 1).to_string());
+                                ui.label(trial.curve.a.to_string());
+                                ui.label(trial.curve.b.to_string());
+                                let (x, y) = trial.point;
+                                ui.label(format!("({x}, {y})"));
+                                match &trial.failure {
+                                    Some(failure) => ui.label(format!(
+                                        "failed at {}·Q, gcd = {}",
+                                        failure.k, failure.gcd
+                                    )),
+                                    None => ui.label(format!("reached {}!·P", ecm.bound)),
+                                };
+                                ui.end_row();
+                            }
+                        });
+                    });
+                let (Some(factor), Some(trial)) = (ecm.factor, ecm.trials.last()) else {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("No factor found on {} curves", ecm.trials.len()),
+                    );
+                    return;
+                };
+                let failure = trial.failure.as_ref().expect("the last curve failed");
+                ui.label(format!(
+                    "On curve {}, computing {}·Q for Q = {} needed the inverse of {} mod {n}, \
+                     but gcd({}, {n}) = {factor}.",
+                    ecm.trials.len(),
+                    failure.k,
+                    Point::from_coords(&trial.curve, failure.before),
+                    failure.value,
+                    failure.value
+                ));
+                ui.strong(format!("{n} = {factor} × {}", n / factor));
+            });
+        self.show_ecm = open;
+    }
+
+    fn ladder_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let point = self.view.tracked[0].base.coords();
+        let mut open = self.show_ladder;
+        egui::Window::new("Double-and-add vs ladder")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "k·P for P = {}. Double-and-add only adds when a bit of k is set, \
+                     so the sequence of operations spells out k; the Montgomery ladder does \
+                     one addition and one doubling for each of the 64 bits, whatever k is.",
+                    Coordinates(point)
+                ));
+                ui.horizontal(|ui| {
+                    let label = ui.label("Scalar k:");
+                    ui.add(egui::DragValue::new(&mut self.ladder_k).range(0..=1_000_000))
+                        .labelled_by(label.id);
+                });
+                ui.horizontal(|ui| {
+                    ui.radio_value(
+                        &mut self.ladder_algorithm,
+                        MulAlgorithm::DoubleAndAdd,
+                        "Double-and-add",
+                    );
+                    ui.radio_value(
+                        &mut self.ladder_algorithm,
+                        MulAlgorithm::MontgomeryLadder,
+                        "Montgomery ladder",
+                    );
+                });
+                if self.composite {
+                    ui.colored_label(egui::Color32::RED, "Needs a prime modulus");
+                    return;
+                }
+                let base = JacobianPoint::from_coords(&curve, point);
+                let (product, ops) = base.traced_mul(self.ladder_k, &curve, self.ladder_algorithm);
+                let count = |op| ops.chars().filter(|&c| c == op).count();
+                ui.label(format!(
+                    "k·P = {}: {} additions (A), {} doublings (D)",
+                    Coordinates(product.to_coords(&curve)),
+                    count('A'),
+                    count('D')
+                ));
+                ui.add(egui::Label::new(egui::RichText::new(&ops).monospace()).wrap());
+                // 另一种算法的结果必须一样
+                let other = match self.ladder_algorithm {
+                    MulAlgorithm::DoubleAndAdd => MulAlgorithm::MontgomeryLadder,
+                    MulAlgorithm::MontgomeryLadder => MulAlgorithm::DoubleAndAdd,
+                };
+                if curve.mul_coords_with(point, self.ladder_k, other) == product.to_coords(&curve) {
+                    ui.colored_label(egui::Color32::DARK_GREEN, "Both algorithms agree");
+                } else {
+                    ui.colored_label(egui::Color32::RED, "The algorithms disagree");
+                }
+            });
+        self.show_ladder = open;
+    }
+
+    // ax² + y² = 1 + dx²y²：点画在窗口自己的画布上，P 是追踪点在 Edwards 模型上的像，
+    // 当前曲线不是它的短形式时取第一个 x ≠ 0 的点
+    fn edwards_window(&mut self, ctx: &egui::Context) {
+        let edwards = TwistedEdwards::new(self.edwards_a, self.edwards_d, self.view.curve.p);
+        if self
+            .edwards
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != edwards)
+        {
+            let reduced = if self.composite {
+                Err("Needs a prime modulus".to_string())
+            } else {
+                edwards
+                    .reduce()
+                    .map(|reduction| (reduction, edwards.points()))
+            };
+            self.edwards = Some((edwards, reduced));
+        }
+        let mut load = None;
+        let mut open = self.show_edwards;
+        egui::Window::new("Twisted Edwards")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "On ax² + y² = 1 + dx²y² one formula adds any two points, doubling and \
+                     the identity O = (0, 1) included. When a is a square and d is not, its \
+                     denominators never vanish. Ed25519 is this curve with a = −1 over \
+                     2^255 − 19.",
+                );
+                ui.horizontal(|ui| {
+                    for (name, value) in [
+                        ("Edwards a:", &mut self.edwards_a),
+                        ("Edwards d:", &mut self.edwards_d),
+                    ] {
+                        let label = ui.label(name);
+                        ui.add(egui::DragValue::new(value).range(-999..=999))
+                            .labelled_by(label.id);
+                    }
+                });
+                let (edwards, reduced) = self.edwards.as_ref().expect("cached above");
+                let (reduction, points) = match reduced {
+                    Ok(reduced) => reduced,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                        return;
+                    }
+                };
+                let complete = if edwards.is_complete() {
+                    "a is a square and d is not, so the addition law is complete"
+                } else {
+                    "not complete: some sums divide by zero"
+                };
+                ui.label(format!("{edwards}: {} points, {complete}", points.len()));
+                let curve = reduction.curve();
+                ui.label(format!(
+                    "Montgomery form {} (A = 2(a + d)/(a − d), B = 4/(a − d)), short form \
+                     y² = x³ + {}x + {}",
+                    reduction.montgomery.montgomery, curve.a, curve.b
+                ));
+                if curve == self.view.curve {
+                    ui.label("The plot shows the short form");
+                } else if ui.button("Plot the short form").clicked() {
+                    load = Some(curve);
+                }
+                let tracked = (curve == self.view.curve)
+                    .then(|| reduction.map_from_short(self.view.tracked[0].base.coords()))
+                    .flatten();
+                let Some(p) = tracked.or_else(|| points.iter().copied().find(|&(x, _)| x != 0))
+                else {
+                    return;
+                };
+                let show = |sum: Option<(i64, i64)>| match sum {
+                    Some((x, y)) => format!("({x}, {y})"),
+                    None => "zero denominator".to_string(),
+                };
+                let double = edwards.add(p, p);
+                ui.horizontal_top(|ui| {
+                    egui::Grid::new("edwards_sums")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("P");
+                            ui.label(show(Some(p)));
+                            ui.end_row();
+                            for (name, sum) in [
+                                ("P + O", edwards.add(p, twisted_edwards::IDENTITY)),
+                                ("P + (−P)", edwards.add(p, edwards.neg(p))),
+                                ("P + P", double),
+                                ("3P", edwards.mul(p, 3)),
+                            ] {
+                                ui.strong(name);
+                                ui.label(show(sum));
+                                ui.end_row();
+                            }
+                        });
+                    small_plot(
+                        ui,
+                        edwards.p,
+                        points,
+                        &[
+                            (Some(twisted_edwards::IDENTITY), egui::Color32::DARK_GRAY),
+                            (Some(p), egui::Color32::RED),
+                            (double, egui::Color32::from_rgb(60, 160, 90)),
+                        ],
+                    );
+                });
+            });
+        self.show_edwards = open;
+        if let Some(curve) = load {
+            self.perform(Action::EditParams {
+                a: curve.a,
+                b: curve.b,
+                p: curve.p,
+            });
+            self.perform(Action::Apply);
+        }
+    }
+
+    // 同一条曲线的三种写法，第一个追踪点 P 在每种写法里的坐标
+    fn models_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        if self.models.is_none_or(|models| models.curve != curve) {
+            self.models = Some(Models::of(&curve));
+        }
+        let models = self.models.expect("computed above");
+        let point = self.view.tracked[0].base.coords();
+        let mut open = self.show_models;
+        egui::Window::new("Curve models")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "y² = x³ + {}x + {} over F_{}, P = {}",
+                    curve.a,
+                    curve.b,
+                    curve.p,
+                    Coordinates(point)
+                ));
+                if self.composite {
+                    ui.colored_label(egui::Color32::RED, "Needs a prime modulus");
+                    return;
+                }
+                let Some(montgomery) = models.montgomery else {
+                    ui.label(
+                        "No Montgomery form: that needs a point (α, 0) of order 2 with \
+                         3α² + a a square, so no Edwards form either",
+                    );
+                    return;
+                };
+                ui.label(format!(
+                    "Montgomery: {}, P = {}",
+                    montgomery.montgomery,
+                    Coordinates(montgomery.map_to_montgomery(point))
+                ));
+                let curve_points = self.view.points.iter().map(Point::coords);
+                let montgomery_points: Vec<(i64, i64)> = curve_points
+                    .clone()
+                    .filter_map(|coords| montgomery.map_to_montgomery(coords))
+                    .collect();
+                let Some(edwards) = models.edwards else {
+                    small_plot(ui, curve.p, &montgomery_points, &[]);
+                    return;
+                };
+                let edwards_point = edwards.map_from_short(point);
+                ui.label(format!(
+                    "Twisted Edwards: {}, P = {}",
+                    edwards.edwards,
+                    match edwards_point {
+                        Some((x, y)) => format!("({x}, {y})"),
+                        None => "at infinity on this model".to_string(),
+                    }
+                ));
+                let edwards_points: Vec<(i64, i64)> = curve_points
+                    .filter_map(|coords| edwards.map_from_short(coords))
+                    .chain([twisted_edwards::IDENTITY])
+                    .collect();
+                let red = egui::Color32::RED;
+                ui.horizontal_top(|ui| {
+                    small_plot(
+                        ui,
+                        curve.p,
+                        &montgomery_points,
+                        &[(montgomery.map_to_montgomery(point), red)],
+                    );
+                    small_plot(ui, curve.p, &edwards_points, &[(edwards_point, red)]);
+                });
+            });
+        self.show_models = open;
+    }
+
+    fn presets_window(&mut self, ctx: &egui::Context) {
+        let all = presets::all();
+        let mut open = self.show_presets;
+        egui::Window::new("Standard curves")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::ComboBox::from_label("Curve")
+                    .selected_text(all[self.preset].name)
+                    .show_ui(ui, |ui| {
+                        for (i, preset) in all.iter().enumerate() {
+                            ui.selectable_value(&mut self.preset, i, preset.name);
+                        }
+                    });
+                let preset = &all[self.preset];
+                ui.label(format!(
+                    "{}: {} over a {}-bit prime field. Far too large to plot, but the \
+                     same group law runs on 256-bit integers.",
+                    preset.name,
+                    preset.equation,
+                    (0..256)
+                        .rev()
+                        .find(|&i| u256::bit(&preset.p, i))
+                        .unwrap_or(0)
+                        + 1
+                ));
+                if *preset == presets::curve25519() {
+                    ui.label(
+                        "Curve25519 is a Montgomery curve; below it is written in short \
+                         Weierstrass form via x = u + 486662/3, which keeps the group and G.",
+                    );
+                }
+                let hex = |limbs: &u256::Limbs| sha256::hex(&u256::to_be_bytes(limbs));
+                for (name, value) in [
+                    ("p", preset.p),
+                    ("a", preset.a),
+                    ("b", preset.b),
+                    ("Gx", preset.g.0),
+                    ("Gy", preset.g.1),
+                    ("n", preset.order),
+                ] {
+                    ui.monospace(format!("{name:>2} = {}", hex(&value)));
+                }
+                ui.monospace(format!(" h = {}", preset.cofactor));
+
+                ui.horizontal(|ui| {
+                    let label = ui.label("k:");
+                    ui.text_edit_singleline(&mut self.preset_k)
+                        .labelled_by(label.id);
+                });
+                let k = match u256::parse(&self.preset_k) {
+                    Ok(k) => k,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                        return;
+                    }
+                };
+                let key = (self.preset, k);
+                let product = match &self.preset_product {
+                    Some((built, product)) if *built == key => *product,
+                    _ => {
+                        let curve = preset.curve();
+                        let product = curve.affine(&curve.mul(&preset.generator(&curve), &k));
+                        self.preset_product = Some((key, product));
+                        product
+                    }
+                };
+                match product {
+                    Some((x, y)) => {
+                        ui.monospace(format!("k·G.x = {}", hex(&x)));
+                        ui.monospace(format!("k·G.y = {}", hex(&y)));
+                    }
+                    None => {
+                        ui.monospace("k·G = O");
+                    }
+                }
+            });
+        self.show_presets = open;
+    }
+
+    fn lattice_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        if self
+            .lattice
+            .as_ref()
+            .is_none_or(|(built, _)| *built != curve)
+        {
+            let lattice = Lattice::new(&curve, self.view.all_points());
+            self.lattice = Some((curve, lattice));
+            self.lattice_selected = None;
+            self.lattice_highlight.clear();
+        }
+        let mut selected = self.lattice_selected;
+        let mut open = self.show_lattice;
+        egui::Window::new("Subgroup lattice")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Every subgroup of E(F_p), ordered by inclusion. E is the direct product of \
+                     its Sylow subgroups, so each subgroup is a product of one subgroup from each; \
+                     lines join H to the subgroups that contain it with prime index. Click a \
+                     subgroup to circle its points on the plot.",
+                );
+                let lattice = match &self.lattice {
+                    Some((_, Ok(lattice))) => lattice,
+                    Some((_, Err(error))) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                        return;
+                    }
+                    None => return,
+                };
+                let rect = ui
+                    .allocate_space(egui::vec2(480.0, 320.0))
+                    .1
+                    .shrink2(egui::vec2(40.0, 14.0));
+                // y = 0 的平凡子群画在最下面
+                let position = |i: usize| {
+                    let (x, y) = lattice.positions[i];
+                    egui::pos2(
+                        rect.min.x + x * rect.width(),
+                        rect.max.y - y * rect.height(),
+                    )
+                };
+                let painter = ui.painter();
+                for &(h, k) in &lattice.edges {
+                    let on_path = selected.is_some_and(|s| s == h || s == k);
+                    let color = if on_path {
+                        egui::Color32::from_rgb(0, 160, 220)
+                    } else {
+                        egui::Color32::GRAY
+                    };
+                    painter.line_segment([position(h), position(k)], egui::Stroke::new(1.0, color));
+                }
+                for (i, node) in lattice.nodes.iter().enumerate() {
+                    let label = format!("H{i} ≅ {}", node.structure_label());
+                    let button = egui::Button::new(egui::RichText::new(label).small())
+                        .selected(selected == Some(i));
+                    let size = egui::vec2(72.0, 18.0);
+                    let response = ui
+                        .put(egui::Rect::from_center_size(position(i), size), button)
+                        .on_hover_text(format!("order {}", node.order));
+                    if response.clicked() {
+                        selected = (selected != Some(i)).then_some(i);
+                    }
+                }
+                if let Some(i) = selected {
+                    let node = &lattice.nodes[i];
+                    ui.label(format!(
+                        "H{i} ≅ {} has order {} and index {} in E",
+                        node.structure_label(),
+                        node.order,
+                        lattice.nodes.last().expect("E itself is a node").order / node.order
+                    ));
+                }
+            });
+        self.show_lattice = open;
+        if selected != self.lattice_selected {
+            self.lattice_selected = selected;
+            self.lattice_highlight = match (&self.lattice, selected) {
+                (Some((_, Ok(lattice))), Some(i)) => lattice.elements(i),
+                _ => Vec::new(),
+            };
+        }
+    }
+
+    fn net_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_net;
+        egui::Window::new("Elliptic net")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "The rank-2 net W(a, b) of P and Q vanishes exactly where aP + bQ = O. \
+                     Row b = 0 is the division polynomial sequence ψ_a(P). With nP = O the Tate \
+                     pairing is W(n+1, 1) / W(n+1, 0), raised to (p − 1) / n.",
+                );
+                if ui.button("Compute from the tracked point").clicked() {
+                    let point = self.view.tracked[0].base.coords();
+                    self.net = Some(net::Demo::run(&self.view.curve, point));
+                }
+                let demo = match &self.net {
+                    None => return,
+                    Some(Err(error)) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                        return;
+                    }
+                    Some(Ok(demo)) => demo,
+                };
+                ui.label(format!(
+                    "n = {}, P = {}, Q = {}",
+                    demo.n,
+                    Coordinates(demo.p),
+                    Coordinates(demo.q)
+                ));
+                egui::ScrollArea::horizontal().show(ui, |ui| {
+                    egui::Grid::new("net_values").striped(true).show(ui, |ui| {
+                        ui.strong("b \\ a");
+                        for a in 0..=demo.net.len() {
+                            ui.strong(a.to_string());
+                        }
+                        ui.end_row();
+                        for b in [1, 0, -1] {
+                            ui.strong(b.to_string());
+                            for a in 0..=demo.net.len() {
+                                let value = demo.net.get(a, b).unwrap_or_default();
+                                if value == 0 {
+                                    ui.colored_label(egui::Color32::RED, "0");
+                                } else {
+                                    ui.label(value.to_string());
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.label(format!(
+                    "Tate pairing via the net: {}, via Miller's algorithm: {}",
+                    demo.via_net, demo.via_miller
+                ));
+                if demo.via_net == demo.via_miller {
+                    ui.colored_label(egui::Color32::DARK_GREEN, "The two computations agree");
+                } else {
+                    ui.colored_label(egui::Color32::RED, "The two computations disagree");
+                }
+            });
+        self.show_net = open;
+    }
+
+    fn isogeny_window(&mut self, ctx: &egui::Context) {
+        let curve = self.view.curve;
+        let stale = match &self.isogenies {
+            Some(Ok(neighbours)) => neighbours.curve != curve,
+            _ => true,
+        };
+        if stale {
+            self.isogenies = Some(Neighbours::new(&curve));
+        }
+        let mut open = self.show_isogenies;
+        egui::Window::new("Isogeny neighbours")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Curves joined by an ℓ-isogeny have j-invariants with Φ_ℓ(j, j') = 0, where \
+                     Φ_ℓ is the classical modular polynomial. Its roots j' in F_p are the edges \
+                     of the ℓ-isogeny graph, found without enumerating any kernel.",
+                );
+                let neighbours = match self.isogenies.as_ref().expect("neighbours were just set") {
+                    Ok(neighbours) => neighbours,
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error.as_str());
+                        return;
+                    }
+                };
+                ui.label(format!("j = {}", neighbours.j));
+                egui::Grid::new("isogeny_neighbours")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("ℓ");
+                        ui.strong("j' with Φ_ℓ(j, j') = 0");
+                        ui.end_row();
+                        for (l, roots) in &neighbours.levels {
+                            ui.label(l.to_string());
+                            if roots.is_empty() {
+                                ui.weak("none");
+                            } else {
+                                let roots: Vec<String> = roots.iter().map(i64::to_string).collect();
+                                ui.label(roots.join(", "));
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                let (a, b) = &mut self.isogeny_other;
+                ui.horizontal(|ui| {
+                    ui.label("Compare with E': y² = x³ + a'x + b'");
+                    let label = ui.label("a':");
+                    ui.add(egui::DragValue::new(a).range(0..=curve.p - 1))
+                        .labelled_by(label.id);
+                    let label = ui.label("b':");
+                    ui.add(egui::DragValue::new(b).range(0..=curve.p - 1))
+                        .labelled_by(label.id);
+                });
+                let other = Curve::new(*a, *b, curve.p);
+                let levels: Result<Vec<u64>, String> = modular::LEVELS
+                    .iter()
+                    .filter_map(|&l| match modular::are_l_isogenous(&curve, &other, l) {
+                        Ok(true) => Some(Ok(l)),
+                        Ok(false) => None,
+                        Err(error) => Some(Err(error)),
+                    })
+                    .collect();
+                match levels {
+                    Err(error) => {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                    Ok(levels) if levels.is_empty() => {
+                        ui.label("E' is not ℓ-isogenous to E for any bundled ℓ");
+                    }
+                    Ok(levels) => {
+                        let levels: Vec<String> = levels.iter().map(u64::to_string).collect();
+                        ui.colored_label(
+                            egui::Color32::DARK_GREEN,
+                            format!(
+                                "E' or its quadratic twist is ℓ-isogenous to E for ℓ = {}",
+                                levels.join(", ")
+                            ),
+                        );
+                    }
+                }
+            });
+        self.show_isogenies = open;
+    }
+
+    fn x_stats_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_x_stats;
+        egui::Window::new("x-coordinate statistics")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let coords: Vec<Coords> =
+                    self.view.all_points().iter().map(Point::coords).collect();
+                let dist = stats::x_distribution(&self.view.curve, &coords);
+                ui.label("Number of x with 0, 1 or 2 points:");
+                bar_chart(
+                    ui,
+                    &[
+                        ("0".to_string(), dist.no_point),
+                        ("1".to_string(), dist.one_point),
+                        ("2".to_string(), dist.two_points),
+                    ],
+                    egui::Color32::from_rgb(90, 120, 200),
+                );
+                ui.label(format!("2-torsion points (y = 0): {}", dist.two_torsion));
+
+                // 直接用统计面板算好的阶分布
+                if let Some(summary) = self.summaries.peek(&self.view.curve) {
+                    ui.separator();
+                    ui.label(format!(
+                        "Points by order (group exponent {}):",
+                        summary.structure.0
+                    ));
+                    let bars: Vec<(String, usize)> = summary
+                        .order_counts
+                        .iter()
+                        .take(SHOWN_ORDERS)
+                        .map(|(order, &count)| (order.to_string(), count))
+                        .collect();
+                    bar_chart(ui, &bars, egui::Color32::from_rgb(120, 160, 90));
+                }
+
+                ui.separator();
+                let g = &self.view.tracked[0].base;
+                ui.horizontal(|ui| {
+                    ui.label(format!("x of kG, G = {g}, k = 1.."));
+                    ui.add(egui::DragValue::new(&mut self.x_stats_k).range(1..=100_000));
+                });
+                let xs = stats::multiples_x(&self.view.curve, g.coords(), self.x_stats_k);
+                if let Some(summary) = stats::summarize(&xs) {
+                    // 与 [0, p) 上均匀分布的期望值对比
+                    let p = self.p as f64;
+                    ui.label(format!(
+                        "n = {}, mean = {:.1} (uniform: {:.1}), std dev = {:.1} (uniform: {:.1})",
+                        summary.count,
+                        summary.mean,
+                        (p - 1.0) / 2.0,
+                        summary.std_dev,
+                        p / 12f64.sqrt()
+                    ));
+                    ui.label(format!("min = {}, max = {}", summary.min, summary.max));
+                    let buckets = stats::histogram(&xs, self.p, 10);
+                    let bars: Vec<(String, usize)> = buckets
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, count)| (format!("{}", i as i64 * self.p / 10), count))
+                        .collect();
+                    bar_chart(ui, &bars, egui::Color32::from_rgb(200, 120, 60));
+                }
+            });
+        self.show_x_stats = open;
+    }
+
+    // 整个界面只依赖 egui::Context，测试里可以不经过 eframe 直接驱动
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.poll_summary(ctx);
+        self.poll_replay(ctx);
+        egui::TopBottomPanel::bottom("log").show(ctx, |ui| self.log_panel(ui));
+        egui::TopBottomPanel::bottom("summary").show(ctx, |ui| self.summary_panel(ui));
+        egui::SidePanel::left("controls").show(ctx, |ui| self.controls(ui));
+        if self.show_x_stats && !self.composite {
+            self.x_stats_window(ctx);
+        }
+        self.factor_window(ctx);
+        if self.show_diagnostics {
+            self.diagnostics_window(ctx);
+        }
+        if self.show_script {
+            self.script_window(ctx);
+        }
+        if self.show_repl {
+            self.repl_window(ctx);
+        }
+        if self.show_ipa {
+            self.ipa_window(ctx);
+        }
+        if self.show_pedersen {
+            self.pedersen_window(ctx);
+        }
+        if self.show_coinflip {
+            self.coinflip_window(ctx);
+        }
+        if self.show_ecdh {
+            self.ecdh_window(ctx);
+        }
+        if self.show_elgamal {
+            self.elgamal_window(ctx);
+        }
+        if self.show_fault {
+            self.fault_window(ctx);
+        }
+        if self.show_ecdsa {
+            self.ecdsa_window(ctx);
+        }
+        if self.show_nonce_reuse {
+            self.nonce_reuse_window(ctx);
+        }
+        if self.show_net {
+            self.net_window(ctx);
+        }
+        if self.show_isogenies {
+            self.isogeny_window(ctx);
+        }
+        if self.show_explorer {
+            self.explorer_window(ctx);
+        }
+        if self.show_lattice {
+            self.lattice_window(ctx);
+        }
+        if self.show_dlog {
+            self.dlog_window(ctx);
+        }
+        if self.show_rho {
+            self.rho_window(ctx);
+        }
+        if self.show_presets {
+            self.presets_window(ctx);
+        }
+        if self.show_ladder {
+            self.ladder_window(ctx);
+        }
+        if self.show_edwards {
+            self.edwards_window(ctx);
+        }
+        if self.show_models {
+            self.models_window(ctx);
+        }
+        if self.show_animation {
+            self.animation_window(ctx);
+        }
+        if self.show_invalid_curve {
+            self.invalid_curve_window(ctx);
+        }
+        if self.show_pohlig {
+            self.pohlig_window(ctx);
+        }
+        if self.show_ecm {
+            self.ecm_window(ctx);
+        }
+
+        self.view_windows(ctx);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.show_generators && !self.composite {
+                self.generators();
+            }
+            let clicked = self.clicked_subgroup().is_some();
+            let show_cosets = (self.show_cosets || clicked) && !self.composite;
+            if show_cosets {
+                self.cosets();
+            }
+            self.field_base = self.field_base.min(self.view.tracked.len() - 1);
+            let cosets = show_cosets
+                .then(|| {
+                    self.coset_cache
+                        .as_ref()
+                        .map(|((_, base), cosets)| (*base, cosets))
+                })
+                .flatten();
+            let field_base = (self.show_field && !self.composite)
+                .then(|| self.view.tracked[self.field_base].base.coords());
+            let highlight = match &self.clicked_subgroup {
+                Some((_, elements)) if clicked => Some(elements.as_slice()),
+                _ => (self.show_lattice && self.lattice_selected.is_some())
+                    .then_some(self.lattice_highlight.as_slice()),
+            };
+            self.view.markers = self.markers();
+            self.view.structure = self
+                .summaries
+                .peek(&self.view.curve)
+                .filter(|_| !self.composite)
+                .map(CurveSummary::structure_label);
+            self.view.animation = self
+                .animation
+                .as_ref()
+                .filter(|timeline| self.show_animation && timeline.curve == self.view.curve)
+                .map(|timeline| timeline.frames[self.playback.frame].clone());
+            self.view.walk = self
+                .rho
+                .as_ref()
+                .filter(|((curve, ..), _)| self.show_rho && *curve == self.view.curve)
+                .map(|(_, rho)| rho.clone());
+            ui.horizontal(|ui| {
+                ui.label("Show the curve over:");
+                ui.radio_value(&mut self.plot_mode, PlotMode::Finite, "F_p");
+                ui.radio_value(&mut self.plot_mode, PlotMode::Real, "ℝ");
+                ui.radio_value(&mut self.plot_mode, PlotMode::Split, "Both");
+            });
+            let curve = self.view.curve;
+            match self.plot_mode {
+                PlotMode::Finite => self.view.plot(ui, cosets, field_base, highlight),
+                PlotMode::Real => self.real_view.ui(ui, &curve),
+                PlotMode::Split => {
+                    ui.horizontal_top(|ui| {
+                        ui.vertical(|ui| self.view.plot(ui, cosets, field_base, highlight));
+                        ui.vertical(|ui| self.real_view.ui(ui, &curve));
+                    });
+                }
+            }
+        });
+    }
+
+    // 打开的协议窗口在曲线上标出的点
+    fn markers(&self) -> Vec<Marker> {
+        let mut markers = Vec::new();
+        let mut mark = |coords: Coords, label: &str, color| {
+            if let Some(coords) = coords {
+                markers.push(Marker {
+                    coords,
+                    label: label.to_string(),
+                    color,
+                });
+            }
+        };
+        // 先画生成元，协议的标记压在上面
+        if self.show_generators
+            && !self.composite
+            && let Some(generators) = self.generators.peek(&self.view.curve)
+        {
+            for &g in generators {
+                mark(g, "", GENERATOR);
+            }
+        }
+        if self.show_ecdh
+            && let (Some(Ok(group)), Some(exchange)) = (&self.ecdh_group, &self.ecdh)
+            && group.curve == self.view.curve
+        {
+            let (alice, bob) = (
+                egui::Color32::from_rgb(40, 110, 220),
+                egui::Color32::from_rgb(20, 150, 80),
+            );
+            mark(group.g, "G", egui::Color32::BLACK);
+            mark(exchange.shares.0, "A = a·G", alice);
+            mark(exchange.shares.1, "B = b·G", bob);
+            // 诚实的交换里两个标签落在同一个点上
+            mark(exchange.alice.flatten(), "Alice's a·B", alice);
+            mark(exchange.bob.flatten(), "Bob's b·A", bob);
+        }
+        if self.show_elgamal
+            && let Some((curve, public, ciphertext)) = &self.elgamal
+            && *curve == self.view.curve
+        {
+            let (plain, cipher) = (
+                egui::Color32::from_rgb(20, 150, 80),
+                egui::Color32::from_rgb(200, 60, 60),
+            );
+            mark(*public, "Y = x·G", egui::Color32::BLACK);
+            mark(ciphertext.c1, "C1 = k·G", cipher);
+            mark(ciphertext.c2, "C2 = M + k·Y", cipher);
+            let message = elgamal::encode(curve, self.elgamal_message);
+            mark(message.map(|encoding| encoding.point), "M", plain);
+        }
+        if self.show_ecdsa
+            && self.ecdsa_steps
+            && let Some(Ok(group)) = &self.ecdsa_group
+            && group.curve == self.view.curve
+        {
+            let (d, k) = ecdsa_keys(
+                self.ecdsa_keys.as_ref(),
+                self.seed,
+                group,
+                self.ecdsa_message.as_bytes(),
+                self.ecdsa_random_nonces,
+            );
+            let message = self.ecdsa_message.as_bytes();
+            if let Some(steps) = ecdsa::Steps::run(group, *d.expose(), message, *k.expose()) {
+                let color = egui::Color32::from_rgb(150, 60, 200);
+                mark(group.g, "G", egui::Color32::BLACK);
+                mark(steps.public, "Q = d·G", color);
+                mark(steps.point, "R = k·G", color);
+                mark(steps.check, "X = u1·G + u2·Q", color);
+            }
+        }
+        if self.show_dlog
+            && let Some(((curve, g, _), bsgs)) = &self.dlog
+            && *curve == self.view.curve
+        {
+            for &step in &bsgs.baby {
+                mark(step, "", BABY_STEP);
+            }
+            for &step in &bsgs.giant {
+                mark(step, "", GIANT_STEP);
+            }
+            mark(*g, "G", BABY_STEP);
+            mark(bsgs.giant[0], "Q", GIANT_STEP);
+            if bsgs.hit.is_some() {
+                mark(
+                    *bsgs.giant.last().expect("giant steps"),
+                    "match",
+                    GIANT_STEP,
+                );
+            }
+        }
+        // 已经播过的帧，当前一帧由动画图层画
+        if self.show_animation
+            && let Some(timeline) = &self.animation
+            && timeline.curve == self.view.curve
+        {
+            for frame in &timeline.frames[..self.playback.frame] {
+                mark(
+                    frame.point,
+                    &animation::name(frame.multiple),
+                    egui::Color32::from_rgb(200, 30, 60).gamma_multiply(0.6),
+                );
+            }
+        }
+        markers
+    }
+
+    // 每个额外视图是一个独立的原生窗口；后端不支持多窗口时（例如测试里）
+    // egui 会把它嵌入主窗口，这时用一个 egui::Window 显示
+    fn view_windows(&mut self, ctx: &egui::Context) {
+        for window in &mut self.windows {
+            let title = window.title();
+            ctx.show_viewport_immediate(
+                egui::ViewportId::from_hash_of(("view", window.id)),
+                egui::ViewportBuilder::default()
+                    .with_title(&title)
+                    .with_inner_size([660.0, 760.0]),
+                |ctx, class| {
+                    if class == egui::ViewportClass::Embedded {
+                        let mut open = window.open;
+                        egui::Window::new(&title)
+                            .open(&mut open)
+                            .show(ctx, |ui| window.ui(ui));
+                        window.open = open;
+                    } else {
+                        egui::CentralPanel::default().show(ctx, |ui| window.ui(ui));
+                        if ctx.input(|i| i.viewport().close_requested()) {
+                            window.open = false;
+                        }
+                    }
+                },
+            );
+        }
+        self.windows.retain(|window| window.open);
+    }
+}
+
+impl eframe::App for EllipticCurveApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.show(ctx);
+    }
+}
+
+// 内积论证的结果和某一轮的折叠：左边是这一轮的向量，右边的画布上
+// 把 G_lo[i]、G_hi[i]（蓝）和 H_lo[i]、H_hi[i]（绿）连到折叠后的点（空心圆）
+// 同一条消息用 Ed25519 签一次：nonce 由私钥和消息确定，(R, S + ℓ) 因为 S ≥ ℓ 被拒绝
+fn ed25519_section(ui: &mut egui::Ui, seed: u64, message: &[u8]) {
+    let mut secret = [0; 32];
+    rng::seeded(seed ^ 0x6564_3235_3531).fill_bytes(&mut secret);
+    let key = ed25519::SigningKey::from_seed(&secret);
+    let signature = key.sign(message);
+    ui.label(
+        "Ed25519 over edwards25519: the nonce is hashed from the key and the message, and \
+         verification checks [8][S]B = [8]R + [8][k]A with S < ℓ.",
+    );
+    ui.label(egui::RichText::new(format!("A = {}", sha256::hex(&key.public))).monospace());
+    egui::Grid::new("ed25519_signatures")
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["", "R", "S", "verifies"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for (name, signature) in [
+                ("signed", signature),
+                ("S + ℓ", ed25519::malleate(&signature)),
+            ] {
+                ui.label(name);
+                for half in signature.chunks(32) {
+                    ui.monospace(format!("{}…", &sha256::hex(half)[..16]));
+                }
+                if ed25519::verify(&key.public, message, &signature) {
+                    ui.colored_label(egui::Color32::DARK_GREEN, "valid");
+                } else {
+                    ui.colored_label(egui::Color32::RED, "rejected");
+                }
+                ui.end_row();
+            }
+        });
+}
+
+// 拖动条只能编辑 u64：把秘密标量复制出来编辑，用完清零。值变了才返回新的 SecretScalar，
+// 调用方换掉旧的，旧的在 drop 时清零
+fn edit_secret(ui: &mut egui::Ui, label: &str, secret: &SecretScalar) -> Option<SecretScalar> {
+    let q = secret.expose().modulus();
+    let mut value = secret.expose().value();
+    let label = ui.label(label);
+    ui.add(egui::DragValue::new(&mut value).range(1..=q - 1))
+        .labelled_by(label.id);
+    let edited =
+        (value != secret.expose().value()).then(|| SecretScalar::new(Scalar::from_u64(value, q)));
+    value.zeroize();
+    edited
+}
+
+// 两个秘密标量加一个随机化按钮，labels 的最后一项是按钮的文字
+fn edit_secret_pair(
+    ui: &mut egui::Ui,
+    [first, second, randomize]: [&str; 3],
+    (a, b): (&SecretScalar, &SecretScalar),
+    rng: &mut DemoRng,
+) -> Option<(SecretScalar, SecretScalar)> {
+    let (new_a, new_b) = (edit_secret(ui, first, a), edit_secret(ui, second, b));
+    if ui.button(randomize).clicked() {
+        let q = a.expose().modulus();
+        return Some((
+            SecretScalar::new(Scalar::random(rng, q)),
+            SecretScalar::new(Scalar::random(rng, q)),
+        ));
+    }
+    if new_a.is_none() && new_b.is_none() {
+        return None;
+    }
+    Some((
+        new_a.unwrap_or_else(|| a.clone()),
+        new_b.unwrap_or_else(|| b.clone()),
+    ))
+}
+
+// 演示的默认秘密值，截到 [1, q) 里
+fn demo_secret(value: u64, q: u64) -> SecretScalar {
+    SecretScalar::new(Scalar::from_u64(value.clamp(1, q - 1), q))
+}
+
+// 逐步演示的 (d, k)：没有手动选过时 d 由种子生成，k 按 RFC 6979 由 d 和消息确定，
+// 打开随机 nonce 时也由种子生成
+fn ecdsa_keys(
+    keys: Option<&(SecretScalar, SecretScalar)>,
+    seed: u64,
+    group: &PrimeGroup,
+    message: &[u8],
+    random: bool,
+) -> (SecretScalar, SecretScalar) {
+    if let Some(keys) = keys {
+        return keys.clone();
+    }
+    let q = group.q;
+    let mut rng = rng::seeded(seed ^ 0x0065_6364_7361);
+    let d = SecretScalar::new(Scalar::random(&mut rng, q));
+    let k = if random {
+        Scalar::random(&mut rng, q)
+    } else {
+        ecdsa::deterministic_nonce(group, *d.expose(), message)
+    };
+    (d, SecretScalar::new(k))
+}
+
+// 签名和验证的每一步
+fn ecdsa_steps(ui: &mut egui::Ui, group: &PrimeGroup, d: Scalar, message: &[u8], k: Scalar) {
+    let Some(steps) = ecdsa::Steps::run(group, d, message, k) else {
+        ui.colored_label(
+            egui::Color32::RED,
+            "r = 0 or s = 0 with this nonce, pick another k",
+        );
+        return;
+    };
+    let ecdsa::Signature { r, s } = steps.signature;
+    let k_inverse = k.invert().expect("s was computed, so k is invertible");
+    for line in [
+        format!("1. z = H(m) mod q = {}", steps.z.value()),
+        format!("2. Q = d·G = {}", Coordinates(steps.public)),
+        format!("3. R = k·G = {}", Coordinates(steps.point)),
+        format!("4. r = x(R) mod q = {}", r.value()),
+        format!(
+            "5. s = k⁻¹(z + r·d) = {}·({} + {}·{}) = {} mod q",
+            k_inverse.value(),
+            steps.z.value(),
+            r.value(),
+            d.value(),
+            s.value()
+        ),
+        format!("6. w = s⁻¹ mod q = {}", steps.w.value()),
+        format!(
+            "7. u1 = z·w = {}, u2 = r·w = {}",
+            steps.u1.value(),
+            steps.u2.value()
+        ),
+        format!("8. X = u1·G + u2·Q = {}", Coordinates(steps.check)),
+    ] {
+        ui.monospace(line);
+    }
+    if steps.accepts() {
+        ui.colored_label(egui::Color32::DARK_GREEN, "9. x(X) mod q = r: valid");
+    } else {
+        ui.colored_label(egui::Color32::RED, "9. x(X) mod q ≠ r: rejected");
+    }
+}
+
+// 同一条消息在 P-256 上签一次（ES256）：256 位的 d 由种子生成，k 按 RFC 6979 确定
+fn p256_section(ui: &mut egui::Ui, seed: u64, message: &[u8]) {
+    let p256 = P256::new();
+    let key = presets::KeyPair::generate(&presets::p256(), &mut rng::seeded(seed ^ 0x7032_3536));
+    let (d, public) = (key.secret(), key.public);
+    let mut nonces = p256.nonces(d, message);
+    let (k, signature) = loop {
+        let k = nonces.next_nonce();
+        if let Some(signature) = p256.sign(d, message, &k) {
+            break (k, signature);
+        }
+    };
+    ui.label(
+        "ECDSA over NIST P-256 with SHA-256 (ES256): the same steps with 256-bit scalars \
+         modulo the group order n, and k derived from d and the message (RFC 6979).",
+    );
+    let hex = |limbs: &u256::Limbs| sha256::hex(&u256::to_be_bytes(limbs));
+    for (name, value) in [
+        ("d", *d),
+        ("Q.x", public.0),
+        ("Q.y", public.1),
+        ("z", p256.hash_message(message)),
+        ("k", k),
+        ("r", signature.r),
+        ("s", signature.s),
+    ] {
+        ui.monospace(format!("{name:>3} = {}", hex(&value)));
+    }
+    if p256.verify(public, message, &signature) {
+        ui.colored_label(egui::Color32::DARK_GREEN, "The P-256 signature verifies");
+    } else {
+        ui.colored_label(egui::Color32::RED, "The P-256 signature is rejected");
+    }
+}
+
+// 同一条消息用 BIP-340 在 secp256k1 上签一次，逐项显示挑战 e 的计算和验证方程
+fn bip340_section(ui: &mut egui::Ui, seed: u64, message: &[u8]) {
+    let mut rng = rng::seeded(seed ^ 0x6269_7033_3430);
+    let mut random = || {
+        let mut bytes = [0; 32];
+        rng.fill_bytes(&mut bytes);
+        bytes
+    };
+    // 落在 [1, n) 外的概率约 2^−128，重新抽一次
+    let key = loop {
+        if let Some(key) = bip340::SigningKey::from_bytes(&random()) {
+            break key;
+        }
+    };
+    let aux = random();
+    let signing = key.sign(message, &aux);
+    ui.label(
+        "Schnorr over secp256k1 as in BIP-340: keys and R are x-only with even y, and every \
+         hash is tagged, H_tag(x) = SHA256(SHA256(tag) ‖ SHA256(tag) ‖ x).",
+    );
+    let hex = sha256::hex;
+    let s: [u8; 32] = signing.signature[32..].try_into().expect("32 bytes");
+    for (name, value) in [
+        ("P", key.public),
+        ("R", signing.r),
+        (
+            "SHA256(tag)",
+            sha256::sha256(bip340::CHALLENGE_TAG.as_bytes()),
+        ),
+        ("e", signing.e.to_be_bytes()),
+        ("s", s),
+    ] {
+        ui.monospace(format!("{name:>11} = {}", hex(&value)));
+    }
+    let negated = |negated: bool| if negated { "n − d" } else { "d" };
+    ui.label(format!(
+        "e = H_{}(R ‖ P ‖ m) mod n, s = k + e·({}) mod n",
+        bip340::CHALLENGE_TAG,
+        negated(signing.negated_key)
+    ));
+    if bip340::verify(&key.public, message, &signing.signature) {
+        ui.colored_label(
+            egui::Color32::DARK_GREEN,
+            "s·G − e·P has even y and x = R: the BIP-340 signature verifies",
+        );
+    } else {
+        ui.colored_label(egui::Color32::RED, "The BIP-340 signature is rejected");
+    }
+}
+
+fn ipa_demo(ui: &mut egui::Ui, demo: &ipa::Demo, round: &mut usize) {
+    let (proof, setup) = (&demo.proof, &demo.setup);
+    let (status, color) = if demo.verified {
+        ("verified", egui::Color32::DARK_GREEN)
+    } else {
+        ("rejected", egui::Color32::RED)
+    };
+    ui.horizontal(|ui| {
+        ui.label(format!(
+            "q = {}, n = {}: {} rounds, proof is {} points + 2 scalars, P = {}",
+            setup.q,
+            setup.g.len(),
+            proof.rounds.len(),
+            2 * proof.rounds.len(),
+            Coordinates(demo.commitment)
+        ));
+        ui.label(egui::RichText::new(status).color(color).strong());
+    });
+    let label = ui.label("round:");
+    ui.add(egui::Slider::new(round, 0..=proof.rounds.len()))
+        .labelled_by(label.id);
+    let fold = &demo.folds[*round];
+    let next = demo.folds.get(*round + 1);
+    ui.horizontal_top(|ui| {
+        ui.vertical(|ui| {
+            egui::Grid::new("ipa_fold").striped(true).show(ui, |ui| {
+                for header in ["i", "a", "b", "G", "H"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for i in 0..fold.a.len() {
+                    ui.label(i.to_string());
+                    ui.label(fold.a[i].value().to_string());
+                    ui.label(fold.b[i].value().to_string());
+                    ui.label(Coordinates(fold.g[i]).to_string());
+                    ui.label(Coordinates(fold.h[i]).to_string());
+                    ui.end_row();
+                }
+            });
+            ui.label(format!("<a, b> = {}", ipa::inner(&fold.a, &fold.b).value()));
+            match proof.rounds.get(*round) {
+                Some(r) => {
+                    ui.label(format!(
+                        "L = {}, R = {}",
+                        Coordinates(r.l),
+                        Coordinates(r.r)
+                    ));
+                    ui.label(format!(
+                        "x = {}: a' = a_lo·x + a_hi·x⁻¹, b' = b_lo·x⁻¹ + b_hi·x, \
+                         G' = G_lo·x⁻¹ + G_hi·x, H' = H_lo·x + H_hi·x⁻¹",
+                        r.x.value()
+                    ));
+                }
+                None => {
+                    ui.label(format!(
+                        "Prover reveals a = {}, b = {}",
+                        proof.a.value(),
+                        proof.b.value()
+                    ));
+                }
+            }
+        });
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(240.0, 240.0), egui::Sense::hover());
+        ui.painter()
+            .rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        let canvas = Canvas::new(ui.painter_at(rect), rect, setup.curve.p);
+        let blue = egui::Color32::from_rgb(90, 120, 200);
+        let green = egui::Color32::from_rgb(60, 160, 90);
+        for (points, folded, color) in [
+            (&fold.g, next.map(|n| &n.g), blue),
+            (&fold.h, next.map(|n| &n.h), green),
+        ] {
+            for (i, &point) in points.iter().enumerate() {
+                let Some((x, y)) = point else { continue };
+                let pos = canvas.to_screen(x, y);
+                canvas.painter.circle_filled(pos, 3.0, color);
+                // 第 i 个和第 i + n/2 个折叠成第 i 个
+                if let Some(Some((fx, fy))) = folded.map(|f| f[i % f.len()]) {
+                    canvas.painter.line_segment(
+                        [pos, canvas.to_screen(fx, fy)],
+                        egui::Stroke::new(1.0, color.gamma_multiply(0.5)),
+                    );
+                }
+            }
+            for &point in folded.into_iter().flatten() {
+                if let Some((x, y)) = point {
+                    canvas.painter.circle_stroke(
+                        canvas.to_screen(x, y),
+                        5.0,
+                        egui::Stroke::new(1.5, color),
+                    );
+                }
+            }
+        }
+    });
+}
+
+// 窗口里的小画布：曲线上的点画成蓝点，marks 画成空心圆
+// 离散对数窗口的 (曲线, G, k)
+type DlogKey = (Curve, Coords, u64);
+
+// 三个离散对数窗口共用：键变了就在后台重新求解，算完的结果写进 solved。
+// 曲线统计里已经有群阶时直接用，否则工作线程要先数一遍点；取消后的 Job 留着，键变了才重新开始
+fn poll_dlog<T: Send + 'static>(
+    ctx: &egui::Context,
+    key: DlogKey,
+    group_order: Option<u64>,
+    solved: &mut Option<(DlogKey, T)>,
+    job: &mut Option<(DlogKey, Job<T>)>,
+    solve: fn(&Curve, Coords, Coords, u64) -> T,
+) {
+    if let Some((built, running)) = job {
+        if let Some(result) = running.poll() {
+            *solved = Some((*built, result));
+            *job = None;
+        } else if *built != key {
+            *job = None;
+        }
+    }
+    if job.is_none() && solved.as_ref().is_none_or(|(built, _)| *built != key) {
+        let (curve, g, secret) = key;
+        let work = move |progress: &Progress| {
+            let group_order = match group_order {
+                Some(n) => n,
+                None => job::nonsingular_points(&curve, progress)?.len() as u64 + 1,
+            };
+            let order = curve.order_of(g, group_order);
+            let q = curve.mul_coords(g, secret % order);
+            Some(solve(&curve, g, q, order))
+        };
+        *job = Some((key, Job::spawn(ctx, work)));
+    }
+}
+
+// 后台任务的进度条和 Cancel 按钮；取消后显示 Restart，点了返回 true，由调用方丢掉 Job 重新开始
+fn job_controls<T>(ui: &mut egui::Ui, job: &Job<T>, label: &str) -> bool {
+    let mut restart = false;
+    ui.horizontal(|ui| {
+        // 没取消却结束了而且没有结果，说明工作线程 panic 了
+        if job.is_cancelled() || job.is_finished() {
+            let reason = if job.is_cancelled() {
+                "cancelled"
+            } else {
+                "failed"
+            };
+            ui.label(format!("{label}: {reason}"));
+            restart = ui.button("Restart").clicked();
+        } else {
+            ui.add(
+                egui::ProgressBar::new(job.progress())
+                    .text(label)
+                    .desired_width(300.0),
+            );
+            if ui.button("Cancel").clicked() {
+                job.cancel();
+            }
+            // 工作线程只在结束时请求重绘，进度条要靠这里刷新
+            ui.ctx().request_repaint();
+        }
+    });
+    restart
+}
+
+fn small_plot(
+    ui: &mut egui::Ui,
+    p: i64,
+    points: &[(i64, i64)],
+    marks: &[(Option<(i64, i64)>, egui::Color32)],
+) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(240.0, 240.0), egui::Sense::hover());
+    ui.painter()
+        .rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+    let canvas = Canvas::new(ui.painter_at(rect), rect, p);
+    for &(x, y) in points {
+        canvas.painter.circle_filled(
+            canvas.to_screen(x, y),
+            2.0,
+            egui::Color32::from_rgb(90, 120, 200),
+        );
+    }
+    for &(point, color) in marks {
+        if let Some((x, y)) = point {
+            canvas.painter.circle_stroke(
+                canvas.to_screen(x, y),
+                5.0,
+                egui::Stroke::new(1.5, color),
+            );
+        }
+    }
+}
+
+// 简单的柱状图，柱子上方标数值，下方标名称
+// 阶的种类可能很多，只列出最小的几个
+const SHOWN_ORDERS: usize = 16;
+
+fn bar_chart(ui: &mut egui::Ui, bars: &[(String, usize)], color: egui::Color32) {
+    let (bar_width, gap, height) = (32.0, 6.0, 90.0);
+    let size = egui::vec2(bars.len() as f32 * (bar_width + gap), height + 32.0);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let max = bars.iter().map(|(_, v)| *v).max().unwrap_or(0).max(1);
+    let font = egui::FontId::proportional(10.0);
+    let text_color = ui.visuals().text_color();
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let left = rect.min.x + i as f32 * (bar_width + gap);
+        let bottom = rect.min.y + 16.0 + height;
+        let top = bottom - height * *value as f32 / max as f32;
+        painter.rect_filled(
+            egui::Rect::from_min_max(egui::pos2(left, top), egui::pos2(left + bar_width, bottom)),
+            0.0,
+            color,
+        );
+        let center = left + bar_width / 2.0;
+        painter.text(
+            egui::pos2(center, top - 2.0),
+            egui::Align2::CENTER_BOTTOM,
+            value.to_string(),
+            font.clone(),
+            text_color,
+        );
+        painter.text(
+            egui::pos2(center, bottom + 2.0),
+            egui::Align2::CENTER_TOP,
+            label,
+            font.clone(),
+            text_color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::ConsoleSubscriber;
+    use crate::viewport::Viewport;
+    use egui::accesskit::{Action, ActionData, ActionRequest, Node, NodeId};
+    use egui::epaint::{ClippedShape, Shape};
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    const SEED: u64 = 7;
+
+    // 无显示环境下驱动界面：通过 AccessKit 树按标签找到控件并发送动作，
+    // 与 egui_kittest 的做法一致
+    struct Harness {
+        ctx: egui::Context,
+        app: EllipticCurveApp,
+        events: Vec<egui::Event>,
+        nodes: Vec<(NodeId, Node)>,
+        shapes: Vec<ClippedShape>,
+        // 只收集测试线程上的日志，后台线程的输出不影响快照
+        _log_guard: tracing::subscriber::DefaultGuard,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let ctx = egui::Context::default();
+            ctx.enable_accesskit();
+            let log = LogBuffer::default();
+            let subscriber = ConsoleSubscriber::new(log.clone(), tracing::Level::DEBUG);
+            let mut harness = Harness {
+                _log_guard: tracing::subscriber::set_default(subscriber),
+                ctx,
+                app: EllipticCurveApp::new(SEED, log),
+                events: vec![],
+                nodes: vec![],
+                shapes: vec![],
+            };
+            harness.run();
+            harness
+        }
+
+        fn step(&mut self) {
+            let input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO,
+                    egui::vec2(1280.0, 960.0),
+                )),
+                events: std::mem::take(&mut self.events),
+                ..Default::default()
+            };
+            let output = self.ctx.run(input, |ctx| self.app.show(ctx));
+            self.nodes = output
+                .platform_output
+                .accesskit_update
+                .map(|update| update.nodes)
+                .unwrap_or_default();
+            self.shapes = output.shapes;
+        }
+
+        // 多跑几帧让布局稳定，并等后台的曲线统计算完，快照才是确定的
+        fn run(&mut self) {
+            for _ in 0..3 {
+                self.step();
+            }
+            let deadline = Instant::now() + Duration::from_secs(30);
+            // 取消后的 Job 会一直留着，只等还在算的
+            let running = |finished: Option<bool>| finished == Some(false);
+            let jobs = |app: &EllipticCurveApp| {
+                [
+                    app.summary_job.as_ref().map(|(_, job)| job.is_finished()),
+                    app.dlog_job.as_ref().map(|(_, job)| job.is_finished()),
+                    app.rho_job.as_ref().map(|(_, job)| job.is_finished()),
+                    app.pohlig_job.as_ref().map(|(_, job)| job.is_finished()),
+                    app.selfcheck_job.as_ref().map(|(_, job)| job.is_finished()),
+                ]
+            };
+            while jobs(&self.app).into_iter().any(running)
+                || self.app.diagnostics_job.is_some()
+                || self.app.explorer_job.is_some()
+                || self.app.player.is_some()
+                || self.app.view.is_enumerating()
+            {
+                assert!(Instant::now() < deadline, "background job did not finish");
+                std::thread::sleep(Duration::from_millis(5));
+                self.step();
+            }
+            self.step();
+        }
+
+        // 按钮的文字在 label 里；输入框通过 labelled_by 指向旁边的 Label，其文字在 value 里
+        fn label_of<'a>(&'a self, node: &'a Node) -> Option<&'a str> {
+            node.label().or_else(|| {
+                let target = node.labelled_by().first()?;
+                let (_, label) = self.nodes.iter().find(|(id, _)| id == target)?;
+                label.value()
+            })
+        }
+
+        // 同名控件（例如每个追踪点都有的单选框）取屏幕上最靠下的一个；
+        // 节点本身的顺序不固定，只能按位置排
+        fn find(&self, label: &str) -> NodeId {
+            self.nodes
+                .iter()
+                .filter(|(_, node)| self.label_of(node) == Some(label))
+                .max_by(|(_, a), (_, b)| {
+                    let y = |node: &Node| node.bounds().map_or(0.0, |rect| rect.y0);
+                    y(a).total_cmp(&y(b))
+                })
+                .map(|(id, _)| *id)
+                .unwrap_or_else(|| panic!("no widget labelled {label:?}"))
+        }
+
+        fn action(&mut self, label: &str, action: Action, data: Option<ActionData>) {
+            let target = self.find(label);
+            self.events
+                .push(egui::Event::AccessKitActionRequest(ActionRequest {
+                    action,
+                    target,
+                    data,
+                }));
+            self.run();
+        }
+
+        fn click(&mut self, label: &str) {
+            self.action(label, Action::Click, None);
+        }
+
+        fn set_value(&mut self, label: &str, value: i64) {
+            let data = ActionData::NumericValue(value as f64);
+            self.action(label, Action::SetValue, Some(data));
+        }
+
+        fn state(&self) -> Vec<String> {
+            let app = &self.app;
+            let mut lines = vec![
+                format!("curve: {:?}", app.view.curve),
+                format!("points: {}", app.view.points.len()),
+                format!("pending singular: {:?}", app.pending_singular),
+                format!("param error: {:?}", app.param_error),
+                format!("new base error: {:?}", app.new_base_error),
+                "tracked:".to_string(),
+            ];
+            lines.extend(app.view.tracked.iter().map(|t| format!("  {}", t.label())));
+            lines
+        }
+
+        // 快照包括应用状态，以及这一帧画出的圆点数量和全部文字
+        fn snapshot(&self) -> String {
+            fn visit(shape: &Shape, circles: &mut usize, texts: &mut Vec<String>) {
+                match shape {
+                    Shape::Vec(shapes) => {
+                        for shape in shapes {
+                            visit(shape, circles, texts);
+                        }
+                    }
+                    Shape::Circle(_) => *circles += 1,
+                    Shape::Text(text) => texts.push(text.galley.text().to_string()),
+                    _ => {}
+                }
+            }
+            let (mut circles, mut texts) = (0, vec![]);
+            for clipped in &self.shapes {
+                visit(&clipped.shape, &mut circles, &mut texts);
+            }
+
+            let mut lines = self.state();
+            lines.push(format!("circles: {circles}"));
+            lines.push("text:".to_string());
+            lines.extend(texts.iter().map(|text| format!("  {text:?}")));
+            lines.join("\n") + "\n"
+        }
+
+        // UPDATE_SNAPSHOTS=1 cargo test 重新生成快照
+        fn assert_snapshot(&self, name: &str) {
+            let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("testdata/snapshots")
+                .join(format!("{name}.txt"));
+            let actual = self.snapshot();
+            if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, &actual).unwrap();
+                return;
+            }
+            let expected = std::fs::read_to_string(&path).unwrap_or_default();
+            assert!(
+                expected == actual,
+                "snapshot {} differs, rerun with UPDATE_SNAPSHOTS=1 if the change is intended:\n{actual}",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn initial_scene() {
+        let harness = Harness::new();
+        harness.assert_snapshot("initial_scene");
+    }
+
+    #[test]
+    fn apply_new_parameters() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        assert_eq!(harness.app.view.curve, Curve::new(2, 3, 97));
+        harness.assert_snapshot("apply_new_parameters");
+    }
+
+    #[test]
+    fn large_curves_are_drawn_from_one_texture() {
+        // 超过 RASTER_THRESHOLD 个点以后点云光栅化成纹理，整层只画一个带纹理的矩形
+        fn textured(shape: &Shape) -> usize {
+            match shape {
+                Shape::Vec(shapes) => shapes.iter().map(textured).sum(),
+                Shape::Mesh(mesh) => (mesh.texture_id != egui::TextureId::default()) as usize,
+                _ => 0,
+            }
+        }
+        let count = |harness: &Harness| {
+            harness
+                .shapes
+                .iter()
+                .map(|clipped| textured(&clipped.shape))
+                .sum::<usize>()
+        };
+        let mut harness = Harness::new();
+        assert_eq!(count(&harness), 0);
+        harness.set_value("p:", 20011);
+        harness.click("Apply");
+        assert_eq!(harness.app.view.curve.p, 20011);
+        assert!(harness.app.view.points.len() > 10_000);
+        assert_eq!(count(&harness), 1);
+    }
+
+    #[test]
+    fn statistics_window_shows_the_order_census() {
+        let mut harness = Harness::new();
+        // y² = x³ + 4 over F_61 ≅ Z_15 × Z_5
+        harness.set_value("p:", 61);
+        harness.set_value("a:", 0);
+        harness.set_value("b:", 4);
+        harness.click("Apply");
+        harness.click("x-coordinate statistics");
+        harness.run();
+        let summary = harness.app.summaries.peek(&Curve::new(0, 4, 61)).unwrap();
+        assert_eq!(summary.structure_label(), "Z_15 × Z_5");
+        assert_eq!(summary.order_counts.values().sum::<usize>(), 75);
+        assert!(
+            harness
+                .snapshot()
+                .contains("Points by order (group exponent 15):")
+        );
+    }
+
+    #[test]
+    fn long_weierstrass_input_is_reduced_and_points_map_back() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 97);
+        harness.click("Long Weierstrass form");
+        // Cremona 11a1：y² + y = x³ − x² − 10x − 20
+        for (name, value) in [
+            ("a1:", 0),
+            ("a2:", -1),
+            ("a3:", 1),
+            ("a4:", -10),
+            ("a6:", -20),
+        ] {
+            harness.set_value(name, value);
+        }
+        harness.click("Reduce to short form");
+        harness.run();
+        let long = LongWeierstrass::new([0, -1, 1, -10, -20], 97);
+        let reduction = long.reduce().unwrap();
+        assert_eq!(harness.app.view.curve, reduction.curve);
+        let point = harness.app.view.tracked[0].base.clone();
+        let (x, y) = reduction.map_to_long(point.coords()).unwrap();
+        assert!(long.contains(x, y));
+        assert!(
+            harness
+                .snapshot()
+                .contains(&format!("{point} on the short form is ({x}, {y})"))
+        );
+    }
+
+    #[test]
+    fn switching_back_reuses_cached_curve_data() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 97);
+        harness.click("Apply");
+        harness.set_value("p:", 599);
+        harness.click("Apply");
+        assert_eq!(harness.app.view.curve, Curve::new(1, 1, 599));
+        assert!(!harness.app.view.is_enumerating());
+        assert!(harness.app.summary_job.is_none());
+        let entries = harness.app.log.entries();
+        let messages: Vec<&str> = entries.iter().map(|e| e.message.as_str()).collect();
+        assert!(messages.contains(&"affine points from cache count=596"));
+        assert_eq!(harness.app.view.cache.len(), 2);
+        assert_eq!(harness.app.summaries.len(), 2);
+    }
+
+    #[test]
+    fn large_curves_are_enumerated_in_parallel() {
+        // p 超过 PARALLEL_MIN_P 时界面的枚举同样按 x 拆给多个线程
+        let mut harness = Harness::new();
+        harness.set_value("p:", 100_003);
+        harness.click("Apply");
+        let curve = Curve::new(1, 1, 100_003);
+        assert_eq!(harness.app.view.curve, curve);
+        assert_eq!(
+            harness.app.view.points.len() as u64 + 1,
+            curve.group_order()
+        );
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let message = format!(
+            "enumerated affine points count={} workers={workers}",
+            harness.app.view.points.len()
+        );
+        let entries = harness.app.log.entries();
+        assert!(entries.iter().any(|e| e.message == message), "{message}");
+    }
+
+    #[test]
+    fn add_point_and_track_doublings() {
+        let mut harness = Harness::new();
+        harness.set_value("x:", 7);
+        harness.set_value("y:", 146);
+        harness.click("Add point");
+        assert_eq!(harness.app.view.tracked.len(), 2);
+        harness.click("P, 2P, 4P, ..., 2^k P");
+        harness.set_value("k:", 5);
+        assert_eq!(
+            harness.app.view.tracked[1].trajectory,
+            Trajectory::Doublings
+        );
+        harness.assert_snapshot("add_point_and_track_doublings");
+    }
+
+    #[test]
+    fn rejects_point_off_the_curve() {
+        let mut harness = Harness::new();
+        harness.set_value("x:", 3);
+        harness.set_value("y:", 4);
+        harness.click("Add point");
+        assert_eq!(harness.app.view.tracked.len(), 1);
+        assert!(harness.app.new_base_error.is_some());
+        let entries = harness.app.log.entries();
+        let warning = entries.last().expect("warning was logged");
+        assert_eq!(warning.level, tracing::Level::WARN);
+        assert_eq!(warning.message, "point is not on the curve x=3 y=4");
+        harness.assert_snapshot("rejects_point_off_the_curve");
+    }
+
+    #[test]
+    fn run_diagnostics() {
+        let mut harness = Harness::new();
+        harness.click("Diagnostics");
+        harness.click("Run checks");
+        let results = harness.app.diagnostics.as_ref().expect("checks have run");
+        assert!(results.iter().all(CheckResult::passed), "{results:?}");
+        let texts = harness.snapshot();
+        assert_eq!(texts.matches("\"PASS\"").count(), results.len());
+    }
+
+    #[test]
+    fn self_check_the_current_curve() {
+        let mut harness = Harness::new();
+        harness.click("Diagnostics");
+        harness.click("Self-check curve");
+        let (curve, result) = harness.app.selfcheck.as_ref().expect("self-check has run");
+        assert_eq!(*curve, harness.app.view.curve);
+        let check = result.as_ref().expect("the default curve is a group");
+        assert_eq!(check.triples, diagnostics::SELFCHECK_TRIPLES);
+        assert_eq!(check.group_order, curve.group_order());
+        let texts = harness.snapshot();
+        assert!(texts.contains("\"PASS\""));
+        assert!(texts.contains("ord(G)·G = O"));
+    }
+
+    #[test]
+    fn fold_vectors_in_the_inner_product_argument() {
+        let mut harness = Harness::new();
+        harness.click("Inner-product argument");
+        harness.click("Prove");
+        let Some(Ok(demo)) = &harness.app.ipa else {
+            panic!(
+                "demo did not run: {:?}",
+                harness.app.ipa.as_ref().map(|r| r.as_ref().err())
+            );
+        };
+        assert!(demo.verified);
+        assert_eq!(demo.proof.rounds.len(), 3);
+        assert!(harness.snapshot().contains("\"verified\""));
+        harness.set_value("round:", 3);
+        assert!(harness.snapshot().contains("Prover reveals a = "));
+    }
+
+    #[test]
+    fn find_a_pedersen_collision_on_a_toy_curve() {
+        let mut harness = Harness::new();
+        harness.click("Pedersen hash");
+        harness.click("Find collision");
+        let Some(Some(collision)) = &harness.app.pedersen_collision else {
+            panic!("no collision: {:?}", harness.app.pedersen_collision);
+        };
+        let message = harness.app.pedersen_message.clone();
+        let collision = collision.message.clone();
+        let Some(Ok(hasher)) = &mut harness.app.pedersen else {
+            panic!("hasher was not set up");
+        };
+        assert_eq!(hasher.hash(&collision), hasher.hash(message.as_bytes()));
+        assert!(harness.snapshot().contains("found by brute force in"));
+    }
+
+    #[test]
+    fn cheating_at_coin_flip_is_detected() {
+        let mut harness = Harness::new();
+        harness.click("Coin flip by telephone");
+        harness.click("Flip and commit");
+        harness.click("Guess heads");
+        harness.click("Cheat: claim the other side");
+        let Some(Ok(game)) = &harness.app.coinflip else {
+            panic!("game was not set up");
+        };
+        assert_eq!(game.outcome, Some(Outcome::CheatDetected));
+        assert!(harness.snapshot().contains("\"Cheating detected\""));
+
+        harness.click("Play again");
+        harness.click("Flip and commit");
+        harness.click("Guess tails");
+        harness.click("Reveal");
+        let Some(Ok(game)) = &harness.app.coinflip else {
+            panic!("game was not set up");
+        };
+        assert!(matches!(
+            game.outcome,
+            Some(Outcome::BobWins | Outcome::AliceWins)
+        ));
+    }
+
+    #[test]
+    fn signed_key_shares_stop_mallory() {
+        let mut harness = Harness::new();
+        harness.click("ECDH man in the middle");
+        let exchange = harness.app.ecdh.as_ref().expect("exchange ran");
+        assert!(exchange.compromised());
+        assert!(harness.snapshot().contains("Mallory knows both"));
+
+        harness.click("Sign key shares");
+        let exchange = harness.app.ecdh.as_ref().expect("exchange ran");
+        assert!(!exchange.compromised());
+        assert_eq!((exchange.alice, exchange.bob), (None, None));
+        assert!(harness.snapshot().contains("were rejected"));
+
+        harness.click("Mallory in the middle");
+        assert!(harness.app.ecdh.as_ref().expect("exchange ran").agreed());
+
+        harness.click("Real world: X25519");
+        let exchange = harness.app.ecdh.as_ref().expect("exchange ran");
+        let real = exchange.real_world.as_ref().expect("X25519 ran");
+        assert_eq!(real.alice, real.bob);
+        assert!(harness.snapshot().contains("The X25519 secrets agree"));
+    }
+
+    #[test]
+    fn chosen_ecdh_keys_meet_at_the_same_point() {
+        let mut harness = Harness::new();
+        harness.click("ECDH man in the middle");
+        harness.click("Mallory in the middle");
+        harness.set_value("Alice's a:", 3);
+        harness.set_value("Bob's b:", 5);
+        let exchange = harness.app.ecdh.as_ref().expect("exchange ran");
+        let (a, b) = (*exchange.secrets.0.expose(), *exchange.secrets.1.expose());
+        assert_eq!((a.value(), b.value()), (3, 5));
+        assert!(exchange.agreed());
+        let group = PrimeGroup::new(&harness.app.view.curve).unwrap();
+        let (x, y) = group.base_mul(a * b).unwrap();
+        // 两个标记落在同一个点上，合成一个标签
+        let label = format!("Alice's a·B, Bob's b·A ({x}, {y})");
+        assert!(
+            harness.snapshot().contains(&label),
+            "{}",
+            harness.snapshot()
+        );
+
+        harness.click("Randomize keys");
+        let exchange = harness.app.ecdh.as_ref().expect("exchange ran");
+        assert!(exchange.agreed());
+        assert!(harness.app.ecdh_secrets.is_some());
+
+        // 换曲线时丢弃手动选的私钥
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        assert!(harness.app.ecdh_secrets.is_none());
+        assert!(harness.app.ecdh.as_ref().expect("exchange ran").agreed());
+    }
+
+    #[test]
+    fn fault_attacks_and_the_on_curve_check() {
+        let mut harness = Harness::new();
+        harness.click("Fault injection");
+        harness.click("Flip scalar bit");
+        assert!(harness.snapshot().contains("so bit 2 of k is 1"));
+        harness.click("Recover k with one bit flip per bit");
+        assert!(harness.snapshot().contains("k·P matches"));
+
+        harness.click("Corrupt x");
+        assert!(harness.snapshot().contains("The output lies on"));
+        harness.click("Check the result is on the curve");
+        assert!(harness.snapshot().contains("Output withheld"));
+    }
+
+    #[test]
+    fn malleated_ecdsa_signatures_until_high_s_is_rejected() {
+        let mut harness = Harness::new();
+        harness.click("ECDSA malleability");
+        let snapshot = harness.snapshot();
+        assert_eq!(snapshot.matches("\"valid\"").count(), 3);
+        harness.click("Reject high s");
+        let snapshot = harness.snapshot();
+        assert_eq!(snapshot.matches("\"valid\"").count(), 2);
+        assert_eq!(snapshot.matches("\"rejected\"").count(), 1);
+
+        // Ed25519 的 S + ℓ 不管 low-s 开关都被拒绝
+        harness.click("Compare with Ed25519");
+        let snapshot = harness.snapshot();
+        assert_eq!(snapshot.matches("\"valid\"").count(), 3);
+        assert_eq!(snapshot.matches("\"rejected\"").count(), 2);
+    }
+
+    #[test]
+    fn ecdsa_step_by_step_with_chosen_keys() {
+        let mut harness = Harness::new();
+        harness.click("ECDSA malleability");
+        harness.click("Step by step");
+        harness.set_value("Private key d:", 4);
+        harness.set_value("Nonce k:", 3);
+        let keys = harness.app.ecdsa_keys.as_ref().expect("keys were chosen");
+        assert_eq!((keys.0.expose().value(), keys.1.expose().value()), (4, 3));
+        let group = PrimeGroup::new(&harness.app.view.curve).unwrap();
+        let (d, k) = (Scalar::from_u64(4, group.q), Scalar::from_u64(3, group.q));
+        let message = harness.app.ecdsa_message.clone();
+        let steps = ecdsa::Steps::run(&group, d, message.as_bytes(), k).unwrap();
+        let snapshot = harness.snapshot();
+        let r = steps.signature.r.value();
+        assert!(
+            snapshot.contains(&format!("4. r = x(R) mod q = {r}")),
+            "{snapshot}"
+        );
+        assert!(snapshot.contains("x(X) mod q = r: valid"));
+        // X 和 R 是同一个点
+        let (x, y) = steps.point.unwrap();
+        assert!(snapshot.contains(&format!("R = k·G, X = u1·G + u2·Q ({x}, {y})")));
+
+        harness.click("Real world: P-256");
+        assert!(harness.snapshot().contains("The P-256 signature verifies"));
+    }
+
+    #[test]
+    fn ecdsa_nonces_follow_rfc6979_until_random_is_chosen() {
+        let mut harness = Harness::new();
+        harness.click("ECDSA malleability");
+        harness.click("Step by step");
+        let group = PrimeGroup::new(&harness.app.view.curve).unwrap();
+        let message = harness.app.ecdsa_message.clone();
+        let nonce_point = |app: &EllipticCurveApp| {
+            let (d, k) = ecdsa_keys(
+                None,
+                app.seed,
+                &group,
+                message.as_bytes(),
+                app.ecdsa_random_nonces,
+            );
+            (*d.expose(), group.base_mul(*k.expose()))
+        };
+        let (d, point) = nonce_point(&harness.app);
+        let k = ecdsa::deterministic_nonce(&group, d, message.as_bytes());
+        assert_eq!(point, group.base_mul(k));
+        assert!(
+            harness
+                .snapshot()
+                .contains(&format!("3. R = k·G = {}", Coordinates(point)))
+        );
+
+        harness.click("Random nonces");
+        let (_, random) = nonce_point(&harness.app);
+        assert_ne!(random, point);
+        assert!(
+            harness
+                .snapshot()
+                .contains(&format!("3. R = k·G = {}", Coordinates(random)))
+        );
+    }
+
+    #[test]
+    fn nonce_reuse_recovers_the_key() {
+        let mut harness = Harness::new();
+        harness.click("ECDSA nonce reuse");
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains("5. d·G = Q: the private key 57 is recovered"),
+            "{snapshot}"
+        );
+        assert!(snapshot.contains("= 23 mod q\""));
+
+        harness.set_value("Victim's d:", 101);
+        harness.set_value("Reused k:", 45);
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("the private key 101 is recovered"));
+        assert!(snapshot.contains("= 45 mod q\""));
+    }
+
+    #[test]
+    fn bip340_section_shows_the_challenge() {
+        let mut harness = Harness::new();
+        harness.click("ECDSA malleability");
+        harness.click("Real world: BIP-340 Schnorr");
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains("the BIP-340 signature verifies"),
+            "{snapshot}"
+        );
+        let tag = sha256::hex(&sha256::sha256(b"BIP0340/challenge"));
+        assert!(snapshot.contains(&format!("SHA256(tag) = {tag}")));
+        assert!(snapshot.contains("e = H_BIP0340/challenge(R ‖ P ‖ m) mod n"));
+    }
+
+    #[test]
+    fn pollard_rho_walk_is_drawn_on_the_plot() {
+        let mut harness = Harness::new();
+        harness.click("Discrete log (Pollard's rho)");
+        harness.set_value("Secret k:", 123);
+        let (_, rho) = harness.app.rho.clone().expect("solved");
+        assert_eq!(rho.log, Some(123));
+        assert_eq!(harness.app.view.walk.as_ref(), Some(&rho));
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("\"k = 123\""), "{snapshot}");
+        assert!(snapshot.contains(&format!("cycle: {} steps", rho.cycle)));
+
+        harness.click("Discrete log (Pollard's rho)");
+        assert_eq!(harness.app.view.walk, None);
+    }
+
+    #[test]
+    fn pohlig_hellman_solves_each_prime_power() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        harness.click("Pohlig–Hellman attack");
+        harness.set_value("Secret k:", 3);
+        let (_, attack) = harness.app.pohlig.as_ref().expect("solved");
+        assert_eq!(attack.log, Some(3 % attack.order));
+        let moduli: u64 = attack.stages.iter().map(|stage| stage.modulus()).product();
+        assert_eq!(moduli, attack.order);
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains(&format!("{} BSGS steps in total", attack.steps())),
+            "{snapshot}"
+        );
+        assert!(snapshot.contains(&format!("⇒ k = {}\"", 3 % attack.order)));
+    }
+
+    #[test]
+    fn elgamal_window_marks_the_ciphertext() {
+        let mut harness = Harness::new();
+        harness.click("EC ElGamal");
+        let (curve, public, ciphertext) = harness.app.elgamal.expect("encrypted");
+        assert_eq!(curve, harness.app.view.curve);
+        let group = PrimeGroup::new(&curve).unwrap();
+        assert_eq!(public, group.base_mul(Scalar::from_u64(17, group.q)));
+        assert_eq!(ciphertext.c1, group.base_mul(Scalar::from_u64(5, group.q)));
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("Bob decodes ⌊"));
+        assert!(snapshot.contains("= 42"));
+        let markers = harness.app.markers();
+        let labels: Vec<&str> = markers.iter().map(|marker| marker.label.as_str()).collect();
+        for label in ["Y = x·G", "C1 = k·G", "C2 = M + k·Y", "M"] {
+            assert!(labels.contains(&label), "{label}");
+        }
+
+        harness.set_value("Alice's k:", 6);
+        let (_, _, other) = harness.app.elgamal.expect("encrypted");
+        assert_ne!(other.c1, ciphertext.c1);
+        assert!(harness.snapshot().contains("Bob decodes ⌊"));
+    }
+
+    #[test]
+    fn ecm_window_shows_the_failing_step() {
+        let mut harness = Harness::new();
+        harness.click("ECM factorization");
+        let ecm = harness.app.ecm.clone().expect("ECM ran");
+        assert!(matches!(ecm.factor, Some(83 | 97)));
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("\"8051 = 83 × 97\"") || snapshot.contains("\"8051 = 97 × 83\""));
+
+        harness.set_value("N:", 8053);
+        assert_eq!(harness.app.ecm.as_ref().map(|ecm| ecm.n), Some(8053));
+        assert!(harness.snapshot().contains("8053 is prime"));
+    }
+
+    #[test]
+    fn edwards_window_plots_the_short_form() {
+        let mut harness = Harness::new();
+        harness.click("Twisted Edwards");
+        let edwards = TwistedEdwards::new(1, 7, 599);
+        let reduction = edwards.reduce().unwrap();
+        let count = edwards.points().len();
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains(&format!(
+            "{edwards}: {count} points, a is a square and d is not, so the addition law is complete"
+        )));
+        assert!(snapshot.contains("\"P + O\""));
+
+        harness.click("Plot the short form");
+        assert_eq!(harness.app.view.curve, reduction.curve());
+        assert_eq!(harness.app.view.curve.group_order(), count as u64);
+        let tracked = harness.app.view.tracked[0].base.coords();
+        let (x, y) = reduction.map_from_short(tracked).expect("complete curve");
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("The plot shows the short form"));
+        assert!(snapshot.contains(&format!("\"({x}, {y})\"")));
+
+        harness.set_value("Edwards a:", 7);
+        assert!(
+            harness
+                .snapshot()
+                .contains("is singular: needs ad ≠ 0 and a ≠ d")
+        );
+    }
+
+    #[test]
+    fn models_window_shows_the_point_in_every_form() {
+        let mut harness = Harness::new();
+        harness.click("Curve models");
+        assert!(harness.snapshot().contains("No Montgomery form"));
+
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        harness.run();
+        let curve = Curve::new(2, 3, 97);
+        let models = harness.app.models.expect("models are computed");
+        assert_eq!(models.curve, curve);
+        let montgomery = models.montgomery.expect("(2, 3, 97) has a Montgomery form");
+        let point = harness.app.view.tracked[0].base.coords();
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains(&format!(
+            "Montgomery: {}, P = {}",
+            montgomery.montgomery,
+            Coordinates(montgomery.map_to_montgomery(point))
+        )));
+        assert!(snapshot.contains("Twisted Edwards: "));
+    }
+
+    // 求解在工作线程里跑：取消后停在原处，不会自己重新开始，点 Restart 才重算
+    #[test]
+    fn dlog_jobs_can_be_cancelled_and_restarted() {
+        let mut harness = Harness::new();
+        harness.click("Discrete log (BSGS)");
+        harness.set_value("Secret k:", 3);
+        // 换成一个等到取消才结束的任务，免得真正的求解在取消之前就算完了
+        let key = (
+            harness.app.view.curve,
+            harness.app.view.tracked[0].base.coords(),
+            5,
+        );
+        let job = Job::spawn(&harness.ctx, |progress: &Progress| {
+            while !progress.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            None
+        });
+        harness.app.dlog_secret = 5;
+        harness.app.dlog_job = Some((key, job));
+        harness.step();
+        assert!(
+            harness
+                .snapshot()
+                .contains("Finding the order of G and solving")
+        );
+
+        harness.click("Cancel");
+        for _ in 0..3 {
+            harness.step();
+        }
+        let (_, job) = harness
+            .app
+            .dlog_job
+            .as_ref()
+            .expect("kept after cancelling");
+        assert!(job.is_cancelled() && job.is_finished());
+        assert_eq!(harness.app.dlog.as_ref().unwrap().0.2, 3);
+        assert!(harness.snapshot().contains("cancelled"));
+
+        harness.click("Restart");
+        let (built, bsgs) = harness.app.dlog.as_ref().expect("solved");
+        assert_eq!(*built, key);
+        assert_eq!(bsgs.log, Some(5 % bsgs.order));
+        assert!(harness.app.dlog_job.is_none());
+    }
+
+    #[test]
+    fn baby_step_giant_step_finds_the_secret() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        harness.click("Discrete log (BSGS)");
+        harness.set_value("Secret k:", 3);
+        let (_, bsgs) = harness.app.dlog.as_ref().expect("solved");
+        let g = harness.app.view.tracked[0].base.coords();
+        let curve = harness.app.view.curve;
+        assert_eq!(bsgs.log, Some(3 % bsgs.order));
+        assert_eq!(
+            curve.discrete_log(g, curve.mul_coords(g, 3)),
+            Some(3 % bsgs.order)
+        );
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains(&format!("{} baby steps", bsgs.m)),
+            "{snapshot}"
+        );
+        assert!(snapshot.contains(&format!("= {}\"", 3 % bsgs.order)));
+        assert!(snapshot.contains("match"));
+
+        harness.click("Random Q");
+        let (_, bsgs) = harness.app.dlog.as_ref().expect("solved");
+        assert_eq!(bsgs.log, Some(harness.app.dlog_secret));
+    }
+
+    #[test]
+    fn real_view_sits_next_to_the_finite_one() {
+        let mut harness = Harness::new();
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("Elliptic Curve Points"));
+        assert!(!snapshot.contains("Over the reals"));
+
+        harness.click("ℝ");
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains("Over the reals (y^2 = x^3 + 1x + 1)"),
+            "{snapshot}"
+        );
+        assert!(!snapshot.contains("Elliptic Curve Points"));
+        assert!(harness.app.real_view.construction().is_some());
+        harness.click("Pause chord");
+        assert!(!harness.app.real_view.playing);
+
+        harness.click("Both");
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("Elliptic Curve Points"));
+        assert!(snapshot.contains("Over the reals"));
+        // p − 1 当作 −1
+        harness.set_value("a:", 598);
+        harness.click("Apply");
+        assert!(
+            harness
+                .snapshot()
+                .contains("a and b are taken as -1 ≡ 598 and 1 ≡ 1 (mod 599)")
+        );
+    }
+
+    #[test]
+    fn animation_steps_through_double_and_add() {
+        let mut harness = Harness::new();
+        harness.click("Animate k·P");
+        harness.set_value("Animate k:", 11);
+        let timeline = harness.app.animation.clone().expect("timeline");
+        assert_eq!(timeline.frames.len(), 6);
+        assert!(harness.snapshot().contains("Step 0 of 5: P = (1, 188)"));
+
+        harness.click("Step");
+        harness.click("Step");
+        assert_eq!(harness.app.playback.frame, 2);
+        assert_eq!(
+            harness
+                .app
+                .view
+                .animation
+                .as_ref()
+                .map(|frame| frame.multiple),
+            Some(4)
+        );
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("Step 2 of 5: 2·2P = 4P"), "{snapshot}");
+        // 播过的帧留在图上
+        let marked: Vec<&str> = harness
+            .app
+            .view
+            .markers
+            .iter()
+            .map(|m| m.label.as_str())
+            .collect();
+        assert_eq!(marked, ["P", "2P"]);
+
+        harness.click("Play");
+        assert!(harness.app.playback.playing);
+        harness.click("Pause");
+        assert!(!harness.app.playback.playing);
+        harness.click("Restart");
+        assert_eq!(harness.app.playback.frame, 0);
+    }
+
+    #[test]
+    fn invalid_curve_attack_recovers_the_key_until_points_are_checked() {
+        let mut harness = Harness::new();
+        harness.click("Invalid-curve attack");
+        harness.set_value("Server secret d:", 321);
+        let (_, attack) = harness.app.invalid_attack.clone().expect("attack ran");
+        assert_eq!(attack.recovered, Some(321));
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains(&format!(
+                "{} queries give d mod {}, which exceeds #E = 597: d = 321",
+                attack.queries.len(),
+                attack.modulus
+            )),
+            "{snapshot}"
+        );
+
+        harness.click("Server checks that Q is on the curve");
+        let (_, attack) = harness.app.invalid_attack.clone().expect("attack ran");
+        assert_eq!((attack.queries.len(), attack.recovered), (1, None));
+        assert!(
+            harness
+                .snapshot()
+                .contains("The server rejects the first point off the curve")
+        );
+    }
+
+    #[test]
+    fn ladder_hides_the_bits_of_k() {
+        let mut harness = Harness::new();
+        harness.click("Double-and-add vs ladder");
+        harness.set_value("Scalar k:", 0b1011);
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains("3 additions (A), 4 doublings (D)"),
+            "{snapshot}"
+        );
+        assert!(snapshot.contains("\"ADADDAD\""));
+        assert!(snapshot.contains("Both algorithms agree"));
+
+        harness.click("Montgomery ladder");
+        assert_eq!(harness.app.ladder_algorithm, MulAlgorithm::MontgomeryLadder);
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("64 additions (A), 64 doublings (D)"));
+        assert!(snapshot.contains("Both algorithms agree"));
+    }
+
+    #[test]
+    fn standard_curves_from_the_dropdown() {
+        let mut harness = Harness::new();
+        harness.click("Standard curves");
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("secp256k1: y² = x³ + 7 over a 256-bit prime field"));
+        // secp256k1 上 2·G 的 x 坐标
+        assert!(
+            snapshot.contains(
+                "k·G.x = c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+            )
+        );
+
+        harness.click("Curve");
+        harness.click("Curve25519");
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("Curve25519: v² = u³ + 486662u² + u over a 255-bit"));
+        assert!(snapshot.contains(" h = 8"));
+        assert_eq!(harness.app.preset_product.map(|(key, _)| key.0), Some(2));
+    }
+
+    #[test]
+    fn tate_pairing_via_an_elliptic_net() {
+        let mut harness = Harness::new();
+        harness.click("Elliptic net");
+        harness.click("Compute from the tracked point");
+        assert!(matches!(harness.app.net, Some(Err(_))));
+
+        // y² = x³ + 4 over F_61 有完整的 5 阶挠群，嵌入度为 1
+        harness.set_value("p:", 61);
+        harness.set_value("a:", 0);
+        harness.set_value("b:", 4);
+        harness.click("Apply");
+        harness.click("Compute from the tracked point");
+        let Some(Ok(demo)) = &harness.app.net else {
+            panic!(
+                "pairing failed: {:?}",
+                harness.app.net.as_ref().map(|r| r.as_ref().err())
+            );
+        };
+        assert_eq!(demo.via_net, demo.via_miller);
+        assert!(harness.snapshot().contains("The two computations agree"));
+    }
+
+    #[test]
+    fn isogeny_neighbours_follow_the_curve() {
+        let mut harness = Harness::new();
+        harness.click("Isogeny neighbours");
+        harness.run();
+        let j = harness.app.view.curve.j_invariant().unwrap();
+        assert!(harness.snapshot().contains(&format!("j = {j}")));
+
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        harness.run();
+        let Some(Ok(neighbours)) = &harness.app.isogenies else {
+            panic!("no neighbours for y² = x³ + 2x + 3");
+        };
+        assert_eq!(neighbours.curve, Curve::new(2, 3, 97));
+        let expected = Neighbours::new(&Curve::new(2, 3, 97)).unwrap();
+        assert_eq!(neighbours.levels, expected.levels);
+        assert!(harness.snapshot().contains(&format!("j = {}", expected.j)));
+
+        // 取一个 2 同源邻居 j'，找出 j 不变量等于 j' 的曲线来比较
+        let (_, roots) = &expected.levels[0];
+        let j2 = *roots
+            .first()
+            .expect("y² = x³ + 2x + 3 has a 2-isogeny over F_97");
+        let (a, b) = (0..97)
+            .flat_map(|a| (0..97).map(move |b| (a, b)))
+            .find(|&(a, b)| Curve::new(a, b, 97).j_invariant() == Some(j2))
+            .unwrap();
+        harness.set_value("a':", a);
+        harness.set_value("b':", b);
+        harness.run();
+        assert!(harness.snapshot().contains("ℓ-isogenous to E for ℓ = 2"));
+    }
+
+    #[test]
+    fn curve_explorer_filters_and_loads_a_row() {
+        let mut harness = Harness::new();
+        harness.click("Curve explorer");
+        harness.set_value("Field size p:", 11);
+        harness.click("Enumerate curves");
+        let rows = explorer::enumerate(11).unwrap();
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains(&format!(
+            "{0} of {0} non-singular curves over F_11",
+            rows.len()
+        )));
+
+        // p = 11 时超奇异的是 j = 0 和 j = 1728 的曲线，#E 都是 12
+        harness.click("Supersingular only");
+        let supersingular = rows.iter().filter(|row| row.is_supersingular()).count();
+        assert_eq!(supersingular, 20);
+        assert!(harness.snapshot().contains(&format!(
+            "{supersingular} of {} non-singular curves over F_11",
+            rows.len()
+        )));
+        harness.click("y² = x³ + 0x + 3");
+        assert_eq!(harness.app.view.curve, Curve::new(0, 3, 11));
+        assert_eq!(
+            harness
+                .app
+                .summaries
+                .peek(&Curve::new(0, 3, 11))
+                .unwrap()
+                .group_order,
+            12
+        );
+    }
+
+    #[test]
+    fn clicked_point_highlights_its_subgroup_and_cosets() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        harness.click("Subgroup of the clicked point");
+        assert!(
+            harness
+                .snapshot()
+                .contains("Click a point to highlight the subgroup it generates")
+        );
+
+        // #E = 100，(3, 6) 的阶是 5
+        harness.app.view.selection = vec![(3, 6)];
+        harness.run();
+        assert!(
+            harness
+                .snapshot()
+                .contains("<(3, 6)> has order 5 and index 20 in E")
+        );
+        let (_, elements) = harness.app.clicked_subgroup.clone().expect("subgroup");
+        let curve = Curve::new(2, 3, 97);
+        let expected: Vec<Coords> = (0..5).map(|k| curve.mul_coords(Some((3, 6)), k)).collect();
+        assert_eq!(elements, expected);
+        let ((_, base), cosets) = harness.app.coset_cache.as_ref().expect("cosets");
+        assert_eq!((*base, cosets.count), (Some((3, 6)), 20));
+    }
+
+    #[test]
+    fn generators_are_marked_on_the_plot() {
+        let mut harness = Harness::new();
+        harness.click("Highlight generators");
+        // #E = 597 = 3 · 199 是循环群，φ(597) = 396 个生成元
+        assert!(
+            harness
+                .snapshot()
+                .contains("396 of the 597 points generate E")
+        );
+        let marked: Vec<_> = harness
+            .app
+            .view
+            .markers
+            .iter()
+            .filter(|marker| marker.color == GENERATOR)
+            .collect();
+        assert_eq!(marked.len(), 396);
+
+        // y² = x³ + 2x + 3 over F_97 是 Z_50 × Z_2
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        assert!(
+            harness
+                .snapshot()
+                .contains("E is not cyclic, so no point generates it")
+        );
+        assert!(harness.app.view.markers.is_empty());
+    }
+
+    #[test]
+    fn subgroup_lattice_highlights_the_clicked_subgroup() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 11);
+        harness.set_value("a:", 10);
+        harness.set_value("b:", 0);
+        harness.click("Apply");
+        harness.click("Subgroup lattice");
+        harness.run();
+        // y² = x³ − x over F_11 是 Z_6 × Z_2，共 10 个子群
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("H0 ≅ {O}"));
+        assert!(snapshot.contains("H9 ≅ Z_6 × Z_2"));
+
+        harness.click("H5 ≅ Z_2 × Z_2");
+        assert!(
+            harness
+                .snapshot()
+                .contains("H5 ≅ Z_2 × Z_2 has order 4 and index 3 in E")
+        );
+        let mut highlight = harness.app.lattice_highlight.clone();
+        highlight.sort();
+        let mut two_torsion: Vec<Coords> = vec![None];
+        two_torsion.extend([(0, 0), (1, 0), (10, 0)].map(Some));
+        assert_eq!(highlight, two_torsion);
+
+        // 换了曲线就重建，选择也清掉
+        harness.set_value("a:", 1);
+        harness.click("Apply");
+        harness.run();
+        let Some((built, Ok(_))) = &harness.app.lattice else {
+            panic!("no lattice for y² = x³ + x");
+        };
+        assert_eq!(*built, Curve::new(1, 0, 11));
+        assert!(harness.app.lattice_highlight.is_empty());
+    }
+
+    #[test]
+    fn minimap_appears_when_zoomed_and_recentres_the_view() {
+        let mut harness = Harness::new();
+        let p = harness.app.view.curve.p;
+        let circles = |harness: &Harness| {
+            harness
+                .snapshot()
+                .lines()
+                .find_map(|line| line.strip_prefix("circles: ")?.parse::<usize>().ok())
+                .unwrap()
+        };
+        let full = circles(&harness);
+        assert!(!harness.snapshot().contains("Reset zoom"));
+
+        harness.app.view.viewport.zoom(4.0, (0.0, 0.0), p);
+        harness.run();
+        assert!(harness.snapshot().contains("Zoom 4.0×"));
+        assert!(circles(&harness) < full);
+
+        // 点小地图的中心，可见区域移到曲线中间
+        let (_, minimap) = harness
+            .nodes
+            .iter()
+            .find(|(id, _)| *id == harness.find("Minimap"))
+            .unwrap();
+        let bounds = minimap.bounds().unwrap();
+        let center = egui::pos2(
+            ((bounds.x0 + bounds.x1) / 2.0) as f32,
+            ((bounds.y0 + bounds.y1) / 2.0) as f32,
+        );
+        for pressed in [true, false] {
+            harness.events.push(egui::Event::PointerMoved(center));
+            harness.events.push(egui::Event::PointerButton {
+                pos: center,
+                button: egui::PointerButton::Primary,
+                pressed,
+                modifiers: egui::Modifiers::NONE,
+            });
+            harness.step();
+        }
+        let viewport = harness.app.view.viewport;
+        let middle = viewport.x + viewport.span / 2.0;
+        assert!((middle - p as f32 / 2.0).abs() < 2.0, "{viewport:?}");
+
+        harness.click("Reset zoom");
+        assert_eq!(harness.app.view.viewport, Viewport::full(p));
+        assert_eq!(circles(&harness), full);
+    }
+
+    #[test]
+    fn clicking_two_points_shows_their_sum() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        let (_, plot) = harness
+            .nodes
+            .iter()
+            .find(|(id, _)| *id == harness.find("Plot"))
+            .unwrap();
+        let bounds = plot.bounds().unwrap();
+        let step = (bounds.x1 - bounds.x0) / 97.0;
+        let click = |harness: &mut Harness, (x, y): (i64, i64)| {
+            let pos = egui::pos2(
+                (bounds.x0 + x as f64 * step) as f32,
+                (bounds.y1 - y as f64 * step) as f32,
+            );
+            for pressed in [true, false] {
+                harness.events.push(egui::Event::PointerMoved(pos));
+                harness.events.push(egui::Event::PointerButton {
+                    pos,
+                    button: egui::PointerButton::Primary,
+                    pressed,
+                    modifiers: egui::Modifiers::NONE,
+                });
+                harness.step();
+            }
+            harness.run();
+        };
+
+        click(&mut harness, (3, 6));
+        assert_eq!(harness.app.view.selection, vec![(3, 6)]);
+        assert!(harness.snapshot().contains("P = (3, 6) of order 5"));
+        // 悬停的点旁边显示它的阶
+        let hover = egui::pos2(
+            (bounds.x0 + 3.0 * step) as f32 + 1.0,
+            (bounds.y1 - 6.0 * step) as f32,
+        );
+        harness.events.push(egui::Event::PointerMoved(hover));
+        harness.run();
+        assert!(harness.snapshot().contains("(3, 6), order 5"));
+        click(&mut harness, (10, 76));
+        assert_eq!(harness.app.view.selection, vec![(3, 6), (10, 76)]);
+        let (x, y) = harness
+            .app
+            .view
+            .curve
+            .add_coords(Some((3, 6)), Some((10, 76)))
+            .unwrap();
+        let snapshot = harness.snapshot();
+        assert!(
+            snapshot.contains(&format!("P + Q ({x}, {y})")),
+            "{snapshot}"
+        );
+        assert!(
+            snapshot.contains(&format!("P + Q = ({x}, {y})")),
+            "{snapshot}"
+        );
+
+        // 再点同一个点是切线，第三次点击重新开始
+        click(&mut harness, (3, 6));
+        click(&mut harness, (3, 6));
+        assert!(harness.snapshot().contains("P = Q (3, 6)"));
+        assert!(harness.snapshot().contains("tangent at P"));
+        harness.click("Clear selection");
+        assert!(harness.app.view.selection.is_empty());
+    }
+
+    #[test]
+    fn record_and_replay_session() {
+        let mut harness = Harness::new();
+        harness.click("Record");
+        harness.set_value("p:", 97);
+        harness.set_value("a:", 2);
+        harness.set_value("b:", 3);
+        harness.click("Apply");
+        harness.set_value("x:", 3);
+        harness.set_value("y:", 6);
+        harness.click("Add point");
+        harness.click("P, 2P, 4P, ..., 2^k P");
+        harness.click("Add random point");
+        harness.click("Stop recording");
+        let expected = harness.state();
+        assert_eq!(harness.app.view.tracked.len(), 3);
+
+        // 回放从录制开始时的状态出发，随机选的点也要一致
+        harness.click("Replay");
+        assert!(harness.app.player.is_none());
+        assert_eq!(harness.state(), expected);
+
+        let path = std::env::temp_dir().join(format!("session-{}.txt", std::process::id()));
+        harness.app.session_path = path.display().to_string();
+        harness.click("Export");
+        let exported = harness.app.session.take();
+        harness.step();
+        harness.click("Import");
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(harness.app.session, exported);
+    }
+
+    #[test]
+    fn run_example_script_from_editor() {
+        let mut harness = Harness::new();
+        harness.click("Script editor");
+        harness.click("Run");
+        let output = harness.app.script_output.clone().expect("script ran");
+        assert_eq!(output.error, None);
+        assert_eq!(output.lines.len(), 4);
+        assert_eq!(harness.app.view.curve, Curve::new(2, 3, 97));
+        let pinned = harness.app.view.tracked.last().expect("a point is pinned");
+        assert_eq!(pinned.base.coords(), Some((3, 6)));
+        assert_eq!(pinned.trajectory, Trajectory::Doublings);
+        assert!(harness.snapshot().contains("\"1 * P = (3, 6)\""));
+    }
+
+    #[test]
+    fn expression_console_keeps_variables_between_inputs() {
+        let mut harness = Harness::new();
+        harness.click("Expression console");
+        harness.app.repl_input = "P = (1, 188); Q = 3*P + P; order(Q)".to_string();
+        harness.click("Evaluate");
+        assert!(harness.app.repl_input.is_empty());
+        let curve = Curve::new(1, 1, 599);
+        let q = curve.point(1, 188) * 4;
+        let (_, output) = &harness.app.repl_history[0];
+        assert_eq!(output.error, None);
+        assert_eq!(output.lines, [curve.order_of(q.coords(), 597).to_string()]);
+
+        harness.app.repl_input = "Q - P; (1, 1)".to_string();
+        harness.click("Evaluate");
+        let (_, output) = &harness.app.repl_history[1];
+        assert_eq!(output.lines, [(curve.point(1, 188) * 3).to_string()]);
+        assert_eq!(output.error.as_deref(), Some("(1, 1) is not on the curve"));
+        let snapshot = harness.snapshot();
+        assert!(snapshot.contains("\"> Q - P; (1, 1)\""));
+        assert!(snapshot.contains("\"(1, 1) is not on the curve\""));
+
+        // curve() 和脚本一样换掉界面上的曲线
+        harness.app.repl_input = "curve(2, 3, 97); P = (3, 6); 5 * P".to_string();
+        harness.click("Evaluate");
+        assert_eq!(harness.app.view.curve, Curve::new(2, 3, 97));
+        assert_eq!(harness.app.repl_history[2].1.lines, ["Infinity"]);
+    }
+
+    #[test]
+    fn views_in_new_windows_are_independent() {
+        let mut harness = Harness::new();
+        harness.click("Open view in new window");
+        assert_eq!(harness.app.windows.len(), 1);
+        assert_eq!(harness.app.windows[0].view.curve, harness.app.view.curve);
+        // 测试里没有原生窗口，视图嵌入在主窗口中
+        assert!(harness.snapshot().contains("\"View 1\""));
+
+        harness
+            .app
+            .perform(crate::recording::Action::EditParams { a: 2, b: 3, p: 97 });
+        harness.app.perform(crate::recording::Action::Apply);
+        harness.run();
+        assert_eq!(harness.app.view.curve, Curve::new(2, 3, 97));
+        assert_eq!(harness.app.windows[0].view.curve, Curve::new(1, 1, 599));
+    }
+
+    #[test]
+    fn explore_singular_curve() {
+        let mut harness = Harness::new();
+        harness.set_value("p:", 23);
+        harness.set_value("a:", 0);
+        harness.set_value("b:", 0);
+        assert!(
+            harness
+                .snapshot()
+                .contains("Warning: 4a³ + 27b² ≡ 0 (mod p) for these parameters")
+        );
+        harness.click("Apply");
+        assert_eq!(harness.app.pending_singular, Some(Curve::new(0, 0, 23)));
+        harness.click("Explore singular curve");
+        assert!(harness.app.view.curve.is_singular());
+        harness.assert_snapshot("explore_singular_curve");
+    }
+}
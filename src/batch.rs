@@ -0,0 +1,138 @@
+// 批量验证签名。每个签名的验证等式都能写成 Σ k_j·P_j = O，给每个等式乘一个随机权重再相加，
+// 整批只做一次多标量乘法，相同的点（G、重复的公钥）合并成一项。
+// 系数按 q 约简，所以等式里的点必须都在 q 阶子群里，构造等式时检查。
+// 有无效签名时加权和仍为 O 的概率约为 1/q，玩具群上并不算低。
+use crate::curve::Coords;
+use crate::scalar::Scalar;
+use crate::subgroup::PrimeGroup;
+use rand_core::RngCore;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equation {
+    pub terms: Vec<(Coords, Scalar)>,
+}
+
+impl Equation {
+    // 单独验证这一个等式
+    pub fn holds(&self, group: &PrimeGroup) -> bool {
+        let terms: Vec<(Coords, u64)> = self.terms.iter().map(|&(p, k)| (p, k.value())).collect();
+        group.curve.msm(&terms).is_none()
+    }
+}
+
+// 整批一起验证，格式不对（None）的签名直接算作无效
+pub fn verify<R: RngCore + ?Sized>(
+    group: &PrimeGroup,
+    equations: &[Option<Equation>],
+    rng: &mut R,
+) -> bool {
+    let mut merged: HashMap<Coords, Scalar> = HashMap::new();
+    for equation in equations {
+        let Some(equation) = equation else {
+            return false;
+        };
+        let weight = Scalar::random(rng, group.q);
+        for &(point, k) in &equation.terms {
+            *merged.entry(point).or_insert(Scalar::zero(group.q)) += weight * k;
+        }
+    }
+    let terms: Vec<(Coords, u64)> = merged.into_iter().map(|(p, k)| (p, k.value())).collect();
+    tracing::trace!(
+        signatures = equations.len(),
+        terms = terms.len(),
+        "batch verification"
+    );
+    group.curve.msm(&terms).is_none()
+}
+
+// 整批不通过时二分，返回无效签名的下标。每一半都重新抽权重
+pub fn find_invalid<R: RngCore + ?Sized>(
+    group: &PrimeGroup,
+    equations: &[Option<Equation>],
+    rng: &mut R,
+) -> Vec<usize> {
+    let _span = tracing::debug_span!("find_invalid", n = equations.len()).entered();
+    let mut invalid = vec![];
+    bisect(group, equations, 0, rng, &mut invalid);
+    tracing::debug!(?invalid, "bisection finished");
+    invalid
+}
+
+fn bisect<R: RngCore + ?Sized>(
+    group: &PrimeGroup,
+    equations: &[Option<Equation>],
+    offset: usize,
+    rng: &mut R,
+    invalid: &mut Vec<usize>,
+) {
+    // 只剩一个时直接验证，没有 1/q 的误判
+    if let [equation] = equations {
+        if !equation.as_ref().is_some_and(|e| e.holds(group)) {
+            invalid.push(offset);
+        }
+        return;
+    }
+    if equations.is_empty() || verify(group, equations, rng) {
+        return;
+    }
+    let (left, right) = equations.split_at(equations.len() / 2);
+    bisect(group, left, offset, rng, invalid);
+    bisect(group, right, offset + left.len(), rng, invalid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+    use crate::ecdsa::{self, SigningKey};
+    use crate::rng;
+    use crate::schnorr::{self, KeyPair};
+
+    // 余因子为 1，q = 1013
+    fn group() -> PrimeGroup {
+        PrimeGroup::new(&Curve::new(1, 14, 1009)).unwrap()
+    }
+
+    fn signatures(group: &PrimeGroup, n: usize) -> Vec<Option<Equation>> {
+        let mut rng = rng::seeded(12);
+        let schnorr_key = KeyPair::generate(group, &mut rng);
+        let ecdsa_key = SigningKey::generate(group, &mut rng);
+        (0..n)
+            .map(|i| {
+                let message = format!("message {i}");
+                if i % 2 == 0 {
+                    let signature = schnorr_key.sign(group, message.as_bytes(), &mut rng);
+                    schnorr::equation(group, schnorr_key.public, message.as_bytes(), &signature)
+                } else {
+                    let signature = ecdsa_key.sign_full(group, message.as_bytes());
+                    ecdsa::equation(group, ecdsa_key.public, message.as_bytes(), &signature)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_valid_batch_passes_and_each_equation_holds() {
+        let group = group();
+        assert_eq!(group.cofactor, 1);
+        let equations = signatures(&group, 20);
+        assert!(equations.iter().flatten().all(|e| e.holds(&group)));
+        let mut rng = rng::seeded(3);
+        assert!(verify(&group, &equations, &mut rng));
+        assert!(find_invalid(&group, &equations, &mut rng).is_empty());
+    }
+
+    #[test]
+    fn bisection_finds_the_bad_signatures() {
+        let group = group();
+        let mut equations = signatures(&group, 20);
+        // 改一个 Schnorr 签名的 s，再放进一个格式不对的签名
+        let bad = equations[6].as_mut().unwrap();
+        bad.terms[0].1 += Scalar::one(group.q);
+        equations[13] = None;
+        let mut rng = rng::seeded(3);
+        assert!(!verify(&group, &equations, &mut rng));
+        assert_eq!(find_invalid(&group, &equations, &mut rng), vec![6, 13]);
+    }
+}
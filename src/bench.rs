@@ -0,0 +1,202 @@
+// `bench` 子命令：在几个不同大小的素数上给有限域和点运算计时，比较同一件事的不同做法。
+// 每种做法的结果先互相核对，不一致时报错而不是给出没有意义的时间。
+use crate::curve::{Coords, Curve};
+use crate::field;
+use crate::jacobian::JacobianPoint;
+use crate::rng;
+use rand_core::RngCore;
+use std::time::{Duration, Instant};
+
+const SEED: u64 = 0xbe4c;
+
+// 8、16、31、61 位的素数；2^61 − 1 时 Jacobian 公式里的 3X² 之类仍然不会溢出 i64
+pub const PRIMES: [i64; 4] = [251, 65_521, 2_147_483_647, 2_305_843_009_213_693_951];
+
+// 一个素数上每种运算的平均耗时
+#[derive(Debug, Clone)]
+pub struct BenchRow {
+    pub p: i64,
+    pub inversion: Duration,
+    pub affine_add: Duration,
+    pub jacobian_add: Duration,
+    pub double_and_add: Duration,
+    pub ladder: Duration,
+    pub wnaf: Duration,
+}
+
+impl BenchRow {
+    pub fn bits(&self) -> u32 {
+        i64::BITS - self.p.leading_zeros()
+    }
+}
+
+// 曲线 y² = x³ + ax + b 放到每个素数上，每种运算做 rounds 次
+pub fn run(a: i64, b: i64, rounds: usize) -> Result<Vec<BenchRow>, String> {
+    PRIMES
+        .iter()
+        .map(|&p| {
+            let curve = Curve::try_new(a, b, p).map_err(|error| format!("p = {p}: {error}"))?;
+            measure(&curve, rounds)
+        })
+        .collect()
+}
+
+fn measure(curve: &Curve, rounds: usize) -> Result<BenchRow, String> {
+    let p = curve.p;
+    let mut rng = rng::seeded(SEED);
+    let points: Vec<Coords> = (0..=rounds)
+        .map(|_| curve.random_point(&mut rng).map(|point| point.coords()))
+        .collect::<Option<_>>()
+        .ok_or_else(|| {
+            format!(
+                "y² = x³ + {}x + {} (mod {p}) has no points",
+                curve.a, curve.b
+            )
+        })?;
+    let scalars: Vec<u64> = (0..rounds).map(|_| rng.next_u64()).collect();
+    let timed = |run: &mut dyn FnMut(usize)| {
+        let start = Instant::now();
+        for i in 0..rounds {
+            run(i);
+        }
+        start.elapsed() / rounds.max(1) as u32
+    };
+
+    let mut inverses = Vec::with_capacity(rounds);
+    let inversion = timed(&mut |i| {
+        inverses.push(field::mod_inverse(
+            points[i].map_or(1, |(x, _)| x.max(1)),
+            p,
+        ))
+    });
+    for (i, inverse) in inverses.iter().enumerate() {
+        let x = points[i].map_or(1, |(x, _)| x.max(1));
+        if field::mul(x, *inverse, p) != 1 {
+            return Err(format!("1/{x} mod {p} = {inverse} is wrong"));
+        }
+    }
+
+    let mut affine = Vec::with_capacity(rounds);
+    let affine_add = timed(&mut |i| affine.push(curve.add_coords(points[i], points[i + 1])));
+    let jacobians: Vec<JacobianPoint> = points
+        .iter()
+        .map(|&coords| JacobianPoint::from_coords(curve, coords))
+        .collect();
+    let mut sums = Vec::with_capacity(rounds);
+    let jacobian_add = timed(&mut |i| sums.push(jacobians[i].add(jacobians[i + 1], curve)));
+    if JacobianPoint::batch_to_coords(&sums, curve) != affine {
+        return Err(format!("affine and Jacobian addition disagree modulo {p}"));
+    }
+
+    let mut products = [const { Vec::new() }; 3];
+    let mut multiply = |index: usize, mul: fn(JacobianPoint, u64, &Curve) -> JacobianPoint| {
+        let results = &mut products[index];
+        timed(&mut |i| results.push(mul(jacobians[i], scalars[i], curve)))
+    };
+    let double_and_add = multiply(0, JacobianPoint::mul);
+    let ladder = multiply(1, JacobianPoint::ladder);
+    let wnaf = multiply(2, JacobianPoint::wnaf);
+    let [plain, laddered, windowed] =
+        products.map(|results| JacobianPoint::batch_to_coords(&results, curve));
+    if laddered != plain || windowed != plain {
+        return Err(format!(
+            "double-and-add, ladder and wNAF disagree modulo {p}"
+        ));
+    }
+
+    Ok(BenchRow {
+        p,
+        inversion,
+        affine_add,
+        jacobian_add,
+        double_and_add,
+        ladder,
+        wnaf,
+    })
+}
+
+// 整条曲线的点枚举：平方根表、逐个 x 的 Tonelli–Shanks，以及逐个试 y 的 O(p²) 做法
+pub const ENUMERATION_PRIMES: [i64; 2] = [100_003, 1_000_003];
+// 逐个试 y 太慢，只抽几列，总共做这么多次乘法，再按列数折算整条曲线
+const TRIAL_WORK: i64 = 20_000_000;
+
+#[derive(Debug, Clone)]
+pub struct EnumerationRow {
+    pub p: i64,
+    pub group_order: u64,
+    pub table: Duration,
+    pub tonelli: Duration,
+    // 估计值
+    pub trial: Duration,
+}
+
+pub fn enumeration(a: i64, b: i64, primes: &[i64]) -> Result<Vec<EnumerationRow>, String> {
+    primes
+        .iter()
+        .map(|&p| {
+            let curve = Curve::try_new(a, b, p).map_err(|error| format!("p = {p}: {error}"))?;
+            enumerate(&curve)
+        })
+        .collect()
+}
+
+fn enumerate(curve: &Curve) -> Result<EnumerationRow, String> {
+    let p = curve.p;
+    let start = Instant::now();
+    let points = curve.points();
+    let table = start.elapsed();
+
+    let start = Instant::now();
+    let tonelli: Vec<_> = (0..p).flat_map(|x| curve.points_at(x)).collect();
+    let tonelli_time = start.elapsed();
+    if tonelli != points {
+        return Err(format!(
+            "p = {p}: the square-root table and Tonelli–Shanks disagree"
+        ));
+    }
+
+    let roots = field::SqrtTable::new(p);
+    let columns = (TRIAL_WORK / p).clamp(1, p);
+    let start = Instant::now();
+    for x in (0..columns).map(|i| i * (p / columns)) {
+        let rhs = curve.rhs(x);
+        let trial: Vec<i64> = (0..p).filter(|&y| field::mul(y, y, p) == rhs).collect();
+        if roots.roots(rhs).is_some_and(|ys| ys != trial) {
+            return Err(format!("p = {p}: square roots of {rhs} differ at x = {x}"));
+        }
+    }
+    let trial = start.elapsed().mul_f64(p as f64 / columns as f64);
+
+    Ok(EnumerationRow {
+        p,
+        group_order: points.len() as u64 + 1,
+        table,
+        tonelli: tonelli_time,
+        trial,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_prime_gets_a_row() {
+        let rows = run(1, 1, 8).unwrap();
+        let bits: Vec<u32> = rows.iter().map(BenchRow::bits).collect();
+        assert_eq!(bits, [8, 16, 31, 61]);
+        // y² = x³ − 3x + 2 = (x − 1)²(x + 2) 在每个素数上都奇异
+        assert_eq!(
+            run(-3, 2, 8).unwrap_err(),
+            "p = 251: curve is singular (4a^3 + 27b^2 = 0 mod p)"
+        );
+    }
+
+    #[test]
+    fn enumeration_strategies_agree() {
+        let rows = enumeration(2, 3, &[97, 1009]).unwrap();
+        let orders: Vec<u64> = rows.iter().map(|row| row.group_order).collect();
+        assert_eq!(orders[0], 100);
+        assert_eq!(orders[1], Curve::new(2, 3, 1009).group_order());
+    }
+}
@@ -0,0 +1,639 @@
+// 任意素数 p < 2^256 上的曲线 y² = x³ + ax + b。Curve 用 i64，装不下 P-256 之类的真实曲线；
+// secp256k1.rs 只针对一个固定的素数做了专门优化。这里的域元素用 Montgomery 形式 aR mod p
+// （R = 2^256），乘法每轮消掉最低的一个肢，不需要除法；点用 Jacobian 坐标，加法和倍点不求逆，
+// 只在取仿射坐标时用费马小定理求一次逆。
+#[cfg(feature = "ct")]
+use crate::ct::{Choice, ConditionallySelectable};
+use crate::field;
+use crate::u256::{
+    Limbs, add_mod, at_least, bit, is_zero, mul_wide, reduce_generic, reduce_once, select,
+    sub_limbs, sub_mod,
+};
+
+// Miller–Rabin 的底：对 3.3·10^24 以下的数是确定的，更大的数出错的概率不超过 4^−12
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// 域元素，只能和产生它的域一起用：PrimeField 里是 Montgomery 形式，PlainField 里是余数本身
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elem(Limbs);
+
+// 域运算的后端。两种表示的加减法一样，只有进出表示和乘法不同；
+// BigCurve 对后端泛型，诊断里用同一条曲线比较两者做标量乘法的耗时
+pub trait FieldBackend: Copy {
+    // p 必须是大于 3 的奇数；是否为素数由调用方负责（见 is_probable_prime）
+    fn new(p: &Limbs) -> Self;
+
+    fn modulus(&self) -> Limbs;
+
+    fn elem(&self, value: &Limbs) -> Elem;
+
+    fn value(&self, e: Elem) -> Limbs;
+
+    fn one(&self) -> Elem;
+
+    fn mul(&self, a: Elem, b: Elem) -> Elem;
+
+    fn zero(&self) -> Elem {
+        Elem([0; 4])
+    }
+
+    fn add(&self, a: Elem, b: Elem) -> Elem {
+        Elem(add_mod(&a.0, &b.0, &self.modulus()))
+    }
+
+    fn sub(&self, a: Elem, b: Elem) -> Elem {
+        Elem(sub_mod(&a.0, &b.0, &self.modulus()))
+    }
+
+    fn neg(&self, a: Elem) -> Elem {
+        self.sub(self.zero(), a)
+    }
+
+    fn square(&self, a: Elem) -> Elem {
+        self.mul(a, a)
+    }
+
+    // 从高位到低位平方-乘。开启 `ct` feature 时每一位都乘，再按位选结果，
+    // ECDSA 里对 nonce 求逆走的就是这里
+    fn pow(&self, base: Elem, exponent: &Limbs) -> Elem {
+        let mut acc = self.one();
+        for i in (0..256).rev() {
+            acc = self.square(acc);
+            if cfg!(feature = "ct") {
+                acc = Elem(select(&acc.0, &self.mul(acc, base).0, bit(exponent, i)));
+            } else if bit(exponent, i) {
+                acc = self.mul(acc, base);
+            }
+        }
+        acc
+    }
+
+    // a^(p−2)，a = 0 时返回 0
+    fn invert(&self, a: Elem) -> Elem {
+        let exponent = sub_limbs(&self.modulus(), &[2, 0, 0, 0]).0;
+        self.pow(a, &exponent)
+    }
+
+    fn is_zero(&self, a: Elem) -> bool {
+        is_zero(&a.0)
+    }
+}
+
+// Montgomery 形式 aR mod p（R = 2^256），乘法用 REDC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrimeField {
+    p: Limbs,
+    // −p⁻¹ mod 2^64
+    inv: u64,
+    // R mod p 是 1 的 Montgomery 形式；R² mod p 把普通整数转进 Montgomery 形式
+    r: Limbs,
+    r2: Limbs,
+}
+
+impl PrimeField {
+    // a·b·R⁻¹ mod p（CIOS）；p < 2^256，中间结果多留一个肢放进位
+    fn montgomery(&self, a: &Limbs, b: &Limbs) -> Limbs {
+        let mut t = [0u64; 6];
+        for &bi in b {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let s = t[j] as u128 + a[j] as u128 * bi as u128 + carry;
+                t[j] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[4] as u128 + carry;
+            t[4] = s as u64;
+            t[5] = (s >> 64) as u64;
+
+            // 加上 m·p 让最低的肢变成 0，再整体右移一个肢
+            let m = t[0].wrapping_mul(self.inv);
+            let mut carry = (t[0] as u128 + m as u128 * self.p[0] as u128) >> 64;
+            for j in 1..4 {
+                let s = t[j] as u128 + m as u128 * self.p[j] as u128 + carry;
+                t[j - 1] = s as u64;
+                carry = s >> 64;
+            }
+            let s = t[4] as u128 + carry;
+            t[3] = s as u64;
+            t[4] = t[5] + (s >> 64) as u64;
+        }
+        reduce_once(&[t[0], t[1], t[2], t[3]], t[4] != 0, &self.p)
+    }
+}
+
+impl FieldBackend for PrimeField {
+    fn new(p: &Limbs) -> Self {
+        // 牛顿迭代，每轮正确的位数翻倍：1 → 2 → ... → 64
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(p[0].wrapping_mul(inverse)));
+        }
+        let r = reduce_generic(&[0, 0, 0, 0, 1, 0, 0, 0], p);
+        PrimeField {
+            p: *p,
+            inv: inverse.wrapping_neg(),
+            r,
+            r2: reduce_generic(&mul_wide(&r, &r), p),
+        }
+    }
+
+    fn modulus(&self) -> Limbs {
+        self.p
+    }
+
+    fn elem(&self, value: &Limbs) -> Elem {
+        // 先约化到 [0, p)，再乘 R²·R⁻¹ = R
+        let reduced = reduce_generic(
+            &[value[0], value[1], value[2], value[3], 0, 0, 0, 0],
+            &self.p,
+        );
+        Elem(self.montgomery(&reduced, &self.r2))
+    }
+
+    fn value(&self, e: Elem) -> Limbs {
+        self.montgomery(&e.0, &[1, 0, 0, 0])
+    }
+
+    fn one(&self) -> Elem {
+        Elem(self.r)
+    }
+
+    fn mul(&self, a: Elem, b: Elem) -> Elem {
+        Elem(self.montgomery(&a.0, &b.0))
+    }
+}
+
+// 直接存余数，乘完把 512 位的积逐位约化，不依赖 p 的任何预计算，用来和 PrimeField 对照
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlainField {
+    p: Limbs,
+}
+
+impl FieldBackend for PlainField {
+    fn new(p: &Limbs) -> Self {
+        PlainField { p: *p }
+    }
+
+    fn modulus(&self) -> Limbs {
+        self.p
+    }
+
+    fn elem(&self, value: &Limbs) -> Elem {
+        Elem(reduce_generic(
+            &[value[0], value[1], value[2], value[3], 0, 0, 0, 0],
+            &self.p,
+        ))
+    }
+
+    fn value(&self, e: Elem) -> Limbs {
+        e.0
+    }
+
+    fn one(&self) -> Elem {
+        Elem([1, 0, 0, 0])
+    }
+
+    fn mul(&self, a: Elem, b: Elem) -> Elem {
+        Elem(reduce_generic(&mul_wide(&a.0, &b.0), &self.p))
+    }
+}
+
+pub fn is_probable_prime(n: &Limbs) -> bool {
+    if n[1..].iter().all(|&limb| limb == 0) && n[0] <= i64::MAX as u64 {
+        return field::is_prime(n[0] as i64);
+    }
+    if n[0] & 1 == 0 {
+        return false;
+    }
+    // n − 1 = d·2^s
+    let minus_one = sub_limbs(n, &[1, 0, 0, 0]).0;
+    let s = (0..256).find(|&i| bit(&minus_one, i)).expect("n > 1");
+    let mut d = minus_one;
+    for _ in 0..s {
+        let mut carry = 0;
+        for limb in d.iter_mut().rev() {
+            let next = *limb & 1;
+            *limb = (*limb >> 1) | (carry << 63);
+            carry = next;
+        }
+    }
+    let field = PrimeField::new(n);
+    let (one, minus_one) = (field.one(), field.neg(field.one()));
+    WITNESSES.iter().all(|&witness| {
+        let mut x = field.pow(field.elem(&[witness, 0, 0, 0]), &d);
+        if x == one || x == minus_one {
+            return true;
+        }
+        (1..s).any(|_| {
+            x = field.square(x);
+            x == minus_one
+        })
+    })
+}
+
+// Jacobian 坐标 (X : Y : Z)，x = X/Z²，y = Y/Z³；Z = 0 是无穷远点
+#[derive(Debug, Clone, Copy)]
+pub struct BigPoint {
+    x: Elem,
+    y: Elem,
+    z: Elem,
+}
+
+// 齐次射影坐标 (X : Y : Z)，x = X/Z，y = Y/Z；只在常量时间的 ladder 里用，
+// 完全加法公式是在这种坐标下给出的
+#[cfg(feature = "ct")]
+#[derive(Debug, Clone, Copy)]
+struct Projective {
+    x: Elem,
+    y: Elem,
+    z: Elem,
+}
+
+#[cfg(feature = "ct")]
+impl ConditionallySelectable for Elem {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Elem(Limbs::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+#[cfg(feature = "ct")]
+impl ConditionallySelectable for Projective {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Projective {
+            x: Elem::conditional_select(&a.x, &b.x, choice),
+            y: Elem::conditional_select(&a.y, &b.y, choice),
+            z: Elem::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BigCurve<F = PrimeField> {
+    pub field: F,
+    a: Elem,
+    b: Elem,
+}
+
+impl BigCurve {
+    pub fn new(a: &Limbs, b: &Limbs, p: &Limbs) -> Result<Self, String> {
+        Self::with_backend(a, b, p)
+    }
+}
+
+impl<F: FieldBackend> BigCurve<F> {
+    pub fn with_backend(a: &Limbs, b: &Limbs, p: &Limbs) -> Result<Self, String> {
+        let _span = tracing::debug_span!("big_curve").entered();
+        if !at_least(p, &[5, 0, 0, 0]) || !is_probable_prime(p) {
+            return Err("p must be a prime greater than 3".to_string());
+        }
+        let field = F::new(p);
+        let (a, b) = (field.elem(a), field.elem(b));
+        // 4a³ + 27b² ≠ 0
+        let four = field.elem(&[4, 0, 0, 0]);
+        let twenty_seven = field.elem(&[27, 0, 0, 0]);
+        let discriminant = field.add(
+            field.mul(four, field.mul(a, field.square(a))),
+            field.mul(twenty_seven, field.square(b)),
+        );
+        if field.is_zero(discriminant) {
+            return Err("the curve is singular".to_string());
+        }
+        Ok(BigCurve { field, a, b })
+    }
+
+    pub fn identity(&self) -> BigPoint {
+        let f = &self.field;
+        BigPoint {
+            x: f.one(),
+            y: f.one(),
+            z: f.zero(),
+        }
+    }
+
+    pub fn contains(&self, x: &Limbs, y: &Limbs) -> bool {
+        let f = &self.field;
+        let (x, y) = (f.elem(x), f.elem(y));
+        let rhs = f.add(f.mul(f.add(f.square(x), self.a), x), self.b);
+        f.square(y) == rhs
+    }
+
+    pub fn point(&self, x: &Limbs, y: &Limbs) -> Result<BigPoint, String> {
+        if !self.contains(x, y) {
+            return Err("the point is not on the curve".to_string());
+        }
+        let f = &self.field;
+        Ok(BigPoint {
+            x: f.elem(x),
+            y: f.elem(y),
+            z: f.one(),
+        })
+    }
+
+    pub fn is_identity(&self, point: &BigPoint) -> bool {
+        self.field.is_zero(point.z)
+    }
+
+    // 仿射坐标，无穷远点返回 None
+    pub fn affine(&self, point: &BigPoint) -> Option<(Limbs, Limbs)> {
+        if self.is_identity(point) {
+            return None;
+        }
+        let f = &self.field;
+        let z_inv = f.invert(point.z);
+        let z_inv2 = f.square(z_inv);
+        let x = f.mul(point.x, z_inv2);
+        let y = f.mul(point.y, f.mul(z_inv2, z_inv));
+        Some((f.value(x), f.value(y)))
+    }
+
+    pub fn neg(&self, point: &BigPoint) -> BigPoint {
+        BigPoint {
+            y: self.field.neg(point.y),
+            ..*point
+        }
+    }
+
+    // dbl-2007-bl，适用于任意 a
+    pub fn double(&self, point: &BigPoint) -> BigPoint {
+        let f = &self.field;
+        if self.is_identity(point) || f.is_zero(point.y) {
+            return self.identity();
+        }
+        let xx = f.square(point.x);
+        let yy = f.square(point.y);
+        let yyyy = f.square(yy);
+        let zz = f.square(point.z);
+        let t = f.sub(f.sub(f.square(f.add(point.x, yy)), xx), yyyy);
+        let s = f.add(t, t);
+        let m = f.add(f.add(f.add(xx, xx), xx), f.mul(self.a, f.square(zz)));
+        let x = f.sub(f.square(m), f.add(s, s));
+        let eight_yyyy = f.add(yyyy, yyyy);
+        let eight_yyyy = f.add(eight_yyyy, eight_yyyy);
+        let eight_yyyy = f.add(eight_yyyy, eight_yyyy);
+        let y = f.sub(f.mul(m, f.sub(s, x)), eight_yyyy);
+        let z = f.sub(f.sub(f.square(f.add(point.y, point.z)), yy), zz);
+        BigPoint { x, y, z }
+    }
+
+    // add-2007-bl；两点相同时转去倍点，互为相反数时得到无穷远点
+    pub fn add(&self, lhs: &BigPoint, rhs: &BigPoint) -> BigPoint {
+        if self.is_identity(lhs) {
+            return *rhs;
+        }
+        if self.is_identity(rhs) {
+            return *lhs;
+        }
+        let f = &self.field;
+        let z1z1 = f.square(lhs.z);
+        let z2z2 = f.square(rhs.z);
+        let u1 = f.mul(lhs.x, z2z2);
+        let u2 = f.mul(rhs.x, z1z1);
+        let s1 = f.mul(lhs.y, f.mul(rhs.z, z2z2));
+        let s2 = f.mul(rhs.y, f.mul(lhs.z, z1z1));
+        let h = f.sub(u2, u1);
+        let r = f.sub(s2, s1);
+        if f.is_zero(h) {
+            return if f.is_zero(r) {
+                self.double(lhs)
+            } else {
+                self.identity()
+            };
+        }
+        let i = f.square(f.add(h, h));
+        let j = f.mul(h, i);
+        let r = f.add(r, r);
+        let v = f.mul(u1, i);
+        let x = f.sub(f.sub(f.square(r), j), f.add(v, v));
+        let s1j = f.mul(s1, j);
+        let y = f.sub(f.mul(r, f.sub(v, x)), f.add(s1j, s1j));
+        let z = f.mul(f.sub(f.sub(f.square(f.add(lhs.z, rhs.z)), z1z1), z2z2), h);
+        BigPoint { x, y, z }
+    }
+
+    // 开启 `ct` feature 时走固定 256 轮的 Montgomery ladder：每轮一次完全加法、一次倍点，
+    // 按 k 的位做条件交换。ladder 里始终有 R1 − R0 = P，公式只在 P 为 2 阶点时有例外，
+    // 这时 kP 只取决于 k 的奇偶；基点是公开的，可以直接判断
+    #[cfg(feature = "ct")]
+    pub fn mul(&self, point: &BigPoint, k: &Limbs) -> BigPoint {
+        let f = &self.field;
+        let identity = Projective {
+            x: f.zero(),
+            y: f.one(),
+            z: f.zero(),
+        };
+        // (X : Y : Z) 的 Jacobian 坐标对应齐次坐标 (X·Z : Y : Z³)
+        let base = Projective {
+            x: f.mul(point.x, point.z),
+            y: point.y,
+            z: f.mul(point.z, f.square(point.z)),
+        };
+        let (mut r0, mut r1) = (identity, base);
+        for i in (0..256).rev() {
+            let choice = Choice::from(u8::from(bit(k, i)));
+            Projective::conditional_swap(&mut r0, &mut r1, choice);
+            r1 = self.complete_add(&r0, &r1);
+            r0 = self.complete_add(&r0, &r0);
+            Projective::conditional_swap(&mut r0, &mut r1, choice);
+        }
+        if !self.is_identity(point) && f.is_zero(point.y) {
+            let odd = Choice::from(u8::from(bit(k, 0)));
+            r0 = Projective::conditional_select(&identity, &base, odd);
+        }
+        // 齐次坐标 (X : Y : Z) 换回 Jacobian 坐标 (X·Z : Y·Z² : Z)
+        BigPoint {
+            x: f.mul(r0.x, r0.z),
+            y: f.mul(r0.y, f.square(r0.z)),
+            z: r0.z,
+        }
+    }
+
+    // Renes–Costello–Batina 完全加法公式 (Algorithm 1)，和 ct.rs 的 i64 版本一样
+    #[cfg(feature = "ct")]
+    fn complete_add(&self, lhs: &Projective, rhs: &Projective) -> Projective {
+        let f = &self.field;
+        let a = self.a;
+        let b3 = f.add(f.add(self.b, self.b), self.b);
+        let (x1, y1, z1) = (lhs.x, lhs.y, lhs.z);
+        let (x2, y2, z2) = (rhs.x, rhs.y, rhs.z);
+        let mul = |u, v| f.mul(u, v);
+        let add = |u, v| f.add(u, v);
+        let sub = |u, v| f.sub(u, v);
+
+        let t0 = mul(x1, x2);
+        let t1 = mul(y1, y2);
+        let t2 = mul(z1, z2);
+        let t3 = mul(add(x1, y1), add(x2, y2));
+        let t3 = sub(t3, add(t0, t1));
+        let t4 = mul(add(x1, z1), add(x2, z2));
+        let t4 = sub(t4, add(t0, t2));
+        let t5 = mul(add(y1, z1), add(y2, z2));
+        let t5 = sub(t5, add(t1, t2));
+        let z3 = add(mul(b3, t2), mul(a, t4));
+        let x3 = sub(t1, z3);
+        let z3 = add(t1, z3);
+        let y3 = mul(x3, z3);
+        let t1 = add(add(t0, t0), t0);
+        let t2 = mul(a, t2);
+        let t4 = mul(b3, t4);
+        let t1 = add(t1, t2);
+        let t2 = mul(a, sub(t0, t2));
+        let t4 = add(t4, t2);
+        let y3 = add(y3, mul(t1, t4));
+        let x3 = sub(mul(x3, t3), mul(t5, t4));
+        let z3 = add(mul(z3, t5), mul(t3, t1));
+        Projective {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    // 倍点-加法，从最高位开始。跳过的加法和 add、double 里的特殊情况都让耗时依赖 k
+    #[cfg(not(feature = "ct"))]
+    pub fn mul(&self, point: &BigPoint, k: &Limbs) -> BigPoint {
+        let mut acc = self.identity();
+        for i in (0..256).rev() {
+            acc = self.double(&acc);
+            if bit(k, i) {
+                acc = self.add(&acc, point);
+            }
+        }
+        acc
+    }
+
+    // 射影坐标下比较：X1·Z2² = X2·Z1²，Y1·Z2³ = Y2·Z1³
+    pub fn equal(&self, lhs: &BigPoint, rhs: &BigPoint) -> bool {
+        match (self.is_identity(lhs), self.is_identity(rhs)) {
+            (true, true) => true,
+            // 不求逆，只做乘法
+            (false, false) => {
+                let f = &self.field;
+                let (z1z1, z2z2) = (f.square(lhs.z), f.square(rhs.z));
+                f.mul(lhs.x, z2z2) == f.mul(rhs.x, z1z1)
+                    && f.mul(lhs.y, f.mul(z2z2, rhs.z)) == f.mul(rhs.y, f.mul(z1z1, lhs.z))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+    use crate::u256::{add_limbs, parse};
+
+    fn hex(text: &str) -> Limbs {
+        parse(&format!("0x{text}")).unwrap()
+    }
+
+    // NIST P-256
+    fn p256() -> (BigCurve, BigPoint, Limbs) {
+        let p = hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        let a = sub_limbs(&p, &[3, 0, 0, 0]).0;
+        let b = hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b");
+        let curve = BigCurve::new(&a, &b, &p).unwrap();
+        let g = curve
+            .point(
+                &hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+                &hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            )
+            .unwrap();
+        let n = hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551");
+        (curve, g, n)
+    }
+
+    #[test]
+    fn p256_known_multiples() {
+        let (curve, g, n) = p256();
+        for (k, x, y) in [
+            (
+                "2",
+                "7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978",
+                "07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1",
+            ),
+            (
+                "3",
+                "5ecbe4d1a6330a44c8f7ef951d4bf165e6c6b721efada985fb41661bc6e7fd6c",
+                "8734640c4998ff7e374b06ce1a64a2ecd82ab036384fb83d9a79b127a27d5032",
+            ),
+            (
+                "c51e4753afdec1e6b6c6a5b992f43f8dd0c7a8933072708b6522468b2ffb06fd",
+                "942c9f408ead9d82d34a1b9a6a827ebe3e2ddf782b448d23be1b6143988ccef4",
+                "8c9eaf6c0d14d992fc63bad3e2496be2eee61cb5b97f65f428ca94a5d0ee19a1",
+            ),
+        ] {
+            let point = curve.mul(&g, &hex(k));
+            assert_eq!(curve.affine(&point), Some((hex(x), hex(y))), "k = {k}");
+        }
+        assert!(curve.is_identity(&curve.mul(&g, &n)));
+        let minus_one = sub_limbs(&n, &[1, 0, 0, 0]).0;
+        assert!(curve.equal(&curve.mul(&g, &minus_one), &curve.neg(&g)));
+        assert!(curve.equal(&curve.add(&g, &g), &curve.double(&g)));
+    }
+
+    #[test]
+    fn backends_agree_on_p256() {
+        let (curve, g, n) = p256();
+        let (gx, gy) = curve.affine(&g).unwrap();
+        let p = curve.field.modulus();
+        let a = sub_limbs(&p, &[3, 0, 0, 0]).0;
+        let b = hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b");
+        let plain = BigCurve::<PlainField>::with_backend(&a, &b, &p).unwrap();
+        let plain_g = plain.point(&gx, &gy).unwrap();
+        let k = hex("c51e4753afdec1e6b6c6a5b992f43f8dd0c7a8933072708b6522468b2ffb06fd");
+        assert_eq!(
+            plain.affine(&plain.mul(&plain_g, &k)),
+            curve.affine(&curve.mul(&g, &k))
+        );
+        assert!(plain.is_identity(&plain.mul(&plain_g, &n)));
+        // 同一个值在两个后端里的表示不同，取出来一样
+        let (montgomery, residue) = (curve.field.elem(&k), plain.field.elem(&k));
+        assert_ne!(montgomery, residue);
+        assert_eq!(curve.field.value(montgomery), plain.field.value(residue));
+    }
+
+    // 小素数上和 i64 的 Curve 逐点对比，覆盖 a ≠ 0、倍点和相反数
+    #[test]
+    fn small_fields_match_the_i64_curve() {
+        let small = Curve::new(2, 3, 97);
+        let curve = BigCurve::new(&[2, 0, 0, 0], &[3, 0, 0, 0], &[97, 0, 0, 0]).unwrap();
+        let to_big = |coords: Option<(i64, i64)>| match coords {
+            Some((x, y)) => curve
+                .point(&[x as u64, 0, 0, 0], &[y as u64, 0, 0, 0])
+                .unwrap(),
+            None => curve.identity(),
+        };
+        let from_big =
+            |point: &BigPoint| curve.affine(point).map(|(x, y)| (x[0] as i64, y[0] as i64));
+        let g = Some((3, 6));
+        for point in small.points() {
+            let sum = small.add_coords(point.coords(), g);
+            assert_eq!(
+                from_big(&curve.add(&to_big(point.coords()), &to_big(g))),
+                sum
+            );
+            for k in [0, 1, 2, 5, 99] {
+                let product = curve.mul(&to_big(point.coords()), &[k, 0, 0, 0]);
+                assert_eq!(from_big(&product), small.mul_coords(point.coords(), k));
+            }
+        }
+        assert!(BigCurve::new(&[0, 0, 0, 0], &[0, 0, 0, 0], &[97, 0, 0, 0]).is_err());
+        assert!(BigCurve::new(&[1, 0, 0, 0], &[1, 0, 0, 0], &[91, 0, 0, 0]).is_err());
+        assert!(curve.point(&[3, 0, 0, 0], &[7, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn miller_rabin_on_large_numbers() {
+        let p256 = hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        assert!(is_probable_prime(&p256));
+        // 2^255 − 19 和 2^127 − 1 是素数，2^128 + 1 = 59649589127497217 · 5704689200685129054721
+        assert!(is_probable_prime(&hex(
+            "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"
+        )));
+        assert!(is_probable_prime(&[u64::MAX, i64::MAX as u64, 0, 0]));
+        assert!(!is_probable_prime(&[1, 0, 1, 0]));
+        assert!(!is_probable_prime(&add_limbs(&p256, &[2, 0, 0, 0]).0));
+        assert!(is_probable_prime(&[97, 0, 0, 0]) && !is_probable_prime(&[91, 0, 0, 0]));
+    }
+}
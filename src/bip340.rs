@@ -0,0 +1,322 @@
+// BIP-340 Schnorr 签名：secp256k1 上的 s = k + e·d，e = H(R.x ‖ P.x ‖ m) mod n。
+// 公钥和 R 都只写 32 字节的 x，y 约定是偶数：d·G 的 y 是奇数时改用 n − d，k 同理。
+// 哈希都带标签，H_tag(x) = SHA256(SHA256(tag) ‖ SHA256(tag) ‖ x)，不同用途的哈希不会互相冒充。
+// nonce 由私钥、公钥、消息和 32 字节的辅助随机数确定；验证算 R = s·G − e·P，
+// 要求 R 不是无穷远点、y 是偶数、x 等于签名里的 r。
+use crate::secp256k1::{Fe, Scalar, Secp256k1Point};
+use crate::sha256::{Sha256, sha256};
+use crate::zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub const CHALLENGE_TAG: &str = "BIP0340/challenge";
+
+pub fn tagged_hash(tag: &str, parts: &[&[u8]]) -> [u8; 32] {
+    let tag = sha256(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(&tag).update(&tag);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
+
+// e = H_challenge(r ‖ P ‖ m) mod n
+pub fn challenge(r: &[u8; 32], public: &[u8; 32], message: &[u8]) -> Scalar {
+    Scalar::from_be_bytes(&tagged_hash(CHALLENGE_TAG, &[r, public, message]))
+}
+
+// 仿射 x 坐标和 y 是否为偶数，无穷远点为 None
+fn x_only(point: &Secp256k1Point) -> Option<([u8; 32], bool)> {
+    point.affine().map(|(x, y)| (x.to_be_bytes(), y.is_even()))
+}
+
+pub struct SigningKey {
+    // 已经换成了 y 为偶数的那个私钥
+    secret: Scalar,
+    pub public: [u8; 32],
+}
+
+// 签名过程的中间值，界面上逐项显示
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signing {
+    // 原来的 d·G、k·G 的 y 是奇数，取了相反数
+    pub negated_key: bool,
+    pub negated_nonce: bool,
+    pub r: [u8; 32],
+    pub e: Scalar,
+    pub signature: [u8; 64],
+}
+
+impl Zeroize for SigningKey {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl Drop for SigningKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SigningKey {}
+
+impl SigningKey {
+    // 私钥是 [1, n) 里的大端整数
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let d = Scalar::from_canonical_bytes(bytes).filter(|d| !d.is_zero())?;
+        let (public, even) = x_only(&Secp256k1Point::generator().mul(&d))?;
+        Some(SigningKey {
+            secret: if even { d } else { -d },
+            public,
+        })
+    }
+
+    pub fn sign(&self, message: &[u8], aux: &[u8; 32]) -> Signing {
+        let negated_key =
+            x_only(&Secp256k1Point::generator().mul(&self.secret)).is_some_and(|(_, even)| !even);
+        // t = d ⊕ H_aux(a)：辅助随机数坏了，nonce 也还是由私钥决定的
+        let mut t = self.secret.to_be_bytes();
+        for (byte, mask) in t.iter_mut().zip(tagged_hash("BIP0340/aux", &[aux])) {
+            *byte ^= mask;
+        }
+        let mut rand = tagged_hash("BIP0340/nonce", &[&t, &self.public, message]);
+        t.zeroize();
+        // k = 0 的概率约 2^−256
+        let k = Scalar::from_be_bytes(&rand);
+        rand.zeroize();
+        assert!(!k.is_zero(), "nonce hashed to zero");
+        let (r, even) = x_only(&Secp256k1Point::generator().mul(&k)).expect("k is nonzero");
+        let k = if even { k } else { -k };
+        let e = challenge(&r, &self.public, message);
+        let s = k + e * self.secret;
+        let mut signature = [0; 64];
+        signature[..32].copy_from_slice(&r);
+        signature[32..].copy_from_slice(&s.to_be_bytes());
+        debug_assert!(verify(&self.public, message, &signature));
+        Signing {
+            negated_key,
+            negated_nonce: !even,
+            r,
+            e,
+            signature,
+        }
+    }
+}
+
+// s·G − e·P，公钥或签名的编码不合法时为 None
+pub fn recover_r(
+    public: &[u8; 32],
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Option<Secp256k1Point> {
+    let p = Secp256k1Point::lift_x(Fe::from_be_bytes(public)?)?;
+    let r: [u8; 32] = signature[..32].try_into().expect("32 bytes");
+    Fe::from_be_bytes(&r)?;
+    let s = Scalar::from_canonical_bytes(signature[32..].try_into().expect("32 bytes"))?;
+    let e = challenge(&r, public, message);
+    let generator = Secp256k1Point::generator();
+    Some(generator.mul(&s) + -p.mul(&e))
+}
+
+pub fn verify(public: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    recover_r(public, message, signature)
+        .and_then(|point| x_only(&point))
+        .is_some_and(|(x, even)| even && x[..] == signature[..32])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes<const N: usize>(hex: &str) -> [u8; N] {
+        let mut out = [0; N];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    // BIP-340 test-vectors.csv 的 0–3：私钥、公钥、辅助随机数、消息、签名
+    #[test]
+    fn reference_signatures() {
+        for (secret, public, aux, message, expected) in [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215\
+                 25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+            ),
+            (
+                "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+                "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+                "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE3341\
+                 8906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+            ),
+            (
+                "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+                "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+                "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+                "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+                "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1B\
+                 AB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+            ),
+            (
+                "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+                "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+                "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC\
+                 97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+            ),
+        ] {
+            let key = SigningKey::from_bytes(&bytes(secret)).unwrap();
+            assert_eq!(key.public, bytes::<32>(public));
+            let message: [u8; 32] = bytes(message);
+            let signature = key.sign(&message, &bytes(aux)).signature;
+            assert_eq!(signature, bytes::<64>(expected));
+            assert!(verify(&key.public, &message, &signature));
+        }
+    }
+
+    // 4–14：只验证的向量，公钥、消息、签名和期望的结果
+    #[test]
+    fn reference_verifications() {
+        let public = "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659";
+        let message = "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89";
+        for (public, message, signature, valid) in [
+            // R 的 x 前面有很多 0
+            (
+                "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9",
+                "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703",
+                "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C63\
+                 76AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+                true,
+            ),
+            // 公钥不在曲线上
+            (
+                "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34",
+                message,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769\
+                 69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+            // R 的 y 是奇数
+            (
+                public,
+                message,
+                "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A1460297556\
+                 3CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+                false,
+            ),
+            // 消息取反
+            (
+                public,
+                message,
+                "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F\
+                 28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+                false,
+            ),
+            // s 取反
+            (
+                public,
+                message,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769\
+                 961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+                false,
+            ),
+            // s·G − e·P 是无穷远点
+            (
+                public,
+                message,
+                "0000000000000000000000000000000000000000000000000000000000000000\
+                 123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+                false,
+            ),
+            (
+                public,
+                message,
+                "0000000000000000000000000000000000000000000000000000000000000001\
+                 7615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197",
+                false,
+            ),
+            // r 不是曲线上点的 x
+            (
+                public,
+                message,
+                "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D\
+                 69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+            // r = p
+            (
+                public,
+                message,
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F\
+                 69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+            // s = n
+            (
+                public,
+                message,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769\
+                 FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+                false,
+            ),
+            // 公钥 = p，超出了域
+            (
+                "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+                message,
+                "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769\
+                 69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+                false,
+            ),
+        ] {
+            let message: [u8; 32] = bytes(message);
+            assert_eq!(
+                verify(&bytes(public), &message, &bytes(signature)),
+                valid,
+                "{signature}"
+            );
+        }
+    }
+
+    #[test]
+    fn keys_and_nonces_are_negated_to_even_y() {
+        // 3·G 的 y 是偶数，n − 3 对应同一个 x 和奇数的 y
+        let three = Scalar::from_u64(3);
+        let key = SigningKey::from_bytes(&three.to_be_bytes()).unwrap();
+        let negated = SigningKey::from_bytes(&(-three).to_be_bytes()).unwrap();
+        assert_eq!(key.public, negated.public);
+        let steps = negated.sign(b"x-only", &[7; 32]);
+        assert!(!steps.negated_key);
+        assert_eq!(steps.signature, key.sign(b"x-only", &[7; 32]).signature);
+        assert_eq!(steps.e, challenge(&steps.r, &key.public, b"x-only"));
+        let point = recover_r(&key.public, b"x-only", &steps.signature).unwrap();
+        assert_eq!(x_only(&point), Some((steps.r, true)));
+        assert!(SigningKey::from_bytes(&[0; 32]).is_none());
+        // 签名里的 r 对了，但换一个标签算出的挑战就对不上
+        assert_ne!(
+            Scalar::from_be_bytes(&tagged_hash(
+                "BIP0340/nonce",
+                &[&steps.r, &key.public, b"x-only"]
+            )),
+            steps.e
+        );
+    }
+
+    #[test]
+    fn zeroize_clears_the_signing_key() {
+        let mut key = SigningKey::from_bytes(&bytes(
+            "0000000000000000000000000000000000000000000000000000000000000003",
+        ))
+        .unwrap();
+        assert!(!key.secret.is_zero());
+        key.zeroize();
+        assert!(key.secret.is_zero());
+    }
+}
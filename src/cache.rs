@@ -0,0 +1,181 @@
+// 按曲线参数缓存算好的数据（点集、群的统计），在最近看过的几条曲线之间来回切换时
+// 不用重算。总大小超过预算时淘汰最久没用过的条目。
+use crate::curve::Curve;
+use crate::summary::CurveSummary;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// 点集和统计各自的预算；p 取到界面上限 10^6 时一条曲线的点集约 46 MiB，也能缓存
+pub const POINTS_BUDGET: usize = 64 << 20;
+pub const SUMMARY_BUDGET: usize = 1 << 20;
+// 生成元按曲线缓存，单个点的阶按 (曲线, 点) 缓存，悬停和点选时不再重新求阶。
+// 生成元最多 #E 个，每个 24 字节，p = 10^6 时不到 24 MiB
+pub const GENERATORS_BUDGET: usize = 24 << 20;
+pub const ORDERS_BUDGET: usize = 1 << 20;
+// 曲线浏览器按 p 缓存整张表，p = 251 时约 3 MiB
+pub const EXPLORER_BUDGET: usize = 8 << 20;
+
+// 缓存的值大约占用多少字节，只用来和预算比较，不需要精确
+pub trait CacheSize {
+    fn bytes(&self) -> usize;
+}
+
+// 点集、生成元、曲线浏览器的表
+impl<T> CacheSize for Vec<T> {
+    fn bytes(&self) -> usize {
+        self.len() * size_of::<T>()
+    }
+}
+
+// 点的阶：值只有 8 字节，键 (Curve, (x, y)) 比它大，按整个条目算
+impl CacheSize for u64 {
+    fn bytes(&self) -> usize {
+        size_of::<(Curve, (i64, i64), u64)>()
+    }
+}
+
+impl CacheSize for CurveSummary {
+    fn bytes(&self) -> usize {
+        size_of::<Self>() + self.order_counts.len() * 2 * size_of::<u64>()
+    }
+}
+
+struct Entry<V> {
+    value: V,
+    bytes: usize,
+    used_at: u64,
+}
+
+pub struct LruCache<K, V> {
+    budget: usize,
+    used: usize,
+    // 每次访问加一，used_at 最小的就是最久没用过的
+    clock: u64,
+    entries: HashMap<K, Entry<V>>,
+}
+
+impl<K: Eq + Hash + Clone + std::fmt::Debug, V: CacheSize> LruCache<K, V> {
+    pub fn new(budget: usize) -> Self {
+        LruCache {
+            budget,
+            used: 0,
+            clock: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    // 取值并标记为最近使用
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.used_at = self.clock;
+        Some(&entry.value)
+    }
+
+    // 只看不改顺序，给绘制界面用
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(|entry| &entry.value)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        let bytes = value.bytes();
+        if let Some(old) = self.entries.remove(&key) {
+            self.used -= old.bytes;
+        }
+        // 比整个预算还大的值不缓存，免得把其它条目全部挤掉
+        if bytes > self.budget {
+            tracing::debug!(?key, bytes, budget = self.budget, "too large to cache");
+            return;
+        }
+        while self.used + bytes > self.budget {
+            self.evict();
+        }
+        self.clock += 1;
+        self.used += bytes;
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                bytes,
+                used_at: self.clock,
+            },
+        );
+    }
+
+    fn evict(&mut self) {
+        let key = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.used_at)
+            .map(|(key, _)| key.clone())
+            .expect("used > 0 means the cache is not empty");
+        let entry = self.entries.remove(&key).expect("key was just found");
+        self.used -= entry.bytes;
+        tracing::debug!(?key, bytes = entry.bytes, "evicted from cache");
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+
+    impl CacheSize for usize {
+        fn bytes(&self) -> usize {
+            *self
+        }
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let mut cache = LruCache::<char, usize>::new(30);
+        cache.insert('a', 10);
+        cache.insert('b', 10);
+        cache.insert('c', 10);
+        // 访问 a 之后，最久没用过的是 b
+        assert_eq!(cache.get(&'a'), Some(&10));
+        cache.insert('d', 10);
+        assert_eq!(cache.peek(&'b'), None);
+        assert!(cache.peek(&'a').is_some() && cache.peek(&'c').is_some());
+        assert_eq!((cache.len(), cache.used()), (3, 30));
+    }
+
+    #[test]
+    fn stays_within_the_budget() {
+        let mut cache = LruCache::<char, usize>::new(25);
+        cache.insert('a', 10);
+        cache.insert('b', 10);
+        // 一个大条目挤掉两个小的
+        cache.insert('c', 20);
+        assert_eq!((cache.len(), cache.used()), (1, 20));
+        // 替换同一个键时先释放旧值
+        cache.insert('c', 5);
+        assert_eq!(cache.used(), 5);
+        // 超过预算的值不缓存
+        cache.insert('d', 26);
+        assert_eq!(cache.peek(&'d'), None);
+        assert_eq!(cache.used(), 5);
+    }
+
+    #[test]
+    fn point_sets_are_sized_by_their_length() {
+        let curve = Curve::new(2, 3, 97);
+        let points = curve.nonsingular_points();
+        let mut cache = LruCache::new(points.bytes());
+        cache.insert(curve, points.clone());
+        assert_eq!(cache.get(&curve), Some(&points));
+        assert_eq!(cache.used(), cache.budget());
+    }
+}
@@ -0,0 +1,120 @@
+// 弦切法的几何：过 P、Q 的直线（P = Q 时是切线）y = λx + c 与曲线交于第三点 −(P + Q)，
+// 关于 x 轴翻转得到 P + Q。在 F_p 上直线是模 p 绕回的，画在 [0, p)² 里就成了若干条平行线段。
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::field;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chord {
+    pub p: (i64, i64),
+    pub q: (i64, i64),
+    // P = −Q 时直线是竖直的，没有斜率
+    pub slope: Option<i64>,
+    // 直线与曲线的第三个交点 −(P + Q)
+    pub third: Coords,
+    pub sum: Coords,
+}
+
+impl Chord {
+    pub fn new(curve: &Curve, p: (i64, i64), q: (i64, i64)) -> Result<Self, EcError> {
+        let m = curve.p;
+        let ((x1, y1), (x2, y2)) = (p, q);
+        let sum = curve.try_add_coords(Some(p), Some(q))?;
+        let slope = if x1 == x2 && (y1 + y2) % m == 0 {
+            None
+        } else if p == q {
+            let numerator = 3 * field::mul(x1, x1, m) + curve.a;
+            Some(field::mul(numerator, field::try_mod_inverse(2 * y1, m)?, m))
+        } else {
+            Some(field::mul(y2 - y1, field::try_mod_inverse(x2 - x1, m)?, m))
+        };
+        Ok(Chord {
+            p,
+            q,
+            slope,
+            third: curve.neg_coords(sum),
+            sum,
+        })
+    }
+
+    // 直线在 [0, p]² 里的各段，单位是域坐标。λ > p/2 时改用 λ − p，同一条直线绕回的次数更少
+    pub fn segments(&self, p: i64) -> Vec<[(f32, f32); 2]> {
+        let (x1, y1) = (self.p.0 as f32, self.p.1 as f32);
+        let size = p as f32;
+        let Some(slope) = self.slope else {
+            return vec![[(x1, 0.0), (x1, size)]];
+        };
+        let slope = if slope > p / 2 { slope - p } else { slope } as f32;
+        if slope == 0.0 {
+            return vec![[(0.0, y1), (size, y1)]];
+        }
+        // 不取模的直线 Y(x) = y1 + λ(x − x1)，落在 [kp, (k+1)p) 的部分平移 kp 画出来
+        let line = |x: f32| y1 + slope * (x - x1);
+        let (low, high) = (line(0.0).min(line(size)), line(0.0).max(line(size)));
+        let bands = (low / size).floor() as i64..=(high / size).floor() as i64;
+        bands
+            .filter_map(|k| {
+                let base = k as f32 * size;
+                let ends = [x1 + (base - y1) / slope, x1 + (base + size - y1) / slope];
+                let start = ends[0].min(ends[1]).max(0.0);
+                let end = ends[0].max(ends[1]).min(size);
+                (start < end).then(|| [(start, line(start) - base), (end, line(end) - base)])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 点 (x, y) 落在某一段上
+    fn on_segments(segments: &[[(f32, f32); 2]], (x, y): (i64, i64)) -> bool {
+        let (x, y) = (x as f32, y as f32);
+        segments.iter().any(|&[(xa, ya), (xb, yb)]| {
+            if xa == xb {
+                return (x - xa).abs() < 1e-3;
+            }
+            let t = (x - xa) / (xb - xa);
+            (0.0..=1.0).contains(&t) && (ya + t * (yb - ya) - y).abs() < 1e-3
+        })
+    }
+
+    #[test]
+    fn chord_passes_through_p_q_and_the_third_point() {
+        let curve = Curve::new(2, 3, 97);
+        let points: Vec<(i64, i64)> = curve.points().iter().filter_map(|p| p.coords()).collect();
+        for &p in points.iter().step_by(7) {
+            for &q in points.iter().step_by(11) {
+                let chord = Chord::new(&curve, p, q).unwrap();
+                assert_eq!(chord.sum, curve.add_coords(Some(p), Some(q)));
+                let segments = chord.segments(97);
+                assert!(on_segments(&segments, p) && on_segments(&segments, q));
+                if let Some(third) = chord.third {
+                    assert!(on_segments(&segments, third), "{p:?} + {q:?}");
+                    // 直线的斜率在模 p 下成立
+                    let slope = chord.slope.unwrap();
+                    assert_eq!((third.1 - p.1 - slope * (third.0 - p.0)).rem_euclid(97), 0);
+                }
+                // 端点都在 [0, p]² 里
+                assert!(segments.iter().flatten().all(|&(x, y)| {
+                    (-1e-3..=97.001).contains(&x) && (-1e-3..=97.001).contains(&y)
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn tangent_and_vertical_lines() {
+        let curve = Curve::new(2, 3, 97);
+        let tangent = Chord::new(&curve, (3, 6), (3, 6)).unwrap();
+        assert_eq!(tangent.sum, Some((80, 10)));
+        assert_eq!(tangent.third, Some((80, 87)));
+        let vertical = Chord::new(&curve, (3, 6), (3, 91)).unwrap();
+        assert_eq!((vertical.slope, vertical.sum), (None, None));
+        assert_eq!(vertical.segments(97), vec![[(3.0, 0.0), (3.0, 97.0)]]);
+        // 模数是合数时分母可能不可逆
+        let composite = Curve::new(1, 1, 91);
+        assert!(Chord::new(&composite, (0, 1), (7, 13)).is_err());
+    }
+}
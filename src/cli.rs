@@ -0,0 +1,798 @@
+// 命令行模式：不打开窗口，直接在终端里做曲线上的计算，输出文本或 JSON。
+//
+//   elliptic-curve points --a 2 --b 3 --p 97
+//   elliptic-curve add --p1 3,6 --p2 80,10 --a 2 --b 3 --p 97
+//   elliptic-curve mul --k 20 --point 1,188 --json
+//   elliptic-curve order --point 1,188
+//   elliptic-curve structure --a 2 --b 3 --p 97
+//   elliptic-curve factor 8051
+//   elliptic-curve preset --name secp256k1 --k 2
+//   elliptic-curve hash abc --name p256
+//   elliptic-curve bench --rounds 200
+//   elliptic-curve selfcheck --a 2 --b 3 --p 97 --triples 5000
+//   elliptic-curve gui --seed 7
+//
+// 没给的曲线参数用界面的默认曲线 y² = x³ + x + 1 (mod 599)。
+use crate::bench::{self, BenchRow, EnumerationRow};
+use crate::curve::{Coords, Curve};
+use crate::diagnostics;
+use crate::ecm::{self, Ecm};
+use crate::hash_to_curve::{self, Sswu};
+use crate::point::{self, Point};
+use crate::presets::{self, Preset};
+use crate::rng;
+use crate::sha256;
+use crate::u256::{self, Limbs};
+
+pub const USAGE: &str = "\
+usage: elliptic-curve [COMMAND] [--a A] [--b B] [--p P] [--json]
+
+commands:
+  gui                       open the visualizer (default; accepts --seed and --script)
+  points                    list the affine points of the curve
+  add --p1 X,Y --p2 X,Y     add two points
+  mul --k K --point X,Y     multiply a point by a scalar
+  order [--point X,Y]       group order, Hasse interval and the order of a point
+  structure                 group structure E ≅ Z_n1 × Z_n2
+  factor N                  split N with Lenstra's elliptic curve method
+  preset --name NAME [--k K]
+                            parameters of secp256k1, P-256 or Curve25519, and k·G
+  hash MESSAGE [--name p256]
+                            hash a message to a point (simplified SWU on P-256)
+  bench [--rounds N]        time field inversion, point addition and scalar
+                            multiplication for y² = x³ + ax + b on 8- to 61-bit primes,
+                            and point enumeration for p around 10⁵ and 10⁶
+  selfcheck [--triples N] [--seed S]
+                            check the group axioms on N random point triples of the
+                            curve and that ord(G)·G = O; exits with 1 on a counterexample
+  help                      print this message
+
+points are written X,Y or (X,Y), and Infinity for the point at infinity";
+
+const BENCH_ROUNDS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Help,
+    Points,
+    Add {
+        p1: Coords,
+        p2: Coords,
+    },
+    Mul {
+        k: u64,
+        point: Coords,
+    },
+    Order {
+        point: Option<Coords>,
+    },
+    Structure,
+    Factor {
+        n: i64,
+    },
+    Preset {
+        preset: Box<Preset>,
+        k: Option<Limbs>,
+    },
+    Hash {
+        message: String,
+        p256: bool,
+    },
+    Bench {
+        rounds: usize,
+    },
+    SelfCheck {
+        triples: usize,
+        seed: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    pub command: Command,
+    pub curve: Curve,
+    pub json: bool,
+}
+
+// 参数（不含程序名）。没有子命令、子命令是 gui 或者只有 `--seed` 之类的选项时返回 None，交给界面处理
+pub fn parse(args: &[String]) -> Result<Option<Invocation>, String> {
+    let Some(name) = args.first().filter(|name| !name.starts_with("--")) else {
+        return Ok(None);
+    };
+    let mut options = Options::new(&args[1..])?;
+    let command = match name.as_str() {
+        "gui" => return Ok(None),
+        "help" => Command::Help,
+        "points" => Command::Points,
+        "add" => Command::Add {
+            p1: coords(options.required("p1")?)?,
+            p2: coords(options.required("p2")?)?,
+        },
+        "mul" => Command::Mul {
+            k: options
+                .required("k")?
+                .parse()
+                .map_err(|_| "--k expects an unsigned integer".to_string())?,
+            point: coords(options.required("point")?)?,
+        },
+        "order" => Command::Order {
+            point: options.take("point").map(coords).transpose()?,
+        },
+        "structure" => Command::Structure,
+        "factor" => {
+            let n = options.argument("N")?;
+            let n = n
+                .parse()
+                .ok()
+                .filter(|n| (4..=ecm::MAX_N).contains(n))
+                .ok_or_else(|| {
+                    format!("N must be an integer from 4 to {}, got `{n}`", ecm::MAX_N)
+                })?;
+            Command::Factor { n }
+        }
+        "preset" => {
+            let name = options.required("name")?;
+            Command::Preset {
+                preset: presets::by_name(name).map(Box::new).ok_or_else(|| {
+                    let names: Vec<&str> = presets::all().iter().map(|p| p.name).collect();
+                    format!(
+                        "unknown curve `{name}`, expected one of {}",
+                        names.join(", ")
+                    )
+                })?,
+                k: options.take("k").map(u256::parse).transpose()?,
+            }
+        }
+        "hash" => {
+            let message = options.argument("MESSAGE")?.to_string();
+            let p256 = match options.take("name") {
+                None => false,
+                Some(name) if presets::by_name(name) == Some(presets::p256()) => true,
+                Some(name) => {
+                    return Err(format!("hashing to `{name}` is not supported, only P-256"));
+                }
+            };
+            Command::Hash { message, p256 }
+        }
+        "bench" => Command::Bench {
+            rounds: options
+                .take("rounds")
+                .map_or(Ok(BENCH_ROUNDS), str::parse)
+                .ok()
+                .filter(|&rounds| rounds > 0)
+                .ok_or_else(|| "--rounds expects a positive integer".to_string())?,
+        },
+        "selfcheck" => Command::SelfCheck {
+            triples: options
+                .take("triples")
+                .map_or(Ok(diagnostics::SELFCHECK_TRIPLES), str::parse)
+                .ok()
+                .filter(|&triples| triples > 0)
+                .ok_or_else(|| "--triples expects a positive integer".to_string())?,
+            // 固定种子，同一条曲线每次抽到的点一样
+            seed: options
+                .take("seed")
+                .map_or(Ok(0), str::parse)
+                .map_err(|_| "--seed expects an unsigned integer".to_string())?,
+        },
+        other => return Err(format!("unknown command `{other}`")),
+    };
+    let [a, b, p] = ["a", "b", "p"].map(|name| options.take(name));
+    let parameter = |value: Option<&str>, default: i64, name: &str| {
+        value.map_or(Ok(default), |value| {
+            value
+                .parse::<i64>()
+                .map_err(|_| format!("--{name} expects an integer"))
+        })
+    };
+    let p = parameter(p, 599, "p")?;
+    let curve = Curve::try_new(parameter(a, 1, "a")?, parameter(b, 1, "b")?, p)
+        .map_err(|error| error.to_string())?;
+    if let Some((name, _)) = options.values.first() {
+        return Err(format!("unknown option `--{name}` for `{}`", args[0]));
+    }
+    if let Some(arg) = options.positional.first() {
+        return Err(format!("unexpected argument `{arg}`"));
+    }
+    Ok(Some(Invocation {
+        command,
+        curve,
+        json: options.json,
+    }))
+}
+
+impl Invocation {
+    pub fn run(&self) -> Result<String, String> {
+        let curve = &self.curve;
+        let point = |coords: Coords| match coords {
+            Some((x, y)) => curve.try_point(x, y).map_err(|error| error.to_string()),
+            None => Ok(Point::from_coords(curve, None)),
+        };
+        let json = self.json;
+        Ok(match self.command {
+            Command::Help => USAGE.to_string(),
+            Command::Points => {
+                let points = curve.points();
+                if json {
+                    let list: Vec<String> =
+                        points.iter().map(|p| json_coords(p.coords())).collect();
+                    format!(
+                        "{{\"a\": {}, \"b\": {}, \"p\": {}, \"points\": [{}]}}",
+                        curve.a,
+                        curve.b,
+                        curve.p,
+                        list.join(", ")
+                    )
+                } else {
+                    let lines: Vec<String> = points.iter().map(Point::to_string).collect();
+                    lines.join("\n")
+                }
+            }
+            Command::Add { p1, p2 } => {
+                let (p1, p2) = (point(p1)?, point(p2)?);
+                let sum = p1.try_add(&p2).map_err(|e| e.to_string())?;
+                if json {
+                    format!(
+                        "{{\"p1\": {}, \"p2\": {}, \"sum\": {}}}",
+                        json_coords(p1.coords()),
+                        json_coords(p2.coords()),
+                        json_coords(sum.coords())
+                    )
+                } else {
+                    format!("{p1} + {p2} = {sum}")
+                }
+            }
+            Command::Mul { k, point: base } => {
+                let base = point(base)?;
+                let product = base.try_mul(k).map_err(|e| e.to_string())?;
+                if json {
+                    format!(
+                        "{{\"k\": {k}, \"point\": {}, \"product\": {}}}",
+                        json_coords(base.coords()),
+                        json_coords(product.coords())
+                    )
+                } else {
+                    format!("{k}·{base} = {product}")
+                }
+            }
+            Command::Order { point: base } => {
+                let group_order = curve.group_order();
+                let (low, high) = curve.hasse_interval();
+                let base = base.map(point).transpose()?;
+                let order = base
+                    .as_ref()
+                    .map(|base| curve.order_of(base.coords(), group_order));
+                if json {
+                    let point_order = match (&base, order) {
+                        (Some(base), Some(order)) => format!(
+                            ", \"point\": {}, \"point_order\": {order}",
+                            json_coords(base.coords())
+                        ),
+                        _ => String::new(),
+                    };
+                    format!(
+                        "{{\"group_order\": {group_order}, \"hasse\": [{low}, {high}]{point_order}}}"
+                    )
+                } else {
+                    let mut text = format!("#E = {group_order} ∈ [{low}, {high}] (Hasse)");
+                    if let (Some(base), Some(order)) = (base, order) {
+                        text += &format!("\nord({base}) = {order}");
+                    }
+                    text
+                }
+            }
+            Command::Structure => {
+                let (n1, n2) = curve.group_structure();
+                let group_order = n1 * n2;
+                if json {
+                    format!(
+                        "{{\"group_order\": {group_order}, \"structure\": [{n1}, {n2}], \"cyclic\": {}}}",
+                        n2 == 1
+                    )
+                } else if n2 == 1 {
+                    format!("E ≅ Z_{n1} (cyclic, #E = {group_order})")
+                } else {
+                    format!("E ≅ Z_{n1} × Z_{n2} (#E = {group_order})")
+                }
+            }
+            Command::Factor { n } => {
+                // 固定种子，同一个 N 每次的输出一样
+                let ecm = Ecm::run(n, ecm::BOUND, &mut rng::seeded(0));
+                let Some(factor) = ecm.factor else {
+                    return Err(if ecm.trials.is_empty() {
+                        format!("{n} is prime")
+                    } else {
+                        format!("no factor of {n} found on {} curves", ecm.trials.len())
+                    });
+                };
+                let trial = ecm.trials.last().expect("the factor came from a curve");
+                let failure = trial.failure.as_ref().expect("the last curve failed");
+                if json {
+                    format!(
+                        "{{\"n\": {n}, \"factor\": {factor}, \"cofactor\": {}, \"curves\": {}, \"bound\": {}}}",
+                        n / factor,
+                        ecm.trials.len(),
+                        ecm.bound
+                    )
+                } else {
+                    let (x, y) = trial.point;
+                    let before = Point::from_coords(&trial.curve, failure.before);
+                    format!(
+                        "{n} = {factor} × {}\n\
+                         curve {}: y² = x³ + {}x + {} (mod {n}), P = ({x}, {y})\n\
+                         {}·{before} needs 1/{} mod {n}, and gcd({}, {n}) = {factor}",
+                        n / factor,
+                        ecm.trials.len(),
+                        trial.curve.a,
+                        trial.curve.b,
+                        failure.k,
+                        failure.value,
+                        failure.value
+                    )
+                }
+            }
+            Command::Preset { ref preset, k } => {
+                let hex = |limbs: &Limbs| format!("0x{}", sha256::hex(&u256::to_be_bytes(limbs)));
+                let mut fields = vec![
+                    ("p", hex(&preset.p)),
+                    ("a", hex(&preset.a)),
+                    ("b", hex(&preset.b)),
+                    ("gx", hex(&preset.g.0)),
+                    ("gy", hex(&preset.g.1)),
+                    ("n", hex(&preset.order)),
+                    ("h", preset.cofactor.to_string()),
+                ];
+                if let Some(k) = k {
+                    let big = preset.curve();
+                    let product = big.affine(&big.mul(&preset.generator(&big), &k));
+                    let (x, y) = product.map_or(("O".to_string(), "O".to_string()), |(x, y)| {
+                        (hex(&x), hex(&y))
+                    });
+                    fields.extend([("k", hex(&k)), ("kx", x), ("ky", y)]);
+                }
+                if json {
+                    let fields: Vec<String> = fields
+                        .iter()
+                        .map(|(name, value)| format!("\"{name}\": \"{value}\""))
+                        .collect();
+                    format!("{{\"name\": \"{}\", {}}}", preset.name, fields.join(", "))
+                } else {
+                    let lines: Vec<String> = fields
+                        .iter()
+                        .map(|(name, value)| format!("{name:>2} = {value}"))
+                        .collect();
+                    format!("{}: {}\n{}", preset.name, preset.equation, lines.join("\n"))
+                }
+            }
+            Command::Hash { ref message, p256 } => {
+                let (x, y) = if p256 {
+                    let hex =
+                        |limbs: &Limbs| format!("0x{}", sha256::hex(&u256::to_be_bytes(limbs)));
+                    let suite = Sswu::p256();
+                    let point = suite.hash(message.as_bytes(), hash_to_curve::DST);
+                    let (x, y) = suite.curve.affine(&point).ok_or("hashed to infinity")?;
+                    // JSON 里 256 位的数写成字符串
+                    if json {
+                        (format!("\"{}\"", hex(&x)), format!("\"{}\"", hex(&y)))
+                    } else {
+                        (hex(&x), hex(&y))
+                    }
+                } else {
+                    let (x, y) = hash_to_curve::hash_to_point(message.as_bytes(), curve)
+                        .ok_or("the curve has no affine points")?;
+                    (x.to_string(), y.to_string())
+                };
+                if json {
+                    format!("{{\"message\": {message:?}, \"point\": [{x}, {y}]}}")
+                } else {
+                    format!("H({message:?}) = ({x}, {y})")
+                }
+            }
+            Command::Bench { rounds } => {
+                let rows = bench::run(curve.a, curve.b, rounds)?;
+                let enumeration = bench::enumeration(curve.a, curve.b, &bench::ENUMERATION_PRIMES)?;
+                if json {
+                    let rows: Vec<String> = rows.iter().map(json_bench_row).collect();
+                    let enumeration: Vec<String> =
+                        enumeration.iter().map(json_enumeration_row).collect();
+                    format!(
+                        "{{\"rounds\": {rounds}, \"primes\": [{}], \"enumeration\": [{}]}}",
+                        rows.join(", "),
+                        enumeration.join(", ")
+                    )
+                } else {
+                    format!(
+                        "{}\n\n{}",
+                        bench_table(&rows, rounds),
+                        enumeration_table(&enumeration)
+                    )
+                }
+            }
+            Command::SelfCheck { triples, seed } => {
+                let check = diagnostics::check_curve(curve, triples, seed)
+                    .map_err(|error| format!("selfcheck failed: {error}"))?;
+                if json {
+                    format!(
+                        "{{\"a\": {}, \"b\": {}, \"p\": {}, \"triples\": {triples}, \"seed\": {seed}, \"generator\": {}, \"order\": {}, \"group_order\": {}}}",
+                        curve.a,
+                        curve.b,
+                        curve.p,
+                        json_coords(check.generator),
+                        check.order,
+                        check.group_order
+                    )
+                } else {
+                    check.to_string()
+                }
+            }
+        })
+    }
+}
+
+// `--name value` 形式的选项，`--json` 不带值；其余的是位置参数，例如 `factor 8051` 的 8051
+struct Options<'a> {
+    values: Vec<(&'a str, &'a str)>,
+    positional: Vec<&'a str>,
+    json: bool,
+}
+
+impl<'a> Options<'a> {
+    fn new(args: &'a [String]) -> Result<Self, String> {
+        let mut options = Options {
+            values: Vec::new(),
+            positional: Vec::new(),
+            json: false,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some("json") => options.json = true,
+                Some(name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("`--{name}` expects a value"))?;
+                    options.values.push((name, value));
+                }
+                None => options.positional.push(arg),
+            }
+        }
+        Ok(options)
+    }
+
+    fn take(&mut self, name: &str) -> Option<&'a str> {
+        let index = self.values.iter().position(|&(key, _)| key == name)?;
+        Some(self.values.remove(index).1)
+    }
+
+    fn required(&mut self, name: &str) -> Result<&'a str, String> {
+        self.take(name)
+            .ok_or_else(|| format!("missing option `--{name}`"))
+    }
+
+    fn argument(&mut self, name: &str) -> Result<&'a str, String> {
+        if self.positional.is_empty() {
+            return Err(format!("missing argument `{name}`"));
+        }
+        Ok(self.positional.remove(0))
+    }
+}
+
+// "x,y"、"(x, y)" 或 "Infinity"
+fn coords(text: &str) -> Result<Coords, String> {
+    point::parse_coords(text).map_err(|error| error.to_string())
+}
+
+// 每种运算一列，时间是单次的平均值
+fn bench_table(rows: &[BenchRow], rounds: usize) -> String {
+    let mut lines = vec![format!(
+        "{:>4}  {:>19}  {:>9}  {:>10}  {:>12}  {:>14}  {:>9}  {:>9}",
+        "bits", "p", "inverse", "affine add", "Jacobian add", "double-and-add", "ladder", "wNAF"
+    )];
+    for row in rows {
+        lines.push(format!(
+            "{:>4}  {:>19}  {:>9}  {:>10}  {:>12}  {:>14}  {:>9}  {:>9}",
+            row.bits(),
+            row.p,
+            format!("{:.1?}", row.inversion),
+            format!("{:.1?}", row.affine_add),
+            format!("{:.1?}", row.jacobian_add),
+            format!("{:.1?}", row.double_and_add),
+            format!("{:.1?}", row.ladder),
+            format!("{:.1?}", row.wnaf),
+        ));
+    }
+    lines.push(format!(
+        "mean of {rounds} runs each; multiplication uses random 64-bit scalars"
+    ));
+    lines.join("\n")
+}
+
+// 逐个试 y 的时间是按抽样的几列折算的
+fn enumeration_table(rows: &[EnumerationRow]) -> String {
+    let mut lines = vec![format!(
+        "{:>9}  {:>9}  {:>12}  {:>14}  {:>14}",
+        "p", "#E", "sqrt table", "Tonelli–Shanks", "trial y (est.)"
+    )];
+    for row in rows {
+        lines.push(format!(
+            "{:>9}  {:>9}  {:>12}  {:>14}  {:>14}",
+            row.p,
+            row.group_order,
+            format!("{:.1?}", row.table),
+            format!("{:.1?}", row.tonelli),
+            format!("{:.1?}", row.trial),
+        ));
+    }
+    lines.push("whole-curve point enumeration".to_string());
+    lines.join("\n")
+}
+
+// JSON 里的时间是纳秒
+fn json_bench_row(row: &BenchRow) -> String {
+    format!(
+        "{{\"p\": {}, \"bits\": {}, \"inverse_ns\": {}, \"affine_add_ns\": {}, \"jacobian_add_ns\": {}, \"double_and_add_ns\": {}, \"ladder_ns\": {}, \"wnaf_ns\": {}}}",
+        row.p,
+        row.bits(),
+        row.inversion.as_nanos(),
+        row.affine_add.as_nanos(),
+        row.jacobian_add.as_nanos(),
+        row.double_and_add.as_nanos(),
+        row.ladder.as_nanos(),
+        row.wnaf.as_nanos()
+    )
+}
+
+fn json_enumeration_row(row: &EnumerationRow) -> String {
+    format!(
+        "{{\"p\": {}, \"group_order\": {}, \"table_ns\": {}, \"tonelli_ns\": {}, \"trial_estimate_ns\": {}}}",
+        row.p,
+        row.group_order,
+        row.table.as_nanos(),
+        row.tonelli.as_nanos(),
+        row.trial.as_nanos()
+    )
+}
+
+// 无穷远点写成 null
+fn json_coords(coords: Coords) -> String {
+    match coords {
+        Some((x, y)) => format!("[{x}, {y}]"),
+        None => "null".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(line: &str) -> Result<String, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse(&args)?.expect("not a gui invocation").run()
+    }
+
+    #[test]
+    fn gui_invocations_are_left_to_the_app() {
+        let args =
+            |line: &str| -> Vec<String> { line.split_whitespace().map(String::from).collect() };
+        assert_eq!(parse(&args("")), Ok(None));
+        assert_eq!(parse(&args("--seed 7")), Ok(None));
+        assert_eq!(parse(&args("gui --script demo.txt")), Ok(None));
+    }
+
+    #[test]
+    fn subcommands_print_text() {
+        let curve = "--a 2 --b 3 --p 97";
+        assert_eq!(
+            run(&format!("add --p1 3,6 --p2 3,6 {curve}")),
+            Ok("(3, 6) + (3, 6) = (80, 10)".to_string())
+        );
+        assert_eq!(
+            run(&format!("mul --k 5 --point 3,6 {curve}")),
+            Ok("5·(3, 6) = Infinity".to_string())
+        );
+        assert_eq!(
+            run(&format!("order --point 3,6 {curve}")),
+            Ok("#E = 100 ∈ [79, 117] (Hasse)\nord((3, 6)) = 5".to_string())
+        );
+        assert_eq!(
+            run(&format!("structure {curve}")),
+            Ok("E ≅ Z_50 × Z_2 (#E = 100)".to_string())
+        );
+        assert_eq!(
+            run("structure"),
+            Ok("E ≅ Z_597 (cyclic, #E = 597)".to_string())
+        );
+        // 点也可以写成 (x,y)，无穷远点写成 Infinity
+        assert_eq!(
+            run(&format!("mul --k 4 --point (3,6) {curve}")),
+            Ok("4·(3, 6) = (3, 91)".to_string())
+        );
+        assert_eq!(
+            run(&format!("add --p1 Infinity --p2 3,6 {curve}")),
+            Ok("Infinity + (3, 6) = (3, 6)".to_string())
+        );
+        let points = run(&format!("points {curve}")).unwrap();
+        assert_eq!(points.lines().count(), 99);
+        assert!(points.lines().any(|line| line == "(3, 6)"));
+        // 默认曲线是界面的 y² = x³ + x + 1 (mod 599)
+        assert_eq!(
+            run("mul --k 2 --point 1,188"),
+            Ok(format!(
+                "2·(1, 188) = {}",
+                Curve::new(1, 1, 599).point(1, 188) * 2
+            ))
+        );
+    }
+
+    #[test]
+    fn subcommands_print_json() {
+        let curve = "--a 2 --b 3 --p 97 --json";
+        assert_eq!(
+            run(&format!("add --p1 3,6 --p2 3,91 {curve}")),
+            Ok("{\"p1\": [3, 6], \"p2\": [3, 91], \"sum\": null}".to_string())
+        );
+        assert_eq!(
+            run(&format!("order --point 3,6 {curve}")),
+            Ok(
+                "{\"group_order\": 100, \"hasse\": [79, 117], \"point\": [3, 6], \"point_order\": 5}"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            run(&format!("structure {curve}")),
+            Ok("{\"group_order\": 100, \"structure\": [50, 2], \"cyclic\": false}".to_string())
+        );
+        assert_eq!(
+            run("points --a 2 --b 3 --p 7 --json"),
+            Ok("{\"a\": 2, \"b\": 3, \"p\": 7, \"points\": [[2, 1], [2, 6], [3, 1], [3, 6], [6, 0]]}".to_string())
+        );
+    }
+
+    #[test]
+    fn factor_reports_the_failing_step() {
+        let text = run("factor 8051").unwrap();
+        assert!(text.starts_with("8051 = 83 × 97\ncurve "), "{text}");
+        assert!(
+            text.ends_with("mod 8051, and gcd(2407, 8051) = 83"),
+            "{text}"
+        );
+        assert_eq!(
+            run("factor 8051 --json"),
+            Ok(
+                "{\"n\": 8051, \"factor\": 83, \"cofactor\": 97, \"curves\": 1, \"bound\": 50}"
+                    .to_string()
+            )
+        );
+        assert_eq!(run("factor 8053"), Err("8053 is prime".to_string()));
+        assert_eq!(run("factor"), Err("missing argument `N`".to_string()));
+        assert_eq!(
+            run("factor 3"),
+            Err("N must be an integer from 4 to 1000000, got `3`".to_string())
+        );
+        assert_eq!(run("points 3"), Err("unexpected argument `3`".to_string()));
+    }
+
+    #[test]
+    fn presets_by_name() {
+        let text = run("preset --name secp256k1 --k 2").unwrap();
+        assert!(text.starts_with("secp256k1: y² = x³ + 7\n"));
+        assert!(
+            text.contains(
+                "kx = 0xc6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+            )
+        );
+        let json = run("preset --name p256 --json").unwrap();
+        assert!(json.starts_with("{\"name\": \"P-256\", \"p\": \"0xffffffff00000001"));
+        assert_eq!(
+            run("preset --name curve448"),
+            Err(
+                "unknown curve `curve448`, expected one of secp256k1, P-256, Curve25519"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn hash_prints_a_point_on_the_curve() {
+        let curve = Curve::new(2, 3, 97);
+        let (x, y) = hash_to_curve::hash_to_point(b"abc", &curve).unwrap();
+        assert!(curve.contains(x, y));
+        assert_eq!(
+            run("hash abc --a 2 --b 3 --p 97"),
+            Ok(format!("H(\"abc\") = ({x}, {y})"))
+        );
+        assert_eq!(
+            run("hash abc --a 2 --b 3 --p 97 --json"),
+            Ok(format!("{{\"message\": \"abc\", \"point\": [{x}, {y}]}}"))
+        );
+        let suite = Sswu::p256();
+        let point = suite.hash(b"abc", hash_to_curve::DST);
+        let (x, _) = suite.curve.affine(&point).unwrap();
+        let output = run("hash abc --name P-256").unwrap();
+        assert!(output.contains(&sha256::hex(&u256::to_be_bytes(&x))));
+        assert_eq!(
+            run("hash abc --name secp256k1"),
+            Err("hashing to `secp256k1` is not supported, only P-256".to_string())
+        );
+        assert_eq!(run("hash"), Err("missing argument `MESSAGE`".to_string()));
+    }
+
+    #[test]
+    fn bench_prints_a_row_per_prime() {
+        let text = run("bench --rounds 4").unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 11, "{text}");
+        assert!(lines[0].trim_start().starts_with("bits"));
+        assert!(lines[4].trim_start().starts_with("61  2305843009213693951"));
+        // 点枚举的表格跟在后面，#E 与 group_order 一致
+        assert!(lines[8].trim_start().starts_with("100003     100181"));
+        assert!(lines[9].trim_start().starts_with("1000003"));
+        let json = run("bench --rounds 4 --json").unwrap();
+        assert!(json.starts_with("{\"rounds\": 4, \"primes\": [{\"p\": 251, \"bits\": 8, "));
+        assert!(json.contains("\"enumeration\": [{\"p\": 100003, \"group_order\": 100181, "));
+        assert_eq!(
+            run("bench --rounds 0"),
+            Err("--rounds expects a positive integer".to_string())
+        );
+    }
+
+    #[test]
+    fn selfcheck_reports_the_generator_order() {
+        assert_eq!(
+            run("selfcheck --a 2 --b 3 --p 97 --triples 50 --seed 3"),
+            Ok(
+                "50 random triples satisfy associativity, commutativity, identity and inverses \
+                (seed 3); ord(G)·G = O for G = (0, 10), ord(G) = 50 | #E = 100"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            run("selfcheck --a 2 --b 3 --p 97 --triples 50 --json"),
+            Ok("{\"a\": 2, \"b\": 3, \"p\": 97, \"triples\": 50, \"seed\": 0, \"generator\": [0, 10], \"order\": 50, \"group_order\": 100}".to_string())
+        );
+        assert!(run("selfcheck").unwrap().starts_with("2000 random triples"));
+        assert_eq!(
+            run("selfcheck --triples none"),
+            Err("--triples expects a positive integer".to_string())
+        );
+    }
+
+    #[test]
+    fn bad_arguments_are_reported() {
+        assert_eq!(
+            run("frobnicate"),
+            Err("unknown command `frobnicate`".to_string())
+        );
+        assert_eq!(
+            run("add --p1 3,6"),
+            Err("missing option `--p2`".to_string())
+        );
+        assert_eq!(
+            run("mul --k 2 --point 3"),
+            Err("expected a point as `x,y` or `(x, y)`, got `3`".to_string())
+        );
+        assert_eq!(
+            run("points --q 3"),
+            Err("unknown option `--q` for `points`".to_string())
+        );
+        assert_eq!(run("points --p"), Err("`--p` expects a value".to_string()));
+        assert_eq!(
+            run("order --p 91"),
+            Err("modulus 91 is not prime".to_string())
+        );
+        assert_eq!(
+            run("order --p 0"),
+            Err("modulus 0 is not prime".to_string())
+        );
+        assert_eq!(
+            run("add --p1 3,7 --p2 3,6 --a 2 --b 3 --p 97"),
+            Err("point (3, 7) is not on the curve".to_string())
+        );
+        // a = −3k²、b = 2k³ 时判别式为 0，k = 2^40 + 7 让 a、b 都接近 p ≈ 2^62
+        assert_eq!(
+            run("add --p1 Infinity --p2 Infinity --a 4611639839045189620 \
+                 --b 576784007235636133 --p 4611686018427388039"),
+            Err("curve is singular (4a^3 + 27b^2 = 0 mod p)".to_string())
+        );
+    }
+}
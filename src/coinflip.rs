@@ -0,0 +1,200 @@
+// 电话抛硬币：Alice 和 Bob 隔着电话抛硬币，谁都不信任对方。
+// Alice 先抛并发送承诺 C = b·G + r·H，Bob 猜正反，Alice 再公开 (b, r)，Bob 验证承诺。
+// 承诺的隐藏性让 Bob 猜之前看不出 b，绑定性让 Alice 看到猜测之后改不了 b。
+use crate::commitment::PedersenCommitment;
+use crate::curve::{Coords, Curve};
+use crate::rng::{self, DemoRng};
+use crate::scalar::Scalar;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    // 等 Alice 抛硬币并承诺
+    Commit,
+    // 等 Bob 猜
+    Guess,
+    // 等 Alice 公开
+    Reveal,
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    BobWins,
+    AliceWins,
+    // 公开的值和承诺对不上，Alice 作弊被发现
+    CheatDetected,
+}
+
+pub struct CoinFlip {
+    pub params: PedersenCommitment,
+    rng: DemoRng,
+    pub stage: Stage,
+    // Alice 的秘密，只有她自己的面板能看到
+    bit: bool,
+    blinding: Scalar,
+    pub commitment: Coords,
+    pub guess: Option<bool>,
+    pub outcome: Option<Outcome>,
+    // 双方之间发送的消息，按顺序
+    pub transcript: Vec<String>,
+}
+
+fn side(bit: bool) -> &'static str {
+    if bit { "heads" } else { "tails" }
+}
+
+impl CoinFlip {
+    pub fn new(curve: &Curve, seed: u64) -> Result<Self, String> {
+        let params = PedersenCommitment::new(curve)?;
+        Ok(CoinFlip {
+            blinding: Scalar::zero(params.q),
+            params,
+            rng: rng::seeded(seed),
+            stage: Stage::Commit,
+            bit: false,
+            commitment: None,
+            guess: None,
+            outcome: None,
+            transcript: vec![],
+        })
+    }
+
+    // 重新开始一局，RNG 接着用，每局的硬币和致盲因子不同
+    pub fn restart(&mut self) {
+        self.stage = Stage::Commit;
+        self.commitment = None;
+        self.guess = None;
+        self.outcome = None;
+        self.transcript.clear();
+    }
+
+    pub fn secret(&self) -> Option<(bool, Scalar)> {
+        (self.stage != Stage::Commit).then_some((self.bit, self.blinding))
+    }
+
+    pub fn commit(&mut self) {
+        assert_eq!(self.stage, Stage::Commit, "Alice has already committed");
+        self.bit = rng::uniform_below(&mut self.rng, 2) == 1;
+        let value = self.params.scalar(self.bit as i64);
+        (self.commitment, self.blinding) = self.params.commit_random(value, &mut self.rng);
+        tracing::debug!(commitment = ?self.commitment, "alice committed");
+        self.transcript
+            .push(format!("Alice → Bob: C = {:?}", self.commitment));
+        self.stage = Stage::Guess;
+    }
+
+    pub fn guess(&mut self, heads: bool) {
+        assert_eq!(self.stage, Stage::Guess, "Bob guesses after the commitment");
+        self.guess = Some(heads);
+        self.transcript
+            .push(format!("Bob → Alice: I guess {}", side(heads)));
+        self.stage = Stage::Reveal;
+    }
+
+    // 诚实地公开 (b, r)
+    pub fn reveal(&mut self) -> Outcome {
+        self.finish(self.bit, self.blinding)
+    }
+
+    // Alice 看到 Bob 猜对了，想改口：公开相反的一面。没有 log_G H 她找不到匹配的
+    // r'，只能沿用原来的 r，于是验证失败
+    pub fn cheat(&mut self) -> Outcome {
+        self.finish(!self.bit, self.blinding)
+    }
+
+    fn finish(&mut self, bit: bool, blinding: Scalar) -> Outcome {
+        assert_eq!(self.stage, Stage::Reveal, "Alice reveals after Bob's guess");
+        self.transcript.push(format!(
+            "Alice → Bob: the coin was {}, r = {}",
+            side(bit),
+            blinding.value()
+        ));
+        let value = self.params.scalar(bit as i64);
+        let outcome = if !self.params.open(self.commitment, value, blinding) {
+            self.transcript.push(format!(
+                "Bob: {}·G + {}·H = {:?} ≠ C, the commitment does not open",
+                bit as u8,
+                blinding.value(),
+                self.params.commit(value, blinding)
+            ));
+            Outcome::CheatDetected
+        } else if self.guess == Some(bit) {
+            self.transcript
+                .push("Bob: the commitment opens, my guess was right".to_string());
+            Outcome::BobWins
+        } else {
+            self.transcript
+                .push("Bob: the commitment opens, my guess was wrong".to_string());
+            Outcome::AliceWins
+        };
+        tracing::debug!(?outcome, "coin flip finished");
+        self.outcome = Some(outcome);
+        self.stage = Stage::Done;
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> CoinFlip {
+        CoinFlip::new(&Curve::new(1, 1, 599), 7).unwrap()
+    }
+
+    #[test]
+    fn honest_games_open_and_pick_a_winner() {
+        let mut game = game();
+        for round in 0..8 {
+            game.restart();
+            game.commit();
+            let (bit, _) = game.secret().unwrap();
+            // 轮流猜对和猜错
+            game.guess(if round % 2 == 0 { bit } else { !bit });
+            let expected = if round % 2 == 0 {
+                Outcome::BobWins
+            } else {
+                Outcome::AliceWins
+            };
+            assert_eq!(game.reveal(), expected);
+            assert_eq!(game.stage, Stage::Done);
+            assert_eq!(game.transcript.len(), 4);
+        }
+    }
+
+    #[test]
+    fn revealing_the_other_side_is_detected() {
+        let mut game = game();
+        game.commit();
+        game.guess(true);
+        assert_eq!(game.cheat(), Outcome::CheatDetected);
+        assert!(game.transcript.last().unwrap().contains("does not open"));
+    }
+
+    #[test]
+    fn the_blinding_factor_hides_the_bit() {
+        // 同一面在不同局里的承诺各不相同，Bob 不能拿 0·G、1·G 去比对
+        let mut game = game();
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..8 {
+            game.restart();
+            game.commit();
+            assert!(seen.insert(game.commitment));
+            let params = game.params;
+            assert_ne!(
+                game.commitment,
+                params.commit(params.scalar(0), params.scalar(0))
+            );
+            assert_ne!(
+                game.commitment,
+                params.commit(params.scalar(1), params.scalar(0))
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Bob guesses after the commitment")]
+    fn steps_must_happen_in_order() {
+        game().guess(true);
+    }
+}
@@ -0,0 +1,81 @@
+// Pedersen 承诺 C = v·G + r·H。r 是随机的致盲因子：只看 C 得不到 v 的任何信息（隐藏性）；
+// 要把 C 打开成另一个值，需要知道 log_G H（绑定性，依赖离散对数困难）。
+// G、H 取 Pedersen 哈希的前两个生成元，都是哈希到曲线得到的，没人知道它们之间的对数。
+use crate::curve::{Coords, Curve};
+use crate::pedersen::PedersenHash;
+use crate::scalar::Scalar;
+use rand_core::RngCore;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PedersenCommitment {
+    pub curve: Curve,
+    pub q: u64,
+    pub g: Coords,
+    pub h: Coords,
+}
+
+impl PedersenCommitment {
+    pub fn new(curve: &Curve) -> Result<Self, String> {
+        let mut hasher = PedersenHash::new(curve)?;
+        Ok(PedersenCommitment {
+            curve: *curve,
+            q: hasher.q,
+            g: hasher.generator(0),
+            h: hasher.generator(1),
+        })
+    }
+
+    pub fn commit(&self, value: Scalar, blinding: Scalar) -> Coords {
+        self.curve
+            .msm(&[(self.g, value.value()), (self.h, blinding.value())])
+    }
+
+    // 承诺一个值，返回承诺和需要保密到打开时的致盲因子
+    pub fn commit_random<R: RngCore + ?Sized>(
+        &self,
+        value: Scalar,
+        rng: &mut R,
+    ) -> (Coords, Scalar) {
+        let blinding = Scalar::random(rng, self.q);
+        (self.commit(value, blinding), blinding)
+    }
+
+    pub fn open(&self, commitment: Coords, value: Scalar, blinding: Scalar) -> bool {
+        self.commit(value, blinding) == commitment
+    }
+
+    pub fn scalar(&self, value: i64) -> Scalar {
+        Scalar::new(value, self.q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    #[test]
+    fn commitments_open_only_to_the_committed_value() {
+        let params = PedersenCommitment::new(&Curve::new(1, 1, 599)).unwrap();
+        let mut rng = rng::seeded(9);
+        let value = params.scalar(42);
+        let (commitment, blinding) = params.commit_random(value, &mut rng);
+        assert!(params.open(commitment, value, blinding));
+        assert!(!params.open(commitment, params.scalar(43), blinding));
+        assert!(!params.open(commitment, value, blinding + params.scalar(1)));
+        // 同一个值每次承诺的结果不同
+        let (again, _) = params.commit_random(value, &mut rng);
+        assert_ne!(again, commitment);
+    }
+
+    #[test]
+    fn commitments_are_additively_homomorphic() {
+        let params = PedersenCommitment::new(&Curve::new(1, 1, 599)).unwrap();
+        let (v1, r1) = (params.scalar(5), params.scalar(17));
+        let (v2, r2) = (params.scalar(190), params.scalar(30));
+        let sum = params
+            .curve
+            .add_coords(params.commit(v1, r1), params.commit(v2, r2));
+        assert_eq!(sum, params.commit(v1 + v2, r1 + r2));
+    }
+}
@@ -0,0 +1,291 @@
+// 日志控制台：一个最小的 tracing Subscriber，把事件和 span 的耗时写进共享缓冲区，
+// 界面里的日志面板从缓冲区读取。span 关闭时记录一条带耗时的日志。
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+// 缓冲区只保留最近的这么多条
+const CAPACITY: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    // 相对于 subscriber 创建的时间
+    pub at: Duration,
+    pub level: Level,
+    // 所在的 span 路径，例如 "summary > enumerate"
+    pub scope: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer(Arc<Mutex<VecDeque<Entry>>>);
+
+impl LogBuffer {
+    fn push(&self, entry: Entry) {
+        let mut entries = self.0.lock().expect("log buffer is not poisoned");
+        if entries.len() == CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    pub fn entries(&self) -> Vec<Entry> {
+        let entries = self.0.lock().expect("log buffer is not poisoned");
+        entries.iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().expect("log buffer is not poisoned").clear();
+    }
+}
+
+struct SpanData {
+    name: &'static str,
+    level: Level,
+    fields: String,
+    opened: Instant,
+    refs: usize,
+}
+
+pub struct ConsoleSubscriber {
+    buffer: LogBuffer,
+    max_level: Level,
+    start: Instant,
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, SpanData>>,
+}
+
+thread_local! {
+    // 每个线程当前进入的 span
+    static STACK: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+impl ConsoleSubscriber {
+    pub fn new(buffer: LogBuffer, max_level: Level) -> Self {
+        ConsoleSubscriber {
+            buffer,
+            max_level,
+            start: Instant::now(),
+            next_id: AtomicU64::new(1),
+            spans: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn scope(&self) -> String {
+        let spans = self.spans.lock().expect("span table is not poisoned");
+        STACK.with(|stack| {
+            stack
+                .borrow()
+                .iter()
+                .filter_map(|id| spans.get(id))
+                .map(|span| span.name)
+                .collect::<Vec<_>>()
+                .join(" > ")
+        })
+    }
+}
+
+// message 字段作为正文，其余字段以 name=value 的形式跟在后面
+#[derive(Default)]
+struct Fields {
+    message: String,
+    rest: String,
+}
+
+impl Fields {
+    fn text(self) -> String {
+        if self.message.is_empty() {
+            self.rest.trim_start().to_string()
+        } else {
+            self.message + &self.rest
+        }
+    }
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.rest, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.rest, " {}={value}", field.name());
+        }
+    }
+}
+
+impl Subscriber for ConsoleSubscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.max_level
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(self.max_level.into())
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let mut fields = Fields::default();
+        attributes.record(&mut fields);
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let data = SpanData {
+            name: attributes.metadata().name(),
+            level: *attributes.metadata().level(),
+            fields: fields.rest,
+            opened: Instant::now(),
+            refs: 1,
+        };
+        self.spans
+            .lock()
+            .expect("span table is not poisoned")
+            .insert(id, data);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut fields = Fields::default();
+        values.record(&mut fields);
+        if let Some(data) = self
+            .spans
+            .lock()
+            .expect("span table is not poisoned")
+            .get_mut(&span.into_u64())
+        {
+            data.fields.push_str(&fields.rest);
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.buffer.push(Entry {
+            at: self.start.elapsed(),
+            level: *event.metadata().level(),
+            scope: self.scope(),
+            message: fields.text(),
+        });
+    }
+
+    fn enter(&self, span: &Id) {
+        STACK.with(|stack| stack.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, span: &Id) {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(index) = stack.iter().rposition(|&id| id == span.into_u64()) {
+                stack.remove(index);
+            }
+        });
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some(data) = self
+            .spans
+            .lock()
+            .expect("span table is not poisoned")
+            .get_mut(&span.into_u64())
+        {
+            data.refs += 1;
+        }
+        span.clone()
+    }
+
+    // 最后一个引用释放时 span 结束，记下它从创建到结束的耗时
+    fn try_close(&self, span: Id) -> bool {
+        let closed = {
+            let mut spans = self.spans.lock().expect("span table is not poisoned");
+            let Some(data) = spans.get_mut(&span.into_u64()) else {
+                return false;
+            };
+            data.refs -= 1;
+            if data.refs > 0 {
+                return false;
+            }
+            spans.remove(&span.into_u64())
+        };
+        if let Some(data) = closed {
+            let scope = self.scope();
+            self.buffer.push(Entry {
+                at: self.start.elapsed(),
+                level: data.level,
+                scope: if scope.is_empty() {
+                    data.name.to_string()
+                } else {
+                    format!("{scope} > {}", data.name)
+                },
+                message: format!("finished in {:.2?}{}", data.opened.elapsed(), data.fields),
+            });
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capture(max_level: Level, f: impl FnOnce()) -> Vec<Entry> {
+        let buffer = LogBuffer::default();
+        let subscriber = ConsoleSubscriber::new(buffer.clone(), max_level);
+        tracing::subscriber::with_default(subscriber, f);
+        buffer.entries()
+    }
+
+    #[test]
+    fn records_events_with_fields_and_scope() {
+        let entries = capture(Level::DEBUG, || {
+            let _span = tracing::info_span!("outer").entered();
+            let _inner = tracing::debug_span!("inner").entered();
+            tracing::info!(p = 599, "counting points");
+        });
+        assert_eq!(entries[0].level, Level::INFO);
+        assert_eq!(entries[0].scope, "outer > inner");
+        assert_eq!(entries[0].message, "counting points p=599");
+    }
+
+    #[test]
+    fn closing_a_span_records_its_duration() {
+        let entries = capture(Level::DEBUG, || {
+            let _span = tracing::info_span!("summary", p = 97).entered();
+        });
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].scope, "summary");
+        assert!(entries[0].message.starts_with("finished in "));
+        assert!(entries[0].message.ends_with(" p=97"));
+    }
+
+    #[test]
+    fn filters_by_level() {
+        let entries = capture(Level::INFO, || {
+            tracing::debug!("hidden");
+            tracing::warn!("shown");
+        });
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "shown");
+    }
+
+    #[test]
+    fn keeps_only_the_most_recent_entries() {
+        let entries = capture(Level::INFO, || {
+            for i in 0..CAPACITY + 5 {
+                tracing::info!(i);
+            }
+        });
+        assert_eq!(entries.len(), CAPACITY);
+        assert_eq!(entries[0].message, "i=5");
+    }
+}
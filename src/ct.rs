@@ -0,0 +1,217 @@
+// 常量时间运算（`ct` feature）
+//
+// 接口与 subtle crate 的 Choice / ConditionallySelectable / ConstantTimeEq 保持一致，
+// 标量乘法只走固定长度的 Montgomery ladder，点加使用完全公式，不依赖秘密数据分支。
+//
+// 开启后哪些接口是常量时间的：
+// - 素数模数上的 Curve::mul_coords（本文件的 ladder_mul）。i64 的玩具曲线只是演示，其余运算仍然按数据分支，
+//   ladder 里的 % 在不同平台上的耗时也未必固定；
+// - BigCurve::mul 和 FieldBackend::pow / invert：presets::KeyPair 生成公钥、P-256 签名求 k·G 和 k⁻¹；
+// - Secp256k1Point::mul 和 Fe::invert：BIP-340 的 d·G、k·G；此时不走 GLV；
+// - EdwardsPoint::mul：Ed25519 的 a·B、r·B；x25519 本来就是 ladder，不受 feature 影响。
+// 256 位的模加、模减、约化（u256::add_mod / sub_mod / reduce_once / reduce_generic）
+// 不管开不开 feature 都用掩码选择结果，没有依赖数据的分支。
+// 仍然是变时间的：at_least 之类的比较（只用来校验公开输入）、affine 和 is_identity
+// （结果本来就公开）、GeneratorTable::mul（按秘密的窗口查表）、mul_plain / mul_glv 这些对照实现，
+// 以及 KeyPair::generate 的拒绝采样（被拒的候选值不会被使用）。
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::field;
+use std::hint::black_box;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Choice(u8);
+
+impl Choice {
+    pub fn unwrap_u8(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for Choice {
+    fn from(bit: u8) -> Self {
+        debug_assert!(bit <= 1);
+        Choice(black_box(bit))
+    }
+}
+
+pub trait ConditionallySelectable: Copy {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self;
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        let t = *a;
+        *a = Self::conditional_select(a, b, choice);
+        *b = Self::conditional_select(b, &t, choice);
+    }
+}
+
+pub trait ConstantTimeEq {
+    fn ct_eq(&self, other: &Self) -> Choice;
+}
+
+impl ConditionallySelectable for i64 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mask = -(choice.unwrap_u8() as i64);
+        a ^ (mask & (a ^ b))
+    }
+}
+
+impl ConditionallySelectable for u64 {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mask = 0u64.wrapping_sub(choice.unwrap_u8() as u64);
+        a ^ (mask & (a ^ b))
+    }
+}
+
+// 256 位的肢数组（bigcurve 的 Elem、secp256k1 的 Fe）逐肢选择
+impl<const N: usize> ConditionallySelectable for [u64; N] {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        std::array::from_fn(|i| u64::conditional_select(&a[i], &b[i], choice))
+    }
+}
+
+impl ConstantTimeEq for i64 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let x = (self ^ other) as u64;
+        // x == 0 时最高位为 0
+        let nonzero = ((x | x.wrapping_neg()) >> 63) as u8;
+        Choice::from(nonzero ^ 1)
+    }
+}
+
+// a + b − p 在 p > 2^62 时会溢出，先算 p − b，结果同样在 (−p, p)
+fn add_mod(a: i64, b: i64, p: i64) -> i64 {
+    let s = a - (p - b);
+    s + (p & (s >> 63))
+}
+
+fn sub_mod(a: i64, b: i64, p: i64) -> i64 {
+    let d = a - b;
+    d + (p & (d >> 63))
+}
+
+fn mul_mod(a: i64, b: i64, p: i64) -> i64 {
+    ((a as i128 * b as i128) % p as i128) as i64
+}
+
+// 射影坐标 (X : Y : Z)，无穷远点为 (0 : 1 : 0)
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectivePoint {
+    x: i64,
+    y: i64,
+    z: i64,
+}
+
+impl ConditionallySelectable for ProjectivePoint {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        ProjectivePoint {
+            x: i64::conditional_select(&a.x, &b.x, choice),
+            y: i64::conditional_select(&a.y, &b.y, choice),
+            z: i64::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+impl ProjectivePoint {
+    pub fn identity() -> Self {
+        ProjectivePoint { x: 0, y: 1, z: 0 }
+    }
+
+    pub fn from_coords(curve: &Curve, coords: Coords) -> Self {
+        match coords {
+            Some((x, y)) => ProjectivePoint {
+                x: x.rem_euclid(curve.p),
+                y: y.rem_euclid(curve.p),
+                z: 1,
+            },
+            None => ProjectivePoint::identity(),
+        }
+    }
+
+    // Z 是公开输出的一部分，这里的分支不泄露秘密。z^(p−2) 只在 p 是素数时是逆元
+    pub fn to_coords(self, curve: &Curve) -> Coords {
+        if self.z.ct_eq(&0).unwrap_u8() == 1 {
+            return None;
+        }
+        let p = curve.p;
+        let mut z_inv = 1;
+        let mut base = self.z;
+        let mut exp = p - 2;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                z_inv = mul_mod(z_inv, base, p);
+            }
+            base = mul_mod(base, base, p);
+            exp >>= 1;
+        }
+        Some((mul_mod(self.x, z_inv, p), mul_mod(self.y, z_inv, p)))
+    }
+}
+
+// Renes–Costello–Batina 完全加法公式 (Algorithm 1)，对 P = Q 与无穷远点同样适用。
+// 当 E(F_p) 没有 2 阶点时公式没有例外情况。
+pub fn complete_add(curve: &Curve, lhs: ProjectivePoint, rhs: ProjectivePoint) -> ProjectivePoint {
+    let p = curve.p;
+    let a = curve.a.rem_euclid(p);
+    let b3 = mul_mod(3, curve.b.rem_euclid(p), p);
+    let (x1, y1, z1) = (lhs.x, lhs.y, lhs.z);
+    let (x2, y2, z2) = (rhs.x, rhs.y, rhs.z);
+    let mul = |u, v| mul_mod(u, v, p);
+    let add = |u, v| add_mod(u, v, p);
+    let sub = |u, v| sub_mod(u, v, p);
+
+    let t0 = mul(x1, x2);
+    let t1 = mul(y1, y2);
+    let t2 = mul(z1, z2);
+    let t3 = mul(add(x1, y1), add(x2, y2));
+    let t3 = sub(t3, add(t0, t1));
+    let t4 = mul(add(x1, z1), add(x2, z2));
+    let t4 = sub(t4, add(t0, t2));
+    let t5 = mul(add(y1, z1), add(y2, z2));
+    let t5 = sub(t5, add(t1, t2));
+    let z3 = add(mul(b3, t2), mul(a, t4));
+    let x3 = sub(t1, z3);
+    let z3 = add(t1, z3);
+    let y3 = mul(x3, z3);
+    let t1 = add(add(t0, t0), t0);
+    let t2 = mul(a, t2);
+    let t4 = mul(b3, t4);
+    let t1 = add(t1, t2);
+    let t2 = mul(a, sub(t0, t2));
+    let t4 = add(t4, t2);
+    let y3 = add(y3, mul(t1, t4));
+    let x3 = sub(mul(x3, t3), mul(t5, t4));
+    let z3 = add(mul(z3, t5), mul(t3, t1));
+    ProjectivePoint {
+        x: x3,
+        y: y3,
+        z: z3,
+    }
+}
+
+// 固定 64 轮的 Montgomery ladder，每轮都做一次加法和一次倍点。
+// ladder 中始终有 R1 - R0 = P，公式只在 P 为 2 阶点时出现例外；
+// 这时 kP 只取决于 k 的奇偶，用条件选择代替分支。
+// 完全公式和费马求逆都要求 p 是素数，合数模数直接拒绝（p 是公开的）
+pub fn ladder_mul(curve: &Curve, coords: Coords, k: u64) -> Result<Coords, EcError> {
+    if !field::is_prime(curve.p) {
+        return Err(EcError::NotPrime(curve.p));
+    }
+    let base = ProjectivePoint::from_coords(curve, coords);
+    let mut r0 = ProjectivePoint::identity();
+    let mut r1 = base;
+    for i in (0..u64::BITS).rev() {
+        let bit = Choice::from(((k >> i) & 1) as u8);
+        ProjectivePoint::conditional_swap(&mut r0, &mut r1, bit);
+        r1 = complete_add(curve, r0, r1);
+        r0 = complete_add(curve, r0, r0);
+        ProjectivePoint::conditional_swap(&mut r0, &mut r1, bit);
+    }
+
+    // 基点是公开的，是否为 2 阶点可以直接判断
+    if coords.is_some_and(|(_, y)| y.rem_euclid(curve.p) == 0) {
+        let odd = Choice::from((k & 1) as u8);
+        r0 = ProjectivePoint::conditional_select(&ProjectivePoint::identity(), &base, odd);
+    }
+    Ok(r0.to_coords(curve))
+}
@@ -0,0 +1,1044 @@
+use crate::affine::AffinePoint;
+use crate::dlog::Bsgs;
+use crate::error::EcError;
+use crate::field;
+use crate::jacobian::{JacobianPoint, MulAlgorithm};
+use crate::point::{self, Point};
+use crate::rng;
+use crate::scalar::Scalar;
+use rand_core::RngCore;
+use std::collections::BTreeMap;
+
+// 仿射坐标，None 表示无穷远点
+pub type Coords = Option<(i64, i64)>;
+
+// 项数达到这个值时 msm 改用 Pippenger
+const PIPPENGER_MIN_TERMS: usize = 16;
+
+// 元素阶的分布：阶 -> 该阶的点数（包括无穷远点），以及群的指数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderCensus {
+    pub group_order: u64,
+    pub exponent: u64,
+    pub counts: BTreeMap<u64, usize>,
+}
+
+// 短 Weierstrass 曲线 y^2 = x^3 + ax + b (mod p)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Curve {
+    pub a: i64,
+    pub b: i64,
+    pub p: i64,
+}
+
+impl Curve {
+    // a、b 规范到 [0, p)，同一条曲线只有一种表示
+    pub fn new(a: i64, b: i64, p: i64) -> Self {
+        Curve {
+            a: a.rem_euclid(p),
+            b: b.rem_euclid(p),
+            p,
+        }
+    }
+
+    // 只接受素数模上的非奇异曲线；界面要展示奇异和合数的情形，所以 new 本身不检查
+    pub fn try_new(a: i64, b: i64, p: i64) -> Result<Self, EcError> {
+        if !field::is_prime(p) {
+            return Err(EcError::NotPrime(p));
+        }
+        let curve = Curve::new(a, b, p);
+        if curve.is_singular() {
+            return Err(EcError::SingularCurve);
+        }
+        Ok(curve)
+    }
+
+    // 在 F_p 上随机选取一条非奇异曲线
+    pub fn random<R: RngCore + ?Sized>(rng: &mut R, p: i64) -> Self {
+        loop {
+            let a = rng::uniform_below(rng, p as u64) as i64;
+            let b = rng::uniform_below(rng, p as u64) as i64;
+            let curve = Curve::new(a, b, p);
+            if !curve.is_singular() {
+                return curve;
+            }
+        }
+    }
+
+    pub fn builder() -> CurveBuilder {
+        CurveBuilder::default()
+    }
+
+    // 4a^3 + 27b^2 (mod p)，为 0 时曲线奇异
+    pub fn discriminant(&self) -> i64 {
+        let (a, b, p) = (self.a, self.b, self.p);
+        let four_a3 = field::mul(4, field::mul(field::mul(a, a, p), a, p), p);
+        field::add(four_a3, field::mul(27, field::mul(b, b, p), p), p)
+    }
+
+    pub fn is_singular(&self) -> bool {
+        self.discriminant() == 0
+    }
+
+    pub fn singularity(&self) -> Option<Singularity> {
+        if !self.is_singular() {
+            return None;
+        }
+        let p = self.p;
+        let a = self.a.rem_euclid(p);
+        let b = self.b.rem_euclid(p);
+        if a == 0 {
+            // 判别式为 0 且 a = 0 时 b 也为 0
+            return Some(Singularity::Cusp { x: 0 });
+        }
+        // 二重根 x0 = -3b / (2a)
+        let x = field::mul(
+            field::mul(-3, b, p),
+            field::mod_inverse(field::add(a, a, p), p),
+            p,
+        );
+        Some(Singularity::Node {
+            x,
+            split: field::is_quadratic_residue(field::mul(3, x, p), p),
+        })
+    }
+
+    // j = 1728 * 4a^3 / (4a^3 + 27b^2)，奇异曲线没有 j 不变量
+    pub fn j_invariant(&self) -> Option<i64> {
+        let d = self.discriminant();
+        if d == 0 {
+            return None;
+        }
+        let (a, p) = (self.a, self.p);
+        let four_a3 = field::mul(4, field::mul(field::mul(a, a, p), a, p), p);
+        Some(field::mul(
+            field::mul(1728, four_a3, p),
+            field::mod_inverse(d, p),
+            p,
+        ))
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        field::mul(y, y, self.p) == self.rhs(x)
+    }
+
+    // x³ + ax + b (mod p)，结果在 [0, p)
+    pub fn rhs(&self, x: i64) -> i64 {
+        let p = self.p;
+        let x = x.rem_euclid(p);
+        field::add(
+            field::mul(field::add(field::mul(x, x, p), self.a, p), x, p),
+            self.b,
+            p,
+        )
+    }
+
+    // 点属于这条曲线：参数相同，并且是无穷远点或者坐标满足方程
+    pub fn contains_point(&self, point: &Point) -> bool {
+        point.curve() == *self && point.coords().is_none_or(|(x, y)| self.contains(x, y))
+    }
+
+    // 坐标必须在曲线上，否则 panic；用户输入走 try_point
+    pub fn point(&self, x: i64, y: i64) -> Point {
+        Point::from_coords(self, Some((x, y)))
+    }
+
+    // 用户输入的坐标先检查是否在曲线上
+    pub fn try_point(&self, x: i64, y: i64) -> Result<Point, EcError> {
+        if self.contains(x, y) {
+            Ok(self.point(x, y))
+        } else {
+            Err(EcError::PointNotOnCurve { x, y })
+        }
+    }
+
+    // 解析 "5,1"、"(5, 1)" 或 "Infinity"，坐标同样要在曲线上
+    pub fn parse_point(&self, text: &str) -> Result<Point, EcError> {
+        match point::parse_coords(text)? {
+            Some((x, y)) => self.try_point(x, y),
+            None => Ok(Point::infinity(self)),
+        }
+    }
+
+    pub fn affine(&self, x: i64, y: i64) -> AffinePoint<'_> {
+        AffinePoint::new(self, x, y)
+    }
+
+    pub fn identity(&self) -> AffinePoint<'_> {
+        AffinePoint::identity(self)
+    }
+
+    pub fn add_coords(&self, lhs: Coords, rhs: Coords) -> Coords {
+        self.try_add_coords(lhs, rhs)
+            .expect("slope denominators are invertible modulo a prime p")
+    }
+
+    // 模数为合数时分母可能不可逆，返回的错误里带着 p 的因子
+    pub fn try_add_coords(&self, lhs: Coords, rhs: Coords) -> Result<Coords, EcError> {
+        let p = self.p;
+        let lhs = lhs.map(|(x, y)| (x.rem_euclid(p), y.rem_euclid(p)));
+        let rhs = rhs.map(|(x, y)| (x.rem_euclid(p), y.rem_euclid(p)));
+        let Some((x1, y1)) = lhs else {
+            return Ok(rhs);
+        };
+        let Some((x2, y2)) = rhs else {
+            return Ok(lhs);
+        };
+
+        if x1 == x2 && field::add(y1, y2, p) == 0 {
+            return Ok(None);
+        }
+
+        let m = |u, v| field::mul(u, v, p);
+        let add = |u, v| field::add(u, v, p);
+        let sub = |u, v| field::sub(u, v, p);
+        let lambda = if x1 == x2 && y1 == y2 {
+            m(
+                add(m(3, m(x1, x1)), self.a),
+                field::try_mod_inverse(add(y1, y1), p)?,
+            )
+        } else {
+            m(sub(y2, y1), field::try_mod_inverse(sub(x2, x1), p)?)
+        };
+
+        let x3 = sub(sub(m(lambda, lambda), x1), x2);
+        let y3 = sub(m(lambda, sub(x1, x3)), y1);
+        Ok(Some((x3, y3)))
+    }
+
+    pub fn neg_coords(&self, coords: Coords) -> Coords {
+        coords.map(|(x, y)| (x.rem_euclid(self.p), (-y).rem_euclid(self.p)))
+    }
+
+    // 对一组点同时计算 P + G，所有斜率的分母一起批量求逆
+    pub fn add_to_all(&self, points: &[Coords], g: Coords) -> Vec<Coords> {
+        let p = self.p;
+        let normalize = |c: Coords| c.map(|(x, y)| (x.rem_euclid(p), y.rem_euclid(p)));
+        let Some((gx, gy)) = normalize(g) else {
+            return points.iter().copied().map(normalize).collect();
+        };
+        let mut denominators = Vec::with_capacity(points.len());
+        let mut pending = Vec::with_capacity(points.len());
+        let mut sums = vec![None; points.len()];
+        for (i, &point) in points.iter().enumerate() {
+            match normalize(point) {
+                None => sums[i] = Some((gx, gy)),
+                // P = -G（包括 2 阶点的倍点）结果是无穷远点
+                Some((x, y)) if x == gx && field::add(y, gy, p) == 0 => sums[i] = None,
+                Some((x, y)) => {
+                    denominators.push(if x == gx {
+                        field::add(y, y, p)
+                    } else {
+                        field::sub(gx, x, p)
+                    });
+                    pending.push((i, x, y));
+                }
+            }
+        }
+
+        let m = |u, v| field::mul(u, v, p);
+        let add = |u, v| field::add(u, v, p);
+        let sub = |u, v| field::sub(u, v, p);
+        let inverses = field::batch_inverse(&denominators, p);
+        for (&(i, x, y), inv) in pending.iter().zip(inverses) {
+            let numerator = if x == gx {
+                add(m(3, m(x, x)), self.a)
+            } else {
+                sub(gy, y)
+            };
+            let lambda = m(numerator, inv);
+            let x3 = sub(sub(m(lambda, lambda), x), gx);
+            let y3 = sub(m(lambda, sub(x, x3)), y);
+            sums[i] = Some((x3, y3));
+        }
+        sums
+    }
+
+    // 开启 `ct` feature 时只使用常量时间的 ladder。合数模数不是密码学场景，
+    // 和下面一样退回仿射坐标的版本，分母不可逆时 panic 而不是给出错误的点
+    #[cfg(feature = "ct")]
+    pub fn mul_coords(&self, coords: Coords, k: u64) -> Coords {
+        crate::ct::ladder_mul(self, coords, k).unwrap_or_else(|_| {
+            self.try_mul_coords(coords, k)
+                .expect("slope denominators are invertible modulo a prime p")
+        })
+    }
+
+    // 在 Jacobian 坐标里做倍点-加法，整条链只在最后求一次逆。
+    // 模数是合数时 Z 可能不可逆，退回仿射坐标的版本
+    #[cfg(not(feature = "ct"))]
+    pub fn mul_coords(&self, coords: Coords, k: u64) -> Coords {
+        let product = JacobianPoint::from_coords(self, coords).mul(k, self);
+        product.try_to_coords(self).unwrap_or_else(|_| {
+            self.try_mul_coords(coords, k)
+                .expect("slope denominators are invertible modulo a prime p")
+        })
+    }
+
+    // 按指定的算法做标量乘法，用来对比；两种都在 Jacobian 坐标里算
+    pub fn mul_coords_with(&self, coords: Coords, k: u64, algorithm: MulAlgorithm) -> Coords {
+        JacobianPoint::from_coords(self, coords)
+            .traced_mul(k, self, algorithm)
+            .0
+            .to_coords(self)
+    }
+
+    // 倍点-加法 (double-and-add)。模数为合数时中途可能遇到不可逆的分母
+    pub fn try_mul_coords(&self, coords: Coords, k: u64) -> Result<Coords, EcError> {
+        let mut result = None;
+        let mut addend = coords;
+        let mut k = k;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = self.try_add_coords(result, addend)?;
+            }
+            addend = self.try_add_coords(addend, addend)?;
+            k >>= 1;
+        }
+        Ok(result)
+    }
+
+    // 多标量乘法 Σ k_i P_i，比逐项相乘再相加省掉大部分倍点。两项时用 Shamir 技巧，项数多时
+    // 用 Pippenger 的分桶法，其余情况所有项共用一串倍点（Straus）。都在 Jacobian 坐标里算，
+    // 只在最后求一次逆。运行时间依赖标量，只用于演示
+    pub fn msm(&self, terms: &[(Coords, u64)]) -> Coords {
+        let sum = match *terms {
+            [first, second] => self.shamir(first, second),
+            _ if terms.len() >= PIPPENGER_MIN_TERMS => self.pippenger(terms),
+            _ => self.straus(terms),
+        };
+        sum.to_coords(self)
+    }
+
+    // 所有标量里最长的位数
+    fn max_bits(terms: &[(Coords, u64)]) -> u32 {
+        terms
+            .iter()
+            .map(|(_, k)| u64::BITS - k.leading_zeros())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn straus(&self, terms: &[(Coords, u64)]) -> JacobianPoint {
+        let points: Vec<JacobianPoint> = terms
+            .iter()
+            .map(|&(coords, _)| JacobianPoint::from_coords(self, coords))
+            .collect();
+        let mut result = JacobianPoint::identity();
+        for bit in (0..Self::max_bits(terms)).rev() {
+            result = result.double(self);
+            for (&point, &(_, k)) in points.iter().zip(terms) {
+                if (k >> bit) & 1 == 1 {
+                    result = result.add(point, self);
+                }
+            }
+        }
+        result
+    }
+
+    // aP + bQ：预先算好 P + Q，每一位最多一次加法
+    fn shamir(&self, (p, a): (Coords, u64), (q, b): (Coords, u64)) -> JacobianPoint {
+        let p = JacobianPoint::from_coords(self, p);
+        let q = JacobianPoint::from_coords(self, q);
+        let both = p.add(q, self);
+        let mut result = JacobianPoint::identity();
+        for bit in (0..u64::BITS - (a | b).leading_zeros()).rev() {
+            result = result.double(self);
+            match ((a >> bit) & 1, (b >> bit) & 1) {
+                (1, 1) => result = result.add(both, self),
+                (1, 0) => result = result.add(p, self),
+                (0, 1) => result = result.add(q, self),
+                _ => {}
+            }
+        }
+        result
+    }
+
+    // 标量按 c 位一个窗口切开，每个窗口里把点按这一段的值 j 放进桶 B_j（每项一次加法），
+    // 再从大到小累加：running = Σ_{i≥j} B_i，窗口和 Σ running = Σ j·B_j。
+    // c ≈ log2 n，每个窗口 n + 2^(c+1) 次加法，和项数几乎成线性
+    fn pippenger(&self, terms: &[(Coords, u64)]) -> JacobianPoint {
+        let c = terms.len().ilog2().clamp(1, 16);
+        let mask = (1 << c) - 1;
+        let points: Vec<JacobianPoint> = terms
+            .iter()
+            .map(|&(coords, _)| JacobianPoint::from_coords(self, coords))
+            .collect();
+        let mut result = JacobianPoint::identity();
+        for window in (0..Self::max_bits(terms).div_ceil(c)).rev() {
+            for _ in 0..c {
+                result = result.double(self);
+            }
+            let mut buckets = vec![JacobianPoint::identity(); mask as usize];
+            for (&point, &(_, k)) in points.iter().zip(terms) {
+                let digit = (k >> (window * c)) & mask;
+                if digit != 0 {
+                    let bucket = &mut buckets[digit as usize - 1];
+                    *bucket = bucket.add(point, self);
+                }
+            }
+            let mut running = JacobianPoint::identity();
+            let mut window_sum = JacobianPoint::identity();
+            for bucket in buckets.into_iter().rev() {
+                running = running.add(bucket, self);
+                window_sum = window_sum.add(running, self);
+            }
+            result = result.add(window_sum, self);
+        }
+        result
+    }
+
+    // 找到所有满足曲线的点
+    pub fn points(&self) -> Vec<Point> {
+        let (a, b, p) = (self.a, self.b, self.p);
+        let _span = tracing::debug_span!("enumerate_points", a, b, p).entered();
+        let mut points = Vec::new();
+        let roots = field::SqrtTable::new(p);
+        // 每完成十分之一报告一次进度
+        let tenth = (p / 10).max(1);
+        for x in 0..p {
+            if x > 0 && x % tenth == 0 {
+                tracing::trace!(percent = x * 100 / p, found = points.len(), "enumerating");
+            }
+            points.extend(self.points_with(&roots, x));
+        }
+        tracing::debug!(count = points.len(), "enumerated affine points");
+        points
+    }
+
+    // 横坐标为 x 的点，按 y 从小到大。素数模数用 Tonelli–Shanks，合数只能逐个试
+    pub fn points_at(&self, x: i64) -> Vec<Point> {
+        let (p, rhs) = (self.p, self.rhs(x));
+        if !field::is_prime(p) {
+            return (0..p)
+                .filter(|&y| field::mul(y, y, p) == rhs)
+                .map(|y| self.point(x, y))
+                .collect();
+        }
+        self.tonelli_points(x)
+    }
+
+    fn tonelli_points(&self, x: i64) -> Vec<Point> {
+        let (p, rhs) = (self.p, self.rhs(x));
+        match field::sqrt_mod(rhs, p) {
+            None => Vec::new(),
+            Some(y) if y == (p - y) % p => vec![self.point(x, y)],
+            Some(y) => vec![self.point(x, y), self.point(x, p - y)],
+        }
+    }
+
+    // 同 points_at，但从平方根表里查 y，不用逐个试。p 太大没有建表时逐个 x 求平方根，
+    // 素性在建表时已经判断过，不用每个 x 重新判断
+    pub fn points_with(&self, roots: &field::SqrtTable, x: i64) -> Vec<Point> {
+        match roots.roots(self.rhs(x)) {
+            Some(ys) => ys.iter().map(|&y| self.point(x, y)).collect(),
+            None if roots.prime() => self.tonelli_points(x),
+            None => self.points_at(x),
+        }
+    }
+
+    // nonsingular_points() 的第一个点，找到就停，不用枚举整条曲线
+    pub fn first_point(&self) -> Option<Point> {
+        let singular = self.singularity().map(|s| (s.x(), 0));
+        (0..self.p)
+            .flat_map(|x| self.points_at(x))
+            .find(|point| point.coords() != singular)
+    }
+
+    // 均匀随机的仿射点（奇点除外），没有这样的点时为 None。素数模不用枚举：随机取 x，
+    // 再随机取一位挑 y，只有一个 y 时这一位有一半概率落空，每个点被选中的概率都是 1/(2p)
+    pub fn random_point<R: RngCore + ?Sized>(&self, rng: &mut R) -> Option<Point> {
+        if !field::is_prime(self.p) {
+            let points = self.nonsingular_points();
+            if points.is_empty() {
+                return None;
+            }
+            let index = rng::uniform_below(rng, points.len() as u64) as usize;
+            return Some(points[index].clone());
+        }
+        let singular = self.singularity().map(|s| (s.x(), 0));
+        self.first_point()?;
+        loop {
+            let x = rng::uniform_below(rng, self.p as u64) as i64;
+            let side = rng::uniform_below(rng, 2) as usize;
+            if let Some(point) = self.points_at(x).get(side)
+                && point.coords() != singular
+            {
+                return Some(point.clone());
+            }
+        }
+    }
+
+    // 已知群阶 n 时，从 n 开始依次去掉素因子得到点的阶
+    pub fn order_of(&self, coords: Coords, group_order: u64) -> u64 {
+        let mut order = group_order;
+        for (q, _) in field::factorize(group_order) {
+            while order.is_multiple_of(q) && self.mul_coords(coords, order / q).is_none() {
+                order /= q;
+            }
+        }
+        order
+    }
+
+    // 求 k 使 k·G = Q（小步大步，见 dlog.rs），Q 不在 G 生成的子群里时返回 None。
+    // 要先数出群阶，只适合玩具曲线
+    pub fn discrete_log(&self, g: Coords, q: Coords) -> Option<u64> {
+        let order = self.order_of(g, self.group_order());
+        Bsgs::run(self, g, q, order).log
+    }
+
+    // 非奇异的点，奇异曲线上去掉奇点后剩下的部分仍然构成群
+    pub fn nonsingular_points(&self) -> Vec<Point> {
+        let mut points = self.points();
+        if let Some(s) = self.singularity() {
+            points.retain(|point| point.coords() != Some((s.x(), 0)));
+        }
+        points
+    }
+
+    // E ≅ Z_n1 × Z_n2，n2 | n1，循环群时 n2 = 1。n1 是群的指数（最大的点阶），由 order_census 求出
+    pub fn group_structure(&self) -> (u64, u64) {
+        let census = self.order_census();
+        (census.exponent, census.group_order / census.exponent)
+    }
+
+    // 阶等于群阶的点，E 不是循环群时没有。P 是生成元当且仅当对 #E 的每个素因子 q 都有 (#E / q)·P ≠ O
+    pub fn generators(&self) -> Vec<Point> {
+        let points = self.nonsingular_points();
+        let n = points.len() as u64 + 1;
+        let primes = field::factorize(n);
+        points
+            .into_iter()
+            .filter(|point| {
+                primes
+                    .iter()
+                    .all(|&(q, _)| self.mul_coords(point.coords(), n / q).is_some())
+            })
+            .collect()
+    }
+
+    // 群的阶 #E，包含无穷远点。只计数不收集，p 很大时内存不随 p 增长
+    pub fn group_order(&self) -> u64 {
+        let _span = tracing::debug_span!("group_order", p = self.p).entered();
+        let roots = field::SqrtTable::new(self.p);
+        let singular = self.singularity().map(|s| (s.x(), 0));
+        let affine: u64 = (0..self.p)
+            .map(|x| {
+                self.points_with(&roots, x)
+                    .iter()
+                    .filter(|point| point.coords() != singular)
+                    .count() as u64
+            })
+            .sum();
+        let order = affine + 1;
+        tracing::debug!(order, "counted #E");
+        order
+    }
+
+    // Hasse 定理：|#E − (p + 1)| ≤ 2√p，取整后的上下界
+    pub fn hasse_interval(&self) -> (u64, u64) {
+        let p = self.p as u64;
+        let width = (4 * p as u128).isqrt() as u64;
+        (p + 1 - width, p + 1 + width)
+    }
+
+    // 先定出 E ≅ Z_n1 × Z_n2，再按公式数出每个阶的点数，不逐点求阶。
+    // E[q] 全在 F_p 上要求 q | p − 1（Weil 配对），所以 q ∤ p − 1 或 q² ∤ #E 时 Sylow q 子群是循环的，
+    // 只有剩下的素数才需要在 (#E / q^e)·P 里找最大的阶，一般试几个点就够了
+    pub fn order_census(&self) -> OrderCensus {
+        let _span = tracing::debug_span!("order_census", p = self.p).entered();
+        let census = self.census_of(&self.nonsingular_points());
+        tracing::debug!(
+            group_order = census.group_order,
+            exponent = census.exponent,
+            orders = census.counts.len(),
+            "order census"
+        );
+        census
+    }
+
+    // 已经有了全部非奇异仿射点时直接用，曲线浏览器批量统计时不重新枚举
+    pub fn census_of(&self, points: &[Point]) -> OrderCensus {
+        let group_order = points.len() as u64 + 1;
+        // (q, q 在 n1 里的指数, q 在 n2 里的指数)
+        let mut sylow = Vec::new();
+        for (q, e) in field::factorize(group_order) {
+            let mut a = e;
+            if e >= 2 && (self.p as u64 - 1).is_multiple_of(q) {
+                let cofactor = group_order / q.pow(e);
+                a = 0;
+                for point in points {
+                    let mut r = self.mul_coords(point.coords(), cofactor);
+                    let mut k = 0;
+                    while r.is_some() {
+                        r = self.mul_coords(r, q);
+                        k += 1;
+                    }
+                    a = a.max(k);
+                    if a == e {
+                        break;
+                    }
+                }
+            }
+            sylow.push((q, a, e - a));
+        }
+        let exponent = sylow.iter().map(|&(q, a, _)| q.pow(a)).product();
+
+        // Z_{q^a} × Z_{q^b} 里阶整除 q^k 的元素有 q^(min(k, a) + min(k, b)) 个
+        let mut counts = BTreeMap::from([(1, 1)]);
+        for &(q, a, b) in &sylow {
+            let torsion = |k: u32| q.pow(k.min(a) + k.min(b));
+            let mut next = BTreeMap::new();
+            for (&order, &count) in &counts {
+                for k in 0..=a {
+                    let exact = if k == 0 {
+                        1
+                    } else {
+                        torsion(k) - torsion(k - 1)
+                    };
+                    next.insert(order * q.pow(k), count * exact as usize);
+                }
+            }
+            counts = next;
+        }
+        OrderCensus {
+            group_order,
+            exponent,
+            counts,
+        }
+    }
+}
+
+// 奇异三次曲线的奇点 (x0, 0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Singularity {
+    // y^2 = x^3 三重根，非奇异点构成 (F_p, +)
+    Cusp { x: i64 },
+    // y^2 = (x - x0)^2 (x + 2x0)，切线斜率 ±sqrt(3x0) 在 F_p 中时为 split
+    Node { x: i64, split: bool },
+}
+
+impl Singularity {
+    pub fn x(&self) -> i64 {
+        match *self {
+            Singularity::Cusp { x } | Singularity::Node { x, .. } => x,
+        }
+    }
+
+    // 非奇异点（含无穷远点）构成的群的阶
+    pub fn group_order(&self, p: i64) -> u64 {
+        match self {
+            Singularity::Cusp { .. } => p as u64,
+            Singularity::Node { split: true, .. } => p as u64 - 1,
+            Singularity::Node { split: false, .. } => p as u64 + 1,
+        }
+    }
+}
+
+// 经过完整校验的曲线参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveParams {
+    pub curve: Curve,
+    pub generator: Point,
+    pub order: u64,
+    pub cofactor: u64,
+}
+
+impl CurveParams {
+    // 模生成元阶 n 的标量
+    pub fn scalar(&self, k: i64) -> Scalar {
+        Scalar::new(k, self.order)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CurveBuilder {
+    a: Option<i64>,
+    b: Option<i64>,
+    p: Option<i64>,
+    generator: Option<(i64, i64)>,
+    // 标准参数里写明的生成元阶，给出时必须和算出来的一致
+    order: Option<u64>,
+}
+
+impl CurveBuilder {
+    pub fn a(mut self, a: i64) -> Self {
+        self.a = Some(a);
+        self
+    }
+
+    pub fn b(mut self, b: i64) -> Self {
+        self.b = Some(b);
+        self
+    }
+
+    pub fn prime(mut self, p: i64) -> Self {
+        self.p = Some(p);
+        self
+    }
+
+    pub fn generator(mut self, x: i64, y: i64) -> Self {
+        self.generator = Some((x, y));
+        self
+    }
+
+    pub fn order(mut self, n: u64) -> Self {
+        self.order = Some(n);
+        self
+    }
+
+    // 依次检查：素数模、判别式、生成元在曲线上、阶整除群阶，给了 order 时还要和它相等
+    pub fn build(&self) -> Result<CurveParams, EcError> {
+        let _span = tracing::debug_span!("build_curve").entered();
+        let a = self.a.ok_or(EcError::MissingParameter("a"))?;
+        let b = self.b.ok_or(EcError::MissingParameter("b"))?;
+        let p = self.p.ok_or(EcError::MissingParameter("p"))?;
+        let (gx, gy) = self
+            .generator
+            .ok_or(EcError::MissingParameter("generator"))?;
+
+        let curve = Curve::try_new(a, b, p)?;
+        let generator = curve.try_point(gx, gy)?;
+        let group_order = curve.group_order();
+        let order = curve.order_of(generator.coords(), group_order);
+        // 由 #E 推出的阶必须真的把生成元送到无穷远点 (Lagrange)
+        if curve.mul_coords(generator.coords(), order).is_some() {
+            return Err(EcError::InvalidOrder { order, group_order });
+        }
+        if let Some(claimed) = self.order
+            && claimed != order
+        {
+            return Err(EcError::InvalidOrder {
+                order: claimed,
+                group_order,
+            });
+        }
+
+        Ok(CurveParams {
+            curve,
+            generator,
+            order,
+            cofactor: group_order / order,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_reports_each_validation_failure() {
+        let builder = || Curve::builder().a(2).b(3).prime(97).generator(3, 6);
+        let params = builder().order(5).build().unwrap();
+        assert_eq!((params.order, params.cofactor), (5, 20));
+        assert_eq!(
+            Curve::builder().a(2).b(3).generator(3, 6).build(),
+            Err(EcError::MissingParameter("p"))
+        );
+        assert_eq!(builder().prime(91).build(), Err(EcError::NotPrime(91)));
+        // 4·(−3)³ + 27·2² = 0
+        assert_eq!(builder().a(-3).b(2).build(), Err(EcError::SingularCurve));
+        assert_eq!(
+            builder().generator(3, 7).build(),
+            Err(EcError::PointNotOnCurve { x: 3, y: 7 })
+        );
+        // (3, 6) 的阶是 5，不是 10
+        assert_eq!(
+            builder().order(10).build(),
+            Err(EcError::InvalidOrder {
+                order: 10,
+                group_order: 100
+            })
+        );
+    }
+
+    #[test]
+    fn group_orders_lie_in_the_hasse_interval() {
+        for (a, b, p) in [(2, 3, 97), (1, 1, 5), (0, 7, 101), (3, 5, 1009)] {
+            let curve = Curve::new(a, b, p);
+            let (low, high) = curve.hasse_interval();
+            assert!((low..=high).contains(&curve.group_order()), "{curve:?}");
+        }
+        assert_eq!(Curve::new(2, 3, 97).hasse_interval(), (79, 117));
+    }
+
+    #[test]
+    fn square_root_table_matches_points_at() {
+        // 素数模数的 points_at 走 Tonelli–Shanks，合数时逐个试，一个余数可能有不止两个根
+        for (a, b, p) in [(2, 3, 97), (1, 1, 91), (0, 7, 101), (3, 5, 64)] {
+            let curve = Curve::new(a, b, p);
+            let roots = field::SqrtTable::new(p);
+            for x in 0..p {
+                assert_eq!(
+                    curve.points_with(&roots, x),
+                    curve.points_at(x),
+                    "{curve:?}"
+                );
+            }
+        }
+        assert_eq!(field::SqrtTable::new(15).roots(4), Some(&[2, 7, 8, 13][..]));
+        assert_eq!(field::SqrtTable::new(97).roots(-1), Some(&[22, 75][..]));
+        // 超过上限的模数不建表，逐个 x 求平方根，不会因为 O(p) 的表耗尽内存
+        let p = 1_000_000_007;
+        let (curve, roots) = (Curve::new(1, 1, p), field::SqrtTable::new(p));
+        assert_eq!(roots.roots(4), None);
+        for x in [0, 1, 2, 12_345, p - 1] {
+            assert_eq!(curve.points_with(&roots, x), curve.points_at(x));
+        }
+    }
+
+    #[test]
+    fn random_points_cover_the_curve_evenly() {
+        let mut rng = rng::seeded(3);
+        // (1, 1, 5) 上有 y = 0 的点，(3, 5, 64) 是合数模数，(0, 0, 23) 的奇点 (0, 0) 要跳过
+        for (a, b, p) in [(2, 3, 97), (1, 1, 5), (3, 5, 64), (0, 0, 23)] {
+            let curve = Curve::new(a, b, p);
+            let points = curve.nonsingular_points();
+            let mut counts = BTreeMap::new();
+            for _ in 0..200 * points.len() {
+                let point = curve.random_point(&mut rng).unwrap();
+                *counts.entry(point.coords()).or_insert(0) += 1;
+            }
+            assert_eq!(counts.len(), points.len(), "{curve:?}");
+            assert!(
+                counts.values().all(|&n| (100..300).contains(&n)),
+                "{curve:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn try_new_rejects_singular_curves_and_composite_moduli() {
+        assert_eq!(Curve::try_new(2, 3, 97), Ok(Curve::new(2, 3, 97)));
+        assert_eq!(Curve::try_new(0, 0, 23), Err(EcError::SingularCurve));
+        // 4·(−3)³ + 27·2² = −108 + 108 = 0
+        assert_eq!(Curve::try_new(-3, 2, 101), Err(EcError::SingularCurve));
+        assert_eq!(Curve::try_new(1, 1, 91), Err(EcError::NotPrime(91)));
+    }
+
+    #[test]
+    fn invariants_do_not_overflow_near_2_62() {
+        // a = b = p − 1 时 4a³ + 27b² ≡ −4 + 27 = 23，j = 1728·(−4)/23
+        let p = 4_611_686_018_427_388_039;
+        let curve = Curve::new(p - 1, p - 1, p);
+        assert_eq!(curve.discriminant(), 23);
+        assert_eq!(curve.j_invariant(), Some(3_007_621_316_365_687_551));
+        assert_eq!(curve.singularity(), None);
+        // a = −3k²、b = 2k³ 是二重根在 x = k 的奇异曲线
+        let k = (1 << 40) + 7;
+        let curve = Curve::new(
+            -3 * field::mul(k, k, p),
+            2 * field::mul(field::mul(k, k, p), k, p) % p,
+            p,
+        );
+        assert_eq!(curve.discriminant(), 0);
+        assert_eq!(
+            Curve::try_new(curve.a, curve.b, p),
+            Err(EcError::SingularCurve)
+        );
+        assert!(matches!(curve.singularity(), Some(Singularity::Node { x, .. }) if x == k));
+    }
+
+    #[test]
+    fn new_normalizes_parameters() {
+        assert_eq!(Curve::new(-1, 0, 103), Curve::new(102, 0, 103));
+        assert_eq!(Curve::new(104, -103, 103), Curve::new(1, 0, 103));
+    }
+
+    #[test]
+    fn contains_accepts_any_representative() {
+        let curve = Curve::new(1, 1, 599);
+        assert!(curve.contains(1, 188));
+        assert!(curve.contains(-598, -411));
+        assert!(curve.contains(600, 787));
+        assert_eq!(curve.try_point(-598, -411), Ok(curve.point(1, 188)));
+        assert_eq!(
+            curve.try_point(1, 1),
+            Err(EcError::PointNotOnCurve { x: 1, y: 1 })
+        );
+    }
+
+    #[test]
+    fn arithmetic_normalizes_inputs() {
+        let curve = Curve::new(1, 1, 599);
+        let g = Some((1, 188));
+        let g_raw = Some((-598, 787));
+        assert_eq!(curve.add_coords(g_raw, g_raw), curve.add_coords(g, g));
+        assert_eq!(curve.neg_coords(g_raw), Some((1, 411)));
+        assert_eq!(curve.add_coords(g_raw, None), g);
+        assert_eq!(
+            curve.add_to_all(&[g_raw, None], g_raw),
+            vec![curve.add_coords(g, g), g]
+        );
+    }
+
+    #[test]
+    fn arithmetic_is_exact_beyond_32_bit_primes() {
+        use crate::bigcurve::BigCurve;
+        // i64 直接相乘时 x² 在 p > 2^31.5 就溢出，和 256 位的实现对照
+        let limbs = |v: i64| [v as u64, 0, 0, 0];
+        let small = |(x, y): ([u64; 4], [u64; 4])| (x[0] as i64, y[0] as i64);
+        for p in [4_294_967_311, (1 << 61) - 1] {
+            let curve = Curve::new(-3, 7, p);
+            let big = BigCurve::new(&limbs(p - 3), &limbs(7), &limbs(p)).unwrap();
+            let lift = |(x, y): (i64, i64)| big.point(&limbs(x), &limbs(y)).unwrap();
+            let g = curve.first_point().unwrap().coords().unwrap();
+            let h = (1..)
+                .find_map(|i| curve.points_at(p - i).first().and_then(Point::coords))
+                .unwrap();
+            assert!(curve.contains(h.0, h.1));
+            for (lhs, rhs) in [(g, g), (g, h), (h, h)] {
+                let expected = big.affine(&big.add(&lift(lhs), &lift(rhs))).map(small);
+                assert_eq!(curve.add_coords(Some(lhs), Some(rhs)), expected, "p = {p}");
+                assert_eq!(curve.add_to_all(&[Some(lhs)], Some(rhs)), vec![expected]);
+            }
+            let k = (1 << 40) + 12345;
+            let expected = big.affine(&big.mul(&lift(h), &limbs(k))).map(small);
+            assert_eq!(curve.mul_coords(Some(h), k as u64), expected);
+        }
+    }
+
+    #[test]
+    fn additions_do_not_overflow_near_2_63() {
+        use crate::bigcurve::BigCurve;
+        // p 接近 2^62 和 2^63 时两个坐标相加、相减就超出 i64，同样和 256 位的实现对照
+        let limbs = |v: i64| [v as u64, 0, 0, 0];
+        let small = |(x, y): ([u64; 4], [u64; 4])| (x[0] as i64, y[0] as i64);
+        for p in [4_611_686_018_427_388_039, 9_223_372_036_854_775_783] {
+            let curve = Curve::new(2, 3, p);
+            let big = BigCurve::new(&limbs(2), &limbs(3), &limbs(p)).unwrap();
+            let lift = |(x, y): (i64, i64)| big.point(&limbs(x), &limbs(y)).unwrap();
+            assert_eq!(curve.rhs(3_000_000_021), curve.rhs(3_000_000_021 - p));
+            let first_from = |x: i64| {
+                (x..)
+                    .find_map(|x| curve.points_at(x).first().and_then(Point::coords))
+                    .unwrap()
+            };
+            let (g, h) = (first_from(3_000_000_021), first_from(p - 1));
+            for (lhs, rhs) in [
+                (g, g),
+                (g, h),
+                (h, h),
+                (h, curve.neg_coords(Some(h)).unwrap()),
+            ] {
+                let expected = big.affine(&big.add(&lift(lhs), &lift(rhs))).map(small);
+                assert_eq!(curve.add_coords(Some(lhs), Some(rhs)), expected, "p = {p}");
+                assert_eq!(curve.add_to_all(&[Some(lhs)], Some(rhs)), vec![expected]);
+            }
+            let k = (1 << 40) + 12345;
+            let expected = big.affine(&big.mul(&lift(h), &limbs(k))).map(small);
+            assert_eq!(curve.mul_coords(Some(h), k as u64), expected);
+            assert_eq!(curve.try_mul_coords(Some(h), k as u64), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn msm_matches_the_sum_of_products() {
+        let curve = Curve::new(1, 1, 599);
+        let terms = [
+            (Some((1, 188)), 5),
+            (Some((7, 146)), 300),
+            (None, 9),
+            (Some((1, 411)), 0),
+        ];
+        let expected = terms.iter().fold(None, |acc, &(coords, k)| {
+            curve.add_coords(acc, curve.mul_coords(coords, k))
+        });
+        assert_eq!(curve.msm(&terms), expected);
+        assert_eq!(curve.msm(&[]), None);
+    }
+
+    #[test]
+    fn shamir_and_pippenger_match_straus() {
+        let curve = Curve::new(1, 1, 599);
+        let points = curve.points();
+        let mut rng = rng::seeded(11);
+        for len in [1, 2, 3, 15, 16, 40, 100] {
+            let terms: Vec<(Coords, u64)> = (0..len)
+                .map(|i| {
+                    let point = &points[rng::uniform_below(&mut rng, points.len() as u64) as usize];
+                    // 有大有小，也有 0 和 64 位满的标量
+                    let k = match i % 4 {
+                        0 => rng.next_u64(),
+                        1 => rng::uniform_below(&mut rng, 1000),
+                        2 => 0,
+                        _ => u64::MAX,
+                    };
+                    (if i == 5 { None } else { point.coords() }, k)
+                })
+                .collect();
+            let expected = terms.iter().fold(None, |acc, &(coords, k)| {
+                curve.add_coords(acc, curve.mul_coords(coords, k))
+            });
+            assert_eq!(curve.msm(&terms), expected, "{len} terms");
+            assert_eq!(curve.straus(&terms).to_coords(&curve), expected);
+            assert_eq!(curve.pippenger(&terms).to_coords(&curve), expected);
+            if let [first, second] = terms[..] {
+                assert_eq!(curve.shamir(first, second).to_coords(&curve), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn order_census_matches_per_point_orders() {
+        // Curve(0, 4, 61) ≅ Z_15 × Z_5，Curve(6, 0, 61) 也不是循环群；最后一条是奇异曲线
+        for curve in [
+            Curve::new(1, 1, 599),
+            Curve::new(2, 3, 97),
+            Curve::new(0, 4, 61),
+            Curve::new(6, 0, 61),
+            Curve::new(1, 0, 101),
+            Curve::new(0, 0, 61),
+        ] {
+            let census = curve.order_census();
+            let mut counts = BTreeMap::from([(1, 1)]);
+            for point in curve.nonsingular_points() {
+                *counts
+                    .entry(curve.order_of(point.coords(), census.group_order))
+                    .or_insert(0) += 1;
+            }
+            assert_eq!(census.counts, counts, "{curve:?}");
+            assert_eq!(census.exponent, *counts.keys().last().unwrap(), "{curve:?}");
+        }
+        let census = Curve::new(0, 4, 61).order_census();
+        assert_eq!((census.group_order, census.exponent), (75, 15));
+    }
+
+    #[test]
+    fn group_structure_splits_off_the_non_cyclic_part() {
+        assert_eq!(Curve::new(1, 1, 599).group_structure(), (597, 1));
+        assert_eq!(Curve::new(2, 3, 97).group_structure(), (50, 2));
+        assert_eq!(Curve::new(0, 4, 61).group_structure(), (15, 5));
+        // y² = x³ − x over F_11：四个 2 阶点都在 F_11 上
+        assert_eq!(Curve::new(-1, 0, 11).group_structure(), (6, 2));
+    }
+
+    #[test]
+    fn generators_are_the_points_of_full_order() {
+        for curve in [
+            Curve::new(2, 3, 97),
+            Curve::new(1, 1, 599),
+            Curve::new(0, 4, 61),
+        ] {
+            let census = curve.order_census();
+            let generators = curve.generators();
+            assert_eq!(
+                generators.len(),
+                census.counts.get(&census.group_order).copied().unwrap_or(0),
+                "{curve:?}"
+            );
+            for g in &generators {
+                assert_eq!(g.order(), census.group_order);
+            }
+        }
+        // Z_15 × Z_5 不是循环群
+        assert!(Curve::new(0, 4, 61).generators().is_empty());
+    }
+
+    #[test]
+    fn affine_point_normalizes_coordinates() {
+        let curve = Curve::new(1, 1, 599);
+        assert_eq!(curve.affine(-598, -411).coords(), Some((1, 188)));
+    }
+}
@@ -0,0 +1,96 @@
+// 除法多项式 ψ_k 在一个点上的取值。ψ_k(P) = 0 当且仅当 kP = O，
+// 并且 x(kP) = x − ψ_{k−1}ψ_{k+1} / ψ_k²。
+// 取值序列 W(k) = ψ_k(P) 是椭圆可除序列（EDS），也是秩 1 的椭圆网。
+use crate::curve::Curve;
+use crate::error::EcError;
+use crate::field::{self, mul};
+
+// ψ_0 .. ψ_4
+pub fn initial_values(curve: &Curve, (x, y): (i64, i64)) -> [i64; 5] {
+    let p = curve.p;
+    let m = |a: i64, b: i64| mul(a, b, p);
+    let (a, b) = (curve.a, curve.b);
+    let (x, y) = (x.rem_euclid(p), y.rem_euclid(p));
+    let x2 = m(x, x);
+    let x3 = m(x2, x);
+    let x4 = m(x2, x2);
+    let x6 = m(x3, x3);
+    let a2 = m(a, a);
+    // ψ_3 = 3x⁴ + 6ax² + 12bx − a²
+    let psi3 = (3 * x4 + 6 * m(a, x2) + 12 * m(b, x) - a2).rem_euclid(p);
+    // ψ_4 = 4y(x⁶ + 5ax⁴ + 20bx³ − 5a²x² − 4abx − 8b² − a³)
+    let inner = (x6 + 5 * m(a, x4) + 20 * m(b, x3)
+        - 5 * m(a2, x2)
+        - 4 * m(m(a, b), x)
+        - 8 * m(b, b)
+        - m(a2, a))
+    .rem_euclid(p);
+    let psi4 = m(4 * y % p, inner);
+    [0, 1, 2 * y % p, psi3, psi4]
+}
+
+// ψ_0 .. ψ_len，用倍分公式逐项递推：
+// ψ_{2m+1} = ψ_{m+2}ψ_m³ − ψ_{m−1}ψ_{m+1}³，ψ_{2m} = (ψ_{m+2}ψ_{m−1}² − ψ_{m−2}ψ_{m+1}²)ψ_m / ψ_2。
+// 只需要除以 ψ_2 = 2y，2 阶点没法这样算
+pub fn sequence(curve: &Curve, point: (i64, i64), len: usize) -> Result<Vec<i64>, EcError> {
+    let p = curve.p;
+    let m = |a: i64, b: i64| mul(a, b, p);
+    let mut psi = initial_values(curve, point).to_vec();
+    let inv2 = field::try_mod_inverse(psi[2], p)?;
+    for k in 5..=len {
+        let half = k / 2;
+        let next = if k % 2 == 1 {
+            let cube = |v: i64| m(m(v, v), v);
+            m(psi[half + 2], cube(psi[half])) - m(psi[half - 1], cube(psi[half + 1]))
+        } else {
+            let sq = |v: i64| m(v, v);
+            let diff = m(psi[half + 2], sq(psi[half - 1])) - m(psi[half - 2], sq(psi[half + 1]));
+            m(m(diff.rem_euclid(p), psi[half]), inv2)
+        };
+        psi.push(next.rem_euclid(p));
+    }
+    psi.truncate(len + 1);
+    Ok(psi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    #[test]
+    fn zeros_and_x_coordinates_match_scalar_multiples() {
+        for curve in [
+            Curve::new(2, 3, 97),
+            Curve::new(1, 1, 599),
+            Curve::new(0, 4, 61),
+        ] {
+            for point in curve.points().iter().take(12).map(Point::coords) {
+                let (x, y) = point.unwrap();
+                if y == 0 {
+                    assert!(sequence(&curve, (x, y), 8).is_err());
+                    continue;
+                }
+                let psi = sequence(&curve, (x, y), 40).unwrap();
+                for k in 1..40 {
+                    let multiple = curve.mul_coords(point, k as u64);
+                    assert_eq!(
+                        psi[k] == 0,
+                        multiple.is_none(),
+                        "{curve:?} {point:?} k = {k}"
+                    );
+                    // x(kP) = x − ψ_{k−1}ψ_{k+1} / ψ_k²
+                    if let Some((xk, _)) = multiple {
+                        let p = curve.p;
+                        let ratio = mul(
+                            mul(psi[k - 1], psi[k + 1], p),
+                            field::mod_inverse(mul(psi[k], psi[k], p), p),
+                            p,
+                        );
+                        assert_eq!((x - ratio).rem_euclid(p), xk, "{curve:?} {point:?} k = {k}");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+// 小步大步 (baby-step giant-step) 求 k·G = Q。n 是 G 的阶，m = ⌈√n⌉：
+// 小步把 j·G (0 ≤ j < m) 记进表里，大步从 Q 出发每次减去 m·G，
+// Q − i·m·G 落在表里时 k = i·m + j。两边都不超过 m 步，时间和空间都是 O(√n)，
+// 而逐个试 k 要 O(n) 步。
+//
+// Pollard rho 同样是 O(√n) 步，但只用常数空间：按 x mod 3 把点分成三类，
+// X ↦ X + G、2X、X + Q 走一条伪随机游走，同时记着 X = a·G + b·Q。
+// 有限群里游走迟早进圈，形状像字母 ρ；Floyd 判圈（乌龟一步、兔子两步）找到
+// X_i = X_2i 时 a1·G + b1·Q = a2·G + b2·Q，解 (b1 − b2)·k ≡ a2 − a1 (mod n) 得到 k。
+use crate::curve::{Coords, Curve};
+use crate::field;
+use crate::point::Point;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bsgs {
+    pub order: u64,
+    pub m: u64,
+    // j·G，下标就是 j
+    pub baby: Vec<Coords>,
+    // Q − i·m·G，走到第一个落在表里的为止
+    pub giant: Vec<Coords>,
+    // 命中时的 (i, j) 和 k
+    pub hit: Option<(u64, u64)>,
+    pub log: Option<u64>,
+}
+
+impl Bsgs {
+    // order 是 G 的阶；Q 不在 <G> 里时 log 为 None
+    pub fn run(curve: &Curve, g: Coords, q: Coords, order: u64) -> Self {
+        let _span = tracing::debug_span!("bsgs", order).entered();
+        let m = order.isqrt() + u64::from(order.isqrt().pow(2) < order);
+        let mut baby = Vec::with_capacity(m as usize);
+        let mut table: HashMap<Coords, u64> = HashMap::with_capacity(m as usize);
+        let mut acc = None;
+        for j in 0..m {
+            table.entry(acc).or_insert(j);
+            baby.push(acc);
+            acc = curve.add_coords(acc, g);
+        }
+
+        // acc 现在是 m·G
+        let stride = curve.neg_coords(acc);
+        let mut giant = Vec::new();
+        let mut gamma = q;
+        let mut hit = None;
+        for i in 0..m {
+            giant.push(gamma);
+            if let Some(&j) = table.get(&gamma) {
+                hit = Some((i, j));
+                break;
+            }
+            gamma = curve.add_coords(gamma, stride);
+        }
+        let log = hit.map(|(i, j)| (i * m + j) % order);
+        tracing::debug!(m, giant = giant.len(), ?log, "baby-step giant-step");
+        Bsgs {
+            order,
+            m,
+            baby,
+            giant,
+            hit,
+            log,
+        }
+    }
+}
+
+// 换起点的次数上限；b1 ≡ b2 或者 Q 不在 <G> 里时换一个起点重走
+pub const RHO_ATTEMPTS: u64 = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rho {
+    pub order: u64,
+    // 成功（或最后一次）尝试的起点 X_0 = a0·G + b0·Q
+    pub start: (u64, u64),
+    // X_0, X_1, ... 走到第一次重复为止，最后一个点等于 walk[tail]
+    pub walk: Vec<Coords>,
+    // 圈的入口下标 μ 和圈长 λ
+    pub tail: usize,
+    pub cycle: usize,
+    // 乌龟和兔子相遇时乌龟走的步数
+    pub meeting: usize,
+    pub attempts: u64,
+    pub log: Option<u64>,
+}
+
+// 游走的一步，系数模 n
+fn rho_step(curve: &Curve, g: Coords, q: Coords, n: u64, (x, a, b): Walker) -> Walker {
+    // 无穷远点归到加 G 的一类，否则倍点会停在 O 上
+    match x.map_or(0, |(x, _)| x % 3) {
+        0 => (curve.add_coords(x, g), (a + 1) % n, b),
+        1 => (curve.add_coords(x, x), 2 * a % n, 2 * b % n),
+        _ => (curve.add_coords(x, q), a, (b + 1) % n),
+    }
+}
+
+type Walker = (Coords, u64, u64);
+
+impl Rho {
+    // order 是 G 的阶；Q 不在 <G> 里时 log 为 None
+    pub fn run(curve: &Curve, g: Coords, q: Coords, order: u64) -> Self {
+        let _span = tracing::debug_span!("pollard_rho", order).entered();
+        let n = order;
+        let step = |walker| rho_step(curve, g, q, n, walker);
+        let mut rho = Rho {
+            order,
+            start: (0, 0),
+            walk: Vec::new(),
+            tail: 0,
+            cycle: 0,
+            meeting: 0,
+            attempts: 0,
+            log: None,
+        };
+        for attempt in 0..RHO_ATTEMPTS {
+            let (a0, b0) = ((attempt + 1) % n, attempt % n);
+            let x0 = curve.add_coords(curve.mul_coords(g, a0), curve.mul_coords(q, b0));
+            let (mut tortoise, mut hare) = (step((x0, a0, b0)), step(step((x0, a0, b0))));
+            let mut meeting = 1;
+            while tortoise.0 != hare.0 {
+                tortoise = step(tortoise);
+                hare = step(step(hare));
+                meeting += 1;
+            }
+            rho.start = (a0, b0);
+            rho.meeting = meeting;
+            rho.attempts = attempt + 1;
+            // (b1 − b2)·k ≡ a2 − a1 (mod n)
+            let (_, a1, b1) = tortoise;
+            let (_, a2, b2) = hare;
+            let u = (b1 + n - b2) % n;
+            let v = (a2 + n - a1) % n;
+            rho.log = solve_linear(u, v, n)
+                .into_iter()
+                .find(|&k| curve.mul_coords(g, k) == q);
+            tracing::debug!(attempt, meeting, ?rho.log, "rho collision");
+            if rho.log.is_some() {
+                break;
+            }
+        }
+        // 再走一遍记下 ρ 的形状；Floyd 相遇时兔子已经走过 2·meeting ≥ μ + λ 步
+        let mut seen: HashMap<Coords, usize> = HashMap::new();
+        let (a0, b0) = rho.start;
+        let x0 = curve.add_coords(curve.mul_coords(g, a0), curve.mul_coords(q, b0));
+        let mut walker = (x0, a0, b0);
+        loop {
+            rho.walk.push(walker.0);
+            if let Some(&first) = seen.get(&walker.0) {
+                rho.tail = first;
+                rho.cycle = rho.walk.len() - 1 - first;
+                break;
+            }
+            seen.insert(walker.0, rho.walk.len() - 1);
+            walker = step(walker);
+        }
+        rho
+    }
+}
+
+// u·k ≡ v (mod n) 的全部解；d = gcd(u, n) 不整除 v 或者 u ≡ 0 时没有可用的解
+fn solve_linear(u: u64, v: u64, n: u64) -> Vec<u64> {
+    let d = gcd(u, n);
+    if u == 0 || !v.is_multiple_of(d) {
+        return Vec::new();
+    }
+    let m = n / d;
+    let k0 = if m == 1 {
+        0
+    } else {
+        (v / d) as i128 * field::mod_inverse((u / d) as i64, m as i64) as i128 % m as i128
+    } as u64;
+    (0..d).map(|t| k0 + t * m).collect()
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+// 求 k 使 k·G = Q；两个点必须在同一条曲线上，模数必须是素数
+pub fn pollard_rho(g: &Point, q: &Point) -> Option<u64> {
+    let curve = g.curve();
+    if q.curve() != curve {
+        return None;
+    }
+    Rho::run(&curve, g.coords(), q.coords(), g.order()).log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // y² = x³ + 2x + 3 over F_97 是 Z_50 × Z_2
+    fn curve_and_generator() -> (Curve, Coords) {
+        let curve = Curve::new(2, 3, 97);
+        let g = curve
+            .points()
+            .iter()
+            .map(|point| point.coords())
+            .find(|&g| curve.order_of(g, 100) == 50)
+            .unwrap();
+        (curve, g)
+    }
+
+    #[test]
+    fn recovers_every_multiple() {
+        let (curve, g) = curve_and_generator();
+        for k in 0..50 {
+            let bsgs = Bsgs::run(&curve, g, curve.mul_coords(g, k), 50);
+            assert_eq!(bsgs.log, Some(k));
+            assert_eq!((bsgs.m, bsgs.baby.len()), (8, 8));
+            assert!(bsgs.giant.len() <= 8);
+        }
+    }
+
+    #[test]
+    fn points_outside_the_subgroup_have_no_log() {
+        let (curve, g) = curve_and_generator();
+        let subgroup: Vec<Coords> = (0..50).map(|k| curve.mul_coords(g, k)).collect();
+        let q = curve
+            .points()
+            .iter()
+            .map(|point| point.coords())
+            .find(|q| !subgroup.contains(q))
+            .unwrap();
+        let bsgs = Bsgs::run(&curve, g, q, 50);
+        assert_eq!((bsgs.hit, bsgs.log), (None, None));
+        assert_eq!(bsgs.giant.len(), 8);
+    }
+
+    #[test]
+    fn rho_recovers_every_multiple() {
+        // n = 50 不是素数，b1 − b2 和 n 不互素时要在 gcd 个候选里挑
+        let (curve, g) = curve_and_generator();
+        for k in 0..50 {
+            let rho = Rho::run(&curve, g, curve.mul_coords(g, k), 50);
+            assert_eq!(rho.log, Some(k), "k = {k}");
+        }
+    }
+
+    #[test]
+    fn rho_walk_has_a_tail_and_a_cycle() {
+        let curve = Curve::new(1, 1, 599);
+        let g = curve.point(1, 188);
+        let q = &g * 123;
+        assert_eq!(pollard_rho(&g, &q), Some(123));
+        let rho = Rho::run(&curve, g.coords(), q.coords(), 597);
+        assert_eq!(rho.walk.len(), rho.tail + rho.cycle + 1);
+        assert_eq!(rho.walk.last(), rho.walk.get(rho.tail));
+        assert!(rho.cycle > 0);
+        // 相遇的步数是圈长的倍数，并且不早于进圈
+        assert!(rho.meeting >= rho.tail && rho.meeting.is_multiple_of(rho.cycle));
+        let (a0, b0) = rho.start;
+        assert_eq!(rho.walk[0], (&g * a0 + &q * b0).coords());
+    }
+
+    #[test]
+    fn rho_finds_no_log_outside_the_subgroup() {
+        let (curve, g) = curve_and_generator();
+        let subgroup: Vec<Coords> = (0..50).map(|k| curve.mul_coords(g, k)).collect();
+        let q = curve
+            .points()
+            .iter()
+            .map(|point| point.coords())
+            .find(|q| !subgroup.contains(q))
+            .unwrap();
+        let rho = Rho::run(&curve, g, q, 50);
+        assert_eq!((rho.log, rho.attempts), (None, RHO_ATTEMPTS));
+        let other = Curve::new(2, 3, 97).point(3, 6);
+        assert_eq!(
+            pollard_rho(&Curve::new(1, 1, 599).point(1, 188), &other),
+            None
+        );
+    }
+}
@@ -0,0 +1,336 @@
+// ECDH 密钥交换和中间人攻击。Alice 发 A = a·G，Bob 发 B = b·G，双方算出同一个 abG。
+// Mallory 在中间把 A、B 都换成自己的点，分别和两边各协商出一个密钥，再在中间转发。
+// Alice 和 Bob 用事先交换过的身份密钥给自己的 A、B 签名后，Mallory 换掉的点没有
+// 合法的签名，接收方会拒绝。
+use crate::curve::Coords;
+use crate::protocol::{Entry, Interceptor, Network, Party};
+use crate::rng::{self, DemoRng};
+use crate::scalar::{Scalar, SecretScalar};
+use crate::schnorr::{self, KeyPair, Signature};
+use crate::subgroup::PrimeGroup;
+use crate::x25519;
+use rand_core::RngCore;
+use std::fmt;
+
+// 一方发出的临时公钥，认证模式下带着身份密钥的签名
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyShare {
+    pub public: Coords,
+    pub signature: Option<Signature>,
+}
+
+impl fmt::Display for KeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key share {:?}", self.public)?;
+        if let Some(signature) = &self.signature {
+            write!(
+                f,
+                " signed (R = {:?}, s = {})",
+                signature.r,
+                signature.s.value()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// 中间人：对每个接收方各生成一个临时密钥，把转发的公钥换成自己的
+pub struct Mallory {
+    group: PrimeGroup,
+    rng: DemoRng,
+    // 冒充对方时出示给某一方的私钥
+    keys: Vec<(Party, Scalar)>,
+    // 截获的各方真实公钥
+    seen: Vec<(Party, Coords)>,
+}
+
+impl Interceptor<KeyShare> for Mallory {
+    fn intercept(&mut self, from: Party, to: Party, share: KeyShare) -> KeyShare {
+        let secret = Scalar::random(&mut self.rng, self.group.q);
+        self.keys.push((to, secret));
+        self.seen.push((from, share.public));
+        // 她换得了公钥，却没法替 from 签名，只能把原来的签名留着
+        KeyShare {
+            public: self.group.base_mul(secret),
+            signature: share.signature,
+        }
+    }
+}
+
+impl Mallory {
+    // 和某一方协商出的密钥：出示给它的私钥乘它的真实公钥
+    pub fn secret_with(&self, party: Party) -> Option<Coords> {
+        let (_, secret) = self.keys.iter().find(|(p, _)| *p == party)?;
+        let (_, public) = self.seen.iter().find(|(p, _)| *p == party)?;
+        Some(self.group.mul(*public, *secret))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub mallory: bool,
+    pub authenticate: bool,
+    // 另外用真实参数（X25519）跑一遍诚实的交换
+    pub x25519: bool,
+}
+
+// X25519 版本的交换：私钥是 32 个随机字节，公钥和共享密钥都是 u 坐标的编码
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RealWorld {
+    pub alice_public: [u8; 32],
+    pub bob_public: [u8; 32],
+    pub alice: [u8; 32],
+    pub bob: [u8; 32],
+}
+
+impl RealWorld {
+    pub fn run(seed: u64) -> Self {
+        // 单独的随机源，打开这个选项不会改变玩具群里的密钥
+        let mut rng = rng::seeded(seed ^ 0x7832_3535_3139);
+        let mut secret = || {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        };
+        let (a, b) = (secret(), secret());
+        let alice_public = x25519::public_key(&a);
+        let bob_public = x25519::public_key(&b);
+        RealWorld {
+            alice_public,
+            bob_public,
+            alice: x25519::x25519(&a, &bob_public),
+            bob: x25519::x25519(&b, &alice_public),
+        }
+    }
+}
+
+pub struct Exchange {
+    pub transcript: Vec<Entry>,
+    // 私钥 a、b 和各自发出的公钥 A = a·G、B = b·G
+    pub secrets: (SecretScalar, SecretScalar),
+    pub shares: (Coords, Coords),
+    // 各自算出的共享密钥，拒绝了对方的公钥时为 None
+    pub alice: Option<Coords>,
+    pub bob: Option<Coords>,
+    // Mallory 分别和 Alice、Bob 协商出的密钥
+    pub mallory: Option<(Coords, Coords)>,
+    pub real_world: Option<RealWorld>,
+}
+
+impl Exchange {
+    // secrets 是手动选的私钥 (a, b)，None 时从种子随机生成
+    pub fn run(
+        group: &PrimeGroup,
+        config: Config,
+        secrets: Option<&(SecretScalar, SecretScalar)>,
+        seed: u64,
+    ) -> Self {
+        let _span = tracing::debug_span!("ecdh", ?config).entered();
+        let mut rng = rng::seeded(seed);
+        let interceptor = config.mallory.then(|| Mallory {
+            group: *group,
+            // Mallory 有自己的随机源，开关她不会改变 Alice 和 Bob 的密钥
+            rng: rng::seeded(seed ^ 0x6d616c6c6f7279),
+            keys: vec![],
+            seen: vec![],
+        });
+        let mut network = Network::new(interceptor);
+
+        // 身份密钥事先通过可信的途径交换过（面对面、证书等）
+        let alice_id = KeyPair::generate(group, &mut rng);
+        let bob_id = KeyPair::generate(group, &mut rng);
+        if config.authenticate {
+            network.note(
+                Party::Alice,
+                format!(
+                    "identity key {:?}, known to Bob in advance",
+                    alice_id.public
+                ),
+            );
+            network.note(
+                Party::Bob,
+                format!(
+                    "identity key {:?}, known to Alice in advance",
+                    bob_id.public
+                ),
+            );
+        }
+
+        // 照常抽取随机私钥，手动选了私钥时签名用的随机数也不变
+        let (a, b) = (
+            SecretScalar::new(Scalar::random(&mut rng, group.q)),
+            SecretScalar::new(Scalar::random(&mut rng, group.q)),
+        );
+        let reduce = |secret: &SecretScalar| {
+            SecretScalar::new(Scalar::from_u64(secret.expose().value(), group.q))
+        };
+        let (a, b) = match secrets {
+            Some((a, b)) => (reduce(a), reduce(b)),
+            None => (a, b),
+        };
+        let share = |id: &KeyPair, secret: Scalar, rng: &mut DemoRng| {
+            let public = group.base_mul(secret);
+            KeyShare {
+                public,
+                signature: config
+                    .authenticate
+                    .then(|| id.sign(group, &schnorr::encode(public), rng)),
+            }
+        };
+        let share_a = share(&alice_id, *a.expose(), &mut rng);
+        let share_b = share(&bob_id, *b.expose(), &mut rng);
+        let to_bob = network.send(Party::Alice, Party::Bob, share_a);
+        let to_alice = network.send(Party::Bob, Party::Alice, share_b);
+
+        let mut finish = |party: Party, peer: Coords, received: KeyShare, secret: Scalar| {
+            if config.authenticate {
+                let signed = received.signature.is_some_and(|signature| {
+                    schnorr::verify(group, peer, &schnorr::encode(received.public), &signature)
+                });
+                if !signed {
+                    network.note(
+                        party,
+                        "signature does not match the peer's identity key, abort",
+                    );
+                    return None;
+                }
+            }
+            let shared = group.mul(received.public, secret);
+            network.note(party, format!("shared secret {shared:?}"));
+            Some(shared)
+        };
+        let bob = finish(Party::Bob, alice_id.public, to_bob, *b.expose());
+        let alice = finish(Party::Alice, bob_id.public, to_alice, *a.expose());
+
+        let mallory = network.interceptor.as_ref().and_then(|mallory| {
+            Some((
+                mallory.secret_with(Party::Alice)?,
+                mallory.secret_with(Party::Bob)?,
+            ))
+        });
+        if let Some((with_alice, with_bob)) = mallory {
+            network.note(
+                Party::Mallory,
+                format!("shares {with_alice:?} with Alice and {with_bob:?} with Bob"),
+            );
+        }
+        Exchange {
+            transcript: network.transcript,
+            secrets: (a, b),
+            shares: (share_a.public, share_b.public),
+            alice,
+            bob,
+            mallory,
+            real_world: config.x25519.then(|| RealWorld::run(seed)),
+        }
+    }
+
+    // Alice 和 Bob 都接受并且得到同一个密钥
+    pub fn agreed(&self) -> bool {
+        self.alice.is_some() && self.alice == self.bob
+    }
+
+    // Mallory 能解开双方的通信：两边都接受了她的公钥
+    pub fn compromised(&self) -> bool {
+        self.mallory.is_some_and(|(with_alice, with_bob)| {
+            self.alice == Some(with_alice) && self.bob == Some(with_bob)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+
+    fn run(mallory: bool, authenticate: bool) -> Exchange {
+        let group = PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap();
+        Exchange::run(
+            &group,
+            Config {
+                mallory,
+                authenticate,
+                x25519: false,
+            },
+            None,
+            21,
+        )
+    }
+
+    #[test]
+    fn honest_exchange_agrees_on_a_secret() {
+        for authenticate in [false, true] {
+            let exchange = run(false, authenticate);
+            assert!(exchange.agreed());
+            assert!(!exchange.compromised());
+        }
+    }
+
+    #[test]
+    fn mallory_bridges_an_unauthenticated_exchange() {
+        let exchange = run(true, false);
+        assert!(!exchange.agreed());
+        assert!(exchange.compromised());
+        assert_ne!(exchange.alice, exchange.bob);
+        let replaced = exchange
+            .transcript
+            .iter()
+            .filter(|entry| entry.party == Party::Mallory && entry.text.starts_with("replaced"))
+            .count();
+        assert_eq!(replaced, 2);
+    }
+
+    #[test]
+    fn signatures_expose_the_substitution() {
+        let exchange = run(true, true);
+        assert_eq!((exchange.alice, exchange.bob), (None, None));
+        assert!(!exchange.compromised());
+        assert!(
+            exchange
+                .transcript
+                .iter()
+                .any(|entry| entry.text.ends_with("abort"))
+        );
+    }
+
+    #[test]
+    fn chosen_secrets_meet_at_abg() {
+        let group = PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap();
+        // 手动选的值按群的阶重新约化
+        let secret = |value| SecretScalar::new(Scalar::from_u64(value, u64::MAX));
+        let exchange = Exchange::run(
+            &group,
+            Config {
+                mallory: false,
+                authenticate: false,
+                x25519: false,
+            },
+            Some(&(secret(7), secret(11))),
+            21,
+        );
+        let (a, b) = (*exchange.secrets.0.expose(), *exchange.secrets.1.expose());
+        assert_eq!((a.value(), b.value()), (7, 11));
+        assert_eq!(exchange.shares, (group.base_mul(a), group.base_mul(b)));
+        assert!(exchange.agreed());
+        assert_eq!(exchange.alice, Some(group.base_mul(a * b)));
+    }
+
+    #[test]
+    fn x25519_option_runs_a_real_world_exchange() {
+        let group = PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap();
+        let config = |x25519| Config {
+            mallory: false,
+            authenticate: false,
+            x25519,
+        };
+        let toy = Exchange::run(&group, config(false), None, 21);
+        let exchange = Exchange::run(&group, config(true), None, 21);
+        // 玩具群里的交换不受影响
+        assert_eq!((toy.alice, toy.bob), (exchange.alice, exchange.bob));
+        assert_eq!(toy.real_world, None);
+        let real = exchange.real_world.unwrap();
+        assert_eq!(real.alice, real.bob);
+        assert_ne!(real.alice_public, real.bob_public);
+        assert_ne!(RealWorld::run(22), real);
+    }
+}
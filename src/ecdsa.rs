@@ -0,0 +1,461 @@
+// ECDSA：R = k·G，r = x(R) mod q，s = k⁻¹(z + r·d) mod q，z 是消息哈希。
+// 验证时算 u1 = z·s⁻¹、u2 = r·s⁻¹，检查 x(u1·G + u2·Q) mod q == r。
+// (r, s) 和 (r, q − s) 同时有效：-k 给出 -R，x 坐标相同。不改私钥就能造出另一个有效签名，
+// 用签名当交易 ID 的系统会出问题，所以约定 s ≤ q/2（low-s），验证方可以拒绝高的那一个。
+// 批量验证需要 R 本身（只有 r 时 y 的符号未知），sign_full 一起返回 R（ECDSA*）。
+// nonce 默认按 RFC 6979 由私钥和消息确定；随机 nonce 只留给攻击演示。
+use crate::batch::Equation;
+use crate::curve::Coords;
+use crate::rfc6979::Rfc6979;
+use crate::scalar::{Scalar, SecretScalar};
+use crate::sha256::sha256;
+use crate::subgroup::PrimeGroup;
+use rand_core::RngCore;
+use std::fmt;
+
+pub struct SigningKey {
+    secret: SecretScalar,
+    pub public: Coords,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub r: Scalar,
+    pub s: Scalar,
+}
+
+// 带着 R = k·G 的签名
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FullSignature {
+    pub signature: Signature,
+    pub point: Coords,
+}
+
+impl Signature {
+    pub fn is_low_s(&self) -> bool {
+        self.s.value() <= self.s.modulus() / 2
+    }
+
+    // 另一个同样有效的签名 (r, q − s)
+    pub fn malleate(self) -> Self {
+        Signature { s: -self.s, ..self }
+    }
+
+    // 两个有效签名里取 s 小的那一个
+    pub fn normalize_s(self) -> Self {
+        if self.is_low_s() {
+            self
+        } else {
+            self.malleate()
+        }
+    }
+}
+
+// SHA-256 取前 8 字节，再只保留和 q 一样多的高位（SEC 1 的 bits2int），最后模 q
+pub fn hash_message(group: &PrimeGroup, message: &[u8]) -> Scalar {
+    let digest = sha256(message);
+    let e = u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"));
+    let bits = u64::BITS - group.q.leading_zeros();
+    Scalar::from_u64(e >> (u64::BITS - bits), group.q)
+}
+
+// r 或 s 为 0 时签名无效，换一个 nonce 重来
+pub fn sign_with_nonce(
+    group: &PrimeGroup,
+    secret: Scalar,
+    z: Scalar,
+    k: Scalar,
+) -> Option<FullSignature> {
+    let point = group.base_mul(k);
+    let (x, _) = point?;
+    let r = Scalar::from_u64(x as u64, group.q);
+    let s = k.invert()? * (z + r * secret);
+    (!r.is_zero() && !s.is_zero()).then_some(FullSignature {
+        signature: Signature { r, s },
+        point,
+    })
+}
+
+// RFC 6979 给出的候选里第一个让 r、s 都不为 0 的 k
+pub fn deterministic_nonce(group: &PrimeGroup, secret: Scalar, message: &[u8]) -> Scalar {
+    let z = hash_message(group, message);
+    let mut nonces = Rfc6979::new(
+        &[group.q, 0, 0, 0],
+        &[secret.value(), 0, 0, 0],
+        &sha256(message),
+    );
+    loop {
+        let k = Scalar::from_u64(nonces.next_nonce()[0], group.q);
+        if sign_with_nonce(group, secret, z, k).is_some() {
+            return k;
+        }
+    }
+}
+
+// 批量验证用的等式 u1·G + u2·Q − R = O，另外要求 x(R) mod q == r
+pub fn equation(
+    group: &PrimeGroup,
+    public: Coords,
+    message: &[u8],
+    full: &FullSignature,
+) -> Option<Equation> {
+    let Signature { r, s } = full.signature;
+    if public.is_none() || r.modulus() != group.q || s.modulus() != group.q || r.is_zero() {
+        return None;
+    }
+    let (x, _) = full.point?;
+    if Scalar::from_u64(x as u64, group.q) != r
+        || !group.contains(full.point)
+        || !group.contains(public)
+    {
+        return None;
+    }
+    let w = s.invert()?;
+    let z = hash_message(group, message);
+    Some(Equation {
+        terms: vec![
+            (group.g, z * w),
+            (public, r * w),
+            (group.curve.neg_coords(full.point), Scalar::one(group.q)),
+        ],
+    })
+}
+
+// 同一个 k 签了两条消息时 r 相同，s1 − s2 = k⁻¹(z1 − z2)，
+// 于是 k = (z1 − z2)/(s1 − s2)，再由 s1·k = z1 + r·d 得 d = (s1·k − z1)/r
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceReuse {
+    pub z1: Scalar,
+    pub z2: Scalar,
+    // 第二个签名按 low-s 取过反时，换回和第一个签名同一个 k 的那个 s
+    pub s2: Scalar,
+    pub nonce: Scalar,
+    pub secret: Scalar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReuseFailure {
+    // r 不同，两个签名用的不是同一个 k
+    DifferentR,
+    // z1 = z2，两个方程其实是同一个
+    SameHash,
+    // 解出的 d 对不上公钥
+    WrongKey,
+}
+
+impl fmt::Display for ReuseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReuseFailure::DifferentR => "the signatures have different r, so k was not reused",
+            ReuseFailure::SameHash => "both messages hash to the same z, which gives one equation",
+            ReuseFailure::WrongKey => "the recovered d does not match the public key",
+        })
+    }
+}
+
+pub fn recover_reused_nonce(
+    group: &PrimeGroup,
+    public: Coords,
+    (m1, sig1): (&[u8], &Signature),
+    (m2, sig2): (&[u8], &Signature),
+) -> Result<NonceReuse, ReuseFailure> {
+    if sig1.r != sig2.r {
+        return Err(ReuseFailure::DifferentR);
+    }
+    let (z1, z2) = (hash_message(group, m1), hash_message(group, m2));
+    if z1 == z2 {
+        return Err(ReuseFailure::SameHash);
+    }
+    // (r, s) 和 (r, −s) 都有效，不知道哪一个是原来的就两个都试
+    [sig2.s, -sig2.s]
+        .into_iter()
+        .find_map(|s2| {
+            let nonce = (z1 - z2).checked_div(sig1.s - s2)?;
+            let secret = (sig1.s * nonce - z1).checked_div(sig1.r)?;
+            (group.base_mul(secret) == public).then_some(NonceReuse {
+                z1,
+                z2,
+                s2,
+                nonce,
+                secret,
+            })
+        })
+        .ok_or(ReuseFailure::WrongKey)
+}
+
+// 签名和验证的中间值，界面逐行展示：R = k·G、r、s，再由 w = s⁻¹ 算出 u1、u2 和 X = u1·G + u2·Q
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Steps {
+    pub z: Scalar,
+    pub public: Coords,
+    pub point: Coords,
+    pub signature: Signature,
+    pub w: Scalar,
+    pub u1: Scalar,
+    pub u2: Scalar,
+    pub check: Coords,
+}
+
+impl Steps {
+    // 私钥和 nonce 都是明文给出的，只用于演示；r 或 s 为 0 时换一个 k
+    pub fn run(group: &PrimeGroup, secret: Scalar, message: &[u8], k: Scalar) -> Option<Self> {
+        let z = hash_message(group, message);
+        let FullSignature { signature, point } = sign_with_nonce(group, secret, z, k)?;
+        let w = signature.s.invert()?;
+        let (u1, u2) = (z * w, signature.r * w);
+        let public = group.base_mul(secret);
+        Some(Steps {
+            z,
+            public,
+            point,
+            signature,
+            w,
+            u1,
+            u2,
+            check: group
+                .curve
+                .add_coords(group.base_mul(u1), group.mul(public, u2)),
+        })
+    }
+
+    // x(X) mod q == r
+    pub fn accepts(&self) -> bool {
+        self.check.is_some_and(|(x, _)| {
+            Scalar::from_u64(x as u64, self.signature.r.modulus()) == self.signature.r
+        })
+    }
+}
+
+impl SigningKey {
+    pub fn generate<R: RngCore + ?Sized>(group: &PrimeGroup, rng: &mut R) -> Self {
+        let secret = Scalar::random(rng, group.q);
+        SigningKey {
+            public: group.base_mul(secret),
+            secret: SecretScalar::new(secret),
+        }
+    }
+
+    pub fn sign(&self, group: &PrimeGroup, message: &[u8]) -> Signature {
+        self.sign_full(group, message).signature
+    }
+
+    pub fn sign_full(&self, group: &PrimeGroup, message: &[u8]) -> FullSignature {
+        let secret = self.secret.expose();
+        let k = SecretScalar::new(deterministic_nonce(group, *secret, message));
+        let z = hash_message(group, message);
+        sign_with_nonce(group, *secret, z, *k.expose()).expect("the nonce was checked")
+    }
+
+    // 随机的 k：签名不再能复现，RNG 出问题时会泄露私钥
+    pub fn sign_random<R: RngCore + ?Sized>(
+        &self,
+        group: &PrimeGroup,
+        message: &[u8],
+        rng: &mut R,
+    ) -> Signature {
+        let z = hash_message(group, message);
+        loop {
+            let k = SecretScalar::new(Scalar::random(rng, group.q));
+            if let Some(full) = sign_with_nonce(group, *self.secret.expose(), z, *k.expose()) {
+                return full.signature;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Verifier {
+    // 只接受 s ≤ q/2 的签名
+    pub reject_high_s: bool,
+}
+
+impl Verifier {
+    pub fn verify(
+        &self,
+        group: &PrimeGroup,
+        public: Coords,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        let Signature { r, s } = *signature;
+        if public.is_none() || r.modulus() != group.q || s.modulus() != group.q {
+            return false;
+        }
+        if r.is_zero() || s.is_zero() || (self.reject_high_s && !signature.is_low_s()) {
+            return false;
+        }
+        let Some(w) = s.invert() else {
+            return false;
+        };
+        let z = hash_message(group, message);
+        let (u1, u2) = (z * w, r * w);
+        let point = group
+            .curve
+            .msm(&[(group.g, u1.value()), (public, u2.value())]);
+        point.is_some_and(|(x, _)| Scalar::from_u64(x as u64, group.q) == r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+    use crate::rng;
+
+    fn group() -> PrimeGroup {
+        PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap()
+    }
+
+    #[test]
+    fn both_signatures_verify_until_high_s_is_rejected() {
+        let group = group();
+        let mut rng = rng::seeded(8);
+        let key = SigningKey::generate(&group, &mut rng);
+        let strict = Verifier {
+            reject_high_s: true,
+        };
+        for _ in 0..16 {
+            let signature = key.sign_random(&group, b"pay 5 coins", &mut rng);
+            let other = signature.malleate();
+            assert_ne!(signature, other);
+            assert_eq!(other.malleate(), signature);
+            assert!(Verifier::default().verify(&group, key.public, b"pay 5 coins", &signature));
+            assert!(Verifier::default().verify(&group, key.public, b"pay 5 coins", &other));
+            assert!(!Verifier::default().verify(&group, key.public, b"pay 6 coins", &signature));
+
+            // 恰好一个是 low-s，规范化后两个都变成它
+            assert_ne!(signature.is_low_s(), other.is_low_s());
+            let low = signature.normalize_s();
+            assert!(low.is_low_s());
+            assert_eq!(other.normalize_s(), low);
+            assert_eq!(low.normalize_s(), low);
+            assert!(strict.verify(&group, key.public, b"pay 5 coins", &low));
+            assert!(!strict.verify(&group, key.public, b"pay 5 coins", &low.malleate()));
+        }
+    }
+
+    #[test]
+    fn known_answer_round_trip() {
+        // q = 199，G = (72, 12)，d = 57，k = 23，m = "abc"，用独立的脚本算出
+        let group = group();
+        assert_eq!(group.g, Some((72, 12)));
+        let q = group.q;
+        let d = Scalar::from_u64(57, q);
+        let public = group.base_mul(d);
+        let z = hash_message(&group, b"abc");
+        assert_eq!(z.value(), 186);
+        let full = sign_with_nonce(&group, d, z, Scalar::from_u64(23, q)).unwrap();
+        let signature = full.signature;
+        assert!(
+            equation(&group, public, b"abc", &full)
+                .unwrap()
+                .holds(&group)
+        );
+        assert_eq!((signature.r.value(), signature.s.value()), (164, 129));
+        assert!(!signature.is_low_s());
+        let low = signature.normalize_s();
+        assert_eq!((low.r.value(), low.s.value()), (164, 70));
+        for signature in [signature, low] {
+            assert!(Verifier::default().verify(&group, public, b"abc", &signature));
+        }
+    }
+
+    #[test]
+    fn deterministic_nonces_repeat_only_for_the_same_message() {
+        let group = group();
+        let mut rng = rng::seeded(6);
+        let key = SigningKey::generate(&group, &mut rng);
+        let signature = key.sign(&group, b"pay 5 coins");
+        assert_eq!(key.sign(&group, b"pay 5 coins"), signature);
+        assert_ne!(key.sign(&group, b"pay 6 coins").r, signature.r);
+        assert!(Verifier::default().verify(&group, key.public, b"pay 5 coins", &signature));
+        // 和 Steps 用同一个 k
+        let d = Scalar::from_u64(57, group.q);
+        let k = deterministic_nonce(&group, d, b"abc");
+        let steps = Steps::run(&group, d, b"abc", k).unwrap();
+        let z = hash_message(&group, b"abc");
+        assert_eq!(
+            sign_with_nonce(&group, d, z, k).map(|full| full.signature),
+            Some(steps.signature)
+        );
+    }
+
+    #[test]
+    fn a_reused_nonce_gives_away_the_key() {
+        let group = group();
+        let q = group.q;
+        let (d, k) = (Scalar::from_u64(57, q), Scalar::from_u64(23, q));
+        let public = group.base_mul(d);
+        let sign = |message: &[u8]| {
+            sign_with_nonce(&group, d, hash_message(&group, message), k)
+                .unwrap()
+                .signature
+        };
+        let (m1, m2): (&[u8], &[u8]) = (b"pay 5 coins", b"pay 500 coins");
+        let (sig1, sig2) = (sign(m1), sign(m2));
+        assert_eq!(sig1.r, sig2.r);
+        let recovered = recover_reused_nonce(&group, public, (m1, &sig1), (m2, &sig2)).unwrap();
+        assert_eq!((recovered.nonce, recovered.secret), (k, d));
+        assert_eq!(recovered.s2, sig2.s);
+        // 第二个签名规范成 low-s 也一样
+        let flipped = sig2.malleate();
+        let again = recover_reused_nonce(&group, public, (m1, &sig1), (m2, &flipped)).unwrap();
+        assert_eq!((again.secret, again.s2), (d, sig2.s));
+
+        let key = SigningKey::generate(&group, &mut rng::seeded(1));
+        let fresh = key.sign(&group, m2);
+        assert_eq!(
+            recover_reused_nonce(&group, key.public, (m1, &sig1), (m2, &fresh)),
+            Err(ReuseFailure::DifferentR)
+        );
+        assert_eq!(
+            recover_reused_nonce(&group, public, (m1, &sig1), (m1, &sig1)),
+            Err(ReuseFailure::SameHash)
+        );
+        assert_eq!(
+            recover_reused_nonce(&group, key.public, (m1, &sig1), (m2, &sig2)),
+            Err(ReuseFailure::WrongKey)
+        );
+    }
+
+    #[test]
+    fn steps_agree_with_the_verifier() {
+        let group = group();
+        let mut rng = rng::seeded(5);
+        for _ in 0..20 {
+            let secret = Scalar::random(&mut rng, group.q);
+            let k = Scalar::random(&mut rng, group.q);
+            let Some(steps) = Steps::run(&group, secret, b"hello", k) else {
+                continue;
+            };
+            assert_eq!(steps.point, group.base_mul(k));
+            assert!(steps.accepts());
+            // u1 + u2·d = s⁻¹(z + r·d) = k，所以 X 就是 R
+            assert_eq!(steps.check, steps.point);
+            let verifier = Verifier::default();
+            assert!(verifier.verify(&group, steps.public, b"hello", &steps.signature));
+        }
+    }
+
+    #[test]
+    fn zero_components_are_rejected() {
+        let group = group();
+        let mut rng = rng::seeded(2);
+        let key = SigningKey::generate(&group, &mut rng);
+        let signature = key.sign(&group, b"m");
+        let zero = Scalar::zero(group.q);
+        for bad in [
+            Signature {
+                r: zero,
+                ..signature
+            },
+            Signature {
+                s: zero,
+                ..signature
+            },
+        ] {
+            assert!(!Verifier::default().verify(&group, key.public, b"m", &bad));
+        }
+        assert!(!Verifier::default().verify(&group, None, b"m", &signature));
+    }
+}
@@ -0,0 +1,139 @@
+// Lenstra 的椭圆曲线分解（ECM）：把合数 N 当成"素数"，在 y² = x³ + ax + b (mod N) 上做点乘。
+// N 的素因子 q 上，这条曲线的群阶 #E(F_q) 如果只有不超过 B 的小素因子，B!·P 在模 q 下就是 O，
+// 算到那一步时斜率的分母是 q 的倍数、模 N 不可逆，gcd(分母, N) 就给出了 q。
+// 群阶不够光滑就换一条随机曲线，这是它比 Pollard p − 1 强的地方：p − 1 只有一个群可选。
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::field;
+use crate::rng;
+use rand_core::RngCore;
+
+// 默认的光滑界 B：依次乘 2, 3, ..., B，相当于乘 B!
+pub const BOUND: u64 = 50;
+// 换曲线的次数上限
+pub const MAX_CURVES: usize = 200;
+// 仿射倍点里 (3x² + a)·(2y)⁻¹ 中间不取模，约是 3N³，要放得进 i64
+pub const MAX_N: i64 = 1_000_000;
+
+// 乘到 k 时出错：要算 k·Q，分母 value 和 N 的公因子是 gcd
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub k: u64,
+    pub before: Coords,
+    pub value: i64,
+    pub gcd: i64,
+}
+
+// 一条随机曲线和它上面的点 P
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trial {
+    pub curve: Curve,
+    pub point: (i64, i64),
+    // 乘完 B! 都没出错时为 None
+    pub failure: Option<Failure>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ecm {
+    pub n: i64,
+    pub bound: u64,
+    pub trials: Vec<Trial>,
+    // 找到的非平凡因子
+    pub factor: Option<i64>,
+}
+
+impl Ecm {
+    // n 是不超过 MAX_N 的合数；素数没有非平凡因子，直接返回
+    pub fn run<R: RngCore + ?Sized>(n: i64, bound: u64, rng: &mut R) -> Self {
+        let _span = tracing::debug_span!("ecm", n, bound).entered();
+        assert!((4..=MAX_N).contains(&n), "N = {n} is out of range");
+        let mut ecm = Ecm {
+            n,
+            bound,
+            trials: Vec::new(),
+            factor: None,
+        };
+        if field::is_prime(n) {
+            return ecm;
+        }
+        while ecm.trials.len() < MAX_CURVES {
+            // 先取点再定 b，曲线自然经过 P，不用在模 N 下开平方
+            let [x, y, a] = [(); 3].map(|_| rng::uniform_below(rng, n as u64) as i64);
+            let b = (y as i128 * y as i128 - (x as i128).pow(3) - a as i128 * x as i128)
+                .rem_euclid(n as i128) as i64;
+            let curve = Curve::new(a, b, n);
+            let failure = stage_one(&curve, Some((x, y)), bound);
+            let gcd = failure.as_ref().map(|failure| failure.gcd);
+            ecm.trials.push(Trial {
+                curve,
+                point: (x, y),
+                failure,
+            });
+            // gcd = N 时两个素因子同时走到了 O，换一条曲线
+            if let Some(gcd) = gcd.filter(|&gcd| gcd > 1 && gcd < n) {
+                tracing::debug!(curves = ecm.trials.len(), gcd, "ecm found a factor");
+                ecm.factor = Some(gcd);
+                break;
+            }
+        }
+        ecm
+    }
+}
+
+// 依次算 2·P, 3·(2·P), ...，直到 B!·P 或者遇到不可逆的分母
+fn stage_one(curve: &Curve, point: Coords, bound: u64) -> Option<Failure> {
+    let mut q = point;
+    for k in 2..=bound {
+        match curve.try_mul_coords(q, k) {
+            Ok(next) => q = next,
+            Err(EcError::NonInvertible { value, gcd, .. }) => {
+                return Some(Failure {
+                    k,
+                    before: q,
+                    value,
+                    gcd,
+                });
+            }
+            Err(error) => unreachable!("affine multiplication only fails to invert: {error}"),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_small_composites() {
+        let mut rng = rng::seeded(7);
+        for (n, factors) in [
+            (8051, [83, 97]),
+            (455839, [599, 761]),
+            (10403, [101, 103]),
+            (999919, [991, 1009]),
+        ] {
+            let ecm = Ecm::run(n, BOUND, &mut rng);
+            let factor = ecm.factor.expect("a factor is found");
+            assert!(factors.contains(&factor), "{n}: {factor}");
+            let last = ecm.trials.last().unwrap();
+            let failure = last.failure.as_ref().unwrap();
+            assert_eq!(failure.gcd, factor);
+            assert_eq!(failure.value % factor, 0);
+            // 出错的那一步确实需要这个分母的逆
+            assert!(matches!(
+                last.curve.try_mul_coords(failure.before, failure.k),
+                Err(EcError::NonInvertible { gcd, .. }) if gcd == factor
+            ));
+            let (x, y) = last.point;
+            assert!(last.curve.contains(x, y));
+        }
+    }
+
+    #[test]
+    fn primes_have_no_factor() {
+        let ecm = Ecm::run(8053, BOUND, &mut rng::seeded(1));
+        assert_eq!(ecm.factor, None);
+        assert!(ecm.trials.is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+// Ed25519（RFC 8032）：在 edwards.rs 的扭曲 Edwards 曲线上做 Schnorr 签名。
+// 私钥是 32 字节种子，SHA-512(种子) 的前半 clamp 后是标量 a，后半是 nonce 前缀。
+// nonce r = H(前缀 ‖ M) 由私钥和消息确定，不依赖随机数发生器；k = H(R ‖ A ‖ M)，S = r + k·a mod ℓ。
+// 验证用带余因子的方程 [8][S]B = [8]R + [8][k]A，并拒绝 S ≥ ℓ，
+// 所以 ECDSA 那种“不改私钥换一个有效签名”的延展性在这里不存在。
+use crate::edwards::EdwardsPoint;
+use crate::sha512::{Sha512, sha512};
+use crate::u256::reduce_once;
+use crate::x25519::clamp;
+
+// 基点的阶 ℓ = 2^252 + 27742317777372353535851937790883648493，小端 64 位肢
+const L: [u64; 4] = [
+    0x5812631a5cf5d3ed,
+    0x14def9dea2f79cd6,
+    0,
+    0x1000000000000000,
+];
+
+pub struct SigningKey {
+    scalar: [u8; 32],
+    prefix: [u8; 32],
+    pub public: [u8; 32],
+}
+
+impl SigningKey {
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let digest = sha512(seed);
+        let scalar = clamp(digest[..32].try_into().expect("32 bytes"));
+        SigningKey {
+            scalar,
+            prefix: digest[32..].try_into().expect("32 bytes"),
+            public: EdwardsPoint::base_point().mul(&scalar).compress(),
+        }
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        let r = hash_to_scalar(&[&self.prefix, message]);
+        let big_r = EdwardsPoint::base_point().mul(&r).compress();
+        let k = hash_to_scalar(&[&big_r, &self.public, message]);
+        let s = mul_add(&k, &self.scalar, &r);
+        let mut signature = [0; 64];
+        signature[..32].copy_from_slice(&big_r);
+        signature[32..].copy_from_slice(&s);
+        signature
+    }
+}
+
+pub fn verify(public: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let big_r: [u8; 32] = signature[..32].try_into().expect("32 bytes");
+    let s: [u8; 32] = signature[32..].try_into().expect("32 bytes");
+    if !is_canonical(&s) {
+        return false;
+    }
+    let (Some(a), Some(r)) = (
+        EdwardsPoint::decompress(public),
+        EdwardsPoint::decompress(&big_r),
+    ) else {
+        return false;
+    };
+    let k = hash_to_scalar(&[&big_r, public, message]);
+    let lhs = EdwardsPoint::base_point().mul(&s);
+    let rhs = r.add(&a.mul(&k));
+    lhs.mul_by_cofactor() == rhs.mul_by_cofactor()
+}
+
+// (R, S + ℓ)：方程照样成立，只有 S < ℓ 的检查能把它挡住
+pub fn malleate(signature: &[u8; 64]) -> [u8; 64] {
+    // S < ℓ < 2^253，S + ℓ 仍然放得进 32 字节
+    let mut s: [u64; 4] = to_limbs(&signature[32..]);
+    let mut carry = 0;
+    for (limb, l) in s.iter_mut().zip(L) {
+        let sum = *limb as u128 + l as u128 + carry;
+        *limb = sum as u64;
+        carry = sum >> 64;
+    }
+    let mut malleated = *signature;
+    malleated[32..].copy_from_slice(&from_limbs(&s));
+    malleated
+}
+
+// SHA-512 的 64 字节按小端整数模 ℓ
+fn hash_to_scalar(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    let wide: [u64; 8] = to_limbs(&hasher.finalize());
+    from_limbs(&reduce(&wide))
+}
+
+// k·a + r mod ℓ
+fn mul_add(k: &[u8; 32], a: &[u8; 32], r: &[u8; 32]) -> [u8; 32] {
+    let (k, a): ([u64; 4], [u64; 4]) = (to_limbs(k), to_limbs(a));
+    let mut wide = [0u64; 8];
+    wide[..4].copy_from_slice(&to_limbs::<4>(r));
+    for (i, &ki) in k.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &aj) in a.iter().enumerate() {
+            let t = ki as u128 * aj as u128 + wide[i + j] as u128 + carry;
+            wide[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        // k、a < 2^256，进位不会越过第 8 肢
+        for limb in &mut wide[i + 4..] {
+            let t = *limb as u128 + carry;
+            *limb = t as u64;
+            carry = t >> 64;
+        }
+    }
+    from_limbs(&reduce(&wide))
+}
+
+fn is_canonical(s: &[u8; 32]) -> bool {
+    !at_least_l(&to_limbs(s))
+}
+
+// 从高位到低位逐位移入：acc ← 2·acc + bit，超过 ℓ 就减去（不分支，a 是私钥）。acc < ℓ < 2^253，不会溢出
+fn reduce(wide: &[u64; 8]) -> [u64; 4] {
+    let mut acc = [0u64; 4];
+    for bit in (0..512).rev() {
+        let mut carry = (wide[bit / 64] >> (bit % 64)) & 1;
+        for limb in &mut acc {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        acc = reduce_once(&acc, false, &L);
+    }
+    acc
+}
+
+fn at_least_l(value: &[u64; 4]) -> bool {
+    for (v, l) in value.iter().zip(L).rev() {
+        if *v != l {
+            return *v > l;
+        }
+    }
+    true
+}
+
+fn to_limbs<const N: usize>(bytes: &[u8]) -> [u64; N] {
+    let mut limbs = [0; N];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().expect("8 bytes"));
+    }
+    limbs
+}
+
+fn from_limbs(limbs: &[u64]) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes<const N: usize>(hex: &str) -> [u8; N] {
+        let mut out = [0; N];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    // RFC 8032 第 7.1 节 TEST 1–3
+    #[test]
+    fn rfc8032_vectors() {
+        let vectors = [
+            (
+                "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+                "",
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555\
+                 fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+            ),
+            (
+                "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+                "72",
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+                 085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            ),
+            (
+                "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+                "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+                "af82",
+                "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac\
+                 18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+            ),
+        ];
+        for (seed, public, message, signature) in vectors {
+            let key = SigningKey::from_seed(&bytes(seed));
+            let message: Vec<u8> = (0..message.len() / 2)
+                .map(|i| u8::from_str_radix(&message[2 * i..2 * i + 2], 16).unwrap())
+                .collect();
+            assert_eq!(key.public, bytes(public));
+            let expected: [u8; 64] = bytes(signature);
+            assert_eq!(key.sign(&message), expected);
+            assert!(verify(&key.public, &message, &expected));
+        }
+    }
+
+    #[test]
+    fn forgeries_and_malleated_signatures_are_rejected() {
+        let key = SigningKey::from_seed(&[7; 32]);
+        let signature = key.sign(b"pay Bob 5 coins");
+        // 同一条消息签两次结果一样：nonce 是确定的
+        assert_eq!(key.sign(b"pay Bob 5 coins"), signature);
+        assert!(verify(&key.public, b"pay Bob 5 coins", &signature));
+        assert!(!verify(&key.public, b"pay Bob 6 coins", &signature));
+        let other = SigningKey::from_seed(&[8; 32]);
+        assert!(!verify(&other.public, b"pay Bob 5 coins", &signature));
+
+        let malleated = malleate(&signature);
+        assert_ne!(malleated, signature);
+        assert!(!verify(&key.public, b"pay Bob 5 coins", &malleated));
+    }
+
+    #[test]
+    fn scalar_reduction_matches_small_arithmetic() {
+        let small = |v: u64| from_limbs(&[v, 0, 0, 0]);
+        assert_eq!(mul_add(&small(6), &small(7), &small(8)), small(50));
+        // ℓ − 1 加 1 回到 0，ℓ 本身不是规范编码
+        let l_minus_one = from_limbs(&[L[0] - 1, L[1], L[2], L[3]]);
+        assert_eq!(mul_add(&small(1), &l_minus_one, &small(1)), small(0));
+        assert!(is_canonical(&l_minus_one));
+        assert!(!is_canonical(&from_limbs(&L)));
+    }
+}
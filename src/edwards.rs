@@ -0,0 +1,217 @@
+// 扭曲 Edwards 曲线 −x² + y² = 1 + d·x²y²，d = −121665/121666，定义在 F_(2^255 − 19) 上，
+// 和 Curve25519 双有理等价。用扩展坐标 (X : Y : Z : T)，x = X/Z，y = Y/Z，xy = T/Z，
+// 加法公式是完备的（不用区分倍点和单位元），Ed25519 直接在这里做标量乘。
+// 点编码成 32 字节：y 的小端编码，最高位放 x 的符号位（RFC 8032 第 5.1.2 节）。
+use crate::field25519::Fe;
+
+const D: [u8; 32] = [
+    0xa3, 0x78, 0x59, 0x13, 0xca, 0x4d, 0xeb, 0x75, 0xab, 0xd8, 0x41, 0x41, 0x4d, 0x0a, 0x70, 0x00,
+    0x98, 0xe8, 0x79, 0x77, 0x79, 0x40, 0xc7, 0x8c, 0x73, 0xfe, 0x6f, 0x2b, 0xee, 0x6c, 0x03, 0x52,
+];
+
+const D2: [u8; 32] = [
+    0x59, 0xf1, 0xb2, 0x26, 0x94, 0x9b, 0xd6, 0xeb, 0x56, 0xb1, 0x83, 0x82, 0x9a, 0x14, 0xe0, 0x00,
+    0x30, 0xd1, 0xf3, 0xee, 0xf2, 0x80, 0x8e, 0x19, 0xe7, 0xfc, 0xdf, 0x56, 0xdc, 0xd9, 0x06, 0x24,
+];
+
+// √−1 = 2^((p − 1) / 4)
+const SQRT_M1: [u8; 32] = [
+    0xb0, 0xa0, 0x0e, 0x4a, 0x27, 0x1b, 0xee, 0xc4, 0x78, 0xe4, 0x2f, 0xad, 0x06, 0x18, 0x43, 0x2f,
+    0xa7, 0xd7, 0xfb, 0x3d, 0x99, 0x00, 0x4d, 0x2b, 0x0b, 0xdf, 0xc1, 0x4f, 0x80, 0x24, 0x83, 0x2b,
+];
+
+// 基点 B：y = 4/5，x 取正的那个
+pub const BASE_POINT: [u8; 32] = [
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct EdwardsPoint {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+    t: Fe,
+}
+
+impl EdwardsPoint {
+    pub const IDENTITY: EdwardsPoint = EdwardsPoint {
+        x: Fe::ZERO,
+        y: Fe::ONE,
+        z: Fe::ONE,
+        t: Fe::ZERO,
+    };
+
+    pub fn base_point() -> EdwardsPoint {
+        EdwardsPoint::decompress(&BASE_POINT).expect("the base point decodes")
+    }
+
+    // 解码失败（y ≥ p、x² 不是平方、x = 0 却带负号）时返回 None
+    pub fn decompress(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
+        let sign = bytes[31] >> 7 == 1;
+        let mut y_bytes = *bytes;
+        y_bytes[31] &= 0x7f;
+        let y = Fe::from_bytes(&y_bytes);
+        if y.to_bytes() != y_bytes {
+            return None;
+        }
+        // x² = (y² − 1) / (d·y² + 1) = u / v，候选根 x = u·v³·(u·v⁷)^((p − 5) / 8)
+        let y2 = y.square();
+        let u = y2 - Fe::ONE;
+        let v = Fe::from_bytes(&D) * y2 + Fe::ONE;
+        let v3 = v.square() * v;
+        let mut x = u * v3 * (u * v3.square() * v).pow_p58();
+        let vx2 = v * x.square();
+        if vx2 == -u {
+            x = x * Fe::from_bytes(&SQRT_M1);
+        } else if vx2 != u {
+            return None;
+        }
+        if x == Fe::ZERO && sign {
+            return None;
+        }
+        if x.is_negative() != sign {
+            x = -x;
+        }
+        Some(EdwardsPoint {
+            x,
+            y,
+            z: Fe::ONE,
+            t: x * y,
+        })
+    }
+
+    pub fn compress(&self) -> [u8; 32] {
+        let inv = self.z.invert();
+        let x = self.x * inv;
+        let mut bytes = (self.y * inv).to_bytes();
+        bytes[31] |= u8::from(x.is_negative()) << 7;
+        bytes
+    }
+
+    // Hisil–Wong–Carter–Dawson 的统一加法（a = −1）
+    pub fn add(&self, other: &EdwardsPoint) -> EdwardsPoint {
+        let a = (self.y - self.x) * (other.y - other.x);
+        let b = (self.y + self.x) * (other.y + other.x);
+        let c = self.t * Fe::from_bytes(&D2) * other.t;
+        let d = (self.z + self.z) * other.z;
+        let (e, f, g, h) = (b - a, d - c, d + c, b + a);
+        EdwardsPoint {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    pub fn double(&self) -> EdwardsPoint {
+        self.add(self)
+    }
+
+    // 开启 `ct` feature 时走固定 256 轮的 Montgomery ladder：加法公式是完备的，
+    // 每轮一次加法、一次倍点，按标量的位条件交换，和 x25519 的做法一样
+    #[cfg(feature = "ct")]
+    pub fn mul(&self, scalar: &[u8; 32]) -> EdwardsPoint {
+        let (mut r0, mut r1) = (EdwardsPoint::IDENTITY, *self);
+        for bit in (0..256).rev() {
+            let choice = u64::from((scalar[bit / 8] >> (bit % 8)) & 1);
+            EdwardsPoint::conditional_swap(&mut r0, &mut r1, choice);
+            r1 = r0.add(&r1);
+            r0 = r0.double();
+            EdwardsPoint::conditional_swap(&mut r0, &mut r1, choice);
+        }
+        r0
+    }
+
+    #[cfg(feature = "ct")]
+    fn conditional_swap(a: &mut EdwardsPoint, b: &mut EdwardsPoint, choice: u64) {
+        Fe::conditional_swap(&mut a.x, &mut b.x, choice);
+        Fe::conditional_swap(&mut a.y, &mut b.y, choice);
+        Fe::conditional_swap(&mut a.z, &mut b.z, choice);
+        Fe::conditional_swap(&mut a.t, &mut b.t, choice);
+    }
+
+    // 小端 256 位标量，从高位到低位倍点再加。只用于演示，不是常数时间
+    #[cfg(not(feature = "ct"))]
+    pub fn mul(&self, scalar: &[u8; 32]) -> EdwardsPoint {
+        let mut result = EdwardsPoint::IDENTITY;
+        for bit in (0..256).rev() {
+            result = result.double();
+            if (scalar[bit / 8] >> (bit % 8)) & 1 == 1 {
+                result = result.add(self);
+            }
+        }
+        result
+    }
+
+    // 乘余因子 8，消掉小阶分量
+    pub fn mul_by_cofactor(&self) -> EdwardsPoint {
+        self.double().double().double()
+    }
+}
+
+// 射影坐标：X1·Z2 = X2·Z1 且 Y1·Z2 = Y2·Z1
+impl PartialEq for EdwardsPoint {
+    fn eq(&self, other: &EdwardsPoint) -> bool {
+        self.x * other.z == other.x * self.z && self.y * other.z == other.y * self.z
+    }
+}
+
+impl Eq for EdwardsPoint {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(value: u64) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        bytes[..8].copy_from_slice(&value.to_le_bytes());
+        bytes
+    }
+
+    // 基点的阶是 ℓ = 2^252 + 27742317777372353535851937790883648493
+    #[test]
+    fn base_point_has_order_l() {
+        let l = [
+            0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9,
+            0xde, 0x14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+        ];
+        let base = EdwardsPoint::base_point();
+        assert_eq!(base.compress(), BASE_POINT);
+        assert_eq!(base.mul(&l), EdwardsPoint::IDENTITY);
+        assert_ne!(base.mul(&scalar(8)), EdwardsPoint::IDENTITY);
+    }
+
+    #[test]
+    fn group_law_and_encoding_round_trip() {
+        let base = EdwardsPoint::base_point();
+        let mut sum = EdwardsPoint::IDENTITY;
+        for k in 1..=20u64 {
+            sum = sum.add(&base);
+            assert_eq!(sum, base.mul(&scalar(k)));
+            let encoded = sum.compress();
+            assert_eq!(EdwardsPoint::decompress(&encoded), Some(sum));
+        }
+        assert_eq!(base.mul_by_cofactor(), base.mul(&scalar(8)));
+        // y = 2 时 x² = 3 / (4d + 1)，不是平方
+        assert_eq!(EdwardsPoint::decompress(&scalar(2)), None);
+        // y = p 不是规范编码
+        let mut p = [0xff; 32];
+        p[0] = 0xed;
+        p[31] = 0x7f;
+        assert_eq!(EdwardsPoint::decompress(&p), None);
+    }
+
+    // (0, −1) 是 2 阶点，乘余因子后消失
+    #[test]
+    fn small_order_points_vanish_under_the_cofactor() {
+        let mut minus_one = [0xff; 32];
+        minus_one[0] = 0xec;
+        minus_one[31] = 0x7f;
+        let torsion = EdwardsPoint::decompress(&minus_one).unwrap();
+        assert_ne!(torsion, EdwardsPoint::IDENTITY);
+        assert_eq!(torsion.double(), EdwardsPoint::IDENTITY);
+        let base = EdwardsPoint::base_point();
+        assert_eq!(base.add(&torsion).mul_by_cofactor(), base.mul_by_cofactor());
+    }
+}
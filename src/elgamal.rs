@@ -0,0 +1,147 @@
+// EC ElGamal：Bob 的私钥 x，公钥 Y = x·G。Alice 把消息编码成曲线上的点 M，取随机的 k，
+// 发出 (C1, C2) = (k·G, M + k·Y)。Bob 算 C2 − x·C1 = M + k·x·G − x·k·G = M。
+// 消息到点用 Koblitz 的办法：依次试 x = m·K + j（j = 0, ..., K − 1），x³ + ax + b 是平方剩余
+// 就取这个 x，解码时 m = ⌊x / K⌋。每次约一半的概率成功，K 次都失败的概率约 2^−K。
+use crate::curve::{Coords, Curve};
+use crate::field;
+use crate::scalar::{Scalar, SecretScalar};
+use crate::subgroup::PrimeGroup;
+use rand_core::RngCore;
+
+// 每条消息留出的 x 坐标个数
+pub const KOBLITZ_K: u64 = 10;
+
+// 能编码的最大消息：m·K + K − 1 < p
+pub fn max_message(curve: &Curve) -> u64 {
+    (curve.p as u64).saturating_sub(KOBLITZ_K) / KOBLITZ_K
+}
+
+// 消息 m 编码成的点，tries 是试到第几个 x 才成功
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Encoding {
+    pub message: u64,
+    pub point: (i64, i64),
+    pub tries: u64,
+}
+
+// m 超出范围，或者 K 个 x 都不在曲线上时为 None
+pub fn encode(curve: &Curve, message: u64) -> Option<Encoding> {
+    if message > max_message(curve) {
+        return None;
+    }
+    (0..KOBLITZ_K).find_map(|j| {
+        let x = (message * KOBLITZ_K + j) as i64;
+        let y = field::sqrt_mod(curve.rhs(x), curve.p)?;
+        Some(Encoding {
+            message,
+            point: (x, y),
+            tries: j + 1,
+        })
+    })
+}
+
+// 无穷远点不是任何消息的编码
+pub fn decode(point: Coords) -> Option<u64> {
+    point.map(|(x, _)| x as u64 / KOBLITZ_K)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ciphertext {
+    pub c1: Coords,
+    pub c2: Coords,
+}
+
+pub struct KeyPair {
+    secret: SecretScalar,
+    pub public: Coords,
+}
+
+impl KeyPair {
+    pub fn generate<R: RngCore + ?Sized>(group: &PrimeGroup, rng: &mut R) -> Self {
+        Self::from_secret(group, Scalar::random(rng, group.q))
+    }
+
+    pub fn from_secret(group: &PrimeGroup, secret: Scalar) -> Self {
+        KeyPair {
+            public: group.base_mul(secret),
+            secret: SecretScalar::new(secret),
+        }
+    }
+
+    // C2 − x·C1
+    pub fn decrypt(&self, group: &PrimeGroup, ciphertext: &Ciphertext) -> Coords {
+        let mask = group.mul(ciphertext.c1, *self.secret.expose());
+        group
+            .curve
+            .add_coords(ciphertext.c2, group.curve.neg_coords(mask))
+    }
+}
+
+pub fn encrypt<R: RngCore + ?Sized>(
+    group: &PrimeGroup,
+    public: Coords,
+    message: Coords,
+    rng: &mut R,
+) -> Ciphertext {
+    let k = SecretScalar::new(Scalar::random(rng, group.q));
+    encrypt_with(group, public, message, *k.expose())
+}
+
+// 指定临时密钥 k，界面上一步一步演示用。同一个 k 用两次，C2 之差就是两条消息之差
+pub fn encrypt_with(group: &PrimeGroup, public: Coords, message: Coords, k: Scalar) -> Ciphertext {
+    Ciphertext {
+        c1: group.base_mul(k),
+        c2: group.curve.add_coords(message, group.mul(public, k)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    fn group() -> PrimeGroup {
+        PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap()
+    }
+
+    #[test]
+    fn every_small_message_survives_a_round_trip() {
+        let group = group();
+        let mut rng = rng::seeded(9);
+        let bob = KeyPair::generate(&group, &mut rng);
+        assert_eq!(max_message(&group.curve), 58);
+        for m in 0..=max_message(&group.curve) {
+            let encoding = encode(&group.curve, m).expect("some x within K tries");
+            let (x, y) = encoding.point;
+            assert!(group.curve.contains(x, y));
+            assert_eq!(x as u64, m * KOBLITZ_K + encoding.tries - 1);
+            let ciphertext = encrypt(&group, bob.public, Some(encoding.point), &mut rng);
+            assert_ne!(ciphertext.c2, Some(encoding.point));
+            let decrypted = bob.decrypt(&group, &ciphertext);
+            assert_eq!(decrypted, Some(encoding.point));
+            assert_eq!(decode(decrypted), Some(m));
+        }
+        assert_eq!(encode(&group.curve, 59), None);
+    }
+
+    #[test]
+    fn only_the_secret_key_removes_the_mask() {
+        let group = group();
+        let bob = KeyPair::from_secret(&group, Scalar::from_u64(17, group.q));
+        let eve = KeyPair::from_secret(&group, Scalar::from_u64(18, group.q));
+        let message = Some(encode(&group.curve, 42).unwrap().point);
+        let k = Scalar::from_u64(5, group.q);
+        let ciphertext = encrypt_with(&group, bob.public, message, k);
+        assert_eq!(ciphertext.c1, group.base_mul(k));
+        assert_eq!(bob.decrypt(&group, &ciphertext), message);
+        assert_ne!(eve.decrypt(&group, &ciphertext), message);
+        // 重复使用 k：C2 − C2' = M − M'，知道一条明文就能解出另一条
+        let other = Some(encode(&group.curve, 7).unwrap().point);
+        let reused = encrypt_with(&group, bob.public, other, k);
+        let curve = &group.curve;
+        assert_eq!(
+            curve.add_coords(reused.c2, curve.neg_coords(ciphertext.c2)),
+            curve.add_coords(other, curve.neg_coords(message))
+        );
+    }
+}
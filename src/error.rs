@@ -0,0 +1,53 @@
+use crate::curve::Curve;
+use std::fmt;
+
+// 曲线与点运算可能出现的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EcError {
+    MissingParameter(&'static str),
+    NotPrime(i64),
+    SingularCurve,
+    PointNotOnCurve { x: i64, y: i64 },
+    InvalidOrder { order: u64, group_order: u64 },
+    // 分母与模数不互素，gcd 是模数的一个非平凡因子（模数为合数时）
+    NonInvertible { value: i64, modulus: i64, gcd: i64 },
+    MismatchedCurves { left: Curve, right: Curve },
+    // SEC1 编码的长度、前缀或坐标不合法
+    InvalidEncoding(&'static str),
+    // 写不成 `x,y` 或 `(x, y)` 的点
+    InvalidPointSyntax(String),
+}
+
+impl fmt::Display for EcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EcError::MissingParameter(name) => write!(f, "missing curve parameter `{name}`"),
+            EcError::NotPrime(p) => write!(f, "modulus {p} is not prime"),
+            EcError::SingularCurve => write!(f, "curve is singular (4a^3 + 27b^2 = 0 mod p)"),
+            EcError::PointNotOnCurve { x, y } => write!(f, "point ({x}, {y}) is not on the curve"),
+            EcError::InvalidOrder { order, group_order } => write!(
+                f,
+                "{order} is not the order of the generator (#E = {group_order})"
+            ),
+            EcError::NonInvertible {
+                value,
+                modulus,
+                gcd,
+            } => write!(
+                f,
+                "{value} is not invertible modulo {modulus} (gcd = {gcd})"
+            ),
+            EcError::MismatchedCurves { left, right } => write!(
+                f,
+                "cannot combine points from different curves: (a, b, p) = ({}, {}, {}) vs ({}, {}, {})",
+                left.a, left.b, left.p, right.a, right.b, right.p
+            ),
+            EcError::InvalidEncoding(reason) => write!(f, "invalid SEC1 encoding: {reason}"),
+            EcError::InvalidPointSyntax(text) => {
+                write!(f, "expected a point as `x,y` or `(x, y)`, got `{text}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EcError {}
@@ -0,0 +1,247 @@
+// 曲线浏览器：对一个小素数 p 列出全部非奇异曲线 y² = x³ + ax + b 的群阶、结构和 j 不变量。
+// 曲线有 p² 条，逐条调用 Curve::points() 是 O(p²)，这里先建一张平方根表，每条曲线 O(p) 数点；
+// 按 a 分给几个线程并行算，结果按 (a, b) 排好。
+use crate::curve::{Curve, OrderCensus};
+use crate::field;
+use std::cmp::Ordering;
+
+// p² 条曲线，再大表格和计算时间都不合适
+pub const MAX_P: i64 = 251;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+    pub curve: Curve,
+    pub group_order: u64,
+    // E ≅ Z_n1 × Z_n2
+    pub structure: (u64, u64),
+    pub j_invariant: i64,
+}
+
+impl Row {
+    fn new(curve: Curve, census: &OrderCensus) -> Self {
+        Row {
+            curve,
+            group_order: census.group_order,
+            structure: (census.exponent, census.group_order / census.exponent),
+            j_invariant: curve.j_invariant().expect("singular curves are skipped"),
+        }
+    }
+
+    pub fn is_prime_order(&self) -> bool {
+        field::is_prime(self.group_order as i64)
+    }
+
+    // p ≥ 5 时迹 t = p + 1 − #E 被 p 整除只能是 t = 0
+    pub fn is_supersingular(&self) -> bool {
+        self.group_order == self.curve.p as u64 + 1
+    }
+
+    pub fn is_cyclic(&self) -> bool {
+        self.structure.1 == 1
+    }
+
+    pub fn structure_label(&self) -> String {
+        match self.structure {
+            (n1, 1) => format!("Z_{n1}"),
+            (n1, n2) => format!("Z_{n1} × Z_{n2}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub prime_order: bool,
+    pub supersingular: bool,
+    pub cyclic: bool,
+}
+
+impl Filter {
+    pub fn accepts(&self, row: &Row) -> bool {
+        (!self.prime_order || row.is_prime_order())
+            && (!self.supersingular || row.is_supersingular())
+            && (!self.cyclic || row.is_cyclic())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    #[default]
+    Parameters,
+    Order,
+    Structure,
+    JInvariant,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 4] = [
+        SortKey::Parameters,
+        SortKey::Order,
+        SortKey::Structure,
+        SortKey::JInvariant,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::Parameters => "(a, b)",
+            SortKey::Order => "#E",
+            SortKey::Structure => "structure",
+            SortKey::JInvariant => "j",
+        }
+    }
+
+    // 主键相同时按 (a, b) 排，顺序是确定的
+    fn compare(self, x: &Row, y: &Row) -> Ordering {
+        let parameters = (x.curve.a, x.curve.b).cmp(&(y.curve.a, y.curve.b));
+        match self {
+            SortKey::Parameters => Ordering::Equal,
+            SortKey::Order => x.group_order.cmp(&y.group_order),
+            // 先按 n2，非循环的排在一起
+            SortKey::Structure => {
+                (x.structure.1, x.structure.0).cmp(&(y.structure.1, y.structure.0))
+            }
+            SortKey::JInvariant => x.j_invariant.cmp(&y.j_invariant),
+        }
+        .then(parameters)
+    }
+}
+
+// 过滤后排序，返回下标，表格只画看得见的那几行
+pub fn select(rows: &[Row], filter: &Filter, key: SortKey, descending: bool) -> Vec<usize> {
+    let mut selected: Vec<usize> = (0..rows.len())
+        .filter(|&i| filter.accepts(&rows[i]))
+        .collect();
+    selected.sort_by(|&i, &j| {
+        let ordering = key.compare(&rows[i], &rows[j]);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    selected
+}
+
+// 所有非奇异曲线，按 (a, b) 排序
+pub fn enumerate(p: i64) -> Result<Vec<Row>, String> {
+    if !(5..=MAX_P).contains(&p) || !field::is_prime(p) {
+        return Err(format!("p must be a prime between 5 and {MAX_P}"));
+    }
+    let _span = tracing::info_span!("explore_curves", p).entered();
+    // roots[r] 是 y² = r 的全部解
+    let mut roots = vec![Vec::new(); p as usize];
+    for y in 0..p {
+        roots[(y * y % p) as usize].push(y);
+    }
+    let cubes: Vec<i64> = (0..p).map(|x| x * x % p * x % p).collect();
+    let row = |a: i64, b: i64| {
+        let curve = Curve::new(a, b, p);
+        if curve.is_singular() {
+            return None;
+        }
+        let points: Vec<_> = (0..p)
+            .flat_map(|x| {
+                let rhs = (cubes[x as usize] + a * x + b) % p;
+                roots[rhs as usize].iter().map(move |&y| (x, y))
+            })
+            .map(|(x, y)| curve.point(x, y))
+            .collect();
+        Some(Row::new(curve, &curve.census_of(&points)))
+    };
+
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = (p as usize).div_ceil(workers);
+    let rows: Vec<Row> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..p)
+            .collect::<Vec<_>>()
+            .chunks(chunk)
+            .map(|values| {
+                let values = values.to_vec();
+                scope.spawn(move || {
+                    values
+                        .into_iter()
+                        .flat_map(|a| (0..p).filter_map(move |b| row(a, b)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("explorer worker panicked"))
+            .collect()
+    });
+    tracing::info!(curves = rows.len(), workers, "enumerated curves");
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::Progress;
+    use crate::summary::CurveSummary;
+
+    #[test]
+    fn rows_match_the_curve_summary() {
+        let rows = enumerate(13).unwrap();
+        // 13² 条里去掉判别式为 0 的
+        let singular = (0..13)
+            .flat_map(|a| (0..13).map(move |b| Curve::new(a, b, 13)))
+            .filter(Curve::is_singular)
+            .count();
+        assert_eq!(rows.len(), 169 - singular);
+        for row in &rows {
+            let summary = CurveSummary::compute_with(&row.curve, &Progress::default()).unwrap();
+            assert_eq!(row.group_order, summary.group_order, "{:?}", row.curve);
+            assert_eq!(row.structure, summary.structure, "{:?}", row.curve);
+            assert_eq!(Some(row.j_invariant), summary.j_invariant);
+        }
+        assert!(enumerate(12).is_err());
+        assert!(enumerate(MAX_P + 2).is_err());
+    }
+
+    #[test]
+    fn filters_and_sorting() {
+        let rows = enumerate(11).unwrap();
+        let all = select(&rows, &Filter::default(), SortKey::Parameters, false);
+        assert_eq!(all, (0..rows.len()).collect::<Vec<_>>());
+
+        // p ≡ 2 (mod 3) 时 y² = x³ + b 都是超奇异的，#E = p + 1
+        let supersingular = Filter {
+            supersingular: true,
+            ..Filter::default()
+        };
+        let selected = select(&rows, &supersingular, SortKey::Order, false);
+        assert!(selected.iter().all(|&i| rows[i].group_order == 12));
+        assert!((1..11).all(|b| {
+            selected
+                .iter()
+                .any(|&i| rows[i].curve == Curve::new(0, b, 11))
+        }));
+
+        let strict = Filter {
+            prime_order: true,
+            cyclic: true,
+            ..Filter::default()
+        };
+        let by_order = select(&rows, &strict, SortKey::Order, true);
+        assert!(!by_order.is_empty());
+        assert!(
+            by_order
+                .iter()
+                .all(|&i| rows[i].is_prime_order() && rows[i].is_cyclic())
+        );
+        assert!(
+            by_order
+                .windows(2)
+                .all(|w| rows[w[0]].group_order >= rows[w[1]].group_order)
+        );
+        let non_cyclic = rows.iter().filter(|row| !row.is_cyclic()).count();
+        let cyclic = Filter {
+            cyclic: true,
+            ..Filter::default()
+        };
+        assert_eq!(
+            select(&rows, &cyclic, SortKey::Structure, false).len(),
+            rows.len() - non_cyclic
+        );
+    }
+}
@@ -0,0 +1,203 @@
+// 故障注入攻击：在倍点-加法中途制造一个错误，看错误的输出泄露了什么。
+// 翻转标量的第 i 位，输出变成 Q ± 2^i·P，和正确结果一比就知道这一位是 0 还是 1；
+// 改坏累加器的坐标，之后的运算落在另一条曲线 y^2 = x^3 + ax + b' 上（加法公式不用 b），
+// 输出不在原曲线上。输出前检查点在曲线上能拦住后一种，拦不住前一种。
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    // 读第 bit 位标量时读反
+    FlipBit,
+    // 处理完第 bit 位后累加器的 x 坐标加一
+    CorruptX,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fault {
+    pub kind: FaultKind,
+    pub bit: u32,
+}
+
+// 处理完一位之后的累加器
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step {
+    pub bit: u32,
+    // 实际用到的标量位，故障时和 k 的这一位不同
+    pub set: bool,
+    pub acc: Coords,
+    pub on_curve: bool,
+}
+
+pub struct Trace {
+    pub steps: Vec<Step>,
+    pub result: Coords,
+}
+
+fn on_curve(curve: &Curve, coords: Coords) -> bool {
+    coords.is_none_or(|(x, y)| curve.contains(x, y))
+}
+
+// 从高位到低位的倍点-加法，每处理一位记录一次累加器
+pub fn traced_mul(curve: &Curve, point: Coords, k: u64, fault: Option<Fault>) -> Trace {
+    let bits = (u64::BITS - k.leading_zeros()).max(fault.map_or(0, |f| f.bit + 1));
+    let mut acc = None;
+    let mut steps = Vec::with_capacity(bits as usize);
+    for bit in (0..bits).rev() {
+        let mut set = (k >> bit) & 1 == 1;
+        acc = curve.add_coords(acc, acc);
+        let here = fault.filter(|f| f.bit == bit);
+        if here.is_some_and(|f| f.kind == FaultKind::FlipBit) {
+            set = !set;
+        }
+        if set {
+            acc = curve.add_coords(acc, point);
+        }
+        if here.is_some_and(|f| f.kind == FaultKind::CorruptX) {
+            // 无穷远点没有坐标可改，就改成 (0, 0)
+            acc = Some(acc.map_or((0, 0), |(x, y)| ((x + 1) % curve.p, y)));
+        }
+        steps.push(Step {
+            bit,
+            set,
+            acc,
+            on_curve: on_curve(curve, acc),
+        });
+    }
+    tracing::trace!(?fault, result = ?acc, "traced scalar multiplication");
+    Trace { steps, result: acc }
+}
+
+// 对策：输出前检查结果在曲线上
+pub fn checked_mul(
+    curve: &Curve,
+    point: Coords,
+    k: u64,
+    fault: Option<Fault>,
+) -> Result<Coords, EcError> {
+    match traced_mul(curve, point, k, fault).result {
+        Some((x, y)) if !curve.contains(x, y) => Err(EcError::PointNotOnCurve { x, y }),
+        result => Ok(result),
+    }
+}
+
+// 比较正确和翻转第 bit 位后的输出：差是 +2^bit·P 说明这一位原来是 0，
+// 是 -2^bit·P 说明原来是 1。2^bit·P 为无穷远点或 2 阶点时分不出来
+pub fn recover_bit(
+    curve: &Curve,
+    point: Coords,
+    correct: Coords,
+    faulty: Coords,
+    bit: u32,
+) -> Option<bool> {
+    let step = curve.mul_coords(point, 1 << bit);
+    let negated = curve.neg_coords(step);
+    if step.is_none() || step == negated {
+        return None;
+    }
+    let diff = curve.add_coords(faulty, curve.neg_coords(correct));
+    if diff == step {
+        Some(false)
+    } else if diff == negated {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+// 攻击者能让设备反复计算 k·P，每次在一位上注入翻转，逐位恢复 k 的低 bits 位。
+// 分不出来的位当作 0
+pub fn bit_flip_attack(
+    curve: &Curve,
+    point: Coords,
+    bits: u32,
+    mut device: impl FnMut(Option<Fault>) -> Coords,
+) -> u64 {
+    let _span = tracing::debug_span!("bit_flip_attack", bits).entered();
+    let correct = device(None);
+    let mut k = 0;
+    for bit in 0..bits {
+        let faulty = device(Some(Fault {
+            kind: FaultKind::FlipBit,
+            bit,
+        }));
+        if recover_bit(curve, point, correct, faulty, bit) == Some(true) {
+            k |= 1 << bit;
+        }
+    }
+    tracing::debug!(k, "recovered scalar");
+    k
+}
+
+// 不在原曲线上的点实际所在的曲线：a 不变，b' = y^2 - x^3 - ax
+pub fn actual_curve(curve: &Curve, coords: Coords) -> Option<Curve> {
+    let (x, y) = coords?;
+    let p = curve.p as i128;
+    let (x, y, a) = (x as i128, y as i128, curve.a as i128);
+    let b = (y * y - x * x % p * x - a * x).rem_euclid(p);
+    Some(Curve::new(curve.a, b as i64, curve.p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (Curve, Coords) {
+        let curve = Curve::new(2, 3, 97);
+        (curve, Some((3, 6)))
+    }
+
+    #[test]
+    fn without_a_fault_the_trace_matches_double_and_add() {
+        let (curve, point) = setup();
+        for k in [0, 1, 2, 5, 77, 1000] {
+            let trace = traced_mul(&curve, point, k, None);
+            assert_eq!(trace.result, curve.mul_coords(point, k));
+            assert!(trace.steps.iter().all(|step| step.on_curve));
+        }
+    }
+
+    #[test]
+    fn bit_flips_give_away_the_scalar() {
+        let curve = Curve::new(1, 1, 599);
+        let point = curve.first_point().unwrap().coords();
+        let order = curve.order_of(point, curve.group_order());
+        let bits = u64::BITS - order.leading_zeros();
+        let k = 0b1011_0110 % order;
+        let recovered = bit_flip_attack(&curve, point, bits, |fault| {
+            traced_mul(&curve, point, k, fault).result
+        });
+        assert_eq!(
+            curve.mul_coords(point, recovered),
+            curve.mul_coords(point, k)
+        );
+        // 翻转标量位得到的点仍在曲线上，检查拦不住
+        let fault = Fault {
+            kind: FaultKind::FlipBit,
+            bit: 2,
+        };
+        assert!(checked_mul(&curve, point, k, Some(fault)).is_ok());
+    }
+
+    #[test]
+    fn corrupted_coordinates_land_off_the_curve() {
+        let (curve, point) = setup();
+        let fault = Fault {
+            kind: FaultKind::CorruptX,
+            bit: 3,
+        };
+        let trace = traced_mul(&curve, point, 77, Some(fault));
+        let (x, y) = trace.result.unwrap();
+        assert!(!curve.contains(x, y));
+        let steps: Vec<bool> = trace.steps.iter().map(|step| step.on_curve).collect();
+        assert_eq!(steps, [true, true, true, false, false, false, false]);
+        // 结果落在同一个 a、另一个 b 的曲线上
+        let other = actual_curve(&curve, trace.result).unwrap();
+        assert_ne!(other.b, curve.b);
+        assert!(other.contains(x, y));
+        assert_eq!(
+            checked_mul(&curve, point, 77, Some(fault)),
+            Err(EcError::PointNotOnCurve { x, y })
+        );
+    }
+}
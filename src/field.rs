@@ -0,0 +1,266 @@
+// 有限域上的辅助运算
+use crate::error::EcError;
+
+// 模 p 的乘法，参数可以是负数或者不小于 p，结果在 [0, p)。乘积走 i128，
+// p 接近 2^63 也不会溢出；i64 直接相乘在 p 超过 2^31.5 左右就会悄悄绕回
+pub fn mul(a: i64, b: i64, p: i64) -> i64 {
+    (a as i128 * b as i128).rem_euclid(p as i128) as i64
+}
+
+// 模 p 的加减法，同样走 i128：p 接近 2^63 时两个 [0, p) 里的数相加就会超出 i64
+pub fn add(a: i64, b: i64, p: i64) -> i64 {
+    (a as i128 + b as i128).rem_euclid(p as i128) as i64
+}
+
+pub fn sub(a: i64, b: i64, p: i64) -> i64 {
+    (a as i128 - b as i128).rem_euclid(p as i128) as i64
+}
+
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+pub fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+// 确定性 Miller-Rabin，这组底数对所有 64 位整数都成立
+pub fn is_prime(n: i64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    let n = n as u64;
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    for &q in &BASES {
+        if n.is_multiple_of(q) {
+            return n == q;
+        }
+    }
+    let mut d = n - 1;
+    let mut s = 0;
+    while d.is_multiple_of(2) {
+        d /= 2;
+        s += 1;
+    }
+    'witness: for &a in &BASES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if a == 0 {
+        (b, 0, 1)
+    } else {
+        let (gcd, x1, y1) = extended_gcd(b % a, a);
+        let x = y1 - (b / a) * x1;
+        let y = x1;
+        (gcd, x, y)
+    }
+}
+
+pub fn mod_inverse(a: i64, p: i64) -> i64 {
+    let (_, x, _) = extended_gcd(a.rem_euclid(p), p);
+    x.rem_euclid(p)
+}
+
+// 模数不是素数时可能不可逆，此时 gcd(a, p) 就是 p 的一个因子
+pub fn try_mod_inverse(a: i64, p: i64) -> Result<i64, EcError> {
+    let value = a.rem_euclid(p);
+    let (gcd, x, _) = extended_gcd(value, p);
+    if gcd != 1 {
+        return Err(EcError::NonInvertible {
+            value,
+            modulus: p,
+            gcd,
+        });
+    }
+    Ok(x.rem_euclid(p))
+}
+
+// Montgomery 批量求逆：n 个非零元素只做一次求逆
+pub fn batch_inverse(values: &[i64], p: i64) -> Vec<i64> {
+    let mul = |a: i64, b: i64| mul(a, b, p);
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut acc = 1;
+    for &v in values {
+        prefix.push(acc);
+        acc = mul(acc, v);
+    }
+    let mut inv = mod_inverse(acc, p);
+    let mut inverses = vec![0; values.len()];
+    for i in (0..values.len()).rev() {
+        inverses[i] = mul(inv, prefix[i]);
+        inv = mul(inv, values[i]);
+    }
+    inverses
+}
+
+// 试除法分解，返回 (素因子, 指数)
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut d = 2;
+    while d * d <= n {
+        if n.is_multiple_of(d) {
+            let mut e = 0;
+            while n.is_multiple_of(d) {
+                n /= d;
+                e += 1;
+            }
+            factors.push((d, e));
+        }
+        d += 1;
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+// Euler 判别法：n 是否为模 p 的二次剩余（0 也算）
+pub fn is_quadratic_residue(n: i64, p: i64) -> bool {
+    let n = n.rem_euclid(p) as u64;
+    n == 0 || pow_mod(n, (p as u64 - 1) / 2, p as u64) == 1
+}
+
+// Tonelli–Shanks：素数 p 下 n 的平方根，返回两个根中较小的一个（另一个是 p − 它）；
+// n 不是二次剩余时返回 None
+pub fn sqrt_mod(n: i64, p: i64) -> Option<i64> {
+    let m = p as u64;
+    let n = n.rem_euclid(p) as u64;
+    if n == 0 || m == 2 {
+        return Some(n as i64);
+    }
+    if pow_mod(n, (m - 1) / 2, m) != 1 {
+        return None;
+    }
+    let root = if m % 4 == 3 {
+        // p ≡ 3 (mod 4) 时 n^((p+1)/4) 直接就是根
+        pow_mod(n, (m + 1) / 4, m)
+    } else {
+        // p − 1 = q·2^s，q 为奇数；z 是任一二次非剩余
+        let s = (m - 1).trailing_zeros();
+        let q = (m - 1) >> s;
+        let z = (2..m)
+            .find(|&z| pow_mod(z, (m - 1) / 2, m) == m - 1)
+            .expect("odd primes have a non-residue");
+        let (mut e, mut c) = (s, pow_mod(z, q, m));
+        let (mut t, mut r) = (pow_mod(n, q, m), pow_mod(n, q.div_ceil(2), m));
+        // 不变量 r² = n·t，每轮让 t 的阶至少减半，t = 1 时 r 就是根
+        while t != 1 {
+            let mut i = 0;
+            let mut square = t;
+            while square != 1 {
+                square = mul_mod(square, square, m);
+                i += 1;
+            }
+            let b = pow_mod(c, 1 << (e - i - 1), m);
+            e = i;
+            c = mul_mod(b, b, m);
+            t = mul_mod(t, c, m);
+            r = mul_mod(r, b, m);
+        }
+        r
+    };
+    Some(root.min(m - root) as i64)
+}
+
+// 建平方根表的最大模数。表占 16m 字节，2^21 时约 32 MB；再大就不建表
+pub const SQRT_TABLE_MAX: i64 = 1 << 21;
+
+// 模 m 的平方根表：roots(n) 是满足 y² ≡ n (mod m) 的全部 y，从小到大；m 不必是素数。
+// 按 y² mod m 做计数排序，建表 O(m)，之后每次查询 O(1)。
+// m 超过 SQRT_TABLE_MAX 时不建表，roots 返回 None，调用方退回逐个 x 的 Tonelli–Shanks
+#[derive(Debug, Clone)]
+pub struct SqrtTable {
+    modulus: i64,
+    // 余数 n 的根在 roots[start[n]..start[n + 1]]
+    table: Option<(Vec<usize>, Vec<i64>)>,
+    prime: bool,
+}
+
+impl SqrtTable {
+    pub fn new(m: i64) -> Self {
+        if m > SQRT_TABLE_MAX {
+            tracing::debug!(m, "modulus too large for a square-root table");
+            return SqrtTable {
+                modulus: m,
+                table: None,
+                prime: is_prime(m),
+            };
+        }
+        let size = m as usize;
+        let square = |y: i64| (y as i128 * y as i128 % m as i128) as usize;
+        let mut start = vec![0; size + 1];
+        for y in 0..m {
+            start[square(y) + 1] += 1;
+        }
+        for n in 0..size {
+            start[n + 1] += start[n];
+        }
+        let mut next = start.clone();
+        let mut roots = vec![0; size];
+        for y in 0..m {
+            let n = square(y);
+            roots[next[n]] = y;
+            next[n] += 1;
+        }
+        SqrtTable {
+            modulus: m,
+            table: Some((start, roots)),
+            prime: is_prime(m),
+        }
+    }
+
+    pub fn prime(&self) -> bool {
+        self.prime
+    }
+
+    pub fn roots(&self, n: i64) -> Option<&[i64]> {
+        let (start, roots) = self.table.as_ref()?;
+        let n = n.rem_euclid(self.modulus) as usize;
+        Some(&roots[start[n]..start[n + 1]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_mod_finds_the_smaller_root() {
+        // 97 ≡ 1 (mod 4) 走 Tonelli–Shanks，103 ≡ 3 (mod 4) 走快速路径，65537 − 1 = 2^16
+        for p in [2, 5, 97, 103, 65537] {
+            for n in 0..p.min(3000) {
+                match sqrt_mod(n, p) {
+                    Some(r) => {
+                        assert_eq!(r * r % p, n, "sqrt({n}) mod {p}");
+                        assert!(2 * r <= p);
+                    }
+                    None => assert!(!is_quadratic_residue(n, p), "{n} mod {p}"),
+                }
+            }
+        }
+        assert_eq!(sqrt_mod(-1, 97), Some(22));
+        assert_eq!(sqrt_mod(5, 97), None);
+    }
+}
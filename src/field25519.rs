@@ -0,0 +1,282 @@
+// F_p，p = 2^255 − 19。i64 装不下，用 5 个 51 位的肢（limb）表示，乘法在 u128 里做，
+// 高位乘出来的 2^255 按 19 折回最低位。各运算的结果只做弱约化（每肢略超 51 位也行），
+// 编码成字节时才约化到 [0, p)。
+use std::ops::{Add, Mul, Neg, Sub};
+
+const MASK: u64 = (1 << 51) - 1;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Fe([u64; 5]);
+
+impl Fe {
+    pub const ZERO: Fe = Fe([0; 5]);
+    pub const ONE: Fe = Fe([1, 0, 0, 0, 0]);
+
+    // 小端 32 字节，最高位（第 255 位）忽略，不要求小于 p
+    pub fn from_bytes(bytes: &[u8; 32]) -> Fe {
+        let load = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().expect("8 bytes"));
+        // 第 i 肢从第 51i 位开始
+        Fe([
+            load(0) & MASK,
+            (load(6) >> 3) & MASK,
+            (load(12) >> 6) & MASK,
+            (load(19) >> 1) & MASK,
+            (load(24) >> 12) & MASK,
+        ])
+    }
+
+    // 约化到 [0, p) 后的小端编码
+    pub fn to_bytes(self) -> [u8; 32] {
+        let mut l = carry(carry(self.0));
+        // l 现在 < 2^255 + 小量；q = 1 当且仅当 l ≥ p
+        let mut q = (l[0] + 19) >> 51;
+        for limb in &l[1..] {
+            q = (limb + q) >> 51;
+        }
+        l[0] += 19 * q;
+        for i in 0..4 {
+            l[i + 1] += l[i] >> 51;
+            l[i] &= MASK;
+        }
+        // 丢掉 2^255
+        l[4] &= MASK;
+        let mut bytes = [0; 32];
+        let mut acc: u128 = 0;
+        let mut bits = 0;
+        let mut index = 0;
+        for limb in l {
+            acc |= (limb as u128) << bits;
+            bits += 51;
+            while bits >= 8 && index < 32 {
+                bytes[index] = acc as u8;
+                acc >>= 8;
+                bits -= 8;
+                index += 1;
+            }
+        }
+        if index < 32 {
+            bytes[index] = acc as u8;
+        }
+        bytes
+    }
+
+    pub fn square(self) -> Fe {
+        self * self
+    }
+
+    // 乘一个小常数，比如 Montgomery ladder 里的 (A − 2) / 4 = 121665
+    pub fn mul_small(self, k: u32) -> Fe {
+        let mut wide = [0u128; 5];
+        for (w, &limb) in wide.iter_mut().zip(&self.0) {
+            *w = limb as u128 * k as u128;
+        }
+        Fe(carry_wide(wide))
+    }
+
+    // Fermat：a^(p − 2)，0 的逆记为 0
+    pub fn invert(self) -> Fe {
+        // p − 2 = 2^255 − 21
+        let mut exponent = [0xff; 32];
+        exponent[0] = 0xeb;
+        exponent[31] = 0x7f;
+        self.pow(&exponent)
+    }
+
+    // a^((p − 5) / 8) = a^(2^252 − 3)，Edwards 点解压时开平方用
+    pub fn pow_p58(self) -> Fe {
+        let mut exponent = [0xff; 32];
+        exponent[0] = 0xfd;
+        exponent[31] = 0x0f;
+        self.pow(&exponent)
+    }
+
+    // 小端指数，从高位到低位平方再乘
+    fn pow(self, exponent: &[u8; 32]) -> Fe {
+        let mut result = Fe::ONE;
+        for bit in (0..256).rev() {
+            result = result.square();
+            if (exponent[bit / 8] >> (bit % 8)) & 1 == 1 {
+                result = result * self;
+            }
+        }
+        result
+    }
+
+    // RFC 8032 的符号位：约化后的最低位
+    pub fn is_negative(self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    // choice 为 1 时交换，不按秘密位分支
+    pub fn conditional_swap(a: &mut Fe, b: &mut Fe, choice: u64) {
+        let mask = choice.wrapping_neg();
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+}
+
+// 每肢进位到下一肢，最高肢的进位乘 19 回到最低肢
+fn carry(mut l: [u64; 5]) -> [u64; 5] {
+    for i in 0..4 {
+        l[i + 1] += l[i] >> 51;
+        l[i] &= MASK;
+    }
+    let top = l[4] >> 51;
+    l[4] &= MASK;
+    l[0] += top * 19;
+    l
+}
+
+fn carry_wide(mut w: [u128; 5]) -> [u64; 5] {
+    for i in 0..4 {
+        w[i + 1] += w[i] >> 51;
+        w[i] &= MASK as u128;
+    }
+    let top = w[4] >> 51;
+    w[4] &= MASK as u128;
+    w[0] += top * 19;
+    let mut l = [0; 5];
+    for (limb, &wide) in l.iter_mut().zip(&w) {
+        *limb = wide as u64;
+    }
+    // 最低肢可能又超过 51 位，再进一位
+    l[1] += l[0] >> 51;
+    l[0] &= MASK;
+    l
+}
+
+impl PartialEq for Fe {
+    fn eq(&self, other: &Fe) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for Fe {}
+
+impl Add for Fe {
+    type Output = Fe;
+    fn add(self, rhs: Fe) -> Fe {
+        let mut l = self.0;
+        for (x, y) in l.iter_mut().zip(&rhs.0) {
+            *x += y;
+        }
+        Fe(carry(l))
+    }
+}
+
+impl Sub for Fe {
+    type Output = Fe;
+    // 先加上 16p 免得下溢，两边的肢都不超过 2^54
+    fn sub(self, rhs: Fe) -> Fe {
+        const SIXTEEN_P: [u64; 5] = [
+            36028797018963664,
+            36028797018963952,
+            36028797018963952,
+            36028797018963952,
+            36028797018963952,
+        ];
+        let mut l = [0; 5];
+        for i in 0..5 {
+            l[i] = self.0[i] + SIXTEEN_P[i] - rhs.0[i];
+        }
+        Fe(carry(l))
+    }
+}
+
+impl Neg for Fe {
+    type Output = Fe;
+    fn neg(self) -> Fe {
+        Fe::ZERO - self
+    }
+}
+
+impl Mul for Fe {
+    type Output = Fe;
+    fn mul(self, rhs: Fe) -> Fe {
+        let a = self.0.map(|x| x as u128);
+        let b = rhs.0.map(|x| x as u128);
+        // 2^255 ≡ 19，超过第 4 肢的部分乘 19 折回来
+        let b19 = b.map(|x| x * 19);
+        Fe(carry_wide([
+            a[0] * b[0] + a[1] * b19[4] + a[2] * b19[3] + a[3] * b19[2] + a[4] * b19[1],
+            a[0] * b[1] + a[1] * b[0] + a[2] * b19[4] + a[3] * b19[3] + a[4] * b19[2],
+            a[0] * b[2] + a[1] * b[1] + a[2] * b[0] + a[3] * b19[4] + a[4] * b19[3],
+            a[0] * b[3] + a[1] * b[2] + a[2] * b[1] + a[3] * b[0] + a[4] * b19[4],
+            a[0] * b[4] + a[1] * b[3] + a[2] * b[2] + a[3] * b[1] + a[4] * b[0],
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small(value: u64) -> Fe {
+        let mut bytes = [0; 32];
+        bytes[..8].copy_from_slice(&value.to_le_bytes());
+        Fe::from_bytes(&bytes)
+    }
+
+    // 用 u128 可以直接算的小数检验
+    #[test]
+    fn small_values_agree_with_integer_arithmetic() {
+        let (a, b) = (0x1234_5678_9abc_u64, 0xfedc_ba98_7654_u64);
+        let product = a as u128 * b as u128;
+        let mut expected = [0; 32];
+        expected[..16].copy_from_slice(&product.to_le_bytes());
+        assert_eq!((small(a) * small(b)).to_bytes(), expected);
+        assert_eq!(small(5) - small(7) + small(2), Fe::ZERO);
+        assert_eq!(small(3).mul_small(121665), small(364995));
+    }
+
+    #[test]
+    fn encoding_reduces_mod_p() {
+        // p 本身编码成 0，p + 1 编码成 1，2^255 − 1 = p + 18
+        let mut p = [0xff; 32];
+        p[0] = 0xed;
+        p[31] = 0x7f;
+        assert_eq!(Fe::from_bytes(&p).to_bytes(), [0; 32]);
+        p[0] = 0xee;
+        assert_eq!(Fe::from_bytes(&p), Fe::ONE);
+        let all_ones = [0xff; 32];
+        assert_eq!(Fe::from_bytes(&all_ones), small(18));
+        // −1 = p − 1
+        let minus_one = Fe::ZERO - Fe::ONE;
+        let mut expected = [0xff; 32];
+        expected[0] = 0xec;
+        expected[31] = 0x7f;
+        assert_eq!(minus_one.to_bytes(), expected);
+    }
+
+    #[test]
+    fn inverse_and_swap() {
+        for value in [1, 2, 9, 121665, u64::MAX] {
+            let x = small(value);
+            assert_eq!(x * x.invert(), Fe::ONE);
+        }
+        assert_eq!(Fe::ZERO.invert(), Fe::ZERO);
+        let (mut a, mut b) = (small(1), small(2));
+        Fe::conditional_swap(&mut a, &mut b, 0);
+        assert_eq!((a, b), (small(1), small(2)));
+        Fe::conditional_swap(&mut a, &mut b, 1);
+        assert_eq!((a, b), (small(2), small(1)));
+    }
+
+    #[test]
+    fn square_roots_via_p58() {
+        // p ≡ 5 (mod 8)：a 是平方时 x = a^((p + 3) / 8) = a·a^((p − 5) / 8) 满足 x² = ±a
+        for value in [2, 3, 121665, 121666] {
+            let a = small(value).square();
+            let x = a * a.pow_p58();
+            assert!(x.square() == a || x.square() == -a, "{value}");
+        }
+        let three = small(3) * small(3).pow_p58();
+        assert_eq!(three.square(), small(3));
+        // 符号位是最低位：1 是“负”的，p − 1 是偶数
+        assert!(Fe::ONE.is_negative());
+        assert!(!(-Fe::ONE).is_negative());
+    }
+}
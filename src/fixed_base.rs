@@ -0,0 +1,79 @@
+// 固定基点的标量乘法：预先算好 d·16^j·G（d = 1..15），之后每次乘法只需要
+// 每 4 位做一次查表加法，不再需要倍点。适合同一个基点反复使用的场合，例如 Pedersen 的生成元。
+use crate::curve::{Coords, Curve};
+use crate::jacobian::JacobianPoint;
+
+const WINDOW: u32 = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedBase {
+    curve: Curve,
+    base: Coords,
+    // table[j][d - 1] = d·16^j·G
+    table: Vec<Vec<Coords>>,
+}
+
+impl FixedBase {
+    // bits 是标量的最大位数，超过的部分回退到普通的倍点-加法
+    pub fn new(curve: &Curve, base: Coords, bits: u32) -> Self {
+        let windows = bits.min(u64::BITS).div_ceil(WINDOW).max(1);
+        // 先在 Jacobian 坐标里算出所有倍数，最后一起求逆转回仿射坐标
+        let mut multiples = Vec::with_capacity(windows as usize * ((1 << WINDOW) - 1));
+        let mut window_base = JacobianPoint::from_coords(curve, base);
+        for _ in 0..windows {
+            let mut multiple = JacobianPoint::identity();
+            for _ in 1..1 << WINDOW {
+                multiple = multiple.add(window_base, curve);
+                multiples.push(multiple);
+            }
+            // 下一个窗口的基点 16·G = 15·G + G
+            window_base = multiple.add(window_base, curve);
+        }
+        let table = JacobianPoint::batch_to_coords(&multiples, curve)
+            .chunks((1 << WINDOW) - 1)
+            .map(<[Coords]>::to_vec)
+            .collect();
+        FixedBase {
+            curve: *curve,
+            base,
+            table,
+        }
+    }
+
+    pub fn base(&self) -> Coords {
+        self.base
+    }
+
+    pub fn mul(&self, k: u64) -> Coords {
+        let windows = self.table.len() as u32;
+        if windows * WINDOW < u64::BITS && k >> (windows * WINDOW) != 0 {
+            return self.curve.mul_coords(self.base, k);
+        }
+        let mut result = None;
+        for (j, row) in self.table.iter().enumerate() {
+            let digit = (k >> (j as u32 * WINDOW)) & ((1 << WINDOW) - 1);
+            if digit != 0 {
+                result = self.curve.add_coords(result, row[digit as usize - 1]);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_double_and_add() {
+        let curve = Curve::new(1, 1, 599);
+        let g = Some((1, 188));
+        let table = FixedBase::new(&curve, g, 10);
+        for k in [0, 1, 2, 15, 16, 17, 255, 596, 597, 1000, 1023] {
+            assert_eq!(table.mul(k), curve.mul_coords(g, k), "k = {k}");
+        }
+        // 超过预算的位数时结果仍然正确
+        assert_eq!(table.mul(1 << 20), curve.mul_coords(g, 1 << 20));
+        assert_eq!(FixedBase::new(&curve, None, 8).mul(5), None);
+    }
+}
@@ -0,0 +1,294 @@
+// 模糊测试入口：每个函数接受任意字节，内部检查不变式，违反时 panic。
+// fuzz/ 下每个 cargo-fuzz target 对应这里的一个函数，只把 libfuzzer 给的数据原样转交；
+// 测试里用固定种子的随机字节跑同样的入口，保证它们一直能用。
+use crate::affine::AffinePoint;
+use crate::curve::{Coords, Curve};
+use crate::field;
+use crate::jacobian::JacobianPoint;
+use crate::point::{Coordinates, Point};
+
+// 字节不够时补 0，任何输入都能被解码
+struct Input<'a> {
+    data: &'a [u8],
+}
+
+impl Input<'_> {
+    fn u16(&mut self) -> u16 {
+        let mut bytes = [0; 2];
+        for byte in &mut bytes {
+            if let Some((&first, rest)) = self.data.split_first() {
+                *byte = first;
+                self.data = rest;
+            }
+        }
+        u16::from_le_bytes(bytes)
+    }
+
+    fn i64(&mut self) -> i64 {
+        (self.u16() as i64) << 16 | self.u16() as i64
+    }
+
+    // [5, 300) 中不小于输入的第一个素数，枚举点仍然很快
+    fn small_prime(&mut self) -> i64 {
+        let mut p = 5 + self.u16() as i64 % 250;
+        while !field::is_prime(p) {
+            p += 1;
+        }
+        p
+    }
+}
+
+// 同一组输入分别走 Coords、Point、AffinePoint、Jacobian、批量求逆和（ct 下的）射影完全公式，
+// 结果必须一致
+pub fn arithmetic(data: &[u8]) {
+    let mut input = Input { data };
+    let p = input.small_prime();
+    let curve = Curve::new(input.u16() as i64, input.u16() as i64, p);
+    if curve.is_singular() {
+        return;
+    }
+    let points: Vec<Coords> = curve.points().iter().map(Point::coords).collect();
+    let mut pick = || {
+        let index = input.u16() as usize % (points.len() + 1);
+        points.get(index).copied().flatten()
+    };
+    let (lhs, rhs) = (pick(), pick());
+    let k = input.u16() as u64;
+
+    let sum = curve.add_coords(lhs, rhs);
+    if let Some((x, y)) = sum {
+        assert!(
+            curve.contains(x, y),
+            "{curve:?}: {lhs:?} + {rhs:?} = {sum:?}"
+        );
+    }
+    let point_sum = Point::from_coords(&curve, lhs) + Point::from_coords(&curve, rhs);
+    assert_eq!(
+        point_sum.coords(),
+        sum,
+        "{curve:?}: Point {lhs:?} + {rhs:?}"
+    );
+    let lift = |coords: Coords| match coords {
+        Some((x, y)) => AffinePoint::new(&curve, x, y),
+        None => AffinePoint::identity(&curve),
+    };
+    assert_eq!(
+        (lift(lhs) + lift(rhs)).coords(),
+        sum,
+        "{curve:?}: AffinePoint {lhs:?} + {rhs:?}"
+    );
+    assert_eq!(
+        curve.add_to_all(&[lhs], rhs),
+        vec![sum],
+        "{curve:?}: batched {lhs:?} + {rhs:?}"
+    );
+    let (jl, jr) = (
+        JacobianPoint::from_coords(&curve, lhs),
+        JacobianPoint::from_coords(&curve, rhs),
+    );
+    assert_eq!(
+        jl.add(jr, &curve).to_coords(&curve),
+        sum,
+        "{curve:?}: Jacobian {lhs:?} + {rhs:?}"
+    );
+
+    let kp = curve.mul_coords(lhs, k);
+    for (name, mul) in [
+        ("double-and-add", JacobianPoint::mul as fn(_, _, &_) -> _),
+        ("ladder", JacobianPoint::ladder),
+        ("wNAF", JacobianPoint::wnaf),
+    ] {
+        assert_eq!(
+            mul(jl, k, &curve).to_coords(&curve),
+            kp,
+            "{curve:?}: Jacobian {name} {k} * {lhs:?}"
+        );
+    }
+    if k > 0 {
+        let previous = curve.mul_coords(lhs, k - 1);
+        assert_eq!(
+            curve.add_coords(previous, lhs),
+            kp,
+            "{curve:?}: {k} * {lhs:?}"
+        );
+    }
+
+    #[cfg(feature = "ct")]
+    if points.iter().all(|c| c.is_some_and(|(_, y)| y != 0)) {
+        use crate::ct::{self, ProjectivePoint};
+
+        let projective = ct::complete_add(
+            &curve,
+            ProjectivePoint::from_coords(&curve, lhs),
+            ProjectivePoint::from_coords(&curve, rhs),
+        );
+        assert_eq!(
+            projective.to_coords(&curve),
+            sum,
+            "{curve:?}: projective {lhs:?} + {rhs:?}"
+        );
+    }
+}
+
+// SEC1 解码：模数可以是合数、曲线可以奇异，只能返回 Err 不能 panic。
+// 解出来的点在曲线上，并且按输入的形式重新编码得到同样的字节
+pub fn from_bytes(data: &[u8]) {
+    let mut input = Input { data };
+    let p = 2 + input.u16() as i64 % 400;
+    let curve = Curve::new(input.u16() as i64, input.u16() as i64, p);
+    let bytes = input.data;
+    let Ok(point) = Point::from_bytes(bytes, &curve) else {
+        return;
+    };
+    assert!(
+        curve.contains_point(&point),
+        "{curve:?}: {bytes:?} -> {point}"
+    );
+    let compressed = matches!(bytes.first(), Some(0x02 | 0x03));
+    assert_eq!(
+        point.to_bytes(compressed),
+        bytes,
+        "{curve:?}: {bytes:?} -> {point}"
+    );
+}
+
+// 坐标文本：解析成功的结果按 Display 写出来再解析，得到同一个值；
+// Curve::parse_point 只返回曲线上的点
+pub fn parse_coords(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(coords) = text.parse::<Coordinates>() else {
+        return;
+    };
+    assert_eq!(
+        coords.to_string().parse(),
+        Ok(coords),
+        "{text:?} -> {coords}"
+    );
+    let curve = Curve::new(2, 3, 97);
+    if let Ok(point) = curve.parse_point(text) {
+        assert!(curve.contains_point(&point), "{text:?} -> {point}");
+    }
+}
+
+// 任意参数交给 builder 和带错误的加法：只能返回 Err，不能 panic；
+// 返回 Ok 时结果必须自洽
+pub fn validation(data: &[u8]) {
+    let mut input = Input { data };
+    let p = input.u16() as i64 % 400;
+    let (a, b) = (input.i64(), input.i64());
+    let (gx, gy) = (input.i64(), input.i64());
+
+    if let Ok(params) = Curve::builder()
+        .a(a)
+        .b(b)
+        .prime(p)
+        .generator(gx, gy)
+        .build()
+    {
+        let curve = params.curve;
+        assert!(field::is_prime(curve.p) && !curve.is_singular());
+        assert!(params.generator.coords().is_some());
+        assert!(
+            curve
+                .mul_coords(params.generator.coords(), params.order)
+                .is_none()
+        );
+        assert_eq!(params.order * params.cofactor, curve.group_order());
+    }
+
+    // 模数可以是合数，坐标也不必在曲线上；素数模下只有不在曲线上的点
+    // （x 相同但 y 既不相等也不互为相反数）才会遇到不可逆的分母
+    if p >= 2 {
+        let curve = Curve::new(a, b, p);
+        let (qx, qy) = (input.i64(), input.i64());
+        match curve.try_add_coords(Some((gx, gy)), Some((qx, qy))) {
+            Ok(Some((x, y))) => assert!((0..p).contains(&x) && (0..p).contains(&y)),
+            Ok(None) => {}
+            Err(error) => assert!(
+                !field::is_prime(p) || !curve.contains(gx, gy) || !curve.contains(qx, qy),
+                "{curve:?}: {error}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+    use rand_core::RngCore;
+
+    const SEED: u64 = 0xf022;
+    const RUNS: usize = 512;
+
+    fn random_inputs() -> impl Iterator<Item = Vec<u8>> {
+        let mut rng = rng::seeded(SEED);
+        (0..RUNS).map(move |_| {
+            let mut data = vec![0; rng::uniform_below(&mut rng, 40) as usize];
+            rng.fill_bytes(&mut data);
+            data
+        })
+    }
+
+    #[test]
+    fn arithmetic_backends_agree() {
+        for data in random_inputs() {
+            arithmetic(&data);
+        }
+    }
+
+    #[test]
+    fn validation_never_panics() {
+        for data in random_inputs() {
+            validation(&data);
+        }
+    }
+
+    #[test]
+    fn sec1_decoding_never_panics() {
+        for data in random_inputs() {
+            from_bytes(&data);
+        }
+        // 随机字节很少是合法编码，再用真实的点喂一遍
+        let curve = Curve::new(2, 3, 97);
+        for point in curve.points() {
+            for compressed in [true, false] {
+                let mut data = vec![97 - 2, 0, 2, 0, 3, 0];
+                data.extend(point.to_bytes(compressed));
+                from_bytes(&data);
+            }
+        }
+    }
+
+    #[test]
+    fn coordinate_parsing_round_trips() {
+        // 随机字节几乎都不是坐标，换成只含坐标字符的文本
+        const ALPHABET: &[u8] = b"0123456789-(), ";
+        for data in random_inputs() {
+            let text: Vec<u8> = data
+                .iter()
+                .map(|&byte| ALPHABET[byte as usize % ALPHABET.len()])
+                .collect();
+            parse_coords(&text);
+            parse_coords(&data);
+        }
+        for text in [
+            "(3, 6)",
+            "Infinity",
+            " ( -1 , 96 ) ",
+            "9223372036854775807,-9223372036854775808",
+        ] {
+            parse_coords(text.as_bytes());
+        }
+    }
+
+    #[test]
+    fn empty_input_is_accepted() {
+        arithmetic(&[]);
+        validation(&[]);
+        from_bytes(&[]);
+        parse_coords(&[]);
+    }
+}
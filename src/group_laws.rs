@@ -0,0 +1,198 @@
+// 群公理的性质测试：随机生成曲线和点，检查结合律、交换律、单位元、逆元等。
+// 新的点运算公式（射影、Edwards、完全公式……）都应该在这里通过。
+use crate::curve::Curve;
+use crate::field;
+use crate::point::Point;
+use crate::rng::{self, DemoRng};
+
+const SEED: u64 = 0x5eed_e11c;
+const CASES: usize = 64;
+const POINTS_PER_CASE: usize = 16;
+
+struct Case {
+    curve: Curve,
+    points: Vec<Point>,
+    rng: DemoRng,
+}
+
+impl Case {
+    // 以一定概率返回无穷远点，保证单位元也被覆盖到
+    fn point(&mut self) -> Point {
+        let index = rng::uniform_below(&mut self.rng, self.points.len() as u64 + 1) as usize;
+        self.points
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| Point::from_coords(&self.curve, None))
+    }
+
+    fn scalar(&mut self) -> u64 {
+        rng::uniform_below(&mut self.rng, 4 * self.points.len() as u64 + 8)
+    }
+}
+
+fn random_prime(rng: &mut DemoRng) -> i64 {
+    loop {
+        let p = 5 + rng::uniform_below(rng, 300) as i64;
+        if field::is_prime(p) {
+            return p;
+        }
+    }
+}
+
+// 每个 case 是一条随机的非奇异曲线，失败信息里带着曲线参数便于复现
+fn cases() -> impl Iterator<Item = Case> {
+    let mut rng = rng::seeded(SEED);
+    std::iter::from_fn(move || {
+        loop {
+            let p = random_prime(&mut rng);
+            let curve = Curve::random(&mut rng, p);
+            let points = curve.points();
+            if !points.is_empty() {
+                let case_rng = rng::seeded(rng::uniform_below(&mut rng, u64::MAX));
+                return Some(Case {
+                    curve,
+                    points,
+                    rng: case_rng,
+                });
+            }
+        }
+    })
+    .take(CASES)
+}
+
+#[test]
+fn addition_is_associative() {
+    for mut case in cases() {
+        for _ in 0..POINTS_PER_CASE {
+            let (p, q, r) = (case.point(), case.point(), case.point());
+            assert_eq!(
+                (&p + &q) + &r,
+                &p + (&q + &r),
+                "{:?}: P = {p:?}, Q = {q:?}, R = {r:?}",
+                case.curve
+            );
+        }
+    }
+}
+
+#[test]
+fn addition_is_commutative() {
+    for mut case in cases() {
+        for _ in 0..POINTS_PER_CASE {
+            let (p, q) = (case.point(), case.point());
+            assert_eq!(&p + &q, &q + &p, "{:?}: P = {p:?}, Q = {q:?}", case.curve);
+        }
+    }
+}
+
+#[test]
+fn infinity_is_the_identity() {
+    for mut case in cases() {
+        let o = Point::from_coords(&case.curve, None);
+        for _ in 0..POINTS_PER_CASE {
+            let p = case.point();
+            assert_eq!(&p + &o, p, "{:?}: P = {p:?}", case.curve);
+            assert_eq!(&o + &p, p, "{:?}: P = {p:?}", case.curve);
+        }
+    }
+}
+
+#[test]
+fn every_point_has_an_inverse() {
+    for mut case in cases() {
+        for _ in 0..POINTS_PER_CASE {
+            let p = case.point();
+            assert!((&p + &(-&p)).is_infinity(), "{:?}: P = {p:?}", case.curve);
+            assert!((&p - &p).is_infinity(), "{:?}: P = {p:?}", case.curve);
+        }
+    }
+}
+
+#[test]
+fn scalar_multiplication_distributes_over_addition() {
+    for mut case in cases() {
+        for _ in 0..POINTS_PER_CASE {
+            let p = case.point();
+            let (m, n) = (case.scalar(), case.scalar());
+            assert_eq!(
+                &p * (m + n),
+                &p * m + &p * n,
+                "{:?}: P = {p:?}, m = {m}, n = {n}",
+                case.curve
+            );
+        }
+    }
+}
+
+#[test]
+fn point_order_divides_group_order() {
+    for mut case in cases() {
+        let group_order = case.curve.group_order();
+        for _ in 0..POINTS_PER_CASE {
+            let p = case.point();
+            let order = case.curve.order_of(p.coords(), group_order);
+            assert!(
+                group_order.is_multiple_of(order),
+                "{:?}: P = {p:?}, order {order}, #E = {group_order}",
+                case.curve
+            );
+            assert!((&p * order).is_infinity(), "{:?}: P = {p:?}", case.curve);
+            assert!(
+                (&p * group_order).is_infinity(),
+                "{:?}: P = {p:?}",
+                case.curve
+            );
+        }
+    }
+}
+
+#[cfg(feature = "ct")]
+#[test]
+fn ladder_matches_double_and_add() {
+    use crate::ct;
+
+    for mut case in cases() {
+        for _ in 0..POINTS_PER_CASE {
+            let p = case.point();
+            let k = case.scalar();
+            let mut expected = None;
+            for _ in 0..k {
+                expected = case.curve.add_coords(expected, p.coords());
+            }
+            assert_eq!(
+                ct::ladder_mul(&case.curve, p.coords(), k),
+                Ok(expected),
+                "{:?}: P = {p:?}, k = {k}",
+                case.curve
+            );
+        }
+    }
+}
+
+// 合数模数上费马求逆给出的是错误的点，ladder 必须拒绝，mul_coords 退回仿射版本
+#[cfg(feature = "ct")]
+#[test]
+fn ladder_rejects_composite_moduli() {
+    use crate::ct;
+    use crate::error::EcError;
+
+    let curve = Curve::new(1, 1, 91);
+    let mut compared = 0;
+    for point in curve.points() {
+        for k in 0..24 {
+            assert_eq!(
+                ct::ladder_mul(&curve, point.coords(), k),
+                Err(EcError::NotPrime(91))
+            );
+            if let Ok(expected) = curve.try_mul_coords(point.coords(), k) {
+                assert_eq!(
+                    curve.mul_coords(point.coords(), k),
+                    expected,
+                    "{point}, k = {k}"
+                );
+                compared += 1;
+            }
+        }
+    }
+    assert!(compared > 0);
+}
@@ -0,0 +1,271 @@
+// 哈希到曲线：从消息确定地得到一个谁都不知道离散对数的点，用作生成元或者要签名的消息点。
+// 玩具曲线用试探-递增：x = SHA-256(标签 ‖ 消息 ‖ 计数器) mod p，曲线上有这个 x 的点就用哈希的
+// 最后一位挑一个 y，没有就把计数器加一。耗时取决于消息，真实曲线上不能这么做。
+// 真实曲线用 RFC 9380 的简化 SWU 映射：u ∈ F_p 到曲线的映射是一个固定的式子，不用试探；
+// hash_to_field 取两个 u，两个映射点相加（random oracle 版本）。这里只处理 p ≡ 3 (mod 4)、
+// a、b 都不为零的曲线，P-256 正好满足，余因子是 1。
+use crate::bigcurve::{BigCurve, BigPoint, Elem, FieldBackend};
+use crate::curve::Curve;
+use crate::sha256::{Sha256, sha256};
+use crate::u256::{self, Limbs};
+
+// 默认的域分隔标签
+pub const DST: &[u8] = b"elliptic-curve hash-to-point";
+
+// 每次约一半的概率成功，试这么多次还找不到说明曲线上几乎没有点
+const MAX_TRIES: u32 = 1 << 16;
+
+// 依次给出每个成功的计数器和对应的点
+pub fn candidates<'a>(
+    curve: &'a Curve,
+    dst: &'a [u8],
+    message: &'a [u8],
+) -> impl Iterator<Item = (u32, (i64, i64))> + 'a {
+    let p = curve.p as u64;
+    (0..MAX_TRIES).filter_map(move |counter| {
+        let digest = Sha256::new()
+            .update(dst)
+            .update(message)
+            .update(&counter.to_be_bytes())
+            .finalize();
+        let x = u64::from_be_bytes(digest[..8].try_into().expect("8 bytes")) % p;
+        let ys = curve.points_at(x as i64);
+        let point = ys.get(digest[31] as usize % 2).or(ys.first())?;
+        Some((counter, point.coords()?))
+    })
+}
+
+// 曲线上没有仿射点时为 None
+pub fn hash_to_point(message: &[u8], curve: &Curve) -> Option<(i64, i64)> {
+    candidates(curve, DST, message)
+        .next()
+        .map(|(_, point)| point)
+}
+
+// RFC 9380 第 5.3.1 节，SHA-256 版本
+pub fn expand_message_xmd(message: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    assert!(
+        dst.len() <= 255 && len <= 255 * 32,
+        "DST or output too long"
+    );
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b0 = Sha256::new()
+        .update(&[0; 64])
+        .update(message)
+        .update(&(len as u16).to_be_bytes())
+        .update(&[0])
+        .update(&dst_prime)
+        .finalize();
+    let mut blocks = vec![sha256(&[&b0[..], &[1], &dst_prime].concat())];
+    while blocks.len() * 32 < len {
+        let previous = blocks.last().expect("starts with one block");
+        let mixed: Vec<u8> = b0.iter().zip(previous).map(|(a, b)| a ^ b).collect();
+        let index = blocks.len() as u8 + 1;
+        blocks.push(sha256(&[&mixed[..], &[index], &dst_prime].concat()));
+    }
+    let mut out = blocks.concat();
+    out.truncate(len);
+    out
+}
+
+pub struct Sswu {
+    pub curve: BigCurve,
+    a: Elem,
+    b: Elem,
+    z: Elem,
+    // hash_to_field 每个元素用的字节数 L = ⌈(⌈log2 p⌉ + 128) / 8⌉
+    len: usize,
+    // (p + 1)/4 求平方根，(p − 1)/2 判断是不是平方
+    sqrt_exponent: Limbs,
+    legendre_exponent: Limbs,
+}
+
+impl Sswu {
+    // z 是 RFC 9380 附录 H.2 选出的非平方数，调用方负责
+    pub fn new(a: &Limbs, b: &Limbs, p: &Limbs, z: &Limbs) -> Result<Self, String> {
+        if p[0] % 4 != 3 {
+            return Err("only p ≡ 3 (mod 4) is supported".to_string());
+        }
+        let curve = BigCurve::new(a, b, p)?;
+        let f = &curve.field;
+        let (a, b, z) = (f.elem(a), f.elem(b), f.elem(z));
+        if f.is_zero(a) || f.is_zero(b) {
+            return Err("the simplified SWU map needs a ≠ 0 and b ≠ 0".to_string());
+        }
+        let bits = 256 - u256::leading_zeros(p) as usize;
+        let sqrt_exponent = shift_right(&u256::add_limbs(p, &[1, 0, 0, 0]).0, 2);
+        let legendre_exponent = shift_right(p, 1);
+        Ok(Sswu {
+            curve,
+            a,
+            b,
+            z,
+            len: (bits + 128).div_ceil(8),
+            sqrt_exponent,
+            legendre_exponent,
+        })
+    }
+
+    // P256_XMD:SHA-256_SSWU_RO_，Z = −10
+    pub fn p256() -> Self {
+        let preset = crate::presets::p256();
+        let z = u256::sub_limbs(&preset.p, &[10, 0, 0, 0]).0;
+        Sswu::new(&preset.a, &preset.b, &preset.p, &z).expect("P-256 satisfies the requirements")
+    }
+
+    pub fn hash_to_field(&self, message: &[u8], dst: &[u8]) -> [Elem; 2] {
+        let bytes = expand_message_xmd(message, dst, 2 * self.len);
+        let f = &self.curve.field;
+        let p = f.modulus();
+        [0, 1].map(|i| {
+            // 大端字节串放进 512 位的整数再模 p
+            let mut wide = [0u64; 8];
+            for (j, &byte) in bytes[i * self.len..(i + 1) * self.len]
+                .iter()
+                .rev()
+                .enumerate()
+            {
+                wide[j / 8] |= u64::from(byte) << (8 * (j % 8));
+            }
+            f.elem(&u256::reduce_generic(&wide, &p))
+        })
+    }
+
+    // RFC 9380 第 6.6.2 节的直接写法
+    pub fn map_to_curve(&self, u: Elem) -> BigPoint {
+        let f = &self.curve.field;
+        let g = |x: Elem| f.add(f.mul(f.add(f.square(x), self.a), x), self.b);
+        let zu2 = f.mul(self.z, f.square(u));
+        // tv1 = 1/(Z²u⁴ + Zu²)，分母为零时取 0
+        let denominator = f.add(f.square(zu2), zu2);
+        let minus_b_over_a = f.neg(f.mul(self.b, f.invert(self.a)));
+        let x1 = if f.is_zero(denominator) {
+            f.mul(self.b, f.invert(f.mul(self.z, self.a)))
+        } else {
+            f.mul(minus_b_over_a, f.add(f.one(), f.invert(denominator)))
+        };
+        let gx1 = g(x1);
+        let (x, gx) = if self.is_square(gx1) {
+            (x1, gx1)
+        } else {
+            let x2 = f.mul(zu2, x1);
+            (x2, g(x2))
+        };
+        let mut y = f.pow(gx, &self.sqrt_exponent);
+        // y 的奇偶和 u 一致
+        if sgn0(&f.value(u)) != sgn0(&f.value(y)) {
+            y = f.neg(y);
+        }
+        self.curve
+            .point(&f.value(x), &f.value(y))
+            .expect("the map lands on the curve")
+    }
+
+    pub fn hash(&self, message: &[u8], dst: &[u8]) -> BigPoint {
+        let [u0, u1] = self.hash_to_field(message, dst);
+        self.curve
+            .add(&self.map_to_curve(u0), &self.map_to_curve(u1))
+    }
+
+    fn is_square(&self, value: Elem) -> bool {
+        let f = &self.curve.field;
+        let legendre = f.pow(value, &self.legendre_exponent);
+        legendre == f.one() || f.is_zero(value)
+    }
+}
+
+fn sgn0(value: &Limbs) -> bool {
+    value[0] & 1 == 1
+}
+
+fn shift_right(value: &Limbs, bits: u32) -> Limbs {
+    let mut out = [0; 4];
+    for i in 0..4 {
+        out[i] = value[i] >> bits;
+        if i < 3 {
+            out[i] |= value[i + 1] << (64 - bits);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(text: &str) -> Limbs {
+        u256::parse(&format!("0x{text}")).unwrap()
+    }
+
+    // RFC 9380 附录 K.1
+    #[test]
+    fn expand_message_xmd_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            crate::sha256::hex(&expand_message_xmd(b"", dst, 0x20)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            crate::sha256::hex(&expand_message_xmd(b"abc", dst, 0x20)),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+    }
+
+    // RFC 9380 附录 J.1.1
+    #[test]
+    fn p256_sswu_vectors() {
+        let dst = b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_";
+        let suite = Sswu::p256();
+        let f = &suite.curve.field;
+        let [u0, u1] = suite.hash_to_field(b"", dst);
+        assert_eq!(
+            f.value(u0),
+            hex("ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009")
+        );
+        assert_eq!(
+            f.value(u1),
+            hex("8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a")
+        );
+        for (message, x, y) in [
+            (
+                &b""[..],
+                "2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4",
+                "8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415",
+            ),
+            (
+                b"abc",
+                "0bb8b87485551aa43ed54f009230450b492fead5f1cc91658775dac4a3388a0f",
+                "5c41b3d0731a27a7b14bc0bf0ccded2d8751f83493404c84a88e71ffd424212e",
+            ),
+        ] {
+            let point = suite.hash(message, dst);
+            assert_eq!(suite.curve.affine(&point), Some((hex(x), hex(y))));
+        }
+    }
+
+    #[test]
+    fn toy_points_are_deterministic_and_on_the_curve() {
+        let curve = Curve::new(2, 3, 97);
+        let (x, y) = hash_to_point(b"hello", &curve).unwrap();
+        assert!(curve.contains(x, y));
+        assert_eq!(hash_to_point(b"hello", &curve), Some((x, y)));
+        let points: Vec<_> = (0..20u8)
+            .filter_map(|i| hash_to_point(&[i], &curve))
+            .collect();
+        assert_eq!(points.len(), 20);
+        assert!(points.iter().any(|&point| point != (x, y)));
+    }
+
+    #[test]
+    fn unsupported_curves_are_rejected() {
+        let secp256k1 = crate::presets::secp256k1();
+        let error = Sswu::new(&secp256k1.a, &secp256k1.b, &secp256k1.p, &[11, 0, 0, 0]);
+        assert!(error.is_err());
+        let curve25519 = crate::presets::curve25519();
+        let error = Sswu::new(&curve25519.a, &curve25519.b, &curve25519.p, &[2, 0, 0, 0]);
+        assert_eq!(
+            error.err().as_deref(),
+            Some("only p ≡ 3 (mod 4) is supported")
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// 无效曲线攻击：ECDH 的服务器收到对方的公钥 Q 后直接算 d·Q，不检查 Q 在不在曲线上。
+// 加法和倍点公式都用不到 b，曲线外的 Q 实际上是在 E': y² = x³ + ax + b'（b' = y² − x³ − ax）上运算。
+// 攻击者挑 #E' 有小素因子 r 的 E'，发一个 r 阶点，服务器的回复 d·Q 只有 r 种可能，
+// 逐个试出 d mod r；凑够了再用中国剩余定理拼出 d。服务器先检查 Q 在曲线上就能拦住。
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::field;
+use crate::jacobian::JacobianPoint;
+
+// 只发阶不超过它的点，攻击者收到回复后逐个试
+pub const MAX_ORDER: u64 = 50;
+
+// 一次查询：E' 上的 r 阶点 Q、服务器的回复和试出来的 d mod r
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub curve: Curve,
+    pub point: (i64, i64),
+    pub order: u64,
+    pub reply: Result<Coords, EcError>,
+    pub residue: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attack {
+    pub queries: Vec<Query>,
+    // 已知 d mod modulus
+    pub modulus: u64,
+    // modulus 超过 #E 时 d 就确定了；服务器拒绝查询时为 None
+    pub recovered: Option<u64>,
+}
+
+// 服务器用私钥 d 乘以收到的点；validate 时先检查点在曲线上。
+// 用 Jacobian 坐标的倍点-加法，公式里没有 b（`ct` 的完全加法公式用到 b，不会落到 E' 上）
+pub fn server(
+    curve: &Curve,
+    d: u64,
+    (x, y): (i64, i64),
+    validate: bool,
+) -> Result<Coords, EcError> {
+    if validate && !curve.contains(x, y) {
+        return Err(EcError::PointNotOnCurve { x, y });
+    }
+    let q = JacobianPoint::from_coords(curve, Some((x, y)));
+    Ok(q.mul(d, curve).to_coords(curve))
+}
+
+impl Attack {
+    // 攻击素数模上的非奇异曲线，d < #E
+    pub fn run(curve: &Curve, d: u64, validate: bool) -> Self {
+        let _span = tracing::debug_span!("invalid_curve_attack", validate).entered();
+        let target = curve.group_order();
+        let mut attack = Attack {
+            queries: Vec::new(),
+            modulus: 1,
+            recovered: None,
+        };
+        let mut known = 0;
+        for b in (0..curve.p).filter(|&b| b != curve.b) {
+            let twist = Curve::new(curve.a, b, curve.p);
+            if twist.is_singular() {
+                continue;
+            }
+            let points = twist.points();
+            let n = points.len() as u64 + 1;
+            for (r, _) in field::factorize(n) {
+                if r > MAX_ORDER || attack.modulus.is_multiple_of(r) {
+                    continue;
+                }
+                // (n / r)·P 不是 O 时就是 r 阶点
+                let Some(q) = points
+                    .iter()
+                    .find_map(|point| twist.mul_coords(point.coords(), n / r))
+                else {
+                    continue;
+                };
+                let reply = server(curve, d, q, validate);
+                let residue = reply
+                    .as_ref()
+                    .ok()
+                    .and_then(|&reply| (0..r).find(|&t| twist.mul_coords(Some(q), t) == reply));
+                attack.queries.push(Query {
+                    curve: twist,
+                    point: q,
+                    order: r,
+                    reply,
+                    residue,
+                });
+                let Some(t) = residue else {
+                    return attack;
+                };
+                // x ≡ known (mod m)，x ≡ t (mod r) ⇒ x = known + m·((t − known)·m⁻¹ mod r)
+                let (m, r) = (attack.modulus as i64, r as i64);
+                let lift =
+                    (t as i64 - known as i64).rem_euclid(r) * field::mod_inverse(m % r, r) % r;
+                known = (known as i64 + m * lift) as u64;
+                attack.modulus *= r as u64;
+                tracing::debug!(b, r, t, known, modulus = attack.modulus, "learned d mod r");
+                if attack.modulus >= target {
+                    attack.recovered = Some(known);
+                    return attack;
+                }
+            }
+        }
+        attack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_order_points_on_other_curves_leak_the_key() {
+        let curve = Curve::new(1, 1, 599);
+        for d in [1, 2, 123, 596] {
+            let attack = Attack::run(&curve, d, false);
+            assert_eq!(attack.recovered, Some(d));
+            assert!(attack.modulus >= 597);
+            for query in &attack.queries {
+                let (x, y) = query.point;
+                assert!(!curve.contains(x, y));
+                assert!(query.curve.contains(x, y));
+                assert!(query.order <= MAX_ORDER && field::is_prime(query.order as i64));
+                assert_eq!(query.residue, Some(d % query.order));
+            }
+        }
+    }
+
+    #[test]
+    fn validating_the_point_stops_the_attack() {
+        let curve = Curve::new(1, 1, 599);
+        let attack = Attack::run(&curve, 123, true);
+        assert_eq!(attack.recovered, None);
+        assert_eq!(attack.queries.len(), 1);
+        let (x, y) = attack.queries[0].point;
+        assert_eq!(
+            attack.queries[0].reply,
+            Err(EcError::PointNotOnCurve { x, y })
+        );
+        // 曲线上的点照常处理
+        assert_eq!(
+            server(&curve, 2, (1, 188), true),
+            Ok(curve.mul_coords(Some((1, 188)), 2))
+        );
+    }
+}
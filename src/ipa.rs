@@ -0,0 +1,349 @@
+// 玩具版的内积论证（Bulletproofs 的 inner-product argument）。
+// 证明者知道长度为 n 的向量 a、b，公开承诺 P = <a, G> + <b, H> + <a, b> U。
+// 每一轮把向量和生成元对半折叠并发送两个点 L、R，log2(n) 轮之后只剩两个标量。
+// 挑战 x 由验证者随机给出（交互式），演示里用种子 RNG 扮演验证者；
+// 非交互版本需要用哈希代替验证者，这里不做。
+use crate::curve::{Coords, Curve};
+use crate::scalar::Scalar;
+use crate::subgroup;
+use rand_core::RngCore;
+
+// 公共参数：素数阶子群里的生成元，标量都模子群的阶 q
+pub struct Setup {
+    pub curve: Curve,
+    pub q: u64,
+    pub g: Vec<Coords>,
+    pub h: Vec<Coords>,
+    pub u: Coords,
+}
+
+// 某一轮折叠之前证明者手里的向量、生成元和当前的承诺，最后一项长度为 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fold {
+    pub a: Vec<Scalar>,
+    pub b: Vec<Scalar>,
+    pub g: Vec<Coords>,
+    pub h: Vec<Coords>,
+    pub commitment: Coords,
+}
+
+// 一轮里证明者发送的 L、R 和验证者回复的挑战
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Round {
+    pub l: Coords,
+    pub r: Coords,
+    pub x: Scalar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proof {
+    pub rounds: Vec<Round>,
+    pub a: Scalar,
+    pub b: Scalar,
+}
+
+impl Setup {
+    // 在群阶的最大素因子 q 对应的子群里随机取 2n + 1 个生成元，n 必须是 2 的幂
+    pub fn new<R: RngCore + ?Sized>(curve: &Curve, n: usize, rng: &mut R) -> Result<Self, String> {
+        if !n.is_power_of_two() {
+            return Err(format!("vector length {n} is not a power of two"));
+        }
+        let (q, cofactor) = subgroup::prime_order_subgroup(curve)?;
+        let points = curve.nonsingular_points();
+        let mut generator = || loop {
+            let index = crate::rng::uniform_below(rng, points.len() as u64) as usize;
+            let coords = curve.mul_coords(points[index].coords(), cofactor);
+            if coords.is_some() {
+                return coords;
+            }
+        };
+        let g = (0..n).map(|_| generator()).collect();
+        let h = (0..n).map(|_| generator()).collect();
+        let u = generator();
+        Ok(Setup {
+            curve: *curve,
+            q,
+            g,
+            h,
+            u,
+        })
+    }
+
+    // <a, G> + <b, H> + <a, b> U
+    pub fn commit(&self, a: &[Scalar], b: &[Scalar]) -> Coords {
+        let mut terms = pair(&self.g, a);
+        terms.extend(pair(&self.h, b));
+        terms.push((self.u, inner(a, b).value()));
+        self.curve.msm(&terms)
+    }
+}
+
+pub fn inner(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    let q = a.first().map_or(1, Scalar::modulus);
+    a.iter()
+        .zip(b)
+        .fold(Scalar::zero(q), |sum, (&x, &y)| sum + x * y)
+}
+
+fn pair(points: &[Coords], scalars: &[Scalar]) -> Vec<(Coords, u64)> {
+    points
+        .iter()
+        .zip(scalars)
+        .map(|(&point, scalar)| (point, scalar.value()))
+        .collect()
+}
+
+// lo * x + hi * y，逐项
+fn fold_scalars(v: &[Scalar], x: Scalar, y: Scalar) -> Vec<Scalar> {
+    let (lo, hi) = v.split_at(v.len() / 2);
+    lo.iter().zip(hi).map(|(&l, &h)| l * x + h * y).collect()
+}
+
+fn fold_points(curve: &Curve, v: &[Coords], x: Scalar, y: Scalar) -> Vec<Coords> {
+    let (lo, hi) = v.split_at(v.len() / 2);
+    lo.iter()
+        .zip(hi)
+        .map(|(&l, &h)| curve.msm(&[(l, x.value()), (h, y.value())]))
+        .collect()
+}
+
+// 界面上的演示：随机的参数和向量、诚实的证明，以及验证结果
+pub struct Demo {
+    pub setup: Setup,
+    pub commitment: Coords,
+    pub proof: Proof,
+    pub folds: Vec<Fold>,
+    pub verified: bool,
+}
+
+impl Demo {
+    pub fn run<R: RngCore + ?Sized>(curve: &Curve, n: usize, rng: &mut R) -> Result<Self, String> {
+        let setup = Setup::new(curve, n, rng)?;
+        let a = (0..n)
+            .map(|_| Scalar::random(rng, setup.q))
+            .collect::<Vec<_>>();
+        let b = (0..n)
+            .map(|_| Scalar::random(rng, setup.q))
+            .collect::<Vec<_>>();
+        let commitment = setup.commit(&a, &b);
+        let (proof, folds) = prove(&setup, a, b, rng);
+        let verified = verify(&setup, commitment, &proof);
+        Ok(Demo {
+            setup,
+            commitment,
+            proof,
+            folds,
+            verified,
+        })
+    }
+}
+
+// 返回证明和每一轮折叠之前的状态（给界面画图用）
+pub fn prove<R: RngCore + ?Sized>(
+    setup: &Setup,
+    a: Vec<Scalar>,
+    b: Vec<Scalar>,
+    verifier: &mut R,
+) -> (Proof, Vec<Fold>) {
+    assert_eq!(a.len(), setup.g.len(), "a has the wrong length");
+    assert_eq!(b.len(), setup.h.len(), "b has the wrong length");
+    let _span = tracing::debug_span!("ipa_prove", n = a.len(), q = setup.q).entered();
+    let curve = &setup.curve;
+    let mut fold = Fold {
+        commitment: setup.commit(&a, &b),
+        a,
+        b,
+        g: setup.g.clone(),
+        h: setup.h.clone(),
+    };
+    let mut folds = Vec::new();
+    let mut rounds = Vec::new();
+    while fold.a.len() > 1 {
+        let n = fold.a.len() / 2;
+        let (a_lo, a_hi) = fold.a.split_at(n);
+        let (b_lo, b_hi) = fold.b.split_at(n);
+        let (g_lo, g_hi) = fold.g.split_at(n);
+        let (h_lo, h_hi) = fold.h.split_at(n);
+        // L = <a_lo, G_hi> + <b_hi, H_lo> + <a_lo, b_hi> U，R 对称
+        let mut terms = pair(g_hi, a_lo);
+        terms.extend(pair(h_lo, b_hi));
+        terms.push((setup.u, inner(a_lo, b_hi).value()));
+        let l = curve.msm(&terms);
+        let mut terms = pair(g_lo, a_hi);
+        terms.extend(pair(h_hi, b_lo));
+        terms.push((setup.u, inner(a_hi, b_lo).value()));
+        let r = curve.msm(&terms);
+
+        let x = Scalar::random(verifier, setup.q);
+        let x_inv = x.invert().expect("q is prime");
+        // P' = x² L + P + x⁻² R
+        let commitment = curve.msm(&[
+            (l, (x * x).value()),
+            (fold.commitment, 1),
+            (r, (x_inv * x_inv).value()),
+        ]);
+        let next = Fold {
+            a: fold_scalars(&fold.a, x, x_inv),
+            b: fold_scalars(&fold.b, x_inv, x),
+            g: fold_points(curve, &fold.g, x_inv, x),
+            h: fold_points(curve, &fold.h, x, x_inv),
+            commitment,
+        };
+        tracing::debug!(round = rounds.len(), n, x = x.value(), "folded");
+        rounds.push(Round { l, r, x });
+        folds.push(std::mem::replace(&mut fold, next));
+    }
+    let proof = Proof {
+        rounds,
+        a: fold.a[0],
+        b: fold.b[0],
+    };
+    folds.push(fold);
+    (proof, folds)
+}
+
+// 第 i 个生成元折叠到最后时的系数：第 j 轮 i 落在后一半时乘 x_j，前一半时乘 x_j⁻¹
+pub fn generator_weights(q: u64, rounds: &[Round]) -> Vec<Scalar> {
+    let k = rounds.len();
+    (0..1usize << k)
+        .map(|i| {
+            rounds
+                .iter()
+                .enumerate()
+                .fold(Scalar::one(q), |s, (j, round)| {
+                    if (i >> (k - 1 - j)) & 1 == 1 {
+                        s * round.x
+                    } else {
+                        s * round.x.invert().expect("challenges are non-zero")
+                    }
+                })
+        })
+        .collect()
+}
+
+// 验证者不逐轮折叠生成元，而是算出每个生成元的最终系数，最后做一次多标量乘法：
+// a s_i G_i + b s_i⁻¹ H_i + ab U == P + Σ (x_j² L_j + x_j⁻² R_j)
+pub fn verify(setup: &Setup, commitment: Coords, proof: &Proof) -> bool {
+    let n = setup.g.len();
+    if n != 1 << proof.rounds.len() || proof.rounds.iter().any(|round| round.x.is_zero()) {
+        return false;
+    }
+    let weights = generator_weights(setup.q, &proof.rounds);
+    let mut lhs = Vec::with_capacity(2 * n + 1);
+    for (i, s) in weights.iter().enumerate() {
+        let s_inv = s
+            .invert()
+            .expect("weights are products of non-zero challenges");
+        lhs.push((setup.g[i], (proof.a * *s).value()));
+        lhs.push((setup.h[i], (proof.b * s_inv).value()));
+    }
+    lhs.push((setup.u, (proof.a * proof.b).value()));
+    let mut rhs = vec![(commitment, 1)];
+    for round in &proof.rounds {
+        let x_inv = round.x.invert().expect("challenges are non-zero");
+        rhs.push((round.l, (round.x * round.x).value()));
+        rhs.push((round.r, (x_inv * x_inv).value()));
+    }
+    setup.curve.msm(&lhs) == setup.curve.msm(&rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    fn vectors(setup: &Setup, rng: &mut rng::DemoRng) -> (Vec<Scalar>, Vec<Scalar>) {
+        let n = setup.g.len();
+        let a = (0..n).map(|_| Scalar::random(rng, setup.q)).collect();
+        let b = (0..n).map(|_| Scalar::random(rng, setup.q)).collect();
+        (a, b)
+    }
+
+    #[test]
+    fn honest_proofs_verify() {
+        let curve = Curve::new(1, 1, 599);
+        let mut rng = rng::seeded(7);
+        for n in [1, 2, 4, 8, 16] {
+            let setup = Setup::new(&curve, n, &mut rng).unwrap();
+            assert_eq!(setup.q, 199);
+            let (a, b) = vectors(&setup, &mut rng);
+            let commitment = setup.commit(&a, &b);
+            let (proof, folds) = prove(&setup, a, b, &mut rng);
+            assert_eq!(proof.rounds.len(), n.trailing_zeros() as usize);
+            assert_eq!(folds.len(), proof.rounds.len() + 1);
+            assert_eq!(folds[0].commitment, commitment);
+            assert!(verify(&setup, commitment, &proof), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn every_fold_keeps_the_commitment_consistent() {
+        let curve = Curve::new(1, 1, 599);
+        let mut rng = rng::seeded(3);
+        let setup = Setup::new(&curve, 8, &mut rng).unwrap();
+        let (a, b) = vectors(&setup, &mut rng);
+        let (_, folds) = prove(&setup, a, b, &mut rng);
+        for fold in &folds {
+            let mut terms = pair(&fold.g, &fold.a);
+            terms.extend(pair(&fold.h, &fold.b));
+            terms.push((setup.u, inner(&fold.a, &fold.b).value()));
+            assert_eq!(curve.msm(&terms), fold.commitment);
+        }
+    }
+
+    #[test]
+    fn verifier_weights_fold_the_generators() {
+        let curve = Curve::new(1, 1, 599);
+        let mut rng = rng::seeded(11);
+        let setup = Setup::new(&curve, 8, &mut rng).unwrap();
+        let (a, b) = vectors(&setup, &mut rng);
+        let (proof, folds) = prove(&setup, a, b, &mut rng);
+        let weights = generator_weights(setup.q, &proof.rounds);
+        let g = curve.msm(&pair(&setup.g, &weights));
+        assert_eq!(folds.last().unwrap().g, vec![g]);
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let curve = Curve::new(1, 1, 599);
+        let mut rng = rng::seeded(5);
+        let setup = Setup::new(&curve, 4, &mut rng).unwrap();
+        let (a, b) = vectors(&setup, &mut rng);
+        let commitment = setup.commit(&a, &b);
+        let (proof, _) = prove(&setup, a, b, &mut rng);
+        let one = Scalar::one(setup.q);
+
+        let mut wrong_a = proof.clone();
+        wrong_a.a += one;
+        assert!(!verify(&setup, commitment, &wrong_a));
+        let mut wrong_l = proof.clone();
+        wrong_l.rounds[0].l = curve.add_coords(wrong_l.rounds[0].l, setup.u);
+        assert!(!verify(&setup, commitment, &wrong_l));
+        let mut missing_round = proof.clone();
+        missing_round.rounds.pop();
+        assert!(!verify(&setup, commitment, &missing_round));
+        assert!(!verify(
+            &setup,
+            curve.add_coords(commitment, setup.u),
+            &proof
+        ));
+    }
+
+    #[test]
+    fn setup_needs_a_usable_group() {
+        let mut rng = rng::seeded(1);
+        let mut err = |curve: Curve, n| Setup::new(&curve, n, &mut rng).err();
+        assert_eq!(
+            err(Curve::new(1, 1, 599), 3).as_deref(),
+            Some("vector length 3 is not a power of two")
+        );
+        assert_eq!(
+            err(Curve::new(0, 0, 97), 2).as_deref(),
+            Some("curve is singular (4a^3 + 27b^2 = 0 mod p)")
+        );
+        assert_eq!(
+            err(Curve::new(1, 1, 91), 2).as_deref(),
+            Some("modulus 91 is not prime")
+        );
+    }
+}
@@ -0,0 +1,379 @@
+// Jacobian 坐标 (X : Y : Z)，对应仿射点 (X/Z², Y/Z³)，Z = 0 是无穷远点。
+// 加法和倍点都不用求逆，一整串倍点-加法只在转回仿射坐标时求一次逆。
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::field;
+
+// 标量乘法的算法。倍点-加法只在比特为 1 时做加法，运算序列泄露 k；
+// Montgomery ladder 固定走 64 轮，每轮一次加法一次倍点，序列和 k 无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MulAlgorithm {
+    #[default]
+    DoubleAndAdd,
+    MontgomeryLadder,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct JacobianPoint {
+    x: i64,
+    y: i64,
+    z: i64,
+}
+
+impl JacobianPoint {
+    pub fn identity() -> Self {
+        JacobianPoint { x: 1, y: 1, z: 0 }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z == 0
+    }
+
+    pub fn from_coords(curve: &Curve, coords: Coords) -> Self {
+        match coords {
+            Some((x, y)) => JacobianPoint {
+                x: x.rem_euclid(curve.p),
+                y: y.rem_euclid(curve.p),
+                z: 1,
+            },
+            None => JacobianPoint::identity(),
+        }
+    }
+
+    // 唯一的一次求逆
+    pub fn to_coords(self, curve: &Curve) -> Coords {
+        self.try_to_coords(curve)
+            .expect("Z is invertible modulo a prime p")
+    }
+
+    // 模数是合数时 Z 可能和它有公因子
+    pub fn try_to_coords(self, curve: &Curve) -> Result<Coords, EcError> {
+        if self.is_identity() {
+            return Ok(None);
+        }
+        let p = curve.p;
+        let z_inv = field::try_mod_inverse(self.z, p)?;
+        let z_inv2 = field::mul(z_inv, z_inv, p);
+        let x = field::mul(self.x, z_inv2, p);
+        let y = field::mul(self.y, field::mul(z_inv2, z_inv, p), p);
+        Ok(Some((x, y)))
+    }
+
+    // 一组点一起转回仿射坐标，所有 Z 一起求逆（见 field::batch_inverse）
+    pub fn batch_to_coords(points: &[JacobianPoint], curve: &Curve) -> Vec<Coords> {
+        let p = curve.p;
+        let zs: Vec<i64> = points
+            .iter()
+            .filter(|point| !point.is_identity())
+            .map(|point| point.z)
+            .collect();
+        let mut inverses = field::batch_inverse(&zs, p).into_iter();
+        points
+            .iter()
+            .map(|point| {
+                if point.is_identity() {
+                    return None;
+                }
+                let z_inv = inverses.next().expect("one inverse per affine point");
+                let z_inv2 = field::mul(z_inv, z_inv, p);
+                Some((
+                    field::mul(point.x, z_inv2, p),
+                    field::mul(point.y, field::mul(z_inv2, z_inv, p), p),
+                ))
+            })
+            .collect()
+    }
+
+    // dbl-2007-bl：M = 3X² + aZ⁴，S = 4XY²，X3 = M² − 2S，Y3 = M(S − X3) − 8Y⁴，Z3 = 2YZ。
+    // y = 0 的 2 阶点得到 Z3 = 0，正好是无穷远点
+    pub fn double(self, curve: &Curve) -> Self {
+        if self.is_identity() {
+            return self;
+        }
+        let p = curve.p;
+        let mul = |u, v| field::mul(u, v, p);
+        let add = |u, v| field::add(u, v, p);
+        let sub = |u, v| field::sub(u, v, p);
+        let (x, y, z) = (self.x, self.y, self.z);
+        let yy = mul(y, y);
+        let zz = mul(z, z);
+        let s = mul(4, mul(x, yy));
+        let m = add(mul(3, mul(x, x)), mul(curve.a, mul(zz, zz)));
+        let x3 = sub(mul(m, m), add(s, s));
+        let y3 = sub(mul(m, sub(s, x3)), mul(8, mul(yy, yy)));
+        let z3 = mul(add(y, y), z);
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    // add-2007-bl 的通用加法：U1 = X1·Z2²，S1 = Y1·Z2³，H = U2 − U1，R = S2 − S1。
+    // H = 0 时两点横坐标相同，要么相等（改做倍点），要么互为相反数
+    pub fn add(self, other: Self, curve: &Curve) -> Self {
+        if self.is_identity() {
+            return other;
+        }
+        if other.is_identity() {
+            return self;
+        }
+        let p = curve.p;
+        let mul = |u, v| field::mul(u, v, p);
+        let sub = |u, v| field::sub(u, v, p);
+        let z1z1 = mul(self.z, self.z);
+        let z2z2 = mul(other.z, other.z);
+        let u1 = mul(self.x, z2z2);
+        let u2 = mul(other.x, z1z1);
+        let s1 = mul(self.y, mul(other.z, z2z2));
+        let s2 = mul(other.y, mul(self.z, z1z1));
+        let h = sub(u2, u1);
+        let r = sub(s2, s1);
+        if h == 0 {
+            return if r == 0 {
+                self.double(curve)
+            } else {
+                JacobianPoint::identity()
+            };
+        }
+        let hh = mul(h, h);
+        let hhh = mul(hh, h);
+        let v = mul(u1, hh);
+        let x3 = sub(sub(sub(mul(r, r), hhh), v), v);
+        let y3 = sub(mul(r, sub(v, x3)), mul(s1, hhh));
+        let z3 = mul(mul(self.z, other.z), h);
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    // 倍点-加法，只在最后转回仿射坐标时求逆
+    pub fn mul(self, k: u64, curve: &Curve) -> Self {
+        self.traced_mul(k, curve, MulAlgorithm::DoubleAndAdd).0
+    }
+
+    pub fn ladder(self, k: u64, curve: &Curve) -> Self {
+        self.traced_mul(k, curve, MulAlgorithm::MontgomeryLadder).0
+    }
+
+    // 宽度 4 的 wNAF：先算好 P、3P、5P、7P，k 写成非零位之间至少隔 3 个 0 的带符号数字，
+    // 减法就是加上相反数。加法次数大约是 64/5，倍点-加法平均要 32 次
+    pub fn wnaf(self, k: u64, curve: &Curve) -> Self {
+        let twice = self.double(curve);
+        let mut odd = [self; 1 << (WNAF_WIDTH - 2)];
+        for i in 1..odd.len() {
+            odd[i] = odd[i - 1].add(twice, curve);
+        }
+        let mut result = JacobianPoint::identity();
+        for &digit in wnaf_digits(k, WNAF_WIDTH).iter().rev() {
+            result = result.double(curve);
+            let addend = odd[digit.unsigned_abs() as usize / 2];
+            if digit > 0 {
+                result = result.add(addend, curve);
+            } else if digit < 0 {
+                result = result.add(addend.neg(curve), curve);
+            }
+        }
+        result
+    }
+
+    fn neg(self, curve: &Curve) -> Self {
+        JacobianPoint {
+            y: (-self.y).rem_euclid(curve.p),
+            ..self
+        }
+    }
+
+    // 同时记下运算序列，'A' 是加法，'D' 是倍点
+    pub fn traced_mul(self, k: u64, curve: &Curve, algorithm: MulAlgorithm) -> (Self, String) {
+        let mut ops = String::new();
+        let result = match algorithm {
+            MulAlgorithm::DoubleAndAdd => {
+                let mut result = JacobianPoint::identity();
+                let mut addend = self;
+                let mut k = k;
+                while k > 0 {
+                    if k & 1 == 1 {
+                        result = result.add(addend, curve);
+                        ops.push('A');
+                    }
+                    addend = addend.double(curve);
+                    ops.push('D');
+                    k >>= 1;
+                }
+                result
+            }
+            // 始终保持 R1 − R0 = P，比特决定加法的结果写进哪一个、倍点哪一个
+            MulAlgorithm::MontgomeryLadder => {
+                let (mut r0, mut r1) = (JacobianPoint::identity(), self);
+                for i in (0..u64::BITS).rev() {
+                    if (k >> i) & 1 == 0 {
+                        r1 = r0.add(r1, curve);
+                        r0 = r0.double(curve);
+                    } else {
+                        r0 = r0.add(r1, curve);
+                        r1 = r1.double(curve);
+                    }
+                    ops.push_str("AD");
+                }
+                r0
+            }
+        };
+        (result, ops)
+    }
+}
+
+const WNAF_WIDTH: u32 = 4;
+
+// k 的宽度为 width 的 NAF，低位在前：每个非零位是 (−2^(width−1), 2^(width−1)) 里的奇数
+fn wnaf_digits(k: u64, width: u32) -> Vec<i8> {
+    let modulus = 1i128 << width;
+    let mut k = k as i128;
+    let mut digits = Vec::new();
+    while k > 0 {
+        let mut digit = 0;
+        if k & 1 == 1 {
+            digit = k.rem_euclid(modulus);
+            if digit >= modulus / 2 {
+                digit -= modulus;
+            }
+            k -= digit;
+        }
+        digits.push(digit as i8);
+        k >>= 1;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agrees_with_affine_arithmetic() {
+        for (a, b, p) in [(2, 3, 97), (1, 1, 599), (0, 7, 103), (-1, 0, 101)] {
+            let curve = Curve::new(a, b, p);
+            let mut points: Vec<Coords> = curve.points().iter().map(|p| p.coords()).collect();
+            points.push(None);
+            for &lhs in points.iter().step_by(5) {
+                let jacobian = JacobianPoint::from_coords(&curve, lhs);
+                assert_eq!(
+                    jacobian.double(&curve).to_coords(&curve),
+                    curve.add_coords(lhs, lhs)
+                );
+                for &rhs in points.iter().step_by(7) {
+                    let sum = jacobian.add(JacobianPoint::from_coords(&curve, rhs), &curve);
+                    assert_eq!(sum.to_coords(&curve), curve.add_coords(lhs, rhs));
+                }
+                for k in [0, 1, 2, 3, 17, 1000, u64::MAX] {
+                    assert_eq!(
+                        jacobian.mul(k, &curve).to_coords(&curve),
+                        curve.try_mul_coords(lhs, k).unwrap(),
+                        "{curve:?}: {k}·{lhs:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ladder_matches_double_and_add_with_a_fixed_pattern() {
+        let curve = Curve::new(1, 1, 599);
+        let p = JacobianPoint::from_coords(&curve, Some((1, 188)));
+        let mut patterns = Vec::new();
+        for k in [0, 1, 2, 5, 596, 597, 1 << 40, u64::MAX] {
+            let (ladder, ops) = p.traced_mul(k, &curve, MulAlgorithm::MontgomeryLadder);
+            assert_eq!(ladder.to_coords(&curve), p.mul(k, &curve).to_coords(&curve));
+            patterns.push(ops);
+        }
+        assert!(patterns.iter().all(|ops| *ops == "AD".repeat(64)));
+        // 倍点-加法的序列里 'A' 的个数就是 k 的汉明重量
+        let (_, ops) = p.traced_mul(0b1011, &curve, MulAlgorithm::DoubleAndAdd);
+        assert_eq!(ops, "ADADDAD");
+    }
+
+    #[test]
+    fn wnaf_matches_double_and_add() {
+        for k in [0, 1, 7, 9, 0b1011, 596, 597, 1 << 40, u64::MAX] {
+            let digits = wnaf_digits(k, WNAF_WIDTH);
+            let value: i128 = digits
+                .iter()
+                .rev()
+                .fold(0, |acc, &digit| 2 * acc + digit as i128);
+            assert_eq!(value, k as i128);
+            // 两个非零位之间至少隔 width − 1 个 0
+            let nonzero: Vec<usize> = (0..digits.len()).filter(|&i| digits[i] != 0).collect();
+            assert!(
+                nonzero
+                    .windows(2)
+                    .all(|w| w[1] - w[0] >= WNAF_WIDTH as usize)
+            );
+            assert!(
+                digits
+                    .iter()
+                    .all(|&d| d == 0 || (d % 2 != 0 && d.abs() < 8))
+            );
+        }
+        for (a, b, p) in [(1, 1, 599), (-1, 0, 101)] {
+            let curve = Curve::new(a, b, p);
+            for point in curve.points().iter().step_by(11) {
+                let jacobian = JacobianPoint::from_coords(&curve, point.coords());
+                for k in [0, 1, 2, 3, 7, 8, 597, 1000, u64::MAX] {
+                    assert_eq!(
+                        jacobian.wnaf(k, &curve).to_coords(&curve),
+                        jacobian.mul(k, &curve).to_coords(&curve),
+                        "{curve:?}: {k}·{point}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn addition_handles_equal_and_opposite_points() {
+        let curve = Curve::new(2, 3, 97);
+        let p = JacobianPoint::from_coords(&curve, Some((3, 6)));
+        // 同一个点换一个 Z = 5 的表示：(X, Y, Z) = (3·5², 6·5³, 5)
+        let scaled = JacobianPoint {
+            x: 75,
+            y: 6 * 125 % 97,
+            z: 5,
+        };
+        assert_eq!(p.add(scaled, &curve).to_coords(&curve), Some((80, 10)));
+        let negated = JacobianPoint::from_coords(&curve, Some((3, 91)));
+        assert!(p.add(negated, &curve).is_identity());
+        // y = 0 的 2 阶点倍点后是无穷远点
+        let torsion = JacobianPoint::from_coords(&Curve::new(-1, 0, 101), Some((0, 0)));
+        assert!(torsion.double(&Curve::new(-1, 0, 101)).is_identity());
+    }
+
+    #[test]
+    fn batch_conversion_matches_one_at_a_time() {
+        let curve = Curve::new(1, 1, 599);
+        let g = JacobianPoint::from_coords(&curve, Some((1, 188)));
+        let mut points = vec![JacobianPoint::identity()];
+        for _ in 0..40 {
+            let next = points.last().unwrap().add(g, &curve);
+            points.push(next);
+        }
+        points.push(g.mul(curve.group_order(), &curve));
+        let batch = JacobianPoint::batch_to_coords(&points, &curve);
+        let single: Vec<Coords> = points.iter().map(|p| p.to_coords(&curve)).collect();
+        assert_eq!(batch, single);
+        assert_eq!((batch[0], batch[41]), (None, None));
+    }
+
+    #[test]
+    fn composite_moduli_report_a_non_invertible_z() {
+        // 91 = 7 · 13，(0, 1) 在 mod 7 的曲线上是 5 阶点，5·P 的 Z 是 7 的倍数
+        let curve = Curve::new(1, 1, 91);
+        let p = JacobianPoint::from_coords(&curve, Some((0, 1)));
+        assert_eq!(p.mul(4, &curve).try_to_coords(&curve), Ok(Some((49, 6))));
+        assert!(matches!(
+            p.mul(5, &curve).try_to_coords(&curve),
+            Err(EcError::NonInvertible { gcd: 7, .. })
+        ));
+    }
+}
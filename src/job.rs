@@ -0,0 +1,180 @@
+// 界面上的耗时计算（曲线统计、离散对数）放到工作线程里，结果通过 channel 发回。
+// 工作线程通过 Progress 报告进度、检查取消标记；丢弃 Job 时自动取消。
+use crate::curve::Curve;
+use crate::field;
+use crate::point::Point;
+use eframe::egui;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+
+#[derive(Debug, Default)]
+struct Shared {
+    done: AtomicU64,
+    total: AtomicU64,
+    cancelled: AtomicBool,
+}
+
+// 工作线程和界面共享的进度与取消标记
+#[derive(Debug, Clone, Default)]
+pub struct Progress(Arc<Shared>);
+
+impl Progress {
+    pub fn set(&self, done: u64, total: u64) {
+        self.0.total.store(total, Ordering::Relaxed);
+        self.0.done.store(done, Ordering::Relaxed);
+    }
+
+    // 还没报告过总量时为 0
+    pub fn fraction(&self) -> f32 {
+        let total = self.0.total.load(Ordering::Relaxed);
+        self.0.done.load(Ordering::Relaxed) as f32 / total.max(1) as f32
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Job<T> {
+    rx: mpsc::Receiver<T>,
+    progress: Progress,
+    finished: bool,
+}
+
+impl<T: Send + 'static> Job<T> {
+    // work 返回 None 表示被取消了；结束后请求重绘，界面在下一帧取结果
+    pub fn spawn<F>(ctx: &egui::Context, work: F) -> Self
+    where
+        F: FnOnce(&Progress) -> Option<T> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let progress = Progress::default();
+        let shared = progress.clone();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            if let Some(result) = work(&shared) {
+                let _ = tx.send(result);
+            }
+            ctx.request_repaint();
+        });
+        Job {
+            rx,
+            progress,
+            finished: false,
+        }
+    }
+}
+
+impl<T> Job<T> {
+    // 结果只取一次；取到结果或者工作线程结束后 is_finished 为真
+    pub fn poll(&mut self) -> Option<T> {
+        if self.finished {
+            return None;
+        }
+        match self.rx.try_recv() {
+            Ok(result) => {
+                self.finished = true;
+                Some(result)
+            }
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.progress.fraction()
+    }
+
+    pub fn cancel(&self) {
+        self.progress.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.progress.is_cancelled()
+    }
+}
+
+impl<T> Drop for Job<T> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+// 和 Curve::nonsingular_points 相同，每个 x 报告一次进度，取消时返回 None
+pub fn nonsingular_points(curve: &Curve, progress: &Progress) -> Option<Vec<Point>> {
+    let p = curve.p;
+    let singular = curve.singularity().map(|s| (s.x(), 0));
+    let roots = field::SqrtTable::new(p);
+    let mut points = Vec::new();
+    for x in 0..p {
+        if progress.is_cancelled() {
+            tracing::debug!(x, "enumeration cancelled");
+            return None;
+        }
+        progress.set(x as u64, p as u64);
+        points.extend(
+            curve
+                .points_with(&roots, x)
+                .into_iter()
+                .filter(|point| point.coords() != singular),
+        );
+    }
+    progress.set(p as u64, p as u64);
+    Some(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait<T>(job: &mut Job<T>) -> Option<T> {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        loop {
+            if let Some(result) = job.poll() {
+                return Some(result);
+            }
+            if job.is_finished() {
+                return None;
+            }
+            assert!(Instant::now() < deadline, "job did not finish");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn finished_jobs_hand_back_their_result() {
+        let curve = Curve::new(2, 3, 97);
+        let mut job = Job::spawn(&egui::Context::default(), move |progress| {
+            nonsingular_points(&curve, progress)
+        });
+        assert_eq!(wait(&mut job), Some(curve.nonsingular_points()));
+        assert_eq!(job.progress(), 1.0);
+        assert!(job.is_finished() && !job.is_cancelled());
+        assert_eq!(job.poll(), None);
+    }
+
+    #[test]
+    fn cancelled_jobs_finish_without_a_result() {
+        // 完整枚举需要很久，取消后应该很快结束
+        let curve = Curve::new(1, 1, 1_000_003);
+        let mut job = Job::spawn(&egui::Context::default(), move |progress| {
+            nonsingular_points(&curve, progress)
+        });
+        job.cancel();
+        assert_eq!(wait(&mut job), None);
+        assert!(job.is_cancelled() && job.progress() < 1.0);
+    }
+}
@@ -0,0 +1,201 @@
+// 已知答案向量：testdata/small_curves.txt 里的数据由 scripts/gen_known_answers.py
+// 独立计算得到，用本仓库的每一种坐标表示/实现重放一遍。测试和诊断页面共用。
+use crate::affine::AffinePoint;
+use crate::bigcurve::{BigCurve, BigPoint, FieldBackend, PlainField, PrimeField};
+use crate::curve::{Coords, Curve};
+use crate::jacobian::MulAlgorithm;
+use crate::point::Point;
+
+const VECTORS: &str = include_str!("../testdata/small_curves.txt");
+
+pub struct Vector {
+    pub line: usize,
+    pub curve: Curve,
+    pub group_order: u64,
+    pub p: Coords,
+    pub q: Coords,
+    pub sum: Coords,
+    pub k: u64,
+    pub kp: Coords,
+    pub order: u64,
+}
+
+fn parse_coords(field: &str) -> Coords {
+    if field == "O" {
+        return None;
+    }
+    let (x, y) = field.split_once(',').expect("coordinates are x,y or O");
+    Some((x.parse().unwrap(), y.parse().unwrap()))
+}
+
+// 每行格式：a b p #E | P | Q | P+Q | k | kP | ord(P)
+pub fn vectors() -> Vec<Vector> {
+    VECTORS
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|(index, line)| {
+            let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+            assert_eq!(fields.len(), 7, "line {}: {line}", index + 1);
+            let header: Vec<i64> = fields[0]
+                .split_whitespace()
+                .map(|n| n.parse().unwrap())
+                .collect();
+            Vector {
+                line: index + 1,
+                curve: Curve::new(header[0], header[1], header[2]),
+                group_order: header[3] as u64,
+                p: parse_coords(fields[1]),
+                q: parse_coords(fields[2]),
+                sum: parse_coords(fields[3]),
+                k: fields[4].parse().unwrap(),
+                kp: parse_coords(fields[5]),
+                order: fields[6].parse().unwrap(),
+            }
+        })
+        .collect()
+}
+
+// 256 位的实现在给定的域后端上重放 P + Q 和 kP。BigCurve::mul 固定走 256 位，
+// PlainField 又是逐位约化，debug 构建下一次就要上百毫秒，这里只按 k 的位数做倍点-加法；
+// 和答案比较时用 equal 交叉相乘，不求逆，只有不一致时才转回仿射坐标
+fn replay_big<F: FieldBackend>(v: &Vector, backend: &str) -> Result<(), String> {
+    let limbs = |n: u64| [n, 0, 0, 0];
+    let curve = v.curve;
+    let big = BigCurve::<F>::with_backend(
+        &limbs(curve.a as u64),
+        &limbs(curve.b as u64),
+        &limbs(curve.p as u64),
+    )
+    .map_err(|e| format!("line {}: {backend}: {e}", v.line))?;
+    let lift = |coords: Coords| match coords {
+        Some((x, y)) => big
+            .point(&limbs(x as u64), &limbs(y as u64))
+            .map_err(|e| format!("line {}: {backend}: {e}", v.line)),
+        None => Ok(big.identity()),
+    };
+    let check = |what: &str, actual: &BigPoint, expected: Coords| {
+        if big.equal(actual, &lift(expected)?) {
+            return Ok(());
+        }
+        let actual = big.affine(actual).map(|(x, y)| (x[0] as i64, y[0] as i64));
+        Err(format!(
+            "line {}: {backend} {what} = {actual:?}, expected {expected:?}",
+            v.line
+        ))
+    };
+    let (p, q) = (lift(v.p)?, lift(v.q)?);
+    check("P + Q", &big.add(&p, &q), v.sum)?;
+    let kp = (0..u64::BITS - v.k.leading_zeros())
+        .rev()
+        .fold(big.identity(), |acc, i| {
+            let acc = big.double(&acc);
+            if (v.k >> i) & 1 == 1 {
+                big.add(&acc, &p)
+            } else {
+                acc
+            }
+        });
+    check("kP", &kp, v.kp)
+}
+
+// 依次检查各个实现，返回第一个不一致的地方
+pub fn replay(v: &Vector) -> Result<(), String> {
+    let curve = v.curve;
+    let check = |backend: &str, what: &str, actual: Coords, expected: Coords| {
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!(
+                "line {}: {backend} {what} = {actual:?}, expected {expected:?}",
+                v.line
+            ))
+        }
+    };
+    check("Coords", "P + Q", curve.add_coords(v.p, v.q), v.sum)?;
+    check("Coords", "kP", curve.mul_coords(v.p, v.k), v.kp)?;
+    check(
+        "Jacobian ladder",
+        "kP",
+        curve.mul_coords_with(v.p, v.k, MulAlgorithm::MontgomeryLadder),
+        v.kp,
+    )?;
+    let (p, q) = (
+        Point::from_coords(&curve, v.p),
+        Point::from_coords(&curve, v.q),
+    );
+    check("Point", "P + Q", (&p + &q).coords(), v.sum)?;
+    check("Point", "kP", (&p * v.k).coords(), v.kp)?;
+    check("Point", "k·P", (v.k * &p).coords(), v.kp)?;
+    let (p, q) = (
+        AffinePoint::from_point(&curve, &p),
+        AffinePoint::from_point(&curve, &q),
+    );
+    check("AffinePoint", "P + Q", (p + q).coords(), v.sum)?;
+    check("AffinePoint", "kP", (p * v.k).coords(), v.kp)?;
+    let sums = curve.add_to_all(&[v.p, None], v.q);
+    check("batched", "P + Q", sums[0], v.sum)?;
+    check("batched", "O + Q", sums[1], v.q)?;
+    replay_big::<PrimeField>(v, "BigCurve<PrimeField>")?;
+    replay_big::<PlainField>(v, "BigCurve<PlainField>")?;
+    #[cfg(feature = "ct")]
+    check(
+        "ladder",
+        "kP",
+        crate::ct::ladder_mul(&curve, v.p, v.k).map_err(|e| e.to_string())?,
+        v.kp,
+    )?;
+
+    let group_order = curve.group_order();
+    if group_order != v.group_order {
+        return Err(format!(
+            "line {}: #E = {group_order}, expected {}",
+            v.line, v.group_order
+        ));
+    }
+    let order = curve.order_of(v.p, group_order);
+    if order != v.order {
+        return Err(format!(
+            "line {}: ord(P) = {order}, expected {}",
+            v.line, v.order
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_file_covers_dozens_of_curves() {
+        let vectors = vectors();
+        let mut curves: Vec<Curve> = vectors.iter().map(|v| v.curve).collect();
+        curves.dedup();
+        assert!(curves.len() >= 24, "only {} curves", curves.len());
+    }
+
+    #[test]
+    fn replay_reports_no_mismatch() {
+        // 每个向量都交给所有实现：仿射坐标、Point、AffinePoint、批量加法、
+        // Jacobian 的 ladder、两种域后端的 BigCurve，以及 #E 和 ord(P)
+        for v in vectors() {
+            assert_eq!(replay(&v), Ok(()));
+        }
+    }
+
+    #[test]
+    fn replay_reports_a_wrong_answer() {
+        let mut v = vectors().remove(0);
+        v.sum = v.sum.map(|(x, y)| (x, (y + 1) % v.curve.p));
+        assert!(replay(&v).unwrap_err().contains("P + Q"));
+        // 33·P ≠ P，答案换成 P 时 BigCurve 同样报出来
+        let mut v = vectors().remove(0);
+        v.kp = v.p;
+        assert!(
+            replay_big::<PlainField>(&v, "BigCurve<PlainField>")
+                .unwrap_err()
+                .contains("BigCurve<PlainField> kP")
+        );
+    }
+}
@@ -0,0 +1,305 @@
+// E(F_p) 的子群格。E ≅ Z_n1 × Z_n2 是各 Sylow 子群的直积，子群格也是各 Sylow 子群的子群格的直积，
+// 所以按素因子 q 分开算：G_q = (#E / q^e)·E，秩不超过 2，每个子群都是两个循环子群的和。
+// 格里 H ⊂ K 且 [K : H] 是素数时 K 覆盖 H，这些就是 Hasse 图的边。
+use crate::curve::{Coords, Curve};
+use crate::field;
+use crate::layout;
+use crate::point::Point;
+use std::collections::{HashMap, HashSet};
+
+// 节点再多就画不下了
+pub const MAX_NODES: usize = 80;
+// G_q 的加法表是 |G_q|² 大小
+const MAX_SYLOW: usize = 256;
+
+// 一个 Sylow 子群：元素、加法表和全部子群（元素下标，升序）
+struct Sylow {
+    elements: Vec<Coords>,
+    subgroups: Vec<Vec<usize>>,
+}
+
+impl Sylow {
+    fn new(curve: &Curve, points: &[Coords], cofactor: u64) -> Result<Self, String> {
+        let mut elements = vec![None];
+        let mut index: HashMap<Coords, usize> = HashMap::from([(None, 0)]);
+        for &point in points {
+            let multiple = curve.mul_coords(point, cofactor);
+            index.entry(multiple).or_insert_with(|| {
+                elements.push(multiple);
+                elements.len() - 1
+            });
+        }
+        if elements.len() > MAX_SYLOW {
+            return Err(format!(
+                "a Sylow subgroup has {} elements, too many to draw",
+                elements.len()
+            ));
+        }
+        let table: Vec<Vec<usize>> = elements
+            .iter()
+            .map(|&a| {
+                elements
+                    .iter()
+                    .map(|&b| index[&curve.add_coords(a, b)])
+                    .collect()
+            })
+            .collect();
+
+        // 循环子群 <g>，再两两相加
+        let mut cyclic: Vec<Vec<usize>> = Vec::new();
+        for (g, row) in table.iter().enumerate() {
+            let mut members = vec![0];
+            let mut acc = g;
+            while acc != 0 {
+                members.push(acc);
+                acc = row[acc];
+            }
+            members.sort_unstable();
+            if !cyclic.contains(&members) {
+                cyclic.push(members);
+            }
+        }
+        let mut seen: HashSet<Vec<usize>> = HashSet::new();
+        let mut subgroups = Vec::new();
+        for (i, a) in cyclic.iter().enumerate() {
+            for b in &cyclic[i..] {
+                let mut sum: Vec<usize> = a
+                    .iter()
+                    .flat_map(|&x| b.iter().map(|&y| table[x][y]).collect::<Vec<_>>())
+                    .collect();
+                sum.sort_unstable();
+                sum.dedup();
+                if seen.insert(sum.clone()) {
+                    subgroups.push(sum);
+                }
+            }
+        }
+        subgroups.sort_by_key(|members| (members.len(), members.clone()));
+        Ok(Sylow {
+            elements,
+            subgroups,
+        })
+    }
+
+    // 子群里元素的最大阶，就是它的指数
+    fn exponent(&self, subgroup: usize, q: u64, curve: &Curve) -> u64 {
+        self.subgroups[subgroup]
+            .iter()
+            .map(|&i| {
+                let mut order = 1;
+                let mut point = self.elements[i];
+                while point.is_some() {
+                    point = curve.mul_coords(point, q);
+                    order *= q;
+                }
+                order
+            })
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub order: u64,
+    // H ≅ Z_m1 × Z_m2
+    pub structure: (u64, u64),
+    // 在每个 Sylow 子群里选的子群
+    parts: Vec<usize>,
+}
+
+impl Node {
+    pub fn structure_label(&self) -> String {
+        match self.structure {
+            (1, 1) => "{O}".to_string(),
+            (m1, 1) => format!("Z_{m1}"),
+            (m1, m2) => format!("Z_{m1} × Z_{m2}"),
+        }
+    }
+}
+
+pub struct Lattice {
+    pub curve: Curve,
+    sylow: Vec<Sylow>,
+    pub nodes: Vec<Node>,
+    // (H, K)，K 覆盖 H
+    pub edges: Vec<(usize, usize)>,
+    // layout::layered 的结果，y = 0 是平凡子群
+    pub positions: Vec<(f32, f32)>,
+}
+
+impl Lattice {
+    pub fn new(curve: &Curve, points: &[Point]) -> Result<Self, String> {
+        let _span = tracing::debug_span!("subgroup_lattice", p = curve.p).entered();
+        if curve.is_singular() || !field::is_prime(curve.p) {
+            return Err("the subgroup lattice needs a non-singular curve over a field".to_string());
+        }
+        let coords: Vec<Coords> = points.iter().map(Point::coords).collect();
+        let census = curve.census_of(points);
+        let n = census.group_order;
+
+        // 每个素因子 q 的子群列表，以及每个子群的 (阶, Z_q^a × Z_q^b)
+        let mut sylow = Vec::new();
+        let mut shapes: Vec<Vec<(u64, u64, u64)>> = Vec::new();
+        for (q, e) in field::factorize(n) {
+            let part = Sylow::new(curve, &coords, n / q.pow(e))?;
+            shapes.push(
+                (0..part.subgroups.len())
+                    .map(|s| {
+                        let order = part.subgroups[s].len() as u64;
+                        let exponent = part.exponent(s, q, curve);
+                        (order, exponent, order / exponent)
+                    })
+                    .collect(),
+            );
+            sylow.push(part);
+        }
+        let count: usize = sylow.iter().map(|s| s.subgroups.len()).product();
+        if count > MAX_NODES {
+            return Err(format!(
+                "E ≅ Z_{} × Z_{} has {count} subgroups, too many to draw",
+                census.exponent,
+                n / census.exponent
+            ));
+        }
+
+        // 各分量的子群组合起来，按阶排序
+        let mut nodes = vec![Node {
+            order: 1,
+            structure: (1, 1),
+            parts: vec![],
+        }];
+        for shape in &shapes {
+            nodes = nodes
+                .iter()
+                .flat_map(|node| {
+                    shape.iter().enumerate().map(|(s, &(order, m1, m2))| {
+                        let mut parts = node.parts.clone();
+                        parts.push(s);
+                        Node {
+                            order: node.order * order,
+                            structure: (node.structure.0 * m1, node.structure.1 * m2),
+                            parts,
+                        }
+                    })
+                })
+                .collect();
+        }
+        nodes.sort_by(|a, b| (a.order, &a.parts).cmp(&(b.order, &b.parts)));
+
+        let contains = |big: &Node, small: &Node| {
+            sylow.iter().enumerate().all(|(q, part)| {
+                let outer = &part.subgroups[big.parts[q]];
+                part.subgroups[small.parts[q]]
+                    .iter()
+                    .all(|i| outer.binary_search(i).is_ok())
+            })
+        };
+        let mut edges = Vec::new();
+        for (h, small) in nodes.iter().enumerate() {
+            for (k, big) in nodes.iter().enumerate() {
+                if big.order % small.order == 0
+                    && field::is_prime((big.order / small.order) as i64)
+                    && contains(big, small)
+                {
+                    edges.push((h, k));
+                }
+            }
+        }
+        // 层数是阶的素因子个数（计重数）
+        let ranks: Vec<usize> = nodes
+            .iter()
+            .map(|node| {
+                field::factorize(node.order)
+                    .iter()
+                    .map(|&(_, e)| e as usize)
+                    .sum()
+            })
+            .collect();
+        let positions = layout::layered(&ranks, &edges);
+        tracing::debug!(subgroups = nodes.len(), covers = edges.len(), "lattice");
+        Ok(Lattice {
+            curve: *curve,
+            sylow,
+            nodes,
+            edges,
+            positions,
+        })
+    }
+
+    // 子群的全部元素：各分量的元素逐个相加
+    pub fn elements(&self, node: usize) -> Vec<Coords> {
+        let mut elements = vec![None];
+        for (part, &s) in self.sylow.iter().zip(&self.nodes[node].parts) {
+            elements = elements
+                .iter()
+                .flat_map(|&a| {
+                    part.subgroups[s]
+                        .iter()
+                        .map(move |&i| self.curve.add_coords(a, part.elements[i]))
+                })
+                .collect();
+        }
+        elements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subgroup;
+
+    fn lattice(a: i64, b: i64, p: i64) -> Lattice {
+        let curve = Curve::new(a, b, p);
+        Lattice::new(&curve, &curve.nonsingular_points()).unwrap()
+    }
+
+    // 循环群 Z_n 的子群和 n 的因子一一对应
+    #[test]
+    fn cyclic_groups_have_one_subgroup_per_divisor() {
+        let lattice = lattice(3, 5, 97);
+        let n = lattice.curve.group_order();
+        let divisors: Vec<u64> = (1..=n).filter(|&d| n.is_multiple_of(d)).collect();
+        let orders: Vec<u64> = lattice.nodes.iter().map(|node| node.order).collect();
+        assert_eq!(orders, divisors);
+        for (i, node) in lattice.nodes.iter().enumerate() {
+            let elements = lattice.elements(i);
+            assert_eq!(elements.len() as u64, node.order);
+            assert_eq!(node.structure, (node.order, 1));
+            // 循环子群：由阶最大的元素生成
+            let generator = elements
+                .iter()
+                .copied()
+                .find(|&g| subgroup::cyclic_subgroup(&lattice.curve, g).len() as u64 == node.order)
+                .unwrap();
+            let mut expected = subgroup::cyclic_subgroup(&lattice.curve, generator);
+            let mut elements = elements;
+            expected.sort();
+            elements.sort();
+            assert_eq!(elements, expected);
+        }
+        assert_eq!(lattice.positions[0].1, 0.0);
+        assert_eq!(lattice.positions.last().unwrap().1, 1.0);
+    }
+
+    // y² = x³ − x over F_11 是 Z_6 × Z_2：Z_2 × Z_2 有 5 个子群，乘上 Z_3 的 2 个
+    #[test]
+    fn klein_four_part_has_three_subgroups_of_order_two() {
+        let lattice = lattice(10, 0, 11);
+        assert_eq!(lattice.nodes.len(), 10);
+        let order_two = lattice.nodes.iter().filter(|n| n.order == 2).count();
+        assert_eq!(order_two, 3);
+        let top = lattice.nodes.len() - 1;
+        assert_eq!(lattice.nodes[top].structure_label(), "Z_6 × Z_2");
+        assert_eq!(lattice.nodes[0].structure_label(), "{O}");
+        // 每个 2 阶子群被 Z_2 × Z_2 和一个 Z_6 覆盖
+        for (i, node) in lattice.nodes.iter().enumerate() {
+            if node.order == 2 {
+                let covers = lattice.edges.iter().filter(|&&(h, _)| h == i).count();
+                assert_eq!(covers, 2);
+            }
+        }
+        let elements: HashSet<Coords> = lattice.elements(top).into_iter().collect();
+        assert_eq!(elements.len(), 12);
+    }
+}
@@ -0,0 +1,931 @@
+// 画布上的可视化图层。每一层从曲线状态计算自己需要的数据，再画到画布上，
+// 可以带一段侧边栏设置。新的实验性视图实现 VisualizationLayer 并注册即可，
+// 不需要改动应用本身。
+use crate::animation::{self, Frame};
+use crate::chord::Chord;
+use crate::curve::{Coords, Curve, Singularity};
+use crate::dlog::Rho;
+use crate::orbit::TrackedPoint;
+use crate::point::Point;
+use crate::subgroup::Cosets;
+use crate::viewport::Viewport;
+use eframe::egui;
+
+// 图层能看到的应用状态
+pub struct PlotState<'a> {
+    pub curve: &'a Curve,
+    pub points: &'a [Point],
+    pub tracked: &'a [TrackedPoint],
+    // 开启陪集着色时 H 的生成元和陪集划分，划分的下标与 points 一致
+    pub cosets: Option<(Coords, &'a Cosets)>,
+    // 开启向量场时的 G
+    pub field_base: Option<Coords>,
+    // 子群格里选中的子群
+    pub highlight: Option<&'a [Coords]>,
+    // 在画布上点选的 P、Q，最多两个
+    pub selection: &'a [(i64, i64)],
+    // 协议窗口要在曲线上标出的点
+    pub markers: &'a [Marker],
+    // k·P 动画当前的一帧
+    pub animation: Option<&'a Frame>,
+    // Pollard rho 的游走
+    pub walk: Option<&'a Rho>,
+}
+
+// 带文字的标记，例如 ECDH 里的 A = a·G
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub coords: (i64, i64),
+    pub label: String,
+    pub color: egui::Color32,
+}
+
+// 绘制区域，y 轴翻转，0 在底部；放大时只映射 viewport 里的部分
+pub struct Canvas {
+    pub painter: egui::Painter,
+    pub rect: egui::Rect,
+    pub viewport: Viewport,
+    step: f32,
+}
+
+impl Canvas {
+    pub fn new(painter: egui::Painter, rect: egui::Rect, p: i64) -> Self {
+        Canvas::with_viewport(painter, rect, Viewport::full(p))
+    }
+
+    pub fn with_viewport(painter: egui::Painter, rect: egui::Rect, viewport: Viewport) -> Self {
+        Canvas {
+            painter,
+            rect,
+            viewport,
+            step: rect.width() / viewport.span,
+        }
+    }
+
+    pub fn to_screen(&self, x: i64, y: i64) -> egui::Pos2 {
+        self.project(x as f32, y as f32)
+    }
+
+    // 不在格点上的位置，例如绕回的直线和边界的交点
+    pub fn project(&self, x: f32, y: f32) -> egui::Pos2 {
+        egui::pos2(
+            self.rect.min.x + (x - self.viewport.x) * self.step,
+            self.rect.max.y - (y - self.viewport.y) * self.step,
+        )
+    }
+
+    // 屏幕位置对应的域坐标，不取整
+    pub fn to_field(&self, pos: egui::Pos2) -> (f32, f32) {
+        (
+            self.viewport.x + (pos.x - self.rect.min.x) / self.step,
+            self.viewport.y + (self.rect.max.y - pos.y) / self.step,
+        )
+    }
+
+    // 每格的像素数
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+}
+
+pub trait VisualizationLayer {
+    fn name(&self) -> &'static str;
+
+    // 每帧绘制前调用；结果应按输入缓存，状态没变时不要重新计算。
+    // 需要上传纹理的图层可以通过画布拿到 egui 的 Context
+    fn compute(&mut self, _state: &PlotState, _canvas: &Canvas) {}
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas);
+
+    // 侧边栏里这一层的设置，只在图层开启时显示
+    fn ui(&mut self, _ui: &mut egui::Ui) {}
+}
+
+// 按注册顺序从下往上绘制
+pub struct LayerRegistry {
+    layers: Vec<(Box<dyn VisualizationLayer>, bool)>,
+}
+
+impl Default for LayerRegistry {
+    fn default() -> Self {
+        let mut registry = LayerRegistry { layers: Vec::new() };
+        registry.register(Box::new(GridLayer));
+        registry.register(Box::new(AxesLayer));
+        registry.register(Box::new(PointsLayer::default()));
+        registry.register(Box::new(FieldLayer::default()));
+        registry.register(Box::new(SubgroupLayer));
+        registry.register(Box::new(OrbitLayer));
+        registry.register(Box::new(GroupLawLayer::default()));
+        registry.register(Box::new(RhoLayer));
+        registry.register(Box::new(MarkersLayer));
+        registry.register(Box::new(AnimationLayer));
+        registry
+    }
+}
+
+impl LayerRegistry {
+    pub fn register(&mut self, layer: Box<dyn VisualizationLayer>) {
+        self.layers.push((layer, true));
+    }
+
+    pub fn paint(&mut self, state: &PlotState, canvas: &Canvas) {
+        for (layer, _) in self.layers.iter_mut().filter(|(_, enabled)| *enabled) {
+            layer.compute(state, canvas);
+            layer.paint(state, canvas);
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        for (layer, enabled) in &mut self.layers {
+            ui.checkbox(enabled, layer.name());
+            if *enabled {
+                ui.indent(layer.name(), |ui| layer.ui(ui));
+            }
+        }
+    }
+}
+
+pub struct GridLayer;
+
+impl VisualizationLayer for GridLayer {
+    fn name(&self) -> &'static str {
+        "Grid"
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let stroke = egui::Stroke::new(1.0, egui::Color32::LIGHT_GRAY);
+        let rect = canvas.rect;
+        let viewport = canvas.viewport;
+        let first = viewport.x.min(viewport.y).floor() as i64;
+        let last = (viewport.x.max(viewport.y) + viewport.span).ceil() as i64;
+        for i in first.max(0)..=last.min(state.curve.p) {
+            let pos = canvas.to_screen(i, i);
+            canvas.painter.line_segment(
+                [egui::pos2(pos.x, rect.min.y), egui::pos2(pos.x, rect.max.y)],
+                stroke,
+            );
+            canvas.painter.line_segment(
+                [egui::pos2(rect.min.x, pos.y), egui::pos2(rect.max.x, pos.y)],
+                stroke,
+            );
+        }
+    }
+}
+
+// 可见范围内的刻度：步长取 1、2、5 乘以 10 的幂，大约 6 个，只在 [0, max] 里
+pub fn ticks(start: f32, span: f32, max: i64) -> Vec<i64> {
+    let raw = (span / 6.0).max(1.0);
+    let magnitude = 10f32.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= raw)
+        .expect("10 × magnitude exceeds raw") as i64;
+    let first = (start.max(0.0) / step as f32).ceil() as i64 * step;
+    let last = ((start + span) as i64).min(max);
+    (first..=last).step_by(step as usize).collect()
+}
+
+// 沿底边和左边标出坐标刻度，放大后跟着可见范围变
+pub struct AxesLayer;
+
+impl VisualizationLayer for AxesLayer {
+    fn name(&self) -> &'static str {
+        "Axes"
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let (rect, viewport) = (canvas.rect, canvas.viewport);
+        let font = egui::FontId::proportional(10.0);
+        let color = egui::Color32::DARK_GRAY;
+        let stroke = egui::Stroke::new(1.0, color);
+        for x in ticks(viewport.x, viewport.span, state.curve.p) {
+            let pos = egui::pos2(canvas.to_screen(x, 0).x, rect.max.y);
+            canvas
+                .painter
+                .line_segment([pos, pos - egui::vec2(0.0, 4.0)], stroke);
+            canvas.painter.text(
+                pos - egui::vec2(0.0, 5.0),
+                egui::Align2::CENTER_BOTTOM,
+                x.to_string(),
+                font.clone(),
+                color,
+            );
+        }
+        for y in ticks(viewport.y, viewport.span, state.curve.p) {
+            let pos = egui::pos2(rect.min.x, canvas.to_screen(0, y).y);
+            canvas
+                .painter
+                .line_segment([pos, pos + egui::vec2(4.0, 0.0)], stroke);
+            canvas.painter.text(
+                pos + egui::vec2(5.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                y.to_string(),
+                font.clone(),
+                color,
+            );
+        }
+    }
+}
+
+// 点数超过这个值时把点光栅化成一张纹理，整层只画一个矩形，
+// 而不是每帧提交成千上万个圆
+const RASTER_THRESHOLD: usize = 5_000;
+// 点的半径，单位是 egui 的逻辑像素
+const POINT_RADIUS: f32 = 2.0;
+
+// 曲线、点数（枚举时会逐渐增加）、H 的生成元、可见区域和纹理的像素尺寸都没变时复用纹理
+type RasterKey = (Curve, usize, Option<Coords>, Viewport, [usize; 2]);
+
+// 曲线上的点，开启陪集着色时每个陪集用一个色相；奇异曲线还会标出奇点
+#[derive(Default)]
+pub struct PointsLayer {
+    raster: Option<(RasterKey, egui::TextureHandle)>,
+}
+
+fn point_color(state: &PlotState, i: usize) -> egui::Color32 {
+    match state.cosets {
+        Some((_, cosets)) => {
+            let hue = cosets.index[i] as f32 / cosets.count as f32;
+            egui::Color32::from(egui::ecolor::Hsva::new(hue, 0.85, 0.9, 1.0))
+        }
+        None => egui::Color32::RED,
+    }
+}
+
+// 在 size 大小的图像上画出 viewport 里的点，坐标映射与 Canvas::to_screen 一致。
+// 小地图也用它画整条曲线的缩略图
+pub fn rasterize(
+    state: &PlotState,
+    viewport: Viewport,
+    size: [usize; 2],
+    radius: f32,
+) -> egui::ColorImage {
+    let [width, height] = size;
+    let mut image = egui::ColorImage::new(size, egui::Color32::TRANSPARENT);
+    let scale = (width as f32 / viewport.span, height as f32 / viewport.span);
+    let r = radius.round().max(1.0) as i64;
+    for (i, point) in state.points.iter().enumerate() {
+        let Some((x, y)) = point.coords() else {
+            continue;
+        };
+        if !viewport.contains(x, y) {
+            continue;
+        }
+        let color = point_color(state, i);
+        let cx = ((x as f32 - viewport.x) * scale.0) as i64;
+        let cy = height as i64 - ((y as f32 - viewport.y) * scale.1) as i64;
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let (px, py) = (cx + dx, cy + dy);
+                if dx * dx + dy * dy <= r * r
+                    && (0..width as i64).contains(&px)
+                    && (0..height as i64).contains(&py)
+                {
+                    image[(px as usize, py as usize)] = color;
+                }
+            }
+        }
+    }
+    image
+}
+
+impl VisualizationLayer for PointsLayer {
+    fn name(&self) -> &'static str {
+        "Points"
+    }
+
+    fn compute(&mut self, state: &PlotState, canvas: &Canvas) {
+        // 放大以后看得见的点少，够少就直接画圆
+        let viewport = canvas.viewport;
+        let visible = if viewport.is_zoomed(state.curve.p) {
+            state
+                .points
+                .iter()
+                .filter_map(Point::coords)
+                .filter(|&(x, y)| viewport.contains(x, y))
+                .count()
+        } else {
+            state.points.len()
+        };
+        if visible <= RASTER_THRESHOLD {
+            self.raster = None;
+            return;
+        }
+        let ctx = canvas.painter.ctx();
+        let pixels_per_point = ctx.pixels_per_point();
+        let size = (canvas.rect.size() * pixels_per_point).round();
+        let size = [size.x as usize, size.y as usize];
+        let key = (
+            *state.curve,
+            state.points.len(),
+            state.cosets.map(|(base, _)| base),
+            viewport,
+            size,
+        );
+        if self
+            .raster
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != key)
+        {
+            let _span =
+                tracing::debug_span!("rasterize_points", points = state.points.len()).entered();
+            let image = rasterize(state, viewport, size, POINT_RADIUS * pixels_per_point);
+            let texture = ctx.load_texture("points", image, egui::TextureOptions::NEAREST);
+            self.raster = Some((key, texture));
+        }
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let p = state.curve.p;
+        match &self.raster {
+            Some(((curve, count, _, viewport, _), texture))
+                if curve == state.curve
+                    && *count == state.points.len()
+                    && *viewport == canvas.viewport =>
+            {
+                canvas.painter.image(
+                    texture.id(),
+                    canvas.rect,
+                    egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                    egui::Color32::WHITE,
+                );
+            }
+            _ => {
+                for (i, point) in state.points.iter().enumerate() {
+                    if let Some((x, y)) = point.coords()
+                        && canvas.viewport.contains(x, y)
+                    {
+                        assert!(x >= 0 && x < p);
+                        assert!(y >= 0 && y < p);
+                        canvas.painter.circle_filled(
+                            canvas.to_screen(x, y),
+                            POINT_RADIUS,
+                            point_color(state, i),
+                        );
+                    }
+                }
+            }
+        }
+
+        // 奇点：圈出来并标明是结点还是尖点
+        if let Some(singularity) = state.curve.singularity() {
+            let pos = canvas.to_screen(singularity.x(), 0);
+            canvas
+                .painter
+                .circle_stroke(pos, 7.0, egui::Stroke::new(2.0, egui::Color32::BLACK));
+            let name = match singularity {
+                Singularity::Cusp { .. } => "cusp",
+                Singularity::Node { .. } => "node",
+            };
+            canvas.painter.text(
+                pos + egui::vec2(9.0, -9.0),
+                egui::Align2::LEFT_BOTTOM,
+                format!("{name} ({}, 0)", singularity.x()),
+                egui::FontId::proportional(12.0),
+                egui::Color32::BLACK,
+            );
+        }
+    }
+}
+
+// (P, P + G) 的列表
+type VectorField = Vec<(Coords, Coords)>;
+
+// 向量场：每个点 P 指向 P + G，G、曲线或点数改变时才重新批量计算
+pub struct FieldLayer {
+    arrow_len: f32,
+    max_arrows: usize,
+    cache: Option<((Curve, Coords, usize), VectorField)>,
+}
+
+impl Default for FieldLayer {
+    fn default() -> Self {
+        FieldLayer {
+            arrow_len: 12.0,
+            max_arrows: 2000,
+            cache: None,
+        }
+    }
+}
+
+impl VisualizationLayer for FieldLayer {
+    fn name(&self) -> &'static str {
+        "Vector field"
+    }
+
+    fn compute(&mut self, state: &PlotState, _canvas: &Canvas) {
+        let Some(g) = state.field_base else {
+            return;
+        };
+        let key = (*state.curve, g, state.points.len());
+        if self.cache.as_ref().is_none_or(|(cached, _)| *cached != key) {
+            let coords: Vec<Coords> = state.points.iter().map(Point::coords).collect();
+            let sums = state.curve.add_to_all(&coords, g);
+            self.cache = Some((key, coords.into_iter().zip(sums).collect()));
+        }
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let (Some(g), Some((key, field))) = (state.field_base, &self.cache) else {
+            return;
+        };
+        if *key != (*state.curve, g, state.points.len()) {
+            return;
+        }
+        // 点太多时均匀抽样，箭头只画一小段指向 P + G 的方向
+        let stride = field.len().div_ceil(self.max_arrows).max(1);
+        for (from, to) in field.iter().step_by(stride) {
+            if let (Some((x1, y1)), Some((x2, y2))) = (from, to) {
+                let start = canvas.to_screen(*x1, *y1);
+                let direction = canvas.to_screen(*x2, *y2) - start;
+                canvas.painter.arrow(
+                    start,
+                    direction.normalized() * self.arrow_len.min(direction.length()),
+                    egui::Stroke::new(1.0, egui::Color32::from_gray(120)),
+                );
+            }
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::Slider::new(&mut self.arrow_len, 4.0..=40.0).text("arrow length"));
+        ui.add(
+            egui::Slider::new(&mut self.max_arrows, 100..=20000)
+                .logarithmic(true)
+                .text("max arrows"),
+        );
+    }
+}
+
+// 子群格里选中的子群：给它的点套一个圆圈
+pub struct SubgroupLayer;
+
+impl VisualizationLayer for SubgroupLayer {
+    fn name(&self) -> &'static str {
+        "Subgroup"
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let Some(elements) = state.highlight else {
+            return;
+        };
+        let stroke = egui::Stroke::new(2.0, egui::Color32::from_rgb(0, 160, 220));
+        for &(x, y) in elements.iter().flatten() {
+            canvas
+                .painter
+                .circle_stroke(canvas.to_screen(x, y), 6.0, stroke);
+        }
+    }
+}
+
+// 追踪点的轨迹：相邻两步之间画箭头，最后一步用空心圆标出
+pub struct OrbitLayer;
+
+impl VisualizationLayer for OrbitLayer {
+    fn name(&self) -> &'static str {
+        "Orbits"
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let p = state.curve.p;
+        for tracked in state.tracked {
+            // 无穷远点没有坐标，跳过
+            for pair in tracked.steps.windows(2) {
+                if let Some((x1, y1)) = pair[0].1.coords()
+                    && let Some((x2, y2)) = pair[1].1.coords()
+                {
+                    let from = canvas.to_screen(x1, y1);
+                    canvas.painter.arrow(
+                        from,
+                        canvas.to_screen(x2, y2) - from,
+                        egui::Stroke::new(1.5, tracked.color),
+                    );
+                }
+            }
+
+            // 步数太多时标签会挤在一起，只画点
+            let show_labels = tracked.steps.len() <= 40;
+            for (i, (label, point)) in tracked.steps.iter().enumerate() {
+                if let Some((x, y)) = point.coords() {
+                    assert!(x >= 0 && x < p);
+                    assert!(y >= 0 && y < p);
+                    let pos = canvas.to_screen(x, y);
+                    if i == tracked.steps.len() - 1 {
+                        canvas.painter.circle(
+                            pos,
+                            5.0,
+                            egui::Color32::YELLOW,
+                            egui::Stroke::new(2.0, tracked.color),
+                        );
+                    } else {
+                        canvas.painter.circle_filled(pos, 4.0, tracked.color);
+                    }
+                    if show_labels {
+                        canvas.painter.text(
+                            pos + egui::vec2(5.0, -5.0),
+                            egui::Align2::LEFT_BOTTOM,
+                            label,
+                            egui::FontId::proportional(11.0),
+                            tracked.color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 曲线和选中的点都没变时复用上次的弦
+type ChordKey = (Curve, Vec<(i64, i64)>);
+
+// 点选的 P、Q：画出过两点的直线（P = Q 时是切线）、第三个交点 −(P + Q) 和翻转后的 P + Q
+#[derive(Default)]
+pub struct GroupLawLayer {
+    chord: Option<(ChordKey, Option<Chord>)>,
+}
+
+impl VisualizationLayer for GroupLawLayer {
+    fn name(&self) -> &'static str {
+        "Group law"
+    }
+
+    fn compute(&mut self, state: &PlotState, _canvas: &Canvas) {
+        let key = (*state.curve, state.selection.to_vec());
+        if self.chord.as_ref().is_none_or(|(cached, _)| *cached != key) {
+            // 合数模数下分母不可逆时只标出选中的点
+            let chord = match state.selection {
+                &[p, q] => Chord::new(state.curve, p, q).ok(),
+                _ => None,
+            };
+            self.chord = Some((key, chord));
+        }
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let color = egui::Color32::from_rgb(230, 120, 0);
+        if let Some((_, Some(chord))) = &self.chord {
+            paint_chord(canvas, state.curve.p, chord, color, ("−(P + Q)", "P + Q"));
+        }
+        // 切线的情况 P、Q 是同一个点，只标一次
+        let names: &[&str] = match state.selection {
+            [p, q] if p == q => &["P = Q"],
+            _ => &["P", "Q"],
+        };
+        for (name, &(x, y)) in names.iter().zip(state.selection) {
+            let pos = canvas.to_screen(x, y);
+            canvas
+                .painter
+                .circle_stroke(pos, 6.0, egui::Stroke::new(2.0, egui::Color32::BLACK));
+            label(canvas, pos, format!("{name} ({x}, {y})"), color);
+        }
+    }
+}
+
+fn label(canvas: &Canvas, pos: egui::Pos2, text: String, color: egui::Color32) {
+    canvas.painter.text(
+        pos + egui::vec2(8.0, -8.0),
+        egui::Align2::LEFT_BOTTOM,
+        text,
+        egui::FontId::proportional(12.0),
+        color,
+    );
+}
+
+// 直线的各段、第三个交点和翻转到的和，names 是这两个点的名字
+fn paint_chord(
+    canvas: &Canvas,
+    p: i64,
+    chord: &Chord,
+    color: egui::Color32,
+    (third_name, sum_name): (&str, &str),
+) {
+    for [(xa, ya), (xb, yb)] in chord.segments(p) {
+        canvas.painter.line_segment(
+            [canvas.project(xa, ya), canvas.project(xb, yb)],
+            egui::Stroke::new(1.5, color.gamma_multiply(0.7)),
+        );
+    }
+    if let (Some((x, y)), Some((_, sum_y))) = (chord.third, chord.sum) {
+        let third = canvas.to_screen(x, y);
+        let sum = canvas.to_screen(x, sum_y);
+        canvas.painter.add(egui::Shape::dashed_line(
+            &[third, sum],
+            egui::Stroke::new(1.0, color),
+            4.0,
+            3.0,
+        ));
+        canvas
+            .painter
+            .circle_stroke(third, 5.0, egui::Stroke::new(1.5, color));
+        label(canvas, third, format!("{third_name} ({x}, {y})"), color);
+        canvas.painter.circle_filled(sum, 5.0, color);
+        label(canvas, sum, format!("{sum_name} ({x}, {sum_y})"), color);
+    }
+}
+
+// 窗口里的说明文字用同样的颜色
+pub const RHO_TAIL: egui::Color32 = egui::Color32::from_rgb(230, 120, 0);
+pub const RHO_CYCLE: egui::Color32 = egui::Color32::from_rgb(200, 30, 60);
+
+// Pollard rho 的游走：进圈前的尾巴和圈用两种颜色的箭头，标出起点和圈的入口
+pub struct RhoLayer;
+
+impl VisualizationLayer for RhoLayer {
+    fn name(&self) -> &'static str {
+        "Rho walk"
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let Some(rho) = state.walk else {
+            return;
+        };
+        for (i, pair) in rho.walk.windows(2).enumerate() {
+            let color = if i < rho.tail { RHO_TAIL } else { RHO_CYCLE };
+            if let (Some((x1, y1)), Some((x2, y2))) = (pair[0], pair[1]) {
+                let from = canvas.to_screen(x1, y1);
+                canvas.painter.arrow(
+                    from,
+                    canvas.to_screen(x2, y2) - from,
+                    egui::Stroke::new(1.5, color),
+                );
+            }
+        }
+        for (i, name, color) in [(0, "X_0", RHO_TAIL), (rho.tail, "cycle entry", RHO_CYCLE)] {
+            if let Some((x, y)) = rho.walk[i] {
+                let pos = canvas.to_screen(x, y);
+                canvas.painter.circle_filled(pos, 5.0, color);
+                label(canvas, pos, format!("{name} ({x}, {y})"), color);
+            }
+        }
+    }
+}
+
+// 协议窗口的标记。落在同一个点上的标记合成一个标签，例如双方算出的同一个 abG；
+// 颜色取第一个标记的
+pub struct MarkersLayer;
+
+impl VisualizationLayer for MarkersLayer {
+    fn name(&self) -> &'static str {
+        "Markers"
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let mut labels: Vec<((i64, i64), Vec<&str>, egui::Color32)> = Vec::new();
+        for marker in state.markers {
+            let i = match labels
+                .iter()
+                .position(|(coords, ..)| *coords == marker.coords)
+            {
+                Some(i) => i,
+                None => {
+                    labels.push((marker.coords, Vec::new(), marker.color));
+                    labels.len() - 1
+                }
+            };
+            // 没有文字的标记只画点，例如 BSGS 的每一步
+            if !marker.label.is_empty() {
+                labels[i].1.push(&marker.label);
+            }
+        }
+        for ((x, y), names, color) in labels {
+            let pos = canvas.to_screen(x, y);
+            canvas.painter.circle_filled(pos, 5.0, color);
+            if names.is_empty() {
+                continue;
+            }
+            canvas.painter.text(
+                pos + egui::vec2(8.0, 8.0),
+                egui::Align2::LEFT_TOP,
+                format!("{} ({x}, {y})", names.join(", ")),
+                egui::FontId::proportional(12.0),
+                color,
+            );
+        }
+    }
+}
+
+// k·P 动画的当前一帧：这一步的切线或弦，以及得到的点
+pub struct AnimationLayer;
+
+impl VisualizationLayer for AnimationLayer {
+    fn name(&self) -> &'static str {
+        "Animation"
+    }
+
+    fn paint(&self, state: &PlotState, canvas: &Canvas) {
+        let Some(frame) = state.animation else {
+            return;
+        };
+        let color = egui::Color32::from_rgb(200, 30, 60);
+        match &frame.chord {
+            Some(chord) => {
+                let result = animation::name(frame.multiple);
+                let third = format!("−{result}");
+                paint_chord(canvas, state.curve.p, chord, color, (&third, &result));
+            }
+            None => {
+                if let Some((x, y)) = frame.point {
+                    let pos = canvas.to_screen(x, y);
+                    canvas.painter.circle_filled(pos, 5.0, color);
+                    label(canvas, pos, format!("{} ({x}, {y})", frame.label), color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // 记录被调用的顺序
+    struct Probe(Rc<RefCell<Vec<String>>>);
+
+    impl VisualizationLayer for Probe {
+        fn name(&self) -> &'static str {
+            "Probe"
+        }
+
+        fn compute(&mut self, state: &PlotState, _canvas: &Canvas) {
+            self.0
+                .borrow_mut()
+                .push(format!("compute {}", state.points.len()));
+        }
+
+        fn paint(&self, _state: &PlotState, _canvas: &Canvas) {
+            self.0.borrow_mut().push("paint".to_string());
+        }
+    }
+
+    fn canvas(p: i64) -> Canvas {
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(100.0, 100.0));
+        // 跑一帧，坐标轴的文字需要字体
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |_| {});
+        let painter = egui::Painter::new(ctx, egui::LayerId::background(), rect);
+        Canvas::new(painter, rect, p)
+    }
+
+    #[test]
+    fn ticks_use_round_steps_inside_the_field() {
+        assert_eq!(ticks(0.0, 599.0, 599), [0, 100, 200, 300, 400, 500]);
+        assert_eq!(ticks(0.0, 97.0, 97), [0, 20, 40, 60, 80]);
+        // 放大后步长变小，负的起点被截到 0
+        assert_eq!(ticks(-3.5, 20.0, 599), [0, 5, 10, 15]);
+        assert_eq!(ticks(590.2, 8.0, 599), [592, 594, 596, 598]);
+        assert_eq!(ticks(0.0, 5.0, 5), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn registered_layers_compute_then_paint_when_enabled() {
+        let curve = Curve::new(2, 3, 97);
+        let points = curve.points();
+        let state = PlotState {
+            curve: &curve,
+            points: &points,
+            tracked: &[],
+            cosets: None,
+            field_base: None,
+            highlight: None,
+            selection: &[],
+            markers: &[],
+            animation: None,
+            walk: None,
+        };
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut registry = LayerRegistry::default();
+        registry.register(Box::new(Probe(calls.clone())));
+        registry.paint(&state, &canvas(curve.p));
+        assert_eq!(
+            *calls.borrow(),
+            vec![format!("compute {}", points.len()), "paint".to_string()]
+        );
+
+        registry.layers.last_mut().expect("probe is registered").1 = false;
+        registry.paint(&state, &canvas(curve.p));
+        assert_eq!(calls.borrow().len(), 2);
+    }
+
+    #[test]
+    fn field_layer_maps_each_point_to_its_sum_with_g() {
+        let curve = Curve::new(2, 3, 97);
+        let points = curve.points();
+        let g = Some((3, 6));
+        let state = PlotState {
+            curve: &curve,
+            points: &points,
+            tracked: &[],
+            cosets: None,
+            field_base: Some(g),
+            highlight: None,
+            selection: &[],
+            markers: &[],
+            animation: None,
+            walk: None,
+        };
+        let mut layer = FieldLayer::default();
+        layer.compute(&state, &canvas(curve.p));
+        let (key, field) = layer.cache.as_ref().expect("field is computed");
+        assert_eq!(*key, (curve, g, points.len()));
+        for (point, (from, to)) in points.iter().zip(field) {
+            assert_eq!(*from, point.coords());
+            assert_eq!(*to, curve.add_coords(point.coords(), g));
+        }
+    }
+
+    #[test]
+    fn raster_puts_points_where_the_canvas_would() {
+        let curve = Curve::new(2, 3, 97);
+        let points = curve.points();
+        let state = PlotState {
+            curve: &curve,
+            points: &points,
+            tracked: &[],
+            cosets: None,
+            field_base: None,
+            highlight: None,
+            selection: &[],
+            markers: &[],
+            animation: None,
+            walk: None,
+        };
+        let image = rasterize(&state, Viewport::full(97), [97, 97], 1.0);
+        let lit = image.pixels.iter().filter(|c| c.a() > 0).count();
+        assert!(lit > points.len() && lit <= 5 * points.len());
+        for point in &points {
+            let (x, y) = point.coords().expect("affine points");
+            if y > 0 {
+                assert_eq!(image[(x as usize, 97 - y as usize)], egui::Color32::RED);
+            }
+        }
+    }
+
+    #[test]
+    fn large_point_sets_are_drawn_from_one_texture() {
+        let curve = Curve::new(1, 1, 20011);
+        let mut points = curve.points();
+        points.truncate(2 * RASTER_THRESHOLD);
+        let mut state = PlotState {
+            curve: &curve,
+            points: &points,
+            tracked: &[],
+            cosets: None,
+            field_base: None,
+            highlight: None,
+            selection: &[],
+            markers: &[],
+            animation: None,
+            walk: None,
+        };
+        let canvas = canvas(curve.p);
+        let mut layer = PointsLayer::default();
+        layer.compute(&state, &canvas);
+        let (key, texture) = layer.raster.clone().expect("points are rasterized");
+        assert_eq!(
+            key,
+            (
+                curve,
+                points.len(),
+                None,
+                Viewport::full(curve.p),
+                [100, 100]
+            )
+        );
+
+        // 状态不变时复用同一张纹理
+        layer.compute(&state, &canvas);
+        assert_eq!(
+            layer.raster.as_ref().map(|(_, t)| t.id()),
+            Some(texture.id())
+        );
+
+        state.points = &points[..RASTER_THRESHOLD];
+        layer.compute(&state, &canvas);
+        assert!(layer.raster.is_none());
+    }
+
+    #[test]
+    fn canvas_puts_the_origin_at_the_bottom_left() {
+        let canvas = canvas(10);
+        assert_eq!(canvas.to_screen(0, 0), egui::pos2(0.0, 100.0));
+        assert_eq!(canvas.to_screen(10, 10), egui::pos2(100.0, 0.0));
+
+        // 放大到 [4, 9] × [2, 7]，每格 20 像素
+        let zoomed = Canvas::with_viewport(
+            canvas.painter,
+            canvas.rect,
+            Viewport {
+                x: 4.0,
+                y: 2.0,
+                span: 5.0,
+            },
+        );
+        assert_eq!(zoomed.step(), 20.0);
+        assert_eq!(zoomed.to_screen(4, 2), egui::pos2(0.0, 100.0));
+        assert_eq!(zoomed.to_screen(6, 3), egui::pos2(40.0, 80.0));
+        assert_eq!(zoomed.to_field(egui::pos2(40.0, 80.0)), (6.0, 3.0));
+    }
+}
@@ -0,0 +1,83 @@
+// 椭圆曲线运算库：curve、point、field 是公开的曲线、点和有限域运算接口，
+// scalar 和 zeroize 是给密码学演示准备的标量类型。app 是 egui 界面，
+// 可执行文件只负责解析命令行参数并启动它，cli 是不开窗口的子命令。
+pub mod affine;
+mod animation;
+pub mod app;
+mod batch;
+mod bench;
+pub mod bigcurve;
+mod bip340;
+mod cache;
+mod chord;
+pub mod cli;
+mod coinflip;
+mod commitment;
+pub mod console;
+#[cfg(feature = "ct")]
+mod ct;
+pub mod curve;
+mod diagnostics;
+mod division;
+pub mod dlog;
+mod ecdh;
+pub mod ecdsa;
+mod ecm;
+mod ed25519;
+mod edwards;
+pub mod elgamal;
+pub mod error;
+mod explorer;
+mod fault;
+pub mod field;
+mod field25519;
+mod fixed_base;
+#[cfg(any(test, fuzzing))]
+pub mod fuzz;
+#[cfg(test)]
+mod group_laws;
+pub mod hash_to_curve;
+mod invalid_curve;
+mod ipa;
+pub mod jacobian;
+mod job;
+mod known_answers;
+mod lattice;
+mod layers;
+mod layout;
+mod models;
+mod modular;
+mod montgomery;
+mod net;
+mod orbit;
+mod p256;
+mod pairing;
+mod pedersen;
+mod pohlig_hellman;
+pub mod point;
+pub mod presets;
+mod protocol;
+mod real;
+mod recording;
+mod rfc6979;
+pub mod rng;
+pub mod scalar;
+mod schnorr;
+mod script;
+mod secp256k1;
+mod sha256;
+mod sha512;
+mod stats;
+mod stream;
+pub mod subgroup;
+mod summary;
+mod twisted_edwards;
+pub mod u256;
+mod view;
+mod viewport;
+mod weierstrass;
+mod x25519;
+pub mod zeroize;
+
+// 脚本输出里的界面操作，可执行文件据此决定要不要打开窗口
+pub use recording::Action;
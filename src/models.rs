@@ -0,0 +1,111 @@
+// 同一条曲线的三种写法：短 Weierstrass、Montgomery 和扭曲 Edwards。
+// 短形式 y² = x³ + ax + b 要有 2 阶点 (α, 0)，并且 3α² + a 是平方，才能写成 Montgomery 形式：
+// 取 s = 1/√(3α² + a)，B = s，A = 3αs，u = s(x − α)，v = sy。Montgomery 形式总能写成 Edwards
+// 形式：a = (A + 2)/B，d = (A − 2)/B。反方向的换元在 montgomery.rs 和 twisted_edwards.rs 里，
+// 这里算出的系数代回去得到的正是原来的曲线，点可以在三种形式之间来回映射。
+use crate::curve::Curve;
+use crate::field::{self, mul};
+use crate::montgomery::{Montgomery, MontgomeryReduction};
+use crate::twisted_edwards::{EdwardsReduction, TwistedEdwards};
+
+// 没有合适的 2 阶点时为 None；有几个时取 α 最小的
+pub fn montgomery_form(curve: &Curve) -> Option<MontgomeryReduction> {
+    let p = curve.p;
+    if p <= 3 || curve.is_singular() {
+        return None;
+    }
+    let m = |x: i64, y: i64| mul(x, y, p);
+    (0..p)
+        .filter(|&alpha| curve.contains(alpha, 0))
+        .find_map(|alpha| {
+            let root = field::sqrt_mod(m(3, m(alpha, alpha)) + curve.a, p)?;
+            let s = field::mod_inverse(root, p);
+            Montgomery::new(m(3, m(alpha, s)), s, p).reduce().ok()
+        })
+}
+
+pub fn edwards_form(montgomery: &Montgomery) -> Result<EdwardsReduction, String> {
+    let Montgomery { a, b, p } = *montgomery;
+    let inv_b = field::mod_inverse(b, p);
+    TwistedEdwards::new(mul(a + 2, inv_b, p), mul(a - 2, inv_b, p), p).reduce()
+}
+
+// 当前曲线能写成的形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Models {
+    pub curve: Curve,
+    pub montgomery: Option<MontgomeryReduction>,
+    pub edwards: Option<EdwardsReduction>,
+}
+
+impl Models {
+    pub fn of(curve: &Curve) -> Self {
+        let montgomery = montgomery_form(curve);
+        let edwards = montgomery.and_then(|reduction| edwards_form(&reduction.montgomery).ok());
+        Models {
+            curve: *curve,
+            montgomery,
+            edwards,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    #[test]
+    fn conversions_give_back_the_same_curve() {
+        for curve in [
+            Curve::new(2, 3, 97),
+            Curve::new(-2, 1, 101),
+            Curve::new(5, 0, 599),
+        ] {
+            let models = Models::of(&curve);
+            let montgomery = models.montgomery.expect("has a Montgomery form");
+            let edwards = models
+                .edwards
+                .expect("Montgomery curves have an Edwards form");
+            assert_eq!(montgomery.curve, curve);
+            assert_eq!(edwards.curve(), curve);
+            assert_eq!(edwards.montgomery, montgomery);
+            for point in curve.points() {
+                let coords = point.coords();
+                let image = montgomery.map_to_montgomery(coords);
+                let (u, v) = image.expect("affine points stay affine");
+                assert!(montgomery.montgomery.contains(u, v));
+                assert_eq!(montgomery.map_to_short(image), coords);
+                // Edwards 模型上缺的点只有 v = 0 或 u = −1 的那些
+                match edwards.map_to_edwards(image) {
+                    Some((x, y)) => {
+                        assert!(edwards.edwards.contains(x, y));
+                        assert_eq!(edwards.map_to_short((x, y)), coords);
+                    }
+                    None => assert!(v == 0 || u == curve.p - 1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn some_curves_have_no_montgomery_form() {
+        // #E = 597 是奇数，没有 2 阶点
+        let models = Models::of(&Curve::new(1, 1, 599));
+        assert_eq!(models.montgomery, None);
+        assert_eq!(models.edwards, None);
+        // 有 2 阶点 (0, 0)，但 3·0² + a = 5 不是模 97 的平方
+        let curve = Curve::new(5, 0, 97);
+        assert!(curve.contains(0, 0));
+        assert!(
+            curve
+                .points()
+                .iter()
+                .filter_map(Point::coords)
+                .all(|(x, y)| {
+                    y != 0 || !field::is_quadratic_residue(3 * x * x + curve.a, curve.p)
+                })
+        );
+        assert_eq!(montgomery_form(&curve), None);
+    }
+}
@@ -0,0 +1,209 @@
+// 经典模多项式 Φ_ℓ(X, Y)：Φ_ℓ(j(E), j(E')) = 0 当且仅当 E 和 E' 之间（在代数闭包上）
+// 有一条核为 ℓ 阶循环群的同源。系数是几十位的整数，由 scripts/gen_modular_polynomials.py
+// 从 j 的 q 展开独立算出，放在 testdata/modular_polynomials.txt 里，读取时直接约化到 F_p。
+use crate::curve::Curve;
+use crate::field::mul;
+
+const DATA: &str = include_str!("../testdata/modular_polynomials.txt");
+
+// 数据文件里有的 ℓ
+pub const LEVELS: [u64; 4] = [2, 3, 5, 7];
+
+pub struct ModularPolynomial {
+    pub l: u64,
+    pub p: i64,
+    // (X 的次数, Y 的次数, 系数 mod p)
+    terms: Vec<(u32, u32, i64)>,
+}
+
+// 十进制大整数模 p
+fn reduce(digits: &str, p: i64) -> i64 {
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, digits),
+    };
+    let value = digits.bytes().fold(0, |acc, d| {
+        assert!(d.is_ascii_digit(), "bad coefficient {digits}");
+        (acc * 10 + i64::from(d - b'0')) % p
+    });
+    if negative { (p - value) % p } else { value }
+}
+
+fn pow(base: i64, exp: u32, p: i64) -> i64 {
+    (0..exp).fold(1 % p, |acc, _| mul(acc, base, p))
+}
+
+impl ModularPolynomial {
+    // 每行格式：ℓ i k c，表示 c·X^i·Y^k
+    pub fn new(l: u64, p: i64) -> Result<Self, String> {
+        let terms: Vec<(u32, u32, i64)> = DATA
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                assert_eq!(fields.len(), 4, "{line}");
+                (fields[0].parse::<u64>().ok()? == l).then(|| {
+                    let degree = |s: &str| s.parse::<u32>().expect("degrees are small");
+                    (degree(fields[1]), degree(fields[2]), reduce(fields[3], p))
+                })
+            })
+            .collect();
+        if terms.is_empty() {
+            return Err(format!("Φ_{l} is not bundled (available: {LEVELS:?})"));
+        }
+        Ok(ModularPolynomial { l, p, terms })
+    }
+
+    pub fn eval(&self, x: i64, y: i64) -> i64 {
+        let p = self.p;
+        let (x, y) = (x.rem_euclid(p), y.rem_euclid(p));
+        self.terms.iter().fold(0, |acc, &(i, k, c)| {
+            (acc + mul(c, mul(pow(x, i, p), pow(y, k, p), p), p)) % p
+        })
+    }
+
+    // Φ_ℓ(j, Y) 在 F_p 里的根：ℓ 同源图里 j 的邻居。先代入 X = j 得到 Y 的多项式，再逐个试 Y
+    pub fn neighbours(&self, j: i64) -> Vec<i64> {
+        let p = self.p;
+        let mut coefficients = vec![0; self.l as usize + 2];
+        for &(i, k, c) in &self.terms {
+            let slot = &mut coefficients[k as usize];
+            *slot = (*slot + mul(c, pow(j.rem_euclid(p), i, p), p)) % p;
+        }
+        (0..p)
+            .filter(|&y| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, &c| (mul(acc, y, p) + c) % p)
+                    == 0
+            })
+            .collect()
+    }
+}
+
+// 只看 j 不变量，不枚举核：结果对 E2 的二次扭曲也成立
+pub fn are_l_isogenous(e1: &Curve, e2: &Curve, l: u64) -> Result<bool, String> {
+    if e1.p != e2.p {
+        return Err(format!("the curves live over F_{} and F_{}", e1.p, e2.p));
+    }
+    let (Some(j1), Some(j2)) = (e1.j_invariant(), e2.j_invariant()) else {
+        return Err("singular curves have no j-invariant".to_string());
+    };
+    Ok(ModularPolynomial::new(l, e1.p)?.eval(j1, j2) == 0)
+}
+
+// 界面用：当前曲线在各个 ℓ 下的同源邻居
+pub struct Neighbours {
+    pub curve: Curve,
+    pub j: i64,
+    pub levels: Vec<(u64, Vec<i64>)>,
+}
+
+impl Neighbours {
+    pub fn new(curve: &Curve) -> Result<Self, String> {
+        let j = curve
+            .j_invariant()
+            .ok_or("singular curves have no j-invariant")?;
+        let levels = LEVELS
+            .iter()
+            .map(|&l| Ok((l, ModularPolynomial::new(l, curve.p)?.neighbours(j))))
+            .collect::<Result<_, String>>()?;
+        Ok(Neighbours {
+            curve: *curve,
+            j,
+            levels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Coords;
+    use crate::point::Point;
+
+    // Vélu 公式：由核生成元算出同源像曲线，作为与模多项式无关的对照
+    fn velu(curve: &Curve, kernel: Coords, l: u64) -> Curve {
+        let p = curve.p;
+        let (mut v, mut w) = (0, 0);
+        // ±Q 只取一个代表
+        for k in 1..=l / 2 {
+            let (x, y) = curve.mul_coords(kernel, k).unwrap();
+            let gx = (3 * mul(x, x, p) + curve.a).rem_euclid(p);
+            let vq = if y == 0 { gx } else { 2 * gx % p };
+            let uq = mul(2 * y, 2 * y, p);
+            v = (v + vq) % p;
+            w = (w + uq + mul(x, vq, p)) % p;
+        }
+        Curve::new(
+            (curve.a - 5 * v).rem_euclid(p),
+            (curve.b - 7 * w).rem_euclid(p),
+            p,
+        )
+    }
+
+    #[test]
+    fn phi_2_matches_the_textbook_formula() {
+        let p = 1_000_003;
+        let phi = ModularPolynomial::new(2, p).unwrap();
+        for (x, y) in [(0, 0), (1, 2), (12345, 678), (999_999, 31337)] {
+            let m = |a: i64, b: i64| mul(a, b, p);
+            let (x2, y2) = (m(x, x), m(y, y));
+            let expected = x2 * x % p + y2 * y % p - m(x2, y2) + m(1488, m(x2, y) + m(x, y2))
+                - m(162000, x2 + y2)
+                + m(40773375, m(x, y))
+                + m(8748000000 % p, x + y)
+                - 157464000000000 % p;
+            assert_eq!(phi.eval(x, y), expected.rem_euclid(p), "({x}, {y})");
+        }
+        assert!(ModularPolynomial::new(11, p).is_err());
+    }
+
+    #[test]
+    fn velu_isogenies_are_roots_of_phi() {
+        let mut checked = [0; LEVELS.len()];
+        for p in [101, 103, 107, 109] {
+            for (a, b) in [(1, 1), (2, 3), (5, 7), (0, 3), (7, 0), (11, 13)] {
+                let curve = Curve::new(a, b, p);
+                let Some(j) = curve.j_invariant() else {
+                    continue;
+                };
+                let points: Vec<Coords> = curve.points().iter().map(Point::coords).collect();
+                for (index, &l) in LEVELS.iter().enumerate() {
+                    let Some(&kernel) = points
+                        .iter()
+                        .find(|&&c| c.is_some() && curve.mul_coords(c, l).is_none())
+                    else {
+                        continue;
+                    };
+                    let image = velu(&curve, kernel, l);
+                    // 同源的曲线点数相同
+                    assert_eq!(image.group_order(), curve.group_order(), "{curve:?} {l}");
+                    assert!(are_l_isogenous(&curve, &image, l).unwrap(), "{curve:?} {l}");
+                    let neighbours = ModularPolynomial::new(l, p).unwrap().neighbours(j);
+                    assert!(neighbours.contains(&image.j_invariant().unwrap()));
+                    checked[index] += 1;
+                }
+            }
+        }
+        assert!(checked.iter().all(|&n| n > 0), "{checked:?}");
+    }
+
+    #[test]
+    fn non_neighbours_are_rejected() {
+        let curve = Curve::new(2, 3, 97);
+        let j = curve.j_invariant().unwrap();
+        let neighbours = Neighbours::new(&curve).unwrap();
+        for (l, roots) in &neighbours.levels {
+            // Φ_ℓ(j, Y) 的次数是 ℓ + 1
+            assert!(roots.len() <= *l as usize + 1);
+            let phi = ModularPolynomial::new(*l, 97).unwrap();
+            for y in 0..97 {
+                assert_eq!(phi.eval(j, y) == 0, roots.contains(&y));
+            }
+        }
+        assert!(are_l_isogenous(&curve, &Curve::new(0, 0, 97), 2).is_err());
+        assert!(are_l_isogenous(&curve, &Curve::new(2, 3, 101), 2).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+// Montgomery 曲线 Bv² = u³ + Au² + u。B(A² − 4) ≠ 0 时非奇异，用 x = u/B + A/(3B)、y = v/B
+// 换元成短形式 y² = x³ + ax + b，其中 a = (3 − A²)/(3B²)，b = (2A³ − 9A)/(27B³)。
+// 这种形式的好处是只用 u 坐标就能做标量乘法：射影坐标 (X : Z) 里，知道 P、Q 和 P − Q 的 u
+// 就能算出 P + Q 的 u（差分加法），倍点也只要 u。Montgomery ladder 始终保持 (mP, (m + 1)P)，
+// 差总是 P，每一位做一次差分加法和一次倍点。X25519 用的是同一组公式。
+use crate::curve::{Coords, Curve};
+use crate::field::{self, mul};
+use crate::scalar::Scalar;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Montgomery {
+    pub a: i64,
+    pub b: i64,
+    pub p: i64,
+}
+
+impl Montgomery {
+    // 系数规范到 [0, p)
+    pub fn new(a: i64, b: i64, p: i64) -> Self {
+        Montgomery {
+            a: a.rem_euclid(p),
+            b: b.rem_euclid(p),
+            p,
+        }
+    }
+
+    pub fn contains(&self, u: i64, v: i64) -> bool {
+        let p = self.p;
+        let m = |x: i64, y: i64| mul(x, y, p);
+        let (u, v) = (u.rem_euclid(p), v.rem_euclid(p));
+        m(self.b, m(v, v)) == (m(m(u, u), u + self.a) + u) % p
+    }
+
+    pub fn reduce(&self) -> Result<MontgomeryReduction, String> {
+        let p = self.p;
+        if p <= 3 {
+            return Err(format!(
+                "p = {p}: reducing to short form needs characteristic ≠ 2, 3"
+            ));
+        }
+        let m = |x: i64, y: i64| mul(x, y, p);
+        let (a, b) = (self.a, self.b);
+        if b == 0 || m(a, a) == 4 % p {
+            return Err(format!("{self} is singular: B(A² − 4) = 0"));
+        }
+        let inv = |v: i64| field::mod_inverse(v, p);
+        let b2 = m(b, b);
+        let short_a = m(3 - m(a, a), inv(m(3, b2)));
+        let short_b = m(m(2 * a, m(a, a)) - m(9, a), inv(m(27, m(b2, b))));
+        Ok(MontgomeryReduction {
+            montgomery: *self,
+            curve: Curve::new(short_a, short_b, p),
+        })
+    }
+
+    // u(k·P)，只用 P 的 u 坐标；结果是无穷远点时为 None。u 不必在曲线上，
+    // 不在的话算的是二次扭曲上的点，X25519 不检查公钥也是这个道理
+    pub fn ladder(&self, k: u64, u: i64) -> Option<i64> {
+        // (0, 0) 是 2 阶点；差分加法要除以差的 u，u = 0 时公式退化
+        if u.rem_euclid(self.p) == 0 {
+            return (k % 2 == 1).then_some(0);
+        }
+        let modulus = self.p as u64;
+        let field = |v: i64| Scalar::new(v, modulus);
+        let a24 = field(mul(self.a - 2, field::mod_inverse(4, self.p), self.p));
+        let x1 = field(u);
+        let mut low = (field(1), field(0));
+        let mut high = (x1, field(1));
+        for bit in (0..u64::BITS - k.leading_zeros()).rev() {
+            let swap = (k >> bit) & 1 == 1;
+            if swap {
+                std::mem::swap(&mut low, &mut high);
+            }
+            (low, high) = ladder_step(x1, |e| a24 * e, low, high);
+            if swap {
+                std::mem::swap(&mut low, &mut high);
+            }
+        }
+        let (x, z) = low;
+        Some((x * z.invert()?).value() as i64)
+    }
+}
+
+impl fmt::Display for Montgomery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v² = u³ + {}u² + u over F_{}", self.b, self.a, self.p)
+    }
+}
+
+// 一轮 ladder：low = (X : Z) of mP，high = (m + 1)P，x1 是 P 的 u 坐标，
+// 返回 2mP 和 (2m + 1)P。mul_a24 乘以 (A − 2)/4，各个域自己决定怎么乘最快
+pub fn ladder_step<F>(
+    x1: F,
+    mul_a24: impl Fn(F) -> F,
+    (x2, z2): (F, F),
+    (x3, z3): (F, F),
+) -> ((F, F), (F, F))
+where
+    F: Copy + Add<Output = F> + Sub<Output = F> + Mul<Output = F>,
+{
+    let a = x2 + z2;
+    let aa = a * a;
+    let b = x2 - z2;
+    let bb = b * b;
+    let e = aa - bb;
+    let c = x3 + z3;
+    let d = x3 - z3;
+    let da = d * a;
+    let cb = c * b;
+    let sum = da + cb;
+    let difference = da - cb;
+    (
+        (aa * bb, e * (aa + mul_a24(e))),
+        (sum * sum, x1 * (difference * difference)),
+    )
+}
+
+// Montgomery 形式和对应的短形式，以及两者之间的变量替换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MontgomeryReduction {
+    pub montgomery: Montgomery,
+    pub curve: Curve,
+}
+
+impl MontgomeryReduction {
+    // (u, v) ↦ ((u + A/3)/B, v/B)
+    pub fn map_to_short(&self, coords: Coords) -> Coords {
+        let (u, v) = coords?;
+        let Montgomery { a, b, p } = self.montgomery;
+        let m = |x: i64, y: i64| mul(x, y, p);
+        let inv = |v: i64| field::mod_inverse(v, p);
+        let inv_b = inv(b);
+        Some((m(u + m(a, inv(3)), inv_b), m(v, inv_b)))
+    }
+
+    // u = Bx − A/3，v = By
+    pub fn map_to_montgomery(&self, coords: Coords) -> Coords {
+        let (x, y) = coords?;
+        let Montgomery { a, b, p } = self.montgomery;
+        let m = |x: i64, y: i64| mul(x, y, p);
+        let u = (m(b, x) - m(a, field::mod_inverse(3, p))).rem_euclid(p);
+        Some((u, m(b, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    fn montgomery_points(curve: &Montgomery) -> Vec<(i64, i64)> {
+        (0..curve.p)
+            .flat_map(|u| (0..curve.p).map(move |v| (u, v)))
+            .filter(|&(u, v)| curve.contains(u, v))
+            .collect()
+    }
+
+    #[test]
+    fn points_map_back_and_forth() {
+        for (a, b, p) in [(3, 1, 101), (6, 5, 97), (486662, 1, 599)] {
+            let montgomery = Montgomery::new(a, b, p);
+            let reduction = montgomery.reduce().unwrap();
+            let short: Vec<Coords> = reduction.curve.points().iter().map(Point::coords).collect();
+            let points = montgomery_points(&montgomery);
+            assert_eq!(points.len(), short.len(), "{montgomery}");
+            // (0, 0) 总在曲线上，是 2 阶点，所以 #E 是偶数
+            assert!(points.contains(&(0, 0)));
+            for (u, v) in points {
+                let image = reduction.map_to_short(Some((u, v)));
+                assert!(short.contains(&image), "{montgomery}: ({u}, {v})");
+                assert_eq!(reduction.map_to_montgomery(image), Some((u, v)));
+            }
+        }
+    }
+
+    #[test]
+    fn ladder_matches_full_scalar_multiplication() {
+        let montgomery = Montgomery::new(3, 1, 101);
+        let reduction = montgomery.reduce().unwrap();
+        let curve = reduction.curve;
+        for (u, v) in montgomery_points(&montgomery) {
+            let point = reduction.map_to_short(Some((u, v)));
+            for k in 0..40 {
+                let expected = reduction
+                    .map_to_montgomery(curve.mul_coords(point, k))
+                    .map(|(u, _)| u);
+                assert_eq!(montgomery.ladder(k, u), expected, "{k}·({u}, {v})");
+            }
+        }
+    }
+
+    #[test]
+    fn singular_curves_are_rejected() {
+        assert!(Montgomery::new(2, 1, 101).reduce().is_err());
+        assert!(Montgomery::new(-2, 1, 101).reduce().is_err());
+        assert!(Montgomery::new(3, 0, 101).reduce().is_err());
+        assert!(Montgomery::new(3, 1, 3).reduce().is_err());
+    }
+}
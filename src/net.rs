@@ -0,0 +1,193 @@
+// 椭圆网（Stange）：点 P、Q 对应的秩 2 网 W(a, b)，W(a, b) = 0 当且仅当 aP + bQ = O，
+// 对任意 u、v、w ∈ Z² 满足 W(u+v)W(u−v)W(w)² = W(u+w)W(u−w)W(v)² − W(v+w)W(v−w)W(u)²。
+// 第 0 行 W(k, 0) 就是 P 的除法多项式序列；第 1 行和第 −1 行交替递推：
+//   W(k+1, 1)W(k−1, −1) = W(k+1, 0)W(k−1, 0) − W(2, 1)W(k, 0)²
+//   W(k+1, −1)W(k−1, 1) = W(k+1, 0)W(k−1, 0)W(1, −1)² + W(2, −1)W(k, 0)²
+// nP = O 时 Tate 配对 t_n(P, Q) = W(n+1, 1)W(1, 0) / (W(n+1, 0)W(1, 1))，不需要 Miller 算法里的辅助点。
+use crate::curve::{Coords, Curve};
+use crate::division;
+use crate::field::{self, mul};
+use crate::pairing;
+
+pub struct EllipticNet {
+    // 第 b = −1、0、1 行，下标 a = 0..=len
+    rows: [Vec<i64>; 3],
+}
+
+impl EllipticNet {
+    pub fn new(curve: &Curve, p: Coords, q: Coords, len: usize) -> Result<Self, String> {
+        let (Some(p), Some(q)) = (p, q) else {
+            return Err("P and Q must be affine points".to_string());
+        };
+        let modulus = curve.p;
+        let m = |a: i64, b: i64| mul(a, b, modulus);
+        let ((x1, y1), (x2, y2)) = (p, q);
+        if x1 == x2 {
+            return Err(format!("Q = {q:?} is ±P"));
+        }
+        let row0 = division::sequence(curve, p, len.max(4)).map_err(|e| e.to_string())?;
+
+        // 秩 2 的初始值
+        let lambda = m(y2 - y1, field::mod_inverse(x2 - x1, modulus));
+        let w21 = (2 * x1 + x2 - m(lambda, lambda)).rem_euclid(modulus);
+        let w1m1 = (x1 - x2).rem_euclid(modulus);
+        let w2m1 = (m(y1 + y2, y1 + y2) - m(2 * x1 + x2, m(x1 - x2, x1 - x2))).rem_euclid(modulus);
+
+        let mut row1 = vec![1, 1];
+        let mut row_m1 = vec![modulus - 1, w1m1];
+        for k in 1..len {
+            let base = m(row0[k + 1], row0[k - 1]);
+            let square = m(row0[k], row0[k]);
+            let divide = |value: i64, by: i64| {
+                field::try_mod_inverse(by, modulus)
+                    .map(|inv| m(value.rem_euclid(modulus), inv))
+                    .map_err(|_| format!("Q = {q:?} is in the subgroup generated by P"))
+            };
+            let up = divide(base - m(w21, square), row_m1[k - 1])?;
+            let down = divide(m(base, m(w1m1, w1m1)) + m(w2m1, square), row1[k - 1])?;
+            row1.push(up);
+            row_m1.push(down);
+        }
+        let mut rows = [row_m1, row0, row1];
+        for row in &mut rows {
+            row.truncate(len + 1);
+        }
+        Ok(EllipticNet { rows })
+    }
+
+    // 网的列数减一
+    pub fn len(&self) -> usize {
+        self.rows[1].len() - 1
+    }
+
+    // W(a, b)，b 只能是 −1、0、1
+    pub fn get(&self, a: usize, b: i64) -> Option<i64> {
+        let row = usize::try_from(b + 1).ok()?;
+        self.rows.get(row)?.get(a).copied()
+    }
+}
+
+// 用椭圆网算 t_n(P, Q)，W(1, 0) = W(1, 1) = 1
+pub fn tate(curve: &Curve, n: u64, p: Coords, q: Coords) -> Result<(i64, EllipticNet), String> {
+    pairing::check_degree(curve, n)?;
+    if curve.mul_coords(p, n).is_some() {
+        return Err(format!("{p:?} is not an {n}-torsion point"));
+    }
+    let _span = tracing::debug_span!("tate_net", n, ?p, ?q).entered();
+    let net = EllipticNet::new(curve, p, q, n as usize + 1)?;
+    let top = n as usize + 1;
+    let (w1, w0) = (net.get(top, 1), net.get(top, 0));
+    let (Some(w1), Some(w0)) = (w1, w0) else {
+        unreachable!("the net has n + 2 columns");
+    };
+    let ratio = mul(w1, field::mod_inverse(w0, curve.p), curve.p);
+    Ok((pairing::final_exponentiation(curve, n, ratio), net))
+}
+
+// 界面用：从一个点出发找出能算配对的 P、Q 和 n
+pub struct Demo {
+    pub n: u64,
+    pub p: Coords,
+    pub q: Coords,
+    pub net: EllipticNet,
+    pub via_net: i64,
+    pub via_miller: i64,
+}
+
+impl Demo {
+    // n 取 ord(P) 和 p − 1 的最大公因数，P 换成 (ord(P) / n)·P；Q 取第一个不在 <P> 里的点
+    pub fn run(curve: &Curve, point: Coords) -> Result<Self, String> {
+        let order = curve.order_of(point, curve.group_order());
+        let n = gcd(order, curve.p as u64 - 1);
+        if n < 2 {
+            return Err(format!(
+                "ord(P) = {order} shares no factor with p − 1 = {}: the pairing lives in an \
+                 extension field",
+                curve.p - 1
+            ));
+        }
+        let p = curve.mul_coords(point, order / n);
+        let multiples: Vec<Coords> = (0..n).map(|k| curve.mul_coords(p, k)).collect();
+        let q = (0..curve.p)
+            .flat_map(|x| curve.points_at(x))
+            .map(|point| point.coords())
+            .find(|c| !multiples.contains(c))
+            .ok_or("every point is a multiple of P")?;
+        let (via_net, net) = tate(curve, n, p, q)?;
+        let via_miller = pairing::tate(curve, n, p, q)?;
+        tracing::debug!(n, via_net, via_miller, "computed the Tate pairing");
+        Ok(Demo {
+            n,
+            p,
+            q,
+            net,
+            via_net,
+            via_miller,
+        })
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zeros_of_the_net_mark_the_relations_between_p_and_q() {
+        let curve = Curve::new(0, 4, 61);
+        let points: Vec<Coords> = curve.points().iter().map(|p| p.coords()).collect();
+        let p = points[0];
+        let multiples: Vec<Coords> = (0..20).map(|k| curve.mul_coords(p, k)).collect();
+        let q = *points.iter().find(|q| !multiples.contains(q)).unwrap();
+        let net = EllipticNet::new(&curve, p, q, 20).unwrap();
+        for a in 0..=20 {
+            let zero = curve.mul_coords(p, a as u64).is_none();
+            assert_eq!(net.get(a, 0) == Some(0), zero, "a = {a}");
+            // Q 不在 <P> 里，aP ± Q 永远不是 O
+            assert_ne!(net.get(a, 1), Some(0));
+            assert_ne!(net.get(a, -1), Some(0));
+        }
+        assert_eq!(net.len(), 20);
+        assert_eq!(net.get(0, 2), None);
+        let multiple = curve.mul_coords(p, 3);
+        assert!(EllipticNet::new(&curve, p, multiple, 20).is_err());
+    }
+
+    #[test]
+    fn the_net_agrees_with_miller() {
+        // y² = x³ + 4 和 y² = x³ + 6x 都在 F_61 上有完整的 5 阶挠群
+        for curve in [Curve::new(0, 4, 61), Curve::new(6, 0, 61)] {
+            let points: Vec<Coords> = curve.points().iter().map(|p| p.coords()).collect();
+            let torsion: Vec<Coords> = points
+                .iter()
+                .copied()
+                .filter(|&c| curve.mul_coords(c, 5).is_none())
+                .collect();
+            let mut values = std::collections::BTreeSet::new();
+            for &p in &torsion {
+                let multiples: Vec<Coords> = (0..5).map(|k| curve.mul_coords(p, k)).collect();
+                // Q 不必是挠点
+                for &q in points.iter().filter(|q| !multiples.contains(q)).take(12) {
+                    let (via_net, _) = tate(&curve, 5, p, q).unwrap();
+                    assert_eq!(via_net, pairing::tate(&curve, 5, p, q).unwrap());
+                    values.insert(via_net);
+                }
+            }
+            // 取遍所有 5 次单位根
+            assert_eq!(values.len(), 5, "{curve:?}");
+        }
+    }
+
+    #[test]
+    fn demo_picks_a_torsion_point_and_a_partner() {
+        let curve = Curve::new(0, 4, 61);
+        let point = curve.first_point().unwrap().coords();
+        let demo = Demo::run(&curve, point).unwrap();
+        assert!(60 % demo.n == 0 && demo.n > 1);
+        assert_eq!(demo.via_net, demo.via_miller);
+        assert!(Demo::run(&Curve::new(1, 1, 599), Some((0, 1))).is_err());
+    }
+}
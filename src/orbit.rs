@@ -0,0 +1,99 @@
+use crate::curve::Curve;
+use crate::error::EcError;
+use crate::point::Point;
+use eframe::egui::Color32;
+
+// 轨迹模式：连续倍数 P, 2P, 3P, ..., kP 或者倍点序列 P, 2P, 4P, ..., 2^k P
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trajectory {
+    Multiples,
+    Doublings,
+}
+
+impl Trajectory {
+    // 倍点模式下 2^k 很快就没有意义，限制在 u64 范围内
+    pub fn max_k(&self) -> u64 {
+        match self {
+            Trajectory::Multiples => 1000,
+            Trajectory::Doublings => 63,
+        }
+    }
+}
+
+// 依次分配给新加入的基点
+const PALETTE: [Color32; 6] = [
+    Color32::BLUE,
+    Color32::from_rgb(0, 150, 0),
+    Color32::from_rgb(230, 120, 0),
+    Color32::from_rgb(150, 0, 200),
+    Color32::from_rgb(0, 160, 160),
+    Color32::from_rgb(200, 0, 120),
+];
+
+// 一个被追踪的基点及其轨迹
+#[derive(Debug, Clone)]
+pub struct TrackedPoint {
+    pub base: Point,
+    pub color: Color32,
+    pub trajectory: Trajectory,
+    pub k: u64,
+    pub steps: Vec<(String, Point)>,
+    pub failure: Option<EcError>,
+}
+
+impl TrackedPoint {
+    pub fn new(curve: &Curve, base: Point, index: usize) -> Self {
+        let mut tracked = TrackedPoint {
+            base,
+            color: PALETTE[index % PALETTE.len()],
+            trajectory: Trajectory::Multiples,
+            k: 20,
+            steps: vec![],
+            failure: None,
+        };
+        tracked.recompute(curve);
+        tracked
+    }
+
+    // 模数为合数时某一步可能遇到不可逆的分母，轨迹在那里停下并记录错误
+    pub fn recompute(&mut self, curve: &Curve) {
+        self.k = self.k.clamp(1, self.trajectory.max_k());
+        self.failure = None;
+        let g = self.base.coords();
+        let mut point = g;
+        self.steps = vec![("P".to_string(), Point::from_coords(curve, g))];
+        let (first, label): (u64, fn(u64) -> String) = match self.trajectory {
+            Trajectory::Multiples => (2, |i| format!("{i}P")),
+            Trajectory::Doublings => (1, |i| format!("2^{i}P")),
+        };
+        for i in first..=self.k {
+            let next = match self.trajectory {
+                Trajectory::Multiples => curve.try_add_coords(point, g),
+                Trajectory::Doublings => curve.try_add_coords(point, point),
+            };
+            match next {
+                Ok(next) => {
+                    point = next;
+                    self.steps
+                        .push((label(i), Point::from_coords(curve, point)));
+                }
+                Err(error) => {
+                    tracing::warn!(step = i, %error, "trajectory stopped");
+                    self.failure = Some(error);
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let (last, point) = self.steps.last().expect("trajectory is never empty");
+        if let Some(error) = &self.failure {
+            return format!("stopped after {last} = {point}: {error}");
+        }
+        match self.trajectory {
+            Trajectory::Multiples => format!("{} * P = {point}", self.k),
+            Trajectory::Doublings => format!("2^{} * P = {point}", self.k),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// NIST P-256 上的 ECDSA（ES256）：步骤和 ecdsa.rs 在玩具群上的一样，只是 z 取整个 SHA-256 摘要，
+// r、s 是模 n 的 256 位整数。曲线运算用 bigcurve，模 n 的运算用同一个 Montgomery 域。
+// nonce 由调用方给出，演示里用 RFC 6979 由私钥和消息确定。
+use crate::bigcurve::{BigCurve, BigPoint, FieldBackend, PrimeField};
+use crate::rfc6979::Rfc6979;
+use crate::sha256::sha256;
+use crate::u256::{self, Limbs};
+
+// 小端 64 位肢
+const P: Limbs = [
+    0xffffffffffffffff,
+    0x00000000ffffffff,
+    0x0000000000000000,
+    0xffffffff00000001,
+];
+const B: Limbs = [
+    0x3bce3c3e27d2604b,
+    0x651d06b0cc53b0f6,
+    0xb3ebbd55769886bc,
+    0x5ac635d8aa3a93e7,
+];
+// G 的阶
+const N: Limbs = [
+    0xf3b9cac2fc632551,
+    0xbce6faada7179e84,
+    0xffffffffffffffff,
+    0xffffffff00000000,
+];
+const GX: Limbs = [
+    0xf4a13945d898c296,
+    0x77037d812deb33a0,
+    0xf8bce6e563a440f2,
+    0x6b17d1f2e12c4247,
+];
+const GY: Limbs = [
+    0xcbb6406837bf51f5,
+    0x2bce33576b315ece,
+    0x8ee7eb4a7c0f9e16,
+    0x4fe342e2fe1a7f9b,
+];
+
+pub struct P256 {
+    pub curve: BigCurve,
+    pub g: BigPoint,
+    // Z_n
+    scalars: PrimeField,
+}
+
+// r、s 都在 [1, n) 里
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    pub r: Limbs,
+    pub s: Limbs,
+}
+
+impl P256 {
+    pub fn new() -> Self {
+        // a = −3
+        let a = u256::sub_limbs(&P, &[3, 0, 0, 0]).0;
+        let curve = BigCurve::new(&a, &B, &P).expect("P-256 parameters are valid");
+        P256 {
+            g: curve.point(&GX, &GY).expect("G is on P-256"),
+            curve,
+            scalars: PrimeField::new(&N),
+        }
+    }
+
+    fn in_range(&self, value: &Limbs) -> bool {
+        !u256::is_zero(value) && !u256::at_least(value, &N)
+    }
+
+    // z = SHA-256(m)，n 也是 256 位，不需要截断，模 n 即可
+    pub fn hash_message(&self, message: &[u8]) -> Limbs {
+        let z = u256::from_be_bytes(&sha256(message));
+        self.scalars.value(self.scalars.elem(&z))
+    }
+
+    // RFC 6979 的 nonce 序列，sign 返回 None 时取下一个
+    pub fn nonces(&self, d: &Limbs, message: &[u8]) -> Rfc6979 {
+        Rfc6979::new(&N, d, &sha256(message))
+    }
+
+    // R = k·G，r = x(R) mod n，s = k⁻¹(z + r·d) mod n；r 或 s 为 0 时返回 None
+    pub fn sign(&self, d: &Limbs, message: &[u8], k: &Limbs) -> Option<Signature> {
+        if !self.in_range(d) || !self.in_range(k) {
+            return None;
+        }
+        let f = &self.scalars;
+        let (x, _) = self.curve.affine(&self.curve.mul(&self.g, k))?;
+        let r = f.elem(&x);
+        let z = f.elem(&self.hash_message(message));
+        let s = f.mul(f.invert(f.elem(k)), f.add(z, f.mul(r, f.elem(d))));
+        let signature = Signature {
+            r: f.value(r),
+            s: f.value(s),
+        };
+        (self.in_range(&signature.r) && self.in_range(&signature.s)).then_some(signature)
+    }
+
+    // u1 = z·s⁻¹，u2 = r·s⁻¹，检查 x(u1·G + u2·Q) mod n == r
+    pub fn verify(&self, public: (Limbs, Limbs), message: &[u8], signature: &Signature) -> bool {
+        let Ok(q) = self.curve.point(&public.0, &public.1) else {
+            return false;
+        };
+        if !self.in_range(&signature.r) || !self.in_range(&signature.s) {
+            return false;
+        }
+        let f = &self.scalars;
+        let w = f.invert(f.elem(&signature.s));
+        let z = f.elem(&self.hash_message(message));
+        let u1 = f.value(f.mul(z, w));
+        let u2 = f.value(f.mul(f.elem(&signature.r), w));
+        let sum = self
+            .curve
+            .add(&self.curve.mul(&self.g, &u1), &self.curve.mul(&q, &u2));
+        self.curve
+            .affine(&sum)
+            .is_some_and(|(x, _)| f.value(f.elem(&x)) == signature.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presets;
+
+    fn hex(text: &str) -> Limbs {
+        u256::parse(&format!("0x{text}")).unwrap()
+    }
+
+    // RFC 6979 A.2.5，消息 "sample"，SHA-256，用文中给出的 k
+    #[test]
+    fn rfc6979_sample_signature() {
+        let p256 = P256::new();
+        let d = hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721");
+        let public = presets::KeyPair::from_secret(&presets::p256(), d)
+            .unwrap()
+            .public;
+        assert_eq!(
+            public,
+            (
+                hex("60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6"),
+                hex("7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"),
+            )
+        );
+        let k = hex("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60");
+        assert_eq!(p256.nonces(&d, b"sample").next_nonce(), k);
+        let signature = p256.sign(&d, b"sample", &k).unwrap();
+        assert_eq!(
+            signature,
+            Signature {
+                r: hex("efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716"),
+                s: hex("f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"),
+            }
+        );
+        assert!(p256.verify(public, b"sample", &signature));
+        assert!(!p256.verify(public, b"test", &signature));
+        let tampered = Signature {
+            s: u256::add_limbs(&signature.s, &[1, 0, 0, 0]).0,
+            ..signature
+        };
+        assert!(!p256.verify(public, b"sample", &tampered));
+        assert_eq!(p256.sign(&d, b"sample", &N), None);
+    }
+}
@@ -0,0 +1,144 @@
+// 嵌入度为 1 的约化 Tate 配对：n | p − 1，P ∈ E(F_p)[n]，Q ∈ E(F_p)。
+// t_n(P, Q) = f_{n,P}(D_Q)^((p−1)/n)，其中 div(f_{n,P}) = n(P) − n(O)，D_Q = (Q + S) − (S)。
+// Miller 算法沿 n 的二进制位累乘直线函数得到 f_{n,P}；结果是 F_p 里的 n 次单位根。
+use crate::curve::{Coords, Curve};
+use crate::field::{self, mul};
+
+// 配对的值域是 F_p* 中的 n 次单位根，需要 n | p − 1
+pub fn check_degree(curve: &Curve, n: u64) -> Result<(), String> {
+    if n < 2 || !(curve.p as u64 - 1).is_multiple_of(n) {
+        return Err(format!(
+            "n = {n} does not divide p − 1 = {}: the embedding degree is larger than 1",
+            curve.p - 1
+        ));
+    }
+    Ok(())
+}
+
+// 最终幂 f^((p−1)/n)，把结果约化成 n 次单位根
+pub fn final_exponentiation(curve: &Curve, n: u64, f: i64) -> i64 {
+    let p = curve.p as u64;
+    field::pow_mod(f.rem_euclid(curve.p) as u64, (p - 1) / n, p) as i64
+}
+
+// 过 T、U 的直线除以过 T + U 的竖线，在 R 处的值。R 落在零点或极点上时为 None
+fn line(curve: &Curve, t: (i64, i64), u: (i64, i64), r: (i64, i64)) -> Option<i64> {
+    let p = curve.p;
+    let ((x1, y1), (x2, y2), (xr, yr)) = (t, u, r);
+    let vertical = (xr - x1).rem_euclid(p);
+    let lambda = if t == u {
+        if y1 == 0 {
+            return (vertical != 0).then_some(vertical);
+        }
+        mul(
+            3 * mul(x1, x1, p) + curve.a,
+            field::mod_inverse(2 * y1, p),
+            p,
+        )
+    } else if x1 == x2 {
+        return (vertical != 0).then_some(vertical);
+    } else {
+        mul(y2 - y1, field::mod_inverse(x2 - x1, p), p)
+    };
+    let numerator = (yr - y1 - mul(lambda, xr - x1, p)).rem_euclid(p);
+    let (x3, _) = curve.add_coords(Some(t), Some(u))?;
+    let denominator = (xr - x3).rem_euclid(p);
+    if numerator == 0 || denominator == 0 {
+        return None;
+    }
+    Some(mul(numerator, field::mod_inverse(denominator, p), p))
+}
+
+// f_{n,P}(R)
+pub fn miller(curve: &Curve, n: u64, point: (i64, i64), r: (i64, i64)) -> Option<i64> {
+    let p = curve.p;
+    let mut f = 1;
+    let mut t = point;
+    for bit in (0..u64::BITS - 1 - n.leading_zeros()).rev() {
+        // 累加器只在最后一步变成 O
+        f = mul(mul(f, f, p), line(curve, t, t, r)?, p);
+        match curve.add_coords(Some(t), Some(t)) {
+            Some(next) => t = next,
+            None => break,
+        }
+        if (n >> bit) & 1 == 1 {
+            f = mul(f, line(curve, t, point, r)?, p);
+            match curve.add_coords(Some(t), Some(point)) {
+                Some(next) => t = next,
+                None => break,
+            }
+        }
+    }
+    Some(f)
+}
+
+// S 取曲线上第一个让 f 在 Q + S 和 S 处都有定义且不为零的点
+pub fn tate(curve: &Curve, n: u64, point: Coords, q: Coords) -> Result<i64, String> {
+    check_degree(curve, n)?;
+    let (Some(base), Some(_)) = (point, q) else {
+        return Err("P and Q must be affine points".to_string());
+    };
+    if curve.mul_coords(point, n).is_some() {
+        return Err(format!("{point:?} is not an {n}-torsion point"));
+    }
+    let _span = tracing::debug_span!("tate_miller", n, ?point, ?q).entered();
+    let p = curve.p;
+    for s in (0..p).flat_map(|x| curve.points_at(x)) {
+        let s = s.coords();
+        let (Some(shifted), Some(s)) = (curve.add_coords(q, s), s) else {
+            continue;
+        };
+        let (Some(num), Some(den)) = (miller(curve, n, base, shifted), miller(curve, n, base, s))
+        else {
+            continue;
+        };
+        let f = mul(num, field::mod_inverse(den, p), p);
+        return Ok(final_exponentiation(curve, n, f));
+    }
+    Err("no auxiliary point S avoids the zeros and poles of f".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // y² = x³ + 4 over F_61，E ≅ Z_5 × Z_15，5 阶挠点全部在 F_61 上
+    fn torsion() -> (Curve, Vec<Coords>) {
+        let curve = Curve::new(0, 4, 61);
+        let points = curve
+            .points()
+            .iter()
+            .map(|point| point.coords())
+            .filter(|&c| curve.mul_coords(c, 5).is_none())
+            .collect();
+        (curve, points)
+    }
+
+    #[test]
+    fn the_pairing_is_bilinear_and_non_degenerate() {
+        let (curve, torsion) = torsion();
+        assert_eq!(torsion.len(), 24);
+        let p = torsion[0];
+        // 找一个配对不为 1 的 Q：5 阶挠群是 Z_5 × Z_5，这样的 Q 一定存在
+        let (q, e) = torsion
+            .iter()
+            .map(|&q| (q, tate(&curve, 5, p, q).unwrap()))
+            .find(|&(_, e)| e != 1)
+            .unwrap();
+        assert_eq!(field::pow_mod(e as u64, 5, 61), 1);
+        for k in 1..5u64 {
+            let kp = curve.mul_coords(p, k);
+            let kq = curve.mul_coords(q, k);
+            let ek = field::pow_mod(e as u64, k, 61) as i64;
+            assert_eq!(tate(&curve, 5, kp, q).unwrap(), ek);
+            assert_eq!(tate(&curve, 5, p, kq).unwrap(), ek);
+        }
+    }
+
+    #[test]
+    fn the_embedding_degree_must_be_one() {
+        let curve = Curve::new(1, 1, 599);
+        assert!(check_degree(&curve, 199).is_err());
+        assert!(check_degree(&curve, 23).is_ok());
+    }
+}
@@ -0,0 +1,218 @@
+// Pedersen 哈希：把消息切成 c 位一块，第 i 块 m_i 当作标量，H(m) = Σ (m_i + 1)·G_i。
+// 生成元 G_i 由 SHA-256 哈希到曲线得到，谁都不知道它们之间的离散对数；
+// 找到碰撞就等于找到了 Σ k_i G_i = O 的非平凡关系，也就是解出了离散对数。
+// 反过来，在玩具曲线上离散对数可以暴力求出，于是碰撞也能构造出来。
+use crate::curve::{Coords, Curve};
+use crate::fixed_base::FixedBase;
+use crate::hash_to_curve;
+use crate::subgroup;
+
+// 哈希到曲线的域分隔标签
+const DOMAIN: &[u8] = b"elliptic-curve pedersen generator";
+
+pub struct PedersenHash {
+    pub curve: Curve,
+    // 生成元所在子群的阶和余因子
+    pub q: u64,
+    cofactor: u64,
+    // 每块的位数，保证 m_i + 1 ∈ [1, q)，不同的块值不会模 q 相等
+    pub chunk_bits: u32,
+    // 按需生成，每个生成元带一张固定基点表
+    generators: Vec<FixedBase>,
+}
+
+// 构造出的碰撞，以及为此求出的离散对数 log_{G_0} G_1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collision {
+    pub log: u64,
+    // 暴力求对数试了多少次
+    pub steps: u64,
+    pub message: Vec<u8>,
+}
+
+impl PedersenHash {
+    pub fn new(curve: &Curve) -> Result<Self, String> {
+        let (q, cofactor) = subgroup::prime_order_subgroup(curve)?;
+        Ok(PedersenHash {
+            curve: *curve,
+            q,
+            cofactor,
+            chunk_bits: (q - 1).ilog2(),
+            generators: vec![],
+        })
+    }
+
+    // 第 i 个生成元：以 i 为消息试探-递增地哈希到曲线，再乘以余因子落到 q 阶子群里
+    fn hash_to_generator(&self, index: usize) -> Coords {
+        let generator =
+            hash_to_curve::candidates(&self.curve, DOMAIN, &(index as u64).to_be_bytes())
+                .find_map(|(counter, point)| {
+                    let coords = self.curve.mul_coords(Some(point), self.cofactor)?;
+                    tracing::trace!(index, counter, ?coords, "derived generator");
+                    Some(coords)
+                })
+                .expect("the counter does not run out before a point is found");
+        Some(generator)
+    }
+
+    pub fn generator(&mut self, index: usize) -> Coords {
+        while self.generators.len() <= index {
+            let base = self.hash_to_generator(self.generators.len());
+            let bits = u64::BITS - self.q.leading_zeros();
+            self.generators
+                .push(FixedBase::new(&self.curve, base, bits));
+        }
+        self.generators[index].base()
+    }
+
+    // 按位从高到低切块，末尾补一个 1 位再补 0，不同长度的消息不会切出相同的块
+    pub fn chunks(&self, message: &[u8]) -> Vec<u64> {
+        let c = self.chunk_bits as usize;
+        let mut bits: Vec<bool> = message
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+            .collect();
+        bits.push(true);
+        while !bits.len().is_multiple_of(c) {
+            bits.push(false);
+        }
+        bits.chunks(c)
+            .map(|chunk| chunk.iter().fold(0, |acc, &bit| (acc << 1) | bit as u64))
+            .collect()
+    }
+
+    // chunks 的逆运算，块不是合法的补位结果时返回 None
+    pub fn message(&self, chunks: &[u64]) -> Option<Vec<u8>> {
+        let c = self.chunk_bits;
+        let mut bits: Vec<bool> = chunks
+            .iter()
+            .flat_map(|&chunk| (0..c).rev().map(move |i| (chunk >> i) & 1 == 1))
+            .collect();
+        let end = bits.iter().rposition(|&bit| bit)?;
+        bits.truncate(end);
+        if !bits.len().is_multiple_of(8) || chunks.iter().any(|&chunk| chunk >> c != 0) {
+            return None;
+        }
+        Some(
+            bits.chunks(8)
+                .map(|byte| byte.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8))
+                .collect(),
+        )
+    }
+
+    pub fn hash_chunks(&mut self, chunks: &[u64]) -> Coords {
+        let mut result = None;
+        for (i, &chunk) in chunks.iter().enumerate() {
+            self.generator(i);
+            let term = self.generators[i].mul(chunk + 1);
+            result = self.curve.add_coords(result, term);
+        }
+        result
+    }
+
+    pub fn hash(&mut self, message: &[u8]) -> Coords {
+        let chunks = self.chunks(message);
+        self.hash_chunks(&chunks)
+    }
+
+    // 暴力求 d = log_{G_0} G_1，然后改动前两块：
+    // (m_0 + 1) G_0 + (m_1 + 1) G_1 = (m_0' + 1) G_0 + (m_1' + 1) G_1
+    // 当且仅当 m_0' ≡ m_0 + d (m_1 − m_1') (mod q)。要求前两块都是消息本身的位
+    pub fn find_collision(&mut self, message: &[u8]) -> Option<Collision> {
+        let _span = tracing::debug_span!("pedersen_collision", q = self.q).entered();
+        let mut chunks = self.chunks(message);
+        if message.len() * 8 < 2 * self.chunk_bits as usize {
+            return None;
+        }
+        let (g0, g1) = (self.generator(0), self.generator(1));
+        let mut acc = None;
+        let mut log = None;
+        for d in 0..self.q {
+            if acc == g1 {
+                log = Some(d);
+                break;
+            }
+            acc = self.curve.add_coords(acc, g0);
+        }
+        let log = log?;
+        let steps = log + 1;
+        tracing::debug!(log, steps, "solved discrete log between generators");
+
+        let limit = 1u64 << self.chunk_bits;
+        let (m0, m1) = (chunks[0] as i128, chunks[1] as i128);
+        let first = |m1_new: u64| {
+            (m0 + log as i128 * (m1 - m1_new as i128)).rem_euclid(self.q as i128) as u64
+        };
+        let m1_new = (0..limit).find(|&m1_new| m1_new != chunks[1] && first(m1_new) < limit)?;
+        chunks[0] = first(m1_new);
+        chunks[1] = m1_new;
+        Some(Collision {
+            log,
+            steps,
+            message: self.message(&chunks)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hasher() -> PedersenHash {
+        PedersenHash::new(&Curve::new(1, 1, 599)).unwrap()
+    }
+
+    #[test]
+    fn chunks_round_trip_and_pad_unambiguously() {
+        let hasher = hasher();
+        assert_eq!((hasher.q, hasher.chunk_bits), (199, 7));
+        for message in [&b""[..], b"a", b"a\0", b"hello, world"] {
+            let chunks = hasher.chunks(message);
+            assert!(chunks.iter().all(|&chunk| chunk < 1 << 7));
+            assert_eq!(hasher.message(&chunks).as_deref(), Some(message));
+        }
+        assert_ne!(hasher.chunks(b"a"), hasher.chunks(b"a\0"));
+    }
+
+    #[test]
+    fn generators_are_deterministic_and_in_the_subgroup() {
+        let mut first = hasher();
+        let mut second = hasher();
+        let curve = first.curve;
+        for i in 0..8 {
+            let g = first.generator(i);
+            assert!(g.is_some());
+            assert_eq!(g, second.generator(i));
+            assert_eq!(curve.mul_coords(g, first.q), None);
+        }
+        assert_ne!(first.generator(0), first.generator(1));
+    }
+
+    #[test]
+    fn hash_is_the_sum_of_chunk_multiples() {
+        let mut hasher = hasher();
+        let curve = hasher.curve;
+        let chunks = hasher.chunks(b"pedersen");
+        let expected = chunks.iter().enumerate().fold(None, |acc, (i, &m)| {
+            curve.add_coords(acc, curve.mul_coords(hasher.generator(i), m + 1))
+        });
+        assert_eq!(hasher.hash(b"pedersen"), expected);
+        assert_ne!(hasher.hash(b"a"), hasher.hash(b"a\0"));
+    }
+
+    #[test]
+    fn solving_the_discrete_log_yields_a_collision() {
+        let mut hasher = hasher();
+        let message = b"toy curves are weak".to_vec();
+        let collision = hasher.find_collision(&message).expect("q = 199 is tiny");
+        assert_ne!(collision.message, message);
+        assert_eq!(hasher.hash(&collision.message), hasher.hash(&message));
+        let curve = hasher.curve;
+        assert_eq!(
+            curve.mul_coords(hasher.generator(0), collision.log),
+            hasher.generator(1)
+        );
+        // 太短的消息前两块里有补位
+        assert_eq!(hasher.find_collision(b"a"), None);
+    }
+}
@@ -0,0 +1,156 @@
+// Pohlig–Hellman：G 的阶 n = Π q^e 时，k·G = Q 可以拆成每个 q^e 上的小问题。
+// k mod q^e 按 q 进制一位一位求：γ = (n/q)·G 的阶是 q，
+// 第 j 位 d_j 满足 d_j·γ = (n/q^(j+1))·(Q − x·G)，x 是已经求出的低位，用 BSGS 在 q 个元素里找。
+// 最后用中国剩余定理把各个 k mod q^e 拼起来。代价取决于 n 最大的素因子而不是 n，
+// 所以 n 光滑（只有小素因子）的曲线上离散对数并不难。
+use crate::curve::{Coords, Curve};
+use crate::dlog::Bsgs;
+use crate::field;
+
+// 一位 q 进制数字：在 <γ> 里求 d·γ = h
+#[derive(Debug, Clone, PartialEq)]
+pub struct Digit {
+    pub h: Coords,
+    pub digit: Option<u64>,
+    // 这次 BSGS 的 m = ⌈√q⌉
+    pub steps: u64,
+}
+
+// n 的一个素数幂因子 q^e 和在它上面求出的 k mod q^e
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub prime: u64,
+    pub exponent: u32,
+    // γ = (n/q)·G
+    pub gamma: Coords,
+    pub digits: Vec<Digit>,
+    pub residue: Option<u64>,
+}
+
+impl Stage {
+    pub fn modulus(&self) -> u64 {
+        self.prime.pow(self.exponent)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PohligHellman {
+    pub order: u64,
+    pub stages: Vec<Stage>,
+    // 各个余数拼起来并且验证过 k·G = Q；Q 不在 <G> 里时为 None
+    pub log: Option<u64>,
+}
+
+impl PohligHellman {
+    // order 是 G 的阶
+    pub fn run(curve: &Curve, g: Coords, q: Coords, order: u64) -> Self {
+        let _span = tracing::debug_span!("pohlig_hellman", order).entered();
+        let stages: Vec<Stage> = field::factorize(order)
+            .into_iter()
+            .map(|(prime, exponent)| stage(curve, g, q, order, prime, exponent))
+            .collect();
+        let log = stages
+            .iter()
+            .try_fold((0, 1), |(k, m), stage| {
+                Some(crt(k, m, stage.residue?, stage.modulus()))
+            })
+            .map(|(k, _)| k)
+            .filter(|&k| curve.mul_coords(g, k) == q);
+        tracing::debug!(stages = stages.len(), ?log, "pohlig-hellman");
+        PohligHellman { order, stages, log }
+    }
+
+    // 所有 BSGS 的步数之和，对比直接在 n 上做 BSGS 的 ⌈√n⌉
+    pub fn steps(&self) -> u64 {
+        self.stages
+            .iter()
+            .flat_map(|stage| &stage.digits)
+            .map(|digit| digit.steps)
+            .sum()
+    }
+}
+
+fn stage(curve: &Curve, g: Coords, q: Coords, n: u64, prime: u64, exponent: u32) -> Stage {
+    let gamma = curve.mul_coords(g, n / prime);
+    let mut digits = Vec::new();
+    // x = d_0 + d_1·q + ...，power = q^j
+    let (mut x, mut power) = (0, 1);
+    for _ in 0..exponent {
+        let rest = curve.add_coords(q, curve.neg_coords(curve.mul_coords(g, x)));
+        let h = curve.mul_coords(rest, n / (power * prime));
+        let bsgs = Bsgs::run(curve, gamma, h, prime);
+        digits.push(Digit {
+            h,
+            digit: bsgs.log,
+            steps: bsgs.m,
+        });
+        let Some(digit) = bsgs.log else {
+            break;
+        };
+        x += digit * power;
+        power *= prime;
+    }
+    let residue = (digits.len() == exponent as usize && digits.iter().all(|d| d.digit.is_some()))
+        .then_some(x);
+    Stage {
+        prime,
+        exponent,
+        gamma,
+        digits,
+        residue,
+    }
+}
+
+// x ≡ a (mod m)，x ≡ b (mod r)，m 与 r 互素 ⇒ x = a + m·((b − a)·m⁻¹ mod r)，模 m·r
+fn crt(a: u64, m: u64, b: u64, r: u64) -> (u64, u64) {
+    let (m_i, r_i) = (m as i64, r as i64);
+    let lift = (b as i64 - a as i64).rem_euclid(r_i) as i128
+        * field::mod_inverse(m_i % r_i, r_i) as i128
+        % r as i128;
+    (a + m * lift as u64, m * r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovers_the_log_one_prime_power_at_a_time() {
+        // y² = x³ + 2x + 3 over F_97 是 Z_50 × Z_2，50 = 2 · 5²
+        let curve = Curve::new(2, 3, 97);
+        let g = curve
+            .points()
+            .iter()
+            .map(|point| point.coords())
+            .find(|&g| curve.order_of(g, 100) == 50)
+            .unwrap();
+        for k in 0..50 {
+            let attack = PohligHellman::run(&curve, g, curve.mul_coords(g, k), 50);
+            assert_eq!(attack.log, Some(k));
+            let residues: Vec<(u64, Option<u64>)> = attack
+                .stages
+                .iter()
+                .map(|stage| (stage.modulus(), stage.residue))
+                .collect();
+            assert_eq!(residues, [(2, Some(k % 2)), (25, Some(k % 25))]);
+            // 5² 的两位数字：k mod 25 = d_0 + 5·d_1
+            let digits: Vec<Option<u64>> =
+                attack.stages[1].digits.iter().map(|d| d.digit).collect();
+            assert_eq!(digits, [Some(k % 5), Some(k % 25 / 5)]);
+            // 2 + 3 + 3 步，直接 BSGS 要 ⌈√50⌉ = 8 步
+            assert_eq!(attack.steps(), 2 + 3 + 3);
+        }
+    }
+
+    #[test]
+    fn points_outside_the_subgroup_have_no_log() {
+        let curve = Curve::new(1, 1, 599);
+        let g = Some((1, 188));
+        // #E = 597 = 3 · 199：Q = 199·G 的阶是 3，不在 3·G 生成的 199 阶子群里
+        let q = curve.mul_coords(g, 199);
+        let attack = PohligHellman::run(&curve, curve.mul_coords(g, 3), q, 199);
+        assert_eq!(attack.log, None);
+        assert_eq!(attack.stages[0].residue, None);
+        assert_eq!(crt(1, 2, 3, 5), (3, 10));
+    }
+}
@@ -0,0 +1,712 @@
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::field;
+use crate::subgroup;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+// 椭圆曲线上的点：所属的曲线加上坐标，无穷远点的坐标是 None。
+// 曲线参数只在 Curve 里，不同曲线上的点比较时不相等、相加时报 MismatchedCurves；
+// 大量点共用一条曲线时用借用曲线的 AffinePoint<'c>
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    curve: Curve,
+    coords: Coords,
+}
+
+impl Point {
+    pub fn infinity(curve: &Curve) -> Self {
+        Point {
+            curve: *curve,
+            coords: None,
+        }
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.coords.is_none()
+    }
+
+    pub fn curve(&self) -> Curve {
+        self.curve
+    }
+
+    pub fn coords(&self) -> Coords {
+        self.coords
+    }
+
+    // 两个点必须在同一条曲线 (a, b, p) 上，并且满足曲线方程。加法公式用不到 b，
+    // 不检查的话曲线外的点会被当成另一条曲线 y² = x³ + ax + b' 上的点来算，见 invalid_curve.rs
+    pub fn try_add(&self, other: &Point) -> Result<Point, EcError> {
+        let curve = self.curve();
+        if curve != other.curve() {
+            return Err(EcError::MismatchedCurves {
+                left: curve,
+                right: other.curve(),
+            });
+        }
+        self.check_on_curve()?;
+        other.check_on_curve()?;
+        let sum = curve.try_add_coords(self.coords(), other.coords())?;
+        Ok(Point::from_coords(&curve, sum))
+    }
+
+    // 运算符 `*` 的可失败版本：点不在曲线上时返回 PointNotOnCurve（和 try_add 一样，
+    // 倍点公式用不到 b），模数为合数时返回 NonInvertible
+    pub fn try_mul(&self, k: u64) -> Result<Point, EcError> {
+        let curve = self.curve();
+        self.check_on_curve()?;
+        let product = curve.try_mul_coords(self.coords(), k)?;
+        Ok(Point::from_coords(&curve, product))
+    }
+
+    fn check_on_curve(&self) -> Result<(), EcError> {
+        match self.coords() {
+            Some((x, y)) if !self.curve.contains(x, y) => Err(EcError::PointNotOnCurve { x, y }),
+            _ => Ok(()),
+        }
+    }
+
+    // 点的阶。先数出整个群的阶（逐个 x 枚举），只适合小的 p
+    pub fn order(&self) -> u64 {
+        let curve = self.curve();
+        curve.order_of(self.coords(), curve.group_order())
+    }
+
+    // P 生成的循环子群 {O, P, 2P, ...}，元素个数就是 P 的阶。模数必须是素数
+    pub fn generate_subgroup(&self) -> Vec<Point> {
+        let curve = self.curve();
+        subgroup::cyclic_subgroup(&curve, self.coords())
+            .into_iter()
+            .map(|coords| Point::from_coords(&curve, coords))
+            .collect()
+    }
+
+    // 坐标规范到 [0, p)，Rust 的 % 是截断取余，负数要用 rem_euclid。
+    // 曲线外的坐标直接 panic，和运算符一样；用户输入走 Curve::try_point
+    pub fn from_coords(curve: &Curve, coords: Coords) -> Self {
+        if let Some((x, y)) = coords {
+            assert!(
+                curve.contains(x, y),
+                "{}",
+                EcError::PointNotOnCurve { x, y }
+            );
+        }
+        Point {
+            curve: *curve,
+            coords: coords.map(|(x, y)| (x.rem_euclid(curve.p), y.rem_euclid(curve.p))),
+        }
+    }
+
+    // SEC1 编码：无穷远点是 0x00，压缩形式 0x02/0x03 || x（前缀是 y 的奇偶），
+    // 不压缩形式 0x04 || x || y，坐标按大端写成定长
+    pub fn to_bytes(&self, compressed: bool) -> Vec<u8> {
+        let Some((x, y)) = self.coords() else {
+            return vec![0x00];
+        };
+        let len = field_len(self.curve.p);
+        let mut bytes = Vec::with_capacity(1 + 2 * len);
+        if compressed {
+            bytes.push(0x02 | (y & 1) as u8);
+            bytes.extend_from_slice(&(x as u64).to_be_bytes()[8 - len..]);
+        } else {
+            bytes.push(0x04);
+            bytes.extend_from_slice(&(x as u64).to_be_bytes()[8 - len..]);
+            bytes.extend_from_slice(&(y as u64).to_be_bytes()[8 - len..]);
+        }
+        bytes
+    }
+
+    // 解压缩要开平方，只支持素数模数
+    pub fn from_bytes(bytes: &[u8], curve: &Curve) -> Result<Point, EcError> {
+        let p = curve.p;
+        let len = field_len(p);
+        let element = |chunk: &[u8]| {
+            let value = chunk.iter().fold(0u64, |acc, &byte| acc << 8 | byte as u64);
+            i64::try_from(value)
+                .ok()
+                .filter(|&v| v < p)
+                .ok_or(EcError::InvalidEncoding("coordinate is not below p"))
+        };
+        match bytes {
+            [0x00] => Ok(Point::infinity(curve)),
+            [prefix @ (0x02 | 0x03), x @ ..] if x.len() == len => {
+                if !field::is_prime(p) {
+                    return Err(EcError::NotPrime(p));
+                }
+                let x = element(x)?;
+                let root = field::sqrt_mod(curve.rhs(x), p)
+                    .ok_or(EcError::InvalidEncoding("no point has this x"))?;
+                let odd = i64::from(prefix & 1);
+                if root == 0 && odd == 1 {
+                    return Err(EcError::InvalidEncoding("y = 0 cannot be odd"));
+                }
+                let y = if root & 1 == odd { root } else { p - root };
+                Ok(curve.point(x, y))
+            }
+            [0x04, xy @ ..] if xy.len() == 2 * len => {
+                let (x, y) = (element(&xy[..len])?, element(&xy[len..])?);
+                if !curve.contains(x, y) {
+                    return Err(EcError::PointNotOnCurve { x, y });
+                }
+                Ok(curve.point(x, y))
+            }
+            [0x00 | 0x02 | 0x03 | 0x04, ..] => Err(EcError::InvalidEncoding("wrong length")),
+            _ => Err(EcError::InvalidEncoding("unknown prefix")),
+        }
+    }
+}
+
+// "(x, y)"，无穷远点是 "Infinity"
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Coordinates(self.coords()).fmt(f)
+    }
+}
+
+// 不带曲线参数的坐标，和 Point 的 Display 写法相同。界面上只有 Coords 的地方
+// 用它显示，FromStr 是 Display 的逆：接受 "5,1"、"(5, 1)" 和 "Infinity"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coordinates(pub Coords);
+
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some((x, y)) => write!(f, "({x}, {y})"),
+            None => write!(f, "Infinity"),
+        }
+    }
+}
+
+impl FromStr for Coordinates {
+    type Err = EcError;
+
+    // 文本里没有曲线参数，不检查点在不在曲线上；要检查过的点用 Curve::parse_point
+    fn from_str(text: &str) -> Result<Self, EcError> {
+        let trimmed = text.trim();
+        if trimmed == "Infinity" {
+            return Ok(Coordinates(None));
+        }
+        let inner = trimmed
+            .strip_prefix('(')
+            .and_then(|rest| rest.strip_suffix(')'))
+            .unwrap_or(trimmed);
+        inner
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+            .map(|xy| Coordinates(Some(xy)))
+            .ok_or_else(|| EcError::InvalidPointSyntax(text.to_string()))
+    }
+}
+
+pub fn parse_coords(text: &str) -> Result<Coords, EcError> {
+    text.parse().map(|Coordinates(coords)| coords)
+}
+
+// 坐标的字节数 ⌈log₂ p / 8⌉，刚好放得下 p − 1
+fn field_len(p: i64) -> usize {
+    (u64::BITS - (p as u64 - 1).leading_zeros())
+        .div_ceil(8)
+        .max(1) as usize
+}
+
+impl Add<&Point> for &Point {
+    type Output = Point;
+
+    // 运算符无法返回错误，曲线不一致时直接 panic
+    fn add(self, other: &Point) -> Point {
+        self.try_add(other)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        &self + &other
+    }
+}
+
+impl Add<&Point> for Point {
+    type Output = Point;
+
+    fn add(self, other: &Point) -> Point {
+        &self + other
+    }
+}
+
+impl Add<Point> for &Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        self + &other
+    }
+}
+
+impl Neg for &Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        let curve = self.curve();
+        Point::from_coords(&curve, curve.neg_coords(self.coords()))
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        -&self
+    }
+}
+
+impl Sub<&Point> for &Point {
+    type Output = Point;
+
+    fn sub(self, other: &Point) -> Point {
+        self + &(-other)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        &self - &other
+    }
+}
+
+impl Sub<&Point> for Point {
+    type Output = Point;
+
+    fn sub(self, other: &Point) -> Point {
+        &self - other
+    }
+}
+
+impl Sub<Point> for &Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        self - &other
+    }
+}
+
+impl AddAssign<&Point> for Point {
+    fn add_assign(&mut self, other: &Point) {
+        *self = &*self + other;
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self += &other;
+    }
+}
+
+impl SubAssign<&Point> for Point {
+    fn sub_assign(&mut self, other: &Point) {
+        *self = &*self - other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self -= &other;
+    }
+}
+
+impl Mul<u64> for &Point {
+    type Output = Point;
+
+    // 和 Add 一样，曲线外的点直接 panic
+    fn mul(self, k: u64) -> Point {
+        if let Err(error) = self.check_on_curve() {
+            panic!("{error}");
+        }
+        let curve = self.curve();
+        Point::from_coords(&curve, curve.mul_coords(self.coords(), k))
+    }
+}
+
+impl Mul<u64> for Point {
+    type Output = Point;
+
+    fn mul(self, k: u64) -> Point {
+        &self * k
+    }
+}
+
+impl Mul<&Point> for u64 {
+    type Output = Point;
+
+    fn mul(self, point: &Point) -> Point {
+        point * self
+    }
+}
+
+impl Mul<Point> for u64 {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        &point * self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::affine::AffinePoint;
+
+    // 公开的构造函数都会拒绝曲线外的坐标，测试里直接写字段
+    fn off_curve(curve: &Curve, x: i64, y: i64) -> Point {
+        Point {
+            curve: *curve,
+            coords: Some((x, y)),
+        }
+    }
+
+    fn two_curves() -> (Point, Point) {
+        let p = Curve::new(1, 1, 599).point(1, 188);
+        let q = Curve::new(2, 3, 97).point(3, 6);
+        (p, q)
+    }
+
+    #[test]
+    fn points_normalize_coordinates() {
+        let curve = Curve::new(1, 1, 599);
+        assert_eq!(curve.point(-598, -411).coords(), Some((1, 188)));
+        assert_eq!(curve.point(600, 787).coords(), Some((1, 188)));
+        assert_eq!(Curve::new(0, 0, 599).point(599, 0).coords(), Some((0, 0)));
+    }
+
+    #[test]
+    fn points_compare_by_curve_and_coordinates() {
+        // Curve::new 规范参数，同一条曲线只有一种表示
+        assert_eq!(
+            Curve::new(-598, 600, 599).point(1, 188),
+            Curve::new(1, 1, 599).point(1, 188)
+        );
+        assert_eq!(
+            Point::infinity(&Curve::new(-1, 0, 103)),
+            Point::infinity(&Curve::new(102, 0, 103))
+        );
+        assert_ne!(
+            Point::infinity(&Curve::new(1, 1, 599)),
+            Point::infinity(&Curve::new(1, 2, 599))
+        );
+    }
+
+    #[test]
+    fn negative_representative_of_inverse_sums_to_infinity() {
+        let curve = Curve::new(1, 1, 599);
+        let p = curve.point(1, 188);
+        assert!((&p + &curve.point(1, -188)).is_infinity());
+        assert_eq!(-&p, curve.point(1, -188));
+    }
+
+    #[test]
+    fn negating_two_torsion_point_keeps_y_zero() {
+        let t = Curve::new(-1, 0, 103).point(0, 0);
+        assert_eq!((-&t).coords(), Some((0, 0)));
+    }
+
+    #[test]
+    fn infinity_is_the_identity_and_its_own_inverse() {
+        let curve = Curve::new(2, 3, 97);
+        let p = curve.point(3, 6);
+        let o = Point::infinity(&curve);
+        assert_eq!(&o + &p, p);
+        assert_eq!(&p - &o, p);
+        assert_eq!(&o - &p, -&p);
+        assert_eq!(-&o, o);
+        assert!((&o - &o).is_infinity());
+        assert!(p.try_mul(0).unwrap().is_infinity());
+        assert_eq!(3 * &o, o);
+    }
+
+    #[test]
+    fn operators_compose_like_the_group_law() {
+        let curve = Curve::new(2, 3, 97);
+        let (g, q) = (curve.point(3, 6), curve.point(0, 10));
+        assert_eq!(&q - &g + 3 * &g, &q + &g * 2);
+        assert_eq!(5 * g.clone(), g.clone() * 5);
+        let mut acc = Point::infinity(&curve);
+        acc += &g;
+        acc += g.clone();
+        assert_eq!(acc, &g * 2);
+        acc -= &g;
+        acc -= g.clone();
+        assert!(acc.is_infinity());
+        acc -= &g;
+        assert_eq!(acc, -&g);
+    }
+
+    #[test]
+    fn display_round_trips_through_parsing() {
+        let curve = Curve::new(2, 3, 97);
+        let p = curve.point(3, 6);
+        let o = Point::infinity(&curve);
+        assert_eq!(p.to_string(), "(3, 6)");
+        assert_eq!(o.to_string(), "Infinity");
+        for point in [p, o] {
+            assert_eq!(curve.parse_point(&point.to_string()), Ok(point));
+        }
+        assert_eq!(parse_coords("5,1"), Ok(Some((5, 1))));
+        assert_eq!(parse_coords(" ( 5 , -1 ) "), Ok(Some((5, -1))));
+        for text in ["5", "(5, 1", "5,1,2", "x,1", ""] {
+            assert_eq!(
+                parse_coords(text),
+                Err(EcError::InvalidPointSyntax(text.to_string()))
+            );
+        }
+        assert_eq!(
+            curve.parse_point("(3, 7)"),
+            Err(EcError::PointNotOnCurve { x: 3, y: 7 })
+        );
+    }
+
+    #[test]
+    fn coordinates_parse_what_they_display() {
+        for coords in [Some((3, 6)), Some((5, -1)), None] {
+            let text = Coordinates(coords).to_string();
+            assert_eq!(text.parse(), Ok(Coordinates(coords)));
+        }
+        assert_eq!(Coordinates(Some((3, 6))).to_string(), "(3, 6)");
+        // 不检查在不在曲线上
+        assert_eq!("(3, 7)".parse(), Ok(Coordinates(Some((3, 7)))));
+        assert_eq!(
+            "Some((3, 6))".parse::<Coordinates>(),
+            Err(EcError::InvalidPointSyntax("Some((3, 6))".to_string()))
+        );
+    }
+
+    #[test]
+    fn try_add_rejects_mismatched_curves() {
+        let (p, q) = two_curves();
+        assert_eq!(
+            p.try_add(&q),
+            Err(EcError::MismatchedCurves {
+                left: p.curve(),
+                right: q.curve(),
+            })
+        );
+    }
+
+    #[test]
+    fn try_add_rejects_curves_differing_only_in_b() {
+        let p = Curve::new(1, 1, 599).point(1, 188);
+        let q = Point::infinity(&Curve::new(1, 2, 599));
+        assert!(matches!(
+            p.try_add(&q),
+            Err(EcError::MismatchedCurves { .. })
+        ));
+    }
+
+    #[test]
+    fn try_add_rejects_points_off_the_curve() {
+        let curve = Curve::new(1, 1, 599);
+        let p = curve.point(1, 188);
+        // (3, 4) 在 y² = x³ + x + b' 上，b' = 16 − 27 − 3 ≠ 1
+        let off = off_curve(&curve, 3, 4);
+        assert!(!curve.contains_point(&off));
+        assert!(curve.contains_point(&p));
+        assert!(curve.contains_point(&Point::infinity(&curve)));
+        assert!(!curve.contains_point(&Curve::new(2, 3, 97).point(3, 6)));
+        assert_eq!(
+            p.try_add(&off),
+            Err(EcError::PointNotOnCurve { x: 3, y: 4 })
+        );
+        assert_eq!(
+            off.try_add(&p),
+            Err(EcError::PointNotOnCurve { x: 3, y: 4 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "not on the curve")]
+    fn add_panics_on_points_off_the_curve() {
+        let curve = Curve::new(1, 1, 599);
+        let _ = curve.point(1, 188) + off_curve(&curve, 3, 4);
+    }
+
+    #[test]
+    fn try_add_accepts_same_curve() {
+        let curve = Curve::new(1, 1, 599);
+        let p = curve.point(1, 188);
+        assert_eq!(p.try_add(&Point::infinity(&curve)), Ok(p.clone()));
+    }
+
+    #[test]
+    fn order_annihilates_the_point() {
+        let curve = Curve::new(2, 3, 97);
+        let p = curve.point(3, 6);
+        assert_eq!(p.order(), 5);
+        assert!((&p * 5).is_infinity());
+        assert_eq!(Point::infinity(&curve).order(), 1);
+        for point in curve.points() {
+            let order = point.order();
+            assert!((&point * order).is_infinity());
+            assert!(curve.group_order().is_multiple_of(order));
+        }
+    }
+
+    #[test]
+    fn generated_subgroup_lists_every_multiple() {
+        let curve = Curve::new(2, 3, 97);
+        let p = curve.point(3, 6);
+        let subgroup = p.generate_subgroup();
+        assert_eq!(subgroup.len() as u64, p.order());
+        for (k, element) in subgroup.iter().enumerate() {
+            assert_eq!(*element, &p * k as u64);
+        }
+        assert_eq!(
+            Point::infinity(&curve).generate_subgroup(),
+            [Point::infinity(&curve)]
+        );
+    }
+
+    #[test]
+    fn try_mul_reports_a_factor_of_a_composite_modulus() {
+        // 91 = 7 · 13，(0, 1) 在 mod 7 的曲线上是 5 阶点
+        let p = Curve::new(1, 1, 91).point(0, 1);
+        assert_eq!(p.try_mul(4).unwrap().coords(), Some((49, 6)));
+        assert_eq!(
+            p.try_mul(5),
+            Err(EcError::NonInvertible {
+                value: 49,
+                modulus: 91,
+                gcd: 7,
+            })
+        );
+        let q = Curve::new(2, 3, 97).point(3, 6);
+        assert_eq!(q.try_mul(5), Ok(&q * 5));
+    }
+
+    #[test]
+    fn sec1_round_trips_every_point() {
+        // 97 ≡ 1 (mod 4)，103 ≡ 3 (mod 4)，65537 刚超过 2¹⁶，坐标要三个字节
+        for (a, b, p) in [(2, 3, 97), (1, 1, 103), (0, 7, 65537)] {
+            let curve = Curve::new(a, b, p);
+            let mut points = curve.points();
+            points.truncate(500);
+            points.push(Point::infinity(&curve));
+            for point in points {
+                for compressed in [true, false] {
+                    let bytes = point.to_bytes(compressed);
+                    assert_eq!(Point::from_bytes(&bytes, &curve), Ok(point.clone()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sec1_layout() {
+        let curve = Curve::new(2, 3, 97);
+        let p = curve.point(3, 6);
+        assert_eq!(p.to_bytes(true), [0x02, 3]);
+        assert_eq!((-&p).to_bytes(true), [0x03, 3]);
+        assert_eq!(p.to_bytes(false), [0x04, 3, 6]);
+        assert_eq!(Point::infinity(&curve).to_bytes(true), [0x00]);
+        let wide = Curve::new(0, 7, 65537).point(1, 8160);
+        // p − 1 = 2¹⁶ 需要 17 位，坐标占三个字节
+        assert_eq!(wide.to_bytes(false), [0x04, 0, 0, 0x01, 0, 0x1f, 0xe0]);
+        assert_eq!(wide.to_bytes(true), [0x02, 0, 0, 0x01]);
+    }
+
+    #[test]
+    fn sec1_rejects_malformed_input() {
+        let curve = Curve::new(2, 3, 97);
+        let invalid = |bytes: &[u8]| match Point::from_bytes(bytes, &curve) {
+            Err(EcError::InvalidEncoding(reason)) => reason,
+            other => panic!("{bytes:?} decoded to {other:?}"),
+        };
+        assert_eq!(invalid(&[]), "unknown prefix");
+        assert_eq!(invalid(&[0x05, 3]), "unknown prefix");
+        assert_eq!(invalid(&[0x02, 3, 0]), "wrong length");
+        assert_eq!(invalid(&[0x04, 97, 0]), "coordinate is not below p");
+        // x = 2 时 x³ + 2x + 3 = 15 不是模 97 的二次剩余
+        assert_eq!(invalid(&[0x02, 2]), "no point has this x");
+        assert_eq!(
+            Point::from_bytes(&[0x04, 3, 7], &curve),
+            Err(EcError::PointNotOnCurve { x: 3, y: 7 })
+        );
+        let composite = Curve::new(1, 1, 91);
+        assert_eq!(
+            Point::from_bytes(&[0x02, 0], &composite),
+            Err(EcError::NotPrime(91))
+        );
+        assert!(Point::from_bytes(&[0x04, 0, 1], &composite).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "different curves")]
+    fn add_panics_on_mismatched_curves() {
+        let (p, q) = two_curves();
+        let _ = p + q;
+    }
+
+    #[test]
+    #[should_panic(expected = "different curves")]
+    fn sub_panics_on_mismatched_curves() {
+        let (p, q) = two_curves();
+        let _ = &p - &q;
+    }
+
+    #[test]
+    #[should_panic(expected = "different curves")]
+    fn add_assign_panics_on_mismatched_curves() {
+        let (mut p, q) = two_curves();
+        p += q;
+    }
+
+    #[test]
+    #[should_panic(expected = "different curves")]
+    fn affine_add_panics_on_mismatched_curves() {
+        let c1 = Curve::new(1, 1, 599);
+        let c2 = Curve::new(2, 3, 97);
+        let _ = AffinePoint::new(&c1, 1, 188) + AffinePoint::new(&c2, 3, 6);
+    }
+
+    #[test]
+    fn multiplication_rejects_points_off_the_curve() {
+        let curve = Curve::new(1, 1, 599);
+        let off = off_curve(&curve, 3, 4);
+        for k in [0, 1, 2, 5] {
+            assert_eq!(off.try_mul(k), Err(EcError::PointNotOnCurve { x: 3, y: 4 }));
+        }
+        assert_eq!(
+            curve.try_point(3, 4),
+            Err(EcError::PointNotOnCurve { x: 3, y: 4 })
+        );
+        assert_eq!(
+            AffinePoint::try_new(&curve, 3, 4),
+            Err(EcError::PointNotOnCurve { x: 3, y: 4 })
+        );
+        assert_eq!(
+            AffinePoint::try_new(&curve, 1, 188),
+            Ok(curve.affine(1, 188))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "not on the curve")]
+    fn mul_panics_on_points_off_the_curve() {
+        let off = off_curve(&Curve::new(1, 1, 599), 3, 4);
+        let _ = 5 * &off;
+    }
+
+    #[test]
+    #[should_panic(expected = "not on the curve")]
+    fn curve_point_panics_off_the_curve() {
+        let _ = Curve::new(1, 1, 599).point(3, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "not on the curve")]
+    fn affine_new_panics_off_the_curve() {
+        let curve = Curve::new(1, 1, 599);
+        let _ = AffinePoint::new(&curve, 3, 4);
+    }
+}
@@ -0,0 +1,247 @@
+// 标准曲线的参数：secp256k1 (SEC 2)、NIST P-256 (FIPS 186-4) 和 Curve25519 (RFC 7748)。
+// bigcurve 只处理短 Weierstrass 形式，Curve25519 的 Montgomery 形式 v² = u³ + Au² + u
+// 用 x = u + A/3 换元成 y² = x³ + ax + b（即 Wei25519），群和阶都不变。
+use crate::bigcurve::{BigCurve, BigPoint};
+use crate::u256::{self, Limbs};
+use crate::zeroize::{Zeroize, ZeroizeOnDrop};
+use rand_core::{CryptoRng, RngCore};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preset {
+    pub name: &'static str,
+    // 通常写法，界面上显示
+    pub equation: &'static str,
+    pub p: Limbs,
+    pub a: Limbs,
+    pub b: Limbs,
+    pub g: (Limbs, Limbs),
+    // G 的阶 n，#E = h·n
+    pub order: Limbs,
+    pub cofactor: u64,
+}
+
+impl Preset {
+    // 十六进制常数，依次是 p、a、b、Gx、Gy、n
+    fn from_hex(
+        name: &'static str,
+        equation: &'static str,
+        [p, a, b, gx, gy, n]: [&str; 6],
+        cofactor: u64,
+    ) -> Self {
+        let parse = |text: &str| u256::parse(text).expect("preset constants are valid");
+        Preset {
+            name,
+            equation,
+            p: parse(p),
+            a: parse(a),
+            b: parse(b),
+            g: (parse(gx), parse(gy)),
+            order: parse(n),
+            cofactor,
+        }
+    }
+
+    pub fn curve(&self) -> BigCurve {
+        BigCurve::new(&self.a, &self.b, &self.p).expect("preset parameters are valid")
+    }
+
+    pub fn generator(&self, curve: &BigCurve) -> BigPoint {
+        curve
+            .point(&self.g.0, &self.g.1)
+            .expect("the generator lies on the curve")
+    }
+}
+
+// 标准曲线上的密钥对：d 在 [1, n) 上均匀分布，Q = dG，离开作用域时 d 清零。
+// 256 位的私钥要求 CryptoRng，可预测的随机数在类型上就传不进来
+pub struct KeyPair {
+    secret: Limbs,
+    pub public: (Limbs, Limbs),
+}
+
+impl KeyPair {
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(preset: &Preset, rng: &mut R) -> Self {
+        let bits = 256 - u256::leading_zeros(&preset.order);
+        // 只保留 n 的位数，拒绝采样每次成功的概率至少一半
+        loop {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            let mut d = u256::from_be_bytes(&bytes);
+            bytes.zeroize();
+            for (i, limb) in d.iter_mut().enumerate() {
+                let low = 64 * i as u32;
+                if bits <= low {
+                    *limb = 0;
+                } else if bits < low + 64 {
+                    *limb &= (1 << (bits - low)) - 1;
+                }
+            }
+            if let Some(key) = KeyPair::from_secret(preset, d) {
+                return key;
+            }
+            d.zeroize();
+        }
+    }
+
+    // d 必须在 [1, n) 里
+    pub fn from_secret(preset: &Preset, secret: Limbs) -> Option<Self> {
+        if u256::is_zero(&secret) || u256::at_least(&secret, &preset.order) {
+            return None;
+        }
+        let curve = preset.curve();
+        let public = curve
+            .affine(&curve.mul(&preset.generator(&curve), &secret))
+            .expect("0 < d < n, so dG is not the identity");
+        Some(KeyPair { secret, public })
+    }
+
+    pub fn secret(&self) -> &Limbs {
+        &self.secret
+    }
+}
+
+impl fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("secret", &"<redacted>")
+            .field("public", &self.public)
+            .finish()
+    }
+}
+
+impl Zeroize for KeyPair {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl Drop for KeyPair {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for KeyPair {}
+
+pub fn secp256k1() -> Preset {
+    Preset::from_hex(
+        "secp256k1",
+        "y² = x³ + 7",
+        [
+            "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            "0x0",
+            "0x7",
+            "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            "0x483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        ],
+        1,
+    )
+}
+
+pub fn p256() -> Preset {
+    Preset::from_hex(
+        "P-256",
+        "y² = x³ − 3x + b",
+        [
+            "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+            "0xffffffff00000001000000000000000000000000fffffffffffffffffffffffc",
+            "0x5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+            "0x6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            "0x4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+            "0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+        ],
+        1,
+    )
+}
+
+// 基点 u = 9 对应 x = 9 + A/3，y 取 RFC 7748 里的 v
+pub fn curve25519() -> Preset {
+    Preset::from_hex(
+        "Curve25519",
+        "v² = u³ + 486662u² + u",
+        [
+            "0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed",
+            "0x2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa984914a144",
+            "0x7b425ed097b425ed097b425ed097b425ed097b425ed097b4260b5e9c7710c864",
+            "0x2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad245a",
+            "0x20ae19a1b8a086b4e01edd2c7748d14c923d4d7e6d7c61b229e9c5a27eced3d9",
+            "0x1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed",
+        ],
+        8,
+    )
+}
+
+pub fn all() -> [Preset; 3] {
+    [secp256k1(), p256(), curve25519()]
+}
+
+// 不区分大小写，也不管连字符，"p256" 和 "P-256" 都行
+pub fn by_name(name: &str) -> Option<Preset> {
+    let normalize = |name: &str| name.to_ascii_lowercase().replace('-', "");
+    all()
+        .into_iter()
+        .find(|preset| normalize(preset.name) == normalize(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bigcurve::FieldBackend;
+
+    #[test]
+    fn generators_have_the_stated_order() {
+        for preset in all() {
+            let curve = preset.curve();
+            let g = preset.generator(&curve);
+            assert!(
+                curve.is_identity(&curve.mul(&g, &preset.order)),
+                "{}",
+                preset.name
+            );
+            let n_minus_one = u256::sub_limbs(&preset.order, &[1, 0, 0, 0]).0;
+            assert!(curve.equal(&curve.mul(&g, &n_minus_one), &curve.neg(&g)));
+        }
+    }
+
+    #[test]
+    fn curve25519_matches_the_montgomery_form() {
+        // x = u + A/3，所以 3x − A = 3u = 27
+        let preset = curve25519();
+        let curve = preset.curve();
+        let f = &curve.field;
+        let x = f.elem(&preset.g.0);
+        let three_x = f.add(x, f.add(x, x));
+        let u = f.sub(three_x, f.elem(&[486662, 0, 0, 0]));
+        assert_eq!(f.value(u), [27, 0, 0, 0]);
+    }
+
+    #[test]
+    fn presets_are_found_by_name() {
+        assert_eq!(by_name("p256"), Some(p256()));
+        assert_eq!(
+            by_name("SECP256K1").map(|preset| preset.name),
+            Some("secp256k1")
+        );
+        assert_eq!(by_name("curve25519").map(|preset| preset.cofactor), Some(8));
+        assert_eq!(by_name("P-384"), None);
+    }
+
+    #[test]
+    fn generated_keys_are_below_the_order() {
+        let mut rng = crate::rng::seeded(7);
+        for preset in all() {
+            let curve = preset.curve();
+            let g = preset.generator(&curve);
+            let key = KeyPair::generate(&preset, &mut rng);
+            assert!(!u256::is_zero(key.secret()));
+            assert!(!u256::at_least(key.secret(), &preset.order));
+            assert_eq!(curve.affine(&curve.mul(&g, key.secret())), Some(key.public));
+            let other = KeyPair::generate(&preset, &mut rng);
+            assert_ne!(other.public, key.public, "{}", preset.name);
+            assert!(KeyPair::from_secret(&preset, preset.order).is_none());
+            assert!(format!("{key:?}").contains("<redacted>"));
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// 三方协议的模拟：Alice 和 Bob 通过不可信的信道互发消息，Mallory 可以截获并替换。
+// 发送、替换和各方的判断都按顺序记进 transcript，界面照着显示。
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Party {
+    Alice,
+    Bob,
+    Mallory,
+}
+
+impl fmt::Display for Party {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Party::Alice => "Alice",
+            Party::Bob => "Bob",
+            Party::Mallory => "Mallory",
+        })
+    }
+}
+
+// transcript 的一行：谁做了什么
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub party: Party,
+    pub text: String,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.party, self.text)
+    }
+}
+
+// 中间人：每条消息都经过它，可以原样放行，也可以换成别的
+pub trait Interceptor<M> {
+    fn intercept(&mut self, from: Party, to: Party, message: M) -> M;
+}
+
+pub struct Network<M, I> {
+    pub interceptor: Option<I>,
+    pub transcript: Vec<Entry>,
+    _message: std::marker::PhantomData<M>,
+}
+
+impl<M: fmt::Display + Clone + PartialEq, I: Interceptor<M>> Network<M, I> {
+    // interceptor 为 None 时信道是诚实的
+    pub fn new(interceptor: Option<I>) -> Self {
+        Network {
+            interceptor,
+            transcript: vec![],
+            _message: std::marker::PhantomData,
+        }
+    }
+
+    pub fn note(&mut self, party: Party, text: impl Into<String>) {
+        self.transcript.push(Entry {
+            party,
+            text: text.into(),
+        });
+    }
+
+    // 返回接收方实际收到的消息
+    pub fn send(&mut self, from: Party, to: Party, message: M) -> M {
+        self.note(from, format!("→ {to}: {message}"));
+        let Some(interceptor) = &mut self.interceptor else {
+            return message;
+        };
+        let delivered = interceptor.intercept(from, to, message.clone());
+        if delivered != message {
+            self.note(Party::Mallory, format!("replaced it with {delivered}"));
+        }
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 把发给 Bob 的数字加一
+    struct AddOne;
+
+    impl Interceptor<u32> for AddOne {
+        fn intercept(&mut self, _from: Party, to: Party, message: u32) -> u32 {
+            if to == Party::Bob {
+                message + 1
+            } else {
+                message
+            }
+        }
+    }
+
+    #[test]
+    fn interceptor_sees_every_message_and_replacements_are_logged() {
+        let mut network = Network::new(Some(AddOne));
+        assert_eq!(network.send(Party::Alice, Party::Bob, 1), 2);
+        assert_eq!(network.send(Party::Bob, Party::Alice, 5), 5);
+        let lines: Vec<String> = network.transcript.iter().map(Entry::to_string).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "[Alice] → Bob: 1",
+                "[Mallory] replaced it with 2",
+                "[Bob] → Alice: 5",
+            ]
+        );
+
+        let mut honest: Network<u32, AddOne> = Network::new(None);
+        assert_eq!(honest.send(Party::Alice, Party::Bob, 1), 1);
+        assert_eq!(honest.transcript.len(), 1);
+    }
+}
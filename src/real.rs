@@ -0,0 +1,453 @@
+// 实数上的曲线 y² = x³ + ax + b：连续的图像和弦切法，和 F_p 上的散点图对照着看。
+// a、b 取模 p 的对称代表元 (−p/2, p/2]，例如 p − 1 当作 −1。
+use crate::curve::Curve;
+use eframe::egui;
+
+const PLOT_SIZE: f32 = 599.0;
+// 每条分支的采样数
+const SAMPLES: usize = 200;
+// 弦切法的动画：0..1 画直线，1..2 翻转到 P + Q，再停一会儿从头开始
+const CONSTRUCTION_LENGTH: f32 = 3.0;
+
+// 画布显示有限域上的点、实数上的曲线，或者左右并排
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlotMode {
+    #[default]
+    Finite,
+    Real,
+    Split,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RealCurve {
+    pub a: f64,
+    pub b: f64,
+}
+
+impl RealCurve {
+    pub fn from_curve(curve: &Curve) -> Self {
+        let centered = |v: i64| {
+            let v = v.rem_euclid(curve.p);
+            if v > curve.p / 2 { v - curve.p } else { v }
+        };
+        RealCurve {
+            a: centered(curve.a) as f64,
+            b: centered(curve.b) as f64,
+        }
+    }
+
+    pub fn rhs(&self, x: f64) -> f64 {
+        x * x * x + self.a * x + self.b
+    }
+
+    pub fn is_singular(&self) -> bool {
+        4.0 * self.a.powi(3) + 27.0 * self.b.powi(2) == 0.0
+    }
+
+    // x³ + ax + b 的实根，从小到大；判别式 −(4a³ + 27b²) > 0 时有三个，用三角函数解法，
+    // 否则用 Cardano 公式求唯一的实根（重根只算一次）
+    pub fn roots(&self) -> Vec<f64> {
+        let (a, b) = (self.a, self.b);
+        if a < 0.0 && 4.0 * a.powi(3) + 27.0 * b * b < 0.0 {
+            let r = 2.0 * (-a / 3.0).sqrt();
+            let phi = (3.0 * b / (a * r)).clamp(-1.0, 1.0).acos() / 3.0;
+            let mut roots: Vec<f64> = (0..3)
+                .map(|k| r * (phi - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos())
+                .collect();
+            roots.sort_by(f64::total_cmp);
+            roots
+        } else {
+            let d = (b * b / 4.0 + a.powi(3) / 27.0).max(0.0).sqrt();
+            vec![(-b / 2.0 + d).cbrt() + (-b / 2.0 - d).cbrt()]
+        }
+    }
+
+    // 横坐标为 x、y 取上半支或下半支的点；x³ + ax + b < 0 时没有
+    pub fn point_at(&self, x: f64, upper: bool) -> Option<(f64, f64)> {
+        let rhs = self.rhs(x);
+        (rhs >= 0.0).then(|| {
+            let y = rhs.sqrt();
+            (x, if upper { y } else { -y })
+        })
+    }
+
+    // 曲线在 x ≤ x_max 的部分画成折线：三个根时是一个卵形加一条无界的分支。
+    // 在根附近 y ~ √(x − r)，用 x = lo + (hi − lo)(1 − cos πt)/2 取样，竖直的切线处也是光滑的
+    pub fn branches(&self, x_max: f64) -> Vec<Vec<(f64, f64)>> {
+        let roots = self.roots();
+        let mut intervals = Vec::new();
+        if let [r1, r2, r3] = roots[..] {
+            intervals.push((r1, r2, true));
+            intervals.push((r3, x_max, false));
+        } else {
+            intervals.push((roots[0], x_max, false));
+        }
+        intervals
+            .into_iter()
+            .filter(|&(lo, hi, _)| lo < hi)
+            .map(|(lo, hi, closed)| {
+                let xs: Vec<f64> = (0..=SAMPLES)
+                    .map(|i| {
+                        let t = i as f64 / SAMPLES as f64;
+                        lo + (hi - lo) * (1.0 - (std::f64::consts::PI * t).cos()) / 2.0
+                    })
+                    .collect();
+                // 舍入可能让端点处的 x³ + ax + b 略小于 0
+                let y = |x: f64| self.rhs(x).max(0.0).sqrt();
+                let mut line: Vec<(f64, f64)> = xs.iter().rev().map(|&x| (x, -y(x))).collect();
+                line.extend(xs.iter().skip(1).map(|&x| (x, y(x))));
+                if closed {
+                    line.push(line[0]);
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+// 实数上的弦切法，P = Q 时是切线；直线竖直时 P + Q 是无穷远点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Construction {
+    pub p: (f64, f64),
+    pub q: (f64, f64),
+    pub slope: Option<f64>,
+    pub third: Option<(f64, f64)>,
+    pub sum: Option<(f64, f64)>,
+}
+
+impl Construction {
+    pub fn new(curve: &RealCurve, p: (f64, f64), q: (f64, f64)) -> Self {
+        let ((x1, y1), (x2, y2)) = (p, q);
+        let slope = if p == q {
+            (y1 != 0.0).then(|| (3.0 * x1 * x1 + curve.a) / (2.0 * y1))
+        } else {
+            (x1 != x2).then(|| (y2 - y1) / (x2 - x1))
+        };
+        let third = slope.map(|slope| {
+            let x3 = slope * slope - x1 - x2;
+            (x3, y1 + slope * (x3 - x1))
+        });
+        Construction {
+            p,
+            q,
+            slope,
+            third,
+            sum: third.map(|(x, y)| (x, -y)),
+        }
+    }
+
+    // 动画进行到 t（0..2）时要画的线段：先从左到右画出整条直线，再从第三个交点竖直翻转。
+    // 直线两端各伸出 margin
+    pub fn segments(&self, t: f64, margin: f64) -> Vec<[(f64, f64); 2]> {
+        let grow = |[start, end]: [(f64, f64); 2], t: f64| {
+            let t = t.clamp(0.0, 1.0);
+            [
+                start,
+                (
+                    start.0 + t * (end.0 - start.0),
+                    start.1 + t * (end.1 - start.1),
+                ),
+            ]
+        };
+        let (Some(slope), Some(third), Some(sum)) = (self.slope, self.third, self.sum) else {
+            let x = self.p.0;
+            return vec![grow([(x, -margin), (x, margin)], t)];
+        };
+        let xs = [self.p.0, self.q.0, third.0];
+        let lo = xs.iter().copied().fold(f64::INFINITY, f64::min) - margin / 4.0;
+        let hi = xs.iter().copied().fold(f64::NEG_INFINITY, f64::max) + margin / 4.0;
+        let line = |x: f64| (x, self.p.1 + slope * (x - self.p.0));
+        let mut segments = vec![grow([line(lo), line(hi)], t)];
+        if t > 1.0 {
+            segments.push(grow([third, sum], t - 1.0));
+        }
+        segments
+    }
+}
+
+// 实数视图：用户在曲线上点选 P、Q，弦切法循环播放
+pub struct RealView {
+    // 切换曲线时重新选默认的 P、Q
+    key: Option<Curve>,
+    curve: RealCurve,
+    // 选中的点：横坐标和在上半支还是下半支
+    pub picks: [(f64, bool); 2],
+    // 下一次点击替换 P 还是 Q
+    next_pick: usize,
+    pub tangent: bool,
+    pub progress: f32,
+    pub playing: bool,
+}
+
+impl Default for RealView {
+    fn default() -> Self {
+        RealView {
+            key: None,
+            curve: RealCurve { a: 0.0, b: 0.0 },
+            picks: [(0.0, true); 2],
+            next_pick: 0,
+            tangent: false,
+            progress: 0.0,
+            playing: true,
+        }
+    }
+}
+
+impl RealView {
+    pub fn set_curve(&mut self, curve: &Curve) {
+        if self.key == Some(*curve) {
+            return;
+        }
+        self.key = Some(*curve);
+        self.curve = RealCurve::from_curve(curve);
+        // 在最大的根右边取两个点，距离随系数的大小伸缩
+        let r = *self.curve.roots().last().expect("a cubic has a real root");
+        let s = self.scale();
+        self.picks = [(r + 0.5 * s, true), (r + 2.0 * s, true)];
+        self.next_pick = 0;
+        self.progress = 0.0;
+    }
+
+    // 系数的大小决定图像的尺度
+    fn scale(&self) -> f64 {
+        1f64.max(self.curve.a.abs().sqrt())
+            .max(self.curve.b.abs().cbrt())
+    }
+
+    pub fn construction(&self) -> Option<Construction> {
+        let [(x1, upper1), (x2, upper2)] = self.picks;
+        let p = self.curve.point_at(x1, upper1)?;
+        let q = if self.tangent {
+            p
+        } else {
+            self.curve.point_at(x2, upper2)?
+        };
+        Some(Construction::new(&self.curve, p, q))
+    }
+
+    // 点击处横坐标上的点，y 的符号决定上下半支；曲线在这个 x 上没有点就不选
+    pub fn pick(&mut self, x: f64, y: f64) -> bool {
+        if self.curve.rhs(x) < 0.0 {
+            return false;
+        }
+        self.picks[self.next_pick] = (x, y >= 0.0);
+        self.next_pick = if self.tangent { 0 } else { 1 - self.next_pick };
+        self.progress = 0.0;
+        true
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, curve: &Curve) {
+        self.set_curve(curve);
+        let RealCurve { a, b } = self.curve;
+        ui.heading(format!("Over the reals (y^2 = x^3 + {a}x + {b})"));
+        if (a, b) != (curve.a as f64, curve.b as f64) {
+            ui.label(format!(
+                "a and b are taken as {a} ≡ {} and {b} ≡ {} (mod {})",
+                curve.a, curve.b, curve.p
+            ));
+        }
+        if self.curve.is_singular() {
+            ui.colored_label(
+                egui::Color32::from_rgb(200, 120, 0),
+                "Singular over ℝ as well: the curve crosses or cusps itself on the x-axis",
+            );
+        }
+        ui.horizontal(|ui| {
+            let text = if self.playing {
+                "Pause chord"
+            } else {
+                "Play chord"
+            };
+            if ui.button(text).clicked() {
+                self.playing = !self.playing;
+            }
+            if ui.button("Replay chord").clicked() {
+                self.progress = 0.0;
+                self.playing = true;
+            }
+            if ui.checkbox(&mut self.tangent, "Tangent at P").changed() {
+                self.next_pick = 0;
+                self.progress = 0.0;
+            }
+        });
+
+        let construction = self.construction();
+        // 显示范围要包住根和弦切法用到的点，但第三点可能跑得很远，最多放大到 20 倍尺度
+        let s = self.scale();
+        let mut extent = self
+            .curve
+            .roots()
+            .iter()
+            .fold(3.0 * s, |m, r| m.max(r.abs() * 1.5));
+        if let Some(c) = &construction {
+            for (x, y) in [Some(c.p), Some(c.q), c.third, c.sum].into_iter().flatten() {
+                extent = extent.max(x.abs().max(y.abs()) * 1.2);
+            }
+        }
+        let extent = extent.min(20.0 * s);
+
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(PLOT_SIZE, PLOT_SIZE), egui::Sense::click());
+        response.widget_info(|| {
+            egui::WidgetInfo::labeled(egui::WidgetType::Other, ui.is_enabled(), "Real plot")
+        });
+        let unit = rect.width() as f64 / (2.0 * extent);
+        let to_screen =
+            |(x, y): (f64, f64)| rect.center() + egui::vec2((x * unit) as f32, (-y * unit) as f32);
+        if response.clicked()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            let offset = pointer - rect.center();
+            self.pick(offset.x as f64 / unit, -offset.y as f64 / unit);
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+        let axis = egui::Stroke::new(1.0, egui::Color32::LIGHT_GRAY);
+        painter.line_segment([to_screen((-extent, 0.0)), to_screen((extent, 0.0))], axis);
+        painter.line_segment([to_screen((0.0, -extent)), to_screen((0.0, extent))], axis);
+        let stroke = egui::Stroke::new(2.0, egui::Color32::from_rgb(40, 110, 220));
+        for branch in self.curve.branches(extent) {
+            let line = branch.into_iter().map(to_screen).collect();
+            painter.add(egui::Shape::line(line, stroke));
+        }
+
+        let Some(construction) = construction else {
+            ui.label("Click the curve to choose P and Q");
+            return;
+        };
+        let t = self.progress.min(2.0) as f64;
+        let chord = egui::Color32::from_rgb(200, 30, 60);
+        for [start, end] in construction.segments(t, extent) {
+            painter.line_segment(
+                [to_screen(start), to_screen(end)],
+                egui::Stroke::new(1.5, chord),
+            );
+        }
+        let dot = |pos: (f64, f64), label: &str, color| {
+            painter.circle_filled(to_screen(pos), 4.0, color);
+            painter.text(
+                to_screen(pos) + egui::vec2(6.0, -6.0),
+                egui::Align2::LEFT_BOTTOM,
+                label,
+                egui::FontId::proportional(13.0),
+                color,
+            );
+        };
+        let same = construction.p == construction.q;
+        dot(
+            construction.p,
+            if same { "P = Q" } else { "P" },
+            egui::Color32::BLACK,
+        );
+        if !same {
+            dot(construction.q, "Q", egui::Color32::BLACK);
+        }
+        if let Some(third) = construction.third
+            && t >= 1.0
+        {
+            dot(third, "−(P + Q)", egui::Color32::GRAY);
+        }
+        if let Some(sum) = construction.sum
+            && t >= 2.0
+        {
+            dot(sum, "P + Q", chord);
+        }
+
+        let format = |(x, y): (f64, f64)| format!("({x:.3}, {y:.3})");
+        let sum = construction.sum.map_or("O".to_string(), format);
+        ui.label(format!(
+            "P = {}, Q = {}, P + Q = {sum}",
+            format(construction.p),
+            format(construction.q)
+        ));
+        if self.playing {
+            let dt = ui.input(|i| i.stable_dt);
+            self.progress = (self.progress + dt) % CONSTRUCTION_LENGTH;
+            ui.ctx().request_repaint();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6 * a.abs().max(1.0)
+    }
+
+    #[test]
+    fn roots_and_branches_follow_the_discriminant() {
+        // p − 1 是 −1：y² = x³ − x 有三个根，图像是一个卵形加一条分支
+        let curve = RealCurve::from_curve(&Curve::new(598, 0, 599));
+        assert_eq!(curve, RealCurve { a: -1.0, b: 0.0 });
+        let roots = curve.roots();
+        assert!(
+            roots
+                .iter()
+                .zip([-1.0, 0.0, 1.0])
+                .all(|(&r, e)| close(r, e))
+        );
+        let branches = curve.branches(3.0);
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].first(), branches[0].last());
+        for &(x, y) in branches.iter().flatten() {
+            assert!((y * y - curve.rhs(x).max(0.0)).abs() < 1e-9);
+        }
+
+        let curve = RealCurve::from_curve(&Curve::new(1, 1, 599));
+        let roots = curve.roots();
+        assert_eq!(roots.len(), 1);
+        assert!(curve.rhs(roots[0]).abs() < 1e-9);
+        assert_eq!(curve.branches(3.0).len(), 1);
+        assert!(curve.point_at(roots[0] - 0.1, true).is_none());
+    }
+
+    #[test]
+    fn chords_and_tangents_match_the_group_law() {
+        // y² = x³ + 17：(−2, 3) 和 (−1, 4) 的弦斜率为 1，第三点 (4, 9)，和为 (4, −9)
+        let curve = RealCurve { a: 0.0, b: 17.0 };
+        let chord = Construction::new(&curve, (-2.0, 3.0), (-1.0, 4.0));
+        assert_eq!(chord.slope, Some(1.0));
+        assert_eq!(chord.third, Some((4.0, 9.0)));
+        assert_eq!(chord.sum, Some((4.0, -9.0)));
+
+        let tangent = Construction::new(&curve, (2.0, 5.0), (2.0, 5.0));
+        let (x, y) = tangent.sum.unwrap();
+        assert!(close(tangent.slope.unwrap(), 1.2));
+        assert!(close(y * y, curve.rhs(x)));
+
+        // P + (−P) 和 y = 0 处的切线都是竖直的
+        let vertical = Construction::new(&curve, (2.0, 5.0), (2.0, -5.0));
+        assert_eq!((vertical.slope, vertical.sum), (None, None));
+        assert_eq!(vertical.segments(0.5, 10.0), [[(2.0, -10.0), (2.0, 0.0)]]);
+    }
+
+    #[test]
+    fn construction_draws_the_line_then_reflects() {
+        let curve = RealCurve { a: 0.0, b: 17.0 };
+        let chord = Construction::new(&curve, (-2.0, 3.0), (-1.0, 4.0));
+        assert_eq!(chord.segments(0.0, 4.0), [[(-3.0, 2.0), (-3.0, 2.0)]]);
+        assert_eq!(chord.segments(1.0, 4.0), [[(-3.0, 2.0), (5.0, 10.0)]]);
+        let segments = chord.segments(2.0, 4.0);
+        assert_eq!(segments[1], [(4.0, 9.0), (4.0, -9.0)]);
+    }
+
+    #[test]
+    fn clicks_alternate_between_p_and_q() {
+        let mut view = RealView::default();
+        view.set_curve(&Curve::new(0, 17, 599));
+        let [p, q] = view.picks;
+        assert!(p.0 < q.0 && view.construction().is_some());
+        assert!(!view.pick(-5.0, 1.0));
+        assert!(view.pick(-2.0, 3.0));
+        assert!(view.pick(-1.0, -4.0));
+        assert_eq!(view.picks, [(-2.0, true), (-1.0, false)]);
+        let construction = view.construction().unwrap();
+        // 斜率 −7，第三点 (52, −375)
+        assert_eq!(construction.sum, Some((52.0, 375.0)));
+        view.tangent = true;
+        assert_eq!(view.construction().unwrap().q, (-2.0, 3.0));
+    }
+}
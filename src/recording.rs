@@ -0,0 +1,427 @@
+// 会话录制与回放：记录用户操作及其时间，之后按同样的节奏重放。
+// 和保存状态不同，这里重放的是过程而不是结果。
+//
+// 文本格式，每行一个事件：
+//   # elliptic-curve session v1
+//   seed 42
+//   start 1 1 599 1 188
+//   1200 params 2 3 97
+//   1500 apply
+use crate::curve::Curve;
+use crate::orbit::Trajectory;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+const HEADER: &str = "# elliptic-curve session v1";
+
+// 界面上所有会改变状态的操作；界面和回放都通过它修改应用
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    // 输入框里的参数，Apply 之前不生效
+    EditParams {
+        a: i64,
+        b: i64,
+        p: i64,
+    },
+    Apply,
+    ExploreSingular,
+    CancelSingular,
+    ResetRng {
+        seed: u64,
+    },
+    RandomCurve,
+    EditNewBase {
+        x: i64,
+        y: i64,
+    },
+    AddPoint,
+    AddRandomPoint,
+    RemoveTracked(usize),
+    SetTrajectory {
+        index: usize,
+        trajectory: Trajectory,
+    },
+    SetK {
+        index: usize,
+        k: u64,
+    },
+    ShowField(bool),
+    FieldBase(usize),
+    ShowCosets(bool),
+    CosetBase(usize),
+    ShowXStats(bool),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::EditParams { a, b, p } => write!(f, "params {a} {b} {p}"),
+            Action::Apply => write!(f, "apply"),
+            Action::ExploreSingular => write!(f, "explore-singular"),
+            Action::CancelSingular => write!(f, "cancel-singular"),
+            Action::ResetRng { seed } => write!(f, "reset-rng {seed}"),
+            Action::RandomCurve => write!(f, "random-curve"),
+            Action::EditNewBase { x, y } => write!(f, "new-base {x} {y}"),
+            Action::AddPoint => write!(f, "add-point"),
+            Action::AddRandomPoint => write!(f, "add-random-point"),
+            Action::RemoveTracked(index) => write!(f, "remove {index}"),
+            Action::SetTrajectory { index, trajectory } => {
+                let name = match trajectory {
+                    Trajectory::Multiples => "multiples",
+                    Trajectory::Doublings => "doublings",
+                };
+                write!(f, "trajectory {index} {name}")
+            }
+            Action::SetK { index, k } => write!(f, "k {index} {k}"),
+            Action::ShowField(on) => write!(f, "show-field {on}"),
+            Action::FieldBase(index) => write!(f, "field-base {index}"),
+            Action::ShowCosets(on) => write!(f, "show-cosets {on}"),
+            Action::CosetBase(index) => write!(f, "coset-base {index}"),
+            Action::ShowXStats(on) => write!(f, "show-x-stats {on}"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let name = words.next().ok_or("empty action")?;
+        let args: Vec<&str> = words.collect();
+        // 按位置取参数并解析成需要的类型
+        fn arg<T: FromStr>(args: &[&str], i: usize) -> Result<T, String> {
+            let word = args
+                .get(i)
+                .ok_or_else(|| format!("missing argument {}", i + 1))?;
+            word.parse()
+                .map_err(|_| format!("invalid argument {word:?}"))
+        }
+        let expected = match name {
+            "apply" | "explore-singular" | "cancel-singular" | "random-curve" | "add-point"
+            | "add-random-point" => 0,
+            "reset-rng" | "remove" | "show-field" | "field-base" | "show-cosets" | "coset-base"
+            | "show-x-stats" => 1,
+            "new-base" | "trajectory" | "k" => 2,
+            "params" => 3,
+            _ => return Err(format!("unknown action {name:?}")),
+        };
+        if args.len() != expected {
+            return Err(format!(
+                "{name} takes {expected} arguments, got {}",
+                args.len()
+            ));
+        }
+        Ok(match name {
+            "params" => Action::EditParams {
+                a: arg(&args, 0)?,
+                b: arg(&args, 1)?,
+                p: arg(&args, 2)?,
+            },
+            "apply" => Action::Apply,
+            "explore-singular" => Action::ExploreSingular,
+            "cancel-singular" => Action::CancelSingular,
+            "reset-rng" => Action::ResetRng {
+                seed: arg(&args, 0)?,
+            },
+            "random-curve" => Action::RandomCurve,
+            "new-base" => Action::EditNewBase {
+                x: arg(&args, 0)?,
+                y: arg(&args, 1)?,
+            },
+            "add-point" => Action::AddPoint,
+            "add-random-point" => Action::AddRandomPoint,
+            "remove" => Action::RemoveTracked(arg(&args, 0)?),
+            "trajectory" => Action::SetTrajectory {
+                index: arg(&args, 0)?,
+                trajectory: match args[1] {
+                    "multiples" => Trajectory::Multiples,
+                    "doublings" => Trajectory::Doublings,
+                    other => return Err(format!("unknown trajectory {other:?}")),
+                },
+            },
+            "k" => Action::SetK {
+                index: arg(&args, 0)?,
+                k: arg(&args, 1)?,
+            },
+            "show-field" => Action::ShowField(arg(&args, 0)?),
+            "field-base" => Action::FieldBase(arg(&args, 0)?),
+            "show-cosets" => Action::ShowCosets(arg(&args, 0)?),
+            "coset-base" => Action::CosetBase(arg(&args, 0)?),
+            "show-x-stats" => Action::ShowXStats(arg(&args, 0)?),
+            _ => unreachable!("action names were checked above"),
+        })
+    }
+}
+
+// 录制开始时的状态：曲线、第一个追踪点和随机数种子
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Start {
+    pub seed: u64,
+    pub curve: (i64, i64, i64),
+    pub base: (i64, i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub start: Start,
+    pub events: Vec<(Duration, Action)>,
+}
+
+impl Recording {
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |(at, _)| *at)
+    }
+}
+
+impl fmt::Display for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Start {
+            seed,
+            curve: (a, b, p),
+            base: (x, y),
+        } = self.start;
+        writeln!(f, "{HEADER}")?;
+        writeln!(f, "seed {seed}")?;
+        writeln!(f, "start {a} {b} {p} {x} {y}")?;
+        for (at, action) in &self.events {
+            writeln!(f, "{} {action}", at.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recording {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+        match lines.next() {
+            Some((_, HEADER)) => {}
+            _ => return Err(format!("missing header {HEADER:?}")),
+        }
+        let mut numbers = |keyword: &str, count: usize| -> Result<Vec<i64>, String> {
+            let (n, line) = lines.next().ok_or(format!("missing {keyword} line"))?;
+            let rest = line
+                .strip_prefix(keyword)
+                .ok_or(format!("line {n}: expected {keyword}"))?;
+            let values: Vec<i64> = rest
+                .split_whitespace()
+                .map(|w| {
+                    w.parse()
+                        .map_err(|_| format!("line {n}: invalid number {w:?}"))
+                })
+                .collect::<Result<_, _>>()?;
+            if values.len() != count {
+                return Err(format!("line {n}: {keyword} takes {count} numbers"));
+            }
+            Ok(values)
+        };
+        let seed = numbers("seed", 1)?[0] as u64;
+        let start = numbers("start", 5)?;
+        // 回放时用 Curve::point 设置基点，曲线外的点在这里就拒绝
+        if start[2] < 2 || !Curve::new(start[0], start[1], start[2]).contains(start[3], start[4]) {
+            return Err(format!(
+                "line 3: ({}, {}) is not on y² = x³ + {}x + {} (mod {})",
+                start[3], start[4], start[0], start[1], start[2]
+            ));
+        }
+
+        let mut events = Vec::new();
+        for (n, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let (at, action) = line
+                .split_once(' ')
+                .ok_or(format!("line {n}: expected <millis> <action>"))?;
+            let at: u64 = at
+                .parse()
+                .map_err(|_| format!("line {n}: invalid time {at:?}"))?;
+            let action = action.parse().map_err(|e| format!("line {n}: {e}"))?;
+            events.push((Duration::from_millis(at), action));
+        }
+        Ok(Recording {
+            start: Start {
+                seed,
+                curve: (start[0], start[1], start[2]),
+                base: (start[3], start[4]),
+            },
+            events,
+        })
+    }
+}
+
+// 正在进行的录制
+#[derive(Debug)]
+pub struct Recorder {
+    started: Instant,
+    recording: Recording,
+}
+
+impl Recorder {
+    pub fn new(start: Start) -> Self {
+        Recorder {
+            started: Instant::now(),
+            recording: Recording {
+                start,
+                events: Vec::new(),
+            },
+        }
+    }
+
+    // 时间截到毫秒，与文件格式的精度一致，导出再导入不会改变录制
+    pub fn record(&mut self, action: Action) {
+        let at = Duration::from_millis(self.started.elapsed().as_millis() as u64);
+        self.recording.events.push((at, action));
+    }
+
+    pub fn len(&self) -> usize {
+        self.recording.events.len()
+    }
+
+    pub fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+// 按录制时的节奏取出到期的事件，speed 为播放倍速
+#[derive(Debug)]
+pub struct Player {
+    recording: Recording,
+    started: Instant,
+    next: usize,
+    speed: f32,
+}
+
+impl Player {
+    pub fn new(recording: Recording, speed: f32) -> Self {
+        Player {
+            recording,
+            started: Instant::now(),
+            next: 0,
+            speed,
+        }
+    }
+
+    pub fn due(&mut self) -> Vec<Action> {
+        self.due_at(self.started.elapsed().mul_f32(self.speed))
+    }
+
+    // 按录制时间计算，到 elapsed 为止还没执行的事件
+    fn due_at(&mut self, elapsed: Duration) -> Vec<Action> {
+        let events = &self.recording.events[self.next..];
+        let count = events.iter().take_while(|(at, _)| *at <= elapsed).count();
+        self.next += count;
+        events[..count].iter().map(|(_, action)| *action).collect()
+    }
+
+    // 距离下一个事件还有多久（按真实时间），用来安排重绘
+    pub fn until_next(&self) -> Option<Duration> {
+        let (at, _) = self.recording.events.get(self.next)?;
+        let elapsed = self.started.elapsed().mul_f32(self.speed);
+        Some(at.saturating_sub(elapsed).div_f32(self.speed))
+    }
+
+    pub fn progress(&self) -> (usize, usize) {
+        (self.next, self.recording.events.len())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.recording.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Recording {
+        Recording {
+            start: Start {
+                seed: 42,
+                curve: (1, 1, 599),
+                base: (1, 188),
+            },
+            events: vec![
+                (
+                    Duration::from_millis(120),
+                    Action::EditParams { a: 2, b: 3, p: 97 },
+                ),
+                (Duration::from_millis(300), Action::Apply),
+                (
+                    Duration::from_millis(900),
+                    Action::SetTrajectory {
+                        index: 0,
+                        trajectory: Trajectory::Doublings,
+                    },
+                ),
+                (Duration::from_millis(950), Action::ShowCosets(true)),
+                (Duration::from_millis(1000), Action::SetK { index: 0, k: 7 }),
+            ],
+        }
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let recording = sample();
+        let text = recording.to_string();
+        assert!(text.starts_with(HEADER));
+        assert!(text.contains("\n300 apply\n"));
+        assert_eq!(text.parse::<Recording>(), Ok(recording));
+    }
+
+    #[test]
+    fn every_action_round_trips() {
+        let actions = [
+            Action::EditParams { a: 0, b: 7, p: 101 },
+            Action::Apply,
+            Action::ExploreSingular,
+            Action::CancelSingular,
+            Action::ResetRng { seed: 9 },
+            Action::RandomCurve,
+            Action::EditNewBase { x: 3, y: 6 },
+            Action::AddPoint,
+            Action::AddRandomPoint,
+            Action::RemoveTracked(2),
+            Action::SetTrajectory {
+                index: 1,
+                trajectory: Trajectory::Multiples,
+            },
+            Action::SetK { index: 1, k: 40 },
+            Action::ShowField(true),
+            Action::FieldBase(1),
+            Action::ShowCosets(false),
+            Action::CosetBase(0),
+            Action::ShowXStats(true),
+        ];
+        for action in actions {
+            assert_eq!(action.to_string().parse(), Ok(action));
+        }
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let text = format!("{HEADER}\nseed 1\nstart 1 1 599 1 188\n10 apply\n20 jump 3\n");
+        let error = text.parse::<Recording>().unwrap_err();
+        assert!(error.starts_with("line 5:"), "{error}");
+        let off_curve = format!("{HEADER}\nseed 1\nstart 1 1 599 3 4\n");
+        assert_eq!(
+            off_curve.parse::<Recording>().unwrap_err(),
+            "line 3: (3, 4) is not on y² = x³ + 1x + 1 (mod 599)"
+        );
+        assert!("apply".parse::<Recording>().is_err());
+        assert!("params 1 2".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn player_releases_events_in_time_order() {
+        let mut player = Player::new(sample(), 1.0);
+        assert!(player.due_at(Duration::from_millis(100)).is_empty());
+        assert_eq!(
+            player.due_at(Duration::from_millis(300)),
+            vec![Action::EditParams { a: 2, b: 3, p: 97 }, Action::Apply]
+        );
+        assert_eq!(player.progress(), (2, 5));
+        assert_eq!(player.due_at(Duration::from_secs(5)).len(), 3);
+        assert!(player.is_finished());
+    }
+}
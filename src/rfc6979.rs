@@ -0,0 +1,126 @@
+// RFC 6979：ECDSA 的 nonce k 由私钥 x 和消息哈希 h 确定，用 HMAC-SHA256 的 DRBG 生成。
+// 同一对 (x, h) 总得到同一个 k，不同消息的 k 互不相关，签名不再依赖随机数发生器：
+// 随机数坏了让两次签名用上同一个 k，私钥就能直接算出来。
+// 整数按 q 的位数 qlen 截断（bits2int）、按 rlen = ⌈qlen/8⌉ 字节编码（int2octets），
+// 所以同一套代码既给玩具群用，也给 256 位的 P-256 用。
+use crate::sha256::hmac;
+use crate::u256::{self, Limbs};
+
+pub struct Rfc6979 {
+    q: Limbs,
+    qlen: u32,
+    key: [u8; 32],
+    v: [u8; 32],
+    // 已经给出过候选，下一次先更新 K、V
+    started: bool,
+}
+
+impl Rfc6979 {
+    // q 是群的阶，x 是私钥（< q），digest 是消息的 SHA-256
+    pub fn new(q: &Limbs, x: &Limbs, digest: &[u8; 32]) -> Self {
+        let qlen = 256 - u256::leading_zeros(q);
+        let rlen = qlen.div_ceil(8) as usize;
+        // bits2octets：截断后模 q，截断后不到 2q，减一次就够
+        let mut h = bits2int(digest, qlen);
+        if u256::at_least(&h, q) {
+            h = u256::sub_limbs(&h, q).0;
+        }
+        let x = &u256::to_be_bytes(x)[32 - rlen..];
+        let h = &u256::to_be_bytes(&h)[32 - rlen..];
+        let mut drbg = Rfc6979 {
+            q: *q,
+            qlen,
+            key: [0; 32],
+            v: [1; 32],
+            started: false,
+        };
+        for separator in [0u8, 1] {
+            drbg.key = hmac(&drbg.key, &[&drbg.v, &[separator], x, h]);
+            drbg.v = hmac(&drbg.key, &[&drbg.v]);
+        }
+        drbg
+    }
+
+    // [1, q) 里的下一个候选。调用方算出 r = 0 或 s = 0 时再取一个
+    pub fn next_nonce(&mut self) -> Limbs {
+        loop {
+            if self.started {
+                self.key = hmac(&self.key, &[&self.v, &[0]]);
+                self.v = hmac(&self.key, &[&self.v]);
+            }
+            self.started = true;
+            // qlen ≤ 256，一块 HMAC 输出就够
+            self.v = hmac(&self.key, &[&self.v]);
+            let k = bits2int(&self.v, self.qlen);
+            if !u256::is_zero(&k) && !u256::at_least(&k, &self.q) {
+                return k;
+            }
+        }
+    }
+}
+
+// 大端字节串的高 qlen 位
+fn bits2int(bytes: &[u8; 32], qlen: u32) -> Limbs {
+    let value = u256::from_be_bytes(bytes);
+    let shift = 256 - qlen;
+    let (words, bits) = ((shift / 64) as usize, shift % 64);
+    let mut out = [0; 4];
+    for i in 0..4 - words {
+        out[i] = value[i + words] >> bits;
+        if bits > 0 && i + words + 1 < 4 {
+            out[i] |= value[i + words + 1] << (64 - bits);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256::sha256;
+
+    fn hex(text: &str) -> Limbs {
+        u256::parse(&format!("0x{text}")).unwrap()
+    }
+
+    // RFC 6979 附录 A.2.5（P-256）和 A.1.2（163 位的 q，要截断），都用 SHA-256
+    #[test]
+    fn appendix_a_nonces() {
+        let q = hex("FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551");
+        let x = hex("C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721");
+        let mut nonces = Rfc6979::new(&q, &x, &sha256(b"sample"));
+        assert_eq!(
+            nonces.next_nonce(),
+            hex("A6E3C57DD01ABE90086538398355DD4C3B17AA873382B0F24D6129493D8AAD60")
+        );
+
+        let q = hex("4000000000000000000020108A2E0CC0D99F8A5EF");
+        let x = hex("09A4D6792295A7F730FC3F2B49CBC0F62E862272F");
+        let mut nonces = Rfc6979::new(&q, &x, &sha256(b"sample"));
+        assert_eq!(
+            nonces.next_nonce(),
+            hex("23AF4074C90A02B3FE61D286D5C87F425E6BDD81B")
+        );
+    }
+
+    #[test]
+    fn small_groups_reject_candidates_above_q() {
+        // q = 199 是 8 位，候选在 [0, 256)，大于等于 q 的被跳过
+        let q = [199, 0, 0, 0];
+        let mut nonces = Rfc6979::new(&q, &[57, 0, 0, 0], &sha256(b"abc"));
+        let first: Vec<Limbs> = (0..32).map(|_| nonces.next_nonce()).collect();
+        assert!(
+            first
+                .iter()
+                .all(|k| (1..199).contains(&k[0]) && k[1..] == [0; 3])
+        );
+        // 确定性：同样的输入给出同样的序列，换一条消息就不同
+        let mut again = Rfc6979::new(&q, &[57, 0, 0, 0], &sha256(b"abc"));
+        assert_eq!(again.next_nonce(), first[0]);
+        let mut other = Rfc6979::new(&q, &[57, 0, 0, 0], &sha256(b"abd"));
+        let other: Vec<Limbs> = (0..4).map(|_| other.next_nonce()).collect();
+        assert_ne!(other, first[..4]);
+        assert_eq!(u256::leading_zeros(&q), 248);
+        assert_eq!(bits2int(&[0xff; 32], 8), [0xff, 0, 0, 0]);
+    }
+}
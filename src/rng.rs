@@ -0,0 +1,24 @@
+// 随机数来源：所有随机操作都接受注入的 RngCore，方便复现
+pub use rand_chacha::ChaCha20Rng as DemoRng;
+use rand_core::{OsRng, RngCore, SeedableRng};
+
+// 固定种子的确定性 RNG，同一个种子在任何机器上都得到同样的序列
+pub fn seeded(seed: u64) -> DemoRng {
+    DemoRng::seed_from_u64(seed)
+}
+
+pub fn from_entropy() -> DemoRng {
+    DemoRng::from_rng(OsRng).expect("operating system RNG is available")
+}
+
+// [0, n) 上的均匀分布，拒绝采样避免取模偏差
+pub fn uniform_below<R: RngCore + ?Sized>(rng: &mut R, n: u64) -> u64 {
+    assert!(n > 0, "range must be non-empty");
+    let zone = u64::MAX - (u64::MAX % n + 1) % n;
+    loop {
+        let v = rng.next_u64();
+        if v <= zone {
+            return v % n;
+        }
+    }
+}
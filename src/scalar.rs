@@ -0,0 +1,295 @@
+use crate::affine::AffinePoint;
+use crate::point::Point;
+use crate::rng;
+use crate::zeroize::{Zeroize, ZeroizeOnDrop};
+use rand_core::RngCore;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// 模群阶 n 的标量，与域元素 (mod p) 区分开
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Scalar {
+    value: u64,
+    modulus: u64,
+}
+
+impl Scalar {
+    pub fn new(value: i64, modulus: u64) -> Self {
+        assert!(modulus > 0, "scalar modulus must be positive");
+        Scalar {
+            value: (value as i128).rem_euclid(modulus as i128) as u64,
+            modulus,
+        }
+    }
+
+    pub fn from_u64(value: u64, modulus: u64) -> Self {
+        assert!(modulus > 0, "scalar modulus must be positive");
+        Scalar {
+            value: value % modulus,
+            modulus,
+        }
+    }
+
+    pub fn zero(modulus: u64) -> Self {
+        Scalar::from_u64(0, modulus)
+    }
+
+    pub fn one(modulus: u64) -> Self {
+        Scalar::from_u64(1, modulus)
+    }
+
+    // [1, n) 上均匀随机的非零标量
+    pub fn random<R: RngCore + ?Sized>(rng: &mut R, modulus: u64) -> Self {
+        assert!(modulus > 1, "no non-zero scalars modulo {modulus}");
+        Scalar::from_u64(1 + rng::uniform_below(rng, modulus - 1), modulus)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    // n 不一定是素数，不可逆时返回 None
+    pub fn invert(&self) -> Option<Scalar> {
+        let (mut old_r, mut r) = (self.value as i128, self.modulus as i128);
+        let (mut old_s, mut s) = (1i128, 0i128);
+        while r != 0 {
+            let q = old_r / r;
+            (old_r, r) = (r, old_r - q * r);
+            (old_s, s) = (s, old_s - q * s);
+        }
+        if old_r != 1 {
+            return None;
+        }
+        Some(Scalar {
+            value: old_s.rem_euclid(self.modulus as i128) as u64,
+            modulus: self.modulus,
+        })
+    }
+
+    // self · other⁻¹，other 不可逆时返回 None
+    pub fn checked_div(&self, other: Scalar) -> Option<Scalar> {
+        self.check_modulus(&other);
+        Some(*self * other.invert()?)
+    }
+
+    pub fn pow(&self, mut exp: u64) -> Scalar {
+        let mut result = Scalar::one(self.modulus);
+        let mut base = *self;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        result
+    }
+
+    fn check_modulus(&self, other: &Scalar) {
+        assert_eq!(
+            self.modulus, other.modulus,
+            "cannot combine scalars with different moduli"
+        );
+    }
+}
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+// 私钥、nonce 等秘密标量：不可 Copy，离开作用域时清零
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretScalar(Scalar);
+
+impl SecretScalar {
+    pub fn new(scalar: Scalar) -> Self {
+        SecretScalar(scalar)
+    }
+
+    // 只借出引用，需要按值参与运算时由调用方显式解引用，副本的生命周期一目了然
+    pub fn expose(&self) -> &Scalar {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for SecretScalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretScalar(<redacted> mod {})", self.0.modulus)
+    }
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+impl Add for Scalar {
+    type Output = Scalar;
+
+    fn add(self, other: Scalar) -> Scalar {
+        self.check_modulus(&other);
+        let sum = (self.value as u128 + other.value as u128) % self.modulus as u128;
+        Scalar::from_u64(sum as u64, self.modulus)
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        Scalar::from_u64(self.modulus - self.value, self.modulus)
+    }
+}
+
+impl Sub for Scalar {
+    type Output = Scalar;
+
+    fn sub(self, other: Scalar) -> Scalar {
+        self + (-other)
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Scalar;
+
+    fn mul(self, other: Scalar) -> Scalar {
+        self.check_modulus(&other);
+        let product = (self.value as u128 * other.value as u128) % self.modulus as u128;
+        Scalar::from_u64(product as u64, self.modulus)
+    }
+}
+
+impl AddAssign for Scalar {
+    fn add_assign(&mut self, other: Scalar) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Scalar {
+    fn sub_assign(&mut self, other: Scalar) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Scalar {
+    fn mul_assign(&mut self, other: Scalar) {
+        *self = *self * other;
+    }
+}
+
+impl Mul<&Point> for Scalar {
+    type Output = Point;
+
+    fn mul(self, point: &Point) -> Point {
+        point * self.value
+    }
+}
+
+impl Mul<Point> for Scalar {
+    type Output = Point;
+
+    fn mul(self, point: Point) -> Point {
+        &point * self.value
+    }
+}
+
+impl<'c> Mul<AffinePoint<'c>> for Scalar {
+    type Output = AffinePoint<'c>;
+
+    fn mul(self, point: AffinePoint<'c>) -> AffinePoint<'c> {
+        point * self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invert_and_divide() {
+        let n = 101;
+        for value in 1..n {
+            let k = Scalar::from_u64(value, n);
+            let inverse = k
+                .invert()
+                .expect("every non-zero scalar mod a prime is invertible");
+            assert_eq!(k * inverse, Scalar::one(n));
+            assert_eq!(Scalar::one(n).checked_div(k), Some(inverse));
+        }
+        assert_eq!(Scalar::zero(n).invert(), None);
+        assert_eq!(Scalar::one(n).checked_div(Scalar::zero(n)), None);
+        // 合数模：和 n 有公因子的不可逆
+        assert_eq!(Scalar::from_u64(6, 100).invert(), None);
+        assert_eq!(
+            Scalar::from_u64(7, 100).checked_div(Scalar::from_u64(10, 100)),
+            None
+        );
+        assert_eq!(
+            Scalar::from_u64(21, 100).checked_div(Scalar::from_u64(3, 100)),
+            Some(Scalar::from_u64(7, 100))
+        );
+    }
+
+    #[test]
+    fn pow_neg_and_reduction() {
+        let n = 97;
+        let k = Scalar::from_u64(5, n);
+        assert_eq!(k.pow(0), Scalar::one(n));
+        assert_eq!(k.pow(3), Scalar::from_u64(125 % 97, n));
+        // 费马小定理
+        assert_eq!(k.pow(n - 1), Scalar::one(n));
+        assert_eq!(-k, Scalar::from_u64(92, n));
+        assert_eq!(-Scalar::zero(n), Scalar::zero(n));
+        assert_eq!(k - k, Scalar::zero(n));
+        assert_eq!(Scalar::new(-1, n), Scalar::from_u64(96, n));
+        assert_eq!(Scalar::from_u64(u64::MAX, n).value(), u64::MAX % n);
+    }
+
+    #[test]
+    fn random_scalars_are_non_zero_and_reduced() {
+        let mut rng = rng::seeded(5);
+        for n in [2, 3, 97] {
+            let mut seen = std::collections::BTreeSet::new();
+            for _ in 0..1000 {
+                let k = Scalar::random(&mut rng, n);
+                assert!(!k.is_zero() && k.value() < n);
+                seen.insert(k.value());
+            }
+            assert_eq!(seen.len() as u64, n - 1);
+        }
+    }
+
+    #[test]
+    fn secret_scalars_are_redacted_and_cleared() {
+        let mut secret = SecretScalar::new(Scalar::from_u64(57, 101));
+        assert_eq!(*secret.expose(), Scalar::from_u64(57, 101));
+        assert_eq!(format!("{secret:?}"), "SecretScalar(<redacted> mod 101)");
+        secret.zeroize();
+        assert!(secret.expose().is_zero());
+        assert_eq!(secret.expose().modulus(), 101);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot combine scalars with different moduli")]
+    fn mismatched_moduli_panic() {
+        let _ = Scalar::one(97) + Scalar::one(101);
+    }
+}
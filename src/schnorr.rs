@@ -0,0 +1,134 @@
+// Schnorr 签名：s = k + e·x，其中 R = k·G，e = H(R || P || m) mod q。
+// 验证 s·G == R + e·P。这里只用来演示身份认证，哈希取 SHA-256 的前 8 字节模 q。
+use crate::batch::Equation;
+use crate::curve::Coords;
+use crate::scalar::{Scalar, SecretScalar};
+use crate::sha256::Sha256;
+use crate::subgroup::PrimeGroup;
+use rand_core::RngCore;
+
+pub struct KeyPair {
+    secret: SecretScalar,
+    pub public: Coords,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Signature {
+    pub r: Coords,
+    pub s: Scalar,
+}
+
+impl KeyPair {
+    pub fn generate<R: RngCore + ?Sized>(group: &PrimeGroup, rng: &mut R) -> Self {
+        let secret = Scalar::random(rng, group.q);
+        KeyPair {
+            public: group.base_mul(secret),
+            secret: SecretScalar::new(secret),
+        }
+    }
+
+    pub fn sign<R: RngCore + ?Sized>(
+        &self,
+        group: &PrimeGroup,
+        message: &[u8],
+        rng: &mut R,
+    ) -> Signature {
+        let k = SecretScalar::new(Scalar::random(rng, group.q));
+        let r = group.base_mul(*k.expose());
+        let e = challenge(group, r, self.public, message);
+        Signature {
+            r,
+            s: *k.expose() + e * *self.secret.expose(),
+        }
+    }
+}
+
+pub fn verify(group: &PrimeGroup, public: Coords, message: &[u8], signature: &Signature) -> bool {
+    if public.is_none() || signature.s.modulus() != group.q {
+        return false;
+    }
+    let e = challenge(group, signature.r, public, message);
+    let lhs = group.base_mul(signature.s);
+    let rhs = group.curve.add_coords(signature.r, group.mul(public, e));
+    lhs == rhs
+}
+
+// 批量验证用的等式 s·G − R − e·P = O；R、P 不在子群里时签名无效
+pub fn equation(
+    group: &PrimeGroup,
+    public: Coords,
+    message: &[u8],
+    signature: &Signature,
+) -> Option<Equation> {
+    let Signature { r, s } = *signature;
+    if public.is_none() || s.modulus() != group.q || !group.contains(r) || !group.contains(public) {
+        return None;
+    }
+    let e = challenge(group, r, public, message);
+    let curve = &group.curve;
+    Some(Equation {
+        terms: vec![
+            (group.g, s),
+            (curve.neg_coords(r), Scalar::one(group.q)),
+            (curve.neg_coords(public), e),
+        ],
+    })
+}
+
+// 点编码成 16 字节 (x, y)，无穷远点是 16 个 0xff，和任何仿射点都不同
+pub fn encode(coords: Coords) -> [u8; 16] {
+    let mut bytes = [0xff; 16];
+    if let Some((x, y)) = coords {
+        bytes[..8].copy_from_slice(&x.to_be_bytes());
+        bytes[8..].copy_from_slice(&y.to_be_bytes());
+    }
+    bytes
+}
+
+fn challenge(group: &PrimeGroup, r: Coords, public: Coords, message: &[u8]) -> Scalar {
+    let digest = Sha256::new()
+        .update(&encode(r))
+        .update(&encode(public))
+        .update(message)
+        .finalize();
+    // 取模有一点偏差，q 很小时可以忽略
+    let e = u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"));
+    Scalar::from_u64(e, group.q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::Curve;
+    use crate::rng;
+
+    fn group() -> PrimeGroup {
+        PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap()
+    }
+
+    #[test]
+    fn signatures_verify_under_the_signing_key_only() {
+        let group = group();
+        let mut rng = rng::seeded(4);
+        let alice = KeyPair::generate(&group, &mut rng);
+        let bob = KeyPair::generate(&group, &mut rng);
+        let signature = alice.sign(&group, b"hello", &mut rng);
+        assert!(verify(&group, alice.public, b"hello", &signature));
+        assert!(!verify(&group, alice.public, b"hellp", &signature));
+        assert!(!verify(&group, bob.public, b"hello", &signature));
+        assert!(!verify(&group, None, b"hello", &signature));
+        let forged = Signature {
+            s: signature.s + Scalar::one(group.q),
+            ..signature
+        };
+        assert!(!verify(&group, alice.public, b"hello", &forged));
+    }
+
+    #[test]
+    fn the_group_generator_has_prime_order() {
+        let group = group();
+        assert_eq!(group.q, 199);
+        assert!(group.g.is_some());
+        assert_eq!(group.curve.mul_coords(group.g, group.q), None);
+    }
+}
@@ -0,0 +1,1271 @@
+// 脚本：一个很小的解释型语言，用来自动化演示场景。整数和点上的运算直接调用库，
+// 界面上的操作通过 Action 交给应用执行，和录制回放走同一条路径。
+//
+//   # y^2 = x^3 + 2x + 3 over F_97
+//   curve(2, 3, 97)
+//   let P = point(3, 6)
+//   for k in 1..4
+//     print k, k * P
+//   end
+//   assert ord(P) * P == infinity()
+//   pin(P)
+//
+// 每行一条语句：let、print、assert、for ... end、if ... end 或者单独的表达式，
+// 一行里也可以用 `;` 隔开几条。`let` 可以省略，`(x, y)` 是当前曲线上的点，
+// `order(P)` 和 `ord(P)` 相同。`/` 和 `%` 按欧几里得除法取整，余数总是非负，适合写模运算。
+//
+// Session 是界面上的表达式控制台：变量在多次输入之间保留，单独一个表达式的值直接显示。
+use crate::commitment::PedersenCommitment;
+use crate::curve::Curve;
+use crate::field;
+use crate::montgomery::{Montgomery, MontgomeryReduction};
+use crate::point::Point;
+use crate::recording::Action;
+use crate::rng::{self, DemoRng};
+use crate::twisted_edwards::{EdwardsReduction, TwistedEdwards};
+use crate::weierstrass::{LongWeierstrass, Reduction};
+use std::collections::HashMap;
+use std::fmt;
+
+// 与界面输入框的上限相同
+const MAX_P: i64 = crate::view::MAX_P;
+// 执行的语句数上限，防止循环写错时卡住界面
+const MAX_STEPS: usize = 100_000;
+
+// 一次运行的结果：打印的行、交给界面的操作，以及出错时的信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Output {
+    pub lines: Vec<String>,
+    pub actions: Vec<Action>,
+    pub error: Option<String>,
+}
+
+// 从给定的曲线和种子开始运行，出错时保留出错前的输出和操作
+pub fn run(source: &str, curve: Curve, seed: u64) -> Output {
+    let _span = tracing::info_span!("script", lines = source.lines().count()).entered();
+    Interpreter::new(curve, seed, false).run(source)
+}
+
+pub struct Session {
+    interpreter: Interpreter,
+}
+
+impl Session {
+    pub fn new(curve: Curve, seed: u64) -> Self {
+        Session {
+            interpreter: Interpreter::new(curve, seed, true),
+        }
+    }
+
+    // 界面换了曲线时跟着换，不产生操作；旧曲线上的点留在变量里，和新点相加会报错
+    pub fn follow(&mut self, curve: Curve) {
+        if curve != self.interpreter.curve {
+            self.interpreter.switch(curve);
+        }
+    }
+
+    // 一次输入只有一行，错误里不带行号
+    pub fn eval(&mut self, input: &str) -> Output {
+        let _span = tracing::debug_span!("console", input).entered();
+        self.interpreter.steps = 0;
+        let mut output = self.interpreter.run(input);
+        if let Some(error) = &mut output.error
+            && let Some(rest) = error.strip_prefix("line 1: ")
+        {
+            *error = rest.to_string();
+        }
+        output
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Ident(String),
+    Str(String),
+    Sym(&'static str),
+}
+
+const SYMBOLS: [&str; 17] = [
+    "..", "==", "!=", "<=", ">=", "<", ">", "=", "+", "-", "*", "/", "%", "(", ")", ",", ";",
+];
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    loop {
+        rest = rest.trim_start();
+        let Some(c) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+        if c == '#' {
+            return Ok(tokens);
+        }
+        if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let value = rest[..end]
+                .parse()
+                .map_err(|_| format!("number {} is too large", &rest[..end]))?;
+            tokens.push(Token::Int(value));
+            rest = &rest[end..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if c == '"' {
+            let end = rest[1..].find('"').ok_or("unterminated string")? + 1;
+            tokens.push(Token::Str(rest[1..end].to_string()));
+            rest = &rest[end + 1..];
+        } else {
+            let sym = SYMBOLS
+                .into_iter()
+                .find(|sym| rest.starts_with(sym))
+                .ok_or_else(|| format!("unexpected character {c:?}"))?;
+            tokens.push(Token::Sym(sym));
+            rest = &rest[sym.len()..];
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl BinOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    Var(String),
+    Neg(Box<Expr>),
+    // (x, y)
+    Point(Box<Expr>, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stmt {
+    Let(String, Expr),
+    Print(Vec<Expr>),
+    Assert(Expr),
+    Expr(Expr),
+    For {
+        var: String,
+        from: Expr,
+        to: Expr,
+        body: Vec<Line>,
+    },
+    If {
+        condition: Expr,
+        body: Vec<Line>,
+    },
+}
+
+// 语句和它所在的行号，报错时使用
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+    number: usize,
+    stmt: Stmt,
+}
+
+fn parse(source: &str) -> Result<Vec<Line>, String> {
+    let mut lines = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let tokens = tokenize(line).map_err(|e| format!("line {}: {e}", i + 1))?;
+        // `;` 隔开的语句共用一个行号
+        for stmt in tokens.split(|token| *token == Token::Sym(";")) {
+            if !stmt.is_empty() {
+                lines.push((i + 1, stmt.to_vec()));
+            }
+        }
+    }
+    let mut lines = lines.into_iter();
+    let (program, end) = parse_block(&mut lines)?;
+    match end {
+        Some(number) => Err(format!("line {number}: `end` without a block")),
+        None => Ok(program),
+    }
+}
+
+// 读到 `end` 或输入结束为止，返回 `end` 所在的行号
+fn parse_block(
+    lines: &mut impl Iterator<Item = (usize, Vec<Token>)>,
+) -> Result<(Vec<Line>, Option<usize>), String> {
+    let mut block = Vec::new();
+    while let Some((number, tokens)) = lines.next() {
+        let mut parser = Parser { tokens, pos: 0 };
+        let at = |e: String| format!("line {number}: {e}");
+        let keyword = parser.keyword();
+        if keyword == Some("end") {
+            parser.finish().map_err(at)?;
+            return Ok((block, Some(number)));
+        }
+        let stmt = parser.statement(keyword).map_err(at)?;
+        parser.finish().map_err(at)?;
+        let stmt = match stmt {
+            Stmt::For { var, from, to, .. } => Stmt::For {
+                var,
+                from,
+                to,
+                body: parse_body(lines, number)?,
+            },
+            Stmt::If { condition, .. } => Stmt::If {
+                condition,
+                body: parse_body(lines, number)?,
+            },
+            stmt => stmt,
+        };
+        block.push(Line { number, stmt });
+    }
+    Ok((block, None))
+}
+
+fn parse_body(
+    lines: &mut impl Iterator<Item = (usize, Vec<Token>)>,
+    opened: usize,
+) -> Result<Vec<Line>, String> {
+    match parse_block(lines)? {
+        (body, Some(_)) => Ok(body),
+        (_, None) => Err(format!("line {opened}: block is missing `end`")),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, sym: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Sym(s)) if *s == sym) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, sym: &str) -> Result<(), String> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            Err(format!("expected `{sym}`"))
+        }
+    }
+
+    // 行首的关键字；不是关键字时不消耗
+    fn keyword(&mut self) -> Option<&'static str> {
+        let Some(Token::Ident(word)) = self.peek() else {
+            return None;
+        };
+        let keyword = ["let", "print", "assert", "for", "if", "end"]
+            .into_iter()
+            .find(|k| k == word)?;
+        self.pos += 1;
+        Some(keyword)
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Ident(word)) if word == keyword => Ok(()),
+            _ => Err(format!("expected `{keyword}`")),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(name),
+            _ => Err("expected a name".to_string()),
+        }
+    }
+
+    fn finish(&self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(format!("unexpected {}", describe(token))),
+        }
+    }
+
+    // 一行语句；for 和 if 的 body 由 parse_block 接着读取
+    fn statement(&mut self, keyword: Option<&str>) -> Result<Stmt, String> {
+        Ok(match keyword {
+            Some("let") => {
+                let name = self.ident()?;
+                self.expect("=")?;
+                Stmt::Let(name, self.expr()?)
+            }
+            Some("print") => {
+                let mut values = vec![self.expr()?];
+                while self.eat(",") {
+                    values.push(self.expr()?);
+                }
+                Stmt::Print(values)
+            }
+            Some("assert") => Stmt::Assert(self.expr()?),
+            Some("for") => {
+                let var = self.ident()?;
+                self.expect_keyword("in")?;
+                let from = self.sum()?;
+                self.expect("..")?;
+                Stmt::For {
+                    var,
+                    from,
+                    to: self.sum()?,
+                    body: Vec::new(),
+                }
+            }
+            Some("if") => Stmt::If {
+                condition: self.expr()?,
+                body: Vec::new(),
+            },
+            // 省略 let 的赋值 `P = ...`
+            _ => match (self.peek(), self.tokens.get(self.pos + 1)) {
+                (Some(Token::Ident(name)), Some(Token::Sym("="))) => {
+                    let name = name.clone();
+                    self.pos += 2;
+                    Stmt::Let(name, self.expr()?)
+                }
+                _ => Stmt::Expr(self.expr()?),
+            },
+        })
+    }
+
+    // 比较的优先级最低，且不能连写
+    fn expr(&mut self) -> Result<Expr, String> {
+        let lhs = self.sum()?;
+        let op = match self.peek() {
+            Some(Token::Sym("==")) => BinOp::Eq,
+            Some(Token::Sym("!=")) => BinOp::Ne,
+            Some(Token::Sym("<")) => BinOp::Lt,
+            Some(Token::Sym("<=")) => BinOp::Le,
+            Some(Token::Sym(">")) => BinOp::Gt,
+            Some(Token::Sym(">=")) => BinOp::Ge,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.sum()?;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("+")) => BinOp::Add,
+                Some(Token::Sym("-")) => BinOp::Sub,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("*")) => BinOp::Mul,
+                Some(Token::Sym("/")) => BinOp::Div,
+                Some(Token::Sym("%")) => BinOp::Rem,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        match self.next() {
+            Some(Token::Int(value)) => Ok(Expr::Int(value)),
+            Some(Token::Str(text)) => Ok(Expr::Str(text)),
+            Some(Token::Ident(name)) if name == "true" || name == "false" => {
+                Ok(Expr::Bool(name == "true"))
+            }
+            Some(Token::Ident(name)) => {
+                if !self.eat("(") {
+                    return Ok(Expr::Var(name));
+                }
+                let mut args = Vec::new();
+                if !self.eat(")") {
+                    args.push(self.expr()?);
+                    while self.eat(",") {
+                        args.push(self.expr()?);
+                    }
+                    self.expect(")")?;
+                }
+                Ok(Expr::Call(name, args))
+            }
+            Some(Token::Sym("(")) => {
+                let inner = self.expr()?;
+                if self.eat(",") {
+                    let y = self.expr()?;
+                    self.expect(")")?;
+                    return Ok(Expr::Point(Box::new(inner), Box::new(y)));
+                }
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Some(token) => Err(format!("unexpected {}", describe(&token))),
+            None => Err("unexpected end of line".to_string()),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Int(value) => format!("number {value}"),
+        Token::Ident(name) => format!("`{name}`"),
+        Token::Str(text) => format!("string {text:?}"),
+        Token::Sym(sym) => format!("`{sym}`"),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Unit,
+    Int(i64),
+    Bool(bool),
+    Str(String),
+    Point(Point),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Unit => "nothing",
+            Value::Int(_) => "an integer",
+            Value::Bool(_) => "a bool",
+            Value::Str(_) => "a string",
+            Value::Point(_) => "a point",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Str(text) => write!(f, "{text}"),
+            Value::Point(point) => write!(f, "{point}"),
+        }
+    }
+}
+
+struct Interpreter {
+    curve: Curve,
+    // 当前曲线的 #E，第一次用到时才计算
+    group_order: Option<u64>,
+    // Pedersen 承诺的参数，同样按需计算
+    commitment: Option<PedersenCommitment>,
+    // long_curve() 化简时的变量替换，long_point() 用它把点映射到短形式
+    long_form: Option<Reduction>,
+    // montgomery_curve() 的换元，montgomery_point() 和 ladder() 用
+    montgomery_form: Option<MontgomeryReduction>,
+    // edwards_curve() 的换元，edwards_point() 用
+    edwards_form: Option<EdwardsReduction>,
+    rng: DemoRng,
+    vars: HashMap<String, Value>,
+    steps: usize,
+    output: Output,
+    // 控制台里单独一个表达式的值也输出
+    echo: bool,
+}
+
+impl Interpreter {
+    fn new(curve: Curve, seed: u64, echo: bool) -> Self {
+        Interpreter {
+            curve,
+            group_order: None,
+            commitment: None,
+            long_form: None,
+            montgomery_form: None,
+            edwards_form: None,
+            rng: rng::seeded(seed),
+            vars: HashMap::new(),
+            steps: 0,
+            output: Output::default(),
+            echo,
+        }
+    }
+
+    // 输出从空开始，变量保留
+    fn run(&mut self, source: &str) -> Output {
+        let result = parse(source).and_then(|program| self.block(&program));
+        if let Err(error) = &result {
+            tracing::warn!(%error, "script failed");
+        }
+        Output {
+            error: result.err(),
+            ..std::mem::take(&mut self.output)
+        }
+    }
+
+    fn block(&mut self, lines: &[Line]) -> Result<(), String> {
+        lines.iter().try_for_each(|line| self.line(line))
+    }
+
+    // 语句本身的错误在这里加上行号，块内的错误已经带了行号
+    fn line(&mut self, line: &Line) -> Result<(), String> {
+        let at = |e: String| format!("line {}: {e}", line.number);
+        self.step().map_err(at)?;
+        match &line.stmt {
+            Stmt::For {
+                var,
+                from,
+                to,
+                body,
+            } => {
+                let (from, to) = (self.int(from).map_err(at)?, self.int(to).map_err(at)?);
+                for i in from..to {
+                    self.step().map_err(at)?;
+                    self.vars.insert(var.clone(), Value::Int(i));
+                    self.block(body)?;
+                }
+                Ok(())
+            }
+            Stmt::If { condition, body } => {
+                if self.condition(condition).map_err(at)? {
+                    self.block(body)?;
+                }
+                Ok(())
+            }
+            stmt => self.stmt(stmt).map_err(at),
+        }
+    }
+
+    // 每条语句和每次循环都计一步
+    fn step(&mut self) -> Result<(), String> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err(format!("script ran more than {MAX_STEPS} steps"));
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), String> {
+        match stmt {
+            Stmt::Let(name, expr) => {
+                let value = self.eval(expr)?;
+                self.vars.insert(name.clone(), value);
+            }
+            Stmt::Print(exprs) => {
+                let values = exprs
+                    .iter()
+                    .map(|expr| self.eval(expr).map(|value| value.to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.output.lines.push(values.join(" "));
+            }
+            Stmt::Assert(expr) => {
+                if !self.condition(expr)? {
+                    return Err("assertion failed".to_string());
+                }
+            }
+            Stmt::Expr(expr) => {
+                let value = self.eval(expr)?;
+                if self.echo && value != Value::Unit {
+                    self.output.lines.push(value.to_string());
+                }
+            }
+            Stmt::For { .. } | Stmt::If { .. } => unreachable!("blocks are run by line()"),
+        }
+        Ok(())
+    }
+
+    fn int(&mut self, expr: &Expr) -> Result<i64, String> {
+        match self.eval(expr)? {
+            Value::Int(value) => Ok(value),
+            other => Err(format!("expected an integer, got {}", other.type_name())),
+        }
+    }
+
+    fn condition(&mut self, expr: &Expr) -> Result<bool, String> {
+        match self.eval(expr)? {
+            Value::Bool(value) => Ok(value),
+            other => Err(format!(
+                "condition must be a bool, got {}",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, String> {
+        Ok(match expr {
+            Expr::Int(value) => Value::Int(*value),
+            Expr::Bool(value) => Value::Bool(*value),
+            Expr::Str(text) => Value::Str(text.clone()),
+            Expr::Var(name) => self
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("unknown variable `{name}`"))?,
+            Expr::Neg(inner) => match self.eval(inner)? {
+                Value::Int(value) => Value::Int(value.checked_neg().ok_or("integer overflow")?),
+                Value::Point(point) => Value::Point(-point),
+                other => return Err(format!("cannot negate {}", other.type_name())),
+            },
+            Expr::Point(x, y) => {
+                let (x, y) = (self.int(x)?, self.int(y)?);
+                self.point(x, y)?
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (self.eval(lhs)?, self.eval(rhs)?);
+                binary(*op, lhs, rhs)?
+            }
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call(name, &args)?
+            }
+        })
+    }
+
+    fn call(&mut self, name: &str, args: &[Value]) -> Result<Value, String> {
+        let arity = |n: usize| {
+            if args.len() == n {
+                Ok(())
+            } else {
+                Err(format!("{name}() takes {n} arguments, got {}", args.len()))
+            }
+        };
+        let int = |i: usize| match &args[i] {
+            Value::Int(value) => Ok(*value),
+            other => Err(format!(
+                "{name}() expects an integer, got {}",
+                other.type_name()
+            )),
+        };
+        let point = |i: usize| match &args[i] {
+            Value::Point(point) => Ok(point.clone()),
+            other => Err(format!(
+                "{name}() expects a point, got {}",
+                other.type_name()
+            )),
+        };
+        Ok(match name {
+            "curve" => {
+                arity(3)?;
+                self.use_curve(Curve::new(int(0)?, int(1)?, int(2)?))?;
+                Value::Unit
+            }
+            "long_curve" => {
+                arity(6)?;
+                let coefficients = [int(0)?, int(1)?, int(2)?, int(3)?, int(4)?];
+                let p = int(5)?;
+                check_prime(p)?;
+                let reduction = LongWeierstrass::new(coefficients, p).reduce()?;
+                self.use_curve(reduction.curve)?;
+                self.long_form = Some(reduction);
+                Value::Unit
+            }
+            "long_point" => {
+                arity(2)?;
+                let (x, y) = (int(0)?, int(1)?);
+                let reduction = self
+                    .long_form
+                    .ok_or("long_point() needs a curve from long_curve()")?;
+                if !reduction.long.contains(x, y) {
+                    return Err(format!("({x}, {y}) is not on {}", reduction.long));
+                }
+                let (x, y) = reduction
+                    .map_to_short(Some((x, y)))
+                    .expect("affine in, affine out");
+                Value::Point(self.curve.point(x, y))
+            }
+            "montgomery_curve" => {
+                arity(3)?;
+                let p = int(2)?;
+                check_prime(p)?;
+                let reduction = Montgomery::new(int(0)?, int(1)?, p).reduce()?;
+                self.use_curve(reduction.curve)?;
+                self.montgomery_form = Some(reduction);
+                Value::Unit
+            }
+            "montgomery_point" => {
+                arity(2)?;
+                let (u, v) = (int(0)?, int(1)?);
+                let reduction = self.montgomery()?;
+                if !reduction.montgomery.contains(u, v) {
+                    return Err(format!("({u}, {v}) is not on {}", reduction.montgomery));
+                }
+                let (x, y) = reduction
+                    .map_to_short(Some((u, v)))
+                    .expect("affine in, affine out");
+                Value::Point(self.curve.point(x, y))
+            }
+            // u(k·P)：只拿 P 的 u 坐标走 x-only ladder
+            "ladder" => {
+                arity(2)?;
+                let k = u64::try_from(int(0)?).map_err(|_| "ladder() needs k ≥ 0")?;
+                let point = point(1)?;
+                let reduction = self.montgomery()?;
+                let (u, _) = reduction
+                    .map_to_montgomery(point.coords())
+                    .ok_or("the point at infinity has no u coordinate")?;
+                let u = reduction
+                    .montgomery
+                    .ladder(k, u)
+                    .ok_or_else(|| format!("{k}·P is the point at infinity"))?;
+                Value::Int(u)
+            }
+            "edwards_curve" => {
+                arity(3)?;
+                let p = int(2)?;
+                check_prime(p)?;
+                let reduction = TwistedEdwards::new(int(0)?, int(1)?, p).reduce()?;
+                self.use_curve(reduction.curve())?;
+                self.edwards_form = Some(reduction);
+                Value::Unit
+            }
+            "edwards_point" => {
+                arity(2)?;
+                let (x, y) = (int(0)?, int(1)?);
+                let reduction = self
+                    .edwards_form
+                    .ok_or("edwards_point() needs a curve from edwards_curve()")?;
+                if !reduction.edwards.contains(x, y) {
+                    return Err(format!("({x}, {y}) is not on {}", reduction.edwards));
+                }
+                let p = reduction.edwards.p;
+                Value::Point(Point::from_coords(
+                    &self.curve,
+                    reduction.map_to_short((x.rem_euclid(p), y.rem_euclid(p))),
+                ))
+            }
+            "random_curve" => {
+                arity(1)?;
+                let p = int(0)?;
+                check_prime(p)?;
+                // 跳过只有无穷远点的曲线，与界面上的随机曲线一致
+                let curve = loop {
+                    let curve = Curve::random(&mut self.rng, p);
+                    if !curve.points().is_empty() {
+                        break curve;
+                    }
+                };
+                self.use_curve(curve)?;
+                Value::Unit
+            }
+            "a" | "b" | "p" => {
+                arity(0)?;
+                let curve = self.curve;
+                Value::Int(match name {
+                    "a" => curve.a,
+                    "b" => curve.b,
+                    _ => curve.p,
+                })
+            }
+            "point" => {
+                arity(2)?;
+                self.point(int(0)?, int(1)?)?
+            }
+            "infinity" => {
+                arity(0)?;
+                Value::Point(Point::from_coords(&self.curve, None))
+            }
+            "random_point" => {
+                arity(0)?;
+                let point = self.curve.random_point(&mut self.rng);
+                Value::Point(point.ok_or("curve has no affine points")?)
+            }
+            "x" | "y" => {
+                arity(1)?;
+                let (x, y) = point(0)?
+                    .coords()
+                    .ok_or("the point at infinity has no coordinates")?;
+                Value::Int(if name == "x" { x } else { y })
+            }
+            "order" if args.len() != 1 => {
+                arity(0)?;
+                Value::Int(self.group_order() as i64)
+            }
+            "ord" | "order" => {
+                arity(1)?;
+                let point = point(0)?;
+                if point.curve() != self.curve {
+                    return Err("point is not on the current curve".to_string());
+                }
+                let group_order = self.group_order();
+                Value::Int(self.curve.order_of(point.coords(), group_order) as i64)
+            }
+            "is_prime" => {
+                arity(1)?;
+                Value::Bool(field::is_prime(int(0)?))
+            }
+            "inverse" => {
+                arity(2)?;
+                let (value, modulus) = (int(0)?, int(1)?);
+                if modulus < 2 {
+                    return Err(format!("modulus {modulus} must be at least 2"));
+                }
+                Value::Int(field::try_mod_inverse(value, modulus).map_err(|e| e.to_string())?)
+            }
+            // Pedersen 承诺 C = v·G + r·H，标量模生成元子群的阶
+            "commit" => {
+                arity(2)?;
+                let params = self.commitment()?;
+                let commitment = params.commit(params.scalar(int(0)?), params.scalar(int(1)?));
+                Value::Point(Point::from_coords(&self.curve, commitment))
+            }
+            "open" => {
+                arity(3)?;
+                let commitment = point(0)?;
+                let params = self.commitment()?;
+                Value::Bool(params.open(
+                    commitment.coords(),
+                    params.scalar(int(1)?),
+                    params.scalar(int(2)?),
+                ))
+            }
+            "seed" => {
+                arity(1)?;
+                self.rng = rng::seeded(int(0)? as u64);
+                Value::Unit
+            }
+            // 以下把操作交给界面
+            "pin" => {
+                arity(1)?;
+                let point = point(0)?;
+                if point.curve() != self.curve {
+                    return Err("point is not on the current curve".to_string());
+                }
+                let (x, y) = point.coords().ok_or("cannot pin the point at infinity")?;
+                self.output.actions.push(Action::EditNewBase { x, y });
+                self.output.actions.push(Action::AddPoint);
+                Value::Unit
+            }
+            "ui" => {
+                arity(1)?;
+                let Value::Str(text) = &args[0] else {
+                    return Err(format!(
+                        "ui() expects a string, got {}",
+                        args[0].type_name()
+                    ));
+                };
+                self.output.actions.push(text.parse()?);
+                Value::Unit
+            }
+            _ => return Err(format!("unknown function `{name}`")),
+        })
+    }
+
+    // 切换当前曲线，同时让界面应用同样的参数
+    fn use_curve(&mut self, curve: Curve) -> Result<(), String> {
+        check_prime(curve.p)?;
+        if curve.is_singular() {
+            return Err(format!(
+                "y^2 = x^3 + {}x + {} is singular mod {}",
+                curve.a, curve.b, curve.p
+            ));
+        }
+        self.switch(curve);
+        self.output.actions.push(Action::EditParams {
+            a: curve.a,
+            b: curve.b,
+            p: curve.p,
+        });
+        self.output.actions.push(Action::Apply);
+        Ok(())
+    }
+
+    fn switch(&mut self, curve: Curve) {
+        self.curve = curve;
+        self.group_order = None;
+        self.commitment = None;
+        self.long_form = None;
+        self.montgomery_form = None;
+        self.edwards_form = None;
+    }
+
+    fn montgomery(&self) -> Result<MontgomeryReduction, String> {
+        self.montgomery_form
+            .ok_or_else(|| "needs a curve from montgomery_curve()".to_string())
+    }
+
+    fn point(&self, x: i64, y: i64) -> Result<Value, String> {
+        if !self.curve.contains(x, y) {
+            return Err(format!("({x}, {y}) is not on the curve"));
+        }
+        Ok(Value::Point(self.curve.point(x, y)))
+    }
+
+    fn commitment(&mut self) -> Result<PedersenCommitment, String> {
+        if self.commitment.is_none() {
+            self.commitment = Some(PedersenCommitment::new(&self.curve)?);
+        }
+        Ok(self.commitment.expect("parameters were just computed"))
+    }
+
+    fn group_order(&mut self) -> u64 {
+        *self
+            .group_order
+            .get_or_insert_with(|| self.curve.group_order())
+    }
+}
+
+fn check_prime(p: i64) -> Result<(), String> {
+    if !(3..=MAX_P).contains(&p) || !field::is_prime(p) {
+        return Err(format!("p must be an odd prime up to {MAX_P}, got {p}"));
+    }
+    Ok(())
+}
+
+fn binary(op: BinOp, lhs: Value, rhs: Value) -> Result<Value, String> {
+    let overflow = || "integer overflow".to_string();
+    Ok(match (op, lhs, rhs) {
+        (BinOp::Eq, lhs, rhs) => Value::Bool(lhs == rhs),
+        (BinOp::Ne, lhs, rhs) => Value::Bool(lhs != rhs),
+        (BinOp::Add, Value::Int(a), Value::Int(b)) => {
+            Value::Int(a.checked_add(b).ok_or_else(overflow)?)
+        }
+        (BinOp::Sub, Value::Int(a), Value::Int(b)) => {
+            Value::Int(a.checked_sub(b).ok_or_else(overflow)?)
+        }
+        (BinOp::Mul, Value::Int(a), Value::Int(b)) => {
+            Value::Int(a.checked_mul(b).ok_or_else(overflow)?)
+        }
+        (BinOp::Div | BinOp::Rem, Value::Int(_), Value::Int(0)) => {
+            return Err("division by zero".to_string());
+        }
+        (BinOp::Div, Value::Int(a), Value::Int(b)) => {
+            Value::Int(a.checked_div_euclid(b).ok_or_else(overflow)?)
+        }
+        (BinOp::Rem, Value::Int(a), Value::Int(b)) => {
+            Value::Int(a.checked_rem_euclid(b).ok_or_else(overflow)?)
+        }
+        (BinOp::Lt, Value::Int(a), Value::Int(b)) => Value::Bool(a < b),
+        (BinOp::Le, Value::Int(a), Value::Int(b)) => Value::Bool(a <= b),
+        (BinOp::Gt, Value::Int(a), Value::Int(b)) => Value::Bool(a > b),
+        (BinOp::Ge, Value::Int(a), Value::Int(b)) => Value::Bool(a >= b),
+        (BinOp::Add, Value::Point(a), Value::Point(b)) => {
+            Value::Point(a.try_add(&b).map_err(|e| e.to_string())?)
+        }
+        (BinOp::Sub, Value::Point(a), Value::Point(b)) => {
+            Value::Point(a.try_add(&-b).map_err(|e| e.to_string())?)
+        }
+        (BinOp::Mul, Value::Int(k), Value::Point(point))
+        | (BinOp::Mul, Value::Point(point), Value::Int(k)) => {
+            // 负的倍数等于相反点的倍数
+            let point = if k < 0 { -point } else { point };
+            Value::Point(point * k.unsigned_abs())
+        }
+        (op, lhs, rhs) => {
+            return Err(format!(
+                "cannot apply `{}` to {} and {}",
+                op.symbol(),
+                lhs.type_name(),
+                rhs.type_name()
+            ));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orbit::Trajectory;
+
+    const SEED: u64 = 0x5c1;
+
+    fn output(source: &str) -> Output {
+        run(source, Curve::new(2, 3, 97), SEED)
+    }
+
+    fn lines(source: &str) -> Vec<String> {
+        let output = output(source);
+        assert_eq!(output.error, None);
+        output.lines
+    }
+
+    #[test]
+    fn integer_arithmetic_follows_precedence() {
+        assert_eq!(
+            lines("print 1 + 2 * 3, (1 + 2) * 3, -7 % 5, -7 / 5, 2 * 3 == 6"),
+            vec!["7 9 3 -2 true"]
+        );
+        assert_eq!(lines("print inverse(3, 97), is_prime(97)"), vec!["65 true"]);
+    }
+
+    #[test]
+    fn point_arithmetic_matches_the_library() {
+        let curve = Curve::new(2, 3, 97);
+        let p = curve.point(3, 6);
+        let q = curve.point(80, 10);
+        assert!(curve.contains(80, 10));
+        let expected = [
+            Value::Point(&p + &q),
+            Value::Point(&p - &q),
+            Value::Point(&p * 5),
+            Value::Point(-(&p * 5)),
+        ]
+        .map(|v| v.to_string())
+        .join(" ");
+        assert_eq!(
+            lines("let P = point(3, 6)\nlet Q = point(80, 10)\nprint P + Q, P - Q, 5 * P, P * -5"),
+            vec![expected]
+        );
+        let order = curve.order_of(p.coords(), curve.group_order());
+        assert_eq!(
+            lines("let P = point(3, 6)\nprint order(), ord(P)\nassert ord(P) * P == infinity()"),
+            vec![format!("{} {order}", curve.group_order())]
+        );
+    }
+
+    #[test]
+    fn loops_and_conditionals() {
+        let source = "
+            let total = 0
+            for i in 1..6
+              if i % 2 == 1
+                let total = total + i
+              end
+            end
+            print total
+        ";
+        assert_eq!(lines(source), vec!["9"]);
+    }
+
+    #[test]
+    fn ui_functions_produce_actions() {
+        let output = output("curve(1, 1, 599)\npin(point(1, 188))\nui(\"trajectory 1 doublings\")");
+        assert_eq!(output.error, None);
+        assert_eq!(
+            output.actions,
+            vec![
+                Action::EditParams { a: 1, b: 1, p: 599 },
+                Action::Apply,
+                Action::EditNewBase { x: 1, y: 188 },
+                Action::AddPoint,
+                Action::SetTrajectory {
+                    index: 1,
+                    trajectory: Trajectory::Doublings
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn long_weierstrass_curves_and_points() {
+        // 11a1：y² + y = x³ − x² − 10x − 20，(5, 5) 是它的有理点
+        let source = "
+            long_curve(0, -1, 1, -10, -20, 97)
+            let P = long_point(5, 5)
+            assert P == point(71, 24)
+            print ord(P)
+        ";
+        let result = output(source);
+        assert_eq!(result.error, None);
+        assert_eq!(result.lines, vec!["5"]);
+        assert!(result.actions.contains(&Action::EditParams {
+            a: 91,
+            b: 51,
+            p: 97
+        }));
+        let error = output("long_curve(0, -1, 1, -10, -20, 97)\nlong_point(5, 6)").error;
+        assert!(error.unwrap().contains("is not on y² + 0xy + 1y"));
+        let error = output("curve(2, 3, 97)\nlong_point(5, 5)").error;
+        assert!(error.unwrap().contains("needs a curve from long_curve()"));
+    }
+
+    #[test]
+    fn montgomery_curves_and_the_ladder() {
+        // v² = u³ + 3u² + u over F_101；(0, 0) 是 2 阶点
+        let source = "
+            montgomery_curve(3, 1, 101)
+            let T = montgomery_point(0, 0)
+            assert ord(T) == 2
+            assert ladder(3, T) == 0
+            let P = random_point()
+            for k in 1..ord(P)
+              assert ladder(k, P) == (x(k * P) - 1) % p()
+            end
+            print a(), b()
+        ";
+        let result = output(source);
+        assert_eq!(result.error, None);
+        // a = (3 − 9)/3 = −2，b = (54 − 27)/27 = 1；x = u + 1
+        assert_eq!(result.lines, vec!["99 1"]);
+        let error = output("montgomery_curve(3, 1, 101)\nladder(2, montgomery_point(0, 0))").error;
+        assert!(error.unwrap().contains("point at infinity"));
+        let error = output("montgomery_curve(2, 1, 101)").error;
+        assert!(error.unwrap().contains("singular"));
+        let error = output("curve(2, 3, 97)\nmontgomery_point(0, 0)").error;
+        assert!(
+            error
+                .unwrap()
+                .contains("needs a curve from montgomery_curve()")
+        );
+    }
+
+    #[test]
+    fn edwards_points_map_to_the_short_form() {
+        // x² + y² = 1 + 5x²y² over F_97：O = (0, 1)，(0, −1) 是 2 阶点，(1, 0) 是 4 阶点
+        let source = "
+            edwards_curve(1, 5, 97)
+            assert edwards_point(0, 1) == infinity()
+            assert ord(edwards_point(0, -1)) == 2
+            let P = edwards_point(1, 0)
+            print ord(P), 2 * P == edwards_point(0, -1)
+        ";
+        let result = output(source);
+        assert_eq!(result.error, None);
+        assert_eq!(result.lines, vec!["4 true"]);
+        let error = output("edwards_curve(1, 5, 97)\nedwards_point(1, 1)").error;
+        assert!(error.unwrap().contains("is not on 1x² + y² = 1 + 5x²y²"));
+        let error = output("curve(2, 3, 97)\nedwards_point(0, 1)").error;
+        assert!(
+            error
+                .unwrap()
+                .contains("needs a curve from edwards_curve()")
+        );
+    }
+
+    #[test]
+    fn random_functions_are_reproducible() {
+        let source = "random_curve(101)\nlet P = random_point()\nprint a(), b(), P";
+        assert_eq!(lines(source), lines(source));
+        assert_ne!(lines(source), lines(&format!("seed(1)\n{source}")));
+    }
+
+    #[test]
+    fn errors_name_the_line_and_keep_earlier_output() {
+        let partial = output("print 1\n\nlet P = point(1, 1)\nprint 2");
+        assert_eq!(partial.lines, vec!["1"]);
+        assert_eq!(
+            partial.error.as_deref(),
+            Some("line 3: (1, 1) is not on the curve")
+        );
+
+        for (source, error) in [
+            ("print 1 +", "line 1: unexpected end of line"),
+            ("for i in 0..3\nprint i", "line 1: block is missing `end`"),
+            ("end", "line 1: `end` without a block"),
+            (
+                "curve(1, 1, 91)",
+                "line 1: p must be an odd prime up to 1000000, got 91",
+            ),
+            (
+                "curve(0, 0, 97)",
+                "line 1: y^2 = x^3 + 0x + 0 is singular mod 97",
+            ),
+            (
+                "for i in 0..2\n  assert i == 0\nend",
+                "line 2: assertion failed",
+            ),
+            (
+                "print point(3, 6) + 1",
+                "line 1: cannot apply `+` to a point and an integer",
+            ),
+            ("ui(\"jump\")", "line 1: unknown action \"jump\""),
+        ] {
+            assert_eq!(output(source).error.as_deref(), Some(error), "{source}");
+        }
+    }
+
+    #[test]
+    fn commitments_bind_to_the_committed_value() {
+        let source = "\
+curve(1, 1, 599)
+let C = commit(1, 57)
+assert open(C, 1, 57)
+assert open(C, 0, 57) == false
+print commit(1, 57) + commit(2, 3) == commit(3, 60)";
+        assert_eq!(lines(source), vec!["true"]);
+    }
+
+    #[test]
+    fn semicolons_bare_assignments_and_point_literals() {
+        assert_eq!(
+            lines("P = (3, 6); Q = 2 * P\nprint Q == point(80, 10), order(P), (1 + 2) * 3"),
+            vec!["true 5 9"]
+        );
+        assert_eq!(lines("for i in 0..3; print i; end"), vec!["0", "1", "2"]);
+        assert_eq!(
+            output("P = (3, 7)").error.as_deref(),
+            Some("line 1: (3, 7) is not on the curve")
+        );
+    }
+
+    #[test]
+    fn sessions_echo_values_and_keep_variables() {
+        let mut session = Session::new(Curve::new(2, 3, 97), SEED);
+        let output = session.eval("P = (3, 6); Q = (80, 10) + P; order(Q)");
+        assert_eq!(output.error, None);
+        assert_eq!(output.lines, vec!["5"]);
+        assert_eq!(session.eval("Q; pin(P)").lines, vec!["(80, 87)"]);
+        assert_eq!(
+            session.eval("x(Q) + ").error.as_deref(),
+            Some("unexpected end of line")
+        );
+        // 跟着界面换了曲线，旧的点还在变量里
+        session.follow(Curve::new(1, 1, 599));
+        assert_eq!(session.eval("order()").lines, vec!["597"]);
+        assert!(
+            session
+                .eval("P + (1, 188)")
+                .error
+                .unwrap()
+                .starts_with("cannot combine points from different curves")
+        );
+    }
+
+    #[test]
+    fn runaway_loops_are_stopped() {
+        let output = output("print 1\nfor i in 0..1000000000000\nend");
+        assert_eq!(output.lines, vec!["1"]);
+        assert_eq!(
+            output.error,
+            Some(format!("line 2: script ran more than {MAX_STEPS} steps"))
+        );
+    }
+}
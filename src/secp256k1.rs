@@ -0,0 +1,834 @@
+// secp256k1：y² = x³ + 7，定义在 F_p 上，p = 2^256 − 2^32 − 977，Bitcoin 用的曲线。
+// 其余模块的曲线都装在 i64 里，这里单独实现全精度的运算：
+// - 域元素是 4 个 64 位肢，乘积的高 256 位乘 2^256 mod p = 2^32 + 977 折回低位，不做通用除法；
+// - 点用 Jacobian 坐标 (X : Y : Z)，x = X/Z²，y = Y/Z³，加法和倍点都不求逆；
+// - β³ = 1 给出自同态 φ(x, y) = (βx, y) = [λ]P，k 拆成 k1 + k2·λ（各约 128 位）后两路同时做
+//   （GLV，由默认开启的 `glv` feature 控制）；
+// - 固定基点 G 的乘法查预先算好的表，和 fixed_base.rs 一样按 4 位一个窗口。
+// 通用的逐位模约化（mul_generic）保留下来，诊断页面用它和专用约化比较速度。
+#[cfg(feature = "ct")]
+use crate::ct::{Choice, ConditionallySelectable};
+use crate::u256::{
+    Limbs, add_limbs, add_mod, at_least, from_be_bytes, mul_wide, reduce_generic, reduce_once,
+    select, sub_limbs, sub_mod, to_be_bytes,
+};
+use crate::zeroize::Zeroize;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// 小端 64 位肢
+const P: Limbs = [
+    0xfffffffefffffc2f,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+    0xffffffffffffffff,
+];
+
+// G 的阶
+const N: Limbs = [
+    0xbfd25e8cd0364141,
+    0xbaaedce6af48a03b,
+    0xfffffffffffffffe,
+    0xffffffffffffffff,
+];
+
+// 2^256 ≡ 2^32 + 977 (mod p)
+const FOLD: u64 = 0x1_0000_03d1;
+
+const GX: Limbs = [
+    0x59f2815b16f81798,
+    0x029bfcdb2dce28d9,
+    0x55a06295ce870b07,
+    0x79be667ef9dcbbac,
+];
+
+const GY: Limbs = [
+    0x9c47d08ffb10d4b8,
+    0xfd17b448a6855419,
+    0x5da4fbfc0e1108a8,
+    0x483ada7726a3c465,
+];
+
+// β 是 F_p 里的三次单位根，λ 是 Z_n 里对应的那个：φ(P) = (βx, y) = [λ]P
+const BETA: Limbs = [
+    0xc1396c28719501ee,
+    0x9cf0497512f58995,
+    0x6e64479eac3434e9,
+    0x7ae96a2b657c0710,
+];
+
+const LAMBDA: Limbs = [
+    0xdf02967c1b23bd72,
+    0x122e22ea20816678,
+    0xa5261c028812645a,
+    0x5363ad4cc05c30e0,
+];
+
+// 拆分 k 用的格基（−b1、−b2）和 g1 = round(2^384·b2/n)、g2 = round(2^384·(−b1)/n)，
+// 取自 libsecp256k1 的 scalar_split_lambda
+const MINUS_B1: Limbs = [0x6f547fa90abfe4c3, 0xe4437ed6010e8828, 0, 0];
+
+const MINUS_B2: Limbs = [
+    0xd765cda83db1562c,
+    0x8a280ac50774346d,
+    0xfffffffffffffffe,
+    0xffffffffffffffff,
+];
+
+const G1: Limbs = [
+    0xe893209a45dbb031,
+    0x3daa8a1471e8ca7f,
+    0xe86c90e49284eb15,
+    0x3086d221a7d46bcd,
+];
+
+const G2: Limbs = [
+    0x1571b4ae8ac47f71,
+    0x221208ac9df506c6,
+    0x6f547fa90abfe4c4,
+    0xe4437ed6010e8828,
+];
+
+const WINDOW: usize = 4;
+
+// 伪梅森素数的约化：高 256 位乘 2^32 + 977 加回低位，两轮之后只剩一次条件减法
+fn reduce_p(wide: &[u64; 8]) -> Limbs {
+    let mut out = [0u64; 4];
+    let mut carry = 0u128;
+    for i in 0..4 {
+        let t = wide[i] as u128 + wide[i + 4] as u128 * FOLD as u128 + carry;
+        out[i] = t as u64;
+        carry = t >> 64;
+    }
+    // carry < 2^34，再折一次
+    let mut extra = carry * FOLD as u128;
+    for limb in &mut out {
+        let t = *limb as u128 + (extra & u64::MAX as u128);
+        *limb = t as u64;
+        extra = (extra >> 64) + (t >> 64);
+    }
+    // 又越过 2^256（extra = 1）时 out 已经很小，加一次 FOLD 不会再进位；乘上 extra 代替分支
+    let out = add_limbs(&out, &[FOLD * extra as u64, 0, 0, 0]).0;
+    reduce_once(&out, false, &P)
+}
+
+// F_p 的元素，总是约化到 [0, p)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fe(Limbs);
+
+impl Fe {
+    pub const ZERO: Fe = Fe([0; 4]);
+    pub const ONE: Fe = Fe([1, 0, 0, 0]);
+
+    pub fn to_be_bytes(self) -> [u8; 32] {
+        to_be_bytes(&self.0)
+    }
+
+    // 大端 32 字节，不小于 p 时为 None
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Option<Fe> {
+        let limbs = from_be_bytes(bytes);
+        (!at_least(&limbs, &P)).then_some(Fe(limbs))
+    }
+
+    pub fn is_even(self) -> bool {
+        self.0[0] & 1 == 0
+    }
+
+    pub fn square(self) -> Fe {
+        self * self
+    }
+
+    // 同样的乘法走通用约化，只用来和专用约化比较
+    pub fn mul_generic(self, rhs: Fe) -> Fe {
+        Fe(reduce_generic(&mul_wide(&self.0, &rhs.0), &P))
+    }
+
+    // Fermat：a^(p − 2)，0 的逆记为 0
+    pub fn invert(self) -> Fe {
+        self.pow(&sub_limbs(&P, &[2, 0, 0, 0]).0)
+    }
+
+    // p ≡ 3 (mod 4)，a^((p + 1)/4) 平方回来等于 a 时就是平方根
+    pub fn sqrt(self) -> Option<Fe> {
+        let (p_plus_1, _) = add_limbs(&P, &[1, 0, 0, 0]);
+        let mut exponent = [0; 4];
+        for i in 0..4 {
+            exponent[i] = p_plus_1[i] >> 2 | p_plus_1.get(i + 1).map_or(0, |next| next << 62);
+        }
+        let root = self.pow(&exponent);
+        (root.square() == self).then_some(root)
+    }
+
+    // 开启 `ct` feature 时每一位都乘，再按位选结果
+    fn pow(self, exponent: &Limbs) -> Fe {
+        let mut result = Fe::ONE;
+        for bit in (0..256).rev() {
+            result = result.square();
+            let set = (exponent[bit / 64] >> (bit % 64)) & 1 == 1;
+            if cfg!(feature = "ct") {
+                result = Fe(select(&result.0, &(result * self).0, set));
+            } else if set {
+                result = result * self;
+            }
+        }
+        result
+    }
+
+    fn is_zero(self) -> bool {
+        self == Fe::ZERO
+    }
+
+    fn double(self) -> Fe {
+        self + self
+    }
+}
+
+impl Add for Fe {
+    type Output = Fe;
+    fn add(self, rhs: Fe) -> Fe {
+        Fe(add_mod(&self.0, &rhs.0, &P))
+    }
+}
+
+impl Sub for Fe {
+    type Output = Fe;
+    fn sub(self, rhs: Fe) -> Fe {
+        Fe(sub_mod(&self.0, &rhs.0, &P))
+    }
+}
+
+impl Neg for Fe {
+    type Output = Fe;
+    fn neg(self) -> Fe {
+        Fe::ZERO - self
+    }
+}
+
+impl Mul for Fe {
+    type Output = Fe;
+    fn mul(self, rhs: Fe) -> Fe {
+        Fe(reduce_p(&mul_wide(&self.0, &rhs.0)))
+    }
+}
+
+// Z_n 的元素，n 是 G 的阶
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scalar(Limbs);
+
+impl Scalar {
+    pub const ZERO: Scalar = Scalar([0; 4]);
+
+    // 大端 32 字节，模 n 约化（私钥超过 n 的概率约 2^−128）
+    pub fn from_be_bytes(bytes: &[u8; 32]) -> Scalar {
+        let mut wide = [0; 8];
+        wide[..4].copy_from_slice(&from_be_bytes(bytes));
+        Scalar(reduce_generic(&wide, &N))
+    }
+
+    // 不做约化：不小于 n 时为 None，签名里的 s 必须这样读
+    pub fn from_canonical_bytes(bytes: &[u8; 32]) -> Option<Scalar> {
+        let limbs = from_be_bytes(bytes);
+        (!at_least(&limbs, &N)).then_some(Scalar(limbs))
+    }
+
+    pub fn from_u64(value: u64) -> Scalar {
+        Scalar([value, 0, 0, 0])
+    }
+
+    pub fn to_be_bytes(self) -> [u8; 32] {
+        to_be_bytes(&self.0)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self == Scalar::ZERO
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (self.0[index / 64] >> (index % 64)) & 1 == 1
+    }
+
+    // GLV 拆出来的两半绝对值都小于 2^128，最高位是 1 的就是负数。返回 (是否为负, 绝对值)
+    fn signed_half(self) -> (bool, u128) {
+        let negative = self.0[3] >> 63 == 1;
+        let magnitude = if negative { -self } else { self };
+        debug_assert!(magnitude.0[2] == 0 && magnitude.0[3] == 0, "{self:?}");
+        (
+            negative,
+            magnitude.0[0] as u128 | (magnitude.0[1] as u128) << 64,
+        )
+    }
+
+    // k = k1 + k2·λ (mod n)，c1、c2 是 k·g1、k·g2 除以 2^384 后四舍五入
+    fn split_lambda(self) -> ((bool, u128), (bool, u128)) {
+        let shift_384 = |g: &Limbs| {
+            let wide = mul_wide(&self.0, g);
+            let rounded = (wide[6] as u128 | (wide[7] as u128) << 64) + (wide[5] >> 63) as u128;
+            Scalar([rounded as u64, (rounded >> 64) as u64, 0, 0])
+        };
+        let c1 = shift_384(&G1) * Scalar(MINUS_B1);
+        let c2 = shift_384(&G2) * Scalar(MINUS_B2);
+        let k2 = c1 + c2;
+        let k1 = self + -(k2 * Scalar(LAMBDA));
+        (k1.signed_half(), k2.signed_half())
+    }
+}
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Add for Scalar {
+    type Output = Scalar;
+    fn add(self, rhs: Scalar) -> Scalar {
+        Scalar(add_mod(&self.0, &rhs.0, &N))
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Scalar;
+    fn neg(self) -> Scalar {
+        Scalar(sub_mod(&[0; 4], &self.0, &N))
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Scalar;
+    fn mul(self, rhs: Scalar) -> Scalar {
+        Scalar(reduce_generic(&mul_wide(&self.0, &rhs.0), &N))
+    }
+}
+
+// Montgomery 的批量求逆：n 个元素只求一次逆，另加约 3n 次乘法；0 的逆仍记为 0
+pub fn batch_invert(values: &mut [Fe]) {
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut acc = Fe::ONE;
+    for &value in values.iter() {
+        prefix.push(acc);
+        if !value.is_zero() {
+            acc = acc * value;
+        }
+    }
+    let mut inv = acc.invert();
+    for (value, before) in values.iter_mut().zip(prefix).rev() {
+        if !value.is_zero() {
+            let original = *value;
+            *value = inv * before;
+            inv = inv * original;
+        }
+    }
+}
+
+// Jacobian 坐标的点，Z = 0 是无穷远点
+#[derive(Debug, Clone, Copy)]
+pub struct Secp256k1Point {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+}
+
+impl Secp256k1Point {
+    pub const IDENTITY: Secp256k1Point = Secp256k1Point {
+        x: Fe::ONE,
+        y: Fe::ONE,
+        z: Fe::ZERO,
+    };
+
+    pub fn generator() -> Secp256k1Point {
+        Secp256k1Point {
+            x: Fe(GX),
+            y: Fe(GY),
+            z: Fe::ONE,
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    // BIP-340 的 lift_x：横坐标为 x、纵坐标为偶数的点，x³ + 7 不是平方数时为 None
+    pub fn lift_x(x: Fe) -> Option<Secp256k1Point> {
+        let y = (x.square() * x + Fe([7, 0, 0, 0])).sqrt()?;
+        Some(Secp256k1Point {
+            x,
+            y: if y.is_even() { y } else { -y },
+            z: Fe::ONE,
+        })
+    }
+
+    // 仿射坐标，无穷远点为 None
+    pub fn affine(&self) -> Option<(Fe, Fe)> {
+        if self.is_identity() {
+            return None;
+        }
+        let z_inv = self.z.invert();
+        let z_inv2 = z_inv.square();
+        Some((self.x * z_inv2, self.y * z_inv2 * z_inv))
+    }
+
+    // 一组点一起转成仿射坐标，所有 Z 一起求逆
+    pub fn batch_affine(points: &[Secp256k1Point]) -> Vec<Option<(Fe, Fe)>> {
+        let mut z_inv: Vec<Fe> = points.iter().map(|point| point.z).collect();
+        batch_invert(&mut z_inv);
+        points
+            .iter()
+            .zip(z_inv)
+            .map(|(point, z_inv)| {
+                let z_inv2 = z_inv.square();
+                (!point.is_identity()).then(|| (point.x * z_inv2, point.y * z_inv2 * z_inv))
+            })
+            .collect()
+    }
+
+    // madd-2007-bl：rhs 是仿射点（Z = 1），比一般的加法少几次乘法
+    pub fn add_affine(&self, (x2, y2): (Fe, Fe)) -> Secp256k1Point {
+        let rhs = Secp256k1Point {
+            x: x2,
+            y: y2,
+            z: Fe::ONE,
+        };
+        if self.is_identity() {
+            return rhs;
+        }
+        let z1z1 = self.z.square();
+        let u2 = x2 * z1z1;
+        let s2 = y2 * self.z * z1z1;
+        let h = u2 - self.x;
+        if h.is_zero() {
+            return if s2 == self.y {
+                rhs.double()
+            } else {
+                Secp256k1Point::IDENTITY
+            };
+        }
+        let hh = h.square();
+        let i = hh.double().double();
+        let j = h * i;
+        let r = (s2 - self.y).double();
+        let v = self.x * i;
+        let x = r.square() - j - v.double();
+        Secp256k1Point {
+            x,
+            y: r * (v - x) - (self.y * j).double(),
+            z: (self.z + h).square() - z1z1 - hh,
+        }
+    }
+
+    // dbl-2009-l（a = 0）
+    pub fn double(&self) -> Secp256k1Point {
+        if self.is_identity() || self.y.is_zero() {
+            return Secp256k1Point::IDENTITY;
+        }
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = ((self.x + b).square() - a - c).double();
+        let e = a.double() + a;
+        let x = e.square() - d.double();
+        let eight_c = c.double().double().double();
+        Secp256k1Point {
+            x,
+            y: e * (d - x) - eight_c,
+            z: (self.y * self.z).double(),
+        }
+    }
+
+    // φ(P) = (βx, y) = [λ]P，只要一次域乘法
+    fn endomorphism(&self) -> Secp256k1Point {
+        Secp256k1Point {
+            x: self.x * Fe(BETA),
+            ..*self
+        }
+    }
+
+    // 从高位到低位的倍点-加法，作为 GLV 和查表的对照
+    pub fn mul_plain(&self, k: &Scalar) -> Secp256k1Point {
+        let mut result = Secp256k1Point::IDENTITY;
+        for bit in (0..256).rev() {
+            result = result.double();
+            if k.bit(bit) {
+                result = result + *self;
+            }
+        }
+        result
+    }
+
+    // 开启 `ct` feature 时走固定 256 轮的 Montgomery ladder，每轮一次完全加法、一次倍点，
+    // 按 k 的位做条件交换。GLV 的拆分和跳过的加法都依赖 k，这时不用。
+    // secp256k1 的阶是素数，没有 2 阶点，完全公式在这里没有例外
+    #[cfg(feature = "ct")]
+    pub fn mul(&self, k: &Scalar) -> Secp256k1Point {
+        let identity = Projective {
+            x: Fe::ZERO,
+            y: Fe::ONE,
+            z: Fe::ZERO,
+        };
+        // Jacobian 的 (X : Y : Z) 对应齐次坐标 (X·Z : Y : Z³)
+        let base = Projective {
+            x: self.x * self.z,
+            y: self.y,
+            z: self.z.square() * self.z,
+        };
+        let (mut r0, mut r1) = (identity, base);
+        for bit in (0..256).rev() {
+            let choice = Choice::from(u8::from(k.bit(bit)));
+            Projective::conditional_swap(&mut r0, &mut r1, choice);
+            r1 = r0.complete_add(&r1);
+            r0 = r0.complete_add(&r0);
+            Projective::conditional_swap(&mut r0, &mut r1, choice);
+        }
+        // 齐次坐标 (X : Y : Z) 换回 Jacobian 坐标 (X·Z : Y·Z² : Z)
+        Secp256k1Point {
+            x: r0.x * r0.z,
+            y: r0.y * r0.z.square(),
+            z: r0.z,
+        }
+    }
+
+    // 默认走 GLV；关掉 `glv` feature 时退回倍点-加法，方便对照或排查问题
+    #[cfg(not(feature = "ct"))]
+    pub fn mul(&self, k: &Scalar) -> Secp256k1Point {
+        if cfg!(feature = "glv") {
+            self.mul_glv(k)
+        } else {
+            self.mul_plain(k)
+        }
+    }
+
+    // GLV：k·P = k1·P + k2·φ(P)，两个约 128 位的标量共用一串倍点（Shamir 技巧），
+    // 倍点从 256 次减到 128 次
+    pub fn mul_glv(&self, k: &Scalar) -> Secp256k1Point {
+        let ((negative1, k1), (negative2, k2)) = k.split_lambda();
+        let p1 = if negative1 { -*self } else { *self };
+        let p2 = if negative2 {
+            -self.endomorphism()
+        } else {
+            self.endomorphism()
+        };
+        let both = p1 + p2;
+        let mut result = Secp256k1Point::IDENTITY;
+        for bit in (0..128).rev() {
+            result = result.double();
+            match ((k1 >> bit) & 1, (k2 >> bit) & 1) {
+                (1, 1) => result = result + both,
+                (1, 0) => result = result + p1,
+                (0, 1) => result = result + p2,
+                _ => {}
+            }
+        }
+        result
+    }
+}
+
+// 齐次射影坐标 (X : Y : Z)，x = X/Z，y = Y/Z，只在常量时间的 ladder 里用
+#[cfg(feature = "ct")]
+#[derive(Debug, Clone, Copy)]
+struct Projective {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+}
+
+#[cfg(feature = "ct")]
+impl Projective {
+    // Renes–Costello–Batina 的 a = 0 完全加法公式 (Algorithm 7)，b3 = 3·7
+    fn complete_add(&self, rhs: &Projective) -> Projective {
+        let b3 = Fe([21, 0, 0, 0]);
+        let (x1, y1, z1) = (self.x, self.y, self.z);
+        let (x2, y2, z2) = (rhs.x, rhs.y, rhs.z);
+        let t0 = x1 * x2;
+        let t1 = y1 * y2;
+        let t2 = z1 * z2;
+        let t3 = (x1 + y1) * (x2 + y2) - (t0 + t1);
+        let t4 = (y1 + z1) * (y2 + z2) - (t1 + t2);
+        let y3 = (x1 + z1) * (x2 + z2) - (t0 + t2);
+        let t0 = t0.double() + t0;
+        let t2 = b3 * t2;
+        let z3 = t1 + t2;
+        let t1 = t1 - t2;
+        let y3 = b3 * y3;
+        let x3 = t3 * t1 - t4 * y3;
+        let y3 = t1 * z3 + y3 * t0;
+        let z3 = z3 * t4 + t0 * t3;
+        Projective {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+}
+
+#[cfg(feature = "ct")]
+impl ConditionallySelectable for Fe {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Fe(Limbs::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+#[cfg(feature = "ct")]
+impl ConditionallySelectable for Projective {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Projective {
+            x: Fe::conditional_select(&a.x, &b.x, choice),
+            y: Fe::conditional_select(&a.y, &b.y, choice),
+            z: Fe::conditional_select(&a.z, &b.z, choice),
+        }
+    }
+}
+
+// add-2007-bl，P = Q 时转给倍点
+impl Add for Secp256k1Point {
+    type Output = Secp256k1Point;
+    fn add(self, rhs: Secp256k1Point) -> Secp256k1Point {
+        if self.is_identity() {
+            return rhs;
+        }
+        if rhs.is_identity() {
+            return self;
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = rhs.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = rhs.x * z1z1;
+        let s1 = self.y * rhs.z * z2z2;
+        let s2 = rhs.y * self.z * z1z1;
+        let h = u2 - u1;
+        if h.is_zero() {
+            return if s1 == s2 {
+                self.double()
+            } else {
+                Secp256k1Point::IDENTITY
+            };
+        }
+        let i = h.double().square();
+        let j = h * i;
+        let r = (s2 - s1).double();
+        let v = u1 * i;
+        let x = r.square() - j - v.double();
+        Secp256k1Point {
+            x,
+            y: r * (v - x) - (s1 * j).double(),
+            z: ((self.z + rhs.z).square() - z1z1 - z2z2) * h,
+        }
+    }
+}
+
+impl Neg for Secp256k1Point {
+    type Output = Secp256k1Point;
+    fn neg(self) -> Secp256k1Point {
+        Secp256k1Point { y: -self.y, ..self }
+    }
+}
+
+// 射影等价：X1·Z2² = X2·Z1² 且 Y1·Z2³ = Y2·Z1³
+impl PartialEq for Secp256k1Point {
+    fn eq(&self, other: &Secp256k1Point) -> bool {
+        if self.is_identity() || other.is_identity() {
+            return self.is_identity() == other.is_identity();
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        self.x * z2z2 == other.x * z1z1 && self.y * z2z2 * other.z == other.y * z1z1 * self.z
+    }
+}
+
+impl Eq for Secp256k1Point {}
+
+// G 的固定基点表：table[j][d − 1] = d·16^j·G，一次乘法 64 次查表加法，没有倍点。
+// 表项存仿射坐标，建表时 960 个点一起求逆，查表时用混合加法
+pub struct GeneratorTable {
+    table: Vec<Vec<(Fe, Fe)>>,
+}
+
+impl GeneratorTable {
+    pub fn new() -> Self {
+        let mut multiples = Vec::with_capacity(256 / WINDOW * ((1 << WINDOW) - 1));
+        let mut window_base = Secp256k1Point::generator();
+        for _ in 0..256 / WINDOW {
+            let mut multiple = Secp256k1Point::IDENTITY;
+            for _ in 1..1 << WINDOW {
+                multiple = multiple + window_base;
+                multiples.push(multiple);
+            }
+            window_base = multiple + window_base;
+        }
+        let affine: Vec<(Fe, Fe)> = Secp256k1Point::batch_affine(&multiples)
+            .into_iter()
+            .map(|point| point.expect("d·16^j < n, so d·16^j·G is not the identity"))
+            .collect();
+        let table = affine
+            .chunks((1 << WINDOW) - 1)
+            .map(<[(Fe, Fe)]>::to_vec)
+            .collect();
+        GeneratorTable { table }
+    }
+
+    pub fn mul(&self, k: &Scalar) -> Secp256k1Point {
+        let mut result = Secp256k1Point::IDENTITY;
+        for (j, row) in self.table.iter().enumerate() {
+            let digit = (k.0[j * WINDOW / 64] >> (j * WINDOW % 64)) & ((1 << WINDOW) - 1);
+            if digit != 0 {
+                result = result.add_affine(row[digit as usize - 1]);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+    use crate::sha256::hex;
+    use rand_core::RngCore;
+
+    fn random_scalars(count: usize) -> Vec<Scalar> {
+        let mut rng = rng::seeded(256);
+        (0..count)
+            .map(|_| {
+                let mut bytes = [0; 32];
+                rng.fill_bytes(&mut bytes);
+                Scalar::from_be_bytes(&bytes)
+            })
+            .collect()
+    }
+
+    fn affine_hex(point: Secp256k1Point) -> (String, String) {
+        let (x, y) = point.affine().unwrap();
+        (hex(&x.to_be_bytes()), hex(&y.to_be_bytes()))
+    }
+
+    #[test]
+    fn batch_inversion_and_mixed_addition() {
+        let g = Secp256k1Point::generator();
+        let points: Vec<Secp256k1Point> = random_scalars(6)
+            .iter()
+            .map(|k| g.mul_plain(k))
+            .chain([Secp256k1Point::IDENTITY])
+            .collect();
+        let mut values: Vec<Fe> = points.iter().map(|point| point.z).collect();
+        batch_invert(&mut values);
+        for (point, inverse) in points.iter().zip(&values) {
+            assert_eq!(*inverse, point.z.invert());
+        }
+        let affine = Secp256k1Point::batch_affine(&points);
+        assert_eq!(
+            affine,
+            points.iter().map(|p| p.affine()).collect::<Vec<_>>()
+        );
+        let q = affine[0].unwrap();
+        for point in &points {
+            assert_eq!(point.add_affine(q), *point + points[0]);
+        }
+        assert_eq!(points[0].add_affine(q), points[0].double());
+        assert!((-points[0]).add_affine(q).is_identity());
+    }
+
+    #[test]
+    fn fast_reduction_matches_the_generic_one() {
+        let mut rng = rng::seeded(1);
+        let mut values = vec![Fe::ZERO, Fe::ONE, -Fe::ONE, Fe(GX), Fe(GY)];
+        for _ in 0..64 {
+            let mut bytes = [0; 32];
+            rng.fill_bytes(&mut bytes);
+            let limbs = from_be_bytes(&bytes);
+            if !at_least(&limbs, &P) {
+                values.push(Fe(limbs));
+            }
+        }
+        for &a in &values {
+            for &b in &values[..8] {
+                assert_eq!(a * b, a.mul_generic(b), "{a:?} * {b:?}");
+            }
+            if a != Fe::ZERO {
+                assert_eq!(a * a.invert(), Fe::ONE);
+            }
+        }
+        // G 在曲线上
+        let (x, y) = (Fe(GX), Fe(GY));
+        assert_eq!(y.square(), x.square() * x + Fe([7, 0, 0, 0]));
+    }
+
+    #[test]
+    fn known_multiples_of_g() {
+        let g = Secp256k1Point::generator();
+        for (k, x, y) in [
+            (
+                Scalar::from_u64(2),
+                "c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+                "1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a",
+            ),
+            (
+                Scalar::from_u64(3),
+                "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                "388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672",
+            ),
+            (
+                -Scalar::from_u64(1),
+                "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                "b7c52588d95c3b9aa25b0403f1eef75702e84bb7597aabe663b82f6f04ef2777",
+            ),
+            (
+                Scalar([
+                    0x8ee3c2613bf99522,
+                    0x624d4cc0fa157859,
+                    0xa65527f7290311a3,
+                    0xaa5e28d6a97a2479,
+                ]),
+                "34f9460f0e4f08393d192b3c5133a6ba099aa0ad9fd54ebccfacdfa239ff49c6",
+                "0b71ea9bd730fd8923f6d25a7a91e7dd7728a960686cb5a901bb419e0f2ca232",
+            ),
+        ] {
+            let expected = (x.to_string(), y.to_string());
+            assert_eq!(affine_hex(g.mul(&k)), expected);
+            assert_eq!(affine_hex(g.mul_plain(&k)), expected);
+        }
+        // n·G = O
+        assert!(g.mul_plain(&Scalar(N)).is_identity());
+        assert!((g + -g).is_identity());
+        assert_eq!(g + g, g.double());
+    }
+
+    #[test]
+    fn glv_split_recombines_into_small_halves() {
+        for k in random_scalars(64) {
+            let ((negative1, k1), (negative2, k2)) = k.split_lambda();
+            let signed = |negative: bool, v: u128| {
+                let s = Scalar([v as u64, (v >> 64) as u64, 0, 0]);
+                if negative { -s } else { s }
+            };
+            assert_eq!(
+                signed(negative1, k1) + signed(negative2, k2) * Scalar(LAMBDA),
+                k
+            );
+        }
+        let g = Secp256k1Point::generator();
+        assert_eq!(g.endomorphism(), g.mul_plain(&Scalar(LAMBDA)));
+        // λ 本身和 λ 附近的标量拆出来一半是 0 或者很小
+        for k in [
+            Scalar(LAMBDA),
+            Scalar(LAMBDA) + Scalar::from_u64(1),
+            -Scalar(LAMBDA),
+        ] {
+            assert_eq!(g.mul_glv(&k), g.mul_plain(&k));
+        }
+    }
+
+    #[test]
+    fn glv_table_and_double_and_add_agree() {
+        let table = GeneratorTable::new();
+        let g = Secp256k1Point::generator();
+        let point = g.mul_plain(&Scalar::from_u64(0xdead_beef));
+        for k in random_scalars(8).into_iter().chain([
+            Scalar::ZERO,
+            Scalar::from_u64(1),
+            -Scalar::from_u64(1),
+        ]) {
+            let expected = g.mul_plain(&k);
+            assert_eq!(g.mul_glv(&k), expected);
+            assert_eq!(g.mul(&k), expected);
+            assert_eq!(table.mul(&k), expected);
+            assert_eq!(point.mul_glv(&k), point.mul_plain(&k));
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::curve::{Coords, Curve};
+
+// 每个 x 对应 0、1、2 个 y 的个数
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XDistribution {
+    pub no_point: usize,
+    pub one_point: usize,
+    pub two_points: usize,
+    // y = 0 的点，即 2 阶点
+    pub two_torsion: usize,
+}
+
+pub fn x_distribution(curve: &Curve, points: &[Coords]) -> XDistribution {
+    let mut per_x = vec![0u8; curve.p as usize];
+    let mut dist = XDistribution::default();
+    for &(x, y) in points.iter().flatten() {
+        per_x[x as usize] += 1;
+        if y == 0 {
+            dist.two_torsion += 1;
+        }
+    }
+    for count in per_x {
+        match count {
+            0 => dist.no_point += 1,
+            1 => dist.one_point += 1,
+            _ => dist.two_points += 1,
+        }
+    }
+    dist
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: i64,
+    pub max: i64,
+}
+
+pub fn summarize(values: &[i64]) -> Option<Summary> {
+    let count = values.len();
+    if count == 0 {
+        return None;
+    }
+    let mean = values.iter().map(|&v| v as f64).sum::<f64>() / count as f64;
+    let variance = values
+        .iter()
+        .map(|&v| (v as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count as f64;
+    Some(Summary {
+        count,
+        mean,
+        std_dev: variance.sqrt(),
+        min: *values.iter().min().expect("values is non-empty"),
+        max: *values.iter().max().expect("values is non-empty"),
+    })
+}
+
+// k = 1..=k_max 时 kG 的 x 坐标，遇到无穷远点就停下
+pub fn multiples_x(curve: &Curve, g: Coords, k_max: u64) -> Vec<i64> {
+    let mut xs = Vec::new();
+    let mut acc = g;
+    for _ in 0..k_max {
+        let Some((x, _)) = acc else {
+            break;
+        };
+        xs.push(x);
+        acc = curve.add_coords(acc, g);
+    }
+    xs
+}
+
+// 把 [0, p) 平均分成若干个桶
+pub fn histogram(values: &[i64], p: i64, buckets: usize) -> Vec<usize> {
+    let mut counts = vec![0; buckets];
+    for &v in values {
+        let bucket = (v as i128 * buckets as i128 / p as i128) as usize;
+        counts[bucket.min(buckets - 1)] += 1;
+    }
+    counts
+}
@@ -0,0 +1,224 @@
+// 在后台线程里枚举曲线上的点，按 x 分批通过 channel 发回，界面边收边画。
+// p 较大时每一批再按 x 切给多个线程同时算，拼回来的顺序不变。
+// 取消标记在每个 x 之间检查，丢弃 PointStream 时自动取消。
+use crate::curve::Curve;
+use crate::field;
+use crate::point::Point;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+
+// 整个 x 范围大约分成这么多批
+const BATCHES: i64 = 100;
+
+// p 小于这个值时一批只有几百个 x，开线程的开销比枚举本身还大
+const PARALLEL_MIN_P: i64 = 100_000;
+
+// 一批点，以及扫描到的位置（下一批从这个 x 开始）
+struct Batch {
+    points: Vec<Point>,
+    scanned: i64,
+}
+
+pub struct PointStream {
+    p: i64,
+    // 每批拆给几个线程
+    workers: usize,
+    scanned: i64,
+    rx: mpsc::Receiver<Batch>,
+    cancel: Arc<AtomicBool>,
+    finished: bool,
+}
+
+impl PointStream {
+    // 从 x = start 开始扫描，结果与 nonsingular_points() 中 x >= start 的部分顺序相同
+    pub fn spawn(curve: Curve, start: i64) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let token = cancel.clone();
+        let workers = if curve.p < PARALLEL_MIN_P {
+            1
+        } else {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        };
+        std::thread::spawn(move || {
+            let p = curve.p;
+            let _span = tracing::debug_span!("stream_points", p, start).entered();
+            let singular = curve.singularity().map(|s| (s.x(), 0));
+            let roots = field::SqrtTable::new(p);
+            let chunk = (p / BATCHES).max(1);
+            // [from, to) 里的点；中途取消时为 None
+            let scan = |from: i64, to: i64| {
+                let mut points = Vec::new();
+                for x in from..to {
+                    if token.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    points.extend(
+                        curve
+                            .points_with(&roots, x)
+                            .into_iter()
+                            .filter(|point| point.coords() != singular),
+                    );
+                }
+                Some(points)
+            };
+            let mut x = start;
+            while x < p {
+                let end = (x + chunk).min(p);
+                let parts: Option<Vec<Vec<Point>>> = if workers == 1 {
+                    scan(x, end).map(|points| vec![points])
+                } else {
+                    let step = ((end - x) as usize).div_ceil(workers);
+                    std::thread::scope(|scope| {
+                        let handles: Vec<_> = (x..end)
+                            .step_by(step)
+                            .map(|from| {
+                                scope.spawn(move || scan(from, (from + step as i64).min(end)))
+                            })
+                            .collect();
+                        handles
+                            .into_iter()
+                            .map(|handle| handle.join().expect("enumeration worker panicked"))
+                            .collect()
+                    })
+                };
+                let Some(parts) = parts else {
+                    tracing::debug!(x, "enumeration cancelled");
+                    return;
+                };
+                let points = parts.concat();
+                x = end;
+                // 接收端已经丢弃，不用再算
+                if tx.send(Batch { points, scanned: x }).is_err() {
+                    return;
+                }
+            }
+        });
+        PointStream {
+            p: curve.p,
+            workers,
+            scanned: start,
+            rx,
+            cancel,
+            finished: false,
+        }
+    }
+
+    // 把已经收到的批次追加到 points 后面，返回是否有新的点
+    pub fn drain_into(&mut self, points: &mut Vec<Point>) -> bool {
+        let before = points.len();
+        loop {
+            match self.rx.try_recv() {
+                Ok(batch) => {
+                    points.extend(batch.points);
+                    self.scanned = batch.scanned;
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.finished = true;
+                    break;
+                }
+            }
+        }
+        points.len() > before
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    // 已扫描的 x 的比例
+    pub fn progress(&self) -> f32 {
+        self.scanned as f32 / self.p as f32
+    }
+
+    // 下一个要扫描的 x，取消后从这里继续
+    pub fn scanned(&self) -> i64 {
+        self.scanned
+    }
+
+    // 后台线程已经结束：扫完了或者被取消了
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.finished && self.scanned == self.p
+    }
+}
+
+impl Drop for PointStream {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn collect(stream: &mut PointStream) -> Vec<Point> {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let mut points = Vec::new();
+        while !stream.is_finished() {
+            assert!(Instant::now() < deadline, "enumeration did not finish");
+            stream.drain_into(&mut points);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        points
+    }
+
+    #[test]
+    fn streams_the_same_points_in_the_same_order() {
+        for curve in [
+            Curve::new(2, 3, 97),
+            Curve::new(1, 1, 599),
+            Curve::new(0, 0, 23),
+        ] {
+            let mut stream = PointStream::spawn(curve, 0);
+            assert_eq!(collect(&mut stream), curve.nonsingular_points());
+            assert!(stream.is_complete());
+            assert_eq!(stream.progress(), 1.0);
+        }
+    }
+
+    #[test]
+    fn resuming_continues_where_the_scan_stopped() {
+        let curve = Curve::new(1, 1, 599);
+        let mut stream = PointStream::spawn(curve, 300);
+        let tail = collect(&mut stream);
+        let expected: Vec<Point> = curve
+            .nonsingular_points()
+            .into_iter()
+            .filter(|point| point.coords().is_some_and(|(x, _)| x >= 300))
+            .collect();
+        assert_eq!(tail, expected);
+    }
+
+    // p 超过 PARALLEL_MIN_P 时每批拆给多个线程，拼回来的点和顺序都不能变
+    #[test]
+    fn parallel_batches_keep_the_order() {
+        let curve = Curve::new(3, 5, 100_003);
+        let mut stream = PointStream::spawn(curve, 50_000);
+        let expected: Vec<Point> = (50_000..curve.p).flat_map(|x| curve.points_at(x)).collect();
+        assert_eq!(collect(&mut stream), expected);
+        assert!(stream.is_complete());
+    }
+
+    #[test]
+    fn cancelling_stops_the_worker() {
+        // 这条曲线完整枚举需要很久，取消后应该很快结束
+        let curve = Curve::new(1, 1, 1_000_003);
+        let mut stream = PointStream::spawn(curve, 0);
+        stream.cancel();
+        collect(&mut stream);
+        assert!(stream.is_finished() && !stream.is_complete());
+        assert!(stream.scanned() < curve.p);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::curve::{Coords, Curve};
+use crate::error::EcError;
+use crate::field;
+use crate::scalar::Scalar;
+use std::collections::HashMap;
+
+// 群阶最大的素因子 q 和余因子 h = #E / q；任意点乘以 h 都落在 q 阶子群里。
+// 演示里的标量都模 q，所以要求 q 是奇素数
+pub fn prime_order_subgroup(curve: &Curve) -> Result<(u64, u64), String> {
+    if !field::is_prime(curve.p) {
+        return Err(EcError::NotPrime(curve.p).to_string());
+    }
+    if curve.is_singular() {
+        return Err(EcError::SingularCurve.to_string());
+    }
+    let order = curve.group_order();
+    let q = field::factorize(order)
+        .last()
+        .map_or(1, |&(factor, _)| factor);
+    if q < 3 {
+        return Err(format!("group order {order} has no odd prime factor"));
+    }
+    Ok((q, order / q))
+}
+
+// 素数阶子群和一个固定的基点 G，密钥交换和签名都在这里面做
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrimeGroup {
+    pub curve: Curve,
+    pub q: u64,
+    pub cofactor: u64,
+    pub g: Coords,
+}
+
+impl PrimeGroup {
+    // G 取第一个乘以余因子后不是无穷远点的点，同一条曲线每次都一样
+    pub fn new(curve: &Curve) -> Result<Self, String> {
+        let (q, cofactor) = prime_order_subgroup(curve)?;
+        let g = curve
+            .nonsingular_points()
+            .iter()
+            .map(|point| curve.mul_coords(point.coords(), cofactor))
+            .find(Option::is_some)
+            .flatten();
+        Ok(PrimeGroup {
+            curve: *curve,
+            q,
+            cofactor,
+            g,
+        })
+    }
+
+    // 点在 q 阶子群里。余因子为 1 时曲线上的点都在，省掉一次标量乘法
+    pub fn contains(&self, point: Coords) -> bool {
+        let Some((x, y)) = point else {
+            return true;
+        };
+        self.curve.contains(x, y)
+            && (self.cofactor == 1 || self.curve.mul_coords(point, self.q).is_none())
+    }
+
+    pub fn mul(&self, point: Coords, k: Scalar) -> Coords {
+        self.curve.mul_coords(point, k.value())
+    }
+
+    // k·G
+    pub fn base_mul(&self, k: Scalar) -> Coords {
+        self.mul(self.g, k)
+    }
+}
+
+// g 生成的循环子群 <g> = {O, g, 2g, ...}
+pub fn cyclic_subgroup(curve: &Curve, g: Coords) -> Vec<Coords> {
+    let _span = tracing::debug_span!("cyclic_subgroup", ?g).entered();
+    let mut elements = vec![None];
+    let mut acc = g;
+    while acc.is_some() {
+        elements.push(acc);
+        acc = curve.add_coords(acc, g);
+    }
+    elements
+}
+
+// 陪集划分的结果，index[i] 是 points[i] 所在陪集的编号
+#[derive(Debug, Clone)]
+pub struct Cosets {
+    pub index: Vec<usize>,
+    pub subgroup_order: usize,
+    pub count: usize,
+}
+
+// 按子群 H 把点划分成陪集 P + H，编号按第一次出现的顺序分配
+pub fn cosets(curve: &Curve, points: &[Coords], subgroup: &[Coords]) -> Cosets {
+    let _span =
+        tracing::debug_span!("cosets", points = points.len(), subgroup = subgroup.len()).entered();
+    let mut assigned: HashMap<Coords, usize> = HashMap::new();
+    let mut count = 0;
+    let index = points
+        .iter()
+        .map(|&point| {
+            if let Some(&i) = assigned.get(&point) {
+                return i;
+            }
+            for &h in subgroup {
+                assigned.insert(curve.add_coords(point, h), count);
+            }
+            count += 1;
+            count - 1
+        })
+        .collect();
+    Cosets {
+        index,
+        subgroup_order: subgroup.len(),
+        count,
+    }
+}
@@ -0,0 +1,63 @@
+use crate::curve::Curve;
+use crate::field;
+use crate::job::{self, Progress};
+use crate::point::Point;
+use std::collections::BTreeMap;
+
+// 曲线的整体统计信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveSummary {
+    pub group_order: u64,
+    // E ≅ Z_n1 × Z_n2，n2 | n1，循环群时 n2 = 1
+    pub structure: (u64, u64),
+    pub generators: usize,
+    // 阶 -> 该阶的点数（包括无穷远点）
+    pub order_counts: BTreeMap<u64, usize>,
+    pub discriminant: i64,
+    pub j_invariant: Option<i64>,
+}
+
+impl CurveSummary {
+    // 界面在后台线程里算：枚举时报告进度，取消时返回 None
+    pub fn compute_with(curve: &Curve, progress: &Progress) -> Option<Self> {
+        let points = job::nonsingular_points(curve, progress)?;
+        Some(Self::from_points(curve, &points))
+    }
+
+    fn from_points(curve: &Curve, points: &[Point]) -> Self {
+        let _span =
+            tracing::info_span!("curve_summary", a = curve.a, b = curve.b, p = curve.p).entered();
+        let census = curve.census_of(points);
+        let (group_order, exponent) = (census.group_order, census.exponent);
+        let generators = census.counts.get(&group_order).copied().unwrap_or(0);
+
+        tracing::info!(
+            group_order,
+            n1 = exponent,
+            n2 = group_order / exponent,
+            "group structure"
+        );
+
+        let p = curve.p as i128;
+        CurveSummary {
+            group_order,
+            structure: (exponent, group_order / exponent),
+            generators,
+            order_counts: census.counts,
+            // Δ = -16(4a^3 + 27b^2)
+            discriminant: (-16 * curve.discriminant() as i128).rem_euclid(p) as i64,
+            j_invariant: curve.j_invariant(),
+        }
+    }
+
+    pub fn structure_label(&self) -> String {
+        match self.structure {
+            (n1, 1) => format!("Z_{n1}"),
+            (n1, n2) => format!("Z_{n1} × Z_{n2}"),
+        }
+    }
+
+    pub fn is_prime_order(&self) -> bool {
+        field::is_prime(self.group_order as i64)
+    }
+}
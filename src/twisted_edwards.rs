@@ -0,0 +1,257 @@
+// 扭曲 Edwards 曲线 ax² + y² = 1 + dx²y²，a、d 非零且不相等。单位元是 (0, 1)，−(x, y) = (−x, y)，
+// 加法只有一个公式：
+//   x3 = (x1y2 + y1x2) / (1 + d·x1x2y1y2)，y3 = (y1y2 − a·x1x2) / (1 − d·x1x2y1y2)
+// 倍点、加单位元、加逆元都不用分情况。a 是平方、d 不是平方时分母永远不为零（完备），
+// edwards.rs 里 Ed25519 的曲线用的也是这个公式，只是换成了射影坐标。
+// 和 Montgomery 形式双有理等价：A = 2(a + d)/(a − d)，B = 4/(a − d)，u = (1 + y)/(1 − y)，v = u/x。
+use crate::curve::{Coords, Curve};
+use crate::field::{self, mul};
+use crate::montgomery::{Montgomery, MontgomeryReduction};
+use std::fmt;
+
+pub const IDENTITY: (i64, i64) = (0, 1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwistedEdwards {
+    pub a: i64,
+    pub d: i64,
+    pub p: i64,
+}
+
+impl TwistedEdwards {
+    // 系数规范到 [0, p)
+    pub fn new(a: i64, d: i64, p: i64) -> Self {
+        TwistedEdwards {
+            a: a.rem_euclid(p),
+            d: d.rem_euclid(p),
+            p,
+        }
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        let p = self.p;
+        let m = |x: i64, y: i64| mul(x, y, p);
+        let (xx, yy) = (m(x, x), m(y, y));
+        (m(self.a, xx) + yy) % p == (1 + m(self.d, m(xx, yy))) % p
+    }
+
+    // a 是非零平方、d 不是平方时加法公式对所有点都成立
+    pub fn is_complete(&self) -> bool {
+        self.a != 0
+            && field::is_quadratic_residue(self.a, self.p)
+            && !field::is_quadratic_residue(self.d, self.p)
+    }
+
+    // 按 x 排序的全部点：y² = (1 − ax²)/(1 − dx²)
+    pub fn points(&self) -> Vec<(i64, i64)> {
+        let p = self.p;
+        let m = |x: i64, y: i64| mul(x, y, p);
+        let mut points = Vec::new();
+        for x in 0..p {
+            let denominator = (1 - m(self.d, m(x, x))).rem_euclid(p);
+            if denominator == 0 {
+                continue;
+            }
+            let rhs = m(1 - m(self.a, m(x, x)), field::mod_inverse(denominator, p));
+            if let Some(y) = field::sqrt_mod(rhs, p) {
+                points.push((x, y));
+                if y != 0 {
+                    points.push((x, p - y));
+                }
+            }
+        }
+        points
+    }
+
+    pub fn neg(&self, (x, y): (i64, i64)) -> (i64, i64) {
+        ((-x).rem_euclid(self.p), y)
+    }
+
+    // 曲线不完备时分母可能为零，和的结果落在这个模型画不出来的无穷远点上
+    pub fn add(&self, (x1, y1): (i64, i64), (x2, y2): (i64, i64)) -> Option<(i64, i64)> {
+        let p = self.p;
+        let m = |x: i64, y: i64| mul(x, y, p);
+        let t = m(self.d, m(m(x1, x2), m(y1, y2)));
+        let (dx, dy) = ((1 + t) % p, (1 - t).rem_euclid(p));
+        if dx == 0 || dy == 0 {
+            return None;
+        }
+        let x3 = m(m(x1, y2) + m(y1, x2), field::mod_inverse(dx, p));
+        let y3 = m(m(y1, y2) - m(self.a, m(x1, x2)), field::mod_inverse(dy, p));
+        Some((x3, y3))
+    }
+
+    // 倍点和加法是同一个公式
+    pub fn mul(&self, point: (i64, i64), mut k: u64) -> Option<(i64, i64)> {
+        let (mut result, mut addend) = (IDENTITY, point);
+        while k > 0 {
+            if k & 1 == 1 {
+                result = self.add(result, addend)?;
+            }
+            k >>= 1;
+            if k > 0 {
+                addend = self.add(addend, addend)?;
+            }
+        }
+        Some(result)
+    }
+
+    pub fn reduce(&self) -> Result<EdwardsReduction, String> {
+        let p = self.p;
+        if p <= 3 {
+            return Err(format!(
+                "p = {p}: reducing to short form needs characteristic ≠ 2, 3"
+            ));
+        }
+        let (a, d) = (self.a, self.d);
+        if a == 0 || d == 0 || a == d {
+            return Err(format!("{self} is singular: needs ad ≠ 0 and a ≠ d"));
+        }
+        let inv = field::mod_inverse(a - d, p);
+        let montgomery = Montgomery::new(mul(2 * (a + d), inv, p), mul(4, inv, p), p).reduce()?;
+        Ok(EdwardsReduction {
+            edwards: *self,
+            montgomery,
+        })
+    }
+}
+
+impl fmt::Display for TwistedEdwards {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x² + y² = 1 + {}x²y² over F_{}",
+            self.a, self.d, self.p
+        )
+    }
+}
+
+// Edwards 形式、对应的 Montgomery 形式和短形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdwardsReduction {
+    pub edwards: TwistedEdwards,
+    pub montgomery: MontgomeryReduction,
+}
+
+impl EdwardsReduction {
+    pub fn curve(&self) -> Curve {
+        self.montgomery.curve
+    }
+
+    // (0, 1) 是单位元，(0, −1) 是 2 阶点 (0, 0)
+    pub fn map_to_montgomery(&self, (x, y): (i64, i64)) -> Coords {
+        let p = self.edwards.p;
+        if (x, y) == IDENTITY {
+            return None;
+        }
+        if x == 0 {
+            return Some((0, 0));
+        }
+        let u = mul(1 + y, field::mod_inverse(1 - y, p), p);
+        Some((u, mul(u, field::mod_inverse(x, p), p)))
+    }
+
+    // v = 0 或 u = −1 的点（d 是平方时才有）在 Edwards 模型里是无穷远点，返回 None
+    pub fn map_to_edwards(&self, coords: Coords) -> Option<(i64, i64)> {
+        let Some((u, v)) = coords else {
+            return Some(IDENTITY);
+        };
+        let p = self.edwards.p;
+        if (u, v) == (0, 0) {
+            return Some((0, p - 1));
+        }
+        if v == 0 || u == p - 1 {
+            return None;
+        }
+        Some((
+            mul(u, field::mod_inverse(v, p), p),
+            mul(u - 1, field::mod_inverse(u + 1, p), p),
+        ))
+    }
+
+    pub fn map_to_short(&self, point: (i64, i64)) -> Coords {
+        self.montgomery.map_to_short(self.map_to_montgomery(point))
+    }
+
+    pub fn map_from_short(&self, coords: Coords) -> Option<(i64, i64)> {
+        self.map_to_edwards(self.montgomery.map_to_montgomery(coords))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    #[test]
+    fn complete_curves_have_the_same_group_as_the_short_form() {
+        for (a, d, p) in [(1, 7, 599), (1, 5, 97), (4, 12, 101)] {
+            let edwards = TwistedEdwards::new(a, d, p);
+            assert!(edwards.is_complete(), "{edwards}");
+            let reduction = edwards.reduce().unwrap();
+            let curve = reduction.curve();
+            let points = edwards.points();
+            assert!(points.iter().all(|&(x, y)| edwards.contains(x, y)));
+            // 完备的曲线上没有无穷远点，仿射点就是整个群
+            assert_eq!(points.len() as u64, curve.group_order(), "{edwards}");
+            assert!(points.contains(&IDENTITY));
+            for &point in &points {
+                let short = reduction.map_to_short(point);
+                assert!(short.is_none_or(|(x, y)| curve.contains(x, y)));
+                assert_eq!(reduction.map_from_short(short), Some(point));
+            }
+        }
+    }
+
+    #[test]
+    fn one_formula_adds_doubles_and_matches_the_short_form() {
+        let edwards = TwistedEdwards::new(1, 5, 97);
+        let reduction = edwards.reduce().unwrap();
+        let curve = reduction.curve();
+        let points = edwards.points();
+        for &p in &points {
+            assert_eq!(edwards.add(p, IDENTITY), Some(p));
+            assert_eq!(edwards.add(p, edwards.neg(p)), Some(IDENTITY));
+            for &q in &points {
+                let sum = edwards
+                    .add(p, q)
+                    .expect("complete curves never divide by zero");
+                assert_eq!(
+                    reduction.map_to_short(sum),
+                    curve.add_coords(reduction.map_to_short(p), reduction.map_to_short(q)),
+                    "{p:?} + {q:?}"
+                );
+            }
+            let short = reduction.map_to_short(p);
+            for k in [2, 3, 10, 55] {
+                assert_eq!(
+                    edwards.mul(p, k).map(|kp| reduction.map_to_short(kp)),
+                    Some(curve.mul_coords(short, k))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn incomplete_curves_can_hit_a_zero_denominator() {
+        // a = −1 在 p ≡ 3 (mod 4) 时不是平方，和 Ed25519 不同
+        let edwards = TwistedEdwards::new(-1, 5, 103);
+        assert!(!edwards.is_complete());
+        let reduction = edwards.reduce().unwrap();
+        let points = edwards.points();
+        assert!((points.len() as u64) < reduction.curve().group_order());
+        assert!(
+            points
+                .iter()
+                .any(|&p| points.iter().any(|&q| edwards.add(p, q).is_none()))
+        );
+        assert!(TwistedEdwards::new(3, 3, 97).reduce().is_err());
+        assert!(TwistedEdwards::new(0, 3, 97).reduce().is_err());
+        let short: Vec<Point> = reduction.curve().points();
+        assert!(
+            short
+                .iter()
+                .any(|point| reduction.map_from_short(point.coords()).is_none())
+        );
+    }
+}
@@ -0,0 +1,187 @@
+// 256 位无符号整数，4 个小端 64 位肢。secp256k1 和任意素数的 256 位曲线（bigcurve）共用这些运算
+use std::hint::black_box;
+
+pub type Limbs = [u64; 4];
+
+pub fn add_limbs(a: &Limbs, b: &Limbs) -> (Limbs, bool) {
+    let mut out = [0; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (s, c1) = a[i].overflowing_add(b[i]);
+        let (s, c2) = s.overflowing_add(u64::from(carry));
+        out[i] = s;
+        carry = c1 | c2;
+    }
+    (out, carry)
+}
+
+pub fn sub_limbs(a: &Limbs, b: &Limbs) -> (Limbs, bool) {
+    let mut out = [0; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(u64::from(borrow));
+        out[i] = d;
+        borrow = b1 | b2;
+    }
+    (out, borrow)
+}
+
+pub fn at_least(a: &Limbs, b: &Limbs) -> bool {
+    for (x, y) in a.iter().zip(b).rev() {
+        if x != y {
+            return x > y;
+        }
+    }
+    true
+}
+
+pub fn mul_wide(a: &Limbs, b: &Limbs) -> [u64; 8] {
+    let mut wide = [0u64; 8];
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &bj) in b.iter().enumerate() {
+            let t = ai as u128 * bj as u128 + wide[i + j] as u128 + carry;
+            wide[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        wide[i + 4] = carry as u64;
+    }
+    wide
+}
+
+// 对任意 256 位模数适用的约化：从高位到低位逐位移入，够了就减。
+// acc < m 时 2·acc + 1 可能超过 2^256，移出的那一位也算进比较
+pub fn reduce_generic(wide: &[u64; 8], m: &Limbs) -> Limbs {
+    let mut acc = [0u64; 4];
+    for bit in (0..512).rev() {
+        let mut carry = (wide[bit / 64] >> (bit % 64)) & 1;
+        for limb in &mut acc {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        acc = reduce_once(&acc, carry == 1, m);
+    }
+    acc
+}
+
+// flag 为真时取 b。用掩码而不是分支，下面的模加减靠它避免依赖数据的跳转
+pub fn select(a: &Limbs, b: &Limbs, flag: bool) -> Limbs {
+    let mask = 0u64.wrapping_sub(u64::from(black_box(flag)));
+    std::array::from_fn(|i| a[i] ^ (mask & (a[i] ^ b[i])))
+}
+
+// value < 2m（overflow 是移出 2^256 的那一位）时约化到 [0, m)：总是做减法，再按借位选结果
+pub fn reduce_once(value: &Limbs, overflow: bool, m: &Limbs) -> Limbs {
+    let (difference, borrow) = sub_limbs(value, m);
+    select(value, &difference, overflow | !borrow)
+}
+
+// a, b < m
+pub fn add_mod(a: &Limbs, b: &Limbs, m: &Limbs) -> Limbs {
+    let (sum, carry) = add_limbs(a, b);
+    reduce_once(&sum, carry, m)
+}
+
+pub fn sub_mod(a: &Limbs, b: &Limbs, m: &Limbs) -> Limbs {
+    let (difference, borrow) = sub_limbs(a, b);
+    select(&difference, &add_limbs(&difference, m).0, borrow)
+}
+
+pub fn from_be_bytes(bytes: &[u8; 32]) -> Limbs {
+    let mut limbs = [0; 4];
+    for (limb, chunk) in limbs.iter_mut().rev().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_be_bytes(chunk.try_into().expect("8 bytes"));
+    }
+    limbs
+}
+
+pub fn to_be_bytes(limbs: &Limbs) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs.iter().rev()) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    bytes
+}
+
+pub fn bit(limbs: &Limbs, index: usize) -> bool {
+    (limbs[index / 64] >> (index % 64)) & 1 == 1
+}
+
+pub fn leading_zeros(value: &Limbs) -> u32 {
+    value
+        .iter()
+        .rev()
+        .position(|&limb| limb != 0)
+        .map_or(256, |i| i as u32 * 64 + value[3 - i].leading_zeros())
+}
+
+pub fn is_zero(limbs: &Limbs) -> bool {
+    limbs.iter().all(|&limb| limb == 0)
+}
+
+// "0x" 开头按十六进制，否则按十进制；超过 256 位报错
+pub fn parse(text: &str) -> Result<Limbs, String> {
+    let text = text.trim();
+    let (digits, radix) = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (text, 10),
+    };
+    if digits.is_empty() {
+        return Err(format!("{text:?} is not a number"));
+    }
+    let mut value = [0u64; 4];
+    for c in digits.chars().filter(|&c| c != '_') {
+        let digit = c
+            .to_digit(radix)
+            .ok_or_else(|| format!("{text:?} is not a number"))?;
+        // value = value·radix + digit
+        let mut carry = digit as u128;
+        for limb in &mut value {
+            let t = *limb as u128 * radix as u128 + carry;
+            *limb = t as u64;
+            carry = t >> 64;
+        }
+        if carry != 0 {
+            return Err(format!("{text:?} does not fit in 256 bits"));
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_and_decimal() {
+        assert_eq!(parse("0x1_0000_0000_0000_0001"), Ok([1, 1, 0, 0]));
+        assert_eq!(parse("18446744073709551617"), Ok([1, 1, 0, 0]));
+        let max = "0x".to_string() + &"f".repeat(64);
+        assert_eq!(parse(&max), Ok([u64::MAX; 4]));
+        assert!(parse(&(max + "0")).is_err());
+        assert!(parse("12a").is_err());
+        assert!(parse("0x").is_err());
+        assert!(bit(&[0, 2, 0, 0], 65) && !bit(&[0, 2, 0, 0], 64));
+    }
+
+    // 模数接近 2^256 时和会越过 2^256，差会借位；两种情况都要选对结果
+    #[test]
+    fn branch_free_modular_add_and_sub() {
+        let m = [u64::MAX - 188, u64::MAX, u64::MAX, u64::MAX];
+        let minus_one = sub_limbs(&m, &[1, 0, 0, 0]).0;
+        assert_eq!(
+            add_mod(&minus_one, &minus_one, &m),
+            sub_limbs(&m, &[2, 0, 0, 0]).0
+        );
+        assert_eq!(add_mod(&minus_one, &[1, 0, 0, 0], &m), [0; 4]);
+        assert_eq!(add_mod(&[5, 0, 0, 0], &[7, 0, 0, 0], &m), [12, 0, 0, 0]);
+        assert_eq!(sub_mod(&[0; 4], &[1, 0, 0, 0], &m), minus_one);
+        assert_eq!(sub_mod(&[0; 4], &[0; 4], &m), [0; 4]);
+        assert_eq!(sub_mod(&[7, 0, 0, 0], &[5, 0, 0, 0], &m), [2, 0, 0, 0]);
+        assert_eq!(reduce_once(&m, false, &m), [0; 4]);
+        assert_eq!(reduce_once(&[3, 0, 0, 0], true, &m), [192, 0, 0, 0]);
+        assert_eq!(select(&[1; 4], &[2; 4], true), [2; 4]);
+    }
+}
@@ -0,0 +1,619 @@
+// 曲线视图：一条曲线、它的点、追踪的基点和画布图层。主窗口和每个额外的窗口
+// 各持有一个，互不影响，可以把不同的曲线放到不同的显示器上。
+use crate::animation::Frame;
+use crate::cache::{self, LruCache};
+use crate::chord::Chord;
+use crate::curve::{Coords, Curve, Singularity};
+use crate::dlog::Rho;
+use crate::field;
+use crate::layers::{self, Canvas, LayerRegistry, Marker, PlotState};
+use crate::orbit::TrackedPoint;
+use crate::point::Point;
+use crate::stream::PointStream;
+use crate::subgroup::Cosets;
+use crate::viewport::Viewport;
+use eframe::egui;
+
+// 画布和小地图的边长
+const PLOT_SIZE: f32 = 599.0;
+const MINIMAP_SIZE: f32 = 120.0;
+// 单击时离点多近算选中，单位是逻辑像素
+const SELECT_RADIUS: f32 = 8.0;
+
+// 参数面板允许的最大 p。点集按 x 分批在后台枚举，点多时按光栅画，
+// 再往上主要受点集的内存限制（每个点 48 字节）
+pub const MAX_P: i64 = 1_000_000;
+
+// 小地图的缩略图只跟曲线、点数和陪集着色有关，平移缩放时不重画
+type MinimapKey = (Curve, usize, Option<Coords>);
+
+pub struct CurveView {
+    pub curve: Curve,
+    // 已经收到的点；后台枚举还没结束时只是一部分
+    pub points: Vec<Point>,
+    pub tracked: Vec<TrackedPoint>,
+    pub layers: LayerRegistry,
+    // 后台枚举，全部收到后为 None；被取消时保留，用来显示进度和继续
+    stream: Option<PointStream>,
+    // 最近看过的曲线的完整点集，切回去时不用重新枚举
+    pub cache: LruCache<Curve, Vec<Point>>,
+    // 悬停、点选时求过的点的阶
+    pub orders: LruCache<(Curve, (i64, i64)), u64>,
+    // 滚轮缩放、拖动平移；放大时右上角显示整条曲线的小地图
+    pub viewport: Viewport,
+    minimap: Option<(MinimapKey, egui::TextureHandle)>,
+    // 点选的 P、Q，选满两个后再点就重新开始
+    pub selection: Vec<(i64, i64)>,
+    // 由应用每帧填入，见 App::markers
+    pub markers: Vec<Marker>,
+    // k·P 动画的当前一帧，同样由应用填入
+    pub animation: Option<Frame>,
+    // Pollard rho 的游走，同样由应用填入
+    pub walk: Option<Rho>,
+    // 群结构，例如 "Z_50 × Z_2"；统计在后台算完以后由应用填入
+    pub structure: Option<String>,
+}
+
+impl CurveView {
+    pub fn new(curve: Curve, generator: &Point) -> Self {
+        let mut view = CurveView {
+            curve,
+            points: vec![],
+            tracked: vec![],
+            layers: LayerRegistry::default(),
+            stream: None,
+            cache: LruCache::new(cache::POINTS_BUDGET),
+            orders: LruCache::new(cache::ORDERS_BUDGET),
+            viewport: Viewport::full(curve.p),
+            minimap: None,
+            selection: Vec::new(),
+            markers: Vec::new(),
+            animation: None,
+            walk: None,
+            structure: None,
+        };
+        view.set_curve(curve, generator);
+        view
+    }
+
+    pub fn set_curve(&mut self, curve: Curve, generator: &Point) {
+        tracing::info!(a = curve.a, b = curve.b, p = curve.p, "loading curve");
+        if curve != self.curve {
+            self.viewport = Viewport::full(curve.p);
+            self.selection.clear();
+        }
+        self.curve = curve;
+        // 奇异曲线的奇点不属于群，单独标出
+        match self.cache.get(&curve) {
+            Some(points) => {
+                tracing::debug!(count = points.len(), "affine points from cache");
+                self.points = points.clone();
+                self.stream = None;
+            }
+            None => {
+                self.points = vec![];
+                self.stream = Some(PointStream::spawn(curve, 0));
+            }
+        }
+        self.tracked = vec![TrackedPoint::new(&curve, generator.clone(), 0)];
+    }
+
+    pub fn poll_points(&mut self) {
+        if let Some(stream) = &mut self.stream {
+            stream.drain_into(&mut self.points);
+            if stream.is_complete() {
+                // 后台线程的日志不进日志面板，在这里记一条
+                tracing::debug!(
+                    count = self.points.len(),
+                    workers = stream.workers(),
+                    "enumerated affine points"
+                );
+                self.stream = None;
+                self.cache.insert(self.curve, self.points.clone());
+            }
+        }
+    }
+
+    pub fn is_enumerating(&self) -> bool {
+        self.stream
+            .as_ref()
+            .is_some_and(|stream| !stream.is_finished())
+    }
+
+    // 需要完整点集的操作（随机选点、陪集、统计）调用它；枚举没完成时就地算完
+    pub fn all_points(&mut self) -> &[Point] {
+        self.poll_points();
+        if self.stream.take().is_some() {
+            self.points = self.curve.nonsingular_points();
+            self.cache.insert(self.curve, self.points.clone());
+        }
+        &self.points
+    }
+
+    // 枚举进度条，可以取消，取消后可以从停下的位置继续
+    fn stream_controls(&mut self, ui: &mut egui::Ui) {
+        let enumerating = self.is_enumerating();
+        let Some(stream) = &self.stream else {
+            return;
+        };
+        let mut resume = false;
+        ui.horizontal(|ui| {
+            if !enumerating {
+                ui.label(format!(
+                    "Enumeration cancelled at x = {} ({} points so far)",
+                    stream.scanned(),
+                    self.points.len()
+                ));
+                resume = ui.button("Resume").clicked();
+            } else {
+                ui.add(
+                    egui::ProgressBar::new(stream.progress())
+                        .text(format!("{} points", self.points.len()))
+                        .desired_width(300.0),
+                );
+                if ui.button("Cancel").clicked() {
+                    stream.cancel();
+                }
+                // 后台线程不持有 Context，收完之前一直重绘
+                ui.ctx().request_repaint();
+            }
+        });
+        if resume {
+            let start = stream.scanned();
+            self.stream = Some(PointStream::spawn(self.curve, start));
+        }
+    }
+
+    // 滚轮以指针为中心缩放，拖动平移，单击选点
+    fn navigate(&mut self, ui: &egui::Ui, response: &egui::Response, rect: egui::Rect) {
+        let p = self.curve.p;
+        let canvas = Canvas::with_viewport(ui.painter().clone(), rect, self.viewport);
+        if response.clicked()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            self.select_near(&canvas, pointer);
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, zoom) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = zoom * (scroll / 200.0).exp();
+            if factor != 1.0 {
+                self.viewport.zoom(factor, canvas.to_field(pointer), p);
+            }
+        }
+        if response.dragged() {
+            let delta = response.drag_delta() / canvas.step();
+            self.viewport.pan(-delta.x, delta.y, p);
+        }
+    }
+
+    // 离指针最近、在 SELECT_RADIUS 像素以内的点
+    fn point_near(&self, canvas: &Canvas, pointer: egui::Pos2) -> Option<(i64, i64)> {
+        self.points
+            .iter()
+            .filter_map(Point::coords)
+            .map(|(x, y)| ((x, y), canvas.to_screen(x, y).distance(pointer)))
+            .filter(|&(_, distance)| distance <= SELECT_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(point, _)| point)
+    }
+
+    // 点的阶；要等枚举完成拿到群阶，合数模数下没有群结构
+    fn point_order(&mut self, point: (i64, i64)) -> Option<u64> {
+        if self.stream.is_some() || !field::is_prime(self.curve.p) {
+            return None;
+        }
+        let key = (self.curve, point);
+        if let Some(&order) = self.orders.get(&key) {
+            return Some(order);
+        }
+        let group_order = self.points.len() as u64 + 1;
+        let order = self.curve.order_of(Some(point), group_order);
+        self.orders.insert(key, order);
+        Some(order)
+    }
+
+    // 指针附近的点、它的阶和标在它上面的标记；不在点上时只显示坐标
+    fn hover_text(&mut self, canvas: &Canvas, pointer: egui::Pos2) -> String {
+        let Some((x, y)) = self.point_near(canvas, pointer) else {
+            let (x, y) = canvas.to_field(pointer);
+            return format!("x ≈ {x:.1}, y ≈ {y:.1}");
+        };
+        let mut text = match self.point_order((x, y)) {
+            Some(order) => format!("({x}, {y}), order {order}"),
+            None => format!("({x}, {y})"),
+        };
+        let labels: Vec<&str> = self
+            .markers
+            .iter()
+            .filter(|marker| marker.coords == (x, y) && !marker.label.is_empty())
+            .map(|marker| marker.label.as_str())
+            .collect();
+        if !labels.is_empty() {
+            text = format!("{}: {text}", labels.join(", "));
+        }
+        text
+    }
+
+    // 点在空白处清空选择
+    fn select_near(&mut self, canvas: &Canvas, pointer: egui::Pos2) {
+        match self.point_near(canvas, pointer) {
+            Some(point) => {
+                if self.selection.len() == 2 {
+                    self.selection.clear();
+                }
+                self.selection.push(point);
+                tracing::debug!(?point, selected = self.selection.len(), "selected point");
+            }
+            None => self.selection.clear(),
+        }
+    }
+
+    // 选中两个点时说明 P + Q 是怎么得到的
+    fn selection_info(&mut self, ui: &mut egui::Ui) {
+        let text = match *self.selection.as_slice() {
+            [] => return,
+            [(x, y)] => {
+                let order = self
+                    .point_order((x, y))
+                    .map_or(String::new(), |order| format!(" of order {order}"));
+                format!("P = ({x}, {y}){order}; click Q, or P again for the tangent")
+            }
+            [p, q] => match Chord::new(&self.curve, p, q) {
+                Ok(Chord {
+                    slope: Some(slope),
+                    third: Some((x, y)),
+                    sum: Some((_, sum_y)),
+                    ..
+                }) => {
+                    let line = if p == q {
+                        "tangent at P"
+                    } else {
+                        "line through P and Q"
+                    };
+                    format!(
+                        "P + Q = ({x}, {sum_y}): the {line} (λ = {slope}) meets the curve again \
+                         at −(P + Q) = ({x}, {y})"
+                    )
+                }
+                Ok(_) => "Q = −P: the vertical line through P meets no third point, so P + Q = O"
+                    .to_string(),
+                Err(error) => format!("P + Q failed: {error}"),
+            },
+            _ => unreachable!("at most two points are selected"),
+        };
+        ui.horizontal(|ui| {
+            ui.label(text);
+            if ui.button("Clear selection").clicked() {
+                self.selection.clear();
+            }
+        });
+    }
+
+    // 右上角的小地图：整条曲线的缩略图和当前可见区域，点击或拖动把可见区域移过去
+    fn minimap(&mut self, ui: &mut egui::Ui, plot: egui::Rect, cosets: Option<(Coords, &Cosets)>) {
+        let p = self.curve.p;
+        let rect = egui::Rect::from_min_size(
+            egui::pos2(plot.max.x - MINIMAP_SIZE - 6.0, plot.min.y + 6.0),
+            egui::vec2(MINIMAP_SIZE, MINIMAP_SIZE),
+        );
+        let response = ui.interact(rect, ui.id().with("minimap"), egui::Sense::click_and_drag());
+        response.widget_info(|| {
+            egui::WidgetInfo::labeled(egui::WidgetType::Other, ui.is_enabled(), "Minimap")
+        });
+        let full = Canvas::new(ui.painter_at(rect), rect, p);
+        if (response.clicked() || response.dragged())
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            let (x, y) = full.to_field(pointer);
+            self.viewport.center_on(x, y, p);
+        }
+
+        let key = (self.curve, self.points.len(), cosets.map(|(base, _)| base));
+        if self
+            .minimap
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != key)
+        {
+            let state = PlotState {
+                curve: &self.curve,
+                points: &self.points,
+                tracked: &[],
+                cosets,
+                field_base: None,
+                highlight: None,
+                selection: &[],
+                markers: &[],
+                animation: None,
+                walk: None,
+            };
+            let pixels = (MINIMAP_SIZE * ui.ctx().pixels_per_point()).round() as usize;
+            let image = layers::rasterize(&state, Viewport::full(p), [pixels, pixels], 1.0);
+            let texture = ui
+                .ctx()
+                .load_texture("minimap", image, egui::TextureOptions::LINEAR);
+            self.minimap = Some((key, texture));
+        }
+        let (_, texture) = self.minimap.as_ref().expect("minimap was just rendered");
+        let viewport = self.viewport;
+        let painter = &full.painter;
+        painter.rect_filled(rect, 2.0, egui::Color32::from_white_alpha(230));
+        painter.image(
+            texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        painter.rect_stroke(
+            rect,
+            2.0,
+            egui::Stroke::new(1.0, egui::Color32::DARK_GRAY),
+            egui::StrokeKind::Inside,
+        );
+        let corner = full.to_screen(0, 0);
+        let step = full.step();
+        let visible = egui::Rect::from_min_max(
+            egui::pos2(
+                corner.x + viewport.x * step,
+                corner.y - (viewport.y + viewport.span) * step,
+            ),
+            egui::pos2(
+                corner.x + (viewport.x + viewport.span) * step,
+                corner.y - viewport.y * step,
+            ),
+        );
+        painter.rect_stroke(
+            visible,
+            0.0,
+            egui::Stroke::new(1.5, egui::Color32::from_rgb(0, 120, 220)),
+            egui::StrokeKind::Outside,
+        );
+    }
+
+    // 标题、奇异曲线的说明、画布和追踪点的图例
+    pub fn plot(
+        &mut self,
+        ui: &mut egui::Ui,
+        cosets: Option<(Coords, &Cosets)>,
+        field_base: Option<Coords>,
+        highlight: Option<&[Coords]>,
+    ) {
+        let curve = self.curve;
+        let structure = self
+            .structure
+            .as_ref()
+            .map_or(String::new(), |structure| format!(", E ≅ {structure}"));
+        ui.heading(format!(
+            "Elliptic Curve Points (y^2 = x^3 + {}x + {} mod {}){structure}",
+            curve.a, curve.b, curve.p
+        ));
+        if let Some(singularity) = curve.singularity() {
+            singular_info(ui, singularity, curve.p);
+        }
+        self.poll_points();
+        self.stream_controls(ui);
+
+        if self.viewport.is_zoomed(curve.p) {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Zoom {:.1}×: x ∈ [{:.0}, {:.0}], y ∈ [{:.0}, {:.0}]",
+                    self.viewport.magnification(curve.p),
+                    self.viewport.x,
+                    self.viewport.x + self.viewport.span,
+                    self.viewport.y,
+                    self.viewport.y + self.viewport.span
+                ));
+                if ui.button("Reset zoom").clicked() {
+                    self.viewport = Viewport::full(curve.p);
+                }
+            });
+        }
+
+        // 绘制点的区域
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(PLOT_SIZE, PLOT_SIZE),
+            egui::Sense::click_and_drag(),
+        );
+        response.widget_info(|| {
+            egui::WidgetInfo::labeled(egui::WidgetType::Other, ui.is_enabled(), "Plot")
+        });
+        self.navigate(ui, &response, rect);
+        let canvas = Canvas::with_viewport(ui.painter_at(rect), rect, self.viewport);
+        if let Some(pointer) = response.hover_pos() {
+            response.on_hover_text_at_pointer(self.hover_text(&canvas, pointer));
+        }
+        let state = PlotState {
+            curve: &self.curve,
+            points: &self.points,
+            tracked: &self.tracked,
+            cosets,
+            field_base,
+            highlight,
+            selection: &self.selection,
+            markers: &self.markers,
+            animation: self.animation.as_ref(),
+            walk: self.walk.as_ref(),
+        };
+        self.layers.paint(&state, &canvas);
+        if self.viewport.is_zoomed(curve.p) {
+            self.minimap(ui, rect, cosets);
+        }
+        self.selection_info(ui);
+
+        for tracked in &self.tracked {
+            ui.colored_label(tracked.color, tracked.label());
+        }
+    }
+}
+
+// 额外窗口里的视图，带一组自己的参数输入
+pub struct ViewWindow {
+    pub id: usize,
+    pub view: CurveView,
+    pub open: bool,
+    params: (i64, i64, i64),
+    error: Option<String>,
+}
+
+impl ViewWindow {
+    pub fn new(id: usize, curve: Curve, generator: &Point) -> Self {
+        ViewWindow {
+            id,
+            view: CurveView::new(curve, generator),
+            open: true,
+            params: (curve.a, curve.b, curve.p),
+            error: None,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        format!("View {}", self.id)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let (a, b, p) = &mut self.params;
+            let label = ui.label("a:");
+            ui.add(egui::DragValue::new(a).range(0..=*p - 1))
+                .labelled_by(label.id);
+            let label = ui.label("b:");
+            ui.add(egui::DragValue::new(b).range(0..=*p - 1))
+                .labelled_by(label.id);
+            let label = ui.label("p:");
+            ui.add(egui::DragValue::new(p).range(3..=MAX_P))
+                .labelled_by(label.id);
+            if ui.button("Apply").clicked() {
+                self.apply();
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+        let curve = self.view.curve;
+        let tracked = &mut self.view.tracked[0];
+        ui.horizontal(|ui| {
+            let label = ui.label("k:");
+            if ui
+                .add(egui::DragValue::new(&mut tracked.k).range(1..=tracked.trajectory.max_k()))
+                .labelled_by(label.id)
+                .changed()
+            {
+                tracked.recompute(&curve);
+            }
+        });
+        ui.collapsing("Layers", |ui| self.view.layers.ui(ui));
+        self.view.plot(ui, None, None, None);
+    }
+
+    // 额外的视图只接受素数模上有仿射点的非奇异曲线，奇异和合数的情形留给主窗口
+    fn apply(&mut self) {
+        let (a, b, p) = self.params;
+        let generator = Curve::try_new(a, b, p)
+            .map_err(|error| error.to_string())
+            .and_then(|curve| {
+                let generator = curve
+                    .first_point()
+                    .ok_or_else(|| "curve has no affine points".to_string())?;
+                Ok((curve, generator))
+            });
+        match generator {
+            Ok((curve, generator)) => {
+                self.error = None;
+                self.view.set_curve(curve, &generator);
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+}
+
+// 说明奇异曲线上去掉奇点后剩下的群是什么
+fn singular_info(ui: &mut egui::Ui, singularity: Singularity, p: i64) {
+    let order = singularity.group_order(p);
+    let text = match singularity {
+        Singularity::Cusp { x } => format!(
+            "Singular curve with a cusp at ({x}, 0): y² = (x − {x})³. The non-singular points \
+             still form a group, isomorphic to (F_p, +) via (x, y) ↦ (x − {x}) / y, of order p = {order}."
+        ),
+        Singularity::Node { x, split: true } => format!(
+            "Singular curve with a split node at ({x}, 0): the tangent slopes ±α with α² = 3·{x} \
+             lie in F_p. The non-singular points form a group isomorphic to F_p^* via \
+             (x, y) ↦ (y + α(x − {x})) / (y − α(x − {x})), of order p − 1 = {order}."
+        ),
+        Singularity::Node { x, split: false } => format!(
+            "Singular curve with a non-split node at ({x}, 0): 3·{x} is not a square mod p, so the \
+             tangent slopes live in F_p². The non-singular points form a group isomorphic to the \
+             norm-1 subgroup of F_p²^*, of order p + 1 = {order}."
+        ),
+    };
+    ui.colored_label(egui::Color32::from_rgb(200, 120, 0), text);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window() -> ViewWindow {
+        let curve = Curve::new(1, 1, 599);
+        ViewWindow::new(1, curve, &curve.point(1, 188))
+    }
+
+    #[test]
+    fn apply_loads_a_new_curve_with_a_generator() {
+        let mut window = window();
+        window.params = (2, 3, 97);
+        window.apply();
+        assert_eq!(window.error, None);
+        assert_eq!(window.view.curve, Curve::new(2, 3, 97));
+        let curve = window.view.curve;
+        assert_eq!(window.view.all_points(), curve.points());
+        assert_eq!(window.view.tracked.len(), 1);
+    }
+
+    #[test]
+    fn cancelled_enumeration_is_completed_on_demand() {
+        let curve = Curve::new(1, 1, 599);
+        let mut view = CurveView::new(curve, &curve.point(1, 188));
+        view.stream.as_ref().unwrap().cancel();
+        while view.is_enumerating() {
+            view.poll_points();
+        }
+        assert!(view.points.len() <= curve.nonsingular_points().len());
+        assert_eq!(view.all_points(), curve.nonsingular_points());
+        assert!(view.stream.is_none());
+    }
+
+    #[test]
+    fn hovering_a_marker_names_it() {
+        let curve = Curve::new(2, 3, 97);
+        let mut view = CurveView::new(curve, &curve.point(3, 6));
+        view.all_points();
+        view.markers = vec![Marker {
+            coords: (3, 6),
+            label: "G".to_string(),
+            color: egui::Color32::BLACK,
+        }];
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(97.0, 97.0));
+        let painter =
+            egui::Painter::new(egui::Context::default(), egui::LayerId::background(), rect);
+        let canvas = Canvas::new(painter, rect, curve.p);
+        let mut at = |x, y| view.hover_text(&canvas, canvas.to_screen(x, y));
+        assert_eq!(at(3, 6), "G: (3, 6), order 5");
+        assert_eq!(at(80, 10), "(80, 10), order 5");
+        assert_eq!(at(50, 50), "x ≈ 50.0, y ≈ 50.0");
+        // 求过的阶留在缓存里
+        assert_eq!(view.orders.peek(&(curve, (80, 10))), Some(&5));
+    }
+
+    #[test]
+    fn apply_rejects_curves_the_view_cannot_show() {
+        for (params, error) in [
+            ((1, 1, 91), "modulus 91 is not prime"),
+            ((0, 0, 97), "curve is singular (4a^3 + 27b^2 = 0 mod p)"),
+        ] {
+            let mut window = window();
+            window.params = params;
+            window.apply();
+            assert_eq!(window.error.as_deref(), Some(error));
+            assert_eq!(window.view.curve, Curve::new(1, 1, 599));
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// 画布上可见的区域，单位是域元素：左下角 (x, y)，边长 span。
+// span = p 时看到整条曲线；缩放和平移都会把区域限制在 [0, p]² 里面。
+
+// 最多放大到一屏只看这么多个格子
+const MIN_SPAN: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub span: f32,
+}
+
+impl Viewport {
+    pub fn full(p: i64) -> Self {
+        Viewport {
+            x: 0.0,
+            y: 0.0,
+            span: p as f32,
+        }
+    }
+
+    pub fn is_zoomed(&self, p: i64) -> bool {
+        self.span < p as f32
+    }
+
+    // 放大倍数，整条曲线是 1
+    pub fn magnification(&self, p: i64) -> f32 {
+        p as f32 / self.span
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        let (x, y) = (x as f32, y as f32);
+        x >= self.x && x < self.x + self.span && y >= self.y && y < self.y + self.span
+    }
+
+    // 以 anchor 为不动点缩放，factor > 1 是放大
+    pub fn zoom(&mut self, factor: f32, anchor: (f32, f32), p: i64) {
+        let span = (self.span / factor).clamp(MIN_SPAN.min(p as f32), p as f32);
+        let ratio = span / self.span;
+        self.x = anchor.0 - (anchor.0 - self.x) * ratio;
+        self.y = anchor.1 - (anchor.1 - self.y) * ratio;
+        self.span = span;
+        self.clamp(p);
+    }
+
+    pub fn pan(&mut self, dx: f32, dy: f32, p: i64) {
+        self.x += dx;
+        self.y += dy;
+        self.clamp(p);
+    }
+
+    // 小地图上点哪里就把哪里移到中间
+    pub fn center_on(&mut self, x: f32, y: f32, p: i64) {
+        self.x = x - self.span / 2.0;
+        self.y = y - self.span / 2.0;
+        self.clamp(p);
+    }
+
+    fn clamp(&mut self, p: i64) {
+        let limit = p as f32 - self.span;
+        self.x = self.x.clamp(0.0, limit);
+        self.y = self.y.clamp(0.0, limit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_keeps_the_anchor_in_place() {
+        let mut viewport = Viewport::full(100);
+        assert!(!viewport.is_zoomed(100));
+        viewport.zoom(4.0, (60.0, 20.0), 100);
+        assert_eq!(viewport.span, 25.0);
+        assert_eq!((viewport.x, viewport.y), (45.0, 15.0));
+        assert_eq!(viewport.magnification(100), 4.0);
+        assert!(viewport.contains(60, 20) && !viewport.contains(70, 20));
+
+        // 缩放有上下限
+        viewport.zoom(100.0, (60.0, 20.0), 100);
+        assert_eq!(viewport.span, MIN_SPAN);
+        viewport.zoom(0.01, (60.0, 20.0), 100);
+        assert_eq!(viewport, Viewport::full(100));
+    }
+
+    #[test]
+    fn panning_stays_inside_the_field() {
+        let mut viewport = Viewport::full(100);
+        viewport.zoom(2.0, (50.0, 50.0), 100);
+        viewport.pan(-80.0, 10.0, 100);
+        assert_eq!((viewport.x, viewport.y), (0.0, 35.0));
+        viewport.center_on(95.0, 95.0, 100);
+        assert_eq!((viewport.x, viewport.y), (50.0, 50.0));
+        viewport.center_on(40.0, 30.0, 100);
+        assert_eq!((viewport.x, viewport.y), (15.0, 5.0));
+    }
+}
@@ -0,0 +1,164 @@
+// 一般（长）Weierstrass 方程 y² + a1·xy + a3·y = x³ + a2·x² + a4·x + a6。特征不是 2、3 时，
+// 配方消去 a1、a3，再平移 x 消去 a2：X = 36x + 3b2，Y = 108(2y + a1·x + a3)，
+// 得到短形式 Y² = X³ − 27c4·X − 54c6。保留这组变量替换，点可以在两种形式之间来回映射。
+use crate::curve::{Coords, Curve};
+use crate::field::{self, mul};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LongWeierstrass {
+    pub a1: i64,
+    pub a2: i64,
+    pub a3: i64,
+    pub a4: i64,
+    pub a6: i64,
+    pub p: i64,
+}
+
+impl LongWeierstrass {
+    // 系数规范到 [0, p)
+    pub fn new([a1, a2, a3, a4, a6]: [i64; 5], p: i64) -> Self {
+        let r = |v: i64| v.rem_euclid(p);
+        LongWeierstrass {
+            a1: r(a1),
+            a2: r(a2),
+            a3: r(a3),
+            a4: r(a4),
+            a6: r(a6),
+            p,
+        }
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        let p = self.p;
+        let m = |a: i64, b: i64| mul(a, b, p);
+        let lhs = m(y, y) + m(self.a1, m(x, y)) + m(self.a3, y);
+        let rhs = m(m(x, x), x) + m(self.a2, m(x, x)) + m(self.a4, x) + self.a6;
+        (lhs - rhs).rem_euclid(p) == 0
+    }
+
+    // b2 = a1² + 4a2，b4 = 2a4 + a1a3，b6 = a3² + 4a6
+    pub fn b_invariants(&self) -> (i64, i64, i64) {
+        let p = self.p;
+        let m = |a: i64, b: i64| mul(a, b, p);
+        (
+            (m(self.a1, self.a1) + 4 * self.a2) % p,
+            (2 * self.a4 + m(self.a1, self.a3)) % p,
+            (m(self.a3, self.a3) + 4 * self.a6) % p,
+        )
+    }
+
+    pub fn reduce(&self) -> Result<Reduction, String> {
+        let p = self.p;
+        if p <= 3 {
+            return Err(format!(
+                "p = {p}: reducing to short form needs characteristic ≠ 2, 3"
+            ));
+        }
+        let m = |a: i64, b: i64| mul(a, b, p);
+        let (b2, b4, b6) = self.b_invariants();
+        // c4 = b2² − 24b4，c6 = −b2³ + 36b2b4 − 216b6
+        let c4 = (m(b2, b2) - 24 * b4).rem_euclid(p);
+        let c6 = (-m(m(b2, b2), b2) + 36 * m(b2, b4) - 216 * b6).rem_euclid(p);
+        Ok(Reduction {
+            long: *self,
+            curve: Curve::new(-m(27, c4), -m(54, c6), p),
+            b2,
+        })
+    }
+}
+
+impl fmt::Display for LongWeierstrass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "y² + {}xy + {}y = x³ + {}x² + {}x + {} over F_{}",
+            self.a1, self.a3, self.a2, self.a4, self.a6, self.p
+        )
+    }
+}
+
+// 长形式和化简后的短形式，以及两者之间的变量替换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reduction {
+    pub long: LongWeierstrass,
+    pub curve: Curve,
+    b2: i64,
+}
+
+impl Reduction {
+    // (x, y) ↦ (36x + 3b2, 108(2y + a1·x + a3))
+    pub fn map_to_short(&self, coords: Coords) -> Coords {
+        let (x, y) = coords?;
+        let p = self.long.p;
+        let m = |a: i64, b: i64| mul(a, b, p);
+        let long = &self.long;
+        Some((
+            (m(36, x) + 3 * self.b2).rem_euclid(p),
+            m(108, 2 * y + m(long.a1, x) + long.a3),
+        ))
+    }
+
+    // x = (X − 3b2) / 36，y = (Y / 108 − a1·x − a3) / 2
+    pub fn map_to_long(&self, coords: Coords) -> Coords {
+        let (big_x, big_y) = coords?;
+        let p = self.long.p;
+        let m = |a: i64, b: i64| mul(a, b, p);
+        let inv = |v: i64| field::mod_inverse(v, p);
+        let long = &self.long;
+        let x = m(big_x - 3 * self.b2, inv(36));
+        let y = m(m(big_y, inv(108)) - m(long.a1, x) - long.a3, inv(2));
+        Some((x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    // 长形式上的仿射点，逐个试 (x, y)
+    fn long_points(long: &LongWeierstrass) -> Vec<(i64, i64)> {
+        (0..long.p)
+            .flat_map(|x| (0..long.p).map(move |y| (x, y)))
+            .filter(|&(x, y)| long.contains(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn points_map_back_and_forth() {
+        // Cremona 11a1、37a1、53a1：y² + y = x³ − x² − 10x − 20，y² + y = x³ − x，y² + xy + y = x³ − x²
+        for coefficients in [[0, -1, 1, -10, -20], [0, 0, 1, -1, 0], [1, -1, 1, 0, 0]] {
+            for p in [97, 101, 599] {
+                let long = LongWeierstrass::new(coefficients, p);
+                let reduction = long.reduce().unwrap();
+                let short: Vec<Coords> =
+                    reduction.curve.points().iter().map(Point::coords).collect();
+                let points = long_points(&long);
+                assert_eq!(points.len(), short.len(), "{long}");
+                for (x, y) in points {
+                    let image = reduction.map_to_short(Some((x, y)));
+                    assert!(short.contains(&image), "{long}: ({x}, {y})");
+                    assert_eq!(reduction.map_to_long(image), Some((x, y)));
+                }
+                assert_eq!(reduction.map_to_short(None), None);
+            }
+        }
+    }
+
+    #[test]
+    fn short_form_is_left_alone_up_to_scaling() {
+        // a1 = a2 = a3 = 0 时 X = 36x，Y = 216y，j 不变量不变
+        let long = LongWeierstrass::new([0, 0, 0, 2, 3], 97);
+        let reduction = long.reduce().unwrap();
+        assert_eq!(
+            reduction.curve.j_invariant(),
+            Curve::new(2, 3, 97).j_invariant()
+        );
+        assert_eq!(
+            reduction.map_to_short(Some((3, 6))),
+            Some((36 * 3 % 97, 216 * 6 % 97))
+        );
+        assert!(LongWeierstrass::new([1, 0, 0, 0, 1], 3).reduce().is_err());
+    }
+}
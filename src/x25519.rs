@@ -0,0 +1,121 @@
+// X25519（RFC 7748）：Curve25519 是 Montgomery 曲线 v² = u³ + 486662u² + u，定义在
+// F_(2^255 − 19) 上。只用 u 坐标：标量先 clamp（清掉低 3 位消去余因子 8，固定最高位），
+// 再走 255 轮 Montgomery ladder（公式在 montgomery.rs），交换只用掩码。
+use crate::field25519::Fe;
+use crate::montgomery::ladder_step;
+
+// 基点 u = 9
+pub const BASE_POINT: [u8; 32] = {
+    let mut u = [0; 32];
+    u[0] = 9;
+    u
+};
+
+// (A − 2) / 4
+const A24: u32 = 121665;
+
+// decodeScalar25519
+pub fn clamp(mut scalar: [u8; 32]) -> [u8; 32] {
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    scalar
+}
+
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let k = clamp(*scalar);
+    // decodeUCoordinate：最高位忽略，≥ p 的值照常约化
+    let x1 = Fe::from_bytes(u);
+    let (mut x2, mut z2, mut x3, mut z3) = (Fe::ONE, Fe::ZERO, x1, Fe::ONE);
+    let mut swap = 0;
+    for t in (0..255).rev() {
+        let bit = u64::from((k[t / 8] >> (t % 8)) & 1);
+        swap ^= bit;
+        Fe::conditional_swap(&mut x2, &mut x3, swap);
+        Fe::conditional_swap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        ((x2, z2), (x3, z3)) = ladder_step(x1, |e| e.mul_small(A24), (x2, z2), (x3, z3));
+    }
+    Fe::conditional_swap(&mut x2, &mut x3, swap);
+    Fe::conditional_swap(&mut z2, &mut z3, swap);
+    (x2 * z2.invert()).to_bytes()
+}
+
+// 私钥对应的公钥 X25519(k, 9)
+pub fn public_key(secret: &[u8; 32]) -> [u8; 32] {
+    x25519(secret, &BASE_POINT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(hex: &str) -> [u8; 32] {
+        let mut out = [0; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    // RFC 7748 第 5.2 节
+    #[test]
+    fn rfc7748_vectors() {
+        let vectors = [
+            (
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+            ),
+            (
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                // 最高位是 1，解码时要忽略
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+            ),
+        ];
+        for (scalar, u, expected) in vectors {
+            assert_eq!(x25519(&bytes(scalar), &bytes(u)), bytes(expected));
+        }
+    }
+
+    // 第 5.2 节的迭代：k、u 从 9 开始，每轮 (k, u) ← (X25519(k, u), k)
+    #[test]
+    fn rfc7748_iterations() {
+        let (mut k, mut u) = (BASE_POINT, BASE_POINT);
+        for round in 1..=1000 {
+            (k, u) = (x25519(&k, &u), k);
+            if round == 1 {
+                assert_eq!(
+                    k,
+                    bytes("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+                );
+            }
+        }
+        assert_eq!(
+            k,
+            bytes("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51")
+        );
+    }
+
+    // 第 6.1 节的 Diffie-Hellman
+    #[test]
+    fn rfc7748_key_agreement() {
+        let alice = bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let alice_public = public_key(&alice);
+        let bob_public = public_key(&bob);
+        assert_eq!(
+            alice_public,
+            bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+        );
+        assert_eq!(
+            bob_public,
+            bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+        );
+        let shared = bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519(&alice, &bob_public), shared);
+        assert_eq!(x25519(&bob, &alice_public), shared);
+    }
+}
@@ -0,0 +1,64 @@
+// 秘密数据清零
+//
+// 与 zeroize crate 的 Zeroize / ZeroizeOnDrop 同名同义：用 volatile 写入加编译器屏障，
+// 防止"写了之后再也不读"的清零被优化掉。
+use std::ptr;
+use std::sync::atomic::{Ordering, compiler_fence};
+
+pub trait Zeroize {
+    fn zeroize(&mut self);
+}
+
+// 标记 trait：实现者在 Drop 时会自动清零
+pub trait ZeroizeOnDrop {}
+
+fn volatile_set<T: Copy>(dst: &mut T, value: T) {
+    // SAFETY: dst 来自可变引用，保证对齐且有效
+    unsafe { ptr::write_volatile(dst, value) };
+    compiler_fence(Ordering::SeqCst);
+}
+
+macro_rules! impl_zeroize_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl Zeroize for $t {
+                fn zeroize(&mut self) {
+                    volatile_set(self, 0);
+                }
+            }
+        )*
+    };
+}
+
+impl_zeroize_for_int!(u8, u64, i64, u128, i128);
+
+impl<T: Zeroize> Zeroize for [T] {
+    fn zeroize(&mut self) {
+        for item in self.iter_mut() {
+            item.zeroize();
+        }
+    }
+}
+
+impl<T: Zeroize> Zeroize for Vec<T> {
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+        self.clear();
+    }
+}
+
+impl<T: Zeroize> Zeroize for Option<T> {
+    fn zeroize(&mut self) {
+        if let Some(value) = self {
+            value.zeroize();
+        }
+        *self = None;
+    }
+}
+
+impl Zeroize for String {
+    fn zeroize(&mut self) {
+        // SAFETY: 全部写成 0 仍是合法的 UTF-8
+        unsafe { self.as_mut_vec() }.zeroize();
+    }
+}
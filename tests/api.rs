@@ -0,0 +1,138 @@
+// 从库外面使用曲线、点和有限域的公开接口
+use elliptic_curve::curve::Curve;
+use elliptic_curve::dlog;
+use elliptic_curve::ecdsa;
+use elliptic_curve::elgamal;
+use elliptic_curve::error::EcError;
+use elliptic_curve::field;
+use elliptic_curve::hash_to_curve::{self, Sswu};
+use elliptic_curve::presets::{self, KeyPair};
+use elliptic_curve::rng;
+use elliptic_curve::scalar::Scalar;
+use elliptic_curve::subgroup::PrimeGroup;
+
+#[test]
+fn point_arithmetic_through_the_public_api() {
+    let curve = Curve::new(2, 3, 97);
+    let p = curve.point(3, 6);
+    assert!(curve.contains(3, 6));
+    let doubled = &p + &p;
+    assert_eq!(doubled, &p * 2);
+    assert_eq!(doubled.coords(), Some((80, 10)));
+    assert!((&p - &p).is_infinity());
+
+    let order = curve.group_order();
+    assert_eq!(order, 100);
+    assert!((&p * order).is_infinity());
+    assert_eq!(field::mod_inverse(3, 97) * 3 % 97, 1);
+
+    let other = Curve::new(1, 1, 97).point(0, 1);
+    assert!(matches!(
+        p.try_add(&other),
+        Err(EcError::MismatchedCurves { .. })
+    ));
+}
+
+#[test]
+fn curves_over_256_bit_primes() {
+    use elliptic_curve::bigcurve::BigCurve;
+    use elliptic_curve::u256;
+
+    let p =
+        u256::parse("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f").unwrap();
+    let secp256k1 = BigCurve::new(&[0; 4], &u256::parse("7").unwrap(), &p).unwrap();
+    let g = secp256k1
+        .point(
+            &u256::parse("0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                .unwrap(),
+            &u256::parse("0x483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8")
+                .unwrap(),
+        )
+        .unwrap();
+    let (x, _) = secp256k1.affine(&secp256k1.mul(&g, &[2, 0, 0, 0])).unwrap();
+    assert_eq!(
+        x,
+        u256::parse("0xc6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5").unwrap()
+    );
+    assert!(BigCurve::new(&[0; 4], &[7, 0, 0, 0], &u256::parse("91").unwrap()).is_err());
+}
+
+#[test]
+fn discrete_logs_on_a_toy_curve() {
+    let curve = Curve::new(2, 3, 97);
+    // (3, 6) 的阶是 5
+    let g = curve.point(3, 6);
+    let q = &g * 37;
+    assert_eq!(curve.discrete_log(g.coords(), q.coords()), Some(2));
+    // (0, 10) 不在 G 生成的子群里
+    let outside = curve.point(0, 10);
+    assert_eq!(curve.discrete_log(g.coords(), outside.coords()), None);
+    assert_eq!(dlog::pollard_rho(&g, &q), Some(2));
+    assert_eq!(dlog::pollard_rho(&g, &outside), None);
+}
+
+#[test]
+fn elgamal_encrypts_small_messages() {
+    let group = PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap();
+    let mut rng = rng::seeded(3);
+    let bob = elgamal::KeyPair::generate(&group, &mut rng);
+    let message = elgamal::encode(&group.curve, 21).unwrap();
+    let ciphertext = elgamal::encrypt(&group, bob.public, Some(message.point), &mut rng);
+    let decrypted = bob.decrypt(&group, &ciphertext);
+    assert_eq!(decrypted, Some(message.point));
+    assert_eq!(elgamal::decode(decrypted), Some(21));
+}
+
+#[test]
+fn ecdsa_nonce_reuse_through_the_public_api() {
+    let group = PrimeGroup::new(&Curve::new(1, 1, 599)).unwrap();
+    let (d, k) = (Scalar::from_u64(120, group.q), Scalar::from_u64(9, group.q));
+    let sign = |message: &[u8]| {
+        let z = ecdsa::hash_message(&group, message);
+        ecdsa::sign_with_nonce(&group, d, z, k).unwrap().signature
+    };
+    let (sig1, sig2) = (sign(b"first"), sign(b"second"));
+    let recovered = ecdsa::recover_reused_nonce(
+        &group,
+        group.base_mul(d),
+        (b"first", &sig1),
+        (b"second", &sig2),
+    )
+    .unwrap();
+    assert_eq!((recovered.nonce, recovered.secret), (k, d));
+    // k·(s1 − s2) = z1 − z2
+    assert_eq!(
+        (recovered.z1 - recovered.z2).checked_div(sig1.s - sig2.s),
+        Some(k)
+    );
+}
+
+#[test]
+fn messages_hash_to_points() {
+    let curve = Curve::new(2, 3, 97);
+    let (x, y) = hash_to_curve::hash_to_point(b"base point", &curve).unwrap();
+    assert!(curve.contains(x, y));
+    assert_eq!(
+        hash_to_curve::hash_to_point(b"base point", &curve),
+        Some((x, y))
+    );
+
+    let suite = Sswu::p256();
+    let point = suite.hash(b"message point", hash_to_curve::DST);
+    let (x, y) = suite.curve.affine(&point).expect("not the identity");
+    assert!(suite.curve.contains(&x, &y));
+}
+
+#[test]
+fn random_points_and_key_pairs() {
+    let mut rng = rng::seeded(5);
+    let curve = Curve::new(2, 3, 97);
+    let point = curve.random_point(&mut rng).expect("the curve has points");
+    let (x, y) = point.coords().expect("an affine point");
+    assert!(curve.contains(x, y));
+
+    let preset = presets::p256();
+    let key = KeyPair::generate(&preset, &mut rng::from_entropy());
+    let (x, y) = key.public;
+    assert!(preset.curve().contains(&x, &y));
+}